@@ -0,0 +1,73 @@
+//! Run a bot from a YAML config with a synthetic tick feed
+//!
+//! Usage: cargo run --example run_bot -- [bot.example.yaml] [--set <path>=<value>]... [--dump-config]
+//!
+//! `AGBOT__*` environment variables and `--set` flags override the file
+//! (see `ag_botkit::config_layers`); `--dump-config` prints the effective
+//! config and exits.
+
+use ag_botkit::{BotBuilder, BotConfig, BotError, BotResult, ChannelSource, ConfigLayers, ConfigValidator};
+use ag_botkit::strategies::MarketTick;
+use chrono::Utc;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> BotResult<()> {
+    let mut path = "bot.example.yaml".to_string();
+    let mut sets = Vec::new();
+    let mut dump_config = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--set" => sets.push(
+                args.next()
+                    .ok_or_else(|| BotError::ConfigError("--set needs <path>=<value>".to_string()))?,
+            ),
+            "--dump-config" => dump_config = true,
+            _ => path = arg,
+        }
+    }
+
+    let mut layers = ConfigLayers::from_yaml_file(&path)?.with_env();
+    for set in &sets {
+        layers = layers.set_arg(set)?;
+    }
+    if dump_config {
+        print!("{}", layers.effective_yaml::<BotConfig>()?);
+        return Ok(());
+    }
+
+    // Venues need credentials; drop them for the offline demo
+    let layers = layers.set("exec.venues", "[]");
+    let markets = layers.resolve::<BotConfig>()?.markets();
+
+    let (ticks, source) = ChannelSource::new(1024);
+    let mut bot = BotBuilder::from_layers(&layers, &ConfigValidator::new())?
+        .market_data(source)
+        .build()
+        .await?;
+    bot.start().await?;
+
+    for i in 0..50 {
+        let mid = 0.5 + 0.01 * ((i as f64) / 5.0).sin();
+        for market in &markets {
+            let tick = MarketTick {
+                market: market.clone(),
+                timestamp: Utc::now(),
+                bid: Some(mid - 0.01),
+                ask: Some(mid + 0.01),
+                bid_size: Some(100.0),
+                ask_size: Some(100.0),
+                last: Some(mid),
+                volume_24h: None,
+                sequence: None,
+            };
+            if ticks.send(tick).await.is_err() {
+                break;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    bot.stop().await
+}
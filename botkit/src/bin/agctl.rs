@@ -0,0 +1,162 @@
+//! Operate a running bot through its control API
+//!
+//! Usage: agctl [--addr <host:port>] <command>
+//!
+//! Commands:
+//!   status                              kill-switch, strategies, health, venues
+//!   positions                           positions per strategy and market
+//!   orders                              orders resting at the venues
+//!   pause <strategy_id>                 stop book updates and timers
+//!   resume <strategy_id>
+//!   kill <reason>                       turn the kill-switch on
+//!   reset <reason>                      turn the kill-switch off
+//!   set-policy <policy> <param> <value> e.g. set-policy PositionLimit max_size 500
+//!   metrics [prefix]                    tail metrics until interrupted
+//!
+//! The address defaults to `AGCTL_ADDR`, then `127.0.0.1:7070` (the
+//! default `control.listen`).
+//!
+//! `set-policy` changes the running policies only: restarting the bot, or
+//! a reload of a watched policy file, restores the configured value.
+
+use ag_botkit::control::BotStatus;
+use ag_botkit::{BotError, BotResult, ControlClient, ControlRequest, ControlResponse};
+
+const USAGE: &str = "usage: agctl [--addr <host:port>] <status|positions|orders|pause <id>|resume <id>|kill <reason>|reset <reason>|set-policy <policy> <param> <value>|metrics [prefix]>";
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("agctl: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> BotResult<()> {
+    let mut addr = std::env::var("AGCTL_ADDR").unwrap_or_else(|_| "127.0.0.1:7070".to_string());
+    let mut words = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().ok_or_else(|| usage("--addr needs <host:port>"))?,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => words.push(arg),
+        }
+    }
+    let request = parse_command(&words)?;
+
+    let mut client = ControlClient::connect(addr.as_str()).await?;
+    if let ControlRequest::TailMetrics { .. } = request {
+        client.send(&request).await?;
+        while let Some(response) = client.next_response().await? {
+            print_response(response)?;
+        }
+        return Ok(());
+    }
+    let response = client.request(&request).await?;
+    print_response(response)
+}
+
+fn parse_command(words: &[String]) -> BotResult<ControlRequest> {
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let request = match words.as_slice() {
+        ["status"] => ControlRequest::Status,
+        ["positions"] => ControlRequest::Positions,
+        ["orders"] => ControlRequest::Orders,
+        ["pause", strategy_id] => ControlRequest::PauseStrategy {
+            strategy_id: strategy_id.to_string(),
+        },
+        ["resume", strategy_id] => ControlRequest::ResumeStrategy {
+            strategy_id: strategy_id.to_string(),
+        },
+        ["kill", reason @ ..] if !reason.is_empty() => ControlRequest::TriggerKillSwitch { reason: reason.join(" ") },
+        ["reset", reason @ ..] if !reason.is_empty() => ControlRequest::ResetKillSwitch { reason: reason.join(" ") },
+        ["set-policy", policy, param, value] => ControlRequest::SetPolicyParam {
+            policy: policy.to_string(),
+            param: param.to_string(),
+            value: value
+                .parse()
+                .map_err(|_| usage(&format!("set-policy value must be a number (got '{}')", value)))?,
+        },
+        ["metrics"] => ControlRequest::TailMetrics { prefix: None },
+        ["metrics", prefix] => ControlRequest::TailMetrics {
+            prefix: Some(prefix.to_string()),
+        },
+        [] => return Err(usage("missing command")),
+        [command, ..] => return Err(usage(&format!("unknown command or arguments for '{}'", command))),
+    };
+    Ok(request)
+}
+
+fn print_response(response: ControlResponse) -> BotResult<()> {
+    match response {
+        ControlResponse::Status(status) => print_status(&status),
+        ControlResponse::Positions(positions) => print_json(&positions)?,
+        ControlResponse::Orders { orders } => print_json(&orders)?,
+        ControlResponse::Done { message } => println!("{}", message),
+        ControlResponse::Metric(sample) => {
+            let labels: Vec<String> = sample.labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            println!("{} {} {{{}}} {}", sample.timestamp.to_rfc3339(), sample.name, labels.join(","), sample.value);
+        }
+        ControlResponse::Error { message } => return Err(BotError::ControlError(message)),
+    }
+    Ok(())
+}
+
+fn print_status(status: &BotStatus) {
+    println!("bot:         {}", status.name);
+    match &status.kill_switch {
+        Some(event) => println!("kill-switch: ON since {} ({}: {})", event.at.to_rfc3339(), event.source, event.reason),
+        None => println!("kill-switch: off"),
+    }
+    println!("feed:        {}", if status.feed_connected { "connected" } else { "disconnected" });
+    if !status.disabled_policies.is_empty() {
+        println!("disabled:    {}", status.disabled_policies.join(", "));
+    }
+    println!("strategies:");
+    for strategy in &status.strategies {
+        let state = match (strategy.paused, strategy.warming_up) {
+            (true, _) => "paused",
+            (false, true) => "warming up",
+            (false, false) => "running",
+        };
+        println!(
+            "  {:<20} {:<10} queued {:<5} dropped {:<5} busy {:>5.1}%  {}",
+            strategy.id,
+            state,
+            strategy.pending_events,
+            strategy.dropped_book_updates,
+            strategy.loop_utilization * 100.0,
+            strategy.markets.join(",")
+        );
+    }
+    println!("health:      {}", if status.health.healthy { "ok" } else { "STALLED" });
+    for subsystem in &status.health.subsystems {
+        println!(
+            "  {:<20} {:<10} last beat {:>7.1}s ago (stall after {:.1}s)",
+            subsystem.name,
+            if subsystem.stalled { "stalled" } else { "alive" },
+            subsystem.silent_ms as f64 / 1000.0,
+            subsystem.stall_after_ms as f64 / 1000.0
+        );
+    }
+    if !status.venues.is_empty() {
+        println!("venues:");
+        for (venue, venue_status) in &status.venues {
+            println!("  {:<20} {}", venue, venue_status.state);
+        }
+    }
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> BotResult<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| BotError::ControlError(e.to_string()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn usage(message: &str) -> BotError {
+    BotError::ConfigError(format!("{}\n{}", message, USAGE))
+}
@@ -0,0 +1,2382 @@
+//! Runnable bot assembled by [`BotBuilder`](crate::BotBuilder)
+
+use crate::config::{BotConfig, TcaSection};
+use crate::control::{ControlServer, MetricSample, METRICS_TAIL_BUFFER};
+use crate::convert::ToStrategy;
+use crate::error::{BotError, BotResult};
+use crate::halt::{HaltReport, HaltRequest, HaltTarget};
+use crate::health::{self, Heartbeats, Subsystems};
+use crate::market_data::MarketDataSource;
+use crate::metrics::MetricLabeler;
+#[cfg(feature = "storage")]
+use crate::replay::MarketDataRecorder;
+use crate::session::{MarketSession, SessionAction, SessionManager};
+use ag_exec::oms::BookDepth;
+use ag_exec::scanner::UniverseUpdate;
+use ag_exec::ratelimit::RateLimiterStats;
+use ag_exec::{
+    bootstrap_positions, liquidate, verify_shutdown, BookValidationStats, BootstrapReport, ConnectionState,
+    ExecutionEngine, Hedger,
+    LiquidationConfig, MarketId, MarketQualityRecorder, Order, OrderId, PositionBootstrapConfig, Reconciler,
+    ReconciliationReport,
+    SequenceEvent, SequenceStats, SequenceTracker, ShutdownReport, VenueId, VenueStatus, VenueStatusChange,
+};
+use ag_risk::{KillSwitchEvent, RecordingSink, RiskEngine, SeriesValue};
+use ag_sched::{next_tick, sleep_until, HealthReport, LivenessRegistry, Ticker};
+#[cfg(feature = "storage")]
+use ag_strategies::flags::FLAG_METRIC;
+#[cfg(feature = "storage")]
+use ag_strategies::StrategyMetric;
+use ag_strategies::{
+    FeatureFlags, HygieneStats, MarketStatus, MarketStatusUpdate, MarketTick, MultiMarketCoordinator,
+    StrategyRegistry, TickHygiene, TickVerdict, VenueStatuses,
+};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Idle wait when no strategy timer is scheduled
+const IDLE_TIMER_WAIT: Duration = Duration::from_secs(60);
+
+/// Halt requests queued for the event loop
+const HALT_QUEUE: usize = 8;
+
+/// Strategy event loop utilization (busy / wall time between metrics
+/// flushes) above which the flush logs a saturation warning
+const LOOP_SATURATION_WARN: f64 = 0.8;
+
+/// Shared handle to the optional storage engine
+#[cfg(feature = "storage")]
+type SharedStorage = Option<Arc<Mutex<ag_storage::StorageEngine>>>;
+
+/// Inputs handed to the event loop and returned to the bot when it stops
+struct Feeds {
+    market_data: Option<Box<dyn MarketDataSource>>,
+    universe: Option<mpsc::Receiver<UniverseUpdate>>,
+    status: Option<mpsc::Receiver<MarketStatusUpdate>>,
+}
+
+/// A fully wired trading bot
+///
+/// `start` spawns the event loop (market data routing, strategy timers and
+/// metric flushing); `stop` halts it and shuts down all strategies.
+pub struct Bot {
+    config: BotConfig,
+    coordinator: Arc<Mutex<MultiMarketCoordinator>>,
+    flags: FeatureFlags,
+    venues: VenueStatuses,
+    exec_engine: Arc<ExecutionEngine>,
+    risk_engine: Arc<parking_lot::Mutex<RiskEngine>>,
+    market_quality: Arc<RwLock<MarketQualityRecorder>>,
+    /// Latest OMS vs venue reconciliation
+    reconciliation: Arc<RwLock<Option<ReconciliationReport>>>,
+    #[cfg(feature = "storage")]
+    storage: SharedStorage,
+    #[cfg(feature = "storage")]
+    execution_store: Option<Arc<ag_storage::ExecutionStore>>,
+    /// Strategy runs recorded by the last start
+    #[cfg(feature = "storage")]
+    runs: Vec<ag_storage::StrategyRun>,
+    market_data: Option<Box<dyn MarketDataSource>>,
+    universe_updates: Option<mpsc::Receiver<UniverseUpdate>>,
+    /// Market status messages taken from the market data source
+    market_status: Option<mpsc::Receiver<MarketStatusUpdate>>,
+    shutdown_tx: Option<watch::Sender<bool>>,
+    /// Halt requests for the running event loop
+    halt_tx: Option<mpsc::Sender<HaltRequest>>,
+    handle: Option<JoinHandle<Feeds>>,
+    /// Metrics published at each flush, for `tail_metrics` control clients
+    metrics_tail: broadcast::Sender<MetricSample>,
+    /// Address the control API listens on while running
+    control_addr: Option<SocketAddr>,
+    control_handle: Option<JoinHandle<()>>,
+    /// Heartbeats of the bot's subsystems and any registered by the caller
+    liveness: LivenessRegistry,
+    /// Subsystems registered by the last start
+    health_subsystems: Vec<String>,
+    health_handle: Option<JoinHandle<()>>,
+    /// Positions imported by the last start
+    bootstrap_report: Option<BootstrapReport>,
+    /// Venue check of the last stop
+    shutdown_report: Option<ShutdownReport>,
+    /// Plugin libraries backing plugin strategies; declared last so it is
+    /// dropped after the coordinator
+    _plugins: StrategyRegistry,
+}
+
+impl Bot {
+    pub(crate) fn new(
+        config: BotConfig,
+        coordinator: MultiMarketCoordinator,
+        exec_engine: ExecutionEngine,
+        risk_engine: Arc<parking_lot::Mutex<RiskEngine>>,
+        market_data: Option<Box<dyn MarketDataSource>>,
+        plugins: StrategyRegistry,
+    ) -> Self {
+        Self {
+            market_quality: Arc::new(RwLock::new(MarketQualityRecorder::new(config.market_quality))),
+            config,
+            flags: coordinator.feature_flags().clone(),
+            venues: coordinator.venue_statuses().clone(),
+            coordinator: Arc::new(Mutex::new(coordinator)),
+            exec_engine: Arc::new(exec_engine),
+            risk_engine,
+            reconciliation: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "storage")]
+            storage: None,
+            #[cfg(feature = "storage")]
+            execution_store: None,
+            #[cfg(feature = "storage")]
+            runs: Vec::new(),
+            market_data,
+            universe_updates: None,
+            market_status: None,
+            shutdown_tx: None,
+            halt_tx: None,
+            handle: None,
+            metrics_tail: broadcast::channel(METRICS_TAIL_BUFFER).0,
+            control_addr: None,
+            control_handle: None,
+            liveness: LivenessRegistry::new(),
+            health_subsystems: Vec::new(),
+            health_handle: None,
+            bootstrap_report: None,
+            shutdown_report: None,
+            _plugins: plugins,
+        }
+    }
+
+    #[cfg(feature = "storage")]
+    pub(crate) fn set_storage(&mut self, storage: Option<ag_storage::StorageEngine>) {
+        self.storage = storage.map(|s| Arc::new(Mutex::new(s)));
+    }
+
+    #[cfg(feature = "storage")]
+    pub(crate) fn set_execution_store(&mut self, store: Option<ag_storage::ExecutionStore>) {
+        self.execution_store = store.map(Arc::new);
+    }
+
+    pub(crate) fn set_universe_updates(&mut self, updates: Option<mpsc::Receiver<UniverseUpdate>>) {
+        self.universe_updates = updates;
+    }
+
+    /// Start the event loop
+    pub async fn start(&mut self) -> BotResult<()> {
+        if self.is_running() {
+            return Err(BotError::AlreadyRunning);
+        }
+
+        if let Some(bootstrap) = self.config.position_bootstrap {
+            self.bootstrap_positions(&bootstrap).await?;
+        }
+
+        let mut market_data = self.market_data.take();
+        if let Some(source) = market_data.as_mut() {
+            let markets = self.coordinator.lock().await.subscribed_markets();
+            source.subscribe(&markets).await?;
+            if self.market_status.is_none() {
+                self.market_status = source.status_updates();
+            }
+        } else {
+            warn!("Bot '{}' started without a market data source", self.config.name);
+        }
+
+        // Risk metrics of the strategy and the order gating engine
+        let mut risk_metrics = vec![("strategy", Arc::new(RecordingSink::new()))];
+        self.risk_engine.lock().set_metrics_sink(risk_metrics[0].1.clone());
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            let sink = Arc::new(RecordingSink::new());
+            risk_engine.lock().await.set_metrics_sink(sink.clone());
+            risk_metrics.push(("exec", sink));
+        }
+
+        #[allow(unused_mut)]
+        let mut labeler = MetricLabeler::new(&self.config.name, &self.config.metrics);
+        #[cfg(feature = "storage")]
+        self.start_runs(&mut labeler).await?;
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let heartbeats = Heartbeats::register(
+            &self.liveness,
+            &self.config.health,
+            Subsystems {
+                event_loop: self.config.kill_switch.check_interval(),
+                market_data: market_data.is_some(),
+                metrics_flush: self.config.metrics.flush_interval(),
+                reconciliation: self.config.reconciliation.interval(),
+                status_poll: self.config.status_polling.as_ref().map(|p| p.tick_interval()),
+                venue_health: self.config.venue_status.as_ref().map(|s| s.health_interval()),
+            },
+        );
+        self.health_subsystems = heartbeats.names();
+        self.health_handle = Some(tokio::spawn(health::supervise(
+            self.config.name.clone(),
+            self.liveness.clone(),
+            self.config.health.check_interval(),
+            labeler.clone(),
+            self.metrics_tail.clone(),
+            shutdown_rx.clone(),
+        )));
+
+        if let Some(control) = &self.config.control {
+            let listener = tokio::net::TcpListener::bind(control.listen.as_str()).await?;
+            let addr = listener.local_addr()?;
+            self.control_addr = Some(addr);
+            let server = Arc::new(ControlServer {
+                bot_name: self.config.name.clone(),
+                coordinator: self.coordinator.clone(),
+                exec_engine: self.exec_engine.clone(),
+                risk_engine: self.risk_engine.clone(),
+                metrics: self.metrics_tail.clone(),
+                liveness: self.liveness.clone(),
+            });
+            self.control_handle = Some(tokio::spawn(server.run(listener, shutdown_rx.clone())));
+            info!("Bot '{}' control API listening on {}", self.config.name, addr);
+        }
+
+        let event_loop = EventLoop {
+            bot_name: self.config.name.clone(),
+            labeler,
+            coordinator: self.coordinator.clone(),
+            flags: self.flags.clone(),
+            venues: self.venues.clone(),
+            exec_engine: self.exec_engine.clone(),
+            market_quality: self.market_quality.clone(),
+            #[cfg(feature = "storage")]
+            storage: self.storage.clone(),
+            #[cfg(feature = "storage")]
+            execution_store: self.execution_store.clone(),
+            #[cfg(feature = "storage")]
+            recorder: (self.config.recording.market_data && self.storage.is_some())
+                .then(|| parking_lot::Mutex::new(MarketDataRecorder::new())),
+            flush_interval: self.config.metrics.flush_interval(),
+            risk_snapshot_interval: self.config.risk_state.snapshot_interval(),
+            position_snapshot_interval: self.config.position_history.snapshot_interval(),
+            tca: self.config.tca.clone(),
+            risk_engine: self.risk_engine.clone(),
+            risk_metrics,
+            metrics_tail: self.metrics_tail.clone(),
+            liveness: self.liveness.clone(),
+            heartbeats,
+            capital_poll_interval: self.config.capital.poll_interval(),
+            reconcile_interval: self.config.reconciliation.interval(),
+            reconciler: Mutex::new(Reconciler::new(self.config.reconciliation.reconciliation_config())),
+            reconciliation: self.reconciliation.clone(),
+            sequence: parking_lot::Mutex::new(SequenceTracker::new(self.config.sequence)),
+            hygiene: self.config.tick_hygiene.map(|config| parking_lot::Mutex::new(TickHygiene::new(config))),
+            sessions: parking_lot::Mutex::new(self.config.session.session_manager()),
+            session_flatten: self.config.session.flatten,
+            kill_switch_flatten: self.config.kill_switch.flatten,
+            kill_switch_check_interval: self.config.kill_switch.check_interval(),
+            liquidation: self.config.liquidation,
+            liquidations: parking_lot::Mutex::new(HashMap::new()),
+            halted_strategies: parking_lot::Mutex::new(HashSet::new()),
+            halted_markets: parking_lot::Mutex::new(HashSet::new()),
+            hedger: self.config.hedging.clone().map(Hedger::new),
+            status_poll_interval: self.config.status_polling.as_ref().map(|p| p.tick_interval()),
+            venue_health_interval: self.config.venue_status.as_ref().map(|s| s.health_interval()),
+            session_end_dates: self.config.session.use_end_dates,
+            universe_strategies: self
+                .config
+                .strategies
+                .iter()
+                .filter(|s| s.follow_universe)
+                .map(|s| s.id.clone())
+                .collect(),
+        };
+
+        let feeds = Feeds {
+            market_data,
+            universe: self.universe_updates.take(),
+            status: self.market_status.take(),
+        };
+        let (halt_tx, halt_rx) = mpsc::channel(HALT_QUEUE);
+        self.handle = Some(tokio::spawn(event_loop.run(feeds, halt_rx, shutdown_rx)));
+        self.shutdown_tx = Some(shutdown_tx);
+        self.halt_tx = Some(halt_tx);
+
+        info!("Bot '{}' started", self.config.name);
+        Ok(())
+    }
+
+    /// Stop the event loop and shut down all strategies
+    ///
+    /// With `shutdown.verify` (the default) the venues are then checked for
+    /// surviving orders, see [`shutdown_report`](Self::shutdown_report).
+    pub async fn stop(&mut self) -> BotResult<()> {
+        let (shutdown_tx, handle) = match (self.shutdown_tx.take(), self.handle.take()) {
+            (Some(tx), Some(handle)) => (tx, handle),
+            _ => return Err(BotError::NotRunning),
+        };
+
+        let _ = shutdown_tx.send(true);
+        let feeds = handle
+            .await
+            .map_err(|e| BotError::TaskError(e.to_string()))?;
+        self.market_data = feeds.market_data;
+        self.universe_updates = feeds.universe;
+        self.market_status = feeds.status;
+        self.halt_tx = None;
+        if let Some(handle) = self.control_handle.take() {
+            let _ = handle.await;
+        }
+        self.control_addr = None;
+        if let Some(handle) = self.health_handle.take() {
+            let _ = handle.await;
+        }
+        for subsystem in self.health_subsystems.drain(..) {
+            self.liveness.deregister(&subsystem);
+        }
+
+        {
+            let mut coordinator = self.coordinator.lock().await;
+            for strategy_id in coordinator.strategy_ids() {
+                coordinator.unregister_strategy(&strategy_id).await?;
+            }
+        }
+
+        #[cfg(feature = "storage")]
+        self.end_runs().await;
+
+        if self.config.shutdown.verify {
+            self.verify_shutdown().await;
+        }
+
+        info!("Bot '{}' stopped", self.config.name);
+        Ok(())
+    }
+
+    /// Record a run per strategy and tag the strategy's orders and metrics
+    /// with its run ID
+    #[cfg(feature = "storage")]
+    async fn start_runs(&mut self, labeler: &mut MetricLabeler) -> BotResult<()> {
+        let Some(store) = self.execution_store.as_ref().filter(|_| self.config.runs.record) else {
+            return Ok(());
+        };
+
+        self.runs.clear();
+        let mut coordinator = self.coordinator.lock().await;
+        for section in &self.config.strategies {
+            let mut run = ag_storage::StrategyRun::new(
+                &self.config.name,
+                &section.id,
+                &section.kind,
+                section.params.clone(),
+                self.config.runs.code_version(),
+            );
+            store.store_strategy_run(&mut run).await?;
+            coordinator.set_order_tag(&section.id, ag_storage::RUN_TAG, &run.run_id)?;
+            labeler.set_run_id(&section.id, &run.run_id);
+            self.runs.push(run);
+        }
+        Ok(())
+    }
+
+    /// Mark the runs of the last start as ended
+    #[cfg(feature = "storage")]
+    async fn end_runs(&mut self) {
+        let Some(store) = &self.execution_store else {
+            return;
+        };
+
+        let now = Utc::now();
+        for run in &mut self.runs {
+            run.ended_at = Some(now);
+            if let Err(e) = store.end_strategy_run(&run.run_id, now).await {
+                error!("Failed to end strategy run {}: {}", run.run_id, e);
+            }
+        }
+    }
+
+    /// Import positions and open orders from the venues and seed the
+    /// strategies holding them
+    ///
+    /// A position is seeded into a strategy context only when exactly one
+    /// strategy trades its market; otherwise it is left to the execution
+    /// engine and risk checks, and logged.
+    async fn bootstrap_positions(&mut self, config: &PositionBootstrapConfig) -> BotResult<()> {
+        let report = bootstrap_positions(&self.exec_engine, config).await?;
+        for venue in &report.venues {
+            if let Some(e) = &venue.error {
+                error!("Bot '{}' could not import positions from {}: {}", self.config.name, venue.venue, e);
+            } else if venue.source.is_none() {
+                warn!("Bot '{}': {} reports neither positions nor fills", self.config.name, venue.venue);
+            }
+        }
+        if config.required && !report.is_complete() {
+            let incomplete: Vec<String> = report
+                .venues
+                .iter()
+                .filter(|v| v.source.is_none() || v.error.is_some() || v.unattributed_fills > 0)
+                .map(|v| v.venue.to_string())
+                .collect();
+            self.bootstrap_report = Some(report);
+            return Err(BotError::BootstrapIncomplete(incomplete.join(", ")));
+        }
+
+        {
+            let mut coordinator = self.coordinator.lock().await;
+            for (market, size) in report.net_positions() {
+                let market = market.as_str();
+                let strategies = coordinator.market_strategies(market);
+                let [strategy_id] = strategies.as_slice() else {
+                    warn!(
+                        "Bot '{}' holds {} in {}, traded by {} strategies; not seeded",
+                        self.config.name,
+                        size,
+                        market,
+                        strategies.len()
+                    );
+                    continue;
+                };
+                let avg_price = report
+                    .positions
+                    .iter()
+                    .find(|p| p.market.as_str() == market)
+                    .and_then(|p| p.avg_price);
+                if avg_price.is_none() {
+                    warn!("Entry price of {} in {} unknown; seeding {} at 0", size, market, strategy_id);
+                }
+                coordinator.set_position(strategy_id, market, size, avg_price.unwrap_or(0.0))?;
+                info!("Seeded {} with {} in {}", strategy_id, size, market);
+            }
+        }
+        self.bootstrap_report = Some(report);
+        Ok(())
+    }
+
+    /// Check the venues for orders that survived shutdown and write the report
+    async fn verify_shutdown(&mut self) {
+        let report = verify_shutdown(&self.exec_engine, &self.config.shutdown.check_config()).await;
+        if !report.is_clean() {
+            for venue in &report.venues {
+                if let Some(e) = &venue.error {
+                    error!("Bot '{}' could not verify shutdown on {}: {}", self.config.name, venue.venue, e);
+                }
+                if !venue.residual_orders.is_empty() {
+                    error!(
+                        "Bot '{}' left {} orders open on {} after {} attempts",
+                        self.config.name,
+                        venue.residual_orders.len(),
+                        venue.venue,
+                        report.attempts
+                    );
+                }
+            }
+        }
+        if !report.residual_positions.is_empty() {
+            warn!(
+                "Bot '{}' stopped with {} open positions: {:?}",
+                self.config.name,
+                report.residual_positions.len(),
+                report.residual_positions
+            );
+        }
+
+        if let Some(path) = &self.config.shutdown.report_path {
+            let written = serde_json::to_string_pretty(&report)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                error!("Failed to write shutdown report to {}: {}", path, e);
+            }
+        }
+        self.shutdown_report = Some(report);
+    }
+
+    /// Stop one strategy while the rest of the bot keeps trading
+    ///
+    /// The strategy is removed from its markets and unregistered, its exec
+    /// orders are cancelled and market data no other strategy needs is
+    /// released. With `liquidate`, positions it leaves in markets no other
+    /// strategy trades are closed with the configured liquidation style;
+    /// exec positions are kept per market, so positions in shared markets
+    /// are only reported (see [`HaltReport::shared_positions`]).
+    pub async fn halt_strategy(&self, strategy_id: &str, liquidate: bool) -> BotResult<HaltReport> {
+        self.halt(HaltTarget::Strategy(strategy_id.to_string()), liquidate).await
+    }
+
+    /// Stop trading one market while the rest of the bot keeps trading
+    ///
+    /// Every strategy is removed from the market (and keeps its other
+    /// markets), all exec orders in it are cancelled and its market data is
+    /// released. With `liquidate`, the position is closed with the
+    /// configured liquidation style.
+    pub async fn halt_market(&self, market_id: &str, liquidate: bool) -> BotResult<HaltReport> {
+        self.halt(HaltTarget::Market(market_id.to_string()), liquidate).await
+    }
+
+    /// Hand a halt to the event loop and wait for its report
+    async fn halt(&self, target: HaltTarget, liquidate: bool) -> BotResult<HaltReport> {
+        let halt_tx = self.halt_tx.as_ref().filter(|_| self.is_running()).ok_or(BotError::NotRunning)?;
+        let (reply, report) = oneshot::channel();
+        halt_tx
+            .send(HaltRequest { target, liquidate, reply })
+            .await
+            .map_err(|_| BotError::NotRunning)?;
+        report.await.map_err(|_| BotError::NotRunning)?
+    }
+
+    /// Whether the event loop is running
+    pub fn is_running(&self) -> bool {
+        self.handle.as_ref().map(|h| !h.is_finished()).unwrap_or(false)
+    }
+
+    /// Address the control API is listening on (None when stopped or
+    /// without a `control` section)
+    pub fn control_addr(&self) -> Option<SocketAddr> {
+        self.control_addr
+    }
+
+    /// Liveness registry the bot's subsystems beat in; register other
+    /// long-running tasks (feeds, the retention scheduler) here to have them
+    /// supervised too
+    pub fn liveness(&self) -> LivenessRegistry {
+        self.liveness.clone()
+    }
+
+    /// Health of every registered subsystem (see [`crate::health`])
+    pub fn health(&self) -> HealthReport {
+        self.liveness.report(Utc::now())
+    }
+
+    /// Bot configuration
+    pub fn config(&self) -> &BotConfig {
+        &self.config
+    }
+
+    /// Strategy coordinator
+    pub fn coordinator(&self) -> Arc<Mutex<MultiMarketCoordinator>> {
+        self.coordinator.clone()
+    }
+
+    /// Execution engine
+    pub fn exec_engine(&self) -> Arc<ExecutionEngine> {
+        self.exec_engine.clone()
+    }
+
+    /// Risk engine used by strategy contexts
+    pub fn risk_engine(&self) -> Arc<parking_lot::Mutex<RiskEngine>> {
+        self.risk_engine.clone()
+    }
+
+    /// Feature flags shared by the coordinator and strategy contexts
+    ///
+    /// Flags set through the returned handle take effect on the next check,
+    /// without a restart, and are reported with the metrics flush.
+    pub fn feature_flags(&self) -> FeatureFlags {
+        self.flags.clone()
+    }
+
+    /// Health of every monitored venue, as seen by the execution engine
+    ///
+    /// Strategies see the venue states through `ctx.venues`.
+    pub fn venue_statuses(&self) -> HashMap<VenueId, VenueStatus> {
+        self.exec_engine.venue_statuses()
+    }
+
+    /// Per-market quality recorder fed by market data ticks
+    ///
+    /// Share it with [`MarketScanner::with_market_quality`](ag_exec::MarketScanner::with_market_quality)
+    /// to rank on observed spread and depth, or feed it trade prints from a
+    /// trade stream with `on_trade`.
+    pub fn market_quality(&self) -> Arc<RwLock<MarketQualityRecorder>> {
+        self.market_quality.clone()
+    }
+
+    /// Latest OMS vs venue reconciliation report, if one has run
+    ///
+    /// Reports are produced every `reconciliation.interval_ms` while the
+    /// bot is running.
+    pub async fn reconciliation(&self) -> Option<ReconciliationReport> {
+        self.reconciliation.read().await.clone()
+    }
+
+    /// Positions imported by the last `start`, if `position_bootstrap` is set
+    pub fn bootstrap_report(&self) -> Option<&BootstrapReport> {
+        self.bootstrap_report.as_ref()
+    }
+
+    /// Venue check made by the last `stop`, if verification is enabled
+    pub fn shutdown_report(&self) -> Option<&ShutdownReport> {
+        self.shutdown_report.as_ref()
+    }
+
+    /// Rate limiter status per venue: tokens remaining, time to the next
+    /// token, queued requests and delayed/rejected counts per priority class
+    pub fn rate_limits(&self) -> HashMap<VenueId, RateLimiterStats> {
+        self.exec_engine.rate_limiter_status()
+    }
+
+    /// Storage engine, if configured
+    #[cfg(feature = "storage")]
+    pub fn storage(&self) -> Option<Arc<Mutex<ag_storage::StorageEngine>>> {
+        self.storage.clone()
+    }
+
+    /// Strategy runs recorded by the last start (empty without storage or
+    /// with `runs.record` off)
+    #[cfg(feature = "storage")]
+    pub fn runs(&self) -> &[ag_storage::StrategyRun] {
+        &self.runs
+    }
+}
+
+/// State moved into the spawned event loop task
+struct EventLoop {
+    bot_name: String,
+    labeler: MetricLabeler,
+    coordinator: Arc<Mutex<MultiMarketCoordinator>>,
+    flags: FeatureFlags,
+    /// Venue states seen by strategy contexts
+    venues: VenueStatuses,
+    exec_engine: Arc<ExecutionEngine>,
+    market_quality: Arc<RwLock<MarketQualityRecorder>>,
+    #[cfg(feature = "storage")]
+    storage: SharedStorage,
+    #[cfg(feature = "storage")]
+    execution_store: Option<Arc<ag_storage::ExecutionStore>>,
+    /// Routed ticks waiting to be written to storage
+    #[cfg(feature = "storage")]
+    recorder: Option<parking_lot::Mutex<MarketDataRecorder>>,
+    flush_interval: Duration,
+    risk_snapshot_interval: Option<Duration>,
+    position_snapshot_interval: Option<Duration>,
+    tca: TcaSection,
+    /// Risk engine shared by strategy contexts
+    risk_engine: Arc<parking_lot::Mutex<RiskEngine>>,
+    /// Metrics emitted by the risk engines, by engine
+    risk_metrics: Vec<(&'static str, Arc<RecordingSink>)>,
+    /// Control API metric subscribers
+    metrics_tail: broadcast::Sender<MetricSample>,
+    /// Registry the heartbeats beat in, reported at each flush
+    liveness: LivenessRegistry,
+    heartbeats: Heartbeats,
+    capital_poll_interval: Option<Duration>,
+    reconcile_interval: Option<Duration>,
+    reconciler: Mutex<Reconciler>,
+    /// Latest reconciliation report, shared with the bot
+    reconciliation: Arc<RwLock<Option<ReconciliationReport>>>,
+    /// Per-market sequence and gap tracking
+    sequence: parking_lot::Mutex<SequenceTracker>,
+    /// Duplicate and late tick filtering (None = off)
+    hygiene: Option<parking_lot::Mutex<TickHygiene>>,
+    /// Market session schedule
+    sessions: parking_lot::Mutex<SessionManager>,
+    /// Flatten positions when a market session stops
+    session_flatten: bool,
+    /// Liquidate open positions when a kill-switch turns on
+    kill_switch_flatten: bool,
+    kill_switch_check_interval: Duration,
+    /// How positions are closed
+    liquidation: LiquidationConfig,
+    /// Running liquidation per market
+    liquidations: parking_lot::Mutex<HashMap<String, JoinHandle<()>>>,
+    /// Strategies halted on request, kept out of universe updates and
+    /// session resumes
+    halted_strategies: parking_lot::Mutex<HashSet<String>>,
+    /// Markets halted on request, likewise
+    halted_markets: parking_lot::Mutex<HashSet<String>>,
+    /// Perp hedging of crypto-linked markets
+    hedger: Option<Hedger>,
+    /// Order status polling round interval (None = polling disabled)
+    status_poll_interval: Option<Duration>,
+    /// Venue health check interval (None = venue status monitoring disabled)
+    venue_health_interval: Option<Duration>,
+    /// Schedule session closes from scanned market end dates
+    session_end_dates: bool,
+    /// Strategies re-subscribed on each universe update
+    universe_strategies: Vec<String>,
+}
+
+impl EventLoop {
+    async fn run(
+        self,
+        feeds: Feeds,
+        mut halt_rx: mpsc::Receiver<HaltRequest>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> Feeds {
+        let Feeds { mut market_data, mut universe, mut status } = feeds;
+        let mut connection = market_data.as_ref().and_then(|source| source.connection_state());
+        let mut flush = Ticker::interval(self.flush_interval);
+        let mut risk_snapshot = self.risk_snapshot_interval.map(Ticker::interval);
+        let mut position_snapshots = self.position_snapshot_interval.map(Ticker::interval);
+        let mut tca_reports = self.tca.report_interval().map(Ticker::interval);
+        let mut capital_polls = self.capital_poll_interval.map(Ticker::interval);
+        let mut reconciliations = self.reconcile_interval.map(Ticker::interval);
+        let mut kill_switch_checks = Ticker::interval(self.kill_switch_check_interval);
+        let mut hedge_rebalances = self.hedger.as_ref().map(|h| Ticker::interval(h.config().rebalance_interval()));
+        let mut status_polls = self.status_poll_interval.map(Ticker::interval);
+        let mut venue_health_checks = self.venue_health_interval.map(Ticker::interval);
+        let mut venue_changes = self.exec_engine.subscribe_venue_status();
+        let mut kill_switch_active = self.kill_switch_active().await;
+        // Last seen 24h volume per market; increases are recorded as trade prints
+        let mut volumes: HashMap<String, f64> = HashMap::new();
+
+        loop {
+            self.heartbeats.event_loop.beat();
+            let idle = Utc::now() + chrono::Duration::seconds(IDLE_TIMER_WAIT.as_secs() as i64);
+            let timer_deadline = self.coordinator.lock().await.next_timer_deadline().unwrap_or(idle);
+            let session_deadline = self.sessions.lock().next_deadline().unwrap_or(idle);
+
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+
+                Some(request) = halt_rx.recv() => {
+                    let result = self.halt(&request.target, request.liquidate, &mut market_data).await;
+                    if let Err(e) = &result {
+                        error!("Bot '{}' failed to halt {}: {}", self.bot_name, request.target, e);
+                    }
+                    let _ = request.reply.send(result);
+                }
+
+                tick = next_market_tick(&mut market_data) => match tick {
+                    Some(mut tick) => {
+                        health::beat(&self.heartbeats.market_data);
+                        if !self.check_hygiene(&mut tick) {
+                            continue;
+                        }
+                        if !self.check_sequence(&tick, &mut market_data).await {
+                            continue;
+                        }
+                        #[cfg(feature = "storage")]
+                        if let Some(recorder) = &self.recorder {
+                            recorder.lock().record(&tick);
+                        }
+
+                        // Touch for post-only crossing checks and FOK emulation
+                        let market = MarketId::new(tick.market.clone());
+                        self.exec_engine.update_touch(&market, tick.bid, tick.ask).await;
+                        let depth = BookDepth::top_of_book(tick.bid.zip(tick.bid_size), tick.ask.zip(tick.ask_size));
+                        {
+                            let mut quality = self.market_quality.write().await;
+                            quality.on_book(&market, &depth, tick.timestamp);
+                            if let Some(volume) = tick.volume_24h {
+                                if let Some(previous) = volumes.insert(tick.market.clone(), volume) {
+                                    if volume > previous {
+                                        quality.on_trade(&market, volume - previous, tick.timestamp);
+                                    }
+                                }
+                            }
+                        }
+                        self.exec_engine.update_book_depth(&market, depth).await;
+
+                        let mut coordinator = self.coordinator.lock().await;
+                        if let Err(e) = coordinator.route_market_tick(&tick.market, &tick).await {
+                            error!("Failed to route tick for {}: {}", tick.market, e);
+                        }
+                    }
+                    None => {
+                        warn!("Market data feed ended");
+                        market_data = None;
+                    }
+                },
+
+                state = next_connection_state(&mut connection) => match state {
+                    Some(state) => self.apply_connection_state(state).await,
+                    None => connection = None,
+                },
+
+                update = next_universe(&mut universe) => match update {
+                    Some(update) => self.apply_universe(update, &mut market_data).await,
+                    None => {
+                        warn!("Universe update feed ended");
+                        universe = None;
+                    }
+                },
+
+                update = next_status(&mut status) => match update {
+                    Some(update) => self.apply_market_status(update, &mut market_data).await,
+                    None => {
+                        warn!("Market status feed ended");
+                        status = None;
+                    }
+                },
+
+                _ = sleep_until(timer_deadline) => {
+                    let mut coordinator = self.coordinator.lock().await;
+                    if let Err(e) = coordinator.fire_due_timers(Utc::now()).await {
+                        error!("Strategy timer failed: {}", e);
+                    }
+                }
+
+                _ = sleep_until(session_deadline) => self.apply_sessions(&mut market_data).await,
+
+                _ = flush.tick() => {
+                    self.flush_metrics(book_validation(&market_data)).await;
+                    self.heartbeats.metrics_flush.beat();
+                }
+
+                _ = next_tick(&mut risk_snapshot) => self.snapshot_risk_state().await,
+
+                _ = next_tick(&mut position_snapshots) => self.snapshot_positions().await,
+
+                _ = next_tick(&mut tca_reports) => self.report_tca().await,
+
+                _ = next_tick(&mut capital_polls) => self.poll_capital().await,
+
+                _ = next_tick(&mut reconciliations) => {
+                    self.reconcile().await;
+                    health::beat(&self.heartbeats.reconciliation);
+                }
+
+                _ = next_tick(&mut hedge_rebalances) => self.rebalance_hedges().await,
+
+                _ = next_tick(&mut status_polls) => {
+                    self.poll_order_statuses().await;
+                    health::beat(&self.heartbeats.status_poll);
+                }
+
+                _ = next_tick(&mut venue_health_checks) => {
+                    self.exec_engine.check_venue_health().await;
+                    health::beat(&self.heartbeats.venue_health);
+                }
+
+                change = venue_changes.recv() => match change {
+                    Ok(change) => self.apply_venue_change(&change),
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("Missed {} venue status changes, resyncing", missed);
+                        for (venue, status) in self.exec_engine.venue_statuses() {
+                            self.venues.set(venue.as_str(), status.state.to_strategy());
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {}
+                },
+
+                _ = kill_switch_checks.tick() => {
+                    self.log_recovery_events().await;
+                    let active = self.kill_switch_active().await;
+                    if active && !kill_switch_active {
+                        self.on_kill_switch().await;
+                    }
+                    kill_switch_active = active;
+                }
+            }
+        }
+
+        self.flush_metrics(book_validation(&market_data)).await;
+        if self.risk_snapshot_interval.is_some() {
+            self.snapshot_risk_state().await;
+        }
+        if self.position_snapshot_interval.is_some() {
+            self.snapshot_positions().await;
+        }
+        Feeds { market_data, universe, status }
+    }
+
+    /// Pause trading while the market data feed is down
+    ///
+    /// On disconnect strategies stop accepting new orders and resting
+    /// orders in subscribed markets are cancelled so nothing rests on
+    /// frozen prices; on reconnect strategies resume.
+    async fn apply_connection_state(&self, state: ConnectionState) {
+        let connected = state.is_connected();
+        let markets = {
+            let mut coordinator = self.coordinator.lock().await;
+            if coordinator.feed_connected() == connected {
+                return;
+            }
+            coordinator.set_feed_connected(connected);
+            coordinator.subscribed_markets()
+        };
+
+        if connected {
+            info!("Bot '{}' market data reconnected, resuming", self.bot_name);
+        } else {
+            warn!("Bot '{}' market data {:?}, pausing and cancelling orders", self.bot_name, state);
+            self.cancel_orders_in(&markets).await;
+        }
+    }
+
+    /// Stop and resume market sessions that are due
+    async fn apply_sessions(&self, market_data: &mut Option<Box<dyn MarketDataSource>>) {
+        let actions = self.sessions.lock().due(Utc::now());
+        for action in actions {
+            match action {
+                SessionAction::Stop(session) => self.stop_session(session, market_data).await,
+                SessionAction::Resume(session) => self.resume_session(session, market_data).await,
+            }
+        }
+    }
+
+    /// Stop trading a market ahead of its close
+    ///
+    /// Reports the close to both risk engines (for `TradingWindow`
+    /// policies), unsubscribes its strategies, cancels resting orders and
+    /// optionally flattens the position.
+    async fn stop_session(&self, session: MarketSession, market_data: &mut Option<Box<dyn MarketDataSource>>) {
+        let market = session.market_id.clone();
+        self.risk_engine.lock().set_market_close(&market, session.close_at);
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            risk_engine.lock().await.set_market_close(&market, session.close_at);
+        }
+
+        let mut strategies = Vec::new();
+        let mut unsubscribed = false;
+        {
+            let mut coordinator = self.coordinator.lock().await;
+            for strategy_id in coordinator.market_strategies(&market) {
+                match coordinator.remove_market(&strategy_id, &market).await {
+                    Ok(removal) => {
+                        unsubscribed |= removal.unsubscribed;
+                        strategies.push(strategy_id);
+                    }
+                    Err(e) => error!("Failed to stop strategy {} in {}: {}", strategy_id, market, e),
+                }
+            }
+        }
+
+        if unsubscribed {
+            if let Some(source) = market_data.as_mut() {
+                if let Err(e) = source.unsubscribe(std::slice::from_ref(&market)).await {
+                    error!("Failed to unsubscribe from {}: {}", market, e);
+                }
+            }
+        }
+        self.cancel_orders_in(std::slice::from_ref(&market)).await;
+
+        if self.session_flatten {
+            self.start_liquidation(&market, "session close");
+        }
+
+        info!(
+            "Bot '{}' stopped trading {} ({} strategies) ahead of close at {}",
+            self.bot_name,
+            market,
+            strategies.len(),
+            session.close_at
+        );
+        self.sessions.lock().record_stopped(session, strategies);
+    }
+
+    /// Resume trading a market after it reopened
+    async fn resume_session(&self, session: MarketSession, market_data: &mut Option<Box<dyn MarketDataSource>>) {
+        let market = session.market_id;
+        self.risk_engine.lock().clear_market_close(&market);
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            risk_engine.lock().await.clear_market_close(&market);
+        }
+
+        let strategies = self.sessions.lock().take_stopped(&market);
+        if self.halted_markets.lock().contains(&market) {
+            info!("Bot '{}' not resuming halted market {}", self.bot_name, market);
+            return;
+        }
+        let strategies: Vec<String> = {
+            let halted = self.halted_strategies.lock();
+            strategies.into_iter().filter(|id| !halted.contains(id)).collect()
+        };
+        let mut subscribed = false;
+        {
+            let mut coordinator = self.coordinator.lock().await;
+            for strategy_id in &strategies {
+                match coordinator.add_market(strategy_id, &market).await {
+                    Ok(first) => subscribed |= first,
+                    Err(e) => error!("Failed to resume strategy {} in {}: {}", strategy_id, market, e),
+                }
+            }
+        }
+
+        if subscribed {
+            if let Some(source) = market_data.as_mut() {
+                if let Err(e) = source.subscribe(std::slice::from_ref(&market)).await {
+                    error!("Failed to subscribe to {}: {}", market, e);
+                }
+            }
+        }
+        info!("Bot '{}' resumed trading {} ({} strategies)", self.bot_name, market, strategies.len());
+    }
+
+    /// Refresh venue balances, report the bankroll to the strategy risk
+    /// engine and reallocate free capital across strategies
+    async fn poll_capital(&self) {
+        if let Err(e) = self.exec_engine.poll_balances().await {
+            warn!("Balance poll failed: {}", e);
+        }
+        let capital = match self.exec_engine.capital().await {
+            Ok(capital) => capital,
+            Err(e) => {
+                error!("Failed to snapshot capital: {}", e);
+                return;
+            }
+        };
+
+        self.risk_engine.lock().set_bankroll(capital.total_cash);
+        self.coordinator.lock().await.allocate_capital(capital.free_capital);
+        debug!(
+            "Capital: {:.2} cash, {:.2} locked, {:.2} free",
+            capital.total_cash, capital.total_locked, capital.free_capital
+        );
+    }
+
+    /// Compare the OMS with every venue and publish the report
+    async fn reconcile(&self) {
+        let report = match self.reconciler.lock().await.reconcile(&self.exec_engine).await {
+            Ok(report) => report,
+            Err(e) => {
+                error!("Reconciliation failed: {}", e);
+                return;
+            }
+        };
+        for venue in report.venues.iter().filter(|v| !v.is_consistent()) {
+            warn!(
+                "Bot '{}' venue {} out of sync: {} discrepancies (oldest {:.0}s){}",
+                self.bot_name,
+                venue.venue,
+                venue.discrepancies.len(),
+                venue.oldest_age_secs(),
+                venue.error.as_ref().map(|e| format!(", error: {}", e)).unwrap_or_default()
+            );
+        }
+        *self.reconciliation.write().await = Some(report);
+    }
+
+    /// Trade the hedge perps towards the delta of the hedged positions
+    async fn rebalance_hedges(&self) {
+        let Some(hedger) = &self.hedger else {
+            return;
+        };
+        match hedger.rebalance(&self.exec_engine).await {
+            Ok(adjustments) => {
+                for adjustment in adjustments.iter().filter(|a| a.ack.is_some()) {
+                    debug!(
+                        "Hedged {}: {:.4} exposure, {} -> {} {}",
+                        adjustment.underlying,
+                        adjustment.exposure,
+                        adjustment.previous,
+                        adjustment.target,
+                        adjustment.perp
+                    );
+                }
+            }
+            Err(e) => error!("Hedge rebalance failed: {}", e),
+        }
+    }
+
+    /// Poll working orders on venues without streaming order updates
+    async fn poll_order_statuses(&self) {
+        match self.exec_engine.poll_order_statuses().await {
+            Ok(updates) => {
+                for update in &updates {
+                    debug!(
+                        "Polled order {:?} on {}: {} -> {}",
+                        update.order_id, update.venue, update.previous, update.status
+                    );
+                }
+            }
+            Err(e) => warn!("Order status polling failed: {}", e),
+        }
+    }
+
+    /// Generate and store a TCA report for the last interval's orders,
+    /// lagged by the longest markout horizon
+    async fn report_tca(&self) {
+        #[cfg(feature = "storage")]
+        if let (Some(store), Some(storage), Some(interval)) =
+            (&self.execution_store, &self.storage, self.tca.report_interval())
+        {
+            let lag = chrono::Duration::from_std(self.tca.max_horizon()).unwrap_or_else(|_| chrono::Duration::zero());
+            let span = chrono::Duration::from_std(interval).unwrap_or_else(|_| chrono::Duration::zero());
+            let end = Utc::now() - lag;
+            let start = end - span;
+
+            let report = {
+                let storage = storage.lock().await;
+                crate::tca::generate_tca_report(
+                    store,
+                    &storage,
+                    start,
+                    end,
+                    ag_storage::OrderFilters::default(),
+                    &self.labeler,
+                    &self.tca.tca_config(),
+                )
+                .await
+            };
+            match report {
+                Ok(report) => {
+                    if let Err(e) = storage.lock().await.store_tca_report(&self.bot_name, &report).await {
+                        error!("Failed to store TCA report: {}", e);
+                    }
+                }
+                Err(e) => error!("Failed to generate TCA report: {}", e),
+            }
+        }
+    }
+
+    /// Re-subscribe universe-following strategies to a new scanner universe
+    ///
+    /// Market data is requested for newly traded markets and released for
+    /// markets no strategy trades anymore; exec orders left in those markets
+    /// are cancelled.
+    async fn apply_universe(&self, update: UniverseUpdate, market_data: &mut Option<Box<dyn MarketDataSource>>) {
+        if self.session_end_dates {
+            let mut sessions = self.sessions.lock();
+            for ranked in &update.universe {
+                if let Some(end_date) = ranked.market.end_date {
+                    sessions.schedule_close(ranked.market.market_id.as_str(), end_date);
+                }
+            }
+        }
+        if self.universe_strategies.is_empty() {
+            return;
+        }
+        // Stopped markets are resubscribed when their session resumes;
+        // halted ones not at all
+        let markets: Vec<String> = {
+            let sessions = self.sessions.lock();
+            let halted = self.halted_markets.lock();
+            update
+                .market_ids()
+                .into_iter()
+                .map(|m| m.0)
+                .filter(|m| !sessions.is_stopped(m) && !halted.contains(m))
+                .collect()
+        };
+        let strategies: Vec<String> = {
+            let halted = self.halted_strategies.lock();
+            self.universe_strategies.iter().filter(|id| !halted.contains(*id)).cloned().collect()
+        };
+
+        let mut subscribed = Vec::new();
+        let mut unsubscribed = Vec::new();
+        {
+            let mut coordinator = self.coordinator.lock().await;
+            for strategy_id in &strategies {
+                let changes = match coordinator.set_strategy_markets(strategy_id, &markets).await {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        error!("Failed to update markets for strategy {}: {}", strategy_id, e);
+                        continue;
+                    }
+                };
+                for removal in &changes.removals {
+                    if let Some(position) = &removal.residual_position {
+                        warn!(
+                            "Strategy {} left market {} holding position {}",
+                            strategy_id, removal.market_id, position.size
+                        );
+                    }
+                }
+                if !changes.deferred.is_empty() {
+                    info!(
+                        "Strategy {} not onboarded to {:?}: new market onboarding is disabled",
+                        strategy_id, changes.deferred
+                    );
+                }
+                unsubscribed.extend(changes.unsubscribed());
+                subscribed.extend(changes.subscribed);
+            }
+        }
+
+        if let Some(source) = market_data.as_mut() {
+            if !subscribed.is_empty() {
+                if let Err(e) = source.subscribe(&subscribed).await {
+                    error!("Failed to subscribe to {:?}: {}", subscribed, e);
+                }
+            }
+        }
+
+        if !unsubscribed.is_empty() {
+            self.release_markets(&unsubscribed, market_data).await;
+            self.cancel_orders_in(&unsubscribed).await;
+        }
+        info!(
+            "Bot '{}' universe updated: {} markets, +{} / -{} subscriptions",
+            self.bot_name,
+            markets.len(),
+            subscribed.len(),
+            unsubscribed.len()
+        );
+    }
+
+    /// Track the tick's sequence number and timestamp; returns whether the
+    /// tick should be applied
+    ///
+    /// Gaps are logged (and trigger a resync of the market if configured);
+    /// duplicate and out-of-order ticks are dropped if configured.
+    async fn check_sequence(&self, tick: &MarketTick, market_data: &mut Option<Box<dyn MarketDataSource>>) -> bool {
+        let (event, config) = {
+            let mut sequence = self.sequence.lock();
+            (sequence.observe(&tick.market, tick.sequence, tick.timestamp), *sequence.config())
+        };
+
+        match event {
+            SequenceEvent::Gap { expected, received, missing } => {
+                warn!("Sequence gap in {}: expected {}, received {} ({} missing)", tick.market, expected, received, missing);
+            }
+            SequenceEvent::TimeGap { silence } => {
+                warn!("No market data for {} in {}ms", tick.market, silence.num_milliseconds());
+            }
+            SequenceEvent::Duplicate | SequenceEvent::OutOfOrder => {
+                debug!("{:?} tick for {} at {}", event, tick.market, tick.timestamp);
+                return !config.drop_out_of_order;
+            }
+            SequenceEvent::First | SequenceEvent::InOrder => {}
+        }
+
+        if event.is_gap() && config.resync_on_gap {
+            if let Some(source) = market_data.as_mut() {
+                if let Err(e) = source.resync(std::slice::from_ref(&tick.market)).await {
+                    error!("Failed to resync {}: {}", tick.market, e);
+                }
+            }
+        }
+        true
+    }
+
+    /// Drop exact duplicate ticks and ticks too far behind the market's
+    /// latest, correcting late timestamps within tolerance; returns whether
+    /// the tick should be processed
+    fn check_hygiene(&self, tick: &mut MarketTick) -> bool {
+        let Some(hygiene) = &self.hygiene else {
+            return true;
+        };
+        let verdict = hygiene.lock().check(tick);
+        if verdict != TickVerdict::Accepted {
+            debug!("{:?} tick for {} at {}", verdict, tick.market, tick.timestamp);
+        }
+        !verdict.is_dropped()
+    }
+
+    /// Activation of whichever risk engine has its kill-switch on
+    async fn kill_switch_activation(&self) -> Option<KillSwitchEvent> {
+        if let Some(event) = self.risk_engine.lock().kill_switch_activation() {
+            return Some(event);
+        }
+        match self.exec_engine.risk_engine() {
+            Some(risk_engine) => risk_engine.lock().await.kill_switch_activation(),
+            None => None,
+        }
+    }
+
+    /// Whether either risk engine has its kill-switch on
+    async fn kill_switch_active(&self) -> bool {
+        if self.risk_engine.lock().is_kill_switch_active() {
+            return true;
+        }
+        match self.exec_engine.risk_engine() {
+            Some(risk_engine) => risk_engine.lock().await.is_kill_switch_active(),
+            None => false,
+        }
+    }
+
+    /// Mirror a venue state change into strategy contexts and alert
+    fn apply_venue_change(&self, change: &VenueStatusChange) {
+        self.venues.set(change.venue.as_str(), change.to.to_strategy());
+        if change.to > change.from {
+            error!(
+                "Bot '{}' venue {} {} -> {}: {}; new entries paused",
+                self.bot_name, change.venue, change.from, change.to, change.reason
+            );
+        } else {
+            info!(
+                "Bot '{}' venue {} {} -> {}: {}",
+                self.bot_name, change.venue, change.from, change.to, change.reason
+            );
+        }
+    }
+
+    /// Log stage changes of both risk engines' breach recovery workflows
+    async fn log_recovery_events(&self) {
+        let mut events = self.risk_engine.lock().poll_recovery();
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            events.extend(risk_engine.lock().await.poll_recovery());
+        }
+        for event in events {
+            match event.until {
+                Some(until) => warn!(
+                    "Bot '{}' risk recovery: {} until {} ({})",
+                    self.bot_name,
+                    event.stage.as_str(),
+                    until.to_rfc3339(),
+                    event.reason
+                ),
+                None => info!("Bot '{}' risk recovery complete: full limits restored ({})", self.bot_name, event.reason),
+            }
+        }
+    }
+
+    /// Cancel every resting order and, if configured, liquidate every
+    /// open position
+    async fn on_kill_switch(&self) {
+        let mut markets: Vec<String> = match self.exec_engine.get_active_orders() {
+            Ok(orders) => orders.into_iter().map(|o| o.market.0).collect(),
+            Err(e) => {
+                error!("Failed to list active orders: {}", e);
+                Vec::new()
+            }
+        };
+        let positions = self.exec_engine.get_all_positions().await;
+        markets.extend(positions.iter().filter(|(_, size)| size.abs() > 1e-8).map(|(market, _)| market.clone()));
+        markets.sort();
+        markets.dedup();
+
+        match self.kill_switch_activation().await {
+            Some(event) => warn!(
+                "Bot '{}' kill-switch active ({}: {}): halting {} markets",
+                self.bot_name,
+                event.source,
+                event.reason,
+                markets.len()
+            ),
+            None => warn!("Bot '{}' kill-switch active: halting {} markets", self.bot_name, markets.len()),
+        }
+        self.cancel_orders_in(&markets).await;
+
+        if self.kill_switch_flatten {
+            for (market, size) in &positions {
+                if size.abs() > 1e-8 {
+                    self.start_liquidation(market, "kill-switch");
+                }
+            }
+        }
+    }
+
+    /// Liquidate a market's position in the background, unless a
+    /// liquidation of it is already running
+    fn start_liquidation(&self, market: &str, reason: &'static str) {
+        let mut liquidations = self.liquidations.lock();
+        if liquidations.get(market).is_some_and(|handle| !handle.is_finished()) {
+            return;
+        }
+
+        let engine = self.exec_engine.clone();
+        let config = self.liquidation;
+        let market_id = MarketId::new(market.to_string());
+        let handle = tokio::spawn(async move {
+            match liquidate(&engine, &market_id, &config).await {
+                Ok(report) if report.is_flat() => info!(
+                    "Liquidated {} in {} on {} ({} orders, {:?})",
+                    report.initial_position, market_id, reason, report.orders_sent, report.elapsed
+                ),
+                Ok(report) => error!(
+                    "Liquidation of {} on {} timed out with position {}",
+                    market_id, reason, report.final_position
+                ),
+                Err(e) => error!("Failed to liquidate {} on {}: {}", market_id, reason, e),
+            }
+        });
+        liquidations.insert(market.to_string(), handle);
+    }
+
+    /// Halt one strategy or market on request
+    ///
+    /// See [`Bot::halt_strategy`] and [`Bot::halt_market`].
+    async fn halt(
+        &self,
+        target: &HaltTarget,
+        liquidate: bool,
+        market_data: &mut Option<Box<dyn MarketDataSource>>,
+    ) -> BotResult<HaltReport> {
+        let removals = {
+            let mut coordinator = self.coordinator.lock().await;
+            match target {
+                HaltTarget::Strategy(strategy_id) => coordinator.halt_strategy(strategy_id).await?,
+                HaltTarget::Market(market) => coordinator.halt_market(market).await?,
+            }
+        };
+        let mut report = HaltReport::new(target.clone(), removals);
+        match target {
+            HaltTarget::Strategy(strategy_id) => self.halted_strategies.lock().insert(strategy_id.clone()),
+            HaltTarget::Market(market) => self.halted_markets.lock().insert(market.clone()),
+        };
+
+        report.unsubscribed = report
+            .removals
+            .iter()
+            .filter(|r| r.unsubscribed)
+            .map(|r| r.market_id.clone())
+            .collect();
+        if !report.unsubscribed.is_empty() {
+            self.release_markets(&report.unsubscribed, market_data).await;
+        }
+
+        match target {
+            HaltTarget::Strategy(strategy_id) => {
+                let unsubscribed = &report.unsubscribed;
+                report.cancelled_orders = self
+                    .cancel_orders(|o| {
+                        o.strategy_id.as_deref() == Some(strategy_id.as_str()) || unsubscribed.contains(&o.market.0)
+                    })
+                    .await;
+                for removal in report.removals.iter().filter(|r| r.residual_position.is_some()) {
+                    if !removal.unsubscribed {
+                        report.shared_positions.push(removal.market_id.clone());
+                    } else if liquidate {
+                        report.liquidating.push(removal.market_id.clone());
+                    }
+                }
+            }
+            HaltTarget::Market(market) => {
+                report.cancelled_orders = self.cancel_orders(|o| o.market.as_str() == market).await;
+                if liquidate && self.exec_engine.get_position(market).await.abs() > 1e-8 {
+                    report.liquidating.push(market.clone());
+                }
+            }
+        }
+
+        let reason = match target {
+            HaltTarget::Strategy(_) => "strategy halt",
+            HaltTarget::Market(_) => "market halt",
+        };
+        for market in &report.liquidating {
+            self.start_liquidation(market, reason);
+        }
+
+        warn!(
+            "Bot '{}' halted {}: {} markets removed, {} orders cancelled, liquidating {:?}",
+            self.bot_name,
+            target,
+            report.removals.len(),
+            report.cancelled_orders.len(),
+            report.liquidating
+        );
+        for market in &report.shared_positions {
+            warn!("Halted {} left a position in {}, still traded by other strategies", target, market);
+        }
+        Ok(report)
+    }
+
+    /// React to a market status message of the data feed
+    ///
+    /// Orders resting in a closed market are cancelled. A resolved market
+    /// is settled: exec orders are cancelled, the exec position and every
+    /// strategy's position are settled at the payout, strategies drop the
+    /// market and the settlements are recorded in storage.
+    async fn apply_market_status(&self, update: MarketStatusUpdate, market_data: &mut Option<Box<dyn MarketDataSource>>) {
+        let market = update.market;
+        match update.status {
+            MarketStatus::Open => debug!("Market {} reported open", market),
+            MarketStatus::Closed => {
+                info!("Market {} closed, cancelling its orders", market);
+                self.cancel_orders_in(std::slice::from_ref(&market)).await;
+            }
+            MarketStatus::Resolved { payout } => {
+                // A running liquidation would trade against a settled position
+                if let Some(handle) = self.liquidations.lock().remove(&market) {
+                    handle.abort();
+                }
+                let cancelled = self.cancel_orders(|o| o.market.0 == market).await;
+                let size = self.exec_engine.settle_position(&MarketId::new(market.clone()), payout).await;
+
+                let settlements = match self.coordinator.lock().await.resolve_market(&market, payout).await {
+                    Ok(settlements) => settlements,
+                    Err(e) => {
+                        error!("Failed to settle strategies in resolved market {}: {}", market, e);
+                        Vec::new()
+                    }
+                };
+                if settlements.iter().any(|s| s.removal.unsubscribed) {
+                    self.release_markets(std::slice::from_ref(&market), market_data).await;
+                }
+
+                info!(
+                    "Bot '{}' settled resolved market {} at {}: position {}, {} strategies, {} orders cancelled",
+                    self.bot_name,
+                    market,
+                    payout,
+                    size,
+                    settlements.len(),
+                    cancelled.len()
+                );
+
+                #[cfg(feature = "storage")]
+                self.record_settlements(&market, payout, size, &settlements, update.timestamp).await;
+            }
+        }
+    }
+
+    /// Store the settlements of a resolved market: one record per strategy
+    /// and one for the exec engine's net position
+    #[cfg(feature = "storage")]
+    async fn record_settlements(
+        &self,
+        market: &str,
+        payout: f64,
+        size: f64,
+        settlements: &[ag_strategies::MarketSettlement],
+        timestamp: chrono::DateTime<Utc>,
+    ) {
+        let Some(storage) = &self.storage else {
+            debug!(
+                "Bot '{}' dropped {} settlements of {} (no storage configured)",
+                self.bot_name,
+                settlements.len() + 1,
+                market
+            );
+            return;
+        };
+
+        let record = |strategy_id, size, avg_entry_price, realized_pnl| ag_storage::MarketSettlementRecord {
+            timestamp,
+            market: market.to_string(),
+            strategy_id,
+            payout,
+            size,
+            avg_entry_price,
+            realized_pnl,
+        };
+        let mut records: Vec<_> = settlements
+            .iter()
+            .map(|s| {
+                let position = s.settled_position.as_ref();
+                record(
+                    Some(s.removal.strategy_id.clone()),
+                    position.map_or(0.0, |p| p.size),
+                    position.map(|p| p.entry_price),
+                    Some(s.realized_pnl),
+                )
+            })
+            .collect();
+        records.push(record(None, size, None, None));
+
+        if let Err(e) = storage.lock().await.store_market_settlements(&self.bot_name, &records).await {
+            error!("Failed to store {} settlements of {}: {}", records.len(), market, e);
+        }
+    }
+
+    /// Release market data for markets no strategy trades anymore
+    async fn release_markets(&self, markets: &[String], market_data: &mut Option<Box<dyn MarketDataSource>>) {
+        if let Some(source) = market_data.as_mut() {
+            if let Err(e) = source.unsubscribe(markets).await {
+                error!("Failed to unsubscribe from {:?}: {}", markets, e);
+            }
+        }
+
+        let mut quality = self.market_quality.write().await;
+        let mut sequence = self.sequence.lock();
+        let mut hygiene = self.hygiene.as_ref().map(|hygiene| hygiene.lock());
+        for market in markets {
+            quality.remove_market(&MarketId::new(market.clone()));
+            sequence.reset(market);
+            if let Some(hygiene) = hygiene.as_mut() {
+                hygiene.reset(market);
+            }
+        }
+    }
+
+    /// Cancel active exec orders matching `filter`; returns the cancelled IDs
+    async fn cancel_orders(&self, filter: impl Fn(&Order) -> bool) -> Vec<OrderId> {
+        let orders = match self.exec_engine.get_active_orders() {
+            Ok(orders) => orders,
+            Err(e) => {
+                error!("Failed to list active orders: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut cancelled = Vec::new();
+        for order in orders.into_iter().filter(|o| filter(o)) {
+            match self.exec_engine.cancel_order(order.id).await {
+                Ok(ack) if ack.success => cancelled.push(order.id),
+                Ok(ack) if ack.outcome.is_closed() => {
+                    debug!("Order {:?} in {} closed before its cancel: {:?}", order.id, order.market, ack.outcome)
+                }
+                Ok(ack) => error!("Cancel of order {:?} in {} failed: {:?}", order.id, order.market, ack.message),
+                Err(e) => error!("Failed to cancel order {:?} in {}: {}", order.id, order.market, e),
+            }
+        }
+        cancelled
+    }
+
+    /// Cancel active exec orders in the given markets
+    async fn cancel_orders_in(&self, markets: &[String]) {
+        self.cancel_orders(|o| markets.contains(&o.market.0)).await;
+
+        for market in markets {
+            let position = self.exec_engine.get_position(market).await;
+            if position.abs() > 1e-8 {
+                warn!("Cancelled orders in {} with open position {}", market, position);
+            }
+        }
+    }
+
+    /// Drain strategy metric buffers, market quality summaries, sequence,
+    /// tick hygiene and book validation counters, reconciliation status and risk engine
+    /// metrics and forward them to storage, along with recorded market data
+    async fn flush_metrics(&self, books: Vec<(String, BookValidationStats)>) {
+        #[cfg(feature = "storage")]
+        self.flush_recorded_ticks().await;
+
+        let now = Utc::now();
+        let metrics = {
+            let mut coordinator = self.coordinator.lock().await;
+            for (strategy_id, utilization) in coordinator.loop_utilization() {
+                if utilization >= LOOP_SATURATION_WARN {
+                    warn!(
+                        "Strategy '{}' event loop {:.0}% busy since the last flush; it may start falling behind",
+                        strategy_id,
+                        utilization * 100.0
+                    );
+                }
+            }
+            coordinator.drain_metrics()
+        };
+        let qualities = self.market_quality.read().await.snapshot(now);
+        let sequences: Vec<(String, SequenceStats)> =
+            self.sequence.lock().all_stats().iter().map(|(market, stats)| (market.clone(), stats.clone())).collect();
+        let hygiene: Vec<(String, HygieneStats)> = match &self.hygiene {
+            Some(hygiene) => hygiene.lock().all_stats().into_iter().collect(),
+            None => Vec::new(),
+        };
+        let reconciliation = self.reconciliation.read().await.clone();
+        let rate_limits = self.exec_engine.rate_limiter_status();
+        let flags = self.flags.snapshot();
+        let health = health::health_metrics(&self.labeler, &self.liveness.report(now));
+        self.risk_engine.lock().publish_metrics();
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            risk_engine.lock().await.publish_metrics();
+        }
+        let risk_metrics: Vec<(&str, SeriesValue)> = self
+            .risk_metrics
+            .iter()
+            .flat_map(|(engine, sink)| sink.values().into_iter().map(move |value| (*engine, value)))
+            .collect();
+        if self.metrics_tail.receiver_count() > 0 {
+            for metric in &metrics {
+                let _ = self.metrics_tail.send(MetricSample::from_strategy_metric(self.labeler.apply(metric.clone())));
+            }
+            for (engine, series) in &risk_metrics {
+                let _ = self.metrics_tail.send(MetricSample::from_risk_metric(&self.labeler, engine, series, now));
+            }
+            for (name, labels, value) in &health {
+                let _ = self.metrics_tail.send(MetricSample {
+                    timestamp: now,
+                    name: name.clone(),
+                    labels: labels.clone(),
+                    value: *value,
+                });
+            }
+        }
+        if metrics.is_empty()
+            && qualities.is_empty()
+            && sequences.is_empty()
+            && hygiene.is_empty()
+            && books.is_empty()
+            && reconciliation.is_none()
+            && rate_limits.is_empty()
+            && flags.is_empty()
+            && risk_metrics.is_empty()
+            && health.is_empty()
+        {
+            return;
+        }
+        let strategy_metrics = metrics.len();
+
+        #[cfg(feature = "storage")]
+        if let Some(storage) = &self.storage {
+            let points: Vec<ag_storage::MetricPoint> = metrics
+                .into_iter()
+                .map(|m| to_metric_point(self.labeler.apply(m)))
+                .chain(qualities.iter().flat_map(|q| quality_metric_points(&self.labeler, q)))
+                .chain(
+                    sequences
+                        .iter()
+                        .flat_map(|(market, stats)| market_counter_points(&self.labeler, market, stats.metric_values(), now)),
+                )
+                .chain(
+                    hygiene
+                        .iter()
+                        .flat_map(|(market, stats)| market_counter_points(&self.labeler, market, stats.metric_values(), now)),
+                )
+                .chain(
+                    books
+                        .iter()
+                        .flat_map(|(market, stats)| market_counter_points(&self.labeler, market, stats.metric_values(), now)),
+                )
+                .chain(reconciliation.iter().flat_map(|report| reconciliation_metric_points(&self.labeler, report)))
+                .chain(
+                    rate_limits
+                        .iter()
+                        .flat_map(|(venue, stats)| rate_limit_metric_points(&self.labeler, venue, stats, now)),
+                )
+                .chain(flags.iter().map(|(flag, enabled)| flag_metric_point(&self.labeler, flag, *enabled, now)))
+                .chain(
+                    risk_metrics
+                        .iter()
+                        .map(|(engine, series)| risk_metric_point(&self.labeler, engine, series, now)),
+                )
+                .chain(health.iter().map(|(name, labels, value)| {
+                    let mut point = ag_storage::MetricPoint::new(name.clone(), *value).with_timestamp(now);
+                    point.labels = labels.clone().into_iter().collect();
+                    point
+                }))
+                .collect();
+            let count = points.len();
+            if let Err(e) = storage.lock().await.insert_metrics_batch(points).await {
+                error!("Failed to store {} metrics: {}", count, e);
+            }
+            return;
+        }
+
+        debug!(
+            "Bot '{}' dropped {} strategy metrics, {} market quality summaries and {} sequence summaries (no storage configured)",
+            self.bot_name,
+            strategy_metrics,
+            qualities.len(),
+            sequences.len()
+        );
+    }
+
+    /// Write buffered market data ticks to storage
+    #[cfg(feature = "storage")]
+    async fn flush_recorded_ticks(&self) {
+        let (Some(recorder), Some(storage)) = (&self.recorder, &self.storage) else {
+            return;
+        };
+        let ticks = recorder.lock().drain();
+        if ticks.is_empty() {
+            return;
+        }
+        if let Err(e) = storage.lock().await.insert_market_ticks(&ticks).await {
+            error!("Failed to record {} market ticks: {}", ticks.len(), e);
+        }
+    }
+
+    /// Write the exec engine's risk state to storage
+    async fn snapshot_risk_state(&self) {
+        let Some(risk_engine) = self.exec_engine.risk_engine() else {
+            return;
+        };
+        let snapshot = risk_engine.lock().await.snapshot();
+
+        #[cfg(feature = "storage")]
+        if let Some(storage) = &self.storage {
+            let state = match serde_json::to_value(&snapshot) {
+                Ok(state) => state,
+                Err(e) => {
+                    error!("Failed to serialize risk state: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = storage.lock().await.store_risk_snapshot(&self.bot_name, state).await {
+                error!("Failed to store risk state snapshot: {}", e);
+            }
+            return;
+        }
+
+        debug!(
+            "Bot '{}' dropped risk state snapshot with {} counters (no storage configured)",
+            self.bot_name,
+            snapshot.counters.len()
+        );
+    }
+
+    /// Write every market's netted, marked position to the positions table
+    async fn snapshot_positions(&self) {
+        let positions = self.coordinator.lock().await.get_cross_market_positions();
+
+        #[cfg(feature = "storage")]
+        if let Some(store) = &self.execution_store {
+            let markets: HashSet<&String> = positions.values().flatten().map(|p| &p.market).collect();
+            let mut marks = HashMap::new();
+            let mut venues = HashMap::new();
+            for market in markets {
+                let id = MarketId::new(market.clone());
+                if let Some(mid) = self.exec_engine.get_touch(&id).await.and_then(|touch| touch.mid()) {
+                    marks.insert(market.clone(), mid);
+                }
+                if let Ok(venue) = self.exec_engine.position_venue(&id) {
+                    venues.insert(market.clone(), venue.as_str().to_string());
+                }
+            }
+
+            let snapshots = crate::position_history::position_snapshots(&positions, &marks, &venues, Utc::now());
+            if let Err(e) = store.store_positions(&snapshots).await {
+                error!("Failed to store {} position snapshots: {}", snapshots.len(), e);
+            }
+            return;
+        }
+
+        debug!(
+            "Bot '{}' dropped position snapshot of {} strategies (no storage configured)",
+            self.bot_name,
+            positions.len()
+        );
+    }
+}
+
+async fn next_connection_state(state: &mut Option<watch::Receiver<ConnectionState>>) -> Option<ConnectionState> {
+    match state {
+        Some(state) => match state.changed().await {
+            Ok(()) => Some(state.borrow_and_update().clone()),
+            Err(_) => None,
+        },
+        None => std::future::pending().await,
+    }
+}
+
+async fn next_universe(updates: &mut Option<mpsc::Receiver<UniverseUpdate>>) -> Option<UniverseUpdate> {
+    match updates {
+        Some(updates) => updates.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn next_status(updates: &mut Option<mpsc::Receiver<MarketStatusUpdate>>) -> Option<MarketStatusUpdate> {
+    match updates {
+        Some(updates) => updates.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Book validation counters reported by the market data source
+fn book_validation(source: &Option<Box<dyn MarketDataSource>>) -> Vec<(String, BookValidationStats)> {
+    source.as_ref().map(|source| source.book_validation()).unwrap_or_default()
+}
+
+async fn next_market_tick(source: &mut Option<Box<dyn MarketDataSource>>) -> Option<MarketTick> {
+    match source {
+        Some(source) => source.next_tick().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(feature = "storage")]
+fn to_metric_point(metric: StrategyMetric) -> ag_storage::MetricPoint {
+    let mut point = ag_storage::MetricPoint::new(metric.metric_name, metric.value).with_timestamp(metric.timestamp);
+    point.labels = metric.labels;
+    point
+}
+
+#[cfg(feature = "storage")]
+fn quality_metric_points(labeler: &MetricLabeler, quality: &ag_exec::MarketQuality) -> Vec<ag_storage::MetricPoint> {
+    quality
+        .metric_values()
+        .into_iter()
+        .map(|(name, value)| {
+            let (name, labels) = labeler.market_metric(name, quality.market_id.as_str());
+            let mut point = ag_storage::MetricPoint::new(name, value).with_timestamp(quality.timestamp);
+            point.labels = labels;
+            point
+        })
+        .collect()
+}
+
+#[cfg(feature = "storage")]
+fn market_counter_points(
+    labeler: &MetricLabeler,
+    market: &str,
+    values: Vec<(&'static str, f64)>,
+    timestamp: chrono::DateTime<Utc>,
+) -> Vec<ag_storage::MetricPoint> {
+    values
+        .into_iter()
+        .map(|(name, value)| {
+            let (name, labels) = labeler.market_metric(name, market);
+            let mut point = ag_storage::MetricPoint::new(name, value).with_timestamp(timestamp);
+            point.labels = labels;
+            point
+        })
+        .collect()
+}
+
+#[cfg(feature = "storage")]
+fn reconciliation_metric_points(labeler: &MetricLabeler, report: &ReconciliationReport) -> Vec<ag_storage::MetricPoint> {
+    report
+        .venues
+        .iter()
+        .flat_map(|venue| {
+            venue.metric_values().into_iter().map(|(name, value)| {
+                let (name, labels) = labeler.venue_metric(name, venue.venue.as_str());
+                let mut point = ag_storage::MetricPoint::new(name, value).with_timestamp(report.generated_at);
+                point.labels = labels;
+                point
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "storage")]
+fn rate_limit_metric_points(
+    labeler: &MetricLabeler,
+    venue: &VenueId,
+    stats: &RateLimiterStats,
+    timestamp: chrono::DateTime<Utc>,
+) -> Vec<ag_storage::MetricPoint> {
+    stats
+        .metric_values()
+        .into_iter()
+        .map(|(name, value)| {
+            let (name, labels) = labeler.venue_metric(&name, venue.as_str());
+            let mut point = ag_storage::MetricPoint::new(name, value).with_timestamp(timestamp);
+            point.labels = labels;
+            point
+        })
+        .collect()
+}
+
+#[cfg(feature = "storage")]
+fn flag_metric_point(
+    labeler: &MetricLabeler,
+    flag: &str,
+    enabled: bool,
+    timestamp: chrono::DateTime<Utc>,
+) -> ag_storage::MetricPoint {
+    let mut labels = labeler.labels().clone();
+    labels.insert("flag".to_string(), flag.to_string());
+    let value = if enabled { 1.0 } else { 0.0 };
+    let mut point = ag_storage::MetricPoint::new(labeler.metric_name(FLAG_METRIC), value).with_timestamp(timestamp);
+    point.labels = labels;
+    point
+}
+
+#[cfg(feature = "storage")]
+fn risk_metric_point(
+    labeler: &MetricLabeler,
+    engine: &str,
+    (name, labels, value): &(String, Vec<(String, String)>, f64),
+    timestamp: chrono::DateTime<Utc>,
+) -> ag_storage::MetricPoint {
+    let mut point_labels = labeler.labels().clone();
+    point_labels.insert("engine".to_string(), engine.to_string());
+    point_labels.extend(labels.iter().cloned());
+    let mut point = ag_storage::MetricPoint::new(labeler.metric_name(name), *value).with_timestamp(timestamp);
+    point.labels = point_labels;
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BotBuilder, ChannelSource};
+
+    const YAML: &str = r#"
+name: test-bot
+risk:
+  policies:
+    - type: PositionLimit
+      max_size: 1000.0
+strategies:
+  - id: mm_1
+    kind: market_maker
+    markets: ["m1"]
+    timer:
+      interval_ms: 50
+"#;
+
+    #[tokio::test]
+    async fn test_start_route_stop() {
+        let (tx, source) = ChannelSource::new(16);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(YAML).unwrap())
+            .market_data(source)
+            .build()
+            .await
+            .unwrap();
+
+        assert!(!bot.is_running());
+        bot.start().await.unwrap();
+        assert!(bot.is_running());
+        assert!(matches!(bot.start().await, Err(BotError::AlreadyRunning)));
+
+        tx.send(MarketTick {
+            market: "m1".to_string(),
+            timestamp: Utc::now(),
+            bid: Some(0.49),
+            ask: Some(0.51),
+            bid_size: Some(100.0),
+            ask_size: Some(100.0),
+            last: Some(0.5),
+            volume_24h: None,
+            sequence: None,
+        })
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(120)).await;
+
+        let quality = bot
+            .market_quality()
+            .read()
+            .await
+            .quality(&MarketId::new("m1"), Utc::now())
+            .unwrap();
+        assert!((quality.spread.unwrap() - 0.02).abs() < 1e-9);
+        assert_eq!(quality.bid_depth, 100.0);
+
+        bot.stop().await.unwrap();
+        assert!(!bot.is_running());
+        assert_eq!(bot.coordinator().lock().await.strategy_count(), 0);
+        assert!(matches!(bot.stop().await, Err(BotError::NotRunning)));
+    }
+
+    #[tokio::test]
+    async fn test_periodic_reconciliation() {
+        let yaml = format!("{}reconciliation:\n  interval_ms: 20\n", YAML);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap()).build().await.unwrap();
+        assert!(bot.reconciliation().await.is_none());
+
+        bot.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        bot.stop().await.unwrap();
+
+        // No venues are registered, so there is nothing to disagree about
+        let report = bot.reconciliation().await.unwrap();
+        assert!(report.venues.is_empty());
+        assert!(report.is_consistent());
+    }
+
+    #[tokio::test]
+    async fn test_feed_disconnect_pauses_strategies() {
+        let (_ticks, source) = ChannelSource::new(16);
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(YAML).unwrap())
+            .market_data(source.with_connection_state(state_rx))
+            .build()
+            .await
+            .unwrap();
+        bot.start().await.unwrap();
+
+        state_tx.send_replace(ConnectionState::Reconnecting {
+            attempt: 1,
+            delay: Duration::from_millis(500),
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!bot.coordinator().lock().await.feed_connected());
+
+        state_tx.send_replace(ConnectionState::Connected);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(bot.coordinator().lock().await.feed_connected());
+
+        bot.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolved_market_settled() {
+        let (_ticks, source) = ChannelSource::new(16);
+        let (status_tx, status_rx) = mpsc::channel(4);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(YAML).unwrap())
+            .market_data(source.with_status_updates(status_rx))
+            .build()
+            .await
+            .unwrap();
+        bot.start().await.unwrap();
+
+        // Closing only cancels orders; the strategy keeps the market
+        for status in [MarketStatus::Closed, MarketStatus::Resolved { payout: 1.0 }] {
+            let subscribed = bot.coordinator().lock().await.subscribed_markets();
+            assert_eq!(subscribed, vec!["m1".to_string()]);
+            status_tx
+                .send(MarketStatusUpdate {
+                    market: "m1".to_string(),
+                    status,
+                    timestamp: Utc::now(),
+                })
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        let coordinator = bot.coordinator();
+        assert!(coordinator.lock().await.subscribed_markets().is_empty());
+        assert_eq!(coordinator.lock().await.strategy_count(), 1);
+
+        // The status feed is kept for the next start
+        bot.stop().await.unwrap();
+        assert!(bot.market_status.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_ticks_dropped() {
+        let (tx, source) = ChannelSource::new(16);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(YAML).unwrap())
+            .market_data(source)
+            .build()
+            .await
+            .unwrap();
+        bot.start().await.unwrap();
+
+        let now = Utc::now();
+        for (sequence, spread) in [(2, 0.02), (1, 0.10)] {
+            tx.send(MarketTick {
+                market: "m1".to_string(),
+                timestamp: now,
+                bid: Some(0.5 - spread / 2.0),
+                ask: Some(0.5 + spread / 2.0),
+                bid_size: Some(100.0),
+                ask_size: Some(100.0),
+                last: None,
+                volume_24h: None,
+                sequence: Some(sequence),
+            })
+            .await
+            .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let quality = bot
+            .market_quality()
+            .read()
+            .await
+            .quality(&MarketId::new("m1"), Utc::now())
+            .unwrap();
+        assert!((quality.spread.unwrap() - 0.02).abs() < 1e-9);
+
+        bot.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replayed_ticks_dropped() {
+        let yaml = format!("{}tick_hygiene: {{}}\n", YAML);
+        let (tx, source) = ChannelSource::new(16);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap())
+            .market_data(source)
+            .build()
+            .await
+            .unwrap();
+        bot.start().await.unwrap();
+
+        // A replay resends both ticks; without hygiene the volume jump
+        // would be counted as a second trade
+        let now = Utc::now();
+        for volume in [100.0, 150.0, 100.0, 150.0] {
+            tx.send(MarketTick {
+                market: "m1".to_string(),
+                timestamp: now,
+                bid: Some(0.49),
+                ask: Some(0.51),
+                bid_size: Some(100.0),
+                ask_size: Some(100.0),
+                last: None,
+                volume_24h: Some(volume),
+                sequence: None,
+            })
+            .await
+            .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let quality = bot
+            .market_quality()
+            .read()
+            .await
+            .quality(&MarketId::new("m1"), Utc::now())
+            .unwrap();
+        assert_eq!(quality.trade_count, 1);
+        assert_eq!(quality.traded_volume, 50.0);
+
+        bot.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shadow_replay() {
+        use crate::{ReplaySource, ReplaySpeed};
+
+        // Shadow mode never connects configured venues (this one has no adapter)
+        let yaml = format!("{}exec:\n  venues:\n    - venue_id: unknown\n      api_endpoint: http://localhost\n", YAML);
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+        let tick = MarketTick {
+            market: "m1".to_string(),
+            timestamp: yesterday,
+            bid: Some(0.48),
+            ask: Some(0.52),
+            bid_size: Some(50.0),
+            ask_size: Some(50.0),
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        };
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap())
+            .shadow(ReplaySource::new(vec![tick], ReplaySpeed::Max))
+            .build()
+            .await
+            .unwrap();
+        bot.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let quality = bot
+            .market_quality()
+            .read()
+            .await
+            .quality(&MarketId::new("m1"), yesterday)
+            .unwrap();
+        assert!((quality.spread.unwrap() - 0.04).abs() < 1e-9);
+
+        bot.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_halt_strategy_and_market() {
+        let yaml = format!(
+            "{}  - id: arb_2\n    kind: cross_market_arb\n    markets: [\"m1\", \"m2\"]\n",
+            YAML
+        );
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap()).build().await.unwrap();
+        assert!(matches!(bot.halt_market("m1", false).await, Err(BotError::NotRunning)));
+        bot.start().await.unwrap();
+
+        // m1 is still traded by arb_2, so nothing is released
+        let report = bot.halt_strategy("mm_1", true).await.unwrap();
+        assert_eq!(report.target, HaltTarget::Strategy("mm_1".to_string()));
+        assert_eq!(report.removals.len(), 1);
+        assert!(report.unsubscribed.is_empty());
+        assert!(report.liquidating.is_empty());
+        assert!(bot.halt_strategy("mm_1", true).await.is_err());
+
+        let report = bot.halt_market("m1", true).await.unwrap();
+        assert_eq!(report.unsubscribed, vec!["m1".to_string()]);
+        assert!(report.liquidating.is_empty());
+        {
+            let coordinator = bot.coordinator();
+            let coordinator = coordinator.lock().await;
+            assert_eq!(coordinator.strategy_ids(), vec!["arb_2".to_string()]);
+            assert_eq!(coordinator.subscribed_markets(), vec!["m2".to_string()]);
+        }
+
+        bot.stop().await.unwrap();
+        assert!(matches!(bot.halt_strategy("arb_2", false).await, Err(BotError::NotRunning)));
+    }
+
+    #[tokio::test]
+    async fn test_universe_update_resubscribes() {
+        use ag_exec::scanner::{CatalogMarket, RankedMarket};
+
+        let yaml = YAML.replace("    timer:", "    follow_universe: true\n    timer:");
+        let (updates_tx, updates_rx) = mpsc::channel(4);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap())
+            .universe_updates(updates_rx)
+            .build()
+            .await
+            .unwrap();
+        bot.start().await.unwrap();
+
+        let ranked = RankedMarket {
+            rank: 1,
+            score: 1.0,
+            market: CatalogMarket {
+                market_id: MarketId::new("m2"),
+                question: "m2?".to_string(),
+                category: None,
+                end_date: None,
+                active: true,
+                closed: false,
+                liquidity_usd: 10_000.0,
+                volume_24h_usd: 5_000.0,
+                best_bid: Some(0.49),
+                best_ask: Some(0.51),
+                token_ids: vec![],
+            },
+        };
+        updates_tx
+            .send(UniverseUpdate {
+                scanned_at: Utc::now(),
+                universe: vec![ranked],
+                added: vec![MarketId::new("m2")],
+                retired: vec![],
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        {
+            let coordinator = bot.coordinator();
+            let coordinator = coordinator.lock().await;
+            assert_eq!(coordinator.strategy_markets("mm_1").unwrap(), ["m2".to_string()]);
+            assert_eq!(coordinator.subscribed_markets(), vec!["m2".to_string()]);
+        }
+
+        bot.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_feature_flags_from_config() {
+        use ag_strategies::flags::ENABLE_NEW_MARKET_ONBOARDING;
+
+        let yaml = format!("{}feature_flags:\n  enable_new_market_onboarding: false\n", YAML);
+        let bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap()).build().await.unwrap();
+        let flags = bot.feature_flags();
+        assert!(!flags.is_enabled(ENABLE_NEW_MARKET_ONBOARDING));
+
+        let coordinator = bot.coordinator();
+        assert!(coordinator.lock().await.add_market("mm_1", "m2").await.is_err());
+
+        // Flipped at runtime through the bot's handle, no rebuild needed
+        flags.set(ENABLE_NEW_MARKET_ONBOARDING, true);
+        assert!(coordinator.lock().await.add_market("mm_1", "m2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_control_api() {
+        use crate::control::{ControlClient, ControlRequest, ControlResponse};
+
+        let yaml = format!("{}control:\n  listen: \"127.0.0.1:0\"\nmetrics:\n  flush_interval_ms: 20\n", YAML);
+        let mut bot = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap()).build().await.unwrap();
+        assert!(bot.control_addr().is_none());
+        bot.start().await.unwrap();
+        let mut client = ControlClient::connect(bot.control_addr().unwrap()).await.unwrap();
+
+        let response = client
+            .request(&ControlRequest::PauseStrategy { strategy_id: "mm_1".to_string() })
+            .await
+            .unwrap();
+        assert!(matches!(response, ControlResponse::Done { .. }));
+        let response = client
+            .request(&ControlRequest::PauseStrategy { strategy_id: "ghost".to_string() })
+            .await
+            .unwrap();
+        assert!(matches!(response, ControlResponse::Error { .. }));
+
+        let response = client
+            .request(&ControlRequest::TriggerKillSwitch { reason: "drill".to_string() })
+            .await
+            .unwrap();
+        assert!(matches!(response, ControlResponse::Done { .. }));
+        let response = client
+            .request(&ControlRequest::SetPolicyParam {
+                policy: "PositionLimit".to_string(),
+                param: "max_size".to_string(),
+                value: 250.0,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(response, ControlResponse::Done { .. }));
+
+        let ControlResponse::Status(status) = client.request(&ControlRequest::Status).await.unwrap() else {
+            panic!("expected a status");
+        };
+        assert_eq!(status.name, "test-bot");
+        assert_eq!(status.kill_switch.unwrap().reason, "drill");
+        assert!(status.strategies[0].paused);
+        assert!(bot.risk_engine().lock().is_kill_switch_active());
+        match &bot.risk_engine().lock().policy_config().policies[0] {
+            ag_risk::PolicyRule::PositionLimit { max_size, .. } => assert_eq!(*max_size, 250.0),
+            other => panic!("unexpected policy {:?}", other),
+        }
+
+        // A second connection tails the metrics flushed from now on
+        let mut tail = ControlClient::connect(bot.control_addr().unwrap()).await.unwrap();
+        tail.send(&ControlRequest::TailMetrics {
+            prefix: Some("strategy.loop_utilization".to_string()),
+        })
+        .await
+        .unwrap();
+        let sample = tokio::time::timeout(Duration::from_secs(2), tail.next_response()).await.unwrap().unwrap();
+        let Some(ControlResponse::Metric(sample)) = sample else {
+            panic!("expected a metric, got {:?}", sample);
+        };
+        assert_eq!(sample.name, "strategy.loop_utilization");
+        assert_eq!(sample.labels["strategy_id"], "mm_1");
+
+        bot.stop().await.unwrap();
+        assert!(bot.control_addr().is_none());
+        // Samples flushed before the stop may still be buffered ahead of the close
+        let drained = tokio::time::timeout(Duration::from_secs(2), async {
+            while let Some(response) = tail.next_response().await.unwrap() {
+                assert!(matches!(response, ControlResponse::Metric(_)), "unexpected {:?}", response);
+            }
+        });
+        drained.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_strategy_kind() {
+        let yaml = YAML.replace("market_maker", "does_not_exist");
+        let result = BotBuilder::new(BotConfig::from_yaml(&yaml).unwrap()).build().await;
+        assert!(matches!(result, Err(BotError::UnknownStrategy(_))));
+    }
+}
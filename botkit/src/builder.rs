@@ -0,0 +1,443 @@
+//! Bot builder composing all components from a single config
+
+use crate::bot::Bot;
+use crate::config::{read_env, BotConfig, StrategySection, VenueSection};
+use crate::config_layers::ConfigLayers;
+use crate::error::{BotError, BotResult};
+use crate::market_data::MarketDataSource;
+use crate::replay::ReplaySource;
+use crate::validate::ConfigValidator;
+use ag_exec::adapters::{VenueAdapter, VenueConfig};
+use ag_exec::ratelimit::{RateLimiter, RateLimiterConfig};
+use ag_exec::scanner::UniverseUpdate;
+use ag_exec::venues::PolymarketAdapter;
+use ag_exec::{ExecutionEngine, ExecutionEngineConfig, SymbolMapper, VenueId};
+use ag_risk::clock::{SharedClock, SharedIdGenerator};
+use ag_risk::{RiskEngine, RiskStateSnapshot};
+use ag_strategies::r#impl::{
+    CrossMarketArbConfig, CrossMarketArbStrategy, MarketMakerConfig, MarketMakerStrategy, PairsTradingConfig,
+    PairsTradingStrategy,
+};
+use ag_strategies::{
+    FeatureFlags, MultiMarketCoordinator, Strategy, StrategyContext, StrategyParams, StrategyRegistry,
+};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::info;
+
+/// Constructs a strategy from its config entry
+pub type StrategyFactory =
+    Box<dyn Fn(&StrategySection) -> BotResult<Box<dyn Strategy>> + Send + Sync>;
+
+/// Builder for [`Bot`]
+///
+/// ```rust,no_run
+/// use ag_botkit::{BotBuilder, ChannelSource};
+///
+/// # async fn run() -> ag_botkit::BotResult<()> {
+/// let (_tx, source) = ChannelSource::new(1024);
+/// let mut bot = BotBuilder::from_yaml_file("bot.yaml")?
+///     .market_data(source)
+///     .build()
+///     .await?;
+///
+/// bot.start().await?;
+/// // ...
+/// bot.stop().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BotBuilder {
+    config: BotConfig,
+    factories: HashMap<String, StrategyFactory>,
+    market_data: Option<Box<dyn MarketDataSource>>,
+    universe_updates: Option<mpsc::Receiver<UniverseUpdate>>,
+    adapters: Vec<(Box<dyn VenueAdapter>, RateLimiter)>,
+    plugin_dir: Option<String>,
+    shadow: bool,
+    clock: Option<SharedClock>,
+    ids: Option<SharedIdGenerator>,
+}
+
+impl BotBuilder {
+    /// Create a builder from a parsed configuration
+    ///
+    /// The built-in `market_maker`, `cross_market_arb` and `pairs_trading`
+    /// strategy kinds are pre-registered.
+    pub fn new(config: BotConfig) -> Self {
+        let mut builder = Self {
+            config,
+            factories: HashMap::new(),
+            market_data: None,
+            universe_updates: None,
+            adapters: Vec::new(),
+            plugin_dir: None,
+            shadow: false,
+            clock: None,
+            ids: None,
+        };
+        builder.factories.insert("market_maker".to_string(), Box::new(market_maker_factory));
+        builder.factories.insert("cross_market_arb".to_string(), Box::new(cross_market_arb_factory));
+        builder.factories.insert("pairs_trading".to_string(), Box::new(pairs_trading_factory));
+        builder
+    }
+
+    /// Create a builder from a YAML config file
+    ///
+    /// The file is checked with the default [`ConfigValidator`]; every
+    /// problem is reported in [`BotError::InvalidConfig`].
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> BotResult<Self> {
+        Self::from_yaml_file_with(path, &ConfigValidator::new())
+    }
+
+    /// Create a builder from a YAML config file using a custom validator
+    /// (e.g. one with parameter schemas for custom strategy kinds)
+    pub fn from_yaml_file_with(path: impl AsRef<Path>, validator: &ConfigValidator) -> BotResult<Self> {
+        let config = validator.validate_file(path).map_err(BotError::InvalidConfig)?;
+        Ok(Self::new(config))
+    }
+
+    /// Create a builder from layered configuration (file, environment and
+    /// CLI overrides)
+    ///
+    /// The merged config is checked with `validator`; issue locations refer
+    /// to the merged YAML (see [`ConfigLayers::merged_yaml`]).
+    pub fn from_layers(layers: &ConfigLayers, validator: &ConfigValidator) -> BotResult<Self> {
+        let config = validator.validate_str(&layers.merged_yaml()?).map_err(BotError::InvalidConfig)?;
+        Ok(Self::new(config))
+    }
+
+    /// Register a factory for a custom strategy kind
+    pub fn strategy_factory<F>(mut self, kind: impl Into<String>, factory: F) -> Self
+    where
+        F: Fn(&StrategySection) -> BotResult<Box<dyn Strategy>> + Send + Sync + 'static,
+    {
+        self.factories.insert(kind.into(), Box::new(factory));
+        self
+    }
+
+    /// Set the market data source
+    pub fn market_data(mut self, source: impl MarketDataSource + 'static) -> Self {
+        self.market_data = Some(Box::new(source));
+        self
+    }
+
+    /// Shadow-test the configured strategies against replayed market data
+    ///
+    /// The bot is named `<name>-shadow` so its metrics stay apart from the
+    /// live bot's, configured venues are not connected (strategy orders stay
+    /// on the strategy contexts' paper engines; adapters registered with
+    /// [`venue_adapter`](Self::venue_adapter) are still used) and market data
+    /// recording is disabled.
+    pub fn shadow(mut self, replay: ReplaySource) -> Self {
+        self.market_data = Some(Box::new(replay));
+        self.shadow = true;
+        self
+    }
+
+    /// Feed market scanner universe updates to the bot
+    ///
+    /// Strategies with `follow_universe: true` are re-subscribed to each
+    /// update's universe (see [`MarketScanner::run`](ag_exec::scanner::MarketScanner::run)).
+    pub fn universe_updates(mut self, updates: mpsc::Receiver<UniverseUpdate>) -> Self {
+        self.universe_updates = Some(updates);
+        self
+    }
+
+    /// Register a venue adapter in addition to those in the config
+    pub fn venue_adapter(mut self, adapter: Box<dyn VenueAdapter>, rate_limiter: RateLimiter) -> Self {
+        self.adapters.push((adapter, rate_limiter));
+        self
+    }
+
+    /// Load strategy plugins from a directory (overrides `plugin_dir` in the config)
+    ///
+    /// Plugin strategy kinds are resolved after registered factories.
+    /// Requires the `plugins` feature.
+    pub fn plugin_dir(mut self, dir: impl Into<String>) -> Self {
+        self.plugin_dir = Some(dir.into());
+        self
+    }
+
+    /// Stamp orders, acks, positions and metrics with `clock` instead of the
+    /// system clock, in the execution engine, coordinator and every
+    /// strategy context (e.g. a `MockClock` in tests)
+    pub fn clock(mut self, clock: SharedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Draw order IDs from `ids` in the execution engine and every
+    /// strategy context (e.g. a `SequentialIdGenerator` in tests)
+    pub fn id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = Some(ids);
+        self
+    }
+
+    /// Build all components and return a stopped bot
+    pub async fn build(self) -> BotResult<Bot> {
+        let mut config = self.config;
+        if self.shadow {
+            config.name = format!("{}-shadow", config.name);
+            config.recording.market_data = false;
+        }
+        info!("Building bot '{}'", config.name);
+
+        // Storage
+        #[cfg(feature = "storage")]
+        let storage = match &config.storage {
+            Some(storage_config) => Some(ag_storage::StorageEngine::new(storage_config.clone()).await?),
+            None => None,
+        };
+
+        // Order/fill store for TCA reports, strategy runs and position history
+        #[cfg(feature = "storage")]
+        let execution_store = match &config.storage {
+            Some(storage_config)
+                if config.tca.report_interval_ms.is_some()
+                    || config.runs.record
+                    || config.position_history.snapshot_interval_ms.is_some() =>
+            {
+                Some(ag_storage::ExecutionStore::new(storage_config.clone()).await?)
+            }
+            _ => None,
+        };
+
+        // Risk state from the last run
+        #[cfg(feature = "storage")]
+        let risk_state = match (&storage, config.risk_state.restore_on_start) {
+            (Some(storage), true) => load_risk_state(storage, &config.name).await?,
+            _ => None,
+        };
+        #[cfg(not(feature = "storage"))]
+        let risk_state: Option<RiskStateSnapshot> = None;
+
+        let new_risk_engine = || {
+            let engine = RiskEngine::new(config.risk.clone());
+            if let Some(snapshot) = &risk_state {
+                engine.restore(snapshot);
+            }
+            engine
+        };
+
+        // Risk engine for strategy pre-trade checks
+        let risk_engine = Arc::new(Mutex::new(new_risk_engine()));
+
+        // Execution engine
+        let mut exec_engine = ExecutionEngine::new(ExecutionEngineConfig {
+            enable_risk_checks: config.exec.enable_risk_checks,
+            enable_validation: config.exec.enable_validation,
+            enable_metrics: config.exec.enable_metrics,
+        });
+        exec_engine.set_risk_engine(new_risk_engine());
+        if let Some(clock) = &self.clock {
+            exec_engine.set_clock(clock.clone());
+        }
+        if let Some(ids) = &self.ids {
+            exec_engine.set_id_generator(ids.clone());
+        }
+        if let Some(path) = &config.exec.symbol_cache {
+            exec_engine.set_symbol_mapper(SymbolMapper::with_cache(path)?);
+        }
+
+        if self.shadow {
+            info!("Shadow mode: not connecting {} configured venues", config.exec.venues.len());
+        } else {
+            for venue in &config.exec.venues {
+                let (adapter, rate_limiter) = build_venue(venue)?;
+                exec_engine.register_adapter(adapter, rate_limiter);
+            }
+        }
+        for (adapter, rate_limiter) in self.adapters {
+            exec_engine.register_adapter(adapter, rate_limiter);
+        }
+        if let Some(polling) = &config.status_polling {
+            exec_engine.enable_status_polling(polling.clone());
+        }
+        if let Some(venue_status) = &config.venue_status {
+            exec_engine.enable_venue_status(venue_status.clone());
+        }
+
+        // Plugin strategies
+        let mut plugins = StrategyRegistry::new();
+        if let Some(dir) = self.plugin_dir.as_ref().or(config.plugin_dir.as_ref()) {
+            load_plugins(&mut plugins, dir)?;
+        }
+
+        // Strategies
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.set_feature_flags(FeatureFlags::new(config.feature_flags.clone()));
+        if let Some(clock) = &self.clock {
+            coordinator.set_clock(clock.clone());
+        }
+        for section in &config.strategies {
+            let params = StrategyParams {
+                params: section.params.clone(),
+            };
+            let strategy = match self.factories.get(&section.kind) {
+                Some(factory) => factory(section)?,
+                None if plugins.contains(&section.kind) => {
+                    plugins.create(&section.kind, &params, &section.markets)?
+                }
+                None => return Err(BotError::UnknownStrategy(section.kind.clone())),
+            };
+
+            let mut context = StrategyContext::new(section.id.clone(), risk_engine.clone(), params);
+            if let Some(ids) = &self.ids {
+                context.set_id_generator(ids.clone());
+            }
+
+            match section.timer {
+                Some(timer) => {
+                    coordinator
+                        .register_strategy_with_timer(
+                            section.id.clone(),
+                            strategy,
+                            context,
+                            section.markets.clone(),
+                            timer.into(),
+                        )
+                        .await?
+                }
+                None => {
+                    coordinator
+                        .register_strategy(section.id.clone(), strategy, context, section.markets.clone())
+                        .await?
+                }
+            }
+            if let Some(weight) = section.capital_weight {
+                coordinator.set_capital_weight(&section.id, weight)?;
+            }
+            info!("Registered strategy {} ({})", section.id, section.kind);
+        }
+
+        let mut bot = Bot::new(config, coordinator, exec_engine, risk_engine, self.market_data, plugins);
+        bot.set_universe_updates(self.universe_updates);
+        #[cfg(feature = "storage")]
+        {
+            bot.set_storage(storage);
+            bot.set_execution_store(execution_store);
+        }
+
+        Ok(bot)
+    }
+}
+
+/// Load the latest risk state snapshot for a bot
+#[cfg(feature = "storage")]
+async fn load_risk_state(
+    storage: &ag_storage::StorageEngine,
+    bot_name: &str,
+) -> BotResult<Option<RiskStateSnapshot>> {
+    let Some((taken_at, state)) = storage.load_latest_risk_snapshot(bot_name).await? else {
+        return Ok(None);
+    };
+
+    let snapshot = RiskStateSnapshot::from_json(&state.to_string()).map_err(BotError::RiskError)?;
+    let kill_switch = match snapshot.kill_switch_history.last() {
+        Some(event) if snapshot.kill_switch_active => format!("active: {}", event),
+        _ if snapshot.kill_switch_active => "active".to_string(),
+        _ => "inactive".to_string(),
+    };
+    info!("Restoring risk state from {} (kill-switch {})", taken_at, kill_switch);
+    Ok(Some(snapshot))
+}
+
+#[cfg(feature = "plugins")]
+fn load_plugins(registry: &mut StrategyRegistry, dir: &str) -> BotResult<()> {
+    // SAFETY: plugin directories are operator-controlled deployment paths,
+    // and the registry is kept alive by the bot for as long as its strategies.
+    let names = unsafe { registry.load_dir(dir)? };
+    info!("Loaded {} plugin strategies from {}: {:?}", names.len(), dir, names);
+    Ok(())
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugins(_registry: &mut StrategyRegistry, dir: &str) -> BotResult<()> {
+    Err(BotError::ConfigError(format!(
+        "plugin_dir '{}' is set but ag-botkit was built without the `plugins` feature",
+        dir
+    )))
+}
+
+/// Build a venue adapter from its config section
+fn build_venue(section: &VenueSection) -> BotResult<(Box<dyn VenueAdapter>, RateLimiter)> {
+    let venue_id = VenueId::new(&section.venue_id);
+
+    let mut venue_config = VenueConfig::new(venue_id.clone(), section.api_endpoint.clone());
+    if let (Some(key), Some(secret)) = (read_env(&section.api_key_env)?, read_env(&section.api_secret_env)?) {
+        venue_config = venue_config.with_credentials(key, secret);
+    }
+    if let Some(ws) = &section.ws_endpoint {
+        venue_config = venue_config.with_ws_endpoint(ws.clone());
+    }
+    if let Some(rounding) = section.rounding {
+        venue_config = venue_config.with_rounding(rounding);
+    }
+    for (key, value) in &section.extra {
+        venue_config = venue_config.with_extra(key.clone(), value.clone());
+    }
+
+    let (adapter, default_limits): (Box<dyn VenueAdapter>, RateLimiterConfig) =
+        match section.venue_id.as_str() {
+            "polymarket" => (
+                Box::new(PolymarketAdapter::new(venue_config)?),
+                RateLimiterConfig::polymarket_default(),
+            ),
+            other => {
+                return Err(BotError::ConfigError(format!(
+                    "No built-in adapter for venue '{}'; register it with BotBuilder::venue_adapter",
+                    other
+                )))
+            }
+        };
+
+    let limits = section.strategy_quotas.iter().fold(
+        RateLimiterConfig::new(
+            section.requests_per_second.unwrap_or(default_limits.requests_per_second),
+            section.burst_size.unwrap_or(default_limits.burst_size),
+        ),
+        |limits, (strategy, quota)| limits.with_strategy_quota(strategy.clone(), *quota),
+    );
+
+    Ok((adapter, limits.build(venue_id)))
+}
+
+fn market_maker_factory(section: &StrategySection) -> BotResult<Box<dyn Strategy>> {
+    let market = section.markets.first().ok_or_else(|| {
+        BotError::ConfigError(format!("Strategy {} requires one market", section.id))
+    })?;
+
+    let config = MarketMakerConfig::from_params(&StrategyParams { params: section.params.clone() });
+
+    Ok(Box::new(MarketMakerStrategy::new(market.clone(), config)))
+}
+
+fn cross_market_arb_factory(section: &StrategySection) -> BotResult<Box<dyn Strategy>> {
+    let [market_a, market_b] = section.markets.as_slice() else {
+        return Err(BotError::ConfigError(format!(
+            "Strategy {} requires exactly two markets",
+            section.id
+        )));
+    };
+
+    let config = CrossMarketArbConfig::from_params(&StrategyParams { params: section.params.clone() });
+
+    Ok(Box::new(CrossMarketArbStrategy::new(market_a.clone(), market_b.clone(), config)))
+}
+
+fn pairs_trading_factory(section: &StrategySection) -> BotResult<Box<dyn Strategy>> {
+    let [market_a, market_b] = section.markets.as_slice() else {
+        return Err(BotError::ConfigError(format!(
+            "Strategy {} requires exactly two markets",
+            section.id
+        )));
+    };
+
+    let config = PairsTradingConfig::from_params(&StrategyParams { params: section.params.clone() });
+
+    Ok(Box::new(PairsTradingStrategy::new(market_a.clone(), market_b.clone(), config)))
+}
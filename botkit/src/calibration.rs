@@ -0,0 +1,89 @@
+//! Fill simulator calibration from stored executions
+//!
+//! Reads historical orders and fills from an [`ExecutionStore`] and feeds
+//! them to a [`FillCalibrator`] so backtests use the live venue's slippage,
+//! fees and latency.
+//!
+//! ```rust,no_run
+//! use ag_botkit::calibration::calibrate_from_store;
+//! use ag_botkit::storage::{ExecutionStore, OrderFilters, StorageConfig};
+//! use ag_botkit::strategies::backtest::FillSimulatorConfig;
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn run() -> ag_botkit::BotResult<()> {
+//! let store = ExecutionStore::new(StorageConfig::default()).await?;
+//! let end = Utc::now();
+//! let report = calibrate_from_store(&store, end - Duration::days(7), end, OrderFilters::default()).await?;
+//!
+//! let config = report.fill_simulator_config("0x123abc", &FillSimulatorConfig::default());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::convert::ToStrategy;
+use crate::error::BotResult;
+use ag_storage::{ExecutionStore, OrderFilters};
+use ag_strategies::backtest::{CalibrationReport, ExecutionSample, FillCalibrator};
+use chrono::{DateTime, Utc};
+use tracing::info;
+
+/// Load fills of orders placed between `start` and `end` as calibration
+/// samples
+///
+/// Each fill is measured against its order's limit price; orders without
+/// one (market orders) are skipped.
+pub async fn load_execution_samples(
+    store: &ExecutionStore,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    filters: OrderFilters,
+) -> BotResult<Vec<ExecutionSample>> {
+    let orders = store.query_orders(start, end, filters).await?;
+
+    let mut samples = Vec::new();
+    for order in orders {
+        let Some(reference_price) = order.price else {
+            continue;
+        };
+
+        for fill in store.query_fills_by_order(order.id).await? {
+            samples.push(ExecutionSample {
+                side: fill.side.to_strategy(),
+                market: fill.market,
+                reference_price,
+                fill_price: fill.price,
+                size: fill.size,
+                fee: fill.fee,
+                is_maker: fill.liquidity.as_deref().and_then(|liquidity| match liquidity {
+                    "maker" => Some(true),
+                    "taker" => Some(false),
+                    _ => None,
+                }),
+                latency_ms: Some((fill.timestamp - order.timestamp).num_milliseconds() as f64),
+            });
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Calibrate a fill simulator from executions stored between `start` and
+/// `end`
+pub async fn calibrate_from_store(
+    store: &ExecutionStore,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    filters: OrderFilters,
+) -> BotResult<CalibrationReport> {
+    let mut calibrator = FillCalibrator::new();
+    calibrator.add_samples(load_execution_samples(store, start, end, filters).await?);
+
+    let report = calibrator.calibrate();
+    info!(
+        "Calibrated fill model from {} fills ({} markets, {} discarded)",
+        report.overall.fills,
+        report.markets.len(),
+        report.discarded
+    );
+    Ok(report)
+}
@@ -0,0 +1,1029 @@
+//! Bot configuration
+//!
+//! A single YAML file describing every component of a bot:
+//!
+//! ```yaml
+//! name: mm-bot
+//! risk:
+//!   policies:
+//!     - type: PositionLimit
+//!       max_size: 1000.0
+//! exec:
+//!   venues:
+//!     - venue_id: polymarket
+//!       api_endpoint: https://clob.polymarket.com
+//!       api_key_env: POLYMARKET_API_KEY
+//!       api_secret_env: POLYMARKET_API_SECRET
+//! strategies:
+//!   - id: mm_1
+//!     kind: market_maker
+//!     markets: ["0x123abc"]
+//!     params:
+//!       target_spread_bps: "25"
+//!     timer:
+//!       interval_ms: 100
+//! metrics:
+//!   flush_interval_ms: 1000
+//!   namespace: agbot
+//!   environment: prod
+//! risk_state:
+//!   snapshot_interval_ms: 60000
+//! position_history:
+//!   snapshot_interval_ms: 300000
+//! market_quality:
+//!   window_secs: 300
+//!   depth_levels: 5
+//! sequence:
+//!   max_time_gap_ms: 30000
+//!   resync_on_gap: true
+//! tick_hygiene:
+//!   tolerance_ms: 50
+//! tca:
+//!   report_interval_ms: 3600000
+//!   markout_horizons_secs: [5, 60, 300]
+//! recording:
+//!   market_data: true
+//! runs:
+//!   code_version: 3f9c2e1
+//! capital:
+//!   poll_interval_ms: 30000
+//! reconciliation:
+//!   interval_ms: 60000
+//! kill_switch:
+//!   flatten: true
+//! shutdown:
+//!   max_attempts: 3
+//!   report_path: logs/shutdown.json
+//! liquidation:
+//!   style:
+//!     type: limit_ladder
+//!     step_bps: 50.0
+//!     max_steps: 10
+//!   step_interval_ms: 1000
+//! hedging:
+//!   venue: binance
+//!   hedge_ratio: 0.8
+//!   perps:
+//!     BTC: { market: BTCUSDT, contract_size: 0.001 }
+//!   markets:
+//!     - { market: "0xbtc100k", underlying: BTC, strike: 100000.0 }
+//!     - { market: "0xbtc110k", underlying: BTC, strike: 110000.0 }
+//! status_polling:
+//!   venues: [polymarket]
+//!   min_interval_ms: 500
+//!   max_interval_ms: 15000
+//!   budget_fraction: 0.25
+//! venue_status:
+//!   health_interval_ms: 10000
+//!   degraded_error_rate: 0.2
+//!   down_error_rate: 0.5
+//!   recovery_secs: 60
+//! position_bootstrap:
+//!   fill_lookback_secs: 86400
+//!   required: true
+//! health:
+//!   stall_after_ms: 30000
+//!   subsystems:
+//!     market_data: 120000
+//! logging:
+//!   strategies:
+//!     mm_1: { file: logs/mm_1.log, level: debug }
+//! session:
+//!   stop_before_secs: 600
+//!   markets:
+//!     - market_id: "0x123abc"
+//!       close_at: 2026-11-03T21:00:00Z
+//!       resume_at: 2026-11-04T14:30:00Z
+//! ```
+
+use crate::error::{BotError, BotResult};
+use crate::session::{MarketSession, SessionManager};
+use ag_exec::adapters::VenueRounding;
+use ag_exec::ratelimit::StrategyQuota;
+use ag_exec::{
+    HedgeConfig, LiquidationConfig, MarketQualityConfig, PositionBootstrapConfig, ReconciliationConfig,
+    SequenceConfig, ShutdownCheckConfig, StatusPollConfig, VenueStatusConfig,
+};
+use ag_risk::RiskPolicyConfig;
+use ag_strategies::{HygieneConfig, TimerConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Top-level bot configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotConfig {
+    /// Bot name (used in logs and metric labels)
+    pub name: String,
+
+    /// Risk policies shared by the exec engine and strategy contexts
+    pub risk: RiskPolicyConfig,
+
+    /// Execution engine and venue configuration
+    #[serde(default)]
+    pub exec: ExecSection,
+
+    /// Strategies to register with the coordinator
+    #[serde(default)]
+    pub strategies: Vec<StrategySection>,
+
+    /// Optional TimescaleDB storage
+    #[cfg(feature = "storage")]
+    #[serde(default)]
+    pub storage: Option<ag_storage::StorageConfig>,
+
+    /// Metrics forwarding
+    #[serde(default)]
+    pub metrics: MetricsSection,
+
+    /// Risk engine state persistence (requires storage)
+    #[serde(default)]
+    pub risk_state: RiskStateSection,
+
+    /// Periodic position snapshots for the positions history (requires
+    /// storage)
+    #[serde(default)]
+    pub position_history: PositionHistorySection,
+
+    /// Per-market spread/depth/volatility recording, flushed with metrics
+    #[serde(default)]
+    pub market_quality: MarketQualityConfig,
+
+    /// Market data sequence/gap tracking, flushed with metrics
+    #[serde(default)]
+    pub sequence: SequenceConfig,
+
+    /// Duplicate tick dropping and per-market timestamp enforcement,
+    /// flushed with metrics (unset = off)
+    #[serde(default)]
+    pub tick_hygiene: Option<HygieneConfig>,
+
+    /// Periodic transaction cost analysis reports (requires storage)
+    #[serde(default)]
+    pub tca: TcaSection,
+
+    /// Market data recording for replay (requires storage)
+    #[serde(default)]
+    pub recording: RecordingSection,
+
+    /// Strategy run history for experiment comparison (requires storage)
+    #[serde(default)]
+    pub runs: RunsSection,
+
+    /// Venue balance polling and capital allocation
+    #[serde(default)]
+    pub capital: CapitalSection,
+
+    /// OMS vs venue reconciliation
+    #[serde(default)]
+    pub reconciliation: ReconciliationSection,
+
+    /// Time-boxed trading sessions around market closes
+    #[serde(default)]
+    pub session: SessionSection,
+
+    /// Reaction to a kill-switch
+    #[serde(default)]
+    pub kill_switch: KillSwitchSection,
+
+    /// Verification that no orders survive shutdown
+    #[serde(default)]
+    pub shutdown: ShutdownSection,
+
+    /// How positions are closed on kill-switch and session stops
+    #[serde(default)]
+    pub liquidation: LiquidationConfig,
+
+    /// Perp hedging of crypto-linked markets (unset = no hedging)
+    #[serde(default)]
+    pub hedging: Option<HedgeConfig>,
+
+    /// Order status polling for venues without streaming updates (unset = off)
+    #[serde(default)]
+    pub status_polling: Option<StatusPollConfig>,
+
+    /// Venue health monitoring; degraded and down venues get no new
+    /// entries (unset = off)
+    #[serde(default)]
+    pub venue_status: Option<VenueStatusConfig>,
+
+    /// Import positions and open orders from the venues on start (unset =
+    /// start flat)
+    #[serde(default)]
+    pub position_bootstrap: Option<PositionBootstrapConfig>,
+
+    /// Operator control API used by `agctl` (unset = off)
+    #[serde(default)]
+    pub control: Option<ControlSection>,
+
+    /// Subsystem heartbeats and stall alerts
+    #[serde(default)]
+    pub health: HealthSection,
+
+    /// Per-strategy log files, see [`LogRouter`](crate::LogRouter)
+    #[serde(default)]
+    pub logging: LoggingSection,
+
+    /// Directory of strategy plugin libraries (requires the `plugins` feature)
+    #[serde(default)]
+    pub plugin_dir: Option<String>,
+
+    /// Initial feature flags (see [`ag_strategies::flags`]); changeable at
+    /// runtime through [`Bot::feature_flags`](crate::Bot::feature_flags)
+    #[serde(default)]
+    pub feature_flags: HashMap<String, bool>,
+}
+
+impl BotConfig {
+    /// Parse configuration from a YAML string
+    pub fn from_yaml(yaml: &str) -> BotResult<Self> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    /// Load configuration from a YAML file
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> BotResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_yaml(&content)
+    }
+
+    /// All markets referenced by configured strategies (deduplicated, in order)
+    pub fn markets(&self) -> Vec<String> {
+        let mut markets: Vec<String> = Vec::new();
+        for strategy in &self.strategies {
+            for market in &strategy.markets {
+                if !markets.contains(market) {
+                    markets.push(market.clone());
+                }
+            }
+        }
+        markets
+    }
+}
+
+/// Execution engine section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSection {
+    /// Enable pre-trade risk checks
+    #[serde(default = "default_true")]
+    pub enable_risk_checks: bool,
+
+    /// Enable order validation
+    #[serde(default = "default_true")]
+    pub enable_validation: bool,
+
+    /// Enable metrics emission
+    #[serde(default = "default_true")]
+    pub enable_metrics: bool,
+
+    /// Venues to connect to
+    #[serde(default)]
+    pub venues: Vec<VenueSection>,
+
+    /// JSON cache of canonical market to venue symbol mappings
+    #[serde(default)]
+    pub symbol_cache: Option<String>,
+}
+
+impl Default for ExecSection {
+    fn default() -> Self {
+        Self {
+            enable_risk_checks: true,
+            enable_validation: true,
+            enable_metrics: true,
+            venues: Vec::new(),
+            symbol_cache: None,
+        }
+    }
+}
+
+/// Venue connection settings
+///
+/// Credentials are read from the named environment variables so secrets
+/// never live in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VenueSection {
+    /// Venue identifier (currently `polymarket` is built in)
+    pub venue_id: String,
+
+    /// REST API endpoint
+    pub api_endpoint: String,
+
+    /// WebSocket endpoint
+    #[serde(default)]
+    pub ws_endpoint: Option<String>,
+
+    /// Environment variable holding the API key
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    /// Environment variable holding the API secret
+    #[serde(default)]
+    pub api_secret_env: Option<String>,
+
+    /// Rate limit (requests per second); venue default if unset
+    #[serde(default)]
+    pub requests_per_second: Option<u32>,
+
+    /// Rate limit burst size; venue default if unset
+    #[serde(default)]
+    pub burst_size: Option<u32>,
+
+    /// Per-strategy caps on new-quote requests; cancels and risk-reducing
+    /// orders always take priority over quotes
+    #[serde(default)]
+    pub strategy_quotas: HashMap<String, StrategyQuota>,
+
+    /// Round prices and sizes to the venue's tick and lot sizes (unset =
+    /// send as submitted)
+    #[serde(default)]
+    pub rounding: Option<VenueRounding>,
+
+    /// Additional venue-specific settings
+    #[serde(default)]
+    pub extra: HashMap<String, String>,
+}
+
+/// Strategy registration entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategySection {
+    /// Unique strategy ID
+    pub id: String,
+
+    /// Strategy kind, resolved through the builder's factory registry
+    pub kind: String,
+
+    /// Markets to subscribe to
+    pub markets: Vec<String>,
+
+    /// Strategy parameters (passed through as `StrategyParams`)
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+
+    /// Optional per-strategy timer
+    #[serde(default)]
+    pub timer: Option<TimerSection>,
+
+    /// Replace `markets` with the scanner universe on each universe update
+    #[serde(default)]
+    pub follow_universe: bool,
+
+    /// Relative share of free capital (default 1.0)
+    #[serde(default)]
+    pub capital_weight: Option<f64>,
+}
+
+/// Per-strategy timer settings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimerSection {
+    /// Timer interval in milliseconds
+    pub interval_ms: u64,
+
+    /// Maximum random jitter in milliseconds
+    #[serde(default)]
+    pub jitter_ms: u64,
+
+    /// Align deadlines to wall-clock multiples of the interval
+    #[serde(default)]
+    pub align: bool,
+}
+
+impl From<TimerSection> for TimerConfig {
+    fn from(section: TimerSection) -> Self {
+        let config = TimerConfig::every(Duration::from_millis(section.interval_ms))
+            .with_jitter(Duration::from_millis(section.jitter_ms));
+        if section.align {
+            config.aligned()
+        } else {
+            config
+        }
+    }
+}
+
+/// Metrics forwarding section
+///
+/// Every forwarded metric is prefixed with `namespace` and labelled with
+/// `bot`, `instance`, `environment` and the static `labels`; see
+/// [`MetricLabeler`](crate::metrics::MetricLabeler).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsSection {
+    /// How often strategy metric buffers are drained, in milliseconds
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+
+    /// Prefix for metric names (`<namespace>.strategy.pnl_usd`)
+    #[serde(default)]
+    pub namespace: Option<String>,
+
+    /// Deployment environment label (e.g. `prod`, `paper`)
+    #[serde(default)]
+    pub environment: Option<String>,
+
+    /// Instance label distinguishing replicas of the same bot
+    #[serde(default)]
+    pub instance: Option<String>,
+
+    /// Additional static labels
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+impl Default for MetricsSection {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: default_flush_interval_ms(),
+            namespace: None,
+            environment: None,
+            instance: None,
+            labels: HashMap::new(),
+        }
+    }
+}
+
+impl MetricsSection {
+    /// Get flush interval as Duration
+    pub fn flush_interval(&self) -> Duration {
+        Duration::from_millis(self.flush_interval_ms)
+    }
+}
+
+/// Risk engine state snapshot section
+///
+/// Snapshots (kill-switch status, stateful policies, counters) are written
+/// to storage periodically and on shutdown, and restored on startup so a
+/// restarted bot resumes with the same risk posture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskStateSection {
+    /// How often to snapshot risk state, in milliseconds (unset = never)
+    #[serde(default)]
+    pub snapshot_interval_ms: Option<u64>,
+
+    /// Restore the latest snapshot when the bot is built
+    #[serde(default = "default_true")]
+    pub restore_on_start: bool,
+}
+
+impl Default for RiskStateSection {
+    fn default() -> Self {
+        Self {
+            snapshot_interval_ms: None,
+            restore_on_start: true,
+        }
+    }
+}
+
+impl RiskStateSection {
+    /// Get snapshot interval as Duration
+    pub fn snapshot_interval(&self) -> Option<Duration> {
+        self.snapshot_interval_ms.map(Duration::from_millis)
+    }
+}
+
+/// Position history section
+///
+/// Every strategy's positions are netted per market, marked at the current
+/// mid and written to the `positions` table with one timestamp per
+/// snapshot, periodically and on shutdown.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PositionHistorySection {
+    /// How often to snapshot positions, in milliseconds (unset = never)
+    #[serde(default)]
+    pub snapshot_interval_ms: Option<u64>,
+}
+
+impl PositionHistorySection {
+    /// Get snapshot interval as Duration
+    pub fn snapshot_interval(&self) -> Option<Duration> {
+        self.snapshot_interval_ms.map(Duration::from_millis)
+    }
+}
+
+/// Transaction cost analysis section
+///
+/// Each report covers the orders placed during one interval, lagged by the
+/// longest markout horizon so every markout has elapsed when it is computed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TcaSection {
+    /// How often to generate a TCA report, in milliseconds (unset = never)
+    #[serde(default)]
+    pub report_interval_ms: Option<u64>,
+
+    /// Markout horizons in seconds
+    #[serde(default = "default_markout_horizons_secs")]
+    pub markout_horizons_secs: Vec<u64>,
+}
+
+impl Default for TcaSection {
+    fn default() -> Self {
+        Self {
+            report_interval_ms: None,
+            markout_horizons_secs: default_markout_horizons_secs(),
+        }
+    }
+}
+
+impl TcaSection {
+    /// Get report interval as Duration
+    pub fn report_interval(&self) -> Option<Duration> {
+        self.report_interval_ms.map(Duration::from_millis)
+    }
+
+    /// Longest markout horizon
+    pub fn max_horizon(&self) -> Duration {
+        Duration::from_secs(self.markout_horizons_secs.iter().copied().max().unwrap_or(0))
+    }
+
+    /// Analyzer settings
+    #[cfg(feature = "storage")]
+    pub fn tca_config(&self) -> ag_storage::TcaConfig {
+        ag_storage::TcaConfig {
+            markout_horizons_secs: self.markout_horizons_secs.clone(),
+        }
+    }
+}
+
+/// Kill-switch section
+///
+/// The risk engines are checked every `check_interval_ms`; when a
+/// kill-switch turns on, resting orders are cancelled and (with `flatten`)
+/// every open position is liquidated with the `liquidation` settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KillSwitchSection {
+    /// Liquidate open positions when the kill-switch turns on
+    #[serde(default)]
+    pub flatten: bool,
+
+    /// How often to check the kill-switch, in milliseconds
+    #[serde(default = "default_kill_switch_check_interval_ms")]
+    pub check_interval_ms: u64,
+}
+
+impl Default for KillSwitchSection {
+    fn default() -> Self {
+        Self {
+            flatten: false,
+            check_interval_ms: default_kill_switch_check_interval_ms(),
+        }
+    }
+}
+
+impl KillSwitchSection {
+    /// Get check interval as Duration
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+}
+
+/// Control section
+///
+/// The bot listens on `listen` for operator commands (status, positions,
+/// orders, pausing strategies, the kill-switch, policy values and a live
+/// metrics tail), sent as newline-delimited JSON by `agctl` or
+/// [`ControlClient`](crate::control::ControlClient). There is no
+/// authentication: bind to a loopback or otherwise private address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ControlSection {
+    /// Address to listen on, e.g. `127.0.0.1:7070` (port 0 picks a free port)
+    #[serde(default = "default_control_listen")]
+    pub listen: String,
+}
+
+impl Default for ControlSection {
+    fn default() -> Self {
+        Self {
+            listen: default_control_listen(),
+        }
+    }
+}
+
+/// Health section
+///
+/// The bot's long-running work (event loop, market data, metrics flush,
+/// exec polling) beats a heartbeat in a
+/// [`LivenessRegistry`](ag_sched::LivenessRegistry); a supervisor task
+/// checks it every `check_interval_ms` and alerts when a subsystem has
+/// been silent for longer than its stall threshold. Periodic jobs never
+/// get a threshold below three of their intervals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthSection {
+    /// How often the supervisor checks heartbeats, in milliseconds
+    #[serde(default = "default_health_check_interval_ms")]
+    pub check_interval_ms: u64,
+
+    /// Silence after which a subsystem counts as stalled, in milliseconds
+    #[serde(default = "default_stall_after_ms")]
+    pub stall_after_ms: u64,
+
+    /// Per-subsystem stall thresholds in milliseconds, e.g. a longer one
+    /// for `market_data` on quiet markets
+    #[serde(default)]
+    pub subsystems: HashMap<String, u64>,
+}
+
+impl Default for HealthSection {
+    fn default() -> Self {
+        Self {
+            check_interval_ms: default_health_check_interval_ms(),
+            stall_after_ms: default_stall_after_ms(),
+            subsystems: HashMap::new(),
+        }
+    }
+}
+
+impl HealthSection {
+    /// Get check interval as Duration
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+
+    /// Stall threshold of `subsystem`, which normally beats every `cycle`
+    /// (None = irregularly)
+    pub fn stall_after(&self, subsystem: &str, cycle: Option<Duration>) -> Duration {
+        if let Some(ms) = self.subsystems.get(subsystem) {
+            return Duration::from_millis(*ms);
+        }
+        let stall_after = Duration::from_millis(self.stall_after_ms);
+        cycle.map_or(stall_after, |cycle| stall_after.max(cycle * 3))
+    }
+}
+
+/// Shutdown section
+///
+/// After `stop` shuts down the strategies, every venue is asked for the
+/// account's open orders; survivors are cancelled again up to
+/// `max_attempts` times. The resulting report (residual orders and
+/// positions) is logged and optionally written to `report_path` as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShutdownSection {
+    /// Verify cancels at the venues on stop
+    #[serde(default = "default_true")]
+    pub verify: bool,
+
+    /// Venue queries before giving up on surviving orders
+    #[serde(default = "default_shutdown_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Wait between cancelling survivors and querying again, in milliseconds
+    #[serde(default = "default_shutdown_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+
+    /// File the shutdown report is written to (unset = log only)
+    #[serde(default)]
+    pub report_path: Option<String>,
+}
+
+impl Default for ShutdownSection {
+    fn default() -> Self {
+        Self {
+            verify: true,
+            max_attempts: default_shutdown_max_attempts(),
+            retry_delay_ms: default_shutdown_retry_delay_ms(),
+            report_path: None,
+        }
+    }
+}
+
+impl ShutdownSection {
+    /// Verification settings
+    pub fn check_config(&self) -> ShutdownCheckConfig {
+        ShutdownCheckConfig {
+            max_attempts: self.max_attempts,
+            retry_delay_ms: self.retry_delay_ms,
+        }
+    }
+}
+
+/// Recording section
+///
+/// Recorded ticks are written to storage with each metrics flush and can be
+/// replayed with [`ReplaySource`](crate::ReplaySource).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordingSection {
+    /// Record every routed market data tick
+    #[serde(default)]
+    pub market_data: bool,
+}
+
+/// Strategy run section
+///
+/// With storage configured, each start records one run per strategy (its
+/// parameters, code version and config hash) and tags the strategy's orders
+/// and metrics with the run ID (see `ag_storage::runs`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunsSection {
+    /// Record strategy runs
+    #[serde(default = "default_true")]
+    pub record: bool,
+
+    /// Code version stored with each run, e.g. a git revision (default:
+    /// the ag-botkit version)
+    #[serde(default)]
+    pub code_version: Option<String>,
+}
+
+impl Default for RunsSection {
+    fn default() -> Self {
+        Self {
+            record: true,
+            code_version: None,
+        }
+    }
+}
+
+impl RunsSection {
+    /// Code version stored with each run
+    pub fn code_version(&self) -> &str {
+        self.code_version.as_deref().unwrap_or(crate::VERSION)
+    }
+}
+
+/// Capital tracking section
+///
+/// On each poll venue balances are refreshed, total cash is reported to the
+/// risk engines for `BankrollLimit` policies and free capital is split
+/// across strategies by `capital_weight`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapitalSection {
+    /// How often to poll venue balances, in milliseconds (unset = never)
+    #[serde(default)]
+    pub poll_interval_ms: Option<u64>,
+}
+
+impl CapitalSection {
+    /// Get poll interval as Duration
+    pub fn poll_interval(&self) -> Option<Duration> {
+        self.poll_interval_ms.map(Duration::from_millis)
+    }
+}
+
+/// Reconciliation section
+///
+/// Every `interval_ms` local open orders, fills and positions are compared
+/// with what each venue reports. The latest report is available from
+/// [`Bot::reconciliation`](crate::Bot::reconciliation) and its per-venue
+/// counts are flushed as metrics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReconciliationSection {
+    /// How often to reconcile, in milliseconds (unset = never)
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+
+    /// Largest position difference treated as agreement
+    #[serde(default = "default_position_tolerance")]
+    pub position_tolerance: f64,
+
+    /// How far back venue fills are compared, in seconds
+    #[serde(default = "default_fill_lookback_secs")]
+    pub fill_lookback_secs: u64,
+}
+
+impl Default for ReconciliationSection {
+    fn default() -> Self {
+        Self {
+            interval_ms: None,
+            position_tolerance: default_position_tolerance(),
+            fill_lookback_secs: default_fill_lookback_secs(),
+        }
+    }
+}
+
+impl ReconciliationSection {
+    /// Get reconciliation interval as Duration
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval_ms.map(Duration::from_millis)
+    }
+
+    /// Reconciler settings
+    pub fn reconciliation_config(&self) -> ReconciliationConfig {
+        ReconciliationConfig {
+            position_tolerance: self.position_tolerance,
+            fill_lookback_secs: self.fill_lookback_secs,
+        }
+    }
+}
+
+/// Per-strategy logging section
+///
+/// Routes logs emitted inside each strategy's callbacks to its own file.
+/// Levels can be changed at runtime with
+/// [`LogRouter::set_level`](crate::LogRouter::set_level).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingSection {
+    /// Log routes keyed by strategy ID
+    #[serde(default)]
+    pub strategies: HashMap<String, StrategyLogSection>,
+}
+
+/// Log route of one strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyLogSection {
+    /// File the strategy's logs are appended to
+    pub file: String,
+
+    /// Most verbose level written (trace, debug, info, warn, error, off)
+    #[serde(default = "default_log_level")]
+    pub level: String,
+}
+
+/// Trading session section
+///
+/// `stop_before_secs` ahead of each market's close the bot stops trading it:
+/// strategies are unsubscribed, resting orders cancelled and (with
+/// `flatten`) the position closed. Trading resumes at `resume_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionSection {
+    /// Default time before a close to stop trading, in seconds
+    #[serde(default = "default_stop_before_secs")]
+    pub stop_before_secs: u64,
+
+    /// Flatten positions when a market stops
+    #[serde(default = "default_true")]
+    pub flatten: bool,
+
+    /// Schedule closes from the end dates of scanned markets
+    #[serde(default)]
+    pub use_end_dates: bool,
+
+    /// Explicit market sessions
+    #[serde(default)]
+    pub markets: Vec<MarketSessionSection>,
+}
+
+impl Default for SessionSection {
+    fn default() -> Self {
+        Self {
+            stop_before_secs: default_stop_before_secs(),
+            flatten: true,
+            use_end_dates: false,
+            markets: Vec::new(),
+        }
+    }
+}
+
+impl SessionSection {
+    /// Default stop buffer as a chrono Duration
+    pub fn stop_before(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.stop_before_secs as i64)
+    }
+
+    /// Build the session manager seeded with the configured sessions
+    pub fn session_manager(&self) -> SessionManager {
+        let mut sessions = SessionManager::new(self.stop_before());
+        for market in &self.markets {
+            sessions.schedule(MarketSession {
+                market_id: market.market_id.clone(),
+                close_at: market.close_at,
+                stop_before: market
+                    .stop_before_secs
+                    .map(|secs| chrono::Duration::seconds(secs as i64))
+                    .unwrap_or_else(|| self.stop_before()),
+                resume_at: market.resume_at,
+            });
+        }
+        sessions
+    }
+}
+
+/// Session of one market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketSessionSection {
+    /// Market identifier
+    pub market_id: String,
+
+    /// Close or resolution time (RFC 3339)
+    pub close_at: DateTime<Utc>,
+
+    /// When trading resumes (unset = stays stopped)
+    #[serde(default)]
+    pub resume_at: Option<DateTime<Utc>>,
+
+    /// Override of the section's `stop_before_secs`
+    #[serde(default)]
+    pub stop_before_secs: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_flush_interval_ms() -> u64 {
+    1000
+}
+
+fn default_stop_before_secs() -> u64 {
+    600
+}
+
+fn default_kill_switch_check_interval_ms() -> u64 {
+    1000
+}
+
+fn default_control_listen() -> String {
+    "127.0.0.1:7070".to_string()
+}
+
+fn default_health_check_interval_ms() -> u64 {
+    1000
+}
+
+fn default_stall_after_ms() -> u64 {
+    30_000
+}
+
+fn default_shutdown_max_attempts() -> u32 {
+    ShutdownCheckConfig::default().max_attempts
+}
+
+fn default_shutdown_retry_delay_ms() -> u64 {
+    ShutdownCheckConfig::default().retry_delay_ms
+}
+
+fn default_position_tolerance() -> f64 {
+    ReconciliationConfig::default().position_tolerance
+}
+
+fn default_fill_lookback_secs() -> u64 {
+    ReconciliationConfig::default().fill_lookback_secs
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_markout_horizons_secs() -> Vec<u64> {
+    vec![5, 60, 300]
+}
+
+/// Read a credential from the environment variable named in the config
+pub(crate) fn read_env(var: &Option<String>) -> BotResult<Option<String>> {
+    match var {
+        Some(name) => std::env::var(name)
+            .map(Some)
+            .map_err(|_| BotError::ConfigError(format!("Environment variable {} is not set", name))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"
+name: test-bot
+risk:
+  policies:
+    - type: PositionLimit
+      max_size: 1000.0
+strategies:
+  - id: mm_1
+    kind: market_maker
+    markets: ["m1"]
+    timer:
+      interval_ms: 100
+  - id: arb_1
+    kind: cross_market_arb
+    markets: ["m1", "m2"]
+"#;
+
+    #[test]
+    fn test_parse_config() {
+        let config = BotConfig::from_yaml(YAML).unwrap();
+        assert_eq!(config.name, "test-bot");
+        assert_eq!(config.strategies.len(), 2);
+        assert!(config.exec.enable_risk_checks);
+        assert_eq!(config.metrics.flush_interval_ms, 1000);
+        assert_eq!(config.markets(), vec!["m1".to_string(), "m2".to_string()]);
+
+        let timer: TimerConfig = config.strategies[0].timer.unwrap().into();
+        assert_eq!(timer.interval, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_missing_env_credential() {
+        let var = Some("AG_BOTKIT_TEST_UNSET_VAR".to_string());
+        assert!(read_env(&var).is_err());
+        assert_eq!(read_env(&None).unwrap(), None);
+    }
+}
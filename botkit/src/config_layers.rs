@@ -0,0 +1,355 @@
+//! Layered configuration: defaults < file < environment < CLI flags
+//!
+//! Containerized deployments need to point a bot at other endpoints or
+//! tighten limits without editing the YAML baked into the image.
+//! [`ConfigLayers`] starts from a config file (or nothing) and applies
+//! overrides on top, always in the same precedence regardless of the order
+//! they were added:
+//!
+//! 1. Defaults: every field the layers leave unset takes its serde default
+//! 2. File: the YAML config
+//! 3. Environment: `AGBOT__<PATH>` variables, `__` separating path segments
+//!    (`AGBOT__EXEC__ENABLE_RISK_CHECKS=false`); paths are lowercased
+//! 4. CLI flags: `--set <path>=<value>` (`--set exec.enable_risk_checks=false`)
+//!
+//! A path is a dotted list of mapping keys. Sequence elements are addressed
+//! by index or by their `id`/`venue_id`, so `strategies.mm_1.params.quote_size`
+//! and `strategies.0.params.quote_size` name the same value. Values are
+//! parsed as YAML scalars (`100`, `true`, `[a, b]`), except that a value
+//! replacing a string stays a string, so strategy params need no quoting.
+//!
+//! Because the sections of the other crates (risk policies, exec venues,
+//! storage, strategies) are part of [`BotConfig`], one set of layers covers
+//! them all; [`ConfigLayers::resolve`] works for any of their config types
+//! on its own as well.
+//!
+//! ```rust,no_run
+//! use ag_botkit::{BotConfig, ConfigLayers};
+//!
+//! # fn run() -> ag_botkit::BotResult<()> {
+//! let layers = ConfigLayers::from_yaml_file("bot.yaml")?
+//!     .with_env()
+//!     .set("metrics.environment", "staging");
+//! let config: BotConfig = layers.resolve()?;
+//! println!("{}", layers.effective_yaml::<BotConfig>()?);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{BotError, BotResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Prefix of environment overrides
+pub const ENV_PREFIX: &str = "AGBOT";
+
+/// Separator of path segments in environment variable names
+const ENV_SEPARATOR: &str = "__";
+
+/// Fields identifying sequence elements in override paths
+const ID_KEYS: [&str; 2] = ["id", "venue_id"];
+
+/// Fields masked in [`ConfigLayers::effective_yaml`]
+const REDACTED_KEYS: [&str; 1] = ["password"];
+
+/// Layer an override comes from, in increasing precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLayer {
+    /// Environment variable
+    Env,
+    /// Command line flag
+    Cli,
+}
+
+/// A single value set on top of the config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    /// Layer the override comes from
+    pub layer: ConfigLayer,
+    /// Dotted path of the value (e.g. `exec.venues.polymarket.api_endpoint`)
+    pub path: String,
+    /// Raw value
+    pub value: String,
+}
+
+/// Config file plus environment and CLI overrides
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    file: Value,
+    overrides: Vec<ConfigOverride>,
+}
+
+impl Default for ConfigLayers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigLayers {
+    /// Layers without a config file; every value comes from overrides or
+    /// defaults
+    pub fn new() -> Self {
+        Self {
+            file: Value::Mapping(Mapping::new()),
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Layers over a YAML config
+    pub fn from_yaml(yaml: &str) -> BotResult<Self> {
+        let file = match serde_yaml::from_str(yaml)? {
+            Value::Null => Value::Mapping(Mapping::new()),
+            value => value,
+        };
+        Ok(Self {
+            file,
+            overrides: Vec::new(),
+        })
+    }
+
+    /// Layers over a YAML config file
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> BotResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_yaml(&content)
+    }
+
+    /// Add overrides from `AGBOT__*` variables in the process environment
+    pub fn with_env(self) -> Self {
+        self.with_env_vars(ENV_PREFIX, std::env::vars())
+    }
+
+    /// Add overrides from `<prefix>__*` variables
+    pub fn with_env_vars<I>(mut self, prefix: &str, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let prefix = format!("{}{}", prefix, ENV_SEPARATOR);
+        let mut vars: Vec<(String, String)> = vars.into_iter().filter(|(name, _)| name.starts_with(&prefix)).collect();
+        // Process environments are unordered; sort so results are reproducible
+        vars.sort();
+        for (name, value) in vars {
+            let path = name[prefix.len()..].to_lowercase().replace(ENV_SEPARATOR, ".");
+            self.overrides.push(ConfigOverride {
+                layer: ConfigLayer::Env,
+                path,
+                value,
+            });
+        }
+        self
+    }
+
+    /// Add a CLI override
+    pub fn set(mut self, path: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push(ConfigOverride {
+            layer: ConfigLayer::Cli,
+            path: path.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Add a CLI override given as `<path>=<value>` (the argument of `--set`)
+    pub fn set_arg(self, assignment: &str) -> BotResult<Self> {
+        match assignment.split_once('=') {
+            Some((path, value)) if !path.is_empty() => Ok(self.set(path, value)),
+            _ => Err(BotError::ConfigError(format!(
+                "Invalid override {:?}, expected <path>=<value>",
+                assignment
+            ))),
+        }
+    }
+
+    /// Overrides in the order they are applied
+    pub fn overrides(&self) -> Vec<&ConfigOverride> {
+        let mut overrides: Vec<&ConfigOverride> = self.overrides.iter().collect();
+        overrides.sort_by_key(|o| o.layer);
+        overrides
+    }
+
+    /// The config file with every override applied
+    pub fn merged(&self) -> BotResult<Value> {
+        let mut merged = self.file.clone();
+        for o in self.overrides() {
+            apply_override(&mut merged, &o.path, &o.value).map_err(|e| {
+                BotError::ConfigError(format!("Cannot apply {:?} override {}: {}", o.layer, o.path, e))
+            })?;
+        }
+        Ok(merged)
+    }
+
+    /// The merged config as YAML, for validation with
+    /// [`ConfigValidator::validate_str`](crate::ConfigValidator::validate_str)
+    pub fn merged_yaml(&self) -> BotResult<String> {
+        Ok(serde_yaml::to_string(&self.merged()?)?)
+    }
+
+    /// Deserialize the merged config, filling unset fields with defaults
+    pub fn resolve<T: DeserializeOwned>(&self) -> BotResult<T> {
+        Ok(serde_yaml::from_value(self.merged()?)?)
+    }
+
+    /// The effective config as YAML: the merged config resolved as `T`
+    /// and serialized back, so defaults are spelled out
+    ///
+    /// Passwords are masked.
+    pub fn effective_yaml<T: DeserializeOwned + Serialize>(&self) -> BotResult<String> {
+        let mut effective = serde_yaml::to_value(self.resolve::<T>()?)?;
+        redact(&mut effective);
+        Ok(serde_yaml::to_string(&effective)?)
+    }
+}
+
+/// Set the value at a dotted path, creating missing mappings on the way
+fn apply_override(root: &mut Value, path: &str, raw: &str) -> Result<(), String> {
+    let segments: Vec<&str> = path.split('.').collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err("empty path segment".to_string());
+    }
+
+    let mut node = root;
+    for (i, segment) in segments.iter().enumerate() {
+        if node.is_null() {
+            *node = Value::Mapping(Mapping::new());
+        }
+        node = match node {
+            Value::Mapping(map) => {
+                let key = Value::String(segment.to_string());
+                if i + 1 < segments.len() {
+                    map.entry(key).or_insert(Value::Null)
+                } else {
+                    let slot = map.entry(key).or_insert(Value::Null);
+                    *slot = parse_value(raw, slot);
+                    return Ok(());
+                }
+            }
+            Value::Sequence(items) => {
+                let index = match segment.parse::<usize>() {
+                    Ok(index) if index < items.len() => index,
+                    Ok(index) => return Err(format!("index {} out of range ({} elements)", index, items.len())),
+                    Err(_) => items
+                        .iter()
+                        .position(|item| ID_KEYS.iter().any(|k| item.get(*k).and_then(Value::as_str) == Some(segment)))
+                        .ok_or_else(|| format!("no element with id {}", segment))?,
+                };
+                let slot = &mut items[index];
+                if i + 1 == segments.len() {
+                    *slot = parse_value(raw, slot);
+                    return Ok(());
+                }
+                slot
+            }
+            _ => return Err(format!("{} is not a mapping or sequence", segments[..i].join("."))),
+        };
+    }
+    Ok(())
+}
+
+/// Parse a raw override value, keeping it a string if it replaces one
+fn parse_value(raw: &str, current: &Value) -> Value {
+    if current.is_string() {
+        return Value::String(raw.to_string());
+    }
+    serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// Mask the values of secret fields
+fn redact(value: &mut Value) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map.iter_mut() {
+                if key.as_str().is_some_and(|k| REDACTED_KEYS.contains(&k)) {
+                    *value = Value::String("***".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Sequence(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BotConfig;
+
+    const YAML: &str = r#"
+name: test-bot
+risk:
+  policies:
+    - type: PositionLimit
+      max_size: 1000.0
+exec:
+  venues:
+    - venue_id: polymarket
+      api_endpoint: https://clob.polymarket.com
+strategies:
+  - id: mm_1
+    kind: market_maker
+    markets: ["m1"]
+    params:
+      quote_size: "100"
+"#;
+
+    #[test]
+    fn test_precedence() {
+        let env = vec![
+            ("AGBOT__METRICS__ENVIRONMENT".to_string(), "staging".to_string()),
+            ("AGBOT__EXEC__VENUES__POLYMARKET__API_ENDPOINT".to_string(), "http://proxy:8080".to_string()),
+            ("OTHER__NAME".to_string(), "ignored".to_string()),
+        ];
+        // The CLI wins over the environment even when added first
+        let layers = ConfigLayers::from_yaml(YAML)
+            .unwrap()
+            .set("metrics.environment", "canary")
+            .with_env_vars(ENV_PREFIX, env)
+            .set_arg("strategies.mm_1.params.quote_size=50")
+            .unwrap()
+            .set("risk.policies.0.max_size", "500")
+            .set("exec.enable_risk_checks", "false");
+        let config: BotConfig = layers.resolve().unwrap();
+
+        assert_eq!(config.name, "test-bot");
+        assert_eq!(config.metrics.environment.as_deref(), Some("canary"));
+        assert_eq!(config.exec.venues[0].api_endpoint, "http://proxy:8080");
+        // Still a string param, not a number
+        assert_eq!(config.strategies[0].params["quote_size"], "50");
+        assert!(!config.exec.enable_risk_checks);
+        match &config.risk.policies[0] {
+            ag_risk::PolicyRule::PositionLimit { max_size, .. } => assert_eq!(*max_size, 500.0),
+            other => panic!("unexpected policy {:?}", other),
+        }
+        assert_eq!(layers.overrides()[0].layer, ConfigLayer::Env);
+    }
+
+    #[test]
+    fn test_overrides_without_file() {
+        let layers = ConfigLayers::new().set("name", "env-only").set("risk.policies", "[]");
+        let config: BotConfig = layers.resolve().unwrap();
+        assert_eq!(config.name, "env-only");
+
+        // Defaults are spelled out in the effective config
+        let effective = layers.effective_yaml::<BotConfig>().unwrap();
+        assert!(effective.contains("enable_risk_checks: true"));
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        let layers = ConfigLayers::from_yaml(YAML).unwrap();
+        assert!(layers.clone().set_arg("no-equals").is_err());
+        assert!(layers.clone().set("strategies.arb_9.params.x", "1").merged().is_err());
+        assert!(layers.clone().set("strategies.5.id", "x").merged().is_err());
+        assert!(layers.clone().set("name.first", "x").merged().is_err());
+    }
+
+    #[test]
+    fn test_redacts_passwords() {
+        let mut value: Value = serde_yaml::from_str("storage:\n  database:\n    password: hunter2\n").unwrap();
+        redact(&mut value);
+        let yaml = serde_yaml::to_string(&value).unwrap();
+        assert!(!yaml.contains("hunter2"));
+    }
+}
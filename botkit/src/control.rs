@@ -0,0 +1,455 @@
+//! Operator control API
+//!
+//! With a `control` section configured, a running [`Bot`](crate::Bot)
+//! accepts operator commands over TCP, one JSON [`ControlRequest`] per line
+//! answered by one JSON [`ControlResponse`] per line:
+//!
+//! ```text
+//! > {"command":"pause_strategy","strategy_id":"mm_1"}
+//! < {"type":"done","message":"paused mm_1"}
+//! > {"command":"set_policy_param","policy":"PositionLimit","param":"max_size","value":500.0}
+//! < {"type":"done","message":"set max_size = 500 on 1 policies in each risk engine"}
+//! ```
+//!
+//! `tail_metrics` turns the connection into a stream of
+//! [`ControlResponse::Metric`] lines, one per strategy or risk metric at
+//! each metrics flush, until the client disconnects.
+//!
+//! The `agctl` binary wraps [`ControlClient`] for the command line:
+//!
+//! ```text
+//! agctl --addr 127.0.0.1:7070 status
+//! agctl pause mm_1
+//! agctl kill "venue outage"
+//! agctl set-policy PositionLimit max_size 500
+//! agctl metrics strategy.
+//! ```
+//!
+//! Kill-switch and policy commands apply to both the strategy and the order
+//! gating risk engine. Paused strategies keep their positions and orders
+//! and still receive fills and cancels, but no book updates or timers.
+
+use crate::error::{BotError, BotResult};
+use crate::metrics::MetricLabeler;
+use ag_exec::{ExecutionEngine, Order, VenueStatus};
+use ag_risk::{KillSwitchEvent, KillSwitchSource, RiskEngine};
+use ag_sched::{HealthReport, LivenessRegistry};
+use ag_strategies::{MultiMarketCoordinator, Position, StrategyMetric};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::{broadcast, watch, Mutex};
+use tracing::{debug, info, warn};
+
+/// Metric samples buffered per `tail_metrics` client before it lags
+pub const METRICS_TAIL_BUFFER: usize = 4096;
+
+/// Command sent to a running bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Kill-switch, strategies, venues, disabled policies and subsystem
+    /// health
+    Status,
+    /// Positions per strategy and per market in the execution engine
+    Positions,
+    /// Orders resting at the venues
+    Orders,
+    /// Stop routing book updates and timers to a strategy
+    PauseStrategy { strategy_id: String },
+    /// Undo `pause_strategy`
+    ResumeStrategy { strategy_id: String },
+    /// Turn the kill-switch on in both risk engines
+    TriggerKillSwitch { reason: String },
+    /// Turn the kill-switch off in both risk engines
+    ResetKillSwitch { reason: String },
+    /// Change a numeric parameter of the policies `policy` names (by type
+    /// or label), see `RiskEngine::set_policy_param`; lasts until the
+    /// policies are reloaded
+    SetPolicyParam { policy: String, param: String, value: f64 },
+    /// Stream metrics whose name starts with `prefix` (all if unset)
+    TailMetrics {
+        #[serde(default)]
+        prefix: Option<String>,
+    },
+}
+
+/// Reply to a [`ControlRequest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlResponse {
+    Status(BotStatus),
+    Positions(PositionsReport),
+    Orders { orders: Vec<Order> },
+    /// A command succeeded
+    Done { message: String },
+    /// One streamed metric
+    Metric(MetricSample),
+    /// A command failed or could not be parsed
+    Error { message: String },
+}
+
+/// Snapshot of a running bot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotStatus {
+    pub name: String,
+    /// Latest activation of a kill-switch that is on (None = trading)
+    pub kill_switch: Option<KillSwitchEvent>,
+    pub feed_connected: bool,
+    pub strategies: Vec<StrategyStatus>,
+    pub venues: BTreeMap<String, VenueStatus>,
+    /// Policies switched off at runtime, by label
+    pub disabled_policies: Vec<String>,
+    /// Heartbeats of the bot's subsystems
+    pub health: HealthReport,
+}
+
+/// State of one registered strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyStatus {
+    pub id: String,
+    pub markets: Vec<String>,
+    pub paused: bool,
+    pub warming_up: bool,
+    /// Events queued for the strategy
+    pub pending_events: usize,
+    pub dropped_book_updates: u64,
+    /// Busy / wall time since the last metrics flush
+    pub loop_utilization: f64,
+}
+
+/// Positions as seen by the strategies and by the execution engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionsReport {
+    /// By strategy
+    pub strategies: BTreeMap<String, Vec<Position>>,
+    /// Net size by market
+    pub exec: BTreeMap<String, f64>,
+}
+
+/// A metric as published at a metrics flush
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub timestamp: DateTime<Utc>,
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+impl MetricSample {
+    /// Sample of a labeled strategy metric
+    pub fn from_strategy_metric(metric: StrategyMetric) -> Self {
+        Self {
+            timestamp: metric.timestamp,
+            name: metric.metric_name,
+            labels: metric.labels.into_iter().collect(),
+            value: metric.value,
+        }
+    }
+
+    /// Sample of a risk engine metric, labeled with the engine (`strategy`
+    /// or `exec`)
+    pub fn from_risk_metric(
+        labeler: &MetricLabeler,
+        engine: &str,
+        (name, labels, value): &(String, Vec<(String, String)>, f64),
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let mut sample_labels: BTreeMap<String, String> =
+            labeler.labels().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        sample_labels.insert("engine".to_string(), engine.to_string());
+        sample_labels.extend(labels.iter().cloned());
+        Self {
+            timestamp,
+            name: labeler.metric_name(name),
+            labels: sample_labels,
+            value: *value,
+        }
+    }
+}
+
+/// Bot components the control server acts on
+pub(crate) struct ControlServer {
+    pub(crate) bot_name: String,
+    pub(crate) coordinator: Arc<Mutex<MultiMarketCoordinator>>,
+    pub(crate) exec_engine: Arc<ExecutionEngine>,
+    pub(crate) risk_engine: Arc<parking_lot::Mutex<RiskEngine>>,
+    pub(crate) metrics: broadcast::Sender<MetricSample>,
+    pub(crate) liveness: LivenessRegistry,
+}
+
+impl ControlServer {
+    /// Accept connections until shutdown
+    pub(crate) async fn run(self: Arc<Self>, listener: TcpListener, mut shutdown_rx: watch::Receiver<bool>) {
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, peer)) => {
+                        debug!("Bot '{}' control connection from {}", self.bot_name, peer);
+                        tokio::spawn(self.clone().serve(stream, shutdown_rx.clone()));
+                    }
+                    Err(e) => warn!("Bot '{}' control listener failed to accept: {}", self.bot_name, e),
+                },
+            }
+        }
+    }
+
+    /// Answer one connection's requests
+    async fn serve(self: Arc<Self>, stream: TcpStream, mut shutdown_rx: watch::Receiver<bool>) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        loop {
+            let line = tokio::select! {
+                _ = shutdown_rx.changed() => return,
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => line,
+                    _ => return,
+                },
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<ControlRequest>(&line) {
+                Ok(ControlRequest::TailMetrics { prefix }) => {
+                    self.tail_metrics(prefix, &mut writer, shutdown_rx).await;
+                    return;
+                }
+                Ok(request) => self.handle(request).await,
+                Err(e) => ControlResponse::Error {
+                    message: format!("invalid request: {}", e),
+                },
+            };
+            if write_line(&mut writer, &response).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn handle(&self, request: ControlRequest) -> ControlResponse {
+        let result = match request {
+            ControlRequest::Status => Ok(ControlResponse::Status(self.status().await)),
+            ControlRequest::Positions => Ok(ControlResponse::Positions(self.positions().await)),
+            ControlRequest::Orders => self
+                .exec_engine
+                .get_active_orders()
+                .map(|orders| ControlResponse::Orders { orders })
+                .map_err(|e| e.to_string()),
+            ControlRequest::PauseStrategy { strategy_id } => {
+                self.coordinator.lock().await.pause_strategy(&strategy_id).map(|paused| done(if paused {
+                    format!("paused {}", strategy_id)
+                } else {
+                    format!("{} was already paused", strategy_id)
+                })).map_err(|e| e.to_string())
+            }
+            ControlRequest::ResumeStrategy { strategy_id } => {
+                self.coordinator.lock().await.resume_strategy(&strategy_id).map(|resumed| done(if resumed {
+                    format!("resumed {}", strategy_id)
+                } else {
+                    format!("{} was not paused", strategy_id)
+                })).map_err(|e| e.to_string())
+            }
+            ControlRequest::TriggerKillSwitch { reason } => {
+                warn!("Bot '{}' kill-switch triggered by operator: {}", self.bot_name, reason);
+                self.risk_engine.lock().trigger_kill_switch(KillSwitchSource::Manual, reason.as_str());
+                if let Some(risk_engine) = self.exec_engine.risk_engine() {
+                    risk_engine.lock().await.trigger_kill_switch(KillSwitchSource::Manual, reason.as_str());
+                }
+                Ok(done("kill-switch on".to_string()))
+            }
+            ControlRequest::ResetKillSwitch { reason } => {
+                info!("Bot '{}' kill-switch reset by operator: {}", self.bot_name, reason);
+                self.risk_engine.lock().reset_kill_switch(KillSwitchSource::Manual, reason.as_str());
+                if let Some(risk_engine) = self.exec_engine.risk_engine() {
+                    risk_engine.lock().await.reset_kill_switch(KillSwitchSource::Manual, reason.as_str());
+                }
+                Ok(done("kill-switch off".to_string()))
+            }
+            ControlRequest::SetPolicyParam { policy, param, value } => self.set_policy_param(&policy, &param, value).await,
+            ControlRequest::TailMetrics { .. } => Err("tail_metrics streams; it cannot be batched".to_string()),
+        };
+        result.unwrap_or_else(|message| ControlResponse::Error { message })
+    }
+
+    async fn status(&self) -> BotStatus {
+        // Read before taking any lock a stalled event loop may hold
+        let health = self.liveness.report(Utc::now());
+        let (strategies, feed_connected) = {
+            let coordinator = self.coordinator.lock().await;
+            let utilization = coordinator.loop_utilization();
+            let warming_up = coordinator.warming_up();
+            let mut ids = coordinator.strategy_ids();
+            ids.sort();
+            let strategies = ids
+                .into_iter()
+                .map(|id| StrategyStatus {
+                    markets: coordinator.strategy_markets(&id).map(|m| m.to_vec()).unwrap_or_default(),
+                    paused: coordinator.is_paused(&id),
+                    warming_up: warming_up.contains(&id),
+                    pending_events: coordinator.pending_events(&id),
+                    dropped_book_updates: coordinator.dropped_book_updates(&id),
+                    loop_utilization: utilization.get(&id).copied().unwrap_or(0.0),
+                    id,
+                })
+                .collect();
+            (strategies, coordinator.feed_connected())
+        };
+
+        let (mut kill_switch, mut disabled_policies) = {
+            let risk_engine = self.risk_engine.lock();
+            (risk_engine.kill_switch_activation(), risk_engine.disabled_policies())
+        };
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            let risk_engine = risk_engine.lock().await;
+            kill_switch = kill_switch.or_else(|| risk_engine.kill_switch_activation());
+            disabled_policies.extend(risk_engine.disabled_policies());
+        }
+        disabled_policies.sort();
+        disabled_policies.dedup();
+
+        BotStatus {
+            name: self.bot_name.clone(),
+            kill_switch,
+            feed_connected,
+            strategies,
+            venues: self
+                .exec_engine
+                .venue_statuses()
+                .into_iter()
+                .map(|(venue, status)| (venue.0, status))
+                .collect(),
+            disabled_policies,
+            health,
+        }
+    }
+
+    async fn positions(&self) -> PositionsReport {
+        let strategies: HashMap<String, Vec<Position>> = self.coordinator.lock().await.get_cross_market_positions();
+        PositionsReport {
+            strategies: strategies.into_iter().collect(),
+            exec: self.exec_engine.get_all_positions().await.into_iter().collect(),
+        }
+    }
+
+    async fn set_policy_param(&self, policy: &str, param: &str, value: f64) -> Result<ControlResponse, String> {
+        let changed = self.risk_engine.lock().set_policy_param(policy, param, value)?;
+        if let Some(risk_engine) = self.exec_engine.risk_engine() {
+            risk_engine.lock().await.set_policy_param(policy, param, value)?;
+        }
+        info!("Bot '{}' operator set {} = {} on {}", self.bot_name, param, value, policy);
+        Ok(done(format!("set {} = {} on {} policies in each risk engine", param, value, changed)))
+    }
+
+    /// Stream metrics to the client until it disconnects or the bot stops
+    async fn tail_metrics<W: AsyncWrite + Unpin>(
+        &self,
+        prefix: Option<String>,
+        writer: &mut W,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) {
+        let mut samples = self.metrics.subscribe();
+        loop {
+            let sample = tokio::select! {
+                _ = shutdown_rx.changed() => return,
+                sample = samples.recv() => sample,
+            };
+            match sample {
+                Ok(sample) => {
+                    if prefix.as_ref().is_some_and(|prefix| !sample.name.starts_with(prefix.as_str())) {
+                        continue;
+                    }
+                    if write_line(writer, &ControlResponse::Metric(sample)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Bot '{}' metrics tail fell behind; skipped {} samples", self.bot_name, missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+}
+
+fn done(message: String) -> ControlResponse {
+    ControlResponse::Done { message }
+}
+
+async fn write_line<W: AsyncWrite + Unpin, T: Serialize>(writer: &mut W, value: &T) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await
+}
+
+/// Client for a bot's control API
+pub struct ControlClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl ControlClient {
+    /// Connect to the address in the bot's `control.listen`
+    pub async fn connect(addr: impl ToSocketAddrs) -> BotResult<Self> {
+        let (reader, writer) = TcpStream::connect(addr).await?.into_split();
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+        })
+    }
+
+    /// Send a request and wait for its response
+    pub async fn request(&mut self, request: &ControlRequest) -> BotResult<ControlResponse> {
+        self.send(request).await?;
+        self.next_response()
+            .await?
+            .ok_or_else(|| BotError::ControlError("connection closed by the bot".to_string()))
+    }
+
+    /// Send a request without waiting (e.g. `tail_metrics`, whose responses
+    /// are read with [`next_response`](Self::next_response))
+    pub async fn send(&mut self, request: &ControlRequest) -> BotResult<()> {
+        write_line(&mut self.writer, request).await?;
+        Ok(())
+    }
+
+    /// Next response line (None once the bot closes the connection)
+    pub async fn next_response(&mut self) -> BotResult<Option<ControlResponse>> {
+        match self.lines.next_line().await? {
+            Some(line) => serde_json::from_str(&line)
+                .map(Some)
+                .map_err(|e| BotError::ControlError(format!("invalid response: {}", e))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_wire_format() {
+        let request: ControlRequest =
+            serde_json::from_str(r#"{"command":"set_policy_param","policy":"PositionLimit","param":"max_size","value":5}"#)
+                .unwrap();
+        assert_eq!(
+            request,
+            ControlRequest::SetPolicyParam {
+                policy: "PositionLimit".to_string(),
+                param: "max_size".to_string(),
+                value: 5.0
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<ControlRequest>(r#"{"command":"tail_metrics"}"#).unwrap(),
+            ControlRequest::TailMetrics { prefix: None }
+        );
+        assert_eq!(serde_json::to_string(&ControlRequest::Status).unwrap(), r#"{"command":"status"}"#);
+        assert!(serde_json::from_str::<ControlRequest>(r#"{"command":"reboot"}"#).is_err());
+    }
+}
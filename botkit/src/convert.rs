@@ -0,0 +1,798 @@
+//! Conversions between execution, strategy and storage types
+//!
+//! `ag-exec`, `ag-strategies` and `ag-storage` each define their own
+//! `Side`, `OrderType`, `OrderStatus`, `Order` and `Fill`. The [`ToExec`],
+//! [`ToStrategy`] and [`ToStorage`] traits convert between them so records
+//! flow from the execution engine to strategies and storage (and back, e.g.
+//! when replaying stored fills) without hand-mapping, e.g.
+//! `order.to_storage()` or `(&order, &fill).to_strategy()`.
+//!
+//! Where one side has no equivalent the nearest meaning is used:
+//!
+//! | From | To | Mapping |
+//! |------|----|---------|
+//! | exec `PostOnly` | strategy / storage order type | `Limit` |
+//! | strategy `Stop` | exec / storage order type | `StopMarket` |
+//! | strategy `GTD` | exec time in force | `GTC` |
+//! | exec `Submitting` / `Working` / `Cancelling` | strategy status | `Submitted` / `Acknowledged` / `Acknowledged` |
+//! | exec `Pending` / `Submitting` / `Working` / `Cancelling`, strategy `Pending` / `Submitted` / `Acknowledged` | storage status | `Open` |
+//! | exec / strategy `Expired` | storage status | `Cancelled` |
+//!
+//! Exec fills carry no market, side or venue, so they convert together with
+//! their order as `(&Order, &Fill)`. Order ids that are not UUIDs (strategy
+//! ids are free-form strings) get a fresh UUID.
+
+use ag_exec::order as exec;
+use ag_strategies as strategies;
+use uuid::Uuid;
+
+/// Conversion into the matching `ag-exec` type
+pub trait ToExec {
+    /// Execution type
+    type Output;
+
+    /// Convert into the execution type
+    fn to_exec(&self) -> Self::Output;
+}
+
+/// Conversion into the matching `ag-strategies` type
+pub trait ToStrategy {
+    /// Strategy type
+    type Output;
+
+    /// Convert into the strategy type
+    fn to_strategy(&self) -> Self::Output;
+}
+
+/// Conversion into the matching `ag-storage` record
+#[cfg(feature = "storage")]
+pub trait ToStorage {
+    /// Storage record type
+    type Output;
+
+    /// Convert into the storage record
+    fn to_storage(&self) -> Self::Output;
+}
+
+/// UUID from a string id, or a fresh one if it does not parse
+fn uuid_or_new(id: Option<&str>) -> Uuid {
+    id.and_then(|id| id.parse().ok()).unwrap_or_else(Uuid::new_v4)
+}
+
+// Side
+
+impl ToStrategy for exec::Side {
+    type Output = strategies::Side;
+
+    fn to_strategy(&self) -> strategies::Side {
+        match self {
+            exec::Side::Buy => strategies::Side::Buy,
+            exec::Side::Sell => strategies::Side::Sell,
+        }
+    }
+}
+
+impl ToExec for strategies::Side {
+    type Output = exec::Side;
+
+    fn to_exec(&self) -> exec::Side {
+        match self {
+            strategies::Side::Buy => exec::Side::Buy,
+            strategies::Side::Sell => exec::Side::Sell,
+        }
+    }
+}
+
+// Order type
+
+impl ToStrategy for exec::OrderType {
+    type Output = strategies::OrderType;
+
+    fn to_strategy(&self) -> strategies::OrderType {
+        match self {
+            exec::OrderType::Limit | exec::OrderType::PostOnly => strategies::OrderType::Limit,
+            exec::OrderType::Market => strategies::OrderType::Market,
+            exec::OrderType::StopMarket => strategies::OrderType::Stop,
+            exec::OrderType::StopLimit => strategies::OrderType::StopLimit,
+        }
+    }
+}
+
+impl ToExec for strategies::OrderType {
+    type Output = exec::OrderType;
+
+    fn to_exec(&self) -> exec::OrderType {
+        match self {
+            strategies::OrderType::Market => exec::OrderType::Market,
+            strategies::OrderType::Limit => exec::OrderType::Limit,
+            strategies::OrderType::Stop => exec::OrderType::StopMarket,
+            strategies::OrderType::StopLimit => exec::OrderType::StopLimit,
+        }
+    }
+}
+
+// Time in force
+
+impl ToStrategy for exec::TimeInForce {
+    type Output = strategies::TimeInForce;
+
+    fn to_strategy(&self) -> strategies::TimeInForce {
+        match self {
+            exec::TimeInForce::GTC => strategies::TimeInForce::GTC,
+            exec::TimeInForce::IOC => strategies::TimeInForce::IOC,
+            exec::TimeInForce::FOK => strategies::TimeInForce::FOK,
+        }
+    }
+}
+
+impl ToExec for strategies::TimeInForce {
+    type Output = exec::TimeInForce;
+
+    fn to_exec(&self) -> exec::TimeInForce {
+        match self {
+            strategies::TimeInForce::GTC | strategies::TimeInForce::GTD => exec::TimeInForce::GTC,
+            strategies::TimeInForce::IOC => exec::TimeInForce::IOC,
+            strategies::TimeInForce::FOK => exec::TimeInForce::FOK,
+        }
+    }
+}
+
+// Pegs
+
+impl ToStrategy for ag_exec::PegReference {
+    type Output = strategies::PegReference;
+
+    fn to_strategy(&self) -> strategies::PegReference {
+        match self {
+            ag_exec::PegReference::BestBid => strategies::PegReference::BestBid,
+            ag_exec::PegReference::BestAsk => strategies::PegReference::BestAsk,
+            ag_exec::PegReference::Mid => strategies::PegReference::Mid,
+        }
+    }
+}
+
+impl ToExec for strategies::PegReference {
+    type Output = ag_exec::PegReference;
+
+    fn to_exec(&self) -> ag_exec::PegReference {
+        match self {
+            strategies::PegReference::BestBid => ag_exec::PegReference::BestBid,
+            strategies::PegReference::BestAsk => ag_exec::PegReference::BestAsk,
+            strategies::PegReference::Mid => ag_exec::PegReference::Mid,
+        }
+    }
+}
+
+impl ToStrategy for ag_exec::Peg {
+    type Output = strategies::Peg;
+
+    fn to_strategy(&self) -> strategies::Peg {
+        strategies::Peg {
+            reference: self.reference.to_strategy(),
+            offset_ticks: self.offset_ticks,
+            limit_price: self.limit_price,
+        }
+    }
+}
+
+impl ToExec for strategies::Peg {
+    type Output = ag_exec::Peg;
+
+    fn to_exec(&self) -> ag_exec::Peg {
+        ag_exec::Peg {
+            reference: self.reference.to_exec(),
+            offset_ticks: self.offset_ticks,
+            limit_price: self.limit_price,
+        }
+    }
+}
+
+// Venue state
+
+impl ToStrategy for ag_exec::VenueState {
+    type Output = strategies::VenueState;
+
+    fn to_strategy(&self) -> strategies::VenueState {
+        match self {
+            ag_exec::VenueState::Up => strategies::VenueState::Up,
+            ag_exec::VenueState::Degraded => strategies::VenueState::Degraded,
+            ag_exec::VenueState::Down => strategies::VenueState::Down,
+        }
+    }
+}
+
+// Order status
+
+impl ToStrategy for exec::OrderStatus {
+    type Output = strategies::OrderStatus;
+
+    fn to_strategy(&self) -> strategies::OrderStatus {
+        match self {
+            exec::OrderStatus::Pending => strategies::OrderStatus::Pending,
+            exec::OrderStatus::Submitting => strategies::OrderStatus::Submitted,
+            exec::OrderStatus::Working | exec::OrderStatus::Cancelling => strategies::OrderStatus::Acknowledged,
+            exec::OrderStatus::PartiallyFilled => strategies::OrderStatus::PartiallyFilled,
+            exec::OrderStatus::Filled => strategies::OrderStatus::Filled,
+            exec::OrderStatus::Cancelled => strategies::OrderStatus::Cancelled,
+            exec::OrderStatus::Rejected => strategies::OrderStatus::Rejected,
+            exec::OrderStatus::Expired => strategies::OrderStatus::Expired,
+        }
+    }
+}
+
+impl ToExec for strategies::OrderStatus {
+    type Output = exec::OrderStatus;
+
+    fn to_exec(&self) -> exec::OrderStatus {
+        match self {
+            strategies::OrderStatus::Pending => exec::OrderStatus::Pending,
+            strategies::OrderStatus::Submitted => exec::OrderStatus::Submitting,
+            strategies::OrderStatus::Acknowledged => exec::OrderStatus::Working,
+            strategies::OrderStatus::PartiallyFilled => exec::OrderStatus::PartiallyFilled,
+            strategies::OrderStatus::Filled => exec::OrderStatus::Filled,
+            strategies::OrderStatus::Cancelled => exec::OrderStatus::Cancelled,
+            strategies::OrderStatus::Rejected => exec::OrderStatus::Rejected,
+            strategies::OrderStatus::Expired => exec::OrderStatus::Expired,
+        }
+    }
+}
+
+// Orders and fills
+
+impl ToStrategy for exec::Order {
+    type Output = strategies::Order;
+
+    fn to_strategy(&self) -> strategies::Order {
+        strategies::Order {
+            id: Some(self.id.to_string()),
+            venue: self.venue.as_str().to_string(),
+            market: self.market.as_str().to_string(),
+            side: self.side.to_strategy(),
+            order_type: self.order_type.to_strategy(),
+            price: self.price,
+            size: self.size,
+            time_in_force: self.time_in_force.to_strategy(),
+            client_order_id: Some(self.client_order_id.clone()).filter(|id| !id.is_empty()),
+            timestamp: self.created_at,
+            status: self.status.to_strategy(),
+            tags: self.tags.clone(),
+            peg: self.peg.map(|peg| peg.to_strategy()),
+        }
+    }
+}
+
+impl ToExec for strategies::Order {
+    type Output = exec::Order;
+
+    fn to_exec(&self) -> exec::Order {
+        let mut order = exec::Order::new(
+            exec::VenueId::new(self.venue.as_str()),
+            exec::MarketId::new(self.market.as_str()),
+            self.side.to_exec(),
+            self.order_type.to_exec(),
+            self.price,
+            self.size,
+            self.time_in_force.to_exec(),
+            self.client_order_id.clone().unwrap_or_default(),
+        );
+        order.id = exec::OrderId::from_uuid(uuid_or_new(self.id.as_deref()));
+        order.status = self.status.to_exec();
+        order.tags = self.tags.clone();
+        order.peg = self.peg.map(|peg| peg.to_exec());
+        order.created_at = self.timestamp;
+        order.updated_at = self.timestamp;
+        order
+    }
+}
+
+impl ToStrategy for (&exec::Order, &exec::Fill) {
+    type Output = strategies::Fill;
+
+    fn to_strategy(&self) -> strategies::Fill {
+        let (order, fill) = *self;
+        strategies::Fill {
+            order_id: order.id.to_string(),
+            market: order.market.as_str().to_string(),
+            price: fill.price,
+            size: fill.size,
+            side: order.side.to_strategy(),
+            fee: fill.fee,
+            timestamp: fill.timestamp,
+        }
+    }
+}
+
+#[cfg(feature = "storage")]
+mod storage_conversions {
+    use super::*;
+    use ag_storage as storage;
+
+    /// Stored liquidity label of an exec liquidity role
+    fn liquidity_label(liquidity: exec::Liquidity) -> String {
+        match liquidity {
+            exec::Liquidity::Maker => "maker".to_string(),
+            exec::Liquidity::Taker => "taker".to_string(),
+        }
+    }
+
+    /// Exec liquidity role of a stored label
+    fn liquidity_role(label: &str) -> Option<exec::Liquidity> {
+        match label {
+            "maker" => Some(exec::Liquidity::Maker),
+            "taker" => Some(exec::Liquidity::Taker),
+            _ => None,
+        }
+    }
+
+    /// Exec time in force of a stored label (GTC if missing or unknown)
+    fn time_in_force(label: Option<&str>) -> exec::TimeInForce {
+        match label {
+            Some("IOC") => exec::TimeInForce::IOC,
+            Some("FOK") => exec::TimeInForce::FOK,
+            _ => exec::TimeInForce::GTC,
+        }
+    }
+
+    // Side
+
+    impl ToStorage for exec::Side {
+        type Output = storage::Side;
+
+        fn to_storage(&self) -> storage::Side {
+            match self {
+                exec::Side::Buy => storage::Side::Buy,
+                exec::Side::Sell => storage::Side::Sell,
+            }
+        }
+    }
+
+    impl ToStorage for strategies::Side {
+        type Output = storage::Side;
+
+        fn to_storage(&self) -> storage::Side {
+            self.to_exec().to_storage()
+        }
+    }
+
+    impl ToExec for storage::Side {
+        type Output = exec::Side;
+
+        fn to_exec(&self) -> exec::Side {
+            match self {
+                storage::Side::Buy => exec::Side::Buy,
+                storage::Side::Sell => exec::Side::Sell,
+            }
+        }
+    }
+
+    impl ToStrategy for storage::Side {
+        type Output = strategies::Side;
+
+        fn to_strategy(&self) -> strategies::Side {
+            self.to_exec().to_strategy()
+        }
+    }
+
+    // Order type
+
+    impl ToStorage for exec::OrderType {
+        type Output = storage::OrderType;
+
+        fn to_storage(&self) -> storage::OrderType {
+            match self {
+                exec::OrderType::Limit | exec::OrderType::PostOnly => storage::OrderType::Limit,
+                exec::OrderType::Market => storage::OrderType::Market,
+                exec::OrderType::StopMarket => storage::OrderType::StopMarket,
+                exec::OrderType::StopLimit => storage::OrderType::StopLimit,
+            }
+        }
+    }
+
+    impl ToStorage for strategies::OrderType {
+        type Output = storage::OrderType;
+
+        fn to_storage(&self) -> storage::OrderType {
+            self.to_exec().to_storage()
+        }
+    }
+
+    impl ToExec for storage::OrderType {
+        type Output = exec::OrderType;
+
+        fn to_exec(&self) -> exec::OrderType {
+            match self {
+                storage::OrderType::Limit => exec::OrderType::Limit,
+                storage::OrderType::Market => exec::OrderType::Market,
+                storage::OrderType::StopMarket => exec::OrderType::StopMarket,
+                storage::OrderType::StopLimit => exec::OrderType::StopLimit,
+            }
+        }
+    }
+
+    impl ToStrategy for storage::OrderType {
+        type Output = strategies::OrderType;
+
+        fn to_strategy(&self) -> strategies::OrderType {
+            self.to_exec().to_strategy()
+        }
+    }
+
+    // Order status
+
+    impl ToStorage for exec::OrderStatus {
+        type Output = storage::OrderStatus;
+
+        fn to_storage(&self) -> storage::OrderStatus {
+            match self {
+                exec::OrderStatus::Pending
+                | exec::OrderStatus::Submitting
+                | exec::OrderStatus::Working
+                | exec::OrderStatus::Cancelling => storage::OrderStatus::Open,
+                exec::OrderStatus::PartiallyFilled => storage::OrderStatus::Partial,
+                exec::OrderStatus::Filled => storage::OrderStatus::Filled,
+                exec::OrderStatus::Cancelled | exec::OrderStatus::Expired => storage::OrderStatus::Cancelled,
+                exec::OrderStatus::Rejected => storage::OrderStatus::Rejected,
+            }
+        }
+    }
+
+    impl ToStorage for strategies::OrderStatus {
+        type Output = storage::OrderStatus;
+
+        fn to_storage(&self) -> storage::OrderStatus {
+            self.to_exec().to_storage()
+        }
+    }
+
+    impl ToExec for storage::OrderStatus {
+        type Output = exec::OrderStatus;
+
+        fn to_exec(&self) -> exec::OrderStatus {
+            match self {
+                storage::OrderStatus::Open => exec::OrderStatus::Working,
+                storage::OrderStatus::Partial => exec::OrderStatus::PartiallyFilled,
+                storage::OrderStatus::Filled => exec::OrderStatus::Filled,
+                storage::OrderStatus::Cancelled => exec::OrderStatus::Cancelled,
+                storage::OrderStatus::Rejected => exec::OrderStatus::Rejected,
+            }
+        }
+    }
+
+    impl ToStrategy for storage::OrderStatus {
+        type Output = strategies::OrderStatus;
+
+        fn to_strategy(&self) -> strategies::OrderStatus {
+            self.to_exec().to_strategy()
+        }
+    }
+
+    // Orders
+
+    impl ToStorage for exec::Order {
+        type Output = storage::Order;
+
+        fn to_storage(&self) -> storage::Order {
+            storage::Order {
+                id: self.id.as_uuid(),
+                timestamp: self.created_at,
+                venue: self.venue.as_str().to_string(),
+                market: self.market.as_str().to_string(),
+                side: self.side.to_storage(),
+                order_type: self.order_type.to_storage(),
+                price: self.price,
+                size: self.size,
+                status: self.status.to_storage(),
+                client_order_id: self.client_order_id.clone(),
+                venue_order_id: None,
+                time_in_force: Some(self.time_in_force.to_string()),
+                correlation_id: None,
+                strategy_id: self.strategy_id.clone(),
+                session_id: None,
+                tags: self.tags.clone(),
+            }
+        }
+    }
+
+    impl ToStorage for strategies::Order {
+        type Output = storage::Order;
+
+        fn to_storage(&self) -> storage::Order {
+            self.to_exec().to_storage()
+        }
+    }
+
+    impl ToExec for storage::Order {
+        type Output = exec::Order;
+
+        fn to_exec(&self) -> exec::Order {
+            let mut order = exec::Order::new(
+                exec::VenueId::new(self.venue.as_str()),
+                exec::MarketId::new(self.market.as_str()),
+                self.side.to_exec(),
+                self.order_type.to_exec(),
+                self.price,
+                self.size,
+                time_in_force(self.time_in_force.as_deref()),
+                self.client_order_id.clone(),
+            );
+            order.id = exec::OrderId::from_uuid(self.id);
+            order.strategy_id = self.strategy_id.clone();
+            order.tags = self.tags.clone();
+            order.status = self.status.to_exec();
+            order.created_at = self.timestamp;
+            order.updated_at = self.timestamp;
+            order
+        }
+    }
+
+    impl ToStrategy for storage::Order {
+        type Output = strategies::Order;
+
+        fn to_strategy(&self) -> strategies::Order {
+            self.to_exec().to_strategy()
+        }
+    }
+
+    // Fills
+
+    impl ToStorage for (&exec::Order, &exec::Fill) {
+        type Output = storage::Fill;
+
+        fn to_storage(&self) -> storage::Fill {
+            let (order, fill) = *self;
+            storage::Fill {
+                id: uuid_or_new(Some(&fill.fill_id)),
+                timestamp: fill.timestamp,
+                order_id: order.id.as_uuid(),
+                venue: order.venue.as_str().to_string(),
+                market: order.market.as_str().to_string(),
+                side: order.side.to_storage(),
+                price: fill.price,
+                size: fill.size,
+                fee: fill.fee,
+                fee_currency: fill.fee_currency.clone(),
+                trade_id: Some(fill.fill_id.clone()),
+                liquidity: fill.liquidity.map(liquidity_label),
+                quoted_price: order.price,
+            }
+        }
+    }
+
+    impl ToExec for storage::Fill {
+        type Output = exec::Fill;
+
+        fn to_exec(&self) -> exec::Fill {
+            exec::Fill {
+                fill_id: self.trade_id.clone().unwrap_or_else(|| self.id.to_string()),
+                order_id: exec::OrderId::from_uuid(self.order_id),
+                venue_order_id: None,
+                price: self.price,
+                size: self.size,
+                fee: self.fee,
+                fee_currency: self.fee_currency.clone(),
+                timestamp: self.timestamp,
+                liquidity: self.liquidity.as_deref().and_then(liquidity_role),
+            }
+        }
+    }
+
+    impl ToStrategy for storage::Fill {
+        type Output = strategies::Fill;
+
+        fn to_strategy(&self) -> strategies::Fill {
+            strategies::Fill {
+                order_id: self.order_id.to_string(),
+                market: self.market.clone(),
+                price: self.price,
+                size: self.size,
+                side: self.side.to_strategy(),
+                fee: self.fee,
+                timestamp: self.timestamp,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXEC_SIDES: [exec::Side; 2] = [exec::Side::Buy, exec::Side::Sell];
+    const EXEC_ORDER_TYPES: [exec::OrderType; 5] = [
+        exec::OrderType::Limit,
+        exec::OrderType::Market,
+        exec::OrderType::PostOnly,
+        exec::OrderType::StopMarket,
+        exec::OrderType::StopLimit,
+    ];
+    const EXEC_STATUSES: [exec::OrderStatus; 9] = [
+        exec::OrderStatus::Pending,
+        exec::OrderStatus::Submitting,
+        exec::OrderStatus::Working,
+        exec::OrderStatus::PartiallyFilled,
+        exec::OrderStatus::Filled,
+        exec::OrderStatus::Cancelling,
+        exec::OrderStatus::Cancelled,
+        exec::OrderStatus::Rejected,
+        exec::OrderStatus::Expired,
+    ];
+    const STRATEGY_STATUSES: [strategies::OrderStatus; 8] = [
+        strategies::OrderStatus::Pending,
+        strategies::OrderStatus::Submitted,
+        strategies::OrderStatus::Acknowledged,
+        strategies::OrderStatus::PartiallyFilled,
+        strategies::OrderStatus::Filled,
+        strategies::OrderStatus::Cancelled,
+        strategies::OrderStatus::Rejected,
+        strategies::OrderStatus::Expired,
+    ];
+
+    fn exec_order() -> exec::Order {
+        let mut order = exec::Order::new(
+            exec::VenueId::new("polymarket"),
+            exec::MarketId::new("0x123abc"),
+            exec::Side::Sell,
+            exec::OrderType::Limit,
+            Some(0.52),
+            100.0,
+            exec::TimeInForce::IOC,
+            "client-1".to_string(),
+        );
+        order.strategy_id = Some("mm".to_string());
+        order.status = exec::OrderStatus::PartiallyFilled;
+        order.with_tag("signal", "imbalance")
+    }
+
+    fn exec_fill(order: &exec::Order) -> exec::Fill {
+        exec::Fill {
+            fill_id: "trade-42".to_string(),
+            order_id: order.id,
+            venue_order_id: None,
+            price: 0.52,
+            size: 40.0,
+            fee: 0.1,
+            fee_currency: "USDC".to_string(),
+            timestamp: order.created_at,
+            liquidity: Some(exec::Liquidity::Maker),
+        }
+    }
+
+    #[test]
+    fn test_enum_round_trips() {
+        for side in EXEC_SIDES {
+            assert_eq!(side.to_strategy().to_exec(), side);
+        }
+        for order_type in EXEC_ORDER_TYPES {
+            let expected = match order_type {
+                exec::OrderType::PostOnly => exec::OrderType::Limit,
+                other => other,
+            };
+            assert_eq!(order_type.to_strategy().to_exec(), expected);
+        }
+        for status in STRATEGY_STATUSES {
+            assert_eq!(status.to_exec().to_strategy(), status);
+        }
+        for status in EXEC_STATUSES {
+            let expected = match status {
+                exec::OrderStatus::Cancelling => exec::OrderStatus::Working,
+                other => other,
+            };
+            assert_eq!(status.to_strategy().to_exec(), expected);
+        }
+        for tif in [exec::TimeInForce::GTC, exec::TimeInForce::IOC, exec::TimeInForce::FOK] {
+            assert_eq!(tif.to_strategy().to_exec(), tif);
+        }
+        assert_eq!(strategies::TimeInForce::GTD.to_exec(), exec::TimeInForce::GTC);
+        for state in [ag_exec::VenueState::Up, ag_exec::VenueState::Degraded, ag_exec::VenueState::Down] {
+            assert_eq!(state.to_strategy().as_str(), state.as_str());
+        }
+    }
+
+    #[test]
+    fn test_order_round_trip_through_strategy() {
+        let order = exec_order();
+        let strategy_order = order.to_strategy();
+        assert_eq!(strategy_order.id, Some(order.id.to_string()));
+        assert_eq!(strategy_order.client_order_id.as_deref(), Some("client-1"));
+        assert_eq!(strategy_order.signal(), Some("imbalance"));
+
+        let back = strategy_order.to_exec();
+        assert_eq!(back.id, order.id);
+        assert_eq!(back.venue, order.venue);
+        assert_eq!(back.market, order.market);
+        assert_eq!(back.side, order.side);
+        assert_eq!(back.price, order.price);
+        assert_eq!(back.size, order.size);
+        assert_eq!(back.time_in_force, order.time_in_force);
+        assert_eq!(back.status, order.status);
+        assert_eq!(back.created_at, order.created_at);
+        assert_eq!(back.tags, order.tags);
+        assert_eq!(back.peg, None);
+
+        let pegged = order.clone().with_peg(ag_exec::Peg::new(ag_exec::PegReference::BestAsk, 1).with_limit(0.50));
+        assert_eq!(pegged.to_strategy().to_exec().peg, pegged.peg);
+
+        let fill = (&order, &exec_fill(&order)).to_strategy();
+        assert_eq!(fill.order_id, order.id.to_string());
+        assert_eq!(fill.market, "0x123abc");
+        assert_eq!(fill.side, strategies::Side::Sell);
+        assert_eq!(fill.size, 40.0);
+    }
+
+    #[test]
+    fn test_non_uuid_strategy_id() {
+        let order = strategies::Order {
+            id: Some("strategy-order-7".to_string()),
+            ..Default::default()
+        };
+        let first = order.to_exec().id;
+        assert_ne!(first, order.to_exec().id);
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_storage_enum_round_trips() {
+        use ag_storage as storage;
+
+        for side in EXEC_SIDES {
+            assert_eq!(side.to_storage().to_exec(), side);
+            assert_eq!(side.to_strategy().to_storage().to_strategy(), side.to_strategy());
+        }
+        for order_type in EXEC_ORDER_TYPES {
+            let expected = match order_type {
+                exec::OrderType::PostOnly => exec::OrderType::Limit,
+                other => other,
+            };
+            assert_eq!(order_type.to_storage().to_exec(), expected);
+            assert_eq!(
+                order_type.to_strategy().to_storage().to_strategy(),
+                order_type.to_strategy()
+            );
+        }
+        for status in [
+            storage::OrderStatus::Open,
+            storage::OrderStatus::Partial,
+            storage::OrderStatus::Filled,
+            storage::OrderStatus::Cancelled,
+            storage::OrderStatus::Rejected,
+        ] {
+            assert_eq!(status.to_exec().to_storage(), status);
+            assert_eq!(status.to_strategy().to_storage(), status);
+        }
+        assert_eq!(exec::OrderStatus::Expired.to_storage(), storage::OrderStatus::Cancelled);
+        assert_eq!(exec::OrderStatus::Cancelling.to_storage(), storage::OrderStatus::Open);
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_order_and_fill_round_trip_through_storage() {
+        let order = exec_order();
+        let stored = order.to_storage();
+        assert_eq!(stored.id, order.id.as_uuid());
+        assert_eq!(stored.time_in_force.as_deref(), Some("IOC"));
+        assert_eq!(stored.strategy_id.as_deref(), Some("mm"));
+
+        let back = stored.to_exec();
+        assert_eq!(back.id, order.id);
+        assert_eq!(back.client_order_id, order.client_order_id);
+        assert_eq!(back.time_in_force, order.time_in_force);
+        assert_eq!(back.strategy_id, order.strategy_id);
+        assert_eq!(back.tags, order.tags);
+        assert_eq!(back.status, order.status);
+
+        let fill = exec_fill(&order);
+        let stored_fill = (&order, &fill).to_storage();
+        assert_eq!(stored_fill.order_id, order.id.as_uuid());
+        assert_eq!(stored_fill.market, "0x123abc");
+        assert_eq!(stored_fill.liquidity.as_deref(), Some("maker"));
+
+        let back_fill = stored_fill.to_exec();
+        assert_eq!(back_fill.fill_id, fill.fill_id);
+        assert_eq!(back_fill.order_id, fill.order_id);
+        assert_eq!(back_fill.liquidity, fill.liquidity);
+
+        let strategy_fill = stored_fill.to_strategy();
+        assert_eq!(strategy_fill.order_id, order.id.to_string());
+        assert_eq!(strategy_fill.side, strategies::Side::Sell);
+    }
+}
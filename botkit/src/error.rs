@@ -0,0 +1,72 @@
+//! Error types for the bot facade
+
+use thiserror::Error;
+
+/// Result type for bot operations
+pub type BotResult<T> = Result<T, BotError>;
+
+/// Errors raised while building or running a bot
+#[derive(Error, Debug)]
+pub enum BotError {
+    /// Invalid or unreadable configuration
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    /// Config failed schema validation
+    #[error("Invalid configuration:\n{0}")]
+    InvalidConfig(crate::validate::ValidationReport),
+
+    /// Risk engine setup failed
+    #[error("Risk error: {0}")]
+    RiskError(String),
+
+    /// No factory registered for a configured strategy kind
+    #[error("Unknown strategy kind: {0}")]
+    UnknownStrategy(String),
+
+    /// `start` called on a running bot
+    #[error("Bot is already running")]
+    AlreadyRunning,
+
+    /// `stop` called on a bot that is not running
+    #[error("Bot is not running")]
+    NotRunning,
+
+    /// Execution engine error
+    #[error("Execution error: {0}")]
+    ExecError(#[from] ag_exec::ExecError),
+
+    /// Strategy error
+    #[error("Strategy error: {0}")]
+    StrategyError(#[from] ag_strategies::StrategyError),
+
+    /// Storage error
+    #[cfg(feature = "storage")]
+    #[error("Storage error: {0}")]
+    StorageError(#[from] ag_storage::StorageError),
+
+    /// Market data source error
+    #[error("Market data error: {0}")]
+    MarketDataError(String),
+
+    /// IO error
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// YAML parsing error
+    #[error("YAML error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+
+    /// Positions could not be imported from every venue and
+    /// `position_bootstrap.required` is set
+    #[error("Position bootstrap incomplete: {0}")]
+    BootstrapIncomplete(String),
+
+    /// Control API request or response could not be handled
+    #[error("Control error: {0}")]
+    ControlError(String),
+
+    /// Background task failed
+    #[error("Task error: {0}")]
+    TaskError(String),
+}
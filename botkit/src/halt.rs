@@ -0,0 +1,77 @@
+//! Partial shutdown of a single strategy or market
+//!
+//! [`Bot::halt_strategy`](crate::Bot::halt_strategy) and
+//! [`Bot::halt_market`](crate::Bot::halt_market) stop one strategy or one
+//! market during an incident while the rest of the bot keeps trading. The
+//! running event loop applies the halt: it removes the strategy (or every
+//! strategy in the market) from the coordinator, cancels the affected exec
+//! orders, releases market data nothing else needs and, on request, closes
+//! positions with the configured liquidation style. Halted strategies and
+//! markets stay out of universe updates and session resumes until restart.
+
+use crate::error::BotResult;
+use ag_exec::OrderId;
+use ag_strategies::MarketRemoval;
+use std::fmt;
+use tokio::sync::oneshot;
+
+/// What to halt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HaltTarget {
+    /// One strategy, in all its markets
+    Strategy(String),
+    /// One market, for all strategies
+    Market(String),
+}
+
+impl fmt::Display for HaltTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HaltTarget::Strategy(id) => write!(f, "strategy {}", id),
+            HaltTarget::Market(id) => write!(f, "market {}", id),
+        }
+    }
+}
+
+/// Outcome of a halt
+#[derive(Debug, Clone)]
+pub struct HaltReport {
+    /// What was halted
+    pub target: HaltTarget,
+
+    /// Per strategy and market removal results from the coordinator
+    pub removals: Vec<MarketRemoval>,
+
+    /// Exec orders cancelled
+    pub cancelled_orders: Vec<OrderId>,
+
+    /// Markets whose market data was released
+    pub unsubscribed: Vec<String>,
+
+    /// Markets whose position is being liquidated
+    pub liquidating: Vec<String>,
+
+    /// Markets where a halted strategy left a position that was not
+    /// liquidated because other strategies still trade them
+    pub shared_positions: Vec<String>,
+}
+
+impl HaltReport {
+    pub(crate) fn new(target: HaltTarget, removals: Vec<MarketRemoval>) -> Self {
+        Self {
+            target,
+            removals,
+            cancelled_orders: Vec::new(),
+            unsubscribed: Vec::new(),
+            liquidating: Vec::new(),
+            shared_positions: Vec::new(),
+        }
+    }
+}
+
+/// Halt handed to the event loop, answered on `reply`
+pub(crate) struct HaltRequest {
+    pub target: HaltTarget,
+    pub liquidate: bool,
+    pub reply: oneshot::Sender<BotResult<HaltReport>>,
+}
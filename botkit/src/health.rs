@@ -0,0 +1,219 @@
+//! Subsystem heartbeats and stall alerts
+//!
+//! While running, a [`Bot`](crate::Bot) registers its long-running work in
+//! a [`LivenessRegistry`] and beats after every unit of progress:
+//!
+//! - `event_loop`: every pass of the event loop (at least once per
+//!   `kill_switch.check_interval_ms`)
+//! - `market_data`: every tick received (with a market data source)
+//! - `metrics_flush`: every metrics flush, including the storage writes
+//! - `exec.reconciliation`, `exec.status_poll`, `exec.venue_health`: every
+//!   round of the configured exec polling
+//!
+//! A supervisor task, independent of the event loop so it notices the
+//! loop itself hanging, checks the registry every
+//! `health.check_interval_ms`. Stalls and recoveries are logged and sent
+//! to `tail_metrics` clients as [`STALLED_METRIC`]; the heartbeat ages and
+//! the overall [`HEALTHY_METRIC`] are published at each metrics flush, and
+//! the full [`HealthReport`] is part of the control API status.
+//!
+//! Other tasks (feed supervisors, the retention scheduler) can register
+//! in the same registry through [`Bot::liveness`](crate::Bot::liveness).
+
+use crate::config::HealthSection;
+use crate::control::MetricSample;
+use crate::metrics::MetricLabeler;
+use ag_sched::{HealthReport, Heartbeat, LivenessEvent, LivenessRegistry, Ticker};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tracing::{error, info};
+
+/// Whether every subsystem is alive, 0 or 1 (gauge)
+pub const HEALTHY_METRIC: &str = "bot.healthy";
+
+/// Time since a subsystem's last heartbeat (gauge, `subsystem` label)
+pub const HEARTBEAT_AGE_METRIC: &str = "bot.heartbeat_age_ms";
+
+/// 1 when a subsystem stalls, 0 when it recovers (`subsystem` label)
+pub const STALLED_METRIC: &str = "bot.subsystem_stalled";
+
+/// Heartbeats of the bot's own subsystems
+pub(crate) struct Heartbeats {
+    pub(crate) event_loop: Heartbeat,
+    pub(crate) market_data: Option<Heartbeat>,
+    pub(crate) metrics_flush: Heartbeat,
+    pub(crate) reconciliation: Option<Heartbeat>,
+    pub(crate) status_poll: Option<Heartbeat>,
+    pub(crate) venue_health: Option<Heartbeat>,
+}
+
+/// Periodic work registered for a run, with its normal cycle
+pub(crate) struct Subsystems {
+    pub(crate) event_loop: Duration,
+    pub(crate) market_data: bool,
+    pub(crate) metrics_flush: Duration,
+    pub(crate) reconciliation: Option<Duration>,
+    pub(crate) status_poll: Option<Duration>,
+    pub(crate) venue_health: Option<Duration>,
+}
+
+impl Heartbeats {
+    /// Register the bot's subsystems
+    pub(crate) fn register(registry: &LivenessRegistry, section: &HealthSection, subsystems: Subsystems) -> Self {
+        let now = Utc::now();
+        let register = |name: &str, cycle: Option<Duration>| registry.register(name, section.stall_after(name, cycle), now);
+        Self {
+            event_loop: register("event_loop", Some(subsystems.event_loop)),
+            market_data: subsystems.market_data.then(|| register("market_data", None)),
+            metrics_flush: register("metrics_flush", Some(subsystems.metrics_flush)),
+            reconciliation: subsystems.reconciliation.map(|cycle| register("exec.reconciliation", Some(cycle))),
+            status_poll: subsystems.status_poll.map(|cycle| register("exec.status_poll", Some(cycle))),
+            venue_health: subsystems.venue_health.map(|cycle| register("exec.venue_health", Some(cycle))),
+        }
+    }
+
+    /// Names of the registered subsystems
+    pub(crate) fn names(&self) -> Vec<String> {
+        [
+            Some(&self.event_loop),
+            self.market_data.as_ref(),
+            Some(&self.metrics_flush),
+            self.reconciliation.as_ref(),
+            self.status_poll.as_ref(),
+            self.venue_health.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|heartbeat| heartbeat.name().to_string())
+        .collect()
+    }
+}
+
+/// Beat an optional heartbeat
+pub(crate) fn beat(heartbeat: &Option<Heartbeat>) {
+    if let Some(heartbeat) = heartbeat {
+        heartbeat.beat();
+    }
+}
+
+/// Check the registry until shutdown, alerting on stalls and recoveries
+pub(crate) async fn supervise(
+    bot_name: String,
+    registry: LivenessRegistry,
+    check_interval: Duration,
+    labeler: MetricLabeler,
+    metrics_tail: broadcast::Sender<MetricSample>,
+    mut shutdown_rx: watch::Receiver<bool>,
+) {
+    let mut checks = Ticker::new(ag_sched::Schedule::every(check_interval));
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.changed() => break,
+            _ = checks.tick() => {
+                let now = Utc::now();
+                for event in registry.poll(now) {
+                    let (subsystem, stalled) = match &event {
+                        LivenessEvent::Stalled { name, last_beat, silent_ms } => {
+                            error!(
+                                "Bot '{}' subsystem '{}' stalled: no heartbeat for {}ms (last at {})",
+                                bot_name, name, silent_ms, last_beat
+                            );
+                            (name, 1.0)
+                        }
+                        LivenessEvent::Recovered { name, .. } => {
+                            info!("Bot '{}' subsystem '{}' recovered", bot_name, name);
+                            (name, 0.0)
+                        }
+                    };
+                    if metrics_tail.receiver_count() > 0 {
+                        let _ = metrics_tail.send(sample(&labeler, STALLED_METRIC, Some(subsystem), stalled, now));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Health gauges as `(name, labels, value)`: the overall state and the
+/// heartbeat age per subsystem
+pub(crate) fn health_metrics(
+    labeler: &MetricLabeler,
+    report: &HealthReport,
+) -> Vec<(String, BTreeMap<String, String>, f64)> {
+    let healthy = if report.healthy { 1.0 } else { 0.0 };
+    std::iter::once(metric(labeler, HEALTHY_METRIC, None, healthy))
+        .chain(
+            report
+                .subsystems
+                .iter()
+                .map(|subsystem| metric(labeler, HEARTBEAT_AGE_METRIC, Some(&subsystem.name), subsystem.silent_ms as f64)),
+        )
+        .collect()
+}
+
+fn metric(
+    labeler: &MetricLabeler,
+    name: &str,
+    subsystem: Option<&str>,
+    value: f64,
+) -> (String, BTreeMap<String, String>, f64) {
+    let mut labels: BTreeMap<String, String> = labeler.labels().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    if let Some(subsystem) = subsystem {
+        labels.insert("subsystem".to_string(), subsystem.to_string());
+    }
+    (labeler.metric_name(name), labels, value)
+}
+
+/// Control API sample of a health metric
+pub(crate) fn sample(
+    labeler: &MetricLabeler,
+    name: &str,
+    subsystem: Option<&str>,
+    value: f64,
+    timestamp: DateTime<Utc>,
+) -> MetricSample {
+    let (name, labels, value) = metric(labeler, name, subsystem, value);
+    MetricSample {
+        timestamp,
+        name,
+        labels,
+        value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_bot_subsystems() {
+        let registry = LivenessRegistry::new();
+        let section = HealthSection::default();
+        let heartbeats = Heartbeats::register(
+            &registry,
+            &section,
+            Subsystems {
+                event_loop: Duration::from_secs(1),
+                market_data: true,
+                metrics_flush: Duration::from_secs(60),
+                reconciliation: None,
+                status_poll: None,
+                venue_health: Some(Duration::from_secs(10)),
+            },
+        );
+        assert_eq!(heartbeats.names(), vec!["event_loop", "market_data", "metrics_flush", "exec.venue_health"]);
+
+        let report = registry.report(Utc::now() + chrono::Duration::seconds(90));
+        let stalled: Vec<&str> = report.stalled().iter().map(|s| s.name.as_str()).collect();
+        // The flush threshold is three flush intervals
+        assert_eq!(stalled, vec!["event_loop", "exec.venue_health", "market_data"]);
+
+        let metrics = health_metrics(&MetricLabeler::default(), &report);
+        assert_eq!(metrics[0].0, HEALTHY_METRIC);
+        assert_eq!(metrics[0].2, 0.0);
+        assert_eq!(metrics.len(), 5);
+        assert_eq!(metrics[1].1["subsystem"], "event_loop");
+    }
+}
@@ -0,0 +1,84 @@
+//! # ag-botkit: Unified Bot Facade
+//!
+//! Composes the ag-botkit crates (risk, exec, strategies, storage) into a
+//! single runnable [`Bot`] built from one YAML config file, instead of
+//! wiring each component by hand as in `examples/minibot`.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use ag_botkit::{BotBuilder, ChannelSource};
+//!
+//! #[tokio::main]
+//! async fn main() -> ag_botkit::BotResult<()> {
+//!     let (ticks, source) = ChannelSource::new(1024);
+//!
+//!     let mut bot = BotBuilder::from_yaml_file("bot.yaml")?
+//!         .market_data(source)
+//!         .build()
+//!         .await?;
+//!
+//!     bot.start().await?;
+//!     // Feed `ticks` from your market data client...
+//!     # drop(ticks);
+//!     tokio::signal::ctrl_c().await?;
+//!     bot.stop().await
+//! }
+//! ```
+
+pub mod bot;
+pub mod builder;
+#[cfg(feature = "storage")]
+pub mod calibration;
+pub mod config;
+pub mod config_layers;
+pub mod control;
+pub mod convert;
+pub mod error;
+pub mod halt;
+pub mod health;
+pub mod logging;
+pub mod market_data;
+pub mod metrics;
+#[cfg(feature = "storage")]
+pub mod position_history;
+pub mod replay;
+#[cfg(feature = "storage")]
+pub mod risk_history;
+pub mod session;
+#[cfg(feature = "storage")]
+pub mod tca;
+pub mod validate;
+
+// Re-export main types
+pub use bot::Bot;
+pub use builder::{BotBuilder, StrategyFactory};
+pub use config::{
+    BotConfig, CapitalSection, ControlSection, ExecSection, HealthSection, KillSwitchSection, LoggingSection,
+    MarketSessionSection, MetricsSection, PositionHistorySection, ReconciliationSection, RecordingSection,
+    RiskStateSection, RunsSection, SessionSection, ShutdownSection, StrategyLogSection, StrategySection, TcaSection,
+    TimerSection, VenueSection,
+};
+pub use config_layers::{ConfigLayer, ConfigLayers, ConfigOverride};
+pub use control::{ControlClient, ControlRequest, ControlResponse};
+pub use error::{BotError, BotResult};
+pub use halt::{HaltReport, HaltTarget};
+pub use logging::LogRouter;
+pub use market_data::{ChannelSource, MarketDataSource};
+pub use metrics::MetricLabeler;
+#[cfg(feature = "storage")]
+pub use replay::MarketDataRecorder;
+pub use replay::{ReplaySource, ReplaySpeed};
+pub use session::{MarketSession, SessionAction, SessionManager};
+pub use validate::{ConfigIssue, ConfigValidator, IssueKind, ParamSpec, ParamType, ValidationReport};
+
+// Re-export component crates
+pub use ag_exec as exec;
+pub use ag_risk as risk;
+pub use ag_sched as sched;
+#[cfg(feature = "storage")]
+pub use ag_storage as storage;
+pub use ag_strategies as strategies;
+
+/// Library version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,353 @@
+//! Per-strategy log routing
+//!
+//! The coordinator runs every strategy callback inside a `strategy` span
+//! carrying its `strategy_id`. [`LogRouter::layer`] is a tracing layer that
+//! appends events from inside such spans to a per-strategy file, filtered at
+//! a per-strategy level that can be raised or lowered while the bot runs:
+//!
+//! ```rust,no_run
+//! use ag_botkit::{BotConfig, LogRouter};
+//! use tracing::level_filters::LevelFilter;
+//! use tracing_subscriber::prelude::*;
+//!
+//! let config = BotConfig::from_yaml_file("bot.yaml")?;
+//! let router = LogRouter::from_config(&config.logging)?;
+//! tracing_subscriber::registry()
+//!     .with(tracing_subscriber::fmt::layer())
+//!     .with(router.layer())
+//!     .init();
+//!
+//! // Debug one strategy live without touching the others
+//! router.set_level("mm_1", LevelFilter::DEBUG)?;
+//! # Ok::<(), ag_botkit::BotError>(())
+//! ```
+//!
+//! The layer carries its own filter, so its levels are independent of the
+//! filters on other layers. Events can also name a strategy explicitly with
+//! a `strategy_id` field.
+
+use crate::config::LoggingSection;
+use crate::error::{BotError, BotResult};
+use chrono::Utc;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write as _};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::Interest;
+use tracing::{Event, Metadata, Subscriber};
+use tracing_subscriber::filter::Filtered;
+use tracing_subscriber::layer::{Context, Filter, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Name of the span the coordinator opens around strategy callbacks
+pub const STRATEGY_SPAN: &str = "strategy";
+
+/// Field holding the strategy ID on spans and events
+const STRATEGY_FIELD: &str = "strategy_id";
+
+/// Parse a level name (`trace`, `debug`, `info`, `warn`, `error`, `off`)
+pub fn parse_level(level: &str) -> BotResult<LevelFilter> {
+    LevelFilter::from_str(level).map_err(|_| BotError::ConfigError(format!("Invalid log level: {}", level)))
+}
+
+/// Log destination of one strategy
+struct Route {
+    /// Level from the config, restored by `reset_level`
+    configured: LevelFilter,
+    /// Current level
+    level: LevelFilter,
+    path: PathBuf,
+    writer: Mutex<LineWriter<File>>,
+}
+
+struct RouterState {
+    routes: RwLock<HashMap<String, Route>>,
+    /// Most verbose level of any route, checked before looking up a route
+    max_level: RwLock<LevelFilter>,
+}
+
+impl Default for RouterState {
+    fn default() -> Self {
+        Self {
+            routes: RwLock::new(HashMap::new()),
+            max_level: RwLock::new(LevelFilter::OFF),
+        }
+    }
+}
+
+impl RouterState {
+    fn update_max_level(&self, routes: &HashMap<String, Route>) {
+        *self.max_level.write() = routes.values().map(|r| r.level).max().unwrap_or(LevelFilter::OFF);
+    }
+}
+
+/// Routes strategy logs to per-strategy files at runtime-adjustable levels
+#[derive(Clone, Default)]
+pub struct LogRouter {
+    state: Arc<RouterState>,
+}
+
+impl fmt::Debug for LogRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routes = self.state.routes.read();
+        let mut map = f.debug_map();
+        for (strategy_id, route) in routes.iter() {
+            map.entry(strategy_id, &(route.level, &route.path));
+        }
+        map.finish()
+    }
+}
+
+impl LogRouter {
+    /// Create a router with no routes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a router with the routes of a config `logging` section
+    pub fn from_config(section: &LoggingSection) -> BotResult<Self> {
+        let router = Self::new();
+        for (strategy_id, route) in &section.strategies {
+            router.add_route(strategy_id, &route.file, parse_level(&route.level)?)?;
+        }
+        Ok(router)
+    }
+
+    /// Route a strategy's logs at `level` and more severe to `path` (appended,
+    /// parent directories created); replaces any existing route
+    pub fn add_route(&self, strategy_id: &str, path: impl AsRef<Path>, level: LevelFilter) -> BotResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let mut routes = self.state.routes.write();
+        routes.insert(
+            strategy_id.to_string(),
+            Route {
+                configured: level,
+                level,
+                path: path.to_path_buf(),
+                writer: Mutex::new(LineWriter::new(file)),
+            },
+        );
+        self.state.update_max_level(&routes);
+        Ok(())
+    }
+
+    /// Stop routing a strategy's logs; returns whether it had a route
+    pub fn remove_route(&self, strategy_id: &str) -> bool {
+        let mut routes = self.state.routes.write();
+        let removed = routes.remove(strategy_id).is_some();
+        self.state.update_max_level(&routes);
+        removed
+    }
+
+    /// Change a routed strategy's level until [`reset_level`](Self::reset_level)
+    pub fn set_level(&self, strategy_id: &str, level: LevelFilter) -> BotResult<()> {
+        let mut routes = self.state.routes.write();
+        let route = routes
+            .get_mut(strategy_id)
+            .ok_or_else(|| BotError::ConfigError(format!("No log route for strategy: {}", strategy_id)))?;
+        route.level = level;
+        self.state.update_max_level(&routes);
+        Ok(())
+    }
+
+    /// Restore a routed strategy's configured level
+    pub fn reset_level(&self, strategy_id: &str) -> BotResult<()> {
+        let configured = self
+            .state
+            .routes
+            .read()
+            .get(strategy_id)
+            .map(|r| r.configured)
+            .ok_or_else(|| BotError::ConfigError(format!("No log route for strategy: {}", strategy_id)))?;
+        self.set_level(strategy_id, configured)
+    }
+
+    /// Current level of a routed strategy
+    pub fn level(&self, strategy_id: &str) -> Option<LevelFilter> {
+        self.state.routes.read().get(strategy_id).map(|r| r.level)
+    }
+
+    /// IDs of routed strategies, sorted
+    pub fn routes(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.state.routes.read().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Tracing layer writing routed events, with its own per-layer filter
+    pub fn layer<S>(&self) -> Filtered<StrategyLogLayer, StrategyLogFilter, S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        StrategyLogLayer { state: self.state.clone() }.with_filter(StrategyLogFilter { state: self.state.clone() })
+    }
+}
+
+/// Strategy ID stored in a `strategy` span's extensions
+struct SpanStrategy(String);
+
+/// Collects an event's message and fields
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+    strategy_id: Option<String>,
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == STRATEGY_FIELD {
+            self.strategy_id = Some(value.to_string());
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => {
+                let _ = write!(self.message, "{:?}", value);
+            }
+            STRATEGY_FIELD => self.strategy_id = Some(format!("{:?}", value)),
+            name => {
+                let _ = write!(self.fields, " {}={:?}", name, value);
+            }
+        }
+    }
+}
+
+/// Layer appending strategy events to their route's file
+pub struct StrategyLogLayer {
+    state: Arc<RouterState>,
+}
+
+impl<S> Layer<S> for StrategyLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != STRATEGY_SPAN {
+            return;
+        }
+        let mut visitor = EventVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(strategy_id), Some(span)) = (visitor.strategy_id, ctx.span(id)) {
+            span.extensions_mut().insert(SpanStrategy(strategy_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+
+        let strategy_id = visitor.strategy_id.take().or_else(|| {
+            ctx.event_scope(event)?
+                .find_map(|span| span.extensions().get::<SpanStrategy>().map(|s| s.0.clone()))
+        });
+        let Some(strategy_id) = strategy_id else {
+            return;
+        };
+
+        let routes = self.state.routes.read();
+        let Some(route) = routes.get(&strategy_id) else {
+            return;
+        };
+        let metadata = event.metadata();
+        if *metadata.level() > route.level {
+            return;
+        }
+
+        let line = format!(
+            "{} {:>5} {}: {}{}\n",
+            Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            metadata.level(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        );
+        let _ = route.writer.lock().write_all(line.as_bytes());
+    }
+}
+
+/// Per-layer filter: `strategy` spans, and events no more verbose than the
+/// most verbose route
+pub struct StrategyLogFilter {
+    state: Arc<RouterState>,
+}
+
+impl<S> Filter<S> for StrategyLogFilter {
+    fn enabled(&self, metadata: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        if metadata.is_span() {
+            return metadata.name() == STRATEGY_SPAN;
+        }
+        *metadata.level() <= *self.state.max_level.read()
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        match (metadata.is_span(), metadata.name() == STRATEGY_SPAN) {
+            (true, true) => Interest::always(),
+            (true, false) => Interest::never(),
+            // Levels change at runtime
+            (false, _) => Interest::sometimes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_routes_by_strategy_span() {
+        let dir = std::env::temp_dir().join(format!("ag-botkit-logs-{}", uuid::Uuid::new_v4()));
+        let router = LogRouter::new();
+        router.add_route("mm_1", dir.join("mm_1.log"), LevelFilter::INFO).unwrap();
+        router.add_route("arb_1", dir.join("arb_1.log"), LevelFilter::WARN).unwrap();
+
+        let subscriber = tracing_subscriber::registry().with(router.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("strategy", strategy_id = "mm_1").in_scope(|| {
+                tracing::info!(spread = 0.02, "quoting");
+                tracing::debug!("hidden until raised");
+            });
+            tracing::info_span!("strategy", strategy_id = "arb_1").in_scope(|| {
+                tracing::info!("too verbose for arb_1");
+                tracing::warn!("leg missing");
+            });
+            tracing::info!("not a strategy event");
+            tracing::info!(strategy_id = "mm_1", "tagged explicitly");
+
+            router.set_level("mm_1", LevelFilter::DEBUG).unwrap();
+            tracing::info_span!("strategy", strategy_id = "mm_1").in_scope(|| tracing::debug!("now visible"));
+            router.reset_level("mm_1").unwrap();
+            tracing::info_span!("strategy", strategy_id = "mm_1").in_scope(|| tracing::debug!("hidden again"));
+        });
+
+        let mm = std::fs::read_to_string(dir.join("mm_1.log")).unwrap();
+        let arb = std::fs::read_to_string(dir.join("arb_1.log")).unwrap();
+        let mm_lines: Vec<&str> = mm.lines().collect();
+        assert_eq!(mm_lines.len(), 3);
+        assert!(mm_lines[0].ends_with("quoting spread=0.02"));
+        assert!(mm_lines[1].ends_with("tagged explicitly"));
+        assert!(mm_lines[2].contains("DEBUG") && mm_lines[2].ends_with("now visible"));
+        assert_eq!(arb.lines().count(), 1);
+        assert!(arb.contains(" WARN ") && arb.contains("leg missing"));
+
+        assert!(router.set_level("unknown", LevelFilter::DEBUG).is_err());
+        assert_eq!(router.routes(), vec!["arb_1", "mm_1"]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,113 @@
+//! Market data sources feeding the coordinator
+
+use crate::error::BotResult;
+use ag_exec::{BookValidationStats, ConnectionState};
+use ag_strategies::{MarketStatusUpdate, MarketTick};
+use async_trait::async_trait;
+use tokio::sync::{mpsc, watch};
+
+/// Source of market ticks for a bot
+///
+/// `next_tick` is polled inside `tokio::select!`, so implementations must be
+/// cancel-safe (a cancelled call must not lose a tick).
+#[async_trait]
+pub trait MarketDataSource: Send {
+    /// Subscribe to the given markets (at start, and as the universe grows)
+    async fn subscribe(&mut self, markets: &[String]) -> BotResult<()>;
+
+    /// Stop receiving ticks for markets no strategy trades anymore
+    ///
+    /// The default does nothing; ticks for unsubscribed markets are then
+    /// simply not routed by the coordinator.
+    async fn unsubscribe(&mut self, _markets: &[String]) -> BotResult<()> {
+        Ok(())
+    }
+
+    /// Resynchronize markets after a detected sequence gap (e.g. request
+    /// fresh book snapshots)
+    ///
+    /// The default does nothing; the next tick then replaces the stale state.
+    async fn resync(&mut self, _markets: &[String]) -> BotResult<()> {
+        Ok(())
+    }
+
+    /// Wait for the next tick; `None` means the feed has ended
+    async fn next_tick(&mut self) -> Option<MarketTick>;
+
+    /// Validation counters of the books the source maintains, per market
+    ///
+    /// Sources that build full depth books from snapshots and updates
+    /// should validate them with an [`ag_exec::BookValidator`] and report
+    /// its counters here; the bot records them as `market_data.book_*`
+    /// metrics on every flush. The default reports nothing.
+    fn book_validation(&self) -> Vec<(String, BookValidationStats)> {
+        Vec::new()
+    }
+
+    /// Connection state of the underlying feed, if it reports one
+    ///
+    /// While the state is not [`ConnectionState::Connected`] the bot
+    /// cancels resting orders and strategies refuse new ones.
+    fn connection_state(&self) -> Option<watch::Receiver<ConnectionState>> {
+        None
+    }
+
+    /// Market status messages of the feed (closures and resolutions), if it
+    /// reports them
+    ///
+    /// Called once per start; the bot keeps the receiver across restarts.
+    /// Resolved markets are settled at their payout, see the botkit README.
+    fn status_updates(&mut self) -> Option<mpsc::Receiver<MarketStatusUpdate>> {
+        None
+    }
+}
+
+/// Market data source backed by an mpsc channel
+///
+/// Useful for wiring an existing feed (e.g. an RTDS client task) or
+/// for tests: push ticks through the [`mpsc::Sender`] returned by [`ChannelSource::new`].
+pub struct ChannelSource {
+    rx: mpsc::Receiver<MarketTick>,
+    state: Option<watch::Receiver<ConnectionState>>,
+    status: Option<mpsc::Receiver<MarketStatusUpdate>>,
+}
+
+impl ChannelSource {
+    /// Create a channel source and its sender
+    pub fn new(buffer: usize) -> (mpsc::Sender<MarketTick>, Self) {
+        let (tx, rx) = mpsc::channel(buffer);
+        (tx, Self { rx, state: None, status: None })
+    }
+
+    /// Report the connection state of the feed behind the channel (e.g.
+    /// [`FeedHandle::watch_state`](ag_exec::FeedHandle::watch_state))
+    pub fn with_connection_state(mut self, state: watch::Receiver<ConnectionState>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Report market status messages pushed through `status`
+    pub fn with_status_updates(mut self, status: mpsc::Receiver<MarketStatusUpdate>) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for ChannelSource {
+    async fn subscribe(&mut self, _markets: &[String]) -> BotResult<()> {
+        Ok(())
+    }
+
+    async fn next_tick(&mut self) -> Option<MarketTick> {
+        self.rx.recv().await
+    }
+
+    fn connection_state(&self) -> Option<watch::Receiver<ConnectionState>> {
+        self.state.clone()
+    }
+
+    fn status_updates(&mut self) -> Option<mpsc::Receiver<MarketStatusUpdate>> {
+        self.status.take()
+    }
+}
@@ -0,0 +1,129 @@
+//! Metric namespacing and standard labels
+//!
+//! A [`MetricLabeler`] built from the `metrics` config section prefixes every
+//! exported metric name with the configured namespace and attaches the
+//! standard labels (`bot`, `instance`, `environment`, plus any static
+//! `labels`) so bots from different deployments never collide in the
+//! monitoring backend. Strategy metrics additionally carry `strategy_id`,
+//! and `run_id` while the strategy's run is recorded.
+
+use crate::config::MetricsSection;
+use ag_strategies::StrategyMetric;
+use std::collections::HashMap;
+
+/// Label keys set by the labeler; static `labels` may not override them
+pub const RESERVED_LABELS: &[&str] = &["bot", "instance", "environment", "strategy_id", "run_id", "market"];
+
+/// Applies the metric namespace and standard labels
+#[derive(Debug, Clone, Default)]
+pub struct MetricLabeler {
+    namespace: Option<String>,
+    labels: HashMap<String, String>,
+    /// Current run ID by strategy
+    run_ids: HashMap<String, String>,
+}
+
+impl MetricLabeler {
+    /// Build from the bot name and metrics section
+    pub fn new(bot_name: &str, section: &MetricsSection) -> Self {
+        let mut labels = section.labels.clone();
+        labels.insert("bot".to_string(), bot_name.to_string());
+        if let Some(instance) = &section.instance {
+            labels.insert("instance".to_string(), instance.clone());
+        }
+        if let Some(environment) = &section.environment {
+            labels.insert("environment".to_string(), environment.clone());
+        }
+        Self {
+            namespace: section.namespace.clone(),
+            labels,
+            run_ids: HashMap::new(),
+        }
+    }
+
+    /// Label a strategy's metrics with its current run ID
+    pub fn set_run_id(&mut self, strategy_id: &str, run_id: &str) {
+        self.run_ids.insert(strategy_id.to_string(), run_id.to_string());
+    }
+
+    /// Namespaced metric name (`<namespace>.<name>`)
+    pub fn metric_name(&self, name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}.{}", namespace, name),
+            None => name.to_string(),
+        }
+    }
+
+    /// Standard labels attached to every metric
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Namespace a strategy metric and add the standard labels, `strategy_id`
+    /// and `run_id`
+    ///
+    /// Standard labels take precedence over labels set by the strategy.
+    pub fn apply(&self, mut metric: StrategyMetric) -> StrategyMetric {
+        metric.metric_name = self.metric_name(&metric.metric_name);
+        metric.labels.insert("strategy_id".to_string(), metric.strategy_id.clone());
+        if let Some(run_id) = self.run_ids.get(&metric.strategy_id) {
+            metric.labels.insert("run_id".to_string(), run_id.clone());
+        }
+        metric.labels.extend(self.labels.clone());
+        metric
+    }
+
+    /// Namespace and label an engine metric for one market
+    pub fn market_metric(&self, name: &str, market: &str) -> (String, HashMap<String, String>) {
+        let mut labels = self.labels.clone();
+        labels.insert("market".to_string(), market.to_string());
+        (self.metric_name(name), labels)
+    }
+
+    /// Namespace and label an engine metric for one venue
+    pub fn venue_metric(&self, name: &str, venue: &str) -> (String, HashMap<String, String>) {
+        let mut labels = self.labels.clone();
+        labels.insert("venue".to_string(), venue.to_string());
+        (self.metric_name(name), labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace_and_labels() {
+        let section = MetricsSection {
+            namespace: Some("agbot".to_string()),
+            environment: Some("paper".to_string()),
+            instance: Some("mm-bot-2".to_string()),
+            labels: HashMap::from([("region".to_string(), "eu".to_string())]),
+            ..MetricsSection::default()
+        };
+        let mut labeler = MetricLabeler::new("mm-bot", &section);
+        labeler.set_run_id("mm_1", "mm_1-20261016T120000Z-0a1b2c3d");
+
+        let metric = StrategyMetric::gauge(
+            "mm_1".to_string(),
+            "strategy.pnl_usd".to_string(),
+            1.5,
+            HashMap::from([("environment".to_string(), "spoofed".to_string())]),
+        );
+        let metric = labeler.apply(metric);
+        assert_eq!(metric.metric_name, "agbot.strategy.pnl_usd");
+        assert_eq!(metric.labels["environment"], "paper");
+        assert_eq!(metric.labels["instance"], "mm-bot-2");
+        assert_eq!(metric.labels["bot"], "mm-bot");
+        assert_eq!(metric.labels["strategy_id"], "mm_1");
+        assert_eq!(metric.labels["region"], "eu");
+        assert_eq!(metric.labels["run_id"], "mm_1-20261016T120000Z-0a1b2c3d");
+
+        let (name, labels) = labeler.market_metric("market.mid", "0x123");
+        assert_eq!(name, "agbot.market.mid");
+        assert_eq!(labels["market"], "0x123");
+        assert_eq!(labels["environment"], "paper");
+
+        assert_eq!(MetricLabeler::default().metric_name("market.mid"), "market.mid");
+    }
+}
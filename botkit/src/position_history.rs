@@ -0,0 +1,110 @@
+//! Periodic position snapshots for the positions history table
+//!
+//! With `position_history.snapshot_interval_ms` set, the event loop nets
+//! every strategy's positions per market, marks them at the exec engine's
+//! latest mid and writes them to the `positions` table in one batch, so the
+//! history is populated without strategies writing their own snapshots.
+//! All snapshots of a batch share one timestamp, which keeps the rows of
+//! different markets aligned for [`crate::risk_history`].
+
+use ag_storage::PositionSnapshot;
+use ag_strategies::Position;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+/// Venue recorded for markets whose venue is unknown
+pub const UNKNOWN_VENUE: &str = "unknown";
+
+/// Net strategy positions per market and mark them at a common time
+///
+/// `positions` are per strategy (see
+/// [`MultiMarketCoordinator::get_cross_market_positions`](ag_strategies::MultiMarketCoordinator::get_cross_market_positions)).
+/// Markets without a price in `marks` keep the most recent mark of their
+/// positions (the last fill price). The entry price is the size-weighted
+/// entry of the net position (zero when flat), realized PnL is summed and
+/// unrealized PnL is recomputed from the mark. Flat markets are kept, so
+/// closing a position records its realized PnL. Snapshots are ordered by
+/// market.
+pub fn position_snapshots(
+    positions: &HashMap<String, Vec<Position>>,
+    marks: &HashMap<String, f64>,
+    venues: &HashMap<String, String>,
+    timestamp: DateTime<Utc>,
+) -> Vec<PositionSnapshot> {
+    let mut by_market: BTreeMap<&str, Vec<&Position>> = BTreeMap::new();
+    for position in positions.values().flatten() {
+        by_market.entry(position.market.as_str()).or_default().push(position);
+    }
+
+    by_market
+        .into_iter()
+        .map(|(market, positions)| {
+            let size: f64 = positions.iter().map(|p| p.size).sum();
+            let cost: f64 = positions.iter().map(|p| p.size * p.entry_price).sum();
+            let realized: f64 = positions.iter().map(|p| p.realized_pnl).sum();
+            let mark = marks.get(market).copied().or_else(|| {
+                positions
+                    .iter()
+                    .filter(|p| p.mark_price > 0.0)
+                    .max_by_key(|p| p.timestamp)
+                    .map(|p| p.mark_price)
+            });
+            let unrealized = match mark {
+                Some(mark) => size * mark - cost,
+                None => positions.iter().map(|p| p.unrealized_pnl).sum(),
+            };
+            let avg_entry_price = if size.abs() > 1e-8 { cost / size } else { 0.0 };
+            let venue = venues.get(market).map(String::as_str).unwrap_or(UNKNOWN_VENUE);
+
+            let mut snapshot =
+                PositionSnapshot::new(venue, market, size, avg_entry_price).with_pnl(unrealized, realized);
+            snapshot.timestamp = timestamp;
+            snapshot.mark_price = mark;
+            snapshot
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(market: &str, size: f64, entry_price: f64, realized_pnl: f64) -> Position {
+        let mut position = Position::new(market.to_string());
+        position.size = size;
+        position.entry_price = entry_price;
+        position.mark_price = entry_price;
+        position.realized_pnl = realized_pnl;
+        position
+    }
+
+    #[test]
+    fn test_snapshots_net_and_mark_positions() {
+        let positions = HashMap::from([
+            ("mm_1".to_string(), vec![position("m1", 100.0, 0.40, 2.0), position("m2", 0.0, 0.0, -1.0)]),
+            ("arb_1".to_string(), vec![position("m1", 50.0, 0.46, 0.5)]),
+        ]);
+        let marks = HashMap::from([("m1".to_string(), 0.50)]);
+        let venues = HashMap::from([("m1".to_string(), "polymarket".to_string())]);
+        let at = Utc::now();
+
+        let snapshots = position_snapshots(&positions, &marks, &venues, at);
+        assert_eq!(snapshots.len(), 2);
+        assert!(snapshots.iter().all(|s| s.timestamp == at));
+
+        let m1 = &snapshots[0];
+        assert_eq!((m1.market.as_str(), m1.venue.as_str()), ("m1", "polymarket"));
+        assert_eq!(m1.size, 150.0);
+        assert!((m1.avg_entry_price - 0.42).abs() < 1e-9);
+        assert!((m1.unrealized_pnl.unwrap() - 12.0).abs() < 1e-9);
+        assert_eq!(m1.realized_pnl, Some(2.5));
+        assert_eq!(m1.mark_price, Some(0.50));
+
+        // Flat, unmarked and on an unknown venue
+        let m2 = &snapshots[1];
+        assert_eq!(m2.venue, UNKNOWN_VENUE);
+        assert_eq!((m2.size, m2.avg_entry_price), (0.0, 0.0));
+        assert_eq!(m2.realized_pnl, Some(-1.0));
+        assert_eq!(m2.mark_price, None);
+    }
+}
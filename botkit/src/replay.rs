@@ -0,0 +1,253 @@
+//! Market data recording and replay
+//!
+//! With `recording.market_data` enabled the bot writes every tick it routes
+//! to storage ([`MarketDataRecorder`], flushed with metrics). A
+//! [`ReplaySource`] streams recorded ticks back into a bot at their original
+//! pace or accelerated, so a new strategy build can be shadow-tested
+//! against yesterday's exact data (see [`BotBuilder::shadow`](crate::BotBuilder::shadow)).
+
+use crate::error::BotResult;
+use crate::market_data::MarketDataSource;
+use ag_strategies::MarketTick;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{HashSet, VecDeque};
+use tokio::time::Instant;
+
+/// Pace of a replay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Original spacing between ticks
+    Original,
+    /// Spacing divided by the factor (e.g. 10.0 replays an hour in 6
+    /// minutes; a factor <= 0 behaves like `Max`)
+    Accelerated(f64),
+    /// No waiting between ticks
+    Max,
+}
+
+/// Market data source replaying recorded ticks
+///
+/// Ticks are delivered in timestamp order, paced relative to the first tick.
+/// Ticks for markets the bot has not subscribed are skipped. The feed ends
+/// (`next_tick` returns `None`) after the last tick.
+pub struct ReplaySource {
+    ticks: VecDeque<MarketTick>,
+    speed: ReplaySpeed,
+    subscribed: HashSet<String>,
+    /// Wall-clock start and timestamp of the first replayed tick
+    anchor: Option<(Instant, DateTime<Utc>)>,
+}
+
+impl ReplaySource {
+    /// Replay the given ticks
+    pub fn new(ticks: impl IntoIterator<Item = MarketTick>, speed: ReplaySpeed) -> Self {
+        let mut ticks: Vec<MarketTick> = ticks.into_iter().collect();
+        ticks.sort_by_key(|tick| tick.timestamp);
+        Self {
+            ticks: ticks.into(),
+            speed,
+            subscribed: HashSet::new(),
+            anchor: None,
+        }
+    }
+
+    /// Replay ticks recorded in storage between `start` and `end` (all
+    /// markets if `markets` is empty)
+    #[cfg(feature = "storage")]
+    pub async fn from_storage(
+        storage: &ag_storage::StorageEngine,
+        markets: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        speed: ReplaySpeed,
+    ) -> BotResult<Self> {
+        let records = storage.query_market_ticks(markets, start, end).await?;
+        Ok(Self::new(records.into_iter().map(tick_from_record), speed))
+    }
+
+    /// Ticks not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// When the tick recorded at `timestamp` is due
+    fn due_at(&mut self, timestamp: DateTime<Utc>) -> Option<Instant> {
+        let factor = match self.speed {
+            ReplaySpeed::Original => 1.0,
+            ReplaySpeed::Accelerated(factor) if factor > 0.0 => factor,
+            ReplaySpeed::Accelerated(_) | ReplaySpeed::Max => return None,
+        };
+        let (started, first) = *self.anchor.get_or_insert((Instant::now(), timestamp));
+        let offset = (timestamp - first).to_std().unwrap_or_default();
+        Some(started + offset.div_f64(factor))
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for ReplaySource {
+    async fn subscribe(&mut self, markets: &[String]) -> BotResult<()> {
+        self.subscribed.extend(markets.iter().cloned());
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, markets: &[String]) -> BotResult<()> {
+        for market in markets {
+            self.subscribed.remove(market);
+        }
+        Ok(())
+    }
+
+    async fn next_tick(&mut self) -> Option<MarketTick> {
+        loop {
+            let tick = self.ticks.front()?;
+            if !self.subscribed.contains(&tick.market) {
+                self.ticks.pop_front();
+                continue;
+            }
+            let timestamp = tick.timestamp;
+
+            // The tick stays queued until its deadline, so a cancelled call
+            // loses nothing and the retry waits for the same deadline
+            if let Some(deadline) = self.due_at(timestamp) {
+                tokio::time::sleep_until(deadline).await;
+            }
+            return self.ticks.pop_front();
+        }
+    }
+}
+
+/// Buffer of routed ticks waiting to be written to storage
+#[cfg(feature = "storage")]
+#[derive(Debug, Default)]
+pub struct MarketDataRecorder {
+    buffer: Vec<ag_storage::MarketTickRecord>,
+}
+
+#[cfg(feature = "storage")]
+impl MarketDataRecorder {
+    /// Create an empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer a tick
+    pub fn record(&mut self, tick: &MarketTick) {
+        self.buffer.push(record_from_tick(tick));
+    }
+
+    /// Buffered ticks
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Whether nothing is buffered
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Take the buffered ticks
+    pub fn drain(&mut self) -> Vec<ag_storage::MarketTickRecord> {
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+/// Storage record of a tick
+#[cfg(feature = "storage")]
+pub fn record_from_tick(tick: &MarketTick) -> ag_storage::MarketTickRecord {
+    ag_storage::MarketTickRecord {
+        timestamp: tick.timestamp,
+        market: tick.market.clone(),
+        bid: tick.bid,
+        bid_size: tick.bid_size,
+        ask: tick.ask,
+        ask_size: tick.ask_size,
+        last: tick.last,
+        volume_24h: tick.volume_24h,
+        sequence: tick.sequence.map(|seq| seq as i64),
+    }
+}
+
+/// Tick from its storage record
+#[cfg(feature = "storage")]
+pub fn tick_from_record(record: ag_storage::MarketTickRecord) -> MarketTick {
+    MarketTick {
+        market: record.market,
+        timestamp: record.timestamp,
+        bid: record.bid,
+        bid_size: record.bid_size,
+        ask: record.ask,
+        ask_size: record.ask_size,
+        last: record.last,
+        volume_24h: record.volume_24h,
+        sequence: record.sequence.map(|seq| seq as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn tick(market: &str, timestamp: DateTime<Utc>) -> MarketTick {
+        MarketTick {
+            market: market.to_string(),
+            timestamp,
+            bid: Some(0.49),
+            bid_size: Some(100.0),
+            ask: Some(0.51),
+            ask_size: Some(100.0),
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_order_and_filter() {
+        let now = Utc::now();
+        let mut source = ReplaySource::new(
+            vec![tick("m1", now + Duration::seconds(2)), tick("m2", now + Duration::seconds(1)), tick("m1", now)],
+            ReplaySpeed::Max,
+        );
+        source.subscribe(&["m1".to_string()]).await.unwrap();
+
+        assert_eq!(source.next_tick().await.unwrap().timestamp, now);
+        assert_eq!(source.next_tick().await.unwrap().timestamp, now + Duration::seconds(2));
+        assert!(source.next_tick().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_accelerated_pacing() {
+        let now = Utc::now();
+        let mut source = ReplaySource::new(
+            vec![tick("m1", now), tick("m1", now + Duration::milliseconds(200))],
+            ReplaySpeed::Accelerated(10.0),
+        );
+        source.subscribe(&["m1".to_string()]).await.unwrap();
+
+        let started = std::time::Instant::now();
+        source.next_tick().await.unwrap();
+        source.next_tick().await.unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed >= std::time::Duration::from_millis(20));
+        assert!(elapsed < std::time::Duration::from_millis(200));
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_record_round_trip() {
+        let mut original = tick("m1", Utc::now());
+        original.sequence = Some(42);
+
+        let mut recorder = MarketDataRecorder::new();
+        recorder.record(&original);
+        let records = recorder.drain();
+        assert!(recorder.is_empty());
+
+        let restored = tick_from_record(records[0].clone());
+        assert_eq!(restored.sequence, Some(42));
+        assert_eq!(restored.timestamp, original.timestamp);
+        assert_eq!(restored.bid, original.bid);
+    }
+}
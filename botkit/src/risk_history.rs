@@ -0,0 +1,353 @@
+//! Return series for the advanced risk models from stored history
+//!
+//! [`VarEngine`] and [`PortfolioAnalyzer`] work on return series, which
+//! otherwise have to be assembled by hand. This module builds them from
+//! what the bot already stores: position snapshots (size and mark price per
+//! market) or the `market.mid` metric combined with current position sizes.
+//! Every market is sampled on the same fixed-interval grid over a lookback
+//! window, so the per-market series line up for correlation and covariance
+//! and the portfolio series reflects the positions held at each step.
+//!
+//! ```rust,no_run
+//! use ag_botkit::risk::advanced::{PortfolioAnalyzer, PortfolioConfig, VarConfig};
+//! use ag_botkit::risk_history::load_position_history;
+//! use ag_botkit::storage::{ExecutionStore, StorageConfig};
+//! use chrono::Duration;
+//!
+//! # async fn run() -> ag_botkit::BotResult<()> {
+//! let store = ExecutionStore::new(StorageConfig::default()).await?;
+//! let history = load_position_history(&store, &["0xabc", "0xdef"], Duration::days(7), Duration::hours(1)).await?;
+//!
+//! let var = history.var_engine(VarConfig::default());
+//! let result = var.calculate_historical_var(history.portfolio_value(), 0.95, 1).unwrap();
+//! let covariance = PortfolioAnalyzer::new(PortfolioConfig::default())
+//!     .calculate_covariance_matrix(&history.asset_returns)
+//!     .unwrap();
+//! println!("95% 1-step VaR {:.2} over {} markets", result.var_amount, covariance.nrows());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::BotResult;
+use crate::metrics::MetricLabeler;
+use crate::tca::MID_METRIC;
+use ag_risk::advanced::{VarConfig, VarEngine};
+use ag_risk::RiskEngine;
+use ag_storage::{ExecutionStore, PositionSnapshot, StorageEngine};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+use tracing::debug;
+
+/// Observation of a market: position size and price at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sample {
+    timestamp: DateTime<Utc>,
+    size: f64,
+    price: f64,
+}
+
+/// Aligned return series over a lookback window
+#[derive(Debug, Clone, Default)]
+pub struct ReturnHistory {
+    /// Grid points the series were sampled at (one more than the returns)
+    pub timestamps: Vec<DateTime<Utc>>,
+
+    /// Simple price returns per market, all of equal length
+    pub asset_returns: HashMap<String, Vec<f64>>,
+
+    /// Return of the gross position value, weighted by the positions held
+    /// at the start of each step
+    pub portfolio_returns: Vec<f64>,
+
+    /// Signed position value per market at the last grid point
+    pub values: HashMap<String, f64>,
+}
+
+impl ReturnHistory {
+    /// Build return series from position snapshots
+    ///
+    /// Snapshots without a mark price are skipped; snapshots of one market
+    /// on several venues are treated as a single series.
+    pub fn from_snapshots(
+        snapshots: &[PositionSnapshot],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        interval: Duration,
+    ) -> Self {
+        let mut series: BTreeMap<String, Vec<Sample>> = BTreeMap::new();
+        for snapshot in snapshots {
+            if let Some(price) = snapshot.mark_price {
+                series.entry(snapshot.market.clone()).or_default().push(Sample {
+                    timestamp: snapshot.timestamp,
+                    size: snapshot.size,
+                    price,
+                });
+            }
+        }
+        Self::from_series(series, start, end, interval)
+    }
+
+    /// Build return series from price observations and fixed position sizes
+    ///
+    /// Markets without a size are weighted as flat (they still get asset
+    /// returns).
+    pub fn from_prices(
+        prices: &HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+        sizes: &HashMap<String, f64>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        interval: Duration,
+    ) -> Self {
+        let series = prices
+            .iter()
+            .map(|(market, points)| {
+                let size = sizes.get(market).copied().unwrap_or(0.0);
+                let samples = points
+                    .iter()
+                    .map(|&(timestamp, price)| Sample { timestamp, size, price })
+                    .collect();
+                (market.clone(), samples)
+            })
+            .collect();
+        Self::from_series(series, start, end, interval)
+    }
+
+    /// Sample every market on a common grid, carrying the last observation
+    /// forward
+    ///
+    /// The grid starts at the first point where every market has been
+    /// observed, so all series have the same length.
+    fn from_series(
+        mut series: BTreeMap<String, Vec<Sample>>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        interval: Duration,
+    ) -> Self {
+        series.retain(|_, samples| !samples.is_empty());
+        if series.is_empty() || interval <= Duration::zero() || end <= start {
+            return Self::default();
+        }
+        for samples in series.values_mut() {
+            samples.sort_by_key(|s| s.timestamp);
+        }
+
+        let first_common = series.values().map(|samples| samples[0].timestamp).max().unwrap_or(start);
+        let mut grid = Vec::new();
+        let mut t = start;
+        while t <= end {
+            if t >= first_common {
+                grid.push(t);
+            }
+            t += interval;
+        }
+
+        let sampled: BTreeMap<&String, Vec<Sample>> = series
+            .iter()
+            .map(|(market, samples)| {
+                let mut next = 0;
+                let values = grid
+                    .iter()
+                    .map(|&at| {
+                        while next + 1 < samples.len() && samples[next + 1].timestamp <= at {
+                            next += 1;
+                        }
+                        samples[next]
+                    })
+                    .collect();
+                (market, values)
+            })
+            .collect();
+
+        let steps = grid.len().saturating_sub(1);
+        let mut asset_returns: HashMap<String, Vec<f64>> = HashMap::new();
+        let mut pnl = vec![0.0; steps];
+        let mut gross = vec![0.0; steps];
+        for (market, values) in &sampled {
+            let returns = asset_returns.entry((*market).clone()).or_default();
+            for (i, pair) in values.windows(2).enumerate() {
+                let (prev, curr) = (pair[0], pair[1]);
+                returns.push(if prev.price > 0.0 { curr.price / prev.price - 1.0 } else { 0.0 });
+                pnl[i] += prev.size * (curr.price - prev.price);
+                gross[i] += (prev.size * prev.price).abs();
+            }
+        }
+
+        let portfolio_returns = pnl
+            .iter()
+            .zip(&gross)
+            .map(|(pnl, gross)| if *gross > 0.0 { pnl / gross } else { 0.0 })
+            .collect();
+        let values = sampled
+            .iter()
+            .filter_map(|(market, values)| values.last().map(|s| ((*market).clone(), s.size * s.price)))
+            .collect();
+
+        Self {
+            timestamps: grid,
+            asset_returns,
+            portfolio_returns,
+            values,
+        }
+    }
+
+    /// Number of return observations per series
+    pub fn observations(&self) -> usize {
+        self.portfolio_returns.len()
+    }
+
+    /// Gross position value at the last grid point
+    pub fn portfolio_value(&self) -> f64 {
+        self.values.values().map(|v| v.abs()).sum()
+    }
+
+    /// Share of the gross position value per market at the last grid point
+    pub fn weights(&self) -> HashMap<String, f64> {
+        let total = self.portfolio_value();
+        self.values
+            .iter()
+            .map(|(market, value)| {
+                let weight = if total > 0.0 { value.abs() / total } else { 0.0 };
+                (market.clone(), weight)
+            })
+            .collect()
+    }
+
+    /// VaR engine seeded with the portfolio returns
+    pub fn var_engine(&self, config: VarConfig) -> VarEngine {
+        VarEngine::with_historical_returns(config, self.portfolio_returns.clone())
+    }
+
+    /// Report the market returns and position values to a risk engine for
+    /// [`RiskEngine::marginal_cvar`]
+    pub fn report_to(&self, engine: &RiskEngine) {
+        engine.set_return_history(self.asset_returns.clone(), self.values.clone());
+    }
+}
+
+/// Load return series from the position snapshots of `markets` over the
+/// trailing `lookback`, sampled every `interval`
+pub async fn load_position_history(
+    store: &ExecutionStore,
+    markets: &[&str],
+    lookback: Duration,
+    interval: Duration,
+) -> BotResult<ReturnHistory> {
+    let end = Utc::now();
+    let start = end - lookback;
+
+    let mut snapshots = Vec::new();
+    for market in markets {
+        snapshots.extend(store.query_positions(market, start, end).await?);
+    }
+
+    let history = ReturnHistory::from_snapshots(&snapshots, start, end, interval);
+    debug!(
+        "Loaded {} return observations for {} markets from {} position snapshots",
+        history.observations(),
+        history.asset_returns.len(),
+        snapshots.len()
+    );
+    Ok(history)
+}
+
+/// Load return series from the `labeler`'s namespaced [`MID_METRIC`] over
+/// the trailing `lookback`, sampled every `interval`
+///
+/// Portfolio returns weight each market by its current size in `sizes`,
+/// i.e. they answer how today's book would have fared over the window.
+pub async fn load_price_history(
+    metrics: &StorageEngine,
+    labeler: &MetricLabeler,
+    sizes: &HashMap<String, f64>,
+    lookback: Duration,
+    interval: Duration,
+) -> BotResult<ReturnHistory> {
+    let end = Utc::now();
+    let start = end - lookback;
+
+    let mut prices = HashMap::new();
+    for market in sizes.keys() {
+        let (name, labels) = labeler.market_metric(MID_METRIC, market);
+        let points = metrics.query_metrics(&name, start, end, Some(labels)).await?;
+        prices.insert(
+            market.clone(),
+            points.into_iter().map(|p| (p.timestamp, p.value)).collect::<Vec<_>>(),
+        );
+    }
+
+    let history = ReturnHistory::from_prices(&prices, sizes, start, end, interval);
+    debug!(
+        "Loaded {} return observations for {} markets from mid prices",
+        history.observations(),
+        history.asset_returns.len()
+    );
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + minute * 60, 0).unwrap()
+    }
+
+    fn snapshot(minute: i64, market: &str, size: f64, mark: Option<f64>) -> PositionSnapshot {
+        let mut snapshot = PositionSnapshot::new("polymarket", market, size, 0.5);
+        snapshot.timestamp = at(minute);
+        snapshot.mark_price = mark;
+        snapshot
+    }
+
+    #[test]
+    fn test_returns_from_snapshots() {
+        // Market b is first seen at minute 1, so the grid starts there
+        let snapshots = vec![
+            snapshot(0, "a", 100.0, Some(0.50)),
+            snapshot(1, "b", -50.0, Some(0.40)),
+            snapshot(2, "a", 100.0, Some(0.55)),
+            snapshot(2, "b", -50.0, None),
+            snapshot(3, "b", -50.0, Some(0.20)),
+        ];
+        let history = ReturnHistory::from_snapshots(&snapshots, at(0), at(3), Duration::minutes(1));
+
+        assert_eq!(history.timestamps, vec![at(1), at(2), at(3)]);
+        assert_eq!(history.observations(), 2);
+        let a = &history.asset_returns["a"];
+        assert!((a[0] - 0.1).abs() < 1e-9 && a[1] == 0.0);
+        assert_eq!(history.asset_returns["b"][0], 0.0);
+        assert!((history.asset_returns["b"][1] + 0.5).abs() < 1e-9);
+
+        // Step 1: a gains 100 * 0.05 on a gross of 50 + 20
+        assert!((history.portfolio_returns[0] - 5.0 / 70.0).abs() < 1e-9);
+        // Step 2: short b gains 50 * 0.20 on a gross of 55 + 20
+        assert!((history.portfolio_returns[1] - 10.0 / 75.0).abs() < 1e-9);
+
+        assert!((history.portfolio_value() - 65.0).abs() < 1e-9);
+        assert!((history.weights()["a"] - 55.0 / 65.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_returns_from_prices() {
+        let prices = HashMap::from([
+            ("a".to_string(), vec![(at(0), 0.50), (at(2), 0.60)]),
+            ("b".to_string(), vec![(at(0), 0.30)]),
+        ]);
+        let sizes = HashMap::from([("a".to_string(), 10.0)]);
+        let history = ReturnHistory::from_prices(&prices, &sizes, at(0), at(4), Duration::minutes(2));
+
+        assert_eq!(history.observations(), 2);
+        assert_eq!(history.asset_returns["b"], vec![0.0, 0.0]);
+        assert!((history.portfolio_returns[0] - 0.2).abs() < 1e-9);
+        assert_eq!(history.weights()["b"], 0.0);
+        let config = VarConfig {
+            min_observations: 2,
+            ..VarConfig::default()
+        };
+        assert!(history.var_engine(config).calculate_historical_var(6.0, 0.95, 1).is_ok());
+
+        let empty = ReturnHistory::from_prices(&HashMap::new(), &sizes, at(0), at(4), Duration::minutes(2));
+        assert_eq!(empty.observations(), 0);
+    }
+}
@@ -0,0 +1,192 @@
+//! Time-boxed trading sessions
+//!
+//! A [`SessionManager`] tracks when each market closes (or resolves) and
+//! when it reopens. Shortly before the close the bot stops trading the
+//! market: the close time is reported to the risk engines (so
+//! `TradingWindow` policies block new entries), subscribed strategies are
+//! unsubscribed, resting orders are cancelled and the position is
+//! optionally flattened. At `resume_at` the strategies are subscribed again.
+//!
+//! Close times come from the `session` config section and, with
+//! `use_end_dates`, from the scanner's market end dates.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Trading session of one market
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketSession {
+    /// Market identifier
+    pub market_id: String,
+    /// Close or resolution time
+    pub close_at: DateTime<Utc>,
+    /// How long before the close trading stops
+    pub stop_before: Duration,
+    /// When trading resumes (None = stays stopped, e.g. a resolved market)
+    pub resume_at: Option<DateTime<Utc>>,
+}
+
+impl MarketSession {
+    /// When trading in the market stops
+    pub fn stop_at(&self) -> DateTime<Utc> {
+        self.close_at - self.stop_before
+    }
+}
+
+/// Due session transition
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionAction {
+    /// Stop trading the market ahead of its close
+    Stop(MarketSession),
+    /// Resume trading after the market reopened
+    Resume(MarketSession),
+}
+
+/// Schedule of market sessions
+#[derive(Debug, Clone)]
+pub struct SessionManager {
+    default_stop_before: Duration,
+    /// Sessions that have not stopped yet
+    scheduled: HashMap<String, MarketSession>,
+    /// Stopped sessions with the strategies unsubscribed from them
+    stopped: HashMap<String, (MarketSession, Vec<String>)>,
+}
+
+impl SessionManager {
+    /// Create a manager stopping `default_stop_before` ahead of closes
+    /// scheduled with [`schedule_close`](Self::schedule_close)
+    pub fn new(default_stop_before: Duration) -> Self {
+        Self {
+            default_stop_before,
+            scheduled: HashMap::new(),
+            stopped: HashMap::new(),
+        }
+    }
+
+    /// Schedule (or reschedule) a market session
+    ///
+    /// Rescheduling the close of a stopped market updates its `resume_at`;
+    /// a session with a different close takes effect after it resumes.
+    pub fn schedule(&mut self, session: MarketSession) {
+        if let Some((stopped, _)) = self.stopped.get_mut(&session.market_id) {
+            if stopped.close_at == session.close_at {
+                stopped.resume_at = session.resume_at;
+                return;
+            }
+        }
+        self.scheduled.insert(session.market_id.clone(), session);
+    }
+
+    /// Schedule a close time with the default stop buffer, keeping an
+    /// already scheduled session for the same close
+    pub fn schedule_close(&mut self, market_id: &str, close_at: DateTime<Utc>) {
+        let known = self
+            .scheduled
+            .get(market_id)
+            .or_else(|| self.stopped.get(market_id).map(|(s, _)| s))
+            .is_some_and(|s| s.close_at == close_at);
+        if !known {
+            self.schedule(MarketSession {
+                market_id: market_id.to_string(),
+                close_at,
+                stop_before: self.default_stop_before,
+                resume_at: None,
+            });
+        }
+    }
+
+    /// Record the strategies unsubscribed when a market stopped
+    pub fn record_stopped(&mut self, session: MarketSession, strategies: Vec<String>) {
+        self.scheduled.remove(&session.market_id);
+        self.stopped.insert(session.market_id.clone(), (session, strategies));
+    }
+
+    /// Forget a stopped market, returning the strategies to resubscribe
+    pub fn take_stopped(&mut self, market_id: &str) -> Vec<String> {
+        self.stopped.remove(market_id).map(|(_, strategies)| strategies).unwrap_or_default()
+    }
+
+    /// Whether trading in a market is currently stopped
+    pub fn is_stopped(&self, market_id: &str) -> bool {
+        self.stopped.contains_key(market_id)
+    }
+
+    /// Markets currently stopped
+    pub fn stopped_markets(&self) -> Vec<String> {
+        self.stopped.keys().cloned().collect()
+    }
+
+    /// Transitions due at `now`, in time order
+    ///
+    /// Stops are reported once; the caller confirms them with
+    /// [`record_stopped`](Self::record_stopped). Resumes are reported until
+    /// the caller calls [`take_stopped`](Self::take_stopped).
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<SessionAction> {
+        let mut actions: Vec<(DateTime<Utc>, SessionAction)> = self
+            .stopped
+            .values()
+            .filter_map(|(session, _)| {
+                let resume_at = session.resume_at?;
+                (resume_at <= now).then(|| (resume_at, SessionAction::Resume(session.clone())))
+            })
+            .collect();
+        actions.extend(
+            self.scheduled
+                .values()
+                .filter(|session| session.stop_at() <= now && !self.stopped.contains_key(&session.market_id))
+                .map(|session| (session.stop_at(), SessionAction::Stop(session.clone()))),
+        );
+        actions.sort_by_key(|(at, _)| *at);
+        actions.into_iter().map(|(_, action)| action).collect()
+    }
+
+    /// Next stop or resume time
+    pub fn next_deadline(&self) -> Option<DateTime<Utc>> {
+        let stops = self
+            .scheduled
+            .values()
+            .filter(|session| !self.stopped.contains_key(&session.market_id))
+            .map(MarketSession::stop_at);
+        let resumes = self.stopped.values().filter_map(|(session, _)| session.resume_at);
+        stops.chain(resumes).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_schedule() {
+        let now = Utc::now();
+        let mut sessions = SessionManager::new(Duration::minutes(10));
+        sessions.schedule(MarketSession {
+            market_id: "m1".to_string(),
+            close_at: now + Duration::minutes(5),
+            stop_before: Duration::minutes(10),
+            resume_at: Some(now + Duration::hours(1)),
+        });
+        sessions.schedule_close("m2", now + Duration::hours(2));
+
+        assert_eq!(sessions.next_deadline(), Some(now - Duration::minutes(5)));
+        let due = sessions.due(now);
+        assert_eq!(due.len(), 1);
+        let SessionAction::Stop(session) = &due[0] else {
+            panic!("Expected a stop");
+        };
+        sessions.record_stopped(session.clone(), vec!["mm_1".to_string()]);
+        assert!(sessions.is_stopped("m1"));
+        assert!(sessions.due(now).is_empty());
+
+        // m1 resumes before m2 stops
+        assert_eq!(sessions.next_deadline(), Some(now + Duration::hours(1)));
+        let due = sessions.due(now + Duration::minutes(110));
+        assert!(matches!(&due[0], SessionAction::Resume(s) if s.market_id == "m1"));
+        assert!(matches!(&due[1], SessionAction::Stop(s) if s.market_id == "m2"));
+        assert_eq!(sessions.take_stopped("m1"), vec!["mm_1".to_string()]);
+
+        // Re-reporting the same close is a no-op
+        sessions.schedule_close("m2", now + Duration::hours(2));
+        assert_eq!(sessions.next_deadline(), Some(now + Duration::minutes(110)));
+    }
+}
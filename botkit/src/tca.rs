@@ -0,0 +1,180 @@
+//! Transaction cost analysis from stored executions
+//!
+//! Joins stored orders and fills with the `market.mid` metric recorded by
+//! the bot's market quality recorder and runs a [`TcaAnalyzer`] over them.
+//! Bots with `tca.report_interval_ms` set do this periodically and store
+//! each report; this module is also usable ad hoc:
+//!
+//! ```rust,no_run
+//! use ag_botkit::tca::generate_tca_report;
+//! use ag_botkit::MetricLabeler;
+//! use ag_botkit::storage::{ExecutionStore, OrderFilters, StorageConfig, StorageEngine, TcaConfig};
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn run() -> ag_botkit::BotResult<()> {
+//! let store = ExecutionStore::new(StorageConfig::default()).await?;
+//! let metrics = StorageEngine::new(StorageConfig::default()).await?;
+//! let end = Utc::now() - Duration::minutes(5);
+//! let report = generate_tca_report(
+//!     &store,
+//!     &metrics,
+//!     end - Duration::days(1),
+//!     end,
+//!     OrderFilters::default(),
+//!     &MetricLabeler::default(),
+//!     &TcaConfig::default(),
+//! )
+//! .await?;
+//!
+//! if let Some(mm) = report.group(Some("mm_1"), None) {
+//!     println!("fill ratio {:.2}, 60s net edge {:?} bps", mm.fill_ratio, mm.net_edge_bps(60));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`generate_attribution_report`] reads the same data to credit realized
+//! PnL, hit rate and edge to the signals orders were tagged with (see
+//! [`ag_strategies::SIGNAL_TAG`]), and [`generate_run_comparison`] to set
+//! strategy runs (e.g. two parameter versions) side by side.
+
+use crate::error::BotResult;
+use crate::metrics::MetricLabeler;
+use ag_storage::{
+    compare_runs, AttributionConfig, AttributionReport, ExecutionStore, Fill, MidPrices, Order, OrderFilters,
+    RunComparison, SignalAttributor, StorageEngine, TcaAnalyzer, TcaConfig, TcaReport, RUN_TAG,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeSet;
+use tracing::info;
+
+/// Metric holding the mid price per market (written by the bot's metric flush)
+pub const MID_METRIC: &str = "market.mid";
+
+/// Analyze orders placed between `start` and `end`
+///
+/// Mid prices are read up to `end` plus the longest markout horizon, from the
+/// `labeler`'s namespaced [`MID_METRIC`] carrying its standard labels (so only
+/// this bot's environment is used).
+pub async fn generate_tca_report(
+    store: &ExecutionStore,
+    metrics: &StorageEngine,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    filters: OrderFilters,
+    labeler: &MetricLabeler,
+    config: &TcaConfig,
+) -> BotResult<TcaReport> {
+    let (orders, fills) = load_executions(store, start, end, filters).await?;
+
+    let max_horizon = config.markout_horizons_secs.iter().copied().max().unwrap_or(0);
+    let mids_end = end + Duration::seconds(max_horizon as i64);
+    let mids = load_mids(metrics, &orders, start, mids_end, labeler).await?;
+
+    let report = TcaAnalyzer::new(config.clone()).analyze(&orders, &fills, &mids, start, end);
+    info!(
+        "TCA report {} - {}: {} orders, {} fills, fill ratio {:.2}",
+        start, end, report.overall.orders, report.overall.fills, report.overall.fill_ratio
+    );
+    Ok(report)
+}
+
+/// Attribute realized PnL of orders placed between `start` and `end` to
+/// their signals
+///
+/// Edges are measured against the `labeler`'s [`MID_METRIC`] at fill time.
+pub async fn generate_attribution_report(
+    store: &ExecutionStore,
+    metrics: &StorageEngine,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    filters: OrderFilters,
+    labeler: &MetricLabeler,
+    config: &AttributionConfig,
+) -> BotResult<AttributionReport> {
+    let (orders, fills) = load_executions(store, start, end, filters).await?;
+    let mids = load_mids(metrics, &orders, start, end, labeler).await?;
+
+    let report = SignalAttributor::new(config.clone()).attribute(&orders, &fills, &mids, start, end);
+    info!(
+        "Attribution report {} - {}: {} signals, net PnL {:.2}",
+        start,
+        end,
+        report.signals.len(),
+        report.total_net_pnl()
+    );
+    Ok(report)
+}
+
+/// Compare strategy runs by the fills of the orders tagged with their run ID
+///
+/// Runs are reported in the order of `run_ids` (unknown IDs are skipped);
+/// edges are measured against the `labeler`'s [`MID_METRIC`] at fill time.
+pub async fn generate_run_comparison(
+    store: &ExecutionStore,
+    metrics: &StorageEngine,
+    run_ids: &[String],
+    labeler: &MetricLabeler,
+) -> BotResult<RunComparison> {
+    let mut runs = store.get_strategy_runs(run_ids).await?;
+    runs.sort_by_key(|run| run_ids.iter().position(|id| *id == run.run_id));
+
+    let mut orders = Vec::new();
+    let mut fills = Vec::new();
+    for run in &runs {
+        for order in store.query_orders_by_tag(RUN_TAG, &run.run_id).await? {
+            fills.extend(store.query_fills_by_order(order.id).await?);
+            orders.push(order);
+        }
+    }
+
+    let now = Utc::now();
+    let start = runs.iter().map(|run| run.started_at).min().unwrap_or(now);
+    let end = runs.iter().map(|run| run.ended_at.unwrap_or(now)).max().unwrap_or(now);
+    let mids = load_mids(metrics, &orders, start, end, labeler).await?;
+
+    let comparison = compare_runs(runs, &orders, &fills, &mids);
+    info!(
+        "Run comparison: {} runs, {} orders, {} fills",
+        comparison.runs.len(),
+        orders.len(),
+        fills.len()
+    );
+    Ok(comparison)
+}
+
+/// Orders placed between `start` and `end` with their fills
+async fn load_executions(
+    store: &ExecutionStore,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    filters: OrderFilters,
+) -> BotResult<(Vec<Order>, Vec<Fill>)> {
+    let orders = store.query_orders(start, end, filters).await?;
+
+    let mut fills = Vec::new();
+    for order in &orders {
+        fills.extend(store.query_fills_by_order(order.id).await?);
+    }
+    Ok((orders, fills))
+}
+
+/// Mid prices of the orders' markets between `start` and `end`
+async fn load_mids(
+    metrics: &StorageEngine,
+    orders: &[Order],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    labeler: &MetricLabeler,
+) -> BotResult<MidPrices> {
+    let markets: BTreeSet<&str> = orders.iter().map(|o| o.market.as_str()).collect();
+
+    let mut mids = MidPrices::new();
+    for market in markets {
+        let (name, labels) = labeler.market_metric(MID_METRIC, market);
+        for point in metrics.query_metrics(&name, start, end, Some(labels)).await? {
+            mids.add(market, point.timestamp, point.value);
+        }
+    }
+    Ok(mids)
+}
@@ -0,0 +1,1371 @@
+//! Configuration validation
+//!
+//! Checks a bot YAML file against the typed schema and reports every
+//! problem with its location, instead of failing on the first serde error
+//! or silently accepting a typo:
+//!
+//! ```text
+//! bot.yaml:12:7: strategies[0].params.quote_sise: unknown parameter for market_maker (expected one of: target_spread_bps, quote_size, ...)
+//! bot.yaml:4:17: risk.policies[0].max_size: must be > 0 (got -5)
+//! ```
+//!
+//! Unknown fields in typed sections are rejected by serde
+//! (`deny_unknown_fields`); strategy params, value ranges and
+//! cross-field rules are checked here.
+
+use crate::config::{BotConfig, StrategySection};
+use crate::logging::parse_level;
+use crate::metrics::RESERVED_LABELS;
+use ag_exec::LiquidationStyle;
+use ag_risk::PolicyRule;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// Kind of configuration problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Field or parameter not in the schema (usually a typo)
+    UnknownField,
+    /// Required field or parameter not present
+    MissingRequired,
+    /// Value outside its allowed range
+    OutOfRange,
+    /// Value of the wrong type or otherwise malformed
+    Invalid,
+}
+
+/// A single configuration problem
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    /// Issue category
+    pub kind: IssueKind,
+    /// Dotted path to the offending value (e.g. `strategies[0].params.quote_size`)
+    pub path: String,
+    /// 1-based line number, when known
+    pub line: Option<usize>,
+    /// 1-based column number, when known
+    pub column: Option<usize>,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// All problems found in a configuration file
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Source file name, if validated from a file
+    pub file: Option<String>,
+    /// Issues in file order
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no issues were found
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<config>");
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", file)?;
+            if let Some(line) = issue.line {
+                write!(f, ":{}", line)?;
+                if let Some(column) = issue.column {
+                    write!(f, ":{}", column)?;
+                }
+            }
+            if issue.path.is_empty() {
+                write!(f, ": {}", issue.message)?;
+            } else {
+                write!(f, ": {}: {}", issue.path, issue.message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+/// Expected type of a strategy parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    /// Floating point number
+    Float,
+    /// Non-negative integer
+    Integer,
+    /// Boolean (`true` / `false`)
+    Bool,
+    /// Free-form string
+    String,
+}
+
+/// Schema for one strategy parameter
+#[derive(Debug, Clone)]
+pub struct ParamSpec {
+    /// Parameter name
+    pub name: &'static str,
+    /// Expected type
+    pub param_type: ParamType,
+    /// Whether the parameter must be present
+    pub required: bool,
+    /// Inclusive minimum (numeric types)
+    pub min: Option<f64>,
+    /// Inclusive maximum (numeric types)
+    pub max: Option<f64>,
+}
+
+impl ParamSpec {
+    /// Optional float parameter
+    pub const fn float(name: &'static str) -> Self {
+        Self {
+            name,
+            param_type: ParamType::Float,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Optional integer parameter
+    pub const fn integer(name: &'static str) -> Self {
+        Self {
+            name,
+            param_type: ParamType::Integer,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Optional boolean parameter
+    pub const fn boolean(name: &'static str) -> Self {
+        Self {
+            name,
+            param_type: ParamType::Bool,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Optional string parameter
+    pub const fn string(name: &'static str) -> Self {
+        Self {
+            name,
+            param_type: ParamType::String,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Mark the parameter as required
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set an inclusive range
+    pub const fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Set an inclusive minimum
+    pub const fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+}
+
+/// Parameters read by `SizingConfig::from_params`
+fn sizing_params() -> Vec<ParamSpec> {
+    vec![
+        ParamSpec::string("sizing_method"),
+        ParamSpec::float("sizing_kelly_fraction").range(0.0, 1.0),
+        ParamSpec::float("sizing_max_edge").min(0.0),
+        ParamSpec::float("sizing_target_volatility").min(0.0),
+        ParamSpec::float("sizing_volatility").min(0.0),
+        ParamSpec::float("sizing_max_drawdown").range(0.0, 1.0),
+        ParamSpec::float("sizing_max_notional_fraction").min(0.0),
+        ParamSpec::float("sizing_max_payout_fraction").min(0.0),
+        ParamSpec::float("sizing_payout_price_band").range(0.0, 0.5),
+        ParamSpec::float("sizing_max_marginal_cvar_fraction").min(0.0),
+        ParamSpec::float("sizing_cvar_confidence").range(0.5, 1.0),
+    ]
+}
+
+/// Validates bot configuration files
+///
+/// Parameter schemas for the built-in strategy kinds are pre-registered;
+/// add schemas for custom kinds with [`with_params`](Self::with_params).
+/// Strategies of kinds without a schema have their params passed through
+/// unchecked.
+pub struct ConfigValidator {
+    param_specs: HashMap<String, Vec<ParamSpec>>,
+}
+
+impl ConfigValidator {
+    /// Create a validator with the built-in strategy schemas
+    pub fn new() -> Self {
+        let mut param_specs = HashMap::new();
+        param_specs.insert(
+            "market_maker".to_string(),
+            vec![
+                ParamSpec::float("target_spread_bps").range(0.0, 10_000.0),
+                ParamSpec::float("quote_size").min(0.0),
+                ParamSpec::float("max_position").min(0.0),
+                ParamSpec::float("inventory_target"),
+                ParamSpec::float("skew_factor").min(0.0),
+                ParamSpec::integer("min_quote_interval_ms"),
+                ParamSpec::boolean("adaptive_requote_enabled"),
+                ParamSpec::integer("requote_min_interval_ms"),
+                ParamSpec::integer("requote_max_interval_ms"),
+                ParamSpec::boolean("toxicity_enabled"),
+                ParamSpec::float("toxicity_widen_bps"),
+                ParamSpec::float("toxicity_pull_bps"),
+                ParamSpec::float("toxicity_max_multiplier").min(1.0),
+            ]
+            .into_iter()
+            .chain(sizing_params())
+            .collect(),
+        );
+        param_specs.insert(
+            "cross_market_arb".to_string(),
+            vec![
+                ParamSpec::float("min_spread_bps").range(0.0, 10_000.0),
+                ParamSpec::float("size").min(0.0),
+                ParamSpec::float("max_position").min(0.0),
+            ],
+        );
+        param_specs.insert(
+            "pairs_trading".to_string(),
+            vec![
+                ParamSpec::integer("bar_secs").min(1.0),
+                ParamSpec::integer("lookback").min(2.0),
+                ParamSpec::float("entry_z").min(0.0),
+                ParamSpec::float("exit_z").min(0.0),
+                ParamSpec::integer("max_holding_secs").min(1.0),
+                ParamSpec::float("hedge_ratio"),
+                ParamSpec::float("size").min(0.0),
+            ]
+            .into_iter()
+            .chain(sizing_params())
+            .collect(),
+        );
+        Self { param_specs }
+    }
+
+    /// Register the parameter schema for a strategy kind
+    pub fn with_params(mut self, kind: impl Into<String>, specs: Vec<ParamSpec>) -> Self {
+        self.param_specs.insert(kind.into(), specs);
+        self
+    }
+
+    /// Validate a config file, returning the parsed config if it is clean
+    pub fn validate_file(&self, path: impl AsRef<Path>) -> Result<BotConfig, ValidationReport> {
+        let path = path.as_ref();
+        let file = Some(path.display().to_string());
+        let source = std::fs::read_to_string(path).map_err(|e| ValidationReport {
+            file: file.clone(),
+            issues: vec![ConfigIssue {
+                kind: IssueKind::Invalid,
+                path: String::new(),
+                line: None,
+                column: None,
+                message: format!("cannot read file: {}", e),
+            }],
+        })?;
+
+        self.validate_str(&source).map_err(|mut report| {
+            report.file = file;
+            report
+        })
+    }
+
+    /// Validate YAML source, returning the parsed config if it is clean
+    pub fn validate_str(&self, source: &str) -> Result<BotConfig, ValidationReport> {
+        let config: BotConfig = match serde_yaml::from_str(source) {
+            Ok(config) => config,
+            Err(e) => {
+                return Err(ValidationReport {
+                    file: None,
+                    issues: vec![serde_issue(&e)],
+                })
+            }
+        };
+
+        let lines = LineIndex::new(source);
+        let mut checker = Checker {
+            lines: &lines,
+            issues: Vec::new(),
+        };
+        self.check(&config, &mut checker);
+
+        if checker.issues.is_empty() {
+            Ok(config)
+        } else {
+            checker.issues.sort_by_key(|i| i.line.unwrap_or(usize::MAX));
+            Err(ValidationReport {
+                file: None,
+                issues: checker.issues,
+            })
+        }
+    }
+
+    fn check(&self, config: &BotConfig, c: &mut Checker<'_>) {
+        if config.name.trim().is_empty() {
+            c.invalid("name", "must not be empty".to_string());
+        }
+
+        // Risk policies
+        for (i, policy) in config.risk.policies.iter().enumerate() {
+            let base = format!("risk.policies[{}]", i);
+            match policy {
+                PolicyRule::PositionLimit { max_size, .. } => {
+                    c.positive(&format!("{}.max_size", base), *max_size);
+                }
+                PolicyRule::InventoryLimit { max_value_usd, .. } => {
+                    c.positive(&format!("{}.max_value_usd", base), *max_value_usd);
+                }
+                PolicyRule::ExposureAcrossVenues { max_exposure, .. } => {
+                    c.positive(&format!("{}.max_exposure", base), *max_exposure);
+                }
+                PolicyRule::CapitalAtRiskLimit { max_loss_usd, .. } | PolicyRule::DailyLossLimit { max_loss_usd, .. } => {
+                    c.positive(&format!("{}.max_loss_usd", base), *max_loss_usd);
+                }
+                PolicyRule::PayoutLimit {
+                    max_payout_usd,
+                    price_band,
+                    ..
+                } => {
+                    c.positive(&format!("{}.max_payout_usd", base), *max_payout_usd);
+                    if !(*price_band > 0.0 && *price_band <= 0.5) {
+                        c.out_of_range(
+                            &format!("{}.price_band", base),
+                            format!("must be in (0, 0.5] (got {})", price_band),
+                        );
+                    }
+                }
+                PolicyRule::HedgedGroupLimit {
+                    max_net_exposure,
+                    max_leg_position,
+                    ..
+                } => {
+                    if *max_net_exposure < 0.0 {
+                        c.out_of_range(
+                            &format!("{}.max_net_exposure", base),
+                            format!("must be >= 0 (got {})", max_net_exposure),
+                        );
+                    }
+                    if let Some(max_leg_position) = max_leg_position {
+                        c.positive(&format!("{}.max_leg_position", base), *max_leg_position);
+                    }
+                }
+                PolicyRule::KillSwitch { .. } | PolicyRule::TradingWindow { .. } => {}
+                PolicyRule::LossStreakCooldown { max_losses, window_secs, .. } => {
+                    if *max_losses == 0 {
+                        c.out_of_range(&format!("{}.max_losses", base), "must be > 0 (got 0)".to_string());
+                    }
+                    if *window_secs == 0 {
+                        c.out_of_range(&format!("{}.window_secs", base), "must be > 0 (got 0)".to_string());
+                    }
+                }
+                PolicyRule::OrderRateLimit { max_orders_per_minute, .. } => {
+                    if *max_orders_per_minute == 0 {
+                        c.out_of_range(
+                            &format!("{}.max_orders_per_minute", base),
+                            "must be > 0 (got 0)".to_string(),
+                        );
+                    }
+                }
+                PolicyRule::GreeksLimit {
+                    max_delta,
+                    max_vega,
+                    max_theta,
+                    ..
+                } => {
+                    let bounds = [("max_delta", max_delta), ("max_vega", max_vega), ("max_theta", max_theta)];
+                    if bounds.iter().all(|(_, bound)| bound.is_none()) {
+                        c.invalid(&base, "set at least one of max_delta, max_vega, max_theta".to_string());
+                    }
+                    for (field, bound) in bounds {
+                        if let Some(bound) = bound {
+                            c.positive(&format!("{}.{}", base, field), *bound);
+                        }
+                    }
+                }
+                PolicyRule::BankrollLimit { max_fraction, .. } => {
+                    if !(*max_fraction > 0.0 && *max_fraction <= 1.0) {
+                        c.out_of_range(
+                            &format!("{}.max_fraction", base),
+                            format!("must be in (0, 1] (got {})", max_fraction),
+                        );
+                    }
+                }
+            }
+        }
+
+        for reference in config.risk.unknown_policy_references() {
+            c.invalid("risk.evaluation", format!("no policy matches '{}'", reference));
+        }
+
+        // Exec venues
+        let mut venue_ids = HashSet::new();
+        for (i, venue) in config.exec.venues.iter().enumerate() {
+            let base = format!("exec.venues[{}]", i);
+            if !venue_ids.insert(venue.venue_id.as_str()) {
+                c.invalid(
+                    &format!("{}.venue_id", base),
+                    format!("duplicate venue '{}'", venue.venue_id),
+                );
+            }
+            if !venue.api_endpoint.starts_with("http://") && !venue.api_endpoint.starts_with("https://") {
+                c.invalid(
+                    &format!("{}.api_endpoint", base),
+                    format!("expected an http(s) URL (got '{}')", venue.api_endpoint),
+                );
+            }
+            if let Some(ws) = &venue.ws_endpoint {
+                if !ws.starts_with("ws://") && !ws.starts_with("wss://") {
+                    c.invalid(
+                        &format!("{}.ws_endpoint", base),
+                        format!("expected a ws(s) URL (got '{}')", ws),
+                    );
+                }
+            }
+            if venue.api_key_env.is_some() != venue.api_secret_env.is_some() {
+                c.missing(&base, "api_key_env and api_secret_env must be set together".to_string());
+            }
+            if venue.requests_per_second == Some(0) {
+                c.out_of_range(&format!("{}.requests_per_second", base), "must be > 0 (got 0)".to_string());
+            }
+            if venue.burst_size == Some(0) {
+                c.out_of_range(&format!("{}.burst_size", base), "must be > 0 (got 0)".to_string());
+            }
+            for (strategy, quota) in &venue.strategy_quotas {
+                let quota_base = format!("{}.strategy_quotas.{}", base, strategy);
+                if quota.requests_per_second == 0 {
+                    c.out_of_range(&format!("{}.requests_per_second", quota_base), "must be > 0 (got 0)".to_string());
+                }
+                if quota.burst_size == 0 {
+                    c.out_of_range(&format!("{}.burst_size", quota_base), "must be > 0 (got 0)".to_string());
+                }
+            }
+            if let Some(rounding) = &venue.rounding {
+                if rounding.tick_size <= 0.0 || (rounding.binary && rounding.tick_size >= 0.5) {
+                    let range = if rounding.binary { "(0, 0.5)" } else { "> 0" };
+                    c.out_of_range(
+                        &format!("{}.rounding.tick_size", base),
+                        format!("must be {} (got {})", range, rounding.tick_size),
+                    );
+                }
+                if rounding.lot_size <= 0.0 {
+                    c.out_of_range(
+                        &format!("{}.rounding.lot_size", base),
+                        format!("must be > 0 (got {})", rounding.lot_size),
+                    );
+                }
+            }
+        }
+
+        // Strategies
+        let mut strategy_ids = HashSet::new();
+        for (i, strategy) in config.strategies.iter().enumerate() {
+            let base = format!("strategies[{}]", i);
+            if strategy.id.trim().is_empty() {
+                c.invalid(&format!("{}.id", base), "must not be empty".to_string());
+            } else if !strategy_ids.insert(strategy.id.as_str()) {
+                c.invalid(&format!("{}.id", base), format!("duplicate strategy id '{}'", strategy.id));
+            }
+            if strategy.markets.is_empty() {
+                c.missing(&format!("{}.markets", base), "at least one market is required".to_string());
+            }
+            if matches!(strategy.kind.as_str(), "cross_market_arb" | "pairs_trading") && strategy.markets.len() != 2 {
+                c.invalid(
+                    &format!("{}.markets", base),
+                    format!("{} requires exactly 2 markets (got {})", strategy.kind, strategy.markets.len()),
+                );
+            }
+            if let Some(timer) = &strategy.timer {
+                if timer.interval_ms == 0 {
+                    c.out_of_range(&format!("{}.timer.interval_ms", base), "must be > 0 (got 0)".to_string());
+                } else if timer.jitter_ms > timer.interval_ms {
+                    c.out_of_range(
+                        &format!("{}.timer.jitter_ms", base),
+                        format!("must not exceed interval_ms ({} > {})", timer.jitter_ms, timer.interval_ms),
+                    );
+                }
+            }
+            if let Some(weight) = strategy.capital_weight {
+                if !(weight.is_finite() && weight >= 0.0) {
+                    c.out_of_range(&format!("{}.capital_weight", base), format!("must be >= 0 (got {})", weight));
+                }
+            }
+            if let Some(specs) = self.param_specs.get(&strategy.kind) {
+                check_params(&base, strategy, specs, c);
+            }
+        }
+
+        // Storage
+        #[cfg(feature = "storage")]
+        if let Some(storage) = &config.storage {
+            let db = &storage.database;
+            if db.host.trim().is_empty() {
+                c.invalid("storage.database.host", "must not be empty".to_string());
+            }
+            if db.port == 0 {
+                c.out_of_range("storage.database.port", "must be > 0 (got 0)".to_string());
+            }
+            if db.max_connections == 0 {
+                c.out_of_range("storage.database.max_connections", "must be > 0 (got 0)".to_string());
+            }
+            let ingestion = &storage.ingestion;
+            if ingestion.batch_size == 0 {
+                c.out_of_range("storage.ingestion.batch_size", "must be > 0 (got 0)".to_string());
+            }
+            if ingestion.flush_interval_ms == 0 {
+                c.out_of_range("storage.ingestion.flush_interval_ms", "must be > 0 (got 0)".to_string());
+            }
+            if ingestion.max_buffer_size < ingestion.batch_size {
+                c.out_of_range(
+                    "storage.ingestion.max_buffer_size",
+                    format!(
+                        "must be >= batch_size ({} < {})",
+                        ingestion.max_buffer_size, ingestion.batch_size
+                    ),
+                );
+            }
+            let retention = &storage.retention;
+            if retention.metrics_retention_days == 0 {
+                c.out_of_range("storage.retention.metrics_retention_days", "must be > 0 (got 0)".to_string());
+            }
+            if retention.execution_retention_days == 0 {
+                c.out_of_range("storage.retention.execution_retention_days", "must be > 0 (got 0)".to_string());
+            }
+            if retention.compression_after_days >= retention.metrics_retention_days {
+                c.out_of_range(
+                    "storage.retention.compression_after_days",
+                    format!(
+                        "must be < metrics_retention_days ({} >= {})",
+                        retention.compression_after_days, retention.metrics_retention_days
+                    ),
+                );
+            }
+            if storage.query.max_results == 0 {
+                c.out_of_range("storage.query.max_results", "must be > 0 (got 0)".to_string());
+            }
+        }
+
+        if config.metrics.flush_interval_ms == 0 {
+            c.out_of_range("metrics.flush_interval_ms", "must be > 0 (got 0)".to_string());
+        }
+        if let Some(namespace) = &config.metrics.namespace {
+            if namespace.trim().is_empty() || namespace.ends_with('.') {
+                c.invalid("metrics.namespace", format!("must be a non-empty prefix without a trailing '.' (got '{}')", namespace));
+            }
+        }
+        let mut label_keys: Vec<&String> = config.metrics.labels.keys().collect();
+        label_keys.sort();
+        for key in label_keys {
+            if RESERVED_LABELS.contains(&key.as_str()) {
+                c.invalid(&format!("metrics.labels.{}", key), format!("'{}' is set by the bot and cannot be overridden", key));
+            }
+        }
+
+        if config.market_quality.window_secs == 0 {
+            c.out_of_range("market_quality.window_secs", "must be > 0 (got 0)".to_string());
+        }
+        if config.market_quality.depth_levels == 0 {
+            c.out_of_range("market_quality.depth_levels", "must be > 0 (got 0)".to_string());
+        }
+        if config.sequence.max_time_gap_ms == Some(0) {
+            c.out_of_range("sequence.max_time_gap_ms", "must be > 0 (got 0)".to_string());
+        }
+
+        // TCA reports
+        if let Some(interval_ms) = config.tca.report_interval_ms {
+            if interval_ms == 0 {
+                c.out_of_range("tca.report_interval_ms", "must be > 0 (got 0)".to_string());
+            }
+            #[cfg(feature = "storage")]
+            let has_storage = config.storage.is_some();
+            #[cfg(not(feature = "storage"))]
+            let has_storage = false;
+            if !has_storage {
+                c.invalid("tca.report_interval_ms", "requires a storage section".to_string());
+            }
+        }
+
+        if config.recording.market_data {
+            #[cfg(feature = "storage")]
+            let has_storage = config.storage.is_some();
+            #[cfg(not(feature = "storage"))]
+            let has_storage = false;
+            if !has_storage {
+                c.invalid("recording.market_data", "requires a storage section".to_string());
+            }
+        }
+
+        if config.capital.poll_interval_ms == Some(0) {
+            c.out_of_range("capital.poll_interval_ms", "must be > 0 (got 0)".to_string());
+        }
+        if config.reconciliation.interval_ms == Some(0) {
+            c.out_of_range("reconciliation.interval_ms", "must be > 0 (got 0)".to_string());
+        }
+        if config.reconciliation.position_tolerance < 0.0 {
+            c.out_of_range(
+                "reconciliation.position_tolerance",
+                format!("must be >= 0 (got {})", config.reconciliation.position_tolerance),
+            );
+        }
+
+        // Kill-switch and liquidation
+        if config.kill_switch.check_interval_ms == 0 {
+            c.out_of_range("kill_switch.check_interval_ms", "must be > 0 (got 0)".to_string());
+        }
+        if config.liquidation.step_interval_ms == 0 {
+            c.out_of_range("liquidation.step_interval_ms", "must be > 0 (got 0)".to_string());
+        }
+        if let LiquidationStyle::LimitLadder { step_bps, .. } = config.liquidation.style {
+            if !(0.0..10_000.0).contains(&step_bps) {
+                c.out_of_range("liquidation.style.step_bps", format!("must be in [0, 10000) (got {})", step_bps));
+            }
+        }
+        if config.shutdown.verify && config.shutdown.max_attempts == 0 {
+            c.out_of_range("shutdown.max_attempts", "must be > 0 (got 0)".to_string());
+        }
+
+        // Perp hedging
+        if let Some(hedging) = &config.hedging {
+            if !venue_ids.contains(hedging.venue.as_str()) {
+                c.invalid("hedging.venue", format!("unknown venue '{}' (not in exec.venues)", hedging.venue.as_str()));
+            }
+            if !(0.0..=1.0).contains(&hedging.hedge_ratio) {
+                c.out_of_range("hedging.hedge_ratio", format!("must be in [0, 1] (got {})", hedging.hedge_ratio));
+            }
+            if hedging.rebalance_interval_secs == 0 {
+                c.out_of_range("hedging.rebalance_interval_secs", "must be > 0 (got 0)".to_string());
+            }
+            if hedging.min_trade_size < 0.0 {
+                c.out_of_range("hedging.min_trade_size", format!("must be >= 0 (got {})", hedging.min_trade_size));
+            }
+            let mut underlyings: Vec<&String> = hedging.perps.keys().collect();
+            underlyings.sort();
+            for underlying in underlyings {
+                let contract_size = hedging.perps[underlying].contract_size;
+                if contract_size <= 0.0 {
+                    c.out_of_range(
+                        &format!("hedging.perps.{}.contract_size", underlying),
+                        format!("must be > 0 (got {})", contract_size),
+                    );
+                }
+            }
+            for (i, market) in hedging.markets.iter().enumerate() {
+                let base = format!("hedging.markets[{}]", i);
+                if !hedging.perps.contains_key(&market.underlying) {
+                    c.invalid(
+                        &format!("{}.underlying", base),
+                        format!("no perp configured for '{}'", market.underlying),
+                    );
+                }
+                if market.strike <= 0.0 {
+                    c.out_of_range(&format!("{}.strike", base), format!("must be > 0 (got {})", market.strike));
+                }
+            }
+        }
+
+        // Order status polling
+        if let Some(polling) = &config.status_polling {
+            for (i, venue) in polling.venues.iter().enumerate() {
+                if !venue_ids.contains(venue.as_str()) {
+                    c.invalid(
+                        &format!("status_polling.venues[{}]", i),
+                        format!("unknown venue '{}' (not in exec.venues)", venue.as_str()),
+                    );
+                }
+            }
+            for (field, value) in [
+                ("tick_interval_ms", polling.tick_interval_ms),
+                ("min_interval_ms", polling.min_interval_ms),
+            ] {
+                if value == 0 {
+                    c.out_of_range(&format!("status_polling.{}", field), "must be > 0 (got 0)".to_string());
+                }
+            }
+            if polling.max_interval_ms < polling.min_interval_ms {
+                c.out_of_range(
+                    "status_polling.max_interval_ms",
+                    format!(
+                        "must be >= min_interval_ms ({}) (got {})",
+                        polling.min_interval_ms, polling.max_interval_ms
+                    ),
+                );
+            }
+            if polling.backoff_factor < 1.0 {
+                c.out_of_range(
+                    "status_polling.backoff_factor",
+                    format!("must be >= 1 (got {})", polling.backoff_factor),
+                );
+            }
+            if polling.near_fill_bps < 0.0 {
+                c.out_of_range("status_polling.near_fill_bps", format!("must be >= 0 (got {})", polling.near_fill_bps));
+            }
+            if !(polling.budget_fraction > 0.0 && polling.budget_fraction <= 1.0) {
+                c.out_of_range(
+                    "status_polling.budget_fraction",
+                    format!("must be in (0, 1] (got {})", polling.budget_fraction),
+                );
+            }
+        }
+
+        // Venue status monitoring
+        if let Some(status) = &config.venue_status {
+            if status.health_interval_ms == 0 {
+                c.out_of_range("venue_status.health_interval_ms", "must be > 0 (got 0)".to_string());
+            }
+            for (field, rate) in [
+                ("degraded_error_rate", status.degraded_error_rate),
+                ("down_error_rate", status.down_error_rate),
+            ] {
+                if !(rate > 0.0 && rate <= 1.0) {
+                    c.out_of_range(&format!("venue_status.{}", field), format!("must be in (0, 1] (got {})", rate));
+                }
+            }
+            if status.down_error_rate < status.degraded_error_rate {
+                c.out_of_range(
+                    "venue_status.down_error_rate",
+                    format!(
+                        "must be >= degraded_error_rate ({}) (got {})",
+                        status.degraded_error_rate, status.down_error_rate
+                    ),
+                );
+            }
+        }
+
+        // Position bootstrap
+        if let Some(bootstrap) = &config.position_bootstrap {
+            if bootstrap.fill_lookback_secs == 0 {
+                c.out_of_range("position_bootstrap.fill_lookback_secs", "must be > 0 (got 0)".to_string());
+            }
+        }
+
+        // Control API
+        if let Some(control) = &config.control {
+            if control.listen.parse::<std::net::SocketAddr>().is_err() {
+                c.invalid(
+                    "control.listen",
+                    format!("expected an IP address and port, e.g. 127.0.0.1:7070 (got '{}')", control.listen),
+                );
+            }
+        }
+
+        // Health
+        if config.health.check_interval_ms == 0 {
+            c.out_of_range("health.check_interval_ms", "must be > 0 (got 0)".to_string());
+        }
+        if config.health.stall_after_ms <= config.health.check_interval_ms {
+            c.out_of_range(
+                "health.stall_after_ms",
+                format!(
+                    "must be > health.check_interval_ms ({}) (got {})",
+                    config.health.check_interval_ms, config.health.stall_after_ms
+                ),
+            );
+        }
+        let mut subsystems: Vec<(&String, &u64)> = config.health.subsystems.iter().collect();
+        subsystems.sort();
+        for (subsystem, stall_after_ms) in subsystems {
+            if *stall_after_ms == 0 {
+                c.out_of_range(&format!("health.subsystems.{}", subsystem), "must be > 0 (got 0)".to_string());
+            }
+        }
+
+        // Per-strategy logs
+        let mut logged: Vec<&String> = config.logging.strategies.keys().collect();
+        logged.sort();
+        for strategy_id in logged {
+            let route = &config.logging.strategies[strategy_id];
+            let base = format!("logging.strategies.{}", strategy_id);
+            if !strategy_ids.contains(strategy_id.as_str()) {
+                c.invalid(&base, format!("unknown strategy '{}' (not in strategies)", strategy_id));
+            }
+            if route.file.trim().is_empty() {
+                c.invalid(&format!("{}.file", base), "must not be empty".to_string());
+            }
+            if parse_level(&route.level).is_err() {
+                c.invalid(
+                    &format!("{}.level", base),
+                    format!("expected trace, debug, info, warn, error or off (got '{}')", route.level),
+                );
+            }
+        }
+
+        // Trading sessions
+        let mut session_markets: Vec<&str> = Vec::new();
+        for (i, market) in config.session.markets.iter().enumerate() {
+            let base = format!("session.markets[{}]", i);
+            if session_markets.contains(&market.market_id.as_str()) {
+                c.invalid(&format!("{}.market_id", base), format!("duplicate session for market '{}'", market.market_id));
+            }
+            session_markets.push(&market.market_id);
+            if let Some(resume_at) = market.resume_at {
+                if resume_at <= market.close_at {
+                    c.out_of_range(
+                        &format!("{}.resume_at", base),
+                        format!("must be after close_at ({} <= {})", resume_at, market.close_at),
+                    );
+                }
+            }
+        }
+
+        // Risk state snapshots
+        if let Some(interval_ms) = config.risk_state.snapshot_interval_ms {
+            if interval_ms == 0 {
+                c.out_of_range("risk_state.snapshot_interval_ms", "must be > 0 (got 0)".to_string());
+            }
+            #[cfg(feature = "storage")]
+            let has_storage = config.storage.is_some();
+            #[cfg(not(feature = "storage"))]
+            let has_storage = false;
+            if !has_storage {
+                c.invalid(
+                    "risk_state.snapshot_interval_ms",
+                    "requires a storage section".to_string(),
+                );
+            }
+        }
+
+        // Position history snapshots
+        if let Some(interval_ms) = config.position_history.snapshot_interval_ms {
+            if interval_ms == 0 {
+                c.out_of_range("position_history.snapshot_interval_ms", "must be > 0 (got 0)".to_string());
+            }
+            #[cfg(feature = "storage")]
+            let has_storage = config.storage.is_some();
+            #[cfg(not(feature = "storage"))]
+            let has_storage = false;
+            if !has_storage {
+                c.invalid(
+                    "position_history.snapshot_interval_ms",
+                    "requires a storage section".to_string(),
+                );
+            }
+        }
+    }
+}
+
+impl Default for ConfigValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_params(base: &str, strategy: &StrategySection, specs: &[ParamSpec], c: &mut Checker<'_>) {
+    for (name, value) in &strategy.params {
+        let path = format!("{}.params.{}", base, name);
+        let Some(spec) = specs.iter().find(|s| s.name == name) else {
+            let expected: Vec<&str> = specs.iter().map(|s| s.name).collect();
+            c.push(
+                IssueKind::UnknownField,
+                &path,
+                format!(
+                    "unknown parameter for {} (expected one of: {})",
+                    strategy.kind,
+                    expected.join(", ")
+                ),
+            );
+            continue;
+        };
+
+        let number = match spec.param_type {
+            ParamType::Float => value.parse::<f64>().ok().filter(|v| v.is_finite()),
+            ParamType::Integer => value.parse::<u64>().ok().map(|v| v as f64),
+            ParamType::Bool => {
+                if value.parse::<bool>().is_err() {
+                    c.invalid(&path, format!("expected true or false (got '{}')", value));
+                }
+                continue;
+            }
+            ParamType::String => continue,
+        };
+
+        let Some(number) = number else {
+            let expected = match spec.param_type {
+                ParamType::Integer => "a non-negative integer",
+                _ => "a number",
+            };
+            c.invalid(&path, format!("expected {} (got '{}')", expected, value));
+            continue;
+        };
+
+        if let Some(min) = spec.min {
+            if number < min {
+                c.out_of_range(&path, format!("must be >= {} (got {})", min, value));
+            }
+        }
+        if let Some(max) = spec.max {
+            if number > max {
+                c.out_of_range(&path, format!("must be <= {} (got {})", max, value));
+            }
+        }
+    }
+
+    for spec in specs.iter().filter(|s| s.required) {
+        if !strategy.params.contains_key(spec.name) {
+            c.missing(
+                &format!("{}.params", base),
+                format!("missing required parameter '{}' for {}", spec.name, strategy.kind),
+            );
+        }
+    }
+}
+
+/// Convert a serde_yaml error into an issue with its location
+fn serde_issue(err: &serde_yaml::Error) -> ConfigIssue {
+    let message = err.to_string();
+    let kind = if message.contains("unknown field") || message.contains("unknown variant") {
+        IssueKind::UnknownField
+    } else if message.contains("missing field") {
+        IssueKind::MissingRequired
+    } else {
+        IssueKind::Invalid
+    };
+
+    // serde_yaml prefixes the message with the path ("strategies[0]: ...")
+    // and suffixes the location; keep them in their own fields.
+    let (path, message) = match message.split_once(": ") {
+        Some((path, rest)) if !path.contains(' ') => (path.to_string(), rest.to_string()),
+        _ => (String::new(), message),
+    };
+    let message = match message.find(" at line ") {
+        Some(idx) => message[..idx].to_string(),
+        None => message,
+    };
+
+    let location = err.location();
+    ConfigIssue {
+        kind,
+        path,
+        line: location.as_ref().map(|l| l.line()),
+        column: location.as_ref().map(|l| l.column()),
+        message,
+    }
+}
+
+/// Collects issues, resolving paths to source lines
+struct Checker<'a> {
+    lines: &'a LineIndex,
+    issues: Vec<ConfigIssue>,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, kind: IssueKind, path: &str, message: String) {
+        let location = self.lines.locate(path);
+        self.issues.push(ConfigIssue {
+            kind,
+            path: path.to_string(),
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            message,
+        });
+    }
+
+    fn invalid(&mut self, path: &str, message: String) {
+        self.push(IssueKind::Invalid, path, message);
+    }
+
+    fn missing(&mut self, path: &str, message: String) {
+        self.push(IssueKind::MissingRequired, path, message);
+    }
+
+    fn out_of_range(&mut self, path: &str, message: String) {
+        self.push(IssueKind::OutOfRange, path, message);
+    }
+
+    fn positive(&mut self, path: &str, value: f64) {
+        if !(value.is_finite() && value > 0.0) {
+            self.out_of_range(path, format!("must be > 0 (got {})", value));
+        }
+    }
+}
+
+/// Maps dotted YAML paths to (line, column) for block-style documents
+///
+/// Handles the subset of YAML used by config files: nested mappings and
+/// `- ` sequences. Flow collections (`[a, b]`, `{}`) are treated as leaves.
+struct LineIndex {
+    paths: HashMap<String, (usize, usize)>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let mut paths = HashMap::new();
+        // (indent, path, is_sequence_item)
+        let mut stack: Vec<(usize, String, bool)> = Vec::new();
+        let mut seq_counters: HashMap<String, usize> = HashMap::new();
+
+        for (line_no, raw) in source.lines().enumerate() {
+            let line_no = line_no + 1;
+            let trimmed = raw.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+                continue;
+            }
+            let mut indent = raw.len() - trimmed.len();
+            let mut content = trimmed;
+
+            if let Some(rest) = content.strip_prefix('-').filter(|r| r.is_empty() || r.starts_with(' ')) {
+                while stack.last().is_some_and(|(i, _, item)| *i > indent || (*i == indent && *item)) {
+                    stack.pop();
+                }
+                let parent = stack.last().map(|(_, p, _)| p.clone()).unwrap_or_default();
+                let counter = seq_counters.entry(parent.clone()).or_insert(0);
+                let path = format!("{}[{}]", parent, counter);
+                *counter += 1;
+
+                paths.entry(path.clone()).or_insert((line_no, indent + 1));
+                stack.push((indent, path, true));
+
+                let rest_trimmed = rest.trim_start();
+                indent += 1 + (rest.len() - rest_trimmed.len());
+                content = rest_trimmed;
+                if content.is_empty() {
+                    continue;
+                }
+            }
+
+            let Some(key) = mapping_key(content) else {
+                continue;
+            };
+            while stack.last().is_some_and(|(i, _, _)| *i >= indent) {
+                stack.pop();
+            }
+            let path = match stack.last() {
+                Some((_, parent, _)) => format!("{}.{}", parent, key),
+                None => key.to_string(),
+            };
+            paths.entry(path.clone()).or_insert((line_no, indent + 1));
+            stack.push((indent, path, false));
+        }
+
+        Self { paths }
+    }
+
+    /// Locate a path, falling back to its nearest known ancestor
+    fn locate(&self, path: &str) -> Option<(usize, usize)> {
+        let mut current = path;
+        loop {
+            if let Some(location) = self.paths.get(current) {
+                return Some(*location);
+            }
+            let cut = current.rfind(['.', '['])?;
+            current = &current[..cut];
+        }
+    }
+}
+
+/// Extract the key from a `key: value` or `key:` line
+fn mapping_key(content: &str) -> Option<&str> {
+    let end = content
+        .find(": ")
+        .or_else(|| content.strip_suffix(':').map(|s| s.len()))?;
+    let key = content[..end].trim();
+    let key = key.trim_matches(|c| c == '"' || c == '\'');
+    if key.is_empty() || key.starts_with(['[', '{']) {
+        None
+    } else {
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const VALID: &str = r#"
+name: test-bot
+risk:
+  policies:
+    - type: PositionLimit
+      max_size: 1000.0
+strategies:
+  - id: mm_1
+    kind: market_maker
+    markets: ["m1"]
+    params:
+      target_spread_bps: "25"
+"#;
+
+    #[test]
+    fn test_valid_config() {
+        let config = ConfigValidator::new().validate_str(VALID).unwrap();
+        assert_eq!(config.strategies.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_field_has_location() {
+        let yaml = VALID.replace("params:", "parms:");
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 1);
+        let issue = &report.issues[0];
+        assert_eq!(issue.kind, IssueKind::UnknownField);
+        assert!(issue.message.contains("parms"));
+        assert!(issue.line.is_some());
+
+        let yaml = VALID.replace("max_size", "max_sise");
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+        assert!(report.to_string().contains("max_sise"));
+    }
+
+    #[test]
+    fn test_params_range_and_typos() {
+        let yaml = VALID.replace(
+            r#"target_spread_bps: "25""#,
+            "target_spread_bps: \"-5\"\n      quote_sise: \"10\"\n      max_position: \"lots\"",
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let kinds: Vec<IssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(report.issues.len(), 3);
+        assert!(kinds.contains(&IssueKind::OutOfRange));
+        assert!(kinds.contains(&IssueKind::UnknownField));
+        assert!(kinds.contains(&IssueKind::Invalid));
+
+        let typo = report.issues.iter().find(|i| i.kind == IssueKind::UnknownField).unwrap();
+        assert_eq!(typo.path, "strategies[0].params.quote_sise");
+        assert_eq!(typo.line, Some(13));
+        assert!(report.to_string().contains("<config>:13:7: strategies[0].params.quote_sise"));
+    }
+
+    #[test]
+    fn test_missing_required_param() {
+        let validator = ConfigValidator::new().with_params(
+            "custom",
+            vec![ParamSpec::string("symbol").required()],
+        );
+        let yaml = VALID.replace("market_maker", "custom").replace("target_spread_bps", "symbol");
+        assert!(validator.validate_str(&yaml).is_ok());
+
+        let yaml = VALID.replace("market_maker", "custom").replace("target_spread_bps", "other");
+        let report = validator.validate_str(&yaml).unwrap_err();
+        assert!(report.issues.iter().any(|i| i.kind == IssueKind::MissingRequired));
+    }
+
+    #[test]
+    fn test_pairs_trading_markets_and_params() {
+        let yaml = VALID.replace("market_maker", "pairs_trading").replace("target_spread_bps", "lookback");
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "strategies[0].markets");
+
+        let yaml = yaml.replace(r#"["m1"]"#, r#"["m1", "m2"]"#);
+        assert!(ConfigValidator::new().validate_str(&yaml).is_ok());
+        let report = ConfigValidator::new().validate_str(&yaml.replace(r#""25""#, r#""1""#)).unwrap_err();
+        assert_eq!(report.issues[0].kind, IssueKind::OutOfRange);
+    }
+
+    #[test]
+    fn test_risk_snapshots_require_storage() {
+        let yaml = format!("{}risk_state:\n  snapshot_interval_ms: 60000\n", VALID);
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "risk_state.snapshot_interval_ms");
+        assert!(report.issues[0].message.contains("storage"));
+    }
+
+    #[test]
+    fn test_evaluation_references_known_policies() {
+        let yaml = VALID.replace(
+            "strategies:",
+            "  evaluation:\n    order: [PositionLimit]\n    disabled: [DailyLoss]\nstrategies:",
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "risk.evaluation");
+        assert!(report.issues[0].message.contains("DailyLoss"));
+    }
+
+    #[test]
+    fn test_greeks_limit_bounds() {
+        let yaml = VALID.replace("strategies:", "    - type: GreeksLimit\n    - type: GreeksLimit\n      max_vega: -5.0\nstrategies:");
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 2);
+        assert_eq!(report.issues[0].path, "risk.policies[1]");
+        assert_eq!(report.issues[1].path, "risk.policies[2].max_vega");
+    }
+
+    #[test]
+    fn test_position_history_requires_storage() {
+        let yaml = format!("{}position_history:\n  snapshot_interval_ms: 0\n", VALID);
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 2);
+        assert!(report.issues.iter().all(|i| i.path == "position_history.snapshot_interval_ms"));
+        assert!(report.issues.iter().any(|i| i.kind == IssueKind::OutOfRange));
+    }
+
+    #[test]
+    fn test_tca_reports_require_storage() {
+        let yaml = format!("{}tca:\n  report_interval_ms: 3600000\n", VALID);
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "tca.report_interval_ms");
+    }
+
+    #[test]
+    fn test_reserved_metric_labels() {
+        let yaml = format!("{}metrics:\n  namespace: agbot\n  labels:\n    environment: prod\n", VALID);
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "metrics.labels.environment");
+    }
+
+    #[test]
+    fn test_venue_rounding() {
+        let yaml = format!(
+            "{}exec:\n  venues:\n    - venue_id: polymarket\n      api_endpoint: https://clob.polymarket.com\n      rounding:\n        tick_size: 0.5\n        lot_size: 0.0\n        policy: bankers\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["exec.venues[0].rounding.tick_size", "exec.venues[0].rounding.lot_size"]);
+    }
+
+    #[test]
+    fn test_capital_settings() {
+        let yaml = format!(
+            "{}capital:\n  poll_interval_ms: 0\n",
+            VALID.replace("    params:", "    capital_weight: -1.0\n    params:")
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["strategies[0].capital_weight", "capital.poll_interval_ms"]);
+    }
+
+    #[test]
+    fn test_liquidation_settings() {
+        let yaml = format!(
+            "{}kill_switch:\n  flatten: true\n  check_interval_ms: 0\nliquidation:\n  style:\n    type: limit_ladder\n    step_bps: -5.0\n    max_steps: 3\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["kill_switch.check_interval_ms", "liquidation.style.step_bps"]);
+
+        let yaml = format!("{}shutdown:\n  max_attempts: 0\n", VALID);
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+        assert_eq!(report.issues[0].path, "shutdown.max_attempts");
+    }
+
+    #[test]
+    fn test_hedging_settings() {
+        let yaml = format!(
+            "{}hedging:\n  venue: binance\n  hedge_ratio: 1.5\n  perps:\n    BTC:\n      market: BTCUSDT\n      contract_size: 0.0\n  markets:\n    - market: m1\n      underlying: ETH\n      strike: 4000.0\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "hedging.venue",
+                "hedging.hedge_ratio",
+                "hedging.perps.BTC.contract_size",
+                "hedging.markets[0].underlying"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_polling_settings() {
+        let yaml = format!(
+            "{}status_polling:\n  venues: [kalshi]\n  min_interval_ms: 2000\n  max_interval_ms: 1000\n  budget_fraction: 0.0\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["status_polling.venues[0]", "status_polling.max_interval_ms", "status_polling.budget_fraction"]
+        );
+    }
+
+    #[test]
+    fn test_logging_settings() {
+        let yaml = format!(
+            "{}logging:\n  strategies:\n    mm_1:\n      file: \"\"\n      level: loud\n    ghost:\n      file: logs/ghost.log\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["logging.strategies.mm_1.file", "logging.strategies.mm_1.level", "logging.strategies.ghost"]);
+    }
+
+    #[test]
+    fn test_control_listen_address() {
+        let yaml = format!("{}control:\n  listen: localhost\n", VALID);
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["control.listen"]);
+
+        let yaml = format!("{}control:\n  listen: \"127.0.0.1:0\"\n", VALID);
+        assert!(ConfigValidator::new().validate_str(&yaml).is_ok());
+    }
+
+    #[test]
+    fn test_health_settings() {
+        let yaml = format!(
+            "{}health:\n  check_interval_ms: 5000\n  stall_after_ms: 5000\n  subsystems:\n    market_data: 0\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["health.stall_after_ms", "health.subsystems.market_data"]);
+
+        let config = BotConfig::from_yaml(&format!("{}health:\n  subsystems:\n    market_data: 120000\n", VALID)).unwrap();
+        let health = &config.health;
+        assert_eq!(health.stall_after("market_data", None), Duration::from_secs(120));
+        assert_eq!(health.stall_after("event_loop", Some(Duration::from_secs(1))), Duration::from_secs(30));
+        assert_eq!(health.stall_after("metrics_flush", Some(Duration::from_secs(60))), Duration::from_secs(180));
+    }
+
+    #[test]
+    fn test_session_settings() {
+        let yaml = format!(
+            "{}session:\n  markets:\n    - market_id: m1\n      close_at: 2026-11-03T21:00:00Z\n      resume_at: 2026-11-03T20:00:00Z\n    - market_id: m1\n      close_at: 2026-11-04T21:00:00Z\n",
+            VALID
+        );
+        let report = ConfigValidator::new().validate_str(&yaml).unwrap_err();
+
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["session.markets[0].resume_at", "session.markets[1].market_id"]);
+    }
+
+    #[test]
+    fn test_line_index_sequences() {
+        let index = LineIndex::new(VALID);
+        assert_eq!(index.locate("risk.policies[0].max_size"), Some((6, 7)));
+        assert_eq!(index.locate("strategies[0].kind"), Some((9, 5)));
+        assert_eq!(index.locate("strategies[0].markets[3]"), Some((10, 5)));
+    }
+}
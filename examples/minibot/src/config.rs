@@ -1,7 +1,9 @@
+use ag_exec::{BackoffConfig, FeedConfig};
 use anyhow::Result;
 use serde::Deserialize;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -14,12 +16,41 @@ pub struct Config {
 pub struct RtdsConfig {
     pub endpoint: String,
     pub ping_interval_sec: u64,
-    #[allow(dead_code)]
+    /// Initial reconnect delay; doubles per failed attempt
     pub reconnect_delay_sec: u64,
+    /// Upper bound on the reconnect delay
+    #[serde(default = "default_max_reconnect_delay_sec")]
+    pub max_reconnect_delay_sec: u64,
+    /// Reconnect when nothing (not even a pong) arrives for this long
+    #[serde(default = "default_stall_timeout_sec")]
+    pub stall_timeout_sec: u64,
     #[allow(dead_code)]
     pub subscribe_topics: Vec<MarketSubscription>,
 }
 
+impl RtdsConfig {
+    /// Supervised feed settings
+    pub fn feed_config(&self) -> FeedConfig {
+        let mut config = FeedConfig::new(self.endpoint.clone()).with_ping_message(r#"{"type":"ping"}"#);
+        config.ping_interval = Duration::from_secs(self.ping_interval_sec);
+        config.stall_timeout = Duration::from_secs(self.stall_timeout_sec);
+        config.backoff = BackoffConfig {
+            initial: Duration::from_secs(self.reconnect_delay_sec),
+            max: Duration::from_secs(self.max_reconnect_delay_sec),
+            multiplier: 2.0,
+        };
+        config
+    }
+}
+
+fn default_max_reconnect_delay_sec() -> u64 {
+    30
+}
+
+fn default_stall_timeout_sec() -> u64 {
+    15
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MarketSubscription {
     #[allow(dead_code)]
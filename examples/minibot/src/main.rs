@@ -1,13 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use futures_util::{SinkExt, StreamExt};
+use ag_exec::{ConnectionState, FeedSupervisor};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tokio::time::{interval, Instant};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 mod config;
 mod metrics;
@@ -95,18 +94,8 @@ async fn main() -> Result<()> {
     let metric_sender = Arc::new(metric_sender);
     info!("Connected to monitor");
 
-    // Connect to RTDS
-    info!("Connecting to Polymarket RTDS at {}", config.rtds.endpoint);
-    let (ws_stream, _) = connect_async(&config.rtds.endpoint).await?;
-    info!("Connected to RTDS");
-
-    let (write, mut read) = ws_stream.split();
-    let write = Arc::new(tokio::sync::Mutex::new(write));
-
-    // Subscribe to RTDS topics
-    let mut w = write.lock().await;
-
-    // Subscribe to crypto_prices (fully supported topic)
+    // Connect to RTDS under supervision: reconnect with backoff, resubscribe
+    // and detect silent stalls
     let crypto_sub = rtds::SubscriptionMessage {
         action: "subscribe".to_string(),
         subscriptions: vec![
@@ -118,10 +107,6 @@ async fn main() -> Result<()> {
         ],
     };
 
-    let json = serde_json::to_string(&crypto_sub)?;
-    info!("Subscribing to crypto_prices");
-    w.send(Message::Text(json)).await?;
-
     // Also try subscribing to activity/trades (unsupported but may work)
     let activity_sub = rtds::SubscriptionMessage {
         action: "subscribe".to_string(),
@@ -134,23 +119,30 @@ async fn main() -> Result<()> {
         ],
     };
 
-    let json = serde_json::to_string(&activity_sub)?;
-    info!("Subscribing to activity/trades");
-    w.send(Message::Text(json)).await?;
-
-    drop(w); // Release lock
+    info!("Connecting to Polymarket RTDS at {}", config.rtds.endpoint);
+    let mut feed = FeedSupervisor::new(config.rtds.feed_config())
+        .subscribe("crypto_prices", serde_json::to_string(&crypto_sub)?)
+        .subscribe("activity/trades", serde_json::to_string(&activity_sub)?)
+        .spawn(1024);
 
-    // Spawn ping task
-    let write_clone = Arc::clone(&write);
-    let ping_interval = config.rtds.ping_interval_sec;
+    // Report connection state changes
+    let mut connection = feed.watch_state();
+    let metric_sender_clone = Arc::clone(&metric_sender);
     tokio::spawn(async move {
-        let mut interval = interval(Duration::from_secs(ping_interval));
-        loop {
-            interval.tick().await;
-            let mut w = write_clone.lock().await;
-            if let Err(e) = w.send(Message::Text(r#"{"type":"ping"}"#.to_string())).await {
-                error!("Failed to send ping: {}", e);
-                break;
+        while connection.changed().await.is_ok() {
+            let state = connection.borrow_and_update().clone();
+            match &state {
+                ConnectionState::Connected => info!("RTDS connected"),
+                ConnectionState::Closed => warn!("RTDS feed closed"),
+                other => warn!("RTDS connection {:?}", other),
+            }
+            if let Err(e) = metric_sender_clone.send(
+                "polymarket.rtds.connected",
+                MetricType::Gauge,
+                if state.is_connected() { 1.0 } else { 0.0 },
+                std::collections::HashMap::new(),
+            ).await {
+                warn!("Failed to send connection metric: {}", e);
             }
         }
     });
@@ -190,29 +182,10 @@ async fn main() -> Result<()> {
         }
     });
 
-    // Main message loop
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Err(e) = handle_message(&text, &state, &metric_sender, &config).await {
-                    warn!("Failed to handle message: {}", e);
-                }
-            }
-            Ok(Message::Ping(_)) => {
-                // Handled automatically by tungstenite
-            }
-            Ok(Message::Pong(_)) => {
-                // Response to our ping
-            }
-            Ok(Message::Close(_)) => {
-                info!("RTDS connection closed");
-                break;
-            }
-            Err(e) => {
-                error!("WebSocket error: {}", e);
-                break;
-            }
-            _ => {}
+    // Main message loop; ends when the supervisor gives up
+    while let Some(text) = feed.next_message().await {
+        if let Err(e) = handle_message(&text, &state, &metric_sender, &config).await {
+            warn!("Failed to handle message: {}", e);
         }
     }
 
@@ -314,7 +287,7 @@ async fn handle_message(
                 ).await?;
 
                 if !decision.allowed {
-                    warn!("Risk check BLOCKED: {:?}", decision.violated_policies);
+                    warn!("Risk check BLOCKED: {:?}", decision.messages());
                 }
             }
         }
@@ -1,39 +1,20 @@
 use anyhow::Result;
-use futures_util::SinkExt;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::sync::Mutex;
-use tokio_tungstenite::{connect_async, tungstenite::Message, WebSocketStream, MaybeTlsStream};
-use tokio::net::TcpStream;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum MetricType {
-    Counter,
-    Gauge,
-    Histogram,
-}
+use ag_exec::monitor::{MetricMessage, MonitorClient};
 
-#[derive(Debug, Serialize)]
-pub struct MetricMessage {
-    pub timestamp: u64,
-    pub metric_type: MetricType,
-    pub metric_name: String,
-    pub value: f64,
-    pub labels: HashMap<String, String>,
-}
+pub use ag_exec::monitor::MetricType;
 
+/// Metrics connection to the monitor, speaking the versioned protocol in
+/// `ag_exec::monitor`
 pub struct MetricSender {
-    ws: Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    client: MonitorClient,
 }
 
 impl MetricSender {
     pub async fn connect(endpoint: &str) -> Result<Self> {
-        let (ws_stream, _) = connect_async(endpoint).await?;
-        Ok(Self {
-            ws: Mutex::new(ws_stream),
-        })
+        let client = MonitorClient::connect(endpoint, concat!("minibot/", env!("CARGO_PKG_VERSION"))).await?;
+        Ok(Self { client })
     }
 
     pub async fn send(
@@ -43,22 +24,10 @@ impl MetricSender {
         value: f64,
         labels: HashMap<String, String>,
     ) -> Result<()> {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)?
-            .as_millis() as u64;
-
-        let msg = MetricMessage {
-            timestamp,
-            metric_type,
-            metric_name: metric_name.to_string(),
-            value,
-            labels,
-        };
-
-        let json = serde_json::to_string(&msg)?;
+        let mut msg = MetricMessage::new(metric_name, metric_type, value);
+        msg.labels = labels;
 
-        let mut ws = self.ws.lock().await;
-        ws.send(Message::Text(json)).await?;
+        self.client.send(msg).await?;
 
         Ok(())
     }
@@ -149,10 +149,10 @@ policies:
             eprintln!("   ✗ Order submission failed: {}\n", e);
 
             // Check if it was a risk rejection
-            if let ag_exec::ExecError::RiskRejected { policies } = e {
+            if let ag_exec::ExecError::RiskRejected { violations } = e {
                 eprintln!("   Violated risk policies:");
-                for policy in policies {
-                    eprintln!("   - {}", policy);
+                for violation in violations {
+                    eprintln!("   - {}", violation);
                 }
             }
         }
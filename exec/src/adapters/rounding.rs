@@ -0,0 +1,300 @@
+//! Rounding of prices and sizes to venue tick and lot sizes
+//!
+//! Strategies work in continuous prices and sizes; venues accept multiples
+//! of a tick and a lot. Which way an off-grid value is rounded matters: a
+//! buy rounded up pays more than intended, and on a binary market a price of
+//! 0.0004 rounded to the nearest cent is 0, which no venue accepts. The
+//! [`RoundingPolicy`] makes the direction explicit:
+//!
+//! - [`RoundingPolicy::Passive`] rounds buy prices down and sell prices up
+//!   (never more aggressive than requested) and sizes down (never larger)
+//! - [`RoundingPolicy::Bankers`] rounds both to the nearest step, ties to
+//!   the even step
+//!
+//! The execution engine applies an adapter's [`VenueRounding`] just before
+//! the order reaches the venue and records the result on the order as
+//! [`AppliedRounding`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExecError, ExecResult};
+use crate::order::{Order, Side};
+
+/// Tolerance for float noise when comparing against the grid
+const GRID_EPSILON: f64 = 1e-9;
+
+/// Direction off-grid prices and sizes are rounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingPolicy {
+    /// Buys down, sells up; sizes down
+    #[default]
+    Passive,
+    /// Nearest step, ties to even
+    Bankers,
+}
+
+fn default_tick_size() -> f64 {
+    0.01
+}
+
+fn default_lot_size() -> f64 {
+    0.01
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Tick and lot sizes of a venue and how to round to them
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VenueRounding {
+    /// Price increment
+    #[serde(default = "default_tick_size")]
+    pub tick_size: f64,
+
+    /// Size increment
+    #[serde(default = "default_lot_size")]
+    pub lot_size: f64,
+
+    /// Rounding direction
+    #[serde(default)]
+    pub policy: RoundingPolicy,
+
+    /// Binary market: rounded prices must lie in `[tick_size, 1 - tick_size]`
+    #[serde(default = "default_true")]
+    pub binary: bool,
+}
+
+impl Default for VenueRounding {
+    fn default() -> Self {
+        Self {
+            tick_size: default_tick_size(),
+            lot_size: default_lot_size(),
+            policy: RoundingPolicy::default(),
+            binary: true,
+        }
+    }
+}
+
+/// Rounding applied to an order at the venue boundary
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AppliedRounding {
+    pub policy: RoundingPolicy,
+    /// Price before rounding
+    pub requested_price: Option<f64>,
+    /// Price sent to the venue
+    pub price: Option<f64>,
+    /// Size before rounding
+    pub requested_size: f64,
+    /// Size sent to the venue
+    pub size: f64,
+}
+
+impl AppliedRounding {
+    /// Whether rounding changed the price or size
+    pub fn changed(&self) -> bool {
+        self.requested_price != self.price || self.requested_size != self.size
+    }
+}
+
+impl VenueRounding {
+    /// Round a limit price for an order side
+    pub fn round_price(&self, price: f64, side: Side) -> f64 {
+        let direction = match (self.policy, side) {
+            (RoundingPolicy::Passive, Side::Buy) => Direction::Down,
+            (RoundingPolicy::Passive, Side::Sell) => Direction::Up,
+            (RoundingPolicy::Bankers, _) => Direction::HalfEven,
+        };
+        round_to_step(price, self.tick_size, direction)
+    }
+
+    /// Round an order size
+    pub fn round_size(&self, size: f64) -> f64 {
+        let direction = match self.policy {
+            RoundingPolicy::Passive => Direction::Down,
+            RoundingPolicy::Bankers => Direction::HalfEven,
+        };
+        round_to_step(size, self.lot_size, direction)
+    }
+
+    /// Round an order's price and size in place and record the rounding
+    ///
+    /// Fails if the size rounds to zero or, on a binary market, the price
+    /// rounds outside `[tick_size, 1 - tick_size]`; the order is unchanged
+    /// in that case.
+    pub fn apply(&self, order: &mut Order) -> ExecResult<AppliedRounding> {
+        let price = order.price.map(|p| self.round_price(p, order.side));
+        let size = self.round_size(order.size);
+
+        if size <= 0.0 {
+            return Err(ExecError::ValidationError(format!(
+                "Size {} rounds to zero at lot size {}",
+                order.size, self.lot_size
+            )));
+        }
+        if let (true, Some(rounded)) = (self.binary, price) {
+            let min = self.tick_size;
+            let max = round_to_step(1.0 - self.tick_size, self.tick_size, Direction::HalfEven);
+            if rounded < min - GRID_EPSILON || rounded > max + GRID_EPSILON {
+                return Err(ExecError::ValidationError(format!(
+                    "{} price {} rounds to {} outside [{}, {}] at tick size {}",
+                    order.side,
+                    order.price.unwrap_or_default(),
+                    rounded,
+                    min,
+                    max,
+                    self.tick_size
+                )));
+            }
+        }
+
+        let applied = AppliedRounding {
+            policy: self.policy,
+            requested_price: order.price,
+            price,
+            requested_size: order.size,
+            size,
+        };
+        order.price = price;
+        order.size = size;
+        order.rounding = Some(applied);
+        Ok(applied)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Down,
+    Up,
+    HalfEven,
+}
+
+/// Round `value` to a multiple of `step`, ignoring float noise (0.29 / 0.01
+/// is 28.999999999999996, which is still on the grid)
+fn round_to_step(value: f64, step: f64, direction: Direction) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    let steps = value / step;
+    let nearest = steps.round();
+    let rounded = if (steps - nearest).abs() < GRID_EPSILON {
+        nearest
+    } else {
+        match direction {
+            Direction::Down => steps.floor(),
+            Direction::Up => steps.ceil(),
+            Direction::HalfEven => {
+                let floor = steps.floor();
+                if (steps - floor - 0.5).abs() < GRID_EPSILON {
+                    if floor % 2.0 == 0.0 {
+                        floor
+                    } else {
+                        floor + 1.0
+                    }
+                } else {
+                    nearest
+                }
+            }
+        }
+    };
+
+    // Strip float noise from the product (29 * 0.01 = 0.29000000000000004)
+    let scale = 10f64.powi(step_decimals(step));
+    (rounded * step * scale).round() / scale
+}
+
+/// Decimal places of a step size (0.01 -> 2, 0.001 -> 3, 5.0 -> 0)
+fn step_decimals(step: f64) -> i32 {
+    (0..12)
+        .find(|&d| {
+            let scaled = step * 10f64.powi(d);
+            (scaled - scaled.round()).abs() < GRID_EPSILON
+        })
+        .unwrap_or(12)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{MarketId, OrderType, TimeInForce, VenueId};
+
+    fn order(side: Side, price: f64, size: f64) -> Order {
+        Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            side,
+            OrderType::Limit,
+            Some(price),
+            size,
+            TimeInForce::GTC,
+            "client-round".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_passive_rounding() {
+        let rounding = VenueRounding::default();
+
+        // Buys never pay more, sells never receive less
+        assert_eq!(rounding.round_price(0.527, Side::Buy), 0.52);
+        assert_eq!(rounding.round_price(0.521, Side::Sell), 0.53);
+        // On-grid values survive float noise untouched
+        assert_eq!(rounding.round_price(0.29, Side::Buy), 0.29);
+        assert_eq!(rounding.round_price(0.1 + 0.2, Side::Sell), 0.3);
+        assert_eq!(rounding.round_size(10.019), 10.01);
+
+        let mut buy = order(Side::Buy, 0.527, 10.019);
+        let applied = rounding.apply(&mut buy).unwrap();
+        assert!(applied.changed());
+        assert_eq!((buy.price, buy.size), (Some(0.52), 10.01));
+        assert_eq!(buy.rounding.unwrap().requested_price, Some(0.527));
+    }
+
+    #[test]
+    fn test_bankers_rounding() {
+        let rounding = VenueRounding {
+            policy: RoundingPolicy::Bankers,
+            ..VenueRounding::default()
+        };
+
+        // Ties go to the even tick, regardless of side
+        assert_eq!(rounding.round_price(0.525, Side::Buy), 0.52);
+        assert_eq!(rounding.round_price(0.535, Side::Sell), 0.54);
+        assert_eq!(rounding.round_price(0.526, Side::Buy), 0.53);
+        assert_eq!(rounding.round_size(2.5), 2.5);
+        assert_eq!(VenueRounding { lot_size: 1.0, ..rounding }.round_size(2.5), 2.0);
+    }
+
+    #[test]
+    fn test_binary_bounds() {
+        let rounding = VenueRounding::default();
+
+        // Near 0: a buy rounds down to 0 and is refused, a sell rounds up to a tick
+        let mut buy = order(Side::Buy, 0.004, 10.0);
+        assert!(rounding.apply(&mut buy).is_err());
+        assert_eq!((buy.price, buy.rounding), (Some(0.004), None));
+        let mut sell = order(Side::Sell, 0.004, 10.0);
+        assert_eq!(rounding.apply(&mut sell).unwrap().price, Some(0.01));
+
+        // Near 1: a sell rounds up to 1 and is refused, a buy rounds down to 0.99
+        let mut sell = order(Side::Sell, 0.996, 10.0);
+        assert!(rounding.apply(&mut sell).is_err());
+        let mut buy = order(Side::Buy, 0.996, 10.0);
+        assert_eq!(rounding.apply(&mut buy).unwrap().price, Some(0.99));
+
+        // Exactly one tick from either end is valid
+        assert!(rounding.apply(&mut order(Side::Buy, 0.01, 10.0)).is_ok());
+        assert!(rounding.apply(&mut order(Side::Sell, 0.99, 10.0)).is_ok());
+
+        // Sizes that round to zero are refused
+        assert!(rounding.apply(&mut order(Side::Buy, 0.5, 0.009)).is_err());
+
+        // Non-binary venues accept any positive grid price
+        let perp = VenueRounding { tick_size: 0.5, lot_size: 0.001, binary: false, ..rounding };
+        let mut buy = order(Side::Buy, 100_000.3, 0.0015);
+        perp.apply(&mut buy).unwrap();
+        assert_eq!((buy.price, buy.size), (Some(100_000.0), 0.001));
+    }
+}
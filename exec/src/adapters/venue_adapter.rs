@@ -4,9 +4,13 @@
 //! must implement. It provides a uniform interface for interacting with different exchanges.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
-use crate::error::ExecResult;
-use crate::order::{CancelAck, Order, OrderAck, OrderId, OrderStatus, VenueId};
+use crate::adapters::rounding::VenueRounding;
+use crate::capital::VenueBalance;
+use crate::error::{ExecError, ExecResult};
+use crate::order::{CancelAck, Fill, Order, OrderAck, OrderId, OrderStatus, TimeInForce, VenueId};
 
 /// Venue adapter trait
 ///
@@ -17,6 +21,21 @@ pub trait VenueAdapter: Send + Sync {
     /// Get the venue identifier
     fn venue_id(&self) -> VenueId;
 
+    /// Whether the venue enforces a time in force natively for limit orders
+    ///
+    /// Unsupported IOC/FOK limit orders are emulated by the execution
+    /// engine. Defaults to GTC only.
+    fn supports_time_in_force(&self, tif: TimeInForce) -> bool {
+        tif == TimeInForce::GTC
+    }
+
+    /// Tick/lot sizes the engine rounds orders to before `place_order`
+    ///
+    /// Defaults to none: prices and sizes are sent as submitted.
+    fn rounding(&self) -> Option<VenueRounding> {
+        None
+    }
+
     /// Place an order on the venue
     ///
     /// # Arguments
@@ -78,6 +97,44 @@ pub trait VenueAdapter: Send + Sync {
     /// * `Ok(false)` - Venue is unhealthy
     /// * `Err(ExecError)` - Health check failed
     async fn health_check(&mut self) -> ExecResult<bool>;
+
+    /// Get the account's cash balance and locked collateral on the venue
+    ///
+    /// # Returns
+    /// * `Ok(VenueBalance)` - Current balance
+    /// * `Err(ExecError::VenueNotSupported)` - The adapter does not report balances (default)
+    async fn get_balance(&mut self) -> ExecResult<VenueBalance> {
+        Err(ExecError::VenueNotSupported(format!(
+            "{} does not report balances",
+            self.venue_id()
+        )))
+    }
+
+    /// Get the account's net position per market, as the venue reports it
+    ///
+    /// Markets are keyed by the venue's own symbol.
+    ///
+    /// # Returns
+    /// * `Ok(HashMap<String, f64>)` - Net position per venue symbol (positive = long)
+    /// * `Err(ExecError::VenueNotSupported)` - The adapter does not report positions (default)
+    async fn get_positions(&mut self) -> ExecResult<HashMap<String, f64>> {
+        Err(ExecError::VenueNotSupported(format!(
+            "{} does not report positions",
+            self.venue_id()
+        )))
+    }
+
+    /// Get the account's fills since a point in time
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Fill>)` - Fills executed at or after `since`
+    /// * `Err(ExecError::VenueNotSupported)` - The adapter does not report fills (default)
+    async fn get_fills(&mut self, _since: DateTime<Utc>) -> ExecResult<Vec<Fill>> {
+        Err(ExecError::VenueNotSupported(format!(
+            "{} does not report fills",
+            self.venue_id()
+        )))
+    }
 }
 
 /// Venue configuration
@@ -98,6 +155,9 @@ pub struct VenueConfig {
     /// API secret
     pub api_secret: Option<String>,
 
+    /// Tick/lot rounding at the venue boundary (None = send as submitted)
+    pub rounding: Option<VenueRounding>,
+
     /// Additional venue-specific configuration
     pub extra: std::collections::HashMap<String, String>,
 }
@@ -111,6 +171,7 @@ impl VenueConfig {
             ws_endpoint: None,
             api_key: None,
             api_secret: None,
+            rounding: None,
             extra: std::collections::HashMap::new(),
         }
     }
@@ -128,6 +189,12 @@ impl VenueConfig {
         self
     }
 
+    /// Round orders to the venue's tick and lot sizes
+    pub fn with_rounding(mut self, rounding: VenueRounding) -> Self {
+        self.rounding = Some(rounding);
+        self
+    }
+
     /// Add extra configuration parameter
     pub fn with_extra(mut self, key: String, value: String) -> Self {
         self.extra.insert(key, value);
@@ -0,0 +1,466 @@
+//! Trade blotter
+//!
+//! Joins orders with their fills and reconstructs round trips: each fill
+//! that reduces a position is matched FIFO against the open lots of the same
+//! market and strategy, producing one entry/exit pair per matched lot with
+//! its round-trip PnL, fees and holding time.
+//!
+//! Build a blotter from the engine's order tracker with
+//! [`ExecutionEngine::trade_blotter`](crate::ExecutionEngine::trade_blotter),
+//! query it with a [`BlotterQuery`], and export fills or round trips as CSV.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ExecResult;
+use crate::oms::tracker::OrderTracker;
+use crate::order::{Fill, Liquidity, MarketId, Order, OrderId, Side};
+
+/// A fill joined with its order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlotterFill {
+    /// Fill ID
+    pub fill_id: String,
+    /// Order ID
+    pub order_id: OrderId,
+    /// Client order ID
+    pub client_order_id: String,
+    /// Strategy that placed the order
+    pub strategy_id: Option<String>,
+    /// Market identifier
+    pub market: MarketId,
+    /// Order side
+    pub side: Side,
+    /// Fill price
+    pub price: f64,
+    /// Fill size
+    pub size: f64,
+    /// Fee amount
+    pub fee: f64,
+    /// Liquidity role
+    pub liquidity: Option<Liquidity>,
+    /// Fill timestamp
+    pub timestamp: DateTime<Utc>,
+}
+
+impl BlotterFill {
+    /// Join a fill with its order
+    pub fn new(order: &Order, fill: &Fill) -> Self {
+        Self {
+            fill_id: fill.fill_id.clone(),
+            order_id: order.id,
+            client_order_id: order.client_order_id.clone(),
+            strategy_id: order.strategy_id.clone(),
+            market: order.market.clone(),
+            side: order.side,
+            price: fill.price,
+            size: fill.size,
+            fee: fill.fee,
+            liquidity: fill.liquidity,
+            timestamp: fill.timestamp,
+        }
+    }
+}
+
+/// A matched entry/exit pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundTrip {
+    /// Market identifier
+    pub market: MarketId,
+    /// Strategy that owned the position
+    pub strategy_id: Option<String>,
+    /// Entry side (`Buy` = long round trip, `Sell` = short)
+    pub direction: Side,
+    /// Matched size
+    pub size: f64,
+    /// Entry order
+    pub entry_order_id: OrderId,
+    /// Exit order
+    pub exit_order_id: OrderId,
+    /// Entry fill price
+    pub entry_price: f64,
+    /// Exit fill price
+    pub exit_price: f64,
+    /// Entry fill time
+    pub entry_time: DateTime<Utc>,
+    /// Exit fill time
+    pub exit_time: DateTime<Utc>,
+    /// PnL before fees
+    pub gross_pnl: f64,
+    /// Entry and exit fees attributable to the matched size
+    pub fees: f64,
+    /// PnL after fees
+    pub net_pnl: f64,
+}
+
+impl RoundTrip {
+    /// Time between entry and exit
+    pub fn holding_time(&self) -> Duration {
+        self.exit_time - self.entry_time
+    }
+}
+
+/// Filter for blotter queries
+///
+/// Fills match on their timestamp; round trips on their exit time.
+#[derive(Debug, Clone, Default)]
+pub struct BlotterQuery {
+    /// Only this market
+    pub market: Option<MarketId>,
+    /// Only this strategy
+    pub strategy_id: Option<String>,
+    /// At or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl BlotterQuery {
+    /// Match everything
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to a market
+    pub fn market(mut self, market: MarketId) -> Self {
+        self.market = Some(market);
+        self
+    }
+
+    /// Restrict to a strategy
+    pub fn strategy(mut self, strategy_id: impl Into<String>) -> Self {
+        self.strategy_id = Some(strategy_id.into());
+        self
+    }
+
+    /// Restrict to `[from, to)`
+    pub fn between(mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.from = Some(from);
+        self.to = Some(to);
+        self
+    }
+
+    fn matches(&self, market: &MarketId, strategy_id: Option<&str>, timestamp: DateTime<Utc>) -> bool {
+        self.market.as_ref().is_none_or(|m| m == market)
+            && self.strategy_id.as_deref().is_none_or(|s| strategy_id == Some(s))
+            && self.from.is_none_or(|from| timestamp >= from)
+            && self.to.is_none_or(|to| timestamp < to)
+    }
+}
+
+/// Aggregate round-trip statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlotterSummary {
+    /// Number of round trips
+    pub round_trips: usize,
+    /// Total net PnL
+    pub net_pnl: f64,
+    /// Total fees
+    pub fees: f64,
+    /// Fraction of round trips with positive net PnL
+    pub win_rate: f64,
+    /// Mean holding time in seconds
+    pub avg_holding_secs: f64,
+}
+
+#[derive(Debug, Clone)]
+struct OpenLot {
+    order_id: OrderId,
+    side: Side,
+    price: f64,
+    remaining: f64,
+    fee_per_unit: f64,
+    timestamp: DateTime<Utc>,
+}
+
+/// Orders and fills joined into trade records
+#[derive(Debug, Clone, Default)]
+pub struct TradeBlotter {
+    fills: Vec<BlotterFill>,
+}
+
+impl TradeBlotter {
+    /// Create an empty blotter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a blotter from every order and fill in a tracker
+    pub fn from_tracker(tracker: &OrderTracker) -> ExecResult<Self> {
+        let mut blotter = Self::new();
+        for order in tracker.get_all_orders()? {
+            for fill in tracker.get_fills(&order.id)? {
+                blotter.add_fill(&order, &fill);
+            }
+        }
+        Ok(blotter)
+    }
+
+    /// Add a fill
+    pub fn add_fill(&mut self, order: &Order, fill: &Fill) {
+        let record = BlotterFill::new(order, fill);
+        let index = self.fills.partition_point(|f| f.timestamp <= record.timestamp);
+        self.fills.insert(index, record);
+    }
+
+    /// Fills matching a query, oldest first
+    pub fn fills(&self, query: &BlotterQuery) -> Vec<&BlotterFill> {
+        self.fills
+            .iter()
+            .filter(|f| query.matches(&f.market, f.strategy_id.as_deref(), f.timestamp))
+            .collect()
+    }
+
+    /// Round trips matching a query, in exit order
+    pub fn round_trips(&self, query: &BlotterQuery) -> Vec<RoundTrip> {
+        let mut lots: HashMap<(MarketId, Option<String>), VecDeque<OpenLot>> = HashMap::new();
+        let mut trips = Vec::new();
+
+        for fill in &self.fills {
+            let key = (fill.market.clone(), fill.strategy_id.clone());
+            let open = lots.entry(key).or_default();
+            let mut remaining = fill.size;
+            let fee_per_unit = if fill.size > 0.0 { fill.fee / fill.size } else { 0.0 };
+
+            while remaining > 1e-12 {
+                let Some(lot) = open.front_mut().filter(|lot| lot.side != fill.side) else {
+                    break;
+                };
+
+                let size = remaining.min(lot.remaining);
+                let direction = match lot.side {
+                    Side::Buy => 1.0,
+                    Side::Sell => -1.0,
+                };
+                let gross_pnl = direction * (fill.price - lot.price) * size;
+                let fees = (lot.fee_per_unit + fee_per_unit) * size;
+
+                trips.push(RoundTrip {
+                    market: fill.market.clone(),
+                    strategy_id: fill.strategy_id.clone(),
+                    direction: lot.side,
+                    size,
+                    entry_order_id: lot.order_id,
+                    exit_order_id: fill.order_id,
+                    entry_price: lot.price,
+                    exit_price: fill.price,
+                    entry_time: lot.timestamp,
+                    exit_time: fill.timestamp,
+                    gross_pnl,
+                    fees,
+                    net_pnl: gross_pnl - fees,
+                });
+
+                lot.remaining -= size;
+                remaining -= size;
+                if lot.remaining <= 1e-12 {
+                    open.pop_front();
+                }
+            }
+
+            if remaining > 1e-12 {
+                open.push_back(OpenLot {
+                    order_id: fill.order_id,
+                    side: fill.side,
+                    price: fill.price,
+                    remaining,
+                    fee_per_unit,
+                    timestamp: fill.timestamp,
+                });
+            }
+        }
+
+        trips.retain(|t| query.matches(&t.market, t.strategy_id.as_deref(), t.exit_time));
+        trips
+    }
+
+    /// Summary statistics over matching round trips
+    pub fn summary(&self, query: &BlotterQuery) -> BlotterSummary {
+        let trips = self.round_trips(query);
+        if trips.is_empty() {
+            return BlotterSummary::default();
+        }
+
+        let n = trips.len() as f64;
+        BlotterSummary {
+            round_trips: trips.len(),
+            net_pnl: trips.iter().map(|t| t.net_pnl).sum(),
+            fees: trips.iter().map(|t| t.fees).sum(),
+            win_rate: trips.iter().filter(|t| t.net_pnl > 0.0).count() as f64 / n,
+            avg_holding_secs: trips
+                .iter()
+                .map(|t| t.holding_time().num_milliseconds() as f64 / 1000.0)
+                .sum::<f64>()
+                / n,
+        }
+    }
+
+    /// Matching fills as CSV
+    pub fn fills_csv(&self, query: &BlotterQuery) -> String {
+        let mut csv = String::from(
+            "timestamp,fill_id,order_id,client_order_id,strategy_id,market,side,price,size,fee,liquidity\n",
+        );
+        for f in self.fills(query) {
+            let liquidity = match f.liquidity {
+                Some(Liquidity::Maker) => "maker",
+                Some(Liquidity::Taker) => "taker",
+                None => "",
+            };
+            csv.push_str(&csv_row(&[
+                f.timestamp.to_rfc3339(),
+                f.fill_id.clone(),
+                f.order_id.to_string(),
+                f.client_order_id.clone(),
+                f.strategy_id.clone().unwrap_or_default(),
+                f.market.to_string(),
+                f.side.to_string(),
+                f.price.to_string(),
+                f.size.to_string(),
+                f.fee.to_string(),
+                liquidity.to_string(),
+            ]));
+        }
+        csv
+    }
+
+    /// Matching round trips as CSV
+    pub fn round_trips_csv(&self, query: &BlotterQuery) -> String {
+        let mut csv = String::from(
+            "market,strategy_id,direction,size,entry_time,exit_time,holding_secs,entry_price,exit_price,gross_pnl,fees,net_pnl,entry_order_id,exit_order_id\n",
+        );
+        for t in self.round_trips(query) {
+            csv.push_str(&csv_row(&[
+                t.market.to_string(),
+                t.strategy_id.clone().unwrap_or_default(),
+                t.direction.to_string(),
+                t.size.to_string(),
+                t.entry_time.to_rfc3339(),
+                t.exit_time.to_rfc3339(),
+                (t.holding_time().num_milliseconds() as f64 / 1000.0).to_string(),
+                t.entry_price.to_string(),
+                t.exit_price.to_string(),
+                t.gross_pnl.to_string(),
+                t.fees.to_string(),
+                t.net_pnl.to_string(),
+                t.entry_order_id.to_string(),
+                t.exit_order_id.to_string(),
+            ]));
+        }
+        csv
+    }
+
+    /// Write matching round trips to a CSV file
+    pub fn write_round_trips_csv(&self, path: impl AsRef<Path>, query: &BlotterQuery) -> ExecResult<()> {
+        std::fs::write(path, self.round_trips_csv(query))?;
+        Ok(())
+    }
+
+    /// Write matching fills to a CSV file
+    pub fn write_fills_csv(&self, path: impl AsRef<Path>, query: &BlotterQuery) -> ExecResult<()> {
+        std::fs::write(path, self.fills_csv(query))?;
+        Ok(())
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    format!("{}\n", escaped.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{OrderType, TimeInForce, VenueId};
+    use chrono::TimeZone;
+
+    fn order(side: Side, strategy: &str) -> Order {
+        Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            side,
+            OrderType::Limit,
+            Some(0.5),
+            100.0,
+            TimeInForce::GTC,
+            "client".to_string(),
+        )
+        .with_strategy(strategy)
+    }
+
+    fn fill(order: &Order, price: f64, size: f64, secs: i64) -> Fill {
+        Fill {
+            fill_id: format!("fill-{}", secs),
+            order_id: order.id,
+            venue_order_id: None,
+            price,
+            size,
+            fee: 0.01 * size,
+            fee_currency: "USD".to_string(),
+            timestamp: Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap(),
+            liquidity: Some(Liquidity::Maker),
+        }
+    }
+
+    #[test]
+    fn test_fifo_round_trips() {
+        let mut blotter = TradeBlotter::new();
+        let buy1 = order(Side::Buy, "mm_1");
+        let buy2 = order(Side::Buy, "mm_1");
+        let sell = order(Side::Sell, "mm_1");
+
+        blotter.add_fill(&buy1, &fill(&buy1, 0.40, 100.0, 0));
+        blotter.add_fill(&buy2, &fill(&buy2, 0.50, 100.0, 10));
+        // Sell 150 closes buy1 fully and half of buy2, then flips nothing
+        blotter.add_fill(&sell, &fill(&sell, 0.60, 150.0, 60));
+
+        let trips = blotter.round_trips(&BlotterQuery::all());
+        assert_eq!(trips.len(), 2);
+        assert_eq!(trips[0].entry_order_id, buy1.id);
+        assert!((trips[0].gross_pnl - 20.0).abs() < 1e-9);
+        assert_eq!(trips[0].holding_time(), Duration::seconds(60));
+        assert_eq!(trips[1].size, 50.0);
+        assert!((trips[1].gross_pnl - 5.0).abs() < 1e-9);
+        assert!((trips[1].fees - 1.0).abs() < 1e-9);
+
+        let summary = blotter.summary(&BlotterQuery::all());
+        assert_eq!(summary.round_trips, 2);
+        assert!((summary.net_pnl - 22.0).abs() < 1e-9);
+        assert_eq!(summary.win_rate, 1.0);
+
+        assert!(blotter.round_trips(&BlotterQuery::all().strategy("other")).is_empty());
+        assert_eq!(blotter.fills(&BlotterQuery::all().market(MarketId::new("0x123abc"))).len(), 3);
+    }
+
+    #[test]
+    fn test_short_round_trip_and_csv() {
+        let mut blotter = TradeBlotter::new();
+        let sell = order(Side::Sell, "arb");
+        let buy = order(Side::Buy, "arb");
+        blotter.add_fill(&buy, &fill(&buy, 0.30, 10.0, 5));
+        blotter.add_fill(&sell, &fill(&sell, 0.45, 10.0, 0));
+
+        let trips = blotter.round_trips(&BlotterQuery::all());
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].direction, Side::Sell);
+        assert!((trips[0].gross_pnl - 1.5).abs() < 1e-9);
+
+        let csv = blotter.round_trips_csv(&BlotterQuery::all());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("market,strategy_id,direction"));
+        assert!(lines[1].starts_with("0x123abc,arb,SELL,10,"));
+        assert_eq!(csv_row(&["a,b".to_string(), "c\"d".to_string()]), "\"a,b\",\"c\"\"d\"\n");
+    }
+}
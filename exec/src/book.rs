@@ -0,0 +1,463 @@
+//! Locally maintained order books and their validation
+//!
+//! [`OrderBook`] applies venue snapshots and level updates to keep a full
+//! depth book per market. Book maintenance bugs (a missed delete, a level
+//! applied twice) silently corrupt quotes, so [`BookValidator`] checks the
+//! local book against what the venue publishes:
+//!
+//! - **Checksums**: feeds that send a checksum of the top levels with each
+//!   update are compared against [`OrderBook::checksum`] (CRC32 of the
+//!   interleaved `bid_price:bid_size:ask_price:ask_size` levels)
+//! - **Snapshots**: periodic full snapshots are diffed level by level
+//!
+//! A failed check is counted per market and, if configured, a diverged book
+//! is replaced by the snapshot it was compared with. Checksum failures
+//! cannot be repaired locally; the feed must resync the market (e.g.
+//! request a fresh snapshot).
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::oms::BookDepth;
+use crate::order::Side;
+
+/// Prices are keyed in units of 1e-9 so levels compare exactly
+const PRICE_SCALE: f64 = 1e9;
+
+fn price_key(price: f64) -> i64 {
+    (price * PRICE_SCALE).round() as i64
+}
+
+fn key_price(key: i64) -> f64 {
+    key as f64 / PRICE_SCALE
+}
+
+/// Full depth book of one market
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBook {
+    market: String,
+    bids: BTreeMap<i64, f64>,
+    asks: BTreeMap<i64, f64>,
+    /// Sequence number of the last applied message, if the feed has them
+    sequence: Option<u64>,
+}
+
+impl OrderBook {
+    /// Empty book
+    pub fn new(market: impl Into<String>) -> Self {
+        Self {
+            market: market.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn market(&self) -> &str {
+        &self.market
+    }
+
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Replace every level with a venue snapshot
+    pub fn apply_snapshot(&mut self, snapshot: &BookDepth, sequence: Option<u64>) {
+        self.bids = levels(&snapshot.bids);
+        self.asks = levels(&snapshot.asks);
+        self.sequence = sequence;
+    }
+
+    /// Set the size at a price level; a size of zero removes the level
+    ///
+    /// `Side::Buy` updates bids and `Side::Sell` asks.
+    pub fn apply_update(&mut self, side: Side, price: f64, size: f64, sequence: Option<u64>) {
+        let book = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        if size > 0.0 {
+            book.insert(price_key(price), size);
+        } else {
+            book.remove(&price_key(price));
+        }
+        if sequence.is_some() {
+            self.sequence = sequence;
+        }
+    }
+
+    /// Best bid as `(price, size)`
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.iter().next_back().map(|(key, size)| (key_price(*key), *size))
+    }
+
+    /// Best ask as `(price, size)`
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.iter().next().map(|(key, size)| (key_price(*key), *size))
+    }
+
+    /// Whether the best bid is at or above the best ask
+    pub fn is_crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid, _)), Some((ask, _))) => bid >= ask,
+            _ => false,
+        }
+    }
+
+    /// Top `levels` of each side, best first
+    pub fn depth(&self, levels: usize) -> BookDepth {
+        BookDepth {
+            bids: self
+                .bids
+                .iter()
+                .rev()
+                .take(levels)
+                .map(|(key, size)| (key_price(*key), *size))
+                .collect(),
+            asks: self
+                .asks
+                .iter()
+                .take(levels)
+                .map(|(key, size)| (key_price(*key), *size))
+                .collect(),
+        }
+    }
+
+    /// Text the checksum is computed over: the top `levels` bids and asks
+    /// interleaved as `bid_price:bid_size:ask_price:ask_size:...`, numbers
+    /// in their shortest decimal form
+    pub fn checksum_payload(&self, levels: usize) -> String {
+        let depth = self.depth(levels);
+        let mut fields = Vec::with_capacity(levels * 4);
+        for i in 0..depth.bids.len().max(depth.asks.len()) {
+            for (price, size) in [depth.bids.get(i), depth.asks.get(i)].into_iter().flatten() {
+                fields.push(price.to_string());
+                fields.push(size.to_string());
+            }
+        }
+        fields.join(":")
+    }
+
+    /// CRC32 of [`checksum_payload`](Self::checksum_payload)
+    pub fn checksum(&self, levels: usize) -> u32 {
+        crc32(self.checksum_payload(levels).as_bytes())
+    }
+}
+
+fn levels(levels: &[(f64, f64)]) -> BTreeMap<i64, f64> {
+    levels
+        .iter()
+        .filter(|(_, size)| *size > 0.0)
+        .map(|(price, size)| (price_key(*price), *size))
+        .collect()
+}
+
+/// CRC32 (IEEE 802.3, as used by exchange book checksums)
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Validation settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BookValidationConfig {
+    /// Compare against a full snapshot this often (unset = checksums only)
+    pub snapshot_interval_ms: Option<u64>,
+
+    /// Levels per side covered by checksums and snapshot comparisons
+    pub depth: usize,
+
+    /// Relative size difference tolerated at a level
+    pub size_tolerance: f64,
+
+    /// Replace a diverged book with the snapshot it was compared with
+    pub resync_on_divergence: bool,
+}
+
+impl Default for BookValidationConfig {
+    fn default() -> Self {
+        Self {
+            snapshot_interval_ms: None,
+            depth: 25,
+            size_tolerance: 1e-9,
+            resync_on_divergence: true,
+        }
+    }
+}
+
+impl BookValidationConfig {
+    pub fn snapshot_interval(&self) -> Option<Duration> {
+        self.snapshot_interval_ms.map(|ms| Duration::milliseconds(ms as i64))
+    }
+}
+
+/// Differences between the local book and a venue snapshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookDivergence {
+    /// Snapshot levels absent locally
+    pub missing_levels: usize,
+    /// Local levels absent from the snapshot
+    pub extra_levels: usize,
+    /// Levels present on both with different sizes
+    pub size_mismatches: usize,
+    /// Largest absolute size difference at a level
+    pub max_size_diff: f64,
+    /// Whether the local book is crossed
+    pub crossed: bool,
+}
+
+impl BookDivergence {
+    /// Levels that differ
+    pub fn divergent_levels(&self) -> usize {
+        self.missing_levels + self.extra_levels + self.size_mismatches
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.divergent_levels() == 0 && !self.crossed
+    }
+}
+
+/// Outcome of one validation
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookCheck {
+    /// The local book matches
+    Valid,
+    /// The venue checksum differs from the local one
+    ChecksumMismatch { expected: u32, computed: u32 },
+    /// The local book differs from a snapshot
+    Diverged(BookDivergence),
+}
+
+impl BookCheck {
+    /// Whether the book should be rebuilt from a fresh snapshot
+    pub fn needs_resync(&self) -> bool {
+        !matches!(self, BookCheck::Valid)
+    }
+}
+
+/// Counters for one market
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookValidationStats {
+    /// Checksum and snapshot validations
+    pub checks: u64,
+    /// Checksum mismatches
+    pub checksum_failures: u64,
+    /// Snapshot comparisons that found differences
+    pub divergences: u64,
+    /// Books rebuilt after a failed check
+    pub resyncs: u64,
+    /// Differences found by the latest failed snapshot comparison
+    pub last_divergence: Option<BookDivergence>,
+    /// Time of the latest snapshot comparison
+    pub last_snapshot_check: Option<DateTime<Utc>>,
+}
+
+impl BookValidationStats {
+    /// Counters as `(metric name, value)` pairs
+    pub fn metric_values(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("market_data.book_checks", self.checks as f64),
+            ("market_data.book_checksum_failures", self.checksum_failures as f64),
+            ("market_data.book_divergences", self.divergences as f64),
+            ("market_data.book_resyncs", self.resyncs as f64),
+            (
+                "market_data.book_divergent_levels",
+                self.last_divergence.as_ref().map_or(0.0, |d| d.divergent_levels() as f64),
+            ),
+        ]
+    }
+}
+
+/// Per-market validation of locally maintained books
+#[derive(Debug, Clone, Default)]
+pub struct BookValidator {
+    config: BookValidationConfig,
+    markets: HashMap<String, BookValidationStats>,
+}
+
+impl BookValidator {
+    /// Create a validator
+    pub fn new(config: BookValidationConfig) -> Self {
+        Self {
+            config,
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Validation settings
+    pub fn config(&self) -> &BookValidationConfig {
+        &self.config
+    }
+
+    /// Compare the book with a checksum sent by the venue
+    pub fn verify_checksum(&mut self, book: &OrderBook, expected: u32) -> BookCheck {
+        let computed = book.checksum(self.config.depth);
+        let stats = self.markets.entry(book.market().to_string()).or_default();
+        stats.checks += 1;
+        if computed == expected {
+            BookCheck::Valid
+        } else {
+            stats.checksum_failures += 1;
+            BookCheck::ChecksumMismatch { expected, computed }
+        }
+    }
+
+    /// Whether the market's next snapshot comparison is due
+    pub fn snapshot_due(&self, market: &str, now: DateTime<Utc>) -> bool {
+        let Some(interval) = self.config.snapshot_interval() else {
+            return false;
+        };
+        match self.markets.get(market).and_then(|stats| stats.last_snapshot_check) {
+            Some(last) => now - last >= interval,
+            None => true,
+        }
+    }
+
+    /// Diff the top levels of the book against a venue snapshot
+    ///
+    /// With `resync_on_divergence`, a diverged book is replaced by the
+    /// snapshot (counted as a resync).
+    pub fn compare_snapshot(&mut self, book: &mut OrderBook, snapshot: &BookDepth, at: DateTime<Utc>) -> BookCheck {
+        let depth = self.config.depth;
+        let local = book.depth(depth);
+        let mut divergence = BookDivergence {
+            crossed: book.is_crossed(),
+            ..Default::default()
+        };
+        diff_side(&local.bids, &snapshot.bids[..snapshot.bids.len().min(depth)], self.config.size_tolerance, &mut divergence);
+        diff_side(&local.asks, &snapshot.asks[..snapshot.asks.len().min(depth)], self.config.size_tolerance, &mut divergence);
+
+        let stats = self.markets.entry(book.market().to_string()).or_default();
+        stats.checks += 1;
+        stats.last_snapshot_check = Some(at);
+        if divergence.is_empty() {
+            return BookCheck::Valid;
+        }
+
+        stats.divergences += 1;
+        stats.last_divergence = Some(divergence.clone());
+        if self.config.resync_on_divergence {
+            book.apply_snapshot(snapshot, book.sequence());
+            stats.resyncs += 1;
+        }
+        BookCheck::Diverged(divergence)
+    }
+
+    /// Count a resync done by the feed (e.g. after a checksum mismatch)
+    pub fn record_resync(&mut self, market: &str) {
+        self.markets.entry(market.to_string()).or_default().resyncs += 1;
+    }
+
+    /// Counters for one market
+    pub fn stats(&self, market: &str) -> Option<&BookValidationStats> {
+        self.markets.get(market)
+    }
+
+    /// Counters for every market
+    pub fn all_stats(&self) -> &HashMap<String, BookValidationStats> {
+        &self.markets
+    }
+
+    /// Forget a market (e.g. after unsubscribing)
+    pub fn reset(&mut self, market: &str) -> Option<BookValidationStats> {
+        self.markets.remove(market)
+    }
+}
+
+/// Accumulate level differences of one side
+fn diff_side(local: &[(f64, f64)], snapshot: &[(f64, f64)], tolerance: f64, divergence: &mut BookDivergence) {
+    let local: BTreeMap<i64, f64> = levels(local);
+    let snapshot: BTreeMap<i64, f64> = levels(snapshot);
+    for (key, size) in &snapshot {
+        match local.get(key) {
+            None => divergence.missing_levels += 1,
+            Some(local_size) => {
+                let diff = (local_size - size).abs();
+                if diff > tolerance * size.abs().max(1.0) {
+                    divergence.size_mismatches += 1;
+                    divergence.max_size_diff = divergence.max_size_diff.max(diff);
+                }
+            }
+        }
+    }
+    divergence.extra_levels += local.keys().filter(|key| !snapshot.contains_key(key)).count();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> BookDepth {
+        BookDepth {
+            bids: vec![(0.49, 100.0), (0.48, 250.0), (0.47, 50.0)],
+            asks: vec![(0.51, 80.0), (0.52, 300.0)],
+        }
+    }
+
+    #[test]
+    fn test_book_updates_and_checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut book = OrderBook::new("m1");
+        book.apply_snapshot(&snapshot(), Some(1));
+        assert_eq!(book.best_bid(), Some((0.49, 100.0)));
+        assert_eq!(book.best_ask(), Some((0.51, 80.0)));
+        assert_eq!(book.checksum_payload(2), "0.49:100:0.51:80:0.48:250:0.52:300");
+
+        book.apply_update(Side::Buy, 0.49, 0.0, Some(2));
+        book.apply_update(Side::Sell, 0.50, 10.0, Some(3));
+        assert_eq!(book.best_bid(), Some((0.48, 250.0)));
+        assert_eq!(book.best_ask(), Some((0.50, 10.0)));
+        assert_eq!(book.sequence(), Some(3));
+        assert!(!book.is_crossed());
+        book.apply_update(Side::Buy, 0.50, 5.0, None);
+        assert!(book.is_crossed());
+    }
+
+    #[test]
+    fn test_validation() {
+        let now = Utc::now();
+        let mut validator = BookValidator::new(BookValidationConfig {
+            snapshot_interval_ms: Some(60_000),
+            ..Default::default()
+        });
+        let mut book = OrderBook::new("m1");
+        book.apply_snapshot(&snapshot(), None);
+
+        let expected = book.checksum(25);
+        assert_eq!(validator.verify_checksum(&book, expected), BookCheck::Valid);
+        assert!(validator.snapshot_due("m1", now));
+        assert_eq!(validator.compare_snapshot(&mut book, &snapshot(), now), BookCheck::Valid);
+        assert!(!validator.snapshot_due("m1", now + Duration::seconds(30)));
+
+        // A missed delete and a double-applied size corrupt the book
+        book.apply_update(Side::Buy, 0.46, 20.0, None);
+        book.apply_update(Side::Sell, 0.52, 600.0, None);
+        let check = validator.verify_checksum(&book, expected);
+        assert!(matches!(check, BookCheck::ChecksumMismatch { .. }));
+        assert!(check.needs_resync());
+
+        let later = now + Duration::seconds(60);
+        assert!(validator.snapshot_due("m1", later));
+        let BookCheck::Diverged(divergence) = validator.compare_snapshot(&mut book, &snapshot(), later) else {
+            panic!("expected divergence");
+        };
+        assert_eq!(divergence.extra_levels, 1);
+        assert_eq!(divergence.size_mismatches, 1);
+        assert_eq!(divergence.max_size_diff, 300.0);
+
+        // Resynced from the snapshot
+        assert_eq!(book.checksum(25), expected);
+        let stats = validator.stats("m1").unwrap();
+        assert_eq!((stats.checks, stats.checksum_failures, stats.divergences, stats.resyncs), (4, 1, 1, 1));
+        assert_eq!(stats.metric_values()[4], ("market_data.book_divergent_levels", 2.0));
+    }
+}
@@ -0,0 +1,347 @@
+//! Position bootstrap on startup
+//!
+//! A restarted bot starts with an empty OMS and believes it is flat, even
+//! when its predecessor left positions open. [`bootstrap_positions`] asks
+//! every venue what the account holds before trading starts:
+//!
+//! - **Positions**: the venue's own net position per market, from
+//!   `VenueAdapter::get_positions`, where the adapter supports it
+//! - **Fills**: otherwise the fills of the last `fill_lookback_secs`,
+//!   netted per market. Fills only name their order, so fills of orders
+//!   neither known locally nor still open at the venue cannot be attributed
+//!   to a market; they are counted in the report instead
+//!
+//! Venues supporting neither are listed as unsupported. Orders still open
+//! at a venue are imported into the OMS (`import_open_orders`), so they can
+//! be cancelled and their fills recorded. Positions are set with
+//! `ExecutionEngine::import_position`; [`BootstrapReport::seed_simulator`]
+//! seeds a `PolymarketSimulator` with them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use ag_risk::PolymarketSimulator;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::engine::ExecutionEngine;
+use crate::error::{ExecError, ExecResult};
+use crate::liquidation::FLAT_EPSILON;
+use crate::order::{MarketId, Order, Side, VenueId};
+
+/// Position bootstrap settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PositionBootstrapConfig {
+    /// How far back fills are netted for venues that don't report
+    /// positions, in seconds
+    pub fill_lookback_secs: u64,
+
+    /// Track orders still open at the venues in the OMS
+    pub import_open_orders: bool,
+
+    /// Treat an incomplete bootstrap (see [`BootstrapReport::is_complete`])
+    /// as fatal rather than starting with possibly missing positions
+    pub required: bool,
+}
+
+impl Default for PositionBootstrapConfig {
+    fn default() -> Self {
+        Self {
+            fill_lookback_secs: 86_400,
+            import_open_orders: true,
+            required: false,
+        }
+    }
+}
+
+/// Where an imported position came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionSource {
+    /// The venue's reported net position
+    Positions,
+    /// Fills netted over the lookback window
+    Fills,
+}
+
+/// A position reported by a venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedPosition {
+    pub venue: VenueId,
+    /// Canonical market
+    pub market: MarketId,
+    /// Net size (positive = long)
+    pub size: f64,
+    /// Average entry price, if the source reports prices
+    pub avg_price: Option<f64>,
+    pub source: PositionSource,
+}
+
+/// Bootstrap outcome of one venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueBootstrap {
+    pub venue: VenueId,
+    /// How positions were reconstructed (None = unsupported or failed)
+    pub source: Option<PositionSource>,
+    /// Orders open at the venue
+    pub open_orders: usize,
+    /// Open orders newly tracked in the OMS
+    pub imported_orders: usize,
+    /// Fills whose order, and so market and side, is unknown
+    pub unattributed_fills: usize,
+    /// Venue query failure
+    pub error: Option<String>,
+}
+
+/// Outcome of the position bootstrap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BootstrapReport {
+    pub completed_at: DateTime<Utc>,
+    /// Per-venue results, sorted by venue
+    pub venues: Vec<VenueBootstrap>,
+    /// Non-flat positions imported, sorted by venue and market
+    pub positions: Vec<ImportedPosition>,
+}
+
+impl BootstrapReport {
+    /// Whether every venue reported its positions with nothing left over
+    pub fn is_complete(&self) -> bool {
+        self.venues
+            .iter()
+            .all(|v| v.source.is_some() && v.error.is_none() && v.unattributed_fills == 0)
+    }
+
+    /// Outcome of one venue
+    pub fn venue(&self, venue: &VenueId) -> Option<&VenueBootstrap> {
+        self.venues.iter().find(|v| &v.venue == venue)
+    }
+
+    /// Net position per market across venues
+    pub fn net_positions(&self) -> HashMap<MarketId, f64> {
+        let mut net = HashMap::new();
+        for position in &self.positions {
+            *net.entry(position.market.clone()).or_insert(0.0) += position.size;
+        }
+        net
+    }
+
+    /// Seed a simulator with the imported positions
+    ///
+    /// Positions without an entry price are booked at `fallback_price`
+    /// (e.g. the current mid); markets with neither are skipped and
+    /// returned.
+    pub fn seed_simulator(
+        &self,
+        simulator: &mut PolymarketSimulator,
+        fallback_price: impl Fn(&MarketId) -> Option<f64>,
+    ) -> Vec<MarketId> {
+        let mut skipped = Vec::new();
+        for position in &self.positions {
+            match position.avg_price.or_else(|| fallback_price(&position.market)) {
+                Some(price) => simulator.update_position(position.market.as_str(), position.size, price),
+                None => skipped.push(position.market.clone()),
+            }
+        }
+        skipped
+    }
+}
+
+/// Reconstruct positions from every registered venue and import them
+///
+/// Positions reported by several venues for the same canonical market are
+/// summed, as the engine keeps one position per market.
+pub async fn bootstrap_positions(
+    engine: &ExecutionEngine,
+    config: &PositionBootstrapConfig,
+) -> ExecResult<BootstrapReport> {
+    let since = Utc::now() - chrono::Duration::seconds(config.fill_lookback_secs as i64);
+    let symbols = engine.symbol_mapper();
+
+    let mut adapters: Vec<_> = engine.adapters().iter().collect();
+    adapters.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+
+    let mut venues = Vec::with_capacity(adapters.len());
+    let mut positions = Vec::new();
+    for (venue_id, adapter) in adapters {
+        let mut status = VenueBootstrap {
+            venue: venue_id.clone(),
+            source: None,
+            open_orders: 0,
+            imported_orders: 0,
+            unattributed_fills: 0,
+            error: None,
+        };
+
+        let mut adapter = adapter.lock().await;
+        let mut open_orders = match adapter.get_open_orders().await {
+            Ok(open_orders) => open_orders,
+            Err(e) => {
+                warn!("Position bootstrap of {} failed: {}", venue_id, e);
+                status.error = Some(e.to_string());
+                venues.push(status);
+                continue;
+            }
+        };
+        let reported = match adapter.get_positions().await {
+            Ok(reported) => Some(reported),
+            Err(ExecError::VenueNotSupported(_)) => None,
+            Err(e) => {
+                warn!("Position query for {} failed: {}", venue_id, e);
+                status.error = Some(e.to_string());
+                venues.push(status);
+                continue;
+            }
+        };
+        let fills = match reported {
+            Some(_) => None,
+            None => match adapter.get_fills(since).await {
+                Ok(fills) => Some(fills),
+                Err(ExecError::VenueNotSupported(_)) => None,
+                Err(e) => {
+                    warn!("Fill query for {} failed: {}", venue_id, e);
+                    status.error = Some(e.to_string());
+                    None
+                }
+            },
+        };
+        drop(adapter);
+
+        // Venues report their own symbols
+        // Size and entry price per canonical market, sorted by market
+        let mut venue_positions: BTreeMap<String, (f64, Option<f64>)> = BTreeMap::new();
+        {
+            let symbols = symbols.read().await;
+            for order in &mut open_orders {
+                order.market = symbols.resolve_canonical(venue_id, order.market.as_str());
+            }
+            if let Some(reported) = reported {
+                status.source = Some(PositionSource::Positions);
+                for (symbol, size) in reported {
+                    let market = symbols.resolve_canonical(venue_id, &symbol);
+                    venue_positions.entry(market.0).or_insert((0.0, None)).0 += size;
+                }
+            }
+        }
+
+        if let Some(fills) = fills {
+            status.source = Some(PositionSource::Fills);
+            // Net size and signed cost per market
+            let mut netted: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+            for fill in fills {
+                let order = engine
+                    .order_tracker()
+                    .get_order(&fill.order_id)
+                    .ok()
+                    .or_else(|| open_orders.iter().find(|o| o.id == fill.order_id).cloned());
+                let Some(order) = order else {
+                    status.unattributed_fills += 1;
+                    continue;
+                };
+                let size = match order.side {
+                    Side::Buy => fill.size,
+                    Side::Sell => -fill.size,
+                };
+                let entry = netted.entry(order.market.0).or_insert((0.0, 0.0));
+                entry.0 += size;
+                entry.1 += size * fill.price;
+            }
+            for (market, (size, cost)) in netted {
+                let avg_price = (size.abs() > FLAT_EPSILON).then(|| cost / size);
+                venue_positions.insert(market, (size, avg_price));
+            }
+            if status.unattributed_fills > 0 {
+                warn!("{} fills from {} could not be attributed to a market", status.unattributed_fills, venue_id);
+            }
+        }
+
+        status.open_orders = open_orders.len();
+        if config.import_open_orders {
+            status.imported_orders = import_orders(engine, open_orders)?;
+        }
+        if status.source.is_none() && status.error.is_none() {
+            warn!("{} reports neither positions nor fills; assuming flat", venue_id);
+        }
+
+        positions.extend(
+            venue_positions
+                .into_iter()
+                .filter(|(_, (size, _))| size.abs() > FLAT_EPSILON)
+                .map(|(market, (size, avg_price))| ImportedPosition {
+                    venue: venue_id.clone(),
+                    market: MarketId::new(market),
+                    size,
+                    avg_price,
+                    source: status.source.unwrap_or(PositionSource::Positions),
+                }),
+        );
+        venues.push(status);
+    }
+
+    let mut totals: HashMap<&MarketId, (&VenueId, f64, Option<f64>)> = HashMap::new();
+    for position in &positions {
+        let entry = totals
+            .entry(&position.market)
+            .or_insert((&position.venue, 0.0, position.avg_price));
+        entry.1 += position.size;
+    }
+    for (market, (venue, size, avg_price)) in totals {
+        engine.import_position(venue, market, size, avg_price).await;
+    }
+
+    info!("Bootstrapped {} positions from {} venues", positions.len(), venues.len());
+    Ok(BootstrapReport {
+        completed_at: Utc::now(),
+        venues,
+        positions,
+    })
+}
+
+/// Track venue orders the OMS doesn't know, returning how many were added
+fn import_orders(engine: &ExecutionEngine, orders: Vec<Order>) -> ExecResult<usize> {
+    let tracker = engine.order_tracker();
+    let mut imported = 0;
+    for order in orders {
+        if tracker.get_order(&order.id).is_ok() {
+            continue;
+        }
+        tracker.track_order(order)?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_simulator() {
+        let position = |market: &str, size: f64, avg_price: Option<f64>| ImportedPosition {
+            venue: VenueId::new("polymarket"),
+            market: MarketId::new(market),
+            size,
+            avg_price,
+            source: PositionSource::Positions,
+        };
+        let report = BootstrapReport {
+            completed_at: Utc::now(),
+            venues: Vec::new(),
+            positions: vec![
+                position("0xaaa", 100.0, Some(0.40)),
+                position("0xbbb", -50.0, None),
+                position("0xccc", 25.0, None),
+            ],
+        };
+
+        let mut simulator = PolymarketSimulator::new();
+        let mids = |market: &MarketId| (market.as_str() == "0xbbb").then_some(0.60);
+        let skipped = report.seed_simulator(&mut simulator, mids);
+
+        assert_eq!(skipped, vec![MarketId::new("0xccc")]);
+        assert_eq!(simulator.get_position("0xaaa"), 100.0);
+        assert_eq!(simulator.get_position("0xbbb"), -50.0);
+        assert_eq!(simulator.get_position("0xccc"), 0.0);
+        assert_eq!(report.net_positions().len(), 3);
+    }
+}
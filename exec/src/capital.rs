@@ -0,0 +1,215 @@
+//! Cash and collateral tracking across venues
+//!
+//! The [`CapitalTracker`] holds the cash balance and venue-reported locked
+//! collateral (margin) per venue. Balances are set authoritatively from
+//! venue balance polls ([`VenueAdapter::get_balance`](crate::adapters::VenueAdapter::get_balance))
+//! and adjusted from fills in between. Collateral reserved by resting buy
+//! orders is derived from the open orders when a [`CapitalSnapshot`] is
+//! taken, so it never drifts from the order tracker.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::order::{Fill, Order, OrderType, Side, VenueId};
+
+/// Balance reported by a venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueBalance {
+    /// Venue identifier
+    pub venue: VenueId,
+    /// Cash balance in USD, including locked collateral
+    pub cash: f64,
+    /// Collateral the venue reports as locked (margin, pending settlement)
+    pub locked: f64,
+    /// Time of the last poll or fill update
+    pub updated_at: DateTime<Utc>,
+}
+
+impl VenueBalance {
+    /// Create a balance with nothing locked
+    pub fn new(venue: VenueId, cash: f64) -> Self {
+        Self {
+            venue,
+            cash,
+            locked: 0.0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Set the locked collateral
+    pub fn with_locked(mut self, locked: f64) -> Self {
+        self.locked = locked;
+        self
+    }
+}
+
+/// Capital usage of one venue at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueCapital {
+    /// Venue identifier
+    pub venue: VenueId,
+    /// Cash balance
+    pub cash: f64,
+    /// Collateral locked by the venue
+    pub locked: f64,
+    /// Cash reserved by resting buy orders
+    pub reserved: f64,
+    /// Cash available for new orders
+    pub free: f64,
+}
+
+/// Capital usage across all venues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapitalSnapshot {
+    /// Snapshot time
+    pub timestamp: DateTime<Utc>,
+    /// Per-venue breakdown
+    pub venues: Vec<VenueCapital>,
+    /// Total cash
+    pub total_cash: f64,
+    /// Total collateral locked by venues or reserved by open orders
+    pub total_locked: f64,
+    /// Total cash available for new orders
+    pub free_capital: f64,
+}
+
+impl CapitalSnapshot {
+    /// Capital of a single venue
+    pub fn venue(&self, venue: &VenueId) -> Option<&VenueCapital> {
+        self.venues.iter().find(|v| &v.venue == venue)
+    }
+}
+
+/// Per-venue cash and collateral tracker
+#[derive(Debug, Default)]
+pub struct CapitalTracker {
+    balances: HashMap<VenueId, VenueBalance>,
+}
+
+impl CapitalTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace a venue's balance with a polled one
+    pub fn set_balance(&mut self, balance: VenueBalance) {
+        self.balances.insert(balance.venue.clone(), balance);
+    }
+
+    /// Adjust a venue's cash for a fill of `order`
+    ///
+    /// Buys spend `price * size` plus fees, sells receive it minus fees.
+    /// Venues without a polled balance start from zero.
+    pub fn on_fill(&mut self, order: &Order, fill: &Fill) {
+        let notional = fill.price * fill.size;
+        let delta = match order.side {
+            Side::Buy => -notional - fill.fee,
+            Side::Sell => notional - fill.fee,
+        };
+
+        let balance = self
+            .balances
+            .entry(order.venue.clone())
+            .or_insert_with(|| VenueBalance::new(order.venue.clone(), 0.0));
+        balance.cash += delta;
+        balance.updated_at = fill.timestamp;
+    }
+
+    /// Tracked balance of a venue
+    pub fn balance(&self, venue: &VenueId) -> Option<&VenueBalance> {
+        self.balances.get(venue)
+    }
+
+    /// Capital usage given the currently open orders
+    pub fn snapshot(&self, open_orders: &[Order]) -> CapitalSnapshot {
+        let mut reserved: HashMap<&VenueId, f64> = HashMap::new();
+        for order in open_orders.iter().filter(|o| o.side == Side::Buy && !o.is_terminal()) {
+            let price = match (order.order_type, order.price) {
+                (OrderType::Market, _) | (_, None) => continue,
+                (_, Some(price)) => price,
+            };
+            let remaining = (order.size - order.filled_size).max(0.0);
+            *reserved.entry(&order.venue).or_insert(0.0) += price * remaining;
+        }
+
+        let mut venues: Vec<VenueCapital> = self
+            .balances
+            .values()
+            .map(|balance| {
+                let reserved = reserved.get(&balance.venue).copied().unwrap_or(0.0);
+                VenueCapital {
+                    venue: balance.venue.clone(),
+                    cash: balance.cash,
+                    locked: balance.locked,
+                    reserved,
+                    free: balance.cash - balance.locked - reserved,
+                }
+            })
+            .collect();
+        venues.sort_by(|a, b| a.venue.as_str().cmp(b.venue.as_str()));
+
+        CapitalSnapshot {
+            timestamp: Utc::now(),
+            total_cash: venues.iter().map(|v| v.cash).sum(),
+            total_locked: venues.iter().map(|v| v.locked + v.reserved).sum(),
+            free_capital: venues.iter().map(|v| v.free).sum(),
+            venues,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{MarketId, OrderId, TimeInForce};
+
+    fn order(side: Side, price: f64, size: f64) -> Order {
+        Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123"),
+            side,
+            OrderType::Limit,
+            Some(price),
+            size,
+            TimeInForce::GTC,
+            "c1".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_capital_tracking() {
+        let venue = VenueId::new("polymarket");
+        let mut tracker = CapitalTracker::new();
+        tracker.set_balance(VenueBalance::new(venue.clone(), 1000.0).with_locked(50.0));
+
+        let buy = order(Side::Buy, 0.50, 200.0);
+        tracker.on_fill(
+            &buy,
+            &Fill {
+                fill_id: "f1".to_string(),
+                order_id: OrderId::new(),
+                venue_order_id: None,
+                price: 0.50,
+                size: 100.0,
+                fee: 1.0,
+                fee_currency: "USDC".to_string(),
+                timestamp: Utc::now(),
+                liquidity: None,
+            },
+        );
+        assert_eq!(tracker.balance(&venue).unwrap().cash, 949.0);
+
+        // Half of the buy is still resting: 100 * 0.50 reserved
+        let mut resting = buy.clone();
+        resting.filled_size = 100.0;
+        let snapshot = tracker.snapshot(&[resting, order(Side::Sell, 0.60, 100.0)]);
+        let capital = snapshot.venue(&venue).unwrap();
+        assert_eq!(capital.reserved, 50.0);
+        assert_eq!(capital.free, 949.0 - 50.0 - 50.0);
+        assert_eq!(snapshot.total_locked, 100.0);
+        assert_eq!(snapshot.free_capital, capital.free);
+    }
+}
@@ -4,18 +4,37 @@
 //! It coordinates venue adapters, risk checks, rate limiting, and order tracking.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
+use ag_risk::clock::{random_ids, system_clock, SharedClock, SharedIdGenerator};
 use ag_risk::{RiskContext, RiskEngine};
 
 use crate::adapters::venue_adapter::VenueAdapter;
-use crate::error::{ExecError, ExecResult};
+use crate::blotter::TradeBlotter;
+use crate::capital::{CapitalSnapshot, CapitalTracker, VenueBalance};
+use crate::error::{ExecError, ExecResult, VenueErrorEvent, VenueErrorKind};
+use crate::oms::client_id::ClientOrderIdGenerator;
+use crate::oms::idempotency::{IdempotencyCache, IdempotencyClaim};
+use crate::oms::post_only::{PostOnlyPolicy, Touch};
+use crate::oms::tif::{check_fok_fillable, BookDepth};
 use crate::oms::tracker::OrderTracker;
 use crate::oms::validator::OrderValidator;
-use crate::order::{CancelAck, Fill, Order, OrderAck, OrderId, OrderStatus, VenueId};
-use crate::ratelimit::limiter::RateLimiter;
+use crate::pegs::{check_peggable, PegConfig, PegManager, PeggedOrder, Reprice};
+use crate::positions::{PositionBook, PositionSnapshot};
+use crate::preflight::{BalanceCheck, PreflightReport, RateLimitCheck};
+use crate::order::{
+    CancelAck, CancelOutcome, Fill, MarketId, Order, OrderAck, OrderId, OrderStatus, OrderType, TimeInForce, VenueId,
+};
+use crate::ratelimit::cooldown::{CooldownConfig, VenueCooldowns};
+use crate::ratelimit::limiter::{RateLimiter, RateLimiterStats, RequestPriority};
+use crate::status_poll::{near_fill, OrderUpdate, StatusPollConfig, StatusPoller};
+use crate::symbols::SymbolMapper;
+use crate::triggers::TriggerEngine;
+use crate::venue_status::{VenueState, VenueStatus, VenueStatusChange, VenueStatusConfig, VenueStatusMonitor};
 
 /// Execution engine configuration
 #[derive(Debug, Clone)]
@@ -38,6 +57,13 @@ impl Default for ExecutionEngineConfig {
     }
 }
 
+/// Buffered order updates per subscriber before the oldest are dropped
+const ORDER_UPDATE_CAPACITY: usize = 1024;
+
+/// Size resolution of pre-flight `max_size` when neither a venue lot size
+/// nor a minimum order size applies
+const PREFLIGHT_SIZE_STEP: f64 = 1e-6;
+
 /// Execution engine orchestrating orders across venues
 pub struct ExecutionEngine {
     /// Venue adapters indexed by venue ID
@@ -59,7 +85,66 @@ pub struct ExecutionEngine {
     config: ExecutionEngineConfig,
 
     /// Current positions (market_id -> position size)
-    positions: Arc<Mutex<HashMap<String, f64>>>,
+    positions: Arc<PositionBook>,
+
+    /// Venues of positions imported at startup, which have no local orders
+    /// to tell where they live
+    position_venues: std::sync::Mutex<HashMap<MarketId, VenueId>>,
+
+    /// Locally armed stop orders
+    triggers: Arc<Mutex<TriggerEngine>>,
+
+    /// Limit orders pegged to the touch
+    pegs: Arc<Mutex<PegManager>>,
+
+    /// Post-only crossing protection
+    post_only: PostOnlyPolicy,
+
+    /// Latest best bid/ask per market
+    touches: Arc<Mutex<HashMap<MarketId, Touch>>>,
+
+    /// Latest visible book depth per market (for FOK emulation)
+    depths: Arc<Mutex<HashMap<MarketId, BookDepth>>>,
+
+    /// Canonical market to venue symbol mapping
+    symbols: Arc<RwLock<SymbolMapper>>,
+
+    /// Generator for orders submitted without a client order ID
+    client_ids: Option<Arc<ClientOrderIdGenerator>>,
+
+    /// Cash and collateral per venue
+    capital: Arc<Mutex<CapitalTracker>>,
+
+    /// Recently submitted idempotency keys
+    idempotency: IdempotencyCache,
+
+    /// Order status polling for venues without streaming updates
+    status_poller: Option<Mutex<StatusPoller>>,
+
+    /// Order status changes detected by polling or status queries
+    order_updates: broadcast::Sender<OrderUpdate>,
+
+    /// Venue health tracking (None = order flow is never paused)
+    venue_status: Option<std::sync::Mutex<VenueStatusMonitor>>,
+
+    /// Venue state transitions
+    venue_status_changes: broadcast::Sender<VenueStatusChange>,
+
+    /// New orders held back after a venue throttled us
+    cooldowns: std::sync::Mutex<VenueCooldowns>,
+
+    /// Classified venue errors on order placement
+    venue_errors: broadcast::Sender<VenueErrorEvent>,
+
+    /// Time source for acks and order status changes
+    clock: SharedClock,
+
+    /// ID source for orders the engine creates
+    ids: SharedIdGenerator,
+
+    /// OpenTelemetry counters (None when metrics are disabled)
+    #[cfg(feature = "otel")]
+    otel_metrics: Option<crate::telemetry::ExecMetrics>,
 }
 
 impl ExecutionEngine {
@@ -71,8 +156,30 @@ impl ExecutionEngine {
             rate_limiters: HashMap::new(),
             order_tracker: Arc::new(OrderTracker::new()),
             validator: OrderValidator::new(),
+            #[cfg(feature = "otel")]
+            otel_metrics: config
+                .enable_metrics
+                .then(crate::telemetry::ExecMetrics::new),
             config,
-            positions: Arc::new(Mutex::new(HashMap::new())),
+            positions: Arc::new(PositionBook::new()),
+            position_venues: std::sync::Mutex::new(HashMap::new()),
+            triggers: Arc::new(Mutex::new(TriggerEngine::new())),
+            pegs: Arc::new(Mutex::new(PegManager::default())),
+            post_only: PostOnlyPolicy::default(),
+            touches: Arc::new(Mutex::new(HashMap::new())),
+            depths: Arc::new(Mutex::new(HashMap::new())),
+            symbols: Arc::new(RwLock::new(SymbolMapper::new())),
+            client_ids: None,
+            capital: Arc::new(Mutex::new(CapitalTracker::new())),
+            idempotency: IdempotencyCache::default(),
+            status_poller: None,
+            order_updates: broadcast::channel(ORDER_UPDATE_CAPACITY).0,
+            venue_status: None,
+            venue_status_changes: broadcast::channel(ORDER_UPDATE_CAPACITY).0,
+            cooldowns: std::sync::Mutex::new(VenueCooldowns::default()),
+            venue_errors: broadcast::channel(ORDER_UPDATE_CAPACITY).0,
+            clock: system_clock(),
+            ids: random_ids(),
         }
     }
 
@@ -89,94 +196,823 @@ impl ExecutionEngine {
         self.rate_limiters.insert(venue_id, rate_limiter);
     }
 
+    /// Rate limiter metrics for a venue
+    pub fn rate_limiter_stats(&self, venue: &VenueId) -> Option<RateLimiterStats> {
+        self.rate_limiters.get(venue).map(RateLimiter::stats)
+    }
+
+    /// Rate limiter metrics of every registered venue
+    pub fn rate_limiter_status(&self) -> HashMap<VenueId, RateLimiterStats> {
+        self.rate_limiters
+            .iter()
+            .map(|(venue, limiter)| (venue.clone(), limiter.stats()))
+            .collect()
+    }
+
     /// Set risk engine
     pub fn set_risk_engine(&mut self, risk_engine: RiskEngine) {
         info!("Setting risk engine");
         self.risk_engine = Some(Arc::new(Mutex::new(risk_engine)));
     }
 
+    /// Risk engine used for pre-trade checks, if set
+    pub fn risk_engine(&self) -> Option<Arc<Mutex<RiskEngine>>> {
+        self.risk_engine.clone()
+    }
+
+    /// Stamp acks and order status changes, and expire idempotency keys,
+    /// with `clock` instead of the system clock (e.g. a `MockClock` in
+    /// tests and backtests)
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        // The engine holds the only reference to its tracker
+        if let Some(tracker) = Arc::get_mut(&mut self.order_tracker) {
+            tracker.set_clock(clock.clone());
+        }
+        self.idempotency.set_clock(clock.clone());
+        self.clock = clock;
+    }
+
+    /// Draw the IDs of orders the engine creates (flattening,
+    /// liquidation, hedging) from `ids`
+    pub fn set_id_generator(&mut self, ids: SharedIdGenerator) {
+        self.ids = ids;
+    }
+
+    /// Time source of the engine
+    pub fn clock(&self) -> SharedClock {
+        self.clock.clone()
+    }
+
+    /// ID source of the engine
+    pub fn id_generator(&self) -> SharedIdGenerator {
+        self.ids.clone()
+    }
+
+    /// Assign client order IDs to orders submitted with an empty one
+    pub fn set_client_id_generator(&mut self, generator: ClientOrderIdGenerator) {
+        info!("Setting client order ID generator (prefix {})", generator.prefix());
+        self.client_ids = Some(Arc::new(generator));
+    }
+
+    /// Remember idempotency keys for `ttl` (default 5 minutes)
+    pub fn set_idempotency_ttl(&mut self, ttl: std::time::Duration) {
+        self.idempotency = IdempotencyCache::with_clock(ttl, self.clock.clone());
+    }
+
+    /// Set the canonical market to venue symbol mapping
+    pub fn set_symbol_mapper(&mut self, mapper: SymbolMapper) {
+        info!("Setting symbol mapper with {} markets", mapper.len());
+        self.symbols = Arc::new(RwLock::new(mapper));
+    }
+
+    /// Shared symbol mapper, for adapters and storage writers that need to
+    /// translate venue symbols back to canonical markets
+    pub fn symbol_mapper(&self) -> Arc<RwLock<SymbolMapper>> {
+        self.symbols.clone()
+    }
+
+    /// Set the post-only crossing protection policy
+    pub fn set_post_only_policy(&mut self, policy: PostOnlyPolicy) {
+        info!("Setting post-only policy: {:?}", policy);
+        self.post_only = policy;
+    }
+
+    /// Set the repricing limits for pegged orders
+    ///
+    /// Pegs tracked so far are dropped, so call this before submitting any.
+    pub fn set_peg_config(&mut self, config: PegConfig) {
+        info!("Setting peg config: {:?}", config);
+        self.pegs = Arc::new(Mutex::new(PegManager::new(config)));
+    }
+
+    /// Update the best bid/ask used for post-only crossing checks
+    ///
+    /// Use [`on_touch`](Self::on_touch) instead to also reprice pegged
+    /// orders.
+    pub async fn update_touch(&self, market: &MarketId, best_bid: Option<f64>, best_ask: Option<f64>) {
+        self.touches
+            .lock()
+            .await
+            .insert(market.clone(), Touch { best_bid, best_ask });
+    }
+
+    /// Update the visible book depth used to emulate FOK orders
+    pub async fn update_book_depth(&self, market: &MarketId, depth: BookDepth) {
+        self.depths.lock().await.insert(market.clone(), depth);
+    }
+
+    /// Latest known best bid/ask for a market
+    pub async fn get_touch(&self, market: &MarketId) -> Option<Touch> {
+        self.touches.lock().await.get(market).copied()
+    }
+
+    /// Persist armed stop orders to `path`, restoring any armed before a
+    /// restart; returns the number of restored stops
+    pub fn set_trigger_store(&mut self, path: impl AsRef<Path>) -> ExecResult<usize> {
+        let triggers = TriggerEngine::with_store(path)?;
+        for stop in triggers.armed() {
+            self.order_tracker.track_order(stop.order)?;
+        }
+
+        let restored = triggers.len();
+        self.triggers = Arc::new(Mutex::new(triggers));
+        Ok(restored)
+    }
+
     /// Submit an order with pre-trade risk checks
-    pub async fn submit_order(&self, mut order: Order) -> ExecResult<OrderAck> {
+    ///
+    /// Stop orders are validated and armed locally; risk checks run when
+    /// the child order is released by [`on_market_price`](Self::on_market_price).
+    ///
+    /// Submissions are idempotent per key (`Order::idempotency_key`, else a
+    /// non-empty client order ID): resubmitting within the TTL returns the
+    /// original ack, or the original order's state if it is still in
+    /// flight, without placing another order.
+    pub async fn submit_order(&self, order: Order) -> ExecResult<OrderAck> {
+        let key = order
+            .idempotency_key
+            .clone()
+            .or_else(|| Some(order.client_order_id.clone()).filter(|id| !id.is_empty()));
+        let Some(key) = key else {
+            return self.submit_new_order(order).await;
+        };
+
+        match self.idempotency.claim(&key, order.id) {
+            IdempotencyClaim::New => {}
+            IdempotencyClaim::Completed(ack) => {
+                info!("Duplicate submission for key {}, returning ack of {:?}", key, ack.order_id);
+                return Ok(ack);
+            }
+            IdempotencyClaim::InFlight(order_id) => {
+                info!("Duplicate submission for key {} while {:?} is in flight", key, order_id);
+                let status = self
+                    .order_tracker
+                    .get_order(&order_id)
+                    .map(|order| order.status)
+                    .unwrap_or(OrderStatus::Pending);
+                return Ok(OrderAck {
+                    order_id,
+                    venue_order_id: None,
+                    status,
+                    timestamp: self.clock.now(),
+                    message: Some(format!("Duplicate of in-flight submission (key {})", key)),
+                    tif_emulated: false,
+                });
+            }
+        }
+
+        let mut claim = ClaimGuard {
+            cache: &self.idempotency,
+            tracker: &self.order_tracker,
+            key: &key,
+            order_id: order.id,
+            completed: false,
+            outcome_unknown: true,
+        };
+        let result = self.submit_new_order(order).await;
+        match &result {
+            Ok(ack) => {
+                self.idempotency.complete(&key, ack.clone());
+                claim.completed = true;
+            }
+            Err(e) => claim.outcome_unknown = e.is_retryable(),
+        }
+        result
+    }
+
+    /// Submit an order without idempotency checks
+    async fn submit_new_order(&self, mut order: Order) -> ExecResult<OrderAck> {
         info!("Submitting order: {:?}", order.id);
 
+        if order.client_order_id.is_empty() {
+            if let Some(generator) = &self.client_ids {
+                order.client_order_id = generator.next_id(order.strategy_id.as_deref().unwrap_or("default"))?;
+            }
+        }
+
+        let touch = self.touches.lock().await.get(&order.market).copied();
+
+        // Price pegged orders from the touch
+        let peg_tick_size = match order.peg {
+            Some(peg) => {
+                check_peggable(&order)?;
+                let tick_size = self.peg_tick_size(&order.venue).await;
+                let price = touch.and_then(|touch| peg.price(order.side, &touch, tick_size));
+                let Some(price) = price else {
+                    return Err(ExecError::ValidationError(format!(
+                        "No {:?} to peg order {} to in {}",
+                        peg.reference, order.id, order.market
+                    )));
+                };
+                order.price = Some(price);
+                Some(tick_size)
+            }
+            None => None,
+        };
+
+        // Keep post-only orders from crossing the book
+        match self.post_only.enforce(&mut order, touch.as_ref()) {
+            Ok(true) => info!("Repriced post-only order {:?} to {:?}", order.id, order.price),
+            Ok(false) => {}
+            Err(e) => {
+                warn!("Post-only order {:?} would cross: {}", order.id, e);
+                #[cfg(feature = "otel")]
+                if let Some(metrics) = &self.otel_metrics {
+                    metrics.order_rejected(&order, "post_only");
+                }
+                return Err(e);
+            }
+        }
+
         // Validate order
         if self.config.enable_validation {
             debug!("Validating order: {:?}", order.id);
-            self.validator.validate(&order)?;
+            self.validator.validate(&order).inspect_err(|_| {
+                #[cfg(feature = "otel")]
+                if let Some(metrics) = &self.otel_metrics {
+                    metrics.order_rejected(&order, "validation");
+                }
+            })?;
         }
 
-        // Pre-trade risk check
-        if self.config.enable_risk_checks {
-            if let Some(risk_engine) = &self.risk_engine {
-                debug!("Running pre-trade risk check for order: {:?}", order.id);
+        // Arm stop orders locally instead of sending them to the venue
+        if order.order_type.is_stop() {
+            let stop_price = order.stop_price.unwrap_or_default();
+            order.update_status_at(OrderStatus::Pending, self.clock.now());
+            self.order_tracker.track_order(order.clone())?;
+            self.triggers.lock().await.arm(order.clone())?;
+
+            info!("Stop order armed: {:?} at {}", order.id, stop_price);
+            return Ok(OrderAck {
+                order_id: order.id,
+                venue_order_id: None,
+                status: OrderStatus::Pending,
+                timestamp: self.clock.now(),
+                message: Some(format!("Stop armed locally at {}", stop_price)),
+                tif_emulated: false,
+            });
+        }
 
-                let positions = self.positions.lock().await;
-                let current_position = positions.get(order.market.as_str()).copied().unwrap_or(0.0);
-                let inventory_value = positions.values().sum::<f64>();
+        // Reduce-only orders must shrink the position, and then skip risk
+        // checks so positions can still be closed under a kill-switch
+        if order.reduce_only && !self.reduces_position(&order).await {
+            let position = self.get_position(order.market.as_str()).await;
+            return Err(ExecError::ValidationError(format!(
+                "reduce-only {:?} of {} would not reduce position {} in {}",
+                order.side, order.size, position, order.market
+            )));
+        }
 
-                let proposed_size = match order.side {
-                    crate::order::Side::Buy => order.size,
-                    crate::order::Side::Sell => -order.size,
-                };
+        // Get venue adapter
+        let adapter = self
+            .adapters
+            .get(&order.venue)
+            .ok_or_else(|| ExecError::VenueNotSupported(order.venue.to_string()))?;
 
-                let risk_ctx = RiskContext {
-                    market_id: order.market.as_str().to_string(),
-                    current_position,
-                    proposed_size,
-                    inventory_value_usd: inventory_value,
-                };
+        // Pause order flow to degraded and down venues; a degraded venue
+        // still takes orders that reduce a position
+        let venue_state = self.venue_state(&order.venue);
+        if venue_state != VenueState::Up {
+            let reducing = order.reduce_only || self.reduces_position(&order).await;
+            if !venue_state.accepts(reducing) {
+                warn!("Order {:?} held back: venue {} is {}", order.id, order.venue, venue_state);
+                return Err(ExecError::VenueUnavailable {
+                    venue: order.venue.to_string(),
+                    message: format!("venue is {}", venue_state),
+                });
+            }
+        }
+
+        // Hold new orders while the venue is throttling us
+        if let Some(remaining) = self.venue_cooldown(&order.venue) {
+            warn!("Order {:?} held back: venue {} cooling down for {}ms", order.id, order.venue, remaining.as_millis());
+            return Err(ExecError::RateLimitExceeded {
+                venue: order.venue.to_string(),
+                message: format!("venue throttled requests, cooling down for {}ms", remaining.as_millis()),
+            });
+        }
+
+        // Check rate limit; orders that shrink the position jump ahead of
+        // new quotes
+        if let Some(rate_limiter) = self.rate_limiters.get(&order.venue) {
+            let priority = self.order_priority(&order).await;
+            debug!("Checking rate limit for venue: {} ({})", order.venue, priority);
+            rate_limiter.acquire(priority, order.strategy_id.as_deref()).await?;
+        }
 
+        let mut adapter = adapter.lock().await;
+
+        // Round to the venue's tick and lot sizes
+        if let Some(rounding) = adapter.rounding() {
+            if let Err(e) = rounding.apply(&mut order) {
+                warn!("Rejecting order {:?}: {}", order.id, e);
+                return Err(e);
+            }
+            if order.rounding.is_some_and(|r| r.changed()) {
+                debug!("Order {:?} rounded: {:?}", order.id, order.rounding);
+            }
+        }
+
+        // Emulate IOC/FOK for limit orders the venue can't handle natively
+        let emulate_tif = order.order_type == OrderType::Limit
+            && order.time_in_force != TimeInForce::GTC
+            && !adapter.supports_time_in_force(order.time_in_force);
+        if emulate_tif && order.time_in_force == TimeInForce::FOK {
+            let depth = self.depths.lock().await.get(&order.market).cloned();
+            if let Err(e) = check_fok_fillable(&order, depth.as_ref()) {
+                warn!("Rejecting emulated FOK order {:?}: {}", order.id, e);
+                #[cfg(feature = "otel")]
+                if let Some(metrics) = &self.otel_metrics {
+                    metrics.order_rejected(&order, "fok_unfillable");
+                }
+                return Err(e);
+            }
+        }
+
+        // Pre-trade risk check, last so that only orders sent to the venue
+        // count against the policies
+        if self.config.enable_risk_checks && !order.reduce_only {
+            if let Some(risk_engine) = &self.risk_engine {
+                debug!("Running pre-trade risk check for order: {:?}", order.id);
+
+                let risk_ctx = self.risk_context(&order).await;
                 let risk_engine = risk_engine.lock().await;
                 let decision = risk_engine.evaluate(&risk_ctx);
 
                 if !decision.allowed {
-                    warn!(
-                        "Risk check rejected order {:?}: {:?}",
-                        order.id, decision.violated_policies
-                    );
+                    warn!("Risk check rejected order {:?}: {}", order.id, decision);
+                    #[cfg(feature = "otel")]
+                    if let Some(metrics) = &self.otel_metrics {
+                        metrics.order_rejected(&order, "risk");
+                    }
                     return Err(ExecError::RiskRejected {
-                        policies: decision.violated_policies,
+                        violations: decision.violations,
                     });
                 }
 
-                debug!("Risk check passed for order: {:?}", order.id);
+                if decision.violations.is_empty() {
+                    debug!("Risk check passed for order: {:?}", order.id);
+                } else {
+                    warn!("Risk check passed order {:?} with warnings: {}", order.id, decision.messages().join("; "));
+                }
             }
         }
 
-        // Get venue adapter
+        // Update order status
+        order.update_status_at(OrderStatus::Submitting, self.clock.now());
+        self.order_tracker.track_order(order.clone())?;
+
+        // Place order via venue adapter, with the venue's symbol for the
+        // market (and as GTC when emulating)
+        let mut venue_order = order.clone();
+        venue_order.market = self.symbols.read().await.resolve(&order.market, &order.venue);
+        if emulate_tif {
+            venue_order.time_in_force = TimeInForce::GTC;
+        }
+        let result = adapter.place_order(&venue_order).await;
+        self.record_venue_request(&order.venue, result.as_ref().err());
+        let mut ack = match result {
+            Ok(ack) => {
+                self.cooldowns.lock().unwrap_or_else(|e| e.into_inner()).succeeded(&order.venue);
+                ack
+            }
+            Err(e) if e.is_post_only_reject() => {
+                warn!("Venue rejected post-only order {:?}: {}", order.id, e);
+                self.order_tracker.update_status(&order.id, OrderStatus::Rejected)?;
+                #[cfg(feature = "otel")]
+                if let Some(metrics) = &self.otel_metrics {
+                    metrics.order_rejected(&order, "post_only");
+                }
+                return Err(e);
+            }
+            Err(e) => {
+                if let Some(kind) = e.venue_error_kind() {
+                    // Rejections the venue definitely acted on free the
+                    // idempotency key for a retry
+                    if kind.is_definitive() {
+                        self.order_tracker.update_status(&order.id, OrderStatus::Rejected)?;
+                    }
+                    // Handling may query the venue again
+                    drop(adapter);
+                    self.on_venue_error(&order, &e, kind).await;
+                }
+                return Err(e);
+            }
+        };
+
+        // Update order status based on ack
+        self.order_tracker.update_status(&order.id, ack.status)?;
+
+        // Track pegged orders left working (replacements keep their peg ID)
+        if let Some(tick_size) = peg_tick_size {
+            order.update_status_at(ack.status, ack.timestamp);
+            if !order.is_terminal() {
+                let mut pegs = self.pegs.lock().await;
+                match pegs.peg_id_of(&order.id) {
+                    Some(peg_id) => pegs.replaced(&peg_id, order.clone()),
+                    None => pegs.add(order.clone(), tick_size)?,
+                }
+            }
+        }
+
+        #[cfg(feature = "otel")]
+        if let Some(metrics) = &self.otel_metrics {
+            metrics.order_submitted(&order);
+        }
+
+        // Cancel whatever did not fill immediately
+        if emulate_tif {
+            ack.tif_emulated = true;
+            if matches!(
+                ack.status,
+                OrderStatus::Pending | OrderStatus::Submitting | OrderStatus::Working | OrderStatus::PartiallyFilled
+            ) {
+                // The order is live: a failed cancel is reported on the
+                // ack rather than as an error, which would lose the order
+                let cancel = match self.rate_limiters.get(&order.venue) {
+                    Some(rate_limiter) => rate_limiter.acquire(RequestPriority::Cancel, None).await,
+                    None => Ok(()),
+                };
+                let cancel = match cancel {
+                    Ok(()) => adapter.cancel_order(&order.id).await,
+                    Err(e) => Err(e),
+                };
+                let failure = match cancel {
+                    Ok(cancel) if cancel.success => None,
+                    Ok(cancel) => Some(cancel.message.unwrap_or_else(|| "not cancelled".to_string())),
+                    Err(e) => Some(e.to_string()),
+                };
+                match failure {
+                    None => {
+                        self.order_tracker.update_status(&order.id, OrderStatus::Cancelled)?;
+                        ack.status = OrderStatus::Cancelled;
+                        ack.message = Some(format!(
+                            "{} emulated: unfilled remainder cancelled",
+                            order.time_in_force
+                        ));
+                    }
+                    Some(reason) => {
+                        warn!(
+                            "Failed to cancel remainder of emulated {} order {:?}: {}",
+                            order.time_in_force, order.id, reason
+                        );
+                        ack.message = Some(format!(
+                            "{} emulated: remainder cancel failed ({}), the remainder may still rest",
+                            order.time_in_force, reason
+                        ));
+                    }
+                }
+            }
+        }
+
+        info!("Order submitted successfully: {:?}", order.id);
+        Ok(ack)
+    }
+
+    /// Simulate submitting an order without placing it
+    ///
+    /// Runs the checks of [`submit_order`](Self::submit_order) (pegging,
+    /// post-only protection, validation, reduce-only and venue checks,
+    /// rounding and the pre-trade risk check), reports rate limit
+    /// availability and whether the venue has the free cash for a buy,
+    /// and finds the largest size that would pass. Nothing is tracked,
+    /// armed or counted: the risk check is a
+    /// [`RiskEngine::dry_run`](ag_risk::RiskEngine::dry_run) and no rate
+    /// limit slot is taken.
+    pub async fn preflight(&self, order: &Order) -> PreflightReport {
+        let mut placed = order.clone();
+        let validation_error = self.preflight_checks(&mut placed).await.err().map(|e| e.to_string());
+        let stop = order.order_type.is_stop();
+
+        let risk_engine = self
+            .risk_engine
+            .as_ref()
+            .filter(|_| self.config.enable_risk_checks && !order.reduce_only && !stop);
+        let risk = match risk_engine {
+            Some(risk_engine) => {
+                let risk_ctx = self.risk_context(&placed).await;
+                Some(risk_engine.lock().await.dry_run(&risk_ctx))
+            }
+            None => None,
+        };
+
+        let rate_limit = self.rate_limiters.get(&order.venue).filter(|_| !stop).map(|limiter| {
+            let stats = limiter.stats();
+            RateLimitCheck {
+                tokens_remaining: stats.tokens_remaining,
+                wait: stats.time_to_next_token,
+            }
+        });
+
+        // Free cash of the venue, for buys at a known price
+        let free = match (placed.side, placed.price) {
+            (crate::order::Side::Buy, Some(_)) if !stop => self
+                .capital()
+                .await
+                .ok()
+                .and_then(|capital| capital.venue(&order.venue).map(|venue| venue.free)),
+            _ => None,
+        };
+        let balance = free.zip(placed.price).map(|(free, price)| BalanceCheck {
+            required: price * placed.size,
+            free,
+        });
+
+        let max_size = self.preflight_max_size(order, &placed, risk_engine, free).await;
+
+        PreflightReport {
+            order: placed,
+            validation_error,
+            risk,
+            rate_limit,
+            balance,
+            venue_state: self.venue_state(&order.venue),
+            max_size,
+        }
+    }
+
+    /// Apply the order changes and checks of a submission short of the
+    /// risk check, without side effects
+    async fn preflight_checks(&self, order: &mut Order) -> ExecResult<()> {
+        let touch = self.touches.lock().await.get(&order.market).copied();
+        if let Some(peg) = order.peg {
+            check_peggable(order)?;
+            let tick_size = self.peg_tick_size(&order.venue).await;
+            let price = touch.and_then(|touch| peg.price(order.side, &touch, tick_size));
+            let Some(price) = price else {
+                return Err(ExecError::ValidationError(format!(
+                    "No {:?} to peg order {} to in {}",
+                    peg.reference, order.id, order.market
+                )));
+            };
+            order.price = Some(price);
+        }
+
+        self.post_only.enforce(order, touch.as_ref())?;
+        if self.config.enable_validation {
+            self.validator.validate(order)?;
+        }
+        if order.order_type.is_stop() {
+            return Ok(());
+        }
+
+        let reducing = self.reduces_position(order).await;
+        if order.reduce_only && !reducing {
+            let position = self.get_position(order.market.as_str()).await;
+            return Err(ExecError::ValidationError(format!(
+                "reduce-only {:?} of {} would not reduce position {} in {}",
+                order.side, order.size, position, order.market
+            )));
+        }
+
         let adapter = self
             .adapters
             .get(&order.venue)
             .ok_or_else(|| ExecError::VenueNotSupported(order.venue.to_string()))?;
+        let venue_state = self.venue_state(&order.venue);
+        if !venue_state.accepts(order.reduce_only || reducing) {
+            return Err(ExecError::VenueUnavailable {
+                venue: order.venue.to_string(),
+                message: format!("venue is {}", venue_state),
+            });
+        }
 
-        // Check rate limit
-        if let Some(rate_limiter) = self.rate_limiters.get(&order.venue) {
-            debug!("Checking rate limit for venue: {}", order.venue);
-            rate_limiter.check().await?;
+        let adapter = adapter.lock().await;
+        if let Some(rounding) = adapter.rounding() {
+            rounding.apply(order)?;
         }
+        let emulate_fok = order.order_type == OrderType::Limit
+            && order.time_in_force == TimeInForce::FOK
+            && !adapter.supports_time_in_force(order.time_in_force);
+        if emulate_fok {
+            let depth = self.depths.lock().await.get(&order.market).cloned();
+            check_fok_fillable(order, depth.as_ref())?;
+        }
+        Ok(())
+    }
 
-        // Update order status
-        order.update_status(OrderStatus::Submitting);
-        self.order_tracker.track_order(order.clone())?;
+    /// Largest size up to the order's that passes validation, risk and
+    /// balance checks (see [`PreflightReport::max_size`])
+    async fn preflight_max_size(
+        &self,
+        order: &Order,
+        placed: &Order,
+        risk_engine: Option<&Arc<Mutex<RiskEngine>>>,
+        free: Option<f64>,
+    ) -> Option<f64> {
+        let mut upper = order.size;
+        if self.config.enable_validation {
+            upper = upper.min(self.validator.max_size());
+        }
+        if let (Some(free), Some(price)) = (free, placed.price.filter(|price| *price > 0.0)) {
+            upper = upper.min(free.max(0.0) / price);
+        }
 
-        // Place order via venue adapter
-        let mut adapter = adapter.lock().await;
-        let ack = adapter.place_order(&order).await?;
+        // Sizes are searched in whole lots
+        let lot_size = match self.adapters.get(&order.venue) {
+            Some(adapter) => adapter.lock().await.rounding().map(|rounding| rounding.lot_size),
+            None => None,
+        };
+        let step = lot_size
+            .or(self.config.enable_validation.then(|| self.validator.min_size()))
+            .filter(|step| *step > 0.0)
+            .unwrap_or(PREFLIGHT_SIZE_STEP);
+        let mut lots = (upper / step + 1e-9).floor() as u64;
+
+        if let Some(risk_engine) = risk_engine {
+            let mut risk_ctx = self.risk_context(placed).await;
+            let sign = match order.side {
+                crate::order::Side::Buy => 1.0,
+                crate::order::Side::Sell => -1.0,
+            };
+            let risk_engine = risk_engine.lock().await;
+            let mut passes = |lots: u64| {
+                risk_ctx.proposed_size = sign * lots as f64 * step;
+                risk_engine.dry_run(&risk_ctx).allowed
+            };
+            if !passes(lots) {
+                let (mut lo, mut hi) = (0, lots);
+                while hi - lo > 1 {
+                    let mid = lo + (hi - lo) / 2;
+                    if passes(mid) {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                lots = lo;
+            }
+        }
+        if lots == 0 {
+            return None;
+        }
 
-        // Update order status based on ack
-        self.order_tracker.update_status(&order.id, ack.status)?;
+        // The size must clear every other check too
+        let size = lots as f64 * step;
+        let mut candidate = order.clone();
+        candidate.size = size;
+        self.preflight_checks(&mut candidate).await.ok()?;
+        Some(candidate.size)
+    }
 
-        info!("Order submitted successfully: {:?}", order.id);
-        Ok(ack)
+    /// Feed a market price to the stop trigger engine
+    ///
+    /// Child orders of triggered stops are submitted immediately; returns
+    /// one result per released child.
+    pub async fn on_market_price(
+        &self,
+        market: &MarketId,
+        price: f64,
+    ) -> ExecResult<Vec<ExecResult<OrderAck>>> {
+        let children = self.triggers.lock().await.on_price(market, price)?;
+
+        let mut results = Vec::with_capacity(children.len());
+        for child in children {
+            let order_id = child.id;
+            // Children inherit the parent's client ID, which already holds its key
+            let result = self.submit_new_order(child).await;
+            if let Err(e) = &result {
+                error!("Triggered stop {:?} failed to submit: {}", order_id, e);
+                self.order_tracker.update_status(&order_id, OrderStatus::Rejected)?;
+            }
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Update the touch of a market and reprice the pegged orders in it
+    ///
+    /// Each repriced order is cancelled and replaced at the new peg price;
+    /// returns one result per replacement.
+    pub async fn on_touch(
+        &self,
+        market: &MarketId,
+        best_bid: Option<f64>,
+        best_ask: Option<f64>,
+    ) -> ExecResult<Vec<ExecResult<OrderAck>>> {
+        self.update_touch(market, best_bid, best_ask).await;
+        let touch = Touch { best_bid, best_ask };
+        let reprices = self.pegs.lock().await.on_touch(market, &touch, self.clock.now());
+
+        let mut results = Vec::with_capacity(reprices.len());
+        for reprice in reprices {
+            results.push(self.reprice_peg(reprice).await);
+        }
+
+        Ok(results)
+    }
+
+    /// Cancel the working order of a peg and place its replacement
+    async fn reprice_peg(&self, reprice: Reprice) -> ExecResult<OrderAck> {
+        let peg_id = reprice.peg_id;
+        let cancel = match self.cancel_working_order(reprice.order.id).await {
+            Ok(cancel) => cancel,
+            Err(e) => {
+                warn!("Failed to cancel pegged order {:?} for repricing: {}", reprice.order.id, e);
+                return Err(e);
+            }
+        };
+        if !cancel.success {
+            // Most likely filled in the meantime; the peg is done either way
+            self.pegs.lock().await.remove(&peg_id);
+            return Err(ExecError::InvalidOrderState {
+                order_id: reprice.order.id,
+                current_state: cancel.message.unwrap_or_else(|| "not cancelled".to_string()),
+                operation: "reprice".to_string(),
+            });
+        }
+
+        let replacement = reprice.replacement(OrderId::generate(&*self.ids));
+        let replacement_id = replacement.id;
+        self.pegs.lock().await.replaced(&peg_id, replacement.clone());
+
+        debug!("Repricing peg {:?} to {} as {:?}", peg_id, reprice.price, replacement_id);
+        let result = self.submit_new_order(replacement).await;
+        if let Err(e) = &result {
+            error!("Replacement {:?} of peg {:?} failed to submit: {}", replacement_id, peg_id, e);
+            self.pegs.lock().await.remove(&peg_id);
+        }
+        result
+    }
+
+    /// Pegged orders currently tracked
+    pub async fn get_pegged_orders(&self) -> Vec<PeggedOrder> {
+        self.pegs.lock().await.pegs()
+    }
+
+    /// Tick size pegged orders on a venue are priced on
+    async fn peg_tick_size(&self, venue: &VenueId) -> f64 {
+        let configured = match self.adapters.get(venue) {
+            Some(adapter) => adapter.lock().await.rounding().map(|rounding| rounding.tick_size),
+            None => None,
+        };
+        configured
+            .filter(|tick_size| *tick_size > 0.0)
+            .unwrap_or(self.pegs.lock().await.config().default_tick_size)
+    }
+
+    /// Stop orders currently armed locally
+    pub async fn get_armed_stops(&self) -> Vec<Order> {
+        self.triggers
+            .lock()
+            .await
+            .armed()
+            .into_iter()
+            .map(|stop| stop.order)
+            .collect()
     }
 
     /// Cancel an order
     pub async fn cancel_order(&self, order_id: OrderId) -> ExecResult<CancelAck> {
         info!("Cancelling order: {:?}", order_id);
 
+        // Armed stops never reached the venue
+        if self.triggers.lock().await.disarm(&order_id)?.is_some() {
+            self.order_tracker.update_status(&order_id, OrderStatus::Cancelled)?;
+            info!("Stop order disarmed: {:?}", order_id);
+            return Ok(CancelAck {
+                order_id,
+                venue_order_id: None,
+                success: true,
+                timestamp: self.clock.now(),
+                message: Some("Stop disarmed locally".to_string()),
+                outcome: CancelOutcome::Cancelled,
+            });
+        }
+
+        // Cancelling a peg (by its peg ID or working order) cancels the
+        // order currently working for it
+        let order_id = match self.pegs.lock().await.remove(&order_id) {
+            Some(peg) => peg.order.id,
+            None => order_id,
+        };
+
+        self.cancel_working_order(order_id).await
+    }
+
+    /// Cancel an order at its venue
+    ///
+    /// Cancels are safe to retry: an order already cancelled is
+    /// acknowledged again without a venue request. When the venue refuses
+    /// the cancel or its answer is ambiguous (timeout, unknown order), the
+    /// order's status is queried and the tracked order converges to what
+    /// the venue reports; `CancelAck::outcome` tells which case applied.
+    async fn cancel_working_order(&self, order_id: OrderId) -> ExecResult<CancelAck> {
         // Get order details
         let order = self.order_tracker.get_order(&order_id)?;
 
+        if order.status == OrderStatus::Cancelled {
+            return Ok(CancelAck {
+                order_id,
+                venue_order_id: None,
+                success: true,
+                timestamp: self.clock.now(),
+                message: Some("Already cancelled".to_string()),
+                outcome: CancelOutcome::Cancelled,
+            });
+        }
+
         // Check if order can be cancelled
         if order.is_terminal() {
             return Err(ExecError::InvalidOrderState {
@@ -192,9 +1028,9 @@ impl ExecutionEngine {
             .get(&order.venue)
             .ok_or_else(|| ExecError::VenueNotSupported(order.venue.to_string()))?;
 
-        // Check rate limit
+        // Check rate limit (cancels take priority over everything else)
         if let Some(rate_limiter) = self.rate_limiters.get(&order.venue) {
-            rate_limiter.check().await?;
+            rate_limiter.acquire(RequestPriority::Cancel, None).await?;
         }
 
         // Update status
@@ -202,19 +1038,357 @@ impl ExecutionEngine {
 
         // Cancel via venue adapter
         let mut adapter = adapter.lock().await;
-        let ack = adapter.cancel_order(&order_id).await?;
+        let result = adapter.cancel_order(&order_id).await;
+        self.record_venue_request(&order.venue, result.as_ref().err());
+        let mut ack = match result {
+            Ok(ack) => ack,
+            Err(e) if is_ambiguous_cancel_error(&e) => {
+                warn!("Cancel of order {:?} ambiguous: {}", order_id, e);
+                CancelAck {
+                    order_id,
+                    venue_order_id: None,
+                    success: false,
+                    timestamp: self.clock.now(),
+                    message: Some(e.to_string()),
+                    outcome: CancelOutcome::Unresolved,
+                }
+            }
+            Err(e) => {
+                // The venue did not act on the cancel
+                self.order_tracker.update_status(&order_id, order.status)?;
+                return Err(e);
+            }
+        };
+
+        if !ack.success {
+            // Ask the venue where the order ended up
+            let (outcome, status) = match adapter.get_order_status(&order_id).await {
+                Ok(OrderStatus::Cancelled) => (CancelOutcome::Cancelled, OrderStatus::Cancelled),
+                Ok(OrderStatus::Filled) => (CancelOutcome::AlreadyFilled, OrderStatus::Filled),
+                Ok(status @ (OrderStatus::Expired | OrderStatus::Rejected)) => (CancelOutcome::AlreadyClosed, status),
+                Ok(status) => (CancelOutcome::StillWorking, status),
+                Err(ExecError::OrderNotFound(_)) => (CancelOutcome::UnknownOrder, OrderStatus::Cancelled),
+                Err(e) => {
+                    warn!("Status query after failed cancel of {:?} failed: {}", order_id, e);
+                    (CancelOutcome::Unresolved, order.status)
+                }
+            };
+            ack.outcome = outcome;
+            ack.success = status == OrderStatus::Cancelled;
+
+            let mut cancelling = order.clone();
+            cancelling.status = OrderStatus::Cancelling;
+            if status != OrderStatus::Cancelled {
+                self.publish_order_update(OrderUpdate::new(&cancelling, status))?;
+            }
+        }
 
         // Update final status
         if ack.success {
             self.order_tracker.update_status(&order_id, OrderStatus::Cancelled)?;
-            info!("Order cancelled successfully: {:?}", order_id);
+
+            #[cfg(feature = "otel")]
+            if let Some(metrics) = &self.otel_metrics {
+                metrics.order_cancelled(&order.venue);
+            }
+
+            info!("Order cancelled successfully: {:?} ({:?})", order_id, ack.outcome);
+        } else if ack.outcome.is_closed() {
+            info!("Order {:?} closed before its cancel: {:?}", order_id, ack.outcome);
         } else {
-            error!("Order cancellation failed: {:?}", order_id);
+            error!("Order cancellation failed: {:?} ({:?})", order_id, ack.outcome);
         }
 
         Ok(ack)
     }
 
+    /// Risk context of an order against the current positions
+    async fn risk_context(&self, order: &Order) -> RiskContext {
+        let positions = self.positions.snapshot();
+        let proposed_size = match order.side {
+            crate::order::Side::Buy => order.size,
+            crate::order::Side::Sell => -order.size,
+        };
+        RiskContext {
+            market_id: order.market.as_str().to_string(),
+            current_position: positions.get(order.market.as_str()),
+            proposed_size,
+            inventory_value_usd: positions.net(),
+        }
+    }
+
+    /// Rate limit priority of an order: risk-reducing if it shrinks the
+    /// current position in its market, otherwise a new quote
+    async fn order_priority(&self, order: &Order) -> RequestPriority {
+        if self.reduces_position(order).await {
+            RequestPriority::RiskReducing
+        } else {
+            RequestPriority::NewQuote
+        }
+    }
+
+    /// Whether the order shrinks the position without flipping it
+    async fn reduces_position(&self, order: &Order) -> bool {
+        let position = self.positions.get(order.market.as_str());
+        match order.side {
+            crate::order::Side::Buy => position < 0.0 && order.size <= -position,
+            crate::order::Side::Sell => position > 0.0 && order.size <= position,
+        }
+    }
+
+    /// Trade blotter of all tracked orders and their fills
+    pub fn trade_blotter(&self) -> ExecResult<TradeBlotter> {
+        TradeBlotter::from_tracker(&self.order_tracker)
+    }
+
+    /// Poll order statuses on venues without streaming updates
+    pub fn enable_status_polling(&mut self, config: StatusPollConfig) {
+        info!("Order status polling enabled (venues: {:?})", config.venues);
+        self.status_poller = Some(Mutex::new(StatusPoller::new(config)));
+    }
+
+    /// Track venue health and pause order flow to degraded and down venues
+    pub fn enable_venue_status(&mut self, config: VenueStatusConfig) {
+        info!("Venue status monitoring enabled (health checks every {}ms)", config.health_interval_ms);
+        self.venue_status = Some(std::sync::Mutex::new(VenueStatusMonitor::new(config)));
+    }
+
+    /// Current state of a venue (always up without venue status monitoring)
+    pub fn venue_state(&self, venue: &VenueId) -> VenueState {
+        match &self.venue_status {
+            Some(monitor) => monitor.lock().unwrap_or_else(|e| e.into_inner()).state(venue),
+            None => VenueState::Up,
+        }
+    }
+
+    /// Status of every monitored venue with recorded activity
+    pub fn venue_statuses(&self) -> HashMap<VenueId, VenueStatus> {
+        match &self.venue_status {
+            Some(monitor) => monitor.lock().unwrap_or_else(|e| e.into_inner()).statuses(),
+            None => HashMap::new(),
+        }
+    }
+
+    /// Subscribe to venue state transitions
+    pub fn subscribe_venue_status(&self) -> broadcast::Receiver<VenueStatusChange> {
+        self.venue_status_changes.subscribe()
+    }
+
+    /// Poll every venue's health endpoint, returning the resulting state
+    /// transitions (also published to `subscribe_venue_status`)
+    ///
+    /// A health check that errors counts as failed.
+    pub async fn check_venue_health(&self) -> Vec<VenueStatusChange> {
+        let Some(monitor) = &self.venue_status else {
+            return Vec::new();
+        };
+        let mut changes = Vec::new();
+        for (venue, adapter) in &self.adapters {
+            let healthy = match adapter.lock().await.health_check().await {
+                Ok(healthy) => healthy,
+                Err(e) => {
+                    debug!("Health check of {} failed: {}", venue, e);
+                    false
+                }
+            };
+            let change = monitor
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record_health_check(venue, healthy, self.clock.now());
+            changes.extend(change.map(|change| self.publish_venue_change(change)));
+        }
+        changes
+    }
+
+    /// Set how long new orders are held back after a venue throttles us
+    pub fn set_cooldown_config(&mut self, config: CooldownConfig) {
+        self.cooldowns = std::sync::Mutex::new(VenueCooldowns::new(config));
+    }
+
+    /// Time left before new orders are sent to a venue that throttled us
+    pub fn venue_cooldown(&self, venue: &VenueId) -> Option<std::time::Duration> {
+        self.cooldowns.lock().unwrap_or_else(|e| e.into_inner()).remaining(venue, self.clock.now())
+    }
+
+    /// Subscribe to classified venue errors on order placement
+    pub fn subscribe_venue_errors(&self) -> broadcast::Receiver<VenueErrorEvent> {
+        self.venue_errors.subscribe()
+    }
+
+    /// Apply the handling for a class of venue error and publish it
+    ///
+    /// Throttling starts an adaptive cooldown, a balance rejection
+    /// refreshes the venue's balance and reports the bankroll to the risk
+    /// engine right away, maintenance counts as a failed health check, and
+    /// an unrecognized error is logged as an alert.
+    async fn on_venue_error(&self, order: &Order, error: &ExecError, kind: VenueErrorKind) {
+        let now = self.clock.now();
+        match kind {
+            VenueErrorKind::RateLimited => {
+                let cooldown =
+                    self.cooldowns.lock().unwrap_or_else(|e| e.into_inner()).throttled(&order.venue, now);
+                warn!(
+                    "Venue {} throttled order {:?}: holding new orders for {}ms",
+                    order.venue,
+                    order.id,
+                    cooldown.as_millis()
+                );
+            }
+            VenueErrorKind::InsufficientBalance => {
+                warn!("Venue {} rejected order {:?} for insufficient balance: {}", order.venue, order.id, error);
+                self.refresh_balance(&order.venue).await;
+            }
+            VenueErrorKind::Maintenance => {
+                warn!("Venue {} is in maintenance: {}", order.venue, error);
+                if let Some(monitor) = &self.venue_status {
+                    let change =
+                        monitor.lock().unwrap_or_else(|e| e.into_inner()).record_health_check(&order.venue, false, now);
+                    if let Some(change) = change {
+                        self.publish_venue_change(change);
+                    }
+                }
+            }
+            VenueErrorKind::InvalidPrice => {
+                warn!("Venue {} rejected the price {:?} of order {:?}: {}", order.venue, order.price, order.id, error);
+            }
+            VenueErrorKind::Unknown => {
+                error!("Unrecognized error from venue {} for order {:?}: {}", order.venue, order.id, error);
+            }
+        }
+
+        let (code, message) = match error {
+            ExecError::VenueError { code, message, .. } => (code.clone(), message.clone()),
+            _ => (None, error.to_string()),
+        };
+        // No subscribers is fine
+        let _ = self.venue_errors.send(VenueErrorEvent {
+            venue: order.venue.clone(),
+            order_id: order.id,
+            kind,
+            code,
+            message,
+            at: now,
+        });
+    }
+
+    /// Count an order or cancel outcome towards the venue's error rate
+    fn record_venue_request(&self, venue: &VenueId, error: Option<&ExecError>) {
+        let Some(monitor) = &self.venue_status else {
+            return;
+        };
+        let failed = error.is_some_and(ExecError::is_venue_fault);
+        let change = monitor
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_request(venue, failed, self.clock.now());
+        if let Some(change) = change {
+            self.publish_venue_change(change);
+        }
+    }
+
+    /// Log and broadcast a venue state transition
+    fn publish_venue_change(&self, change: VenueStatusChange) -> VenueStatusChange {
+        if change.to > change.from {
+            warn!("Venue {} is {} ({}): pausing order flow", change.venue, change.to, change.reason);
+        } else {
+            info!("Venue {} is {} ({}): resuming order flow", change.venue, change.to, change.reason);
+        }
+        // No subscribers is fine
+        let _ = self.venue_status_changes.send(change.clone());
+        change
+    }
+
+    /// Subscribe to order status changes detected by polling or `get_status`
+    pub fn subscribe_order_updates(&self) -> broadcast::Receiver<OrderUpdate> {
+        self.order_updates.subscribe()
+    }
+
+    /// Run one round of order status polling
+    ///
+    /// Working orders on polled venues are queried when due, within the
+    /// polling share of each venue's rate limit; polls that would exceed the
+    /// limiter are deferred to the next round. Detected changes update the
+    /// order tracker, are published to `subscribe_order_updates` and
+    /// returned. Does nothing unless polling is enabled.
+    pub async fn poll_order_statuses(&self) -> ExecResult<Vec<OrderUpdate>> {
+        let Some(poller) = &self.status_poller else {
+            return Ok(Vec::new());
+        };
+        let mut poller = poller.lock().await;
+
+        // Orders still being submitted get their status from the ack
+        let orders: HashMap<OrderId, Order> = self
+            .order_tracker
+            .get_active_orders()?
+            .into_iter()
+            .filter(|o| !matches!(o.status, OrderStatus::Pending | OrderStatus::Submitting))
+            .filter(|o| self.adapters.contains_key(&o.venue) && poller.polls_venue(&o.venue))
+            .map(|o| (o.id, o))
+            .collect();
+        let working: Vec<(OrderId, VenueId)> = orders.values().map(|o| (o.id, o.venue.clone())).collect();
+
+        let now = Instant::now();
+        poller.sync(&working, now);
+        let rates: HashMap<VenueId, u32> = self
+            .rate_limiters
+            .iter()
+            .map(|(venue, limiter)| (venue.clone(), limiter.requests_per_second()))
+            .collect();
+        let due = poller.due(now, &rates);
+
+        let near_fill_bps = poller.config().near_fill_bps;
+        let mut updates = Vec::new();
+        for order_id in due {
+            let order = &orders[&order_id];
+            if let Some(rate_limiter) = self.rate_limiters.get(&order.venue) {
+                if rate_limiter.try_acquire(RequestPriority::NewQuote, None).is_err() {
+                    poller.refund(&order_id);
+                    continue;
+                }
+            }
+
+            let adapter = &self.adapters[&order.venue];
+            let status = match adapter.lock().await.get_order_status(&order_id).await {
+                Ok(status) => status,
+                Err(e) => {
+                    warn!("Status poll failed for order {:?}: {}", order_id, e);
+                    poller.record(&order_id, false, false, Instant::now());
+                    continue;
+                }
+            };
+
+            let changed = status != order.status;
+            if changed {
+                let update = OrderUpdate::new(order, status);
+                self.publish_order_update(update.clone())?;
+                updates.push(update);
+            }
+
+            let touch = self.touches.lock().await.get(&order.market).copied();
+            let mut polled = order.clone();
+            polled.status = status;
+            let near = near_fill(&polled, touch.as_ref(), near_fill_bps);
+            poller.record(&order_id, changed, near, Instant::now());
+        }
+
+        if !updates.is_empty() {
+            debug!("Status polling detected {} order update(s)", updates.len());
+        }
+        Ok(updates)
+    }
+
+    /// Apply a detected status change to the tracker and notify subscribers
+    fn publish_order_update(&self, update: OrderUpdate) -> ExecResult<()> {
+        info!(
+            "Order {:?} on {}: {} -> {}",
+            update.order_id, update.venue, update.previous, update.status
+        );
+        self.order_tracker.update_status(&update.order_id, update.status)?;
+        // No subscribers is fine
+        let _ = self.order_updates.send(update);
+        Ok(())
+    }
+
     /// Get order status
     pub async fn get_status(&self, order_id: OrderId) -> ExecResult<OrderStatus> {
         debug!("Getting status for order: {:?}", order_id);
@@ -236,7 +1410,9 @@ impl ExecutionEngine {
         let status = adapter.get_order_status(&order_id).await?;
 
         // Update cached status
-        self.order_tracker.update_status(&order_id, status)?;
+        if status != order.status {
+            self.publish_order_update(OrderUpdate::new(&order, status))?;
+        }
 
         Ok(status)
     }
@@ -247,37 +1423,209 @@ impl ExecutionEngine {
 
         // Record fill in tracker
         self.order_tracker.record_fill(&fill.order_id, fill.clone())?;
+        self.pegs.lock().await.on_fill(&fill.order_id, fill.size, fill.price);
 
         // Update positions
         let order = self.order_tracker.get_order(&fill.order_id)?;
-        let mut positions = self.positions.lock().await;
+
+        #[cfg(feature = "otel")]
+        if let Some(metrics) = &self.otel_metrics {
+            metrics.fill(&order, fill.size);
+        }
+
+        self.capital.lock().await.on_fill(&order, &fill);
+        self.update_bankroll().await;
 
         let position_delta = match order.side {
             crate::order::Side::Buy => fill.size,
             crate::order::Side::Sell => -fill.size,
         };
 
-        *positions.entry(order.market.as_str().to_string()).or_insert(0.0) += position_delta;
+        // Margin book for `CapitalAtRiskLimit` policies, venue positions
+        // for `ExposureAcrossVenues`
+        if let Some(risk_engine) = &self.risk_engine {
+            let risk_engine = risk_engine.lock().await;
+            risk_engine.record_fill(order.market.as_str(), position_delta, fill.price);
+            risk_engine.record_venue_fill(order.market.as_str(), order.venue.as_str(), position_delta);
+        }
 
-        debug!(
-            "Updated position for {}: {}",
-            order.market,
-            positions.get(order.market.as_str()).unwrap()
-        );
+        let position = self.positions.add(order.market.as_str(), position_delta);
+        debug!("Updated position for {}: {}", order.market, position);
 
         Ok(())
     }
 
+    /// Set a position reported by a venue (e.g. when bootstrapping after a
+    /// restart, see [`crate::bootstrap`])
+    ///
+    /// Replaces the local position in `market` and the risk engine's
+    /// position on `venue`. With a known entry price the difference is also
+    /// booked in the risk engine's margin book.
+    pub async fn import_position(&self, venue: &VenueId, market: &MarketId, size: f64, price: Option<f64>) {
+        let previous = self.positions.set(market.as_str(), size);
+        self.position_venues
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(market.clone(), venue.clone());
+
+        if let Some(risk_engine) = &self.risk_engine {
+            let risk_engine = risk_engine.lock().await;
+            risk_engine.set_venue_position(market.as_str(), venue.as_str(), size);
+            if let Some(price) = price {
+                risk_engine.record_fill(market.as_str(), size - previous, price);
+            }
+        }
+        info!("Imported position {} in {} from {} (was {})", size, market, venue, previous);
+    }
+
+    /// Settle the position in a resolved market at `payout` per share
+    ///
+    /// Clears the local position and books the settlement in the risk
+    /// engine's margin book as a fill of the whole position at the payout.
+    /// Returns the settled size.
+    pub async fn settle_position(&self, market: &MarketId, payout: f64) -> f64 {
+        let size = self.positions.remove(market.as_str());
+        if let Some(risk_engine) = &self.risk_engine {
+            risk_engine.lock().await.clear_venue_positions(market.as_str());
+        }
+        if size.abs() > 1e-8 {
+            if let Some(risk_engine) = &self.risk_engine {
+                risk_engine.lock().await.record_fill(market.as_str(), -size, payout);
+            }
+            info!("Settled position {} in {} at {}", size, market, payout);
+        }
+        size
+    }
+
     /// Get current position for a market
     pub async fn get_position(&self, market_id: &str) -> f64 {
-        let positions = self.positions.lock().await;
-        positions.get(market_id).copied().unwrap_or(0.0)
+        self.positions.get(market_id)
     }
 
     /// Get all positions
     pub async fn get_all_positions(&self) -> HashMap<String, f64> {
-        let positions = self.positions.lock().await;
-        positions.clone()
+        self.positions.snapshot().sizes().clone()
+    }
+
+    /// Current positions, read without locking (safe to call from
+    /// latency-sensitive code outside the async runtime)
+    pub fn position_snapshot(&self) -> Arc<PositionSnapshot> {
+        self.positions.snapshot()
+    }
+
+    /// Close the position in a market with a market order
+    ///
+    /// The order goes to the venue of the most recent order in the market
+    /// and is reduce-only, so it is sent even under a kill-switch. Returns
+    /// `None` if the position is already flat. See [`crate::liquidation`]
+    /// for priced exits tracked until flat.
+    pub async fn flatten_position(&self, market: &MarketId) -> ExecResult<Option<OrderAck>> {
+        let position = self.get_position(market.as_str()).await;
+        if position.abs() < 1e-8 {
+            return Ok(None);
+        }
+
+        let venue = self.position_venue(market)?;
+
+        let side = if position > 0.0 {
+            crate::order::Side::Sell
+        } else {
+            crate::order::Side::Buy
+        };
+        info!("Flattening position {} in {} on {}", position, market, venue);
+
+        let mut order = Order::new(
+            venue,
+            market.clone(),
+            side,
+            OrderType::Market,
+            None,
+            position.abs(),
+            TimeInForce::IOC,
+            String::new(),
+        )
+        .stamped(self.clock.as_ref(), self.ids.as_ref());
+        order.reduce_only = true;
+        if self.client_ids.is_none() {
+            order.client_order_id = format!("flatten-{}", order.id);
+        }
+        self.submit_order(order).await.map(Some)
+    }
+
+    /// Venue of the most recent order in a market (where its position lives)
+    ///
+    /// Falls back to the venue an imported position came from.
+    pub fn position_venue(&self, market: &MarketId) -> ExecResult<VenueId> {
+        self.order_tracker
+            .get_all_orders()?
+            .into_iter()
+            .filter(|o| &o.market == market)
+            .max_by_key(|o| o.updated_at)
+            .map(|o| o.venue)
+            .or_else(|| {
+                self.position_venues
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(market)
+                    .cloned()
+            })
+            .ok_or_else(|| ExecError::InternalError(format!("No venue known for position in {}", market)))
+    }
+
+    /// Set a venue's balance (e.g. from an external balance feed)
+    pub async fn set_balance(&self, balance: VenueBalance) {
+        self.capital.lock().await.set_balance(balance);
+        self.update_bankroll().await;
+    }
+
+    /// Poll balances from every registered adapter
+    ///
+    /// Adapters that do not report balances are skipped; their cash is
+    /// tracked from fills only. Returns the number of venues polled.
+    pub async fn poll_balances(&self) -> ExecResult<usize> {
+        let mut polled = 0;
+        for (venue_id, adapter) in &self.adapters {
+            let balance = match adapter.lock().await.get_balance().await {
+                Ok(balance) => balance,
+                Err(ExecError::VenueNotSupported(_)) => continue,
+                Err(e) => {
+                    warn!("Balance poll failed for {}: {}", venue_id, e);
+                    return Err(e);
+                }
+            };
+            debug!("Balance for {}: {} ({} locked)", venue_id, balance.cash, balance.locked);
+            self.capital.lock().await.set_balance(balance);
+            polled += 1;
+        }
+        self.update_bankroll().await;
+        Ok(polled)
+    }
+
+    /// Refresh one venue's balance, if it reports one, and report the
+    /// bankroll to the risk engine
+    async fn refresh_balance(&self, venue: &VenueId) {
+        if let Some(adapter) = self.adapters.get(venue) {
+            match adapter.lock().await.get_balance().await {
+                Ok(balance) => self.capital.lock().await.set_balance(balance),
+                Err(ExecError::VenueNotSupported(_)) => {}
+                Err(e) => warn!("Balance refresh failed for {}: {}", venue, e),
+            }
+        }
+        self.update_bankroll().await;
+    }
+
+    /// Cash, locked collateral and free capital per venue
+    pub async fn capital(&self) -> ExecResult<CapitalSnapshot> {
+        let open_orders = self.order_tracker.get_active_orders()?;
+        Ok(self.capital.lock().await.snapshot(&open_orders))
+    }
+
+    /// Report total cash to the risk engine for `BankrollLimit` policies
+    async fn update_bankroll(&self) {
+        if let Some(risk_engine) = &self.risk_engine {
+            let total_cash = self.capital.lock().await.snapshot(&[]).total_cash;
+            risk_engine.lock().await.set_bankroll(total_cash);
+        }
     }
 
     /// Get all active orders
@@ -290,12 +1638,62 @@ impl ExecutionEngine {
         self.order_tracker.get_order(order_id)
     }
 
+    /// Registered venue adapters
+    pub(crate) fn adapters(&self) -> &HashMap<VenueId, Arc<Mutex<Box<dyn VenueAdapter>>>> {
+        &self.adapters
+    }
+
     /// Get order tracker (for advanced usage)
     pub fn order_tracker(&self) -> &Arc<OrderTracker> {
         &self.order_tracker
     }
 }
 
+/// Idempotency claim held while an order is submitted
+///
+/// Unless the submission completed, dropping the guard (on error, or when
+/// the submitting future is cancelled) releases the key if the order never
+/// reached the venue, was rejected, or failed definitively. Orders whose
+/// outcome is unknown keep the key so a retry cannot place them twice.
+struct ClaimGuard<'a> {
+    cache: &'a IdempotencyCache,
+    tracker: &'a OrderTracker,
+    key: &'a str,
+    order_id: OrderId,
+    completed: bool,
+    outcome_unknown: bool,
+}
+
+impl Drop for ClaimGuard<'_> {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        let reached_venue = self
+            .tracker
+            .get_order(&self.order_id)
+            .map(|order| order.status != OrderStatus::Rejected)
+            .unwrap_or(false);
+        if !(reached_venue && self.outcome_unknown) {
+            self.cache.release(self.key);
+        }
+    }
+}
+
+/// Whether a cancel error leaves it open if the venue acted on the cancel
+fn is_ambiguous_cancel_error(error: &ExecError) -> bool {
+    matches!(
+        error,
+        ExecError::Timeout(_)
+            | ExecError::NetworkError(_)
+            | ExecError::HttpError(_)
+            | ExecError::WebSocketError(_)
+            | ExecError::OrderNotFound(_)
+            | ExecError::InvalidResponse(_)
+            | ExecError::VenueError { .. }
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,7 +1711,9 @@ mod tests {
     #[tokio::test]
     async fn test_position_tracking() {
         let config = ExecutionEngineConfig::default();
-        let engine = ExecutionEngine::new(config);
+        let mut engine = ExecutionEngine::new(config);
+        engine.set_risk_engine(RiskEngine::from_yaml("policies:\n  - type: KillSwitch\n    enabled: false\n").unwrap());
+        let risk_engine = engine.risk_engine().unwrap();
 
         let market_id = "0x123abc";
         let initial_position = engine.get_position(market_id).await;
@@ -349,6 +1749,168 @@ mod tests {
 
         let position = engine.get_position(market_id).await;
         assert_eq!(position, 100.0);
+        assert_eq!(risk_engine.lock().await.venue_positions(market_id)["polymarket"], 100.0);
+
+        // Resolution settles the position
+        assert_eq!(engine.settle_position(&MarketId::new(market_id), 1.0).await, 100.0);
+        assert_eq!(engine.get_position(market_id).await, 0.0);
+        assert_eq!(risk_engine.lock().await.asset_exposure(market_id), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_stop_order_arm_and_cancel() {
+        let engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+        let market = MarketId::new("0x123abc");
+
+        let order = Order::new(
+            VenueId::new("polymarket"),
+            market.clone(),
+            Side::Sell,
+            OrderType::StopMarket,
+            None,
+            100.0,
+            TimeInForce::GTC,
+            "client-stop".to_string(),
+        )
+        .with_stop_price(0.40);
+
+        // No adapter is registered: stops are armed without touching a venue
+        let ack = engine.submit_order(order.clone()).await.unwrap();
+        assert_eq!(ack.status, OrderStatus::Pending);
+        assert_eq!(engine.get_armed_stops().await.len(), 1);
+        assert!(engine.on_market_price(&market, 0.45).await.unwrap().is_empty());
+
+        let cancel = engine.cancel_order(order.id).await.unwrap();
+        assert!(cancel.success);
+        assert_eq!(engine.get_order(&order.id).unwrap().status, OrderStatus::Cancelled);
+        assert!(engine.get_armed_stops().await.is_empty());
+
+        // A released child with no venue adapter is rejected
+        let order = Order {
+            id: OrderId::new(),
+            client_order_id: "client-stop-2".to_string(),
+            ..order
+        };
+        engine.submit_order(order.clone()).await.unwrap();
+        let results = engine.on_market_price(&market, 0.39).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+        assert_eq!(engine.get_order(&order.id).unwrap().status, OrderStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_injected_clock_and_ids() {
+        use ag_risk::clock::{IdGenerator, MockClock, SequentialIdGenerator};
+        use chrono::TimeZone;
+
+        let start = chrono::Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let ids = SequentialIdGenerator::new(1);
+        let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+        engine.set_clock(Arc::new(clock.clone()));
+        engine.set_id_generator(Arc::new(ids.clone()));
+
+        let order = Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            Side::Sell,
+            OrderType::StopMarket,
+            None,
+            100.0,
+            TimeInForce::GTC,
+            "client-clock".to_string(),
+        )
+        .with_stop_price(0.40)
+        .stamped(engine.clock().as_ref(), engine.id_generator().as_ref());
+        assert_eq!(order.id, OrderId::from_uuid(uuid::Uuid::from_u128(1)));
+        assert_eq!(order.created_at, start);
+
+        let ack = engine.submit_order(order.clone()).await.unwrap();
+        assert_eq!(ack.timestamp, start);
+
+        clock.advance(chrono::Duration::seconds(30));
+        let cancel = engine.cancel_order(order.id).await.unwrap();
+        assert_eq!(cancel.timestamp, start + chrono::Duration::seconds(30));
+        assert_eq!(engine.get_order(&order.id).unwrap().updated_at, start + chrono::Duration::seconds(30));
+        assert_eq!(ids.next_id(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_post_only_crossing_protection() {
+        let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+        let market = MarketId::new("0x123abc");
+        engine.update_touch(&market, Some(0.48), Some(0.52)).await;
+
+        engine.set_post_only_policy(PostOnlyPolicy {
+            on_cross: crate::oms::post_only::CrossingAction::Reject,
+            tick_size: 0.01,
+        });
+
+        let order = Order::new(
+            VenueId::new("polymarket"),
+            market.clone(),
+            Side::Buy,
+            OrderType::PostOnly,
+            Some(0.53),
+            100.0,
+            TimeInForce::GTC,
+            "client-po".to_string(),
+        );
+
+        let err = engine.submit_order(order).await.unwrap_err();
+        assert!(err.is_post_only_reject());
+        assert_eq!(engine.get_touch(&market).await.unwrap().best_ask, Some(0.52));
+    }
+
+    #[tokio::test]
+    async fn test_symbol_mapper_shared() {
+        use crate::symbols::VenueSymbol;
+
+        let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+        let mut mapper = SymbolMapper::new();
+        mapper
+            .insert(
+                MarketId::new("btc-100k"),
+                VenueId::new("polymarket"),
+                VenueSymbol::polymarket("0xcond", "7123"),
+            )
+            .unwrap();
+        engine.set_symbol_mapper(mapper);
+
+        let symbols = engine.symbol_mapper();
+        let symbols = symbols.read().await;
+        assert_eq!(
+            symbols.resolve(&MarketId::new("btc-100k"), &VenueId::new("polymarket")),
+            MarketId::new("7123")
+        );
+        assert_eq!(
+            symbols.resolve_canonical(&VenueId::new("polymarket"), "7123"),
+            MarketId::new("btc-100k")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_order_id_assigned() {
+        let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+        engine.set_client_id_generator(ClientOrderIdGenerator::new("ag").unwrap());
+
+        let order = Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            Side::Buy,
+            OrderType::StopMarket,
+            None,
+            100.0,
+            TimeInForce::GTC,
+            String::new(),
+        )
+        .with_stop_price(0.60)
+        .with_strategy("mm_1");
+
+        engine.submit_order(order.clone()).await.unwrap();
+        let tracked = engine.get_order(&order.id).unwrap();
+        let parsed = ClientOrderIdGenerator::parse(&tracked.client_order_id).unwrap();
+        assert_eq!(parsed.strategy_id, "mm_1");
     }
 
     #[test]
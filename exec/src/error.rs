@@ -1,8 +1,12 @@
 //! Error types for the execution gateway
 
+use ag_risk::Violation;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use thiserror::Error;
 
-use crate::order::OrderId;
+use crate::order::{OrderId, VenueId};
 
 /// Result type for execution operations
 pub type ExecResult<T> = Result<T, ExecError>;
@@ -15,10 +19,10 @@ pub enum ExecError {
     ValidationError(String),
 
     /// Risk check rejected the order
-    #[error("Risk check rejected order: {policies:?}")]
+    #[error("Risk check rejected order: {}", join_violations(.violations))]
     RiskRejected {
-        /// List of violated risk policies
-        policies: Vec<String>,
+        /// Violated risk policies
+        violations: Vec<Violation>,
     },
 
     /// Rate limit exceeded
@@ -39,6 +43,26 @@ pub enum ExecError {
         message: String,
         /// Optional error code from venue
         code: Option<String>,
+        /// Class of the error, deciding how the engine reacts
+        kind: VenueErrorKind,
+    },
+
+    /// Post-only order would have taken liquidity
+    #[error("Post-only order rejected by {venue}: {message}")]
+    PostOnlyRejected {
+        /// Venue identifier
+        venue: String,
+        /// Error message
+        message: String,
+    },
+
+    /// Order flow to the venue is paused while it is degraded or down
+    #[error("Venue {venue} unavailable: {message}")]
+    VenueUnavailable {
+        /// Venue identifier
+        venue: String,
+        /// Error message
+        message: String,
     },
 
     /// Order not found
@@ -101,6 +125,63 @@ pub enum ExecError {
     IoError(#[from] std::io::Error),
 }
 
+/// Class of an error reported by a venue
+///
+/// Adapters classify the venue's error codes and messages so the engine
+/// can react per class: cooling down after rate limiting, refreshing
+/// balances for the risk engine after a balance rejection, and alerting on
+/// errors nobody recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VenueErrorKind {
+    /// Not enough free balance or collateral for the order
+    InsufficientBalance,
+    /// Price off the tick grid or outside the allowed range
+    InvalidPrice,
+    /// The venue throttled the request (HTTP 429)
+    RateLimited,
+    /// The venue is in maintenance or temporarily unavailable
+    Maintenance,
+    /// Error code or message the adapter does not recognize
+    Unknown,
+}
+
+impl VenueErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VenueErrorKind::InsufficientBalance => "insufficient_balance",
+            VenueErrorKind::InvalidPrice => "invalid_price",
+            VenueErrorKind::RateLimited => "rate_limited",
+            VenueErrorKind::Maintenance => "maintenance",
+            VenueErrorKind::Unknown => "unknown",
+        }
+    }
+
+    /// Whether the venue definitively rejected the request, so nothing was
+    /// placed
+    pub fn is_definitive(&self) -> bool {
+        !matches!(self, VenueErrorKind::Unknown)
+    }
+}
+
+impl fmt::Display for VenueErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Classified venue error on order placement, published by the engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueErrorEvent {
+    pub venue: VenueId,
+    pub order_id: OrderId,
+    pub kind: VenueErrorKind,
+    /// Error code from the venue
+    pub code: Option<String>,
+    pub message: String,
+    pub at: DateTime<Utc>,
+}
+
 impl ExecError {
     /// Check if error is retryable
     pub fn is_retryable(&self) -> bool {
@@ -110,12 +191,55 @@ impl ExecError {
                 | ExecError::Timeout(_)
                 | ExecError::RateLimitExceeded { .. }
                 | ExecError::HttpError(_)
+                | ExecError::VenueError {
+                    kind: VenueErrorKind::RateLimited | VenueErrorKind::Maintenance,
+                    ..
+                }
         )
     }
 
-    /// Check if error is due to rate limiting
+    /// Check if error is due to rate limiting, locally or by the venue
     pub fn is_rate_limit(&self) -> bool {
-        matches!(self, ExecError::RateLimitExceeded { .. })
+        matches!(
+            self,
+            ExecError::RateLimitExceeded { .. }
+                | ExecError::VenueError {
+                    kind: VenueErrorKind::RateLimited,
+                    ..
+                }
+        )
+    }
+
+    /// Class of a venue error (None for other errors)
+    pub fn venue_error_kind(&self) -> Option<VenueErrorKind> {
+        match self {
+            ExecError::VenueError { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Check if a post-only order was rejected for crossing the book
+    pub fn is_post_only_reject(&self) -> bool {
+        matches!(self, ExecError::PostOnlyRejected { .. })
+    }
+
+    /// Check if the error points at a problem with the venue itself
+    /// (counted towards its error rate by venue status monitoring)
+    ///
+    /// Rejections caused by the request (balance, price) or its rate are
+    /// not the venue's fault.
+    pub fn is_venue_fault(&self) -> bool {
+        matches!(
+            self,
+            ExecError::VenueError {
+                kind: VenueErrorKind::Maintenance | VenueErrorKind::Unknown,
+                ..
+            } | ExecError::NetworkError(_)
+                | ExecError::Timeout(_)
+                | ExecError::InvalidResponse(_)
+                | ExecError::HttpError(_)
+                | ExecError::WebSocketError(_)
+        )
     }
 
     /// Check if error is due to risk rejection
@@ -124,6 +248,11 @@ impl ExecError {
     }
 }
 
+/// Log messages of risk violations, separated by "; "
+fn join_violations(violations: &[Violation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,12 +273,40 @@ mod tests {
         assert!(rate_limit_err.is_rate_limit());
     }
 
+    #[test]
+    fn test_venue_error_kinds() {
+        let venue_err = |kind| ExecError::VenueError {
+            venue: "polymarket".to_string(),
+            message: "Order placement failed".to_string(),
+            code: Some("400".to_string()),
+            kind,
+        };
+        let throttled = venue_err(VenueErrorKind::RateLimited);
+        assert!(throttled.is_rate_limit());
+        assert!(throttled.is_retryable());
+        assert!(!throttled.is_venue_fault());
+
+        let balance = venue_err(VenueErrorKind::InsufficientBalance);
+        assert_eq!(balance.venue_error_kind(), Some(VenueErrorKind::InsufficientBalance));
+        assert!(!balance.is_retryable());
+        assert!(!balance.is_venue_fault());
+
+        assert!(venue_err(VenueErrorKind::Maintenance).is_venue_fault());
+        assert!(venue_err(VenueErrorKind::Unknown).is_venue_fault());
+        assert!(!VenueErrorKind::Unknown.is_definitive());
+        assert_eq!(ExecError::Timeout("place".to_string()).venue_error_kind(), None);
+    }
+
     #[test]
     fn test_risk_rejection() {
         let risk_err = ExecError::RiskRejected {
-            policies: vec!["PositionLimit".to_string()],
+            violations: vec![Violation::new("PositionLimit", "PositionLimit: new position 1100.00 exceeds max 1000.00")],
         };
         assert!(risk_err.is_risk_rejection());
+        assert_eq!(
+            risk_err.to_string(),
+            "Risk check rejected order: PositionLimit: new position 1100.00 exceeds max 1000.00"
+        );
         assert!(!risk_err.is_retryable());
     }
 }
@@ -0,0 +1,470 @@
+//! Supervised websocket market data feeds
+//!
+//! A [`FeedSupervisor`] owns one websocket connection (e.g. Polymarket RTDS
+//! or the CLOB market channel) and keeps it alive:
+//!
+//! - reconnects with exponential backoff after errors, closes and stalls
+//! - resends the full subscription set after every reconnect; subscribing
+//!   to a key that is already subscribed is a no-op
+//! - treats a connection that delivers nothing (not even a pong) for
+//!   `stall_timeout` as dead, since a silently stalled socket otherwise
+//!   looks exactly like a quiet market
+//! - publishes every [`ConnectionState`] change on a watch channel so
+//!   consumers can pause quoting instead of trading on frozen prices
+//! - optionally beats a liveness [`Heartbeat`] for every frame received
+//!   while connected, so a supervisor sees a feed stuck reconnecting
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use ag_sched::{Heartbeat, Ticker};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+/// Connection state of a supervised feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// Initial connection attempt in progress
+    Connecting,
+    /// Connected and subscribed
+    Connected,
+    /// No message within the stall timeout; about to reconnect
+    Stalled,
+    /// Waiting `delay` before reconnect attempt `attempt`
+    Reconnecting { attempt: u32, delay: Duration },
+    /// Supervisor stopped (closed, or out of reconnect attempts)
+    Closed,
+}
+
+impl ConnectionState {
+    /// Whether prices from the feed are live
+    pub fn is_connected(&self) -> bool {
+        matches!(self, ConnectionState::Connected)
+    }
+}
+
+/// Exponential reconnect backoff
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BackoffConfig {
+    /// Delay before the first reconnect
+    pub initial: Duration,
+    /// Upper bound on the delay
+    pub max: Duration,
+    /// Growth factor per failed attempt
+    pub multiplier: f64,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Delay before reconnect attempt `attempt` (1-based)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(attempt.saturating_sub(1).min(64) as i32);
+        let delay = self.initial.as_secs_f64() * factor;
+        Duration::from_secs_f64(delay.min(self.max.as_secs_f64()))
+    }
+}
+
+/// Supervised feed configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedConfig {
+    /// Websocket endpoint
+    pub endpoint: String,
+    /// How often to send a ping
+    pub ping_interval: Duration,
+    /// Text ping frame (e.g. `{"type":"ping"}` for RTDS); None sends a
+    /// websocket ping
+    pub ping_message: Option<String>,
+    /// Reconnect after this long without any message
+    pub stall_timeout: Duration,
+    /// Reconnect backoff
+    pub backoff: BackoffConfig,
+    /// Give up after this many consecutive failed attempts (None = never)
+    pub max_attempts: Option<u32>,
+}
+
+impl FeedConfig {
+    /// Configuration with a 5s ping, 15s stall timeout and default backoff
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            ping_interval: Duration::from_secs(5),
+            ping_message: None,
+            stall_timeout: Duration::from_secs(15),
+            backoff: BackoffConfig::default(),
+            max_attempts: None,
+        }
+    }
+
+    /// Send a text ping frame instead of a websocket ping
+    pub fn with_ping_message(mut self, message: impl Into<String>) -> Self {
+        self.ping_message = Some(message.into());
+        self
+    }
+}
+
+/// Subscription frames keyed by topic, resent on every reconnect
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubscriptionSet {
+    frames: BTreeMap<String, String>,
+}
+
+impl SubscriptionSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a subscription; returns false if `key` is already subscribed
+    pub fn insert(&mut self, key: impl Into<String>, frame: impl Into<String>) -> bool {
+        let key = key.into();
+        if self.frames.contains_key(&key) {
+            return false;
+        }
+        self.frames.insert(key, frame.into());
+        true
+    }
+
+    /// Remove a subscription; returns false if it was not subscribed
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.frames.remove(key).is_some()
+    }
+
+    /// Whether `key` is subscribed
+    pub fn contains(&self, key: &str) -> bool {
+        self.frames.contains_key(key)
+    }
+
+    /// Subscription frames in key order
+    pub fn frames(&self) -> impl Iterator<Item = &String> {
+        self.frames.values()
+    }
+
+    /// Number of subscriptions
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether nothing is subscribed
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+enum FeedCommand {
+    Subscribe { key: String, frame: String },
+    Unsubscribe { key: String, frame: Option<String> },
+    Close,
+}
+
+/// How a connected session ended
+enum SessionEnd {
+    /// Closed by the handle or the message receiver was dropped
+    Shutdown,
+    /// Server closed the connection or a socket error occurred
+    Lost,
+    /// Nothing received within the stall timeout
+    Stalled,
+}
+
+/// Builds and spawns a supervised websocket feed
+pub struct FeedSupervisor {
+    config: FeedConfig,
+    subscriptions: SubscriptionSet,
+    heartbeat: Option<Heartbeat>,
+}
+
+impl FeedSupervisor {
+    /// Create a supervisor
+    pub fn new(config: FeedConfig) -> Self {
+        Self {
+            config,
+            subscriptions: SubscriptionSet::new(),
+            heartbeat: None,
+        }
+    }
+
+    /// Beat `heartbeat` for every frame received (pongs included) while
+    /// connected
+    ///
+    /// Register it with a stall threshold above `ping_interval`.
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Add a subscription sent on connect; duplicates are ignored
+    pub fn subscribe(mut self, key: impl Into<String>, frame: impl Into<String>) -> Self {
+        self.subscriptions.insert(key, frame);
+        self
+    }
+
+    /// Spawn the supervision task
+    ///
+    /// `buffer` bounds the channel of received text frames.
+    pub fn spawn(self, buffer: usize) -> FeedHandle {
+        let (messages_tx, messages) = mpsc::channel(buffer);
+        let (state_tx, state) = watch::channel(ConnectionState::Connecting);
+        let (commands, commands_rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(supervise(
+            self.config,
+            self.subscriptions,
+            self.heartbeat,
+            messages_tx,
+            state_tx,
+            commands_rx,
+        ));
+        FeedHandle {
+            messages,
+            state,
+            commands,
+            task,
+        }
+    }
+}
+
+/// Handle to a running supervised feed
+pub struct FeedHandle {
+    messages: mpsc::Receiver<String>,
+    state: watch::Receiver<ConnectionState>,
+    commands: mpsc::UnboundedSender<FeedCommand>,
+    task: JoinHandle<()>,
+}
+
+impl FeedHandle {
+    /// Next text frame; `None` once the supervisor has stopped
+    ///
+    /// Cancel safe.
+    pub async fn next_message(&mut self) -> Option<String> {
+        self.messages.recv().await
+    }
+
+    /// Current connection state
+    pub fn state(&self) -> ConnectionState {
+        self.state.borrow().clone()
+    }
+
+    /// Watch connection state changes
+    pub fn watch_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+
+    /// Subscribe at runtime; ignored if `key` is already subscribed
+    pub fn subscribe(&self, key: impl Into<String>, frame: impl Into<String>) {
+        let _ = self.commands.send(FeedCommand::Subscribe {
+            key: key.into(),
+            frame: frame.into(),
+        });
+    }
+
+    /// Unsubscribe at runtime, sending `frame` if connected
+    pub fn unsubscribe(&self, key: impl Into<String>, frame: Option<String>) {
+        let _ = self.commands.send(FeedCommand::Unsubscribe { key: key.into(), frame });
+    }
+
+    /// Close the connection and wait for the supervisor to stop
+    pub async fn close(self) {
+        let _ = self.commands.send(FeedCommand::Close);
+        let _ = self.task.await;
+    }
+}
+
+async fn supervise(
+    config: FeedConfig,
+    mut subscriptions: SubscriptionSet,
+    heartbeat: Option<Heartbeat>,
+    messages: mpsc::Sender<String>,
+    state: watch::Sender<ConnectionState>,
+    mut commands: mpsc::UnboundedReceiver<FeedCommand>,
+) {
+    let mut attempt = 0u32;
+    loop {
+        match connect_async(config.endpoint.as_str()).await {
+            Ok((stream, _)) => {
+                attempt = 0;
+                let session = run_session(
+                    &config,
+                    stream,
+                    &mut subscriptions,
+                    heartbeat.as_ref(),
+                    &messages,
+                    &state,
+                    &mut commands,
+                );
+                match session.await {
+                    SessionEnd::Shutdown => break,
+                    SessionEnd::Stalled => {
+                        warn!("Feed {} stalled: nothing received for {:?}", config.endpoint, config.stall_timeout);
+                        state.send_replace(ConnectionState::Stalled);
+                    }
+                    SessionEnd::Lost => warn!("Feed {} disconnected", config.endpoint),
+                }
+            }
+            Err(e) => warn!("Feed {} connect failed: {}", config.endpoint, e),
+        }
+
+        attempt += 1;
+        if config.max_attempts.is_some_and(|max| attempt > max) {
+            warn!("Feed {} giving up after {} attempts", config.endpoint, attempt - 1);
+            break;
+        }
+        let delay = config.backoff.delay(attempt);
+        state.send_replace(ConnectionState::Reconnecting { attempt, delay });
+        info!("Feed {} reconnecting in {:?} (attempt {})", config.endpoint, delay, attempt);
+
+        // Keep applying subscription changes while waiting
+        let wake = Instant::now() + delay;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake) => break,
+                command = commands.recv() => match command {
+                    Some(FeedCommand::Subscribe { key, frame }) => {
+                        subscriptions.insert(key, frame);
+                    }
+                    Some(FeedCommand::Unsubscribe { key, .. }) => {
+                        subscriptions.remove(&key);
+                    }
+                    Some(FeedCommand::Close) | None => {
+                        state.send_replace(ConnectionState::Closed);
+                        return;
+                    }
+                },
+            }
+        }
+    }
+    state.send_replace(ConnectionState::Closed);
+}
+
+async fn run_session(
+    config: &FeedConfig,
+    stream: tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+    subscriptions: &mut SubscriptionSet,
+    heartbeat: Option<&Heartbeat>,
+    messages: &mpsc::Sender<String>,
+    state: &watch::Sender<ConnectionState>,
+    commands: &mut mpsc::UnboundedReceiver<FeedCommand>,
+) -> SessionEnd {
+    let (mut write, mut read) = stream.split();
+
+    for frame in subscriptions.frames() {
+        if let Err(e) = write.send(Message::Text(frame.clone())).await {
+            warn!("Feed {} resubscribe failed: {}", config.endpoint, e);
+            return SessionEnd::Lost;
+        }
+    }
+    state.send_replace(ConnectionState::Connected);
+    info!("Feed {} connected ({} subscriptions)", config.endpoint, subscriptions.len());
+
+    let mut ping = Ticker::new(ag_sched::Schedule::every(config.ping_interval));
+    let mut last_seen = Instant::now();
+
+    loop {
+        let outgoing = tokio::select! {
+            message = read.next() => {
+                last_seen = Instant::now();
+                if let Some(heartbeat) = heartbeat {
+                    heartbeat.beat();
+                }
+                match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if messages.send(text).await.is_err() {
+                            debug!("Feed {} receiver dropped", config.endpoint);
+                            return SessionEnd::Shutdown;
+                        }
+                        None
+                    }
+                    Some(Ok(Message::Close(_))) | None => return SessionEnd::Lost,
+                    Some(Ok(_)) => None,
+                    Some(Err(e)) => {
+                        warn!("Feed {} error: {}", config.endpoint, e);
+                        return SessionEnd::Lost;
+                    }
+                }
+            }
+
+            _ = tokio::time::sleep_until(last_seen + config.stall_timeout) => return SessionEnd::Stalled,
+
+            _ = ping.tick() => Some(match &config.ping_message {
+                Some(text) => Message::Text(text.clone()),
+                None => Message::Ping(Vec::new()),
+            }),
+
+            command = commands.recv() => match command {
+                Some(FeedCommand::Subscribe { key, frame }) => {
+                    subscriptions.insert(key, frame.clone()).then_some(Message::Text(frame))
+                }
+                Some(FeedCommand::Unsubscribe { key, frame }) => {
+                    if subscriptions.remove(&key) { frame.map(Message::Text) } else { None }
+                }
+                Some(FeedCommand::Close) | None => {
+                    let _ = write.send(Message::Close(None)).await;
+                    return SessionEnd::Shutdown;
+                }
+            },
+        };
+
+        if let Some(message) = outgoing {
+            if let Err(e) = write.send(message).await {
+                warn!("Feed {} send failed: {}", config.endpoint, e);
+                return SessionEnd::Lost;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = BackoffConfig {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(5),
+            multiplier: 2.0,
+        };
+        assert_eq!(backoff.delay(1), Duration::from_millis(500));
+        assert_eq!(backoff.delay(3), Duration::from_secs(2));
+        assert_eq!(backoff.delay(10), Duration::from_secs(5));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_subscription_set_dedup() {
+        let mut subs = SubscriptionSet::new();
+        assert!(subs.insert("crypto_prices", r#"{"action":"subscribe","topic":"crypto_prices"}"#));
+        assert!(!subs.insert("crypto_prices", r#"{"action":"subscribe","topic":"crypto_prices"}"#));
+        assert!(subs.insert("activity", r#"{"action":"subscribe","topic":"activity"}"#));
+        assert_eq!(subs.len(), 2);
+        assert!(subs.remove("activity"));
+        assert!(!subs.remove("activity"));
+        assert_eq!(subs.frames().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_feed_closes_after_max_attempts() {
+        let mut config = FeedConfig::new("ws://127.0.0.1:1");
+        config.backoff.initial = Duration::from_millis(1);
+        config.max_attempts = Some(2);
+        let mut feed = FeedSupervisor::new(config).spawn(8);
+        let mut state = feed.watch_state();
+
+        assert_eq!(feed.next_message().await, None);
+        state.wait_for(|s| *s == ConnectionState::Closed).await.unwrap();
+        assert!(!feed.state().is_connected());
+    }
+}
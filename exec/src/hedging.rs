@@ -0,0 +1,378 @@
+//! Delta hedging of crypto-linked binary markets with perpetual futures
+//!
+//! A market like "BTC above 100k on Dec 31" gains value as BTC rises. Its
+//! sensitivity to the underlying is read off the implied probability curve
+//! of sibling markets on the same underlying: if the whole curve shifts with
+//! spot (sticky moneyness), `dP/dS = -dP/dK`, the negative slope of P(above K)
+//! across strikes. A position of `n` shares (each paying 1 USD) then carries
+//! `n * dP/dS` units of underlying exposure.
+//!
+//! [`Hedger::rebalance`] sums that exposure per underlying, scales it by
+//! `hedge_ratio` and trades the configured perpetual on a CEX venue towards
+//! the offsetting position. Trades smaller than `min_trade_size` are skipped
+//! so small price moves don't churn the hedge.
+//!
+//! The curve needs at least two priced strikes per underlying; a market with
+//! no neighbouring strike has no delta estimate and is left unhedged.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::engine::ExecutionEngine;
+use crate::error::ExecResult;
+use crate::order::{MarketId, Order, OrderAck, OrderType, Side, TimeInForce, VenueId};
+
+/// Which side of the strike a market's YES outcome pays on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryDirection {
+    /// YES if the underlying settles above the strike
+    #[default]
+    Above,
+    /// YES if the underlying settles below the strike
+    Below,
+}
+
+/// A binary market linked to a crypto underlying
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HedgedMarket {
+    /// Binary outcome market
+    pub market: MarketId,
+
+    /// Underlying asset (key into [`HedgeConfig::perps`], e.g. "BTC")
+    pub underlying: String,
+
+    /// Strike price in USD
+    pub strike: f64,
+
+    /// Side of the strike paying YES
+    #[serde(default)]
+    pub direction: BinaryDirection,
+}
+
+/// Perpetual contract used to hedge an underlying
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PerpContract {
+    /// Perp market on the hedge venue (e.g. "BTCUSDT")
+    pub market: MarketId,
+
+    /// Units of underlying per contract
+    #[serde(default = "default_contract_size")]
+    pub contract_size: f64,
+}
+
+fn default_contract_size() -> f64 {
+    1.0
+}
+
+fn default_hedge_ratio() -> f64 {
+    1.0
+}
+
+fn default_rebalance_interval_secs() -> u64 {
+    60
+}
+
+fn default_min_trade_size() -> f64 {
+    0.001
+}
+
+/// Hedging settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HedgeConfig {
+    /// Venue the perps trade on
+    pub venue: VenueId,
+
+    /// Fraction of the delta exposure to offset (1.0 = fully hedged)
+    #[serde(default = "default_hedge_ratio")]
+    pub hedge_ratio: f64,
+
+    /// Time between rebalances
+    #[serde(default = "default_rebalance_interval_secs")]
+    pub rebalance_interval_secs: u64,
+
+    /// Smallest hedge adjustment sent, in contracts
+    #[serde(default = "default_min_trade_size")]
+    pub min_trade_size: f64,
+
+    /// Perp per underlying
+    #[serde(default)]
+    pub perps: HashMap<String, PerpContract>,
+
+    /// Binary markets whose positions are hedged
+    #[serde(default)]
+    pub markets: Vec<HedgedMarket>,
+}
+
+impl HedgeConfig {
+    /// Time between rebalances
+    pub fn rebalance_interval(&self) -> Duration {
+        Duration::from_secs(self.rebalance_interval_secs)
+    }
+}
+
+/// Implied probability of the underlying settling above each strike
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImpliedCurve {
+    /// (strike, P(above strike)), sorted by strike
+    points: Vec<(f64, f64)>,
+}
+
+impl ImpliedCurve {
+    /// Build a curve from (strike, P(above strike)) points
+    ///
+    /// Points sharing a strike (an "above" and a "below" market) are averaged.
+    pub fn new(points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        let mut points: Vec<(f64, f64)> = points.into_iter().collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged: Vec<(f64, f64, usize)> = Vec::with_capacity(points.len());
+        for (strike, probability) in points {
+            match merged.last_mut() {
+                Some(last) if last.0 == strike => {
+                    last.1 += probability;
+                    last.2 += 1;
+                }
+                _ => merged.push((strike, probability, 1)),
+            }
+        }
+
+        Self {
+            points: merged.into_iter().map(|(k, p, n)| (k, p / n as f64)).collect(),
+        }
+    }
+
+    /// Number of distinct strikes
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the curve has no strikes
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Sensitivity of P(above `strike`) to the underlying price, per USD
+    ///
+    /// The negative slope of the curve around `strike` (central difference,
+    /// one-sided at the ends). Non-monotone noise is floored at zero.
+    /// `None` if `strike` is not on the curve or has no neighbour.
+    pub fn delta_above(&self, strike: f64) -> Option<f64> {
+        let i = self.points.iter().position(|(k, _)| *k == strike)?;
+        let lo = i.saturating_sub(1);
+        let hi = (i + 1).min(self.points.len() - 1);
+        if lo == hi {
+            return None;
+        }
+
+        let (k_lo, p_lo) = self.points[lo];
+        let (k_hi, p_hi) = self.points[hi];
+        Some((-(p_hi - p_lo) / (k_hi - k_lo)).max(0.0))
+    }
+}
+
+/// Outcome of rebalancing one underlying
+#[derive(Debug, Clone)]
+pub struct HedgeAdjustment {
+    /// Underlying hedged
+    pub underlying: String,
+    /// Perp traded
+    pub perp: MarketId,
+    /// Delta of the binary positions, in units of underlying
+    pub exposure: f64,
+    /// Perp position before the rebalance, in contracts
+    pub previous: f64,
+    /// Target perp position, in contracts
+    pub target: f64,
+    /// Ack of the hedge order, if one was sent and accepted
+    pub ack: Option<OrderAck>,
+}
+
+impl HedgeAdjustment {
+    /// Signed contracts traded (or that would have been traded)
+    pub fn trade_size(&self) -> f64 {
+        self.target - self.previous
+    }
+}
+
+/// Keeps perp hedges in line with the delta of binary positions
+#[derive(Debug, Clone)]
+pub struct Hedger {
+    config: HedgeConfig,
+}
+
+impl Hedger {
+    /// Create a hedger
+    pub fn new(config: HedgeConfig) -> Self {
+        Self { config }
+    }
+
+    /// Hedging settings
+    pub fn config(&self) -> &HedgeConfig {
+        &self.config
+    }
+
+    /// Implied curve per underlying from current mid prices
+    ///
+    /// Markets without a two-sided touch are left out.
+    pub async fn curves(&self, engine: &ExecutionEngine) -> HashMap<String, ImpliedCurve> {
+        let mut points: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+        for hedged in &self.config.markets {
+            let Some(mid) = engine.get_touch(&hedged.market).await.and_then(|t| t.mid()) else {
+                continue;
+            };
+            let above = match hedged.direction {
+                BinaryDirection::Above => mid,
+                BinaryDirection::Below => 1.0 - mid,
+            };
+            points
+                .entry(hedged.underlying.clone())
+                .or_default()
+                .push((hedged.strike, above));
+        }
+
+        points
+            .into_iter()
+            .map(|(underlying, points)| (underlying, ImpliedCurve::new(points)))
+            .collect()
+    }
+
+    /// Delta exposure of the binary positions per underlying, in units of underlying
+    pub async fn exposures(&self, engine: &ExecutionEngine) -> HashMap<String, f64> {
+        let curves = self.curves(engine).await;
+        let mut exposures: HashMap<String, f64> = HashMap::new();
+
+        for hedged in &self.config.markets {
+            let position = engine.get_position(hedged.market.as_str()).await;
+            if position == 0.0 {
+                continue;
+            }
+            let delta = curves
+                .get(&hedged.underlying)
+                .and_then(|curve| curve.delta_above(hedged.strike));
+            let Some(delta) = delta else {
+                warn!(
+                    "No delta estimate for {} ({} @ {}), leaving it unhedged",
+                    hedged.market, hedged.underlying, hedged.strike
+                );
+                continue;
+            };
+            let delta = match hedged.direction {
+                BinaryDirection::Above => delta,
+                BinaryDirection::Below => -delta,
+            };
+            *exposures.entry(hedged.underlying.clone()).or_insert(0.0) += position * delta;
+        }
+
+        exposures
+    }
+
+    /// Trade each perp towards `-hedge_ratio * exposure`
+    ///
+    /// Hedges are market (IOC) orders; adjustments that only shrink an
+    /// existing hedge are reduce-only, so hedges unwind under a kill-switch.
+    /// Returns one adjustment per configured perp, including skipped ones.
+    pub async fn rebalance(&self, engine: &ExecutionEngine) -> ExecResult<Vec<HedgeAdjustment>> {
+        let exposures = self.exposures(engine).await;
+        let mut adjustments = Vec::with_capacity(self.config.perps.len());
+
+        for (underlying, perp) in &self.config.perps {
+            let exposure = exposures.get(underlying).copied().unwrap_or(0.0);
+            let mut adjustment = HedgeAdjustment {
+                underlying: underlying.clone(),
+                perp: perp.market.clone(),
+                exposure,
+                previous: engine.get_position(perp.market.as_str()).await,
+                target: -self.config.hedge_ratio * exposure / perp.contract_size,
+                ack: None,
+            };
+
+            let trade = adjustment.trade_size();
+            if trade.abs() < self.config.min_trade_size {
+                adjustments.push(adjustment);
+                continue;
+            }
+
+            let side = if trade > 0.0 { Side::Buy } else { Side::Sell };
+            let mut order = Order::new(
+                self.config.venue.clone(),
+                perp.market.clone(),
+                side,
+                OrderType::Market,
+                None,
+                trade.abs(),
+                TimeInForce::IOC,
+                String::new(),
+            )
+            .stamped(engine.clock().as_ref(), engine.id_generator().as_ref());
+            order.client_order_id = format!("hedge-{}", order.id);
+            let previous = adjustment.previous;
+            if previous != 0.0 && trade.signum() != previous.signum() && trade.abs() <= previous.abs() {
+                order = order.with_reduce_only();
+            }
+
+            info!(
+                "Rebalancing {} hedge: {} -> {} contracts of {} (exposure {})",
+                underlying, previous, adjustment.target, perp.market, exposure
+            );
+            match engine.submit_order(order).await {
+                Ok(ack) => adjustment.ack = Some(ack),
+                Err(e) => warn!("Hedge order for {} failed: {}", perp.market, e),
+            }
+            adjustments.push(adjustment);
+        }
+
+        Ok(adjustments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_delta() {
+        // P(above) falls 0.2 per 10k around 100k
+        let curve = ImpliedCurve::new([(110_000.0, 0.30), (90_000.0, 0.70), (100_000.0, 0.50)]);
+
+        assert!((curve.delta_above(100_000.0).unwrap() - 0.00002).abs() < 1e-12);
+        assert!((curve.delta_above(90_000.0).unwrap() - 0.00002).abs() < 1e-12);
+        assert_eq!(curve.delta_above(95_000.0), None);
+        assert_eq!(ImpliedCurve::new([(100_000.0, 0.5)]).delta_above(100_000.0), None);
+
+        // An inverted (noisy) curve has no negative delta
+        let noisy = ImpliedCurve::new([(90_000.0, 0.40), (100_000.0, 0.45)]);
+        assert_eq!(noisy.delta_above(90_000.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_curve_merges_strikes() {
+        let curve = ImpliedCurve::new([(100_000.0, 0.52), (100_000.0, 0.48), (110_000.0, 0.30)]);
+        assert_eq!(curve.len(), 2);
+        assert!((curve.delta_above(110_000.0).unwrap() - 0.00002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_config_deserialize() {
+        let config: HedgeConfig = serde_json::from_str(
+            r#"{
+                "venue": "binance",
+                "hedge_ratio": 0.5,
+                "perps": {"BTC": {"market": "BTCUSDT", "contract_size": 0.001}},
+                "markets": [{"market": "0xabc", "underlying": "BTC", "strike": 100000.0, "direction": "below"}]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.hedge_ratio, 0.5);
+        assert_eq!(config.rebalance_interval(), Duration::from_secs(60));
+        assert_eq!(config.perps["BTC"].contract_size, 0.001);
+        assert_eq!(config.markets[0].direction, BinaryDirection::Below);
+    }
+}
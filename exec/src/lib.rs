@@ -63,9 +63,10 @@ pub mod error;
 pub mod order;
 
 // Re-export main types
-pub use error::{ExecError, ExecResult};
+pub use ag_risk::clock::{Clock, IdGenerator, MockClock, SequentialIdGenerator, SystemClock};
+pub use error::{ExecError, ExecResult, VenueErrorEvent, VenueErrorKind};
 pub use order::{
-    CancelAck, Fill, Liquidity, MarketId, Order, OrderAck, OrderId, OrderStatus, OrderType, Side,
+    CancelAck, CancelOutcome, Fill, Liquidity, MarketId, Order, OrderAck, OrderId, OrderStatus, OrderType, Side,
     TimeInForce, VenueId,
 };
 
@@ -74,25 +75,39 @@ mod engine;
 
 // OMS modules
 pub mod oms {
+    pub mod client_id;
+    pub mod idempotency;
+    pub mod post_only;
+    pub mod tif;
     pub mod tracker;
     pub mod validator;
 
+    pub use client_id::{ClientOrderIdGenerator, ParsedClientOrderId};
+    pub use idempotency::{IdempotencyCache, IdempotencyClaim, DEFAULT_IDEMPOTENCY_TTL};
+    pub use post_only::{CrossingAction, PostOnlyPolicy, Touch};
+    pub use tif::BookDepth;
     pub use tracker::OrderTracker;
     pub use validator::OrderValidator;
 }
 
 // Adapter modules
 pub mod adapters {
+    pub mod rounding;
     pub mod venue_adapter;
 
+    pub use rounding::{AppliedRounding, RoundingPolicy, VenueRounding};
     pub use venue_adapter::{VenueAdapter, VenueConfig};
 }
 
 // Rate limiting
 pub mod ratelimit {
+    pub mod cooldown;
     pub mod limiter;
 
-    pub use limiter::{RateLimiter, RateLimiterConfig};
+    pub use cooldown::{CooldownConfig, VenueCooldowns};
+    pub use limiter::{
+        PriorityStats, RateLimiter, RateLimiterConfig, RateLimiterStats, RequestPriority, StrategyQuota,
+    };
 }
 
 // Venue implementations
@@ -105,6 +120,58 @@ pub mod venues {
 // Re-export engine
 pub use engine::{ExecutionEngine, ExecutionEngineConfig};
 
+// Orders joined with fills into round trips
+pub mod blotter;
+
+pub use blotter::{BlotterQuery, RoundTrip, TradeBlotter};
+
+// Canonical market <-> venue symbol mapping
+pub mod symbols;
+
+pub use symbols::{MarketMapping, SymbolMapper, VenueSymbol};
+
+// Cash balances, locked collateral and free capital per venue
+pub mod capital;
+
+pub use capital::{CapitalSnapshot, CapitalTracker, VenueBalance, VenueCapital};
+
+// Per-market spread, depth, trade frequency and volatility
+pub mod market_quality;
+
+pub use market_quality::{MarketQuality, MarketQualityConfig, MarketQualityRecorder};
+
+// Supervised websocket feeds (reconnect, resubscribe, stall detection)
+pub mod feed;
+
+pub use feed::{BackoffConfig, ConnectionState, FeedConfig, FeedHandle, FeedSupervisor, SubscriptionSet};
+
+// Market scanner / universe selection
+pub mod scanner;
+
+pub use scanner::{
+    CatalogMarket, MarketCatalog, MarketScanner, PolymarketCatalog, RankedMarket, RankingWeights, RetireReason,
+    ScannerFilters, UniverseUpdate,
+};
+
+// Local stop order triggers
+pub mod triggers;
+
+pub use triggers::{ArmedStop, TriggerEngine};
+
+// Limit orders pegged to the touch
+pub mod pegs;
+
+pub use pegs::{Peg, PegConfig, PegManager, PegReference, PeggedOrder, Reprice};
+
+// Dry runs of order submissions
+pub mod preflight;
+
+pub use preflight::{BalanceCheck, PreflightReport, RateLimitCheck};
+
+// OpenTelemetry export
+#[cfg(feature = "otel")]
+pub mod telemetry;
+
 // Initialize tracing
 pub fn init_tracing() {
     use tracing_subscriber::{fmt, prelude::*, EnvFilter};
@@ -131,3 +198,67 @@ mod tests {
         let _: OrderStatus;
     }
 }
+
+// Per-topic market data sequence tracking and gap detection
+pub mod sequence;
+
+pub use sequence::{SequenceConfig, SequenceEvent, SequenceStats, SequenceTracker};
+
+// Locally maintained order books with checksum and snapshot validation
+pub mod book;
+
+pub use book::{BookCheck, BookDivergence, BookValidationConfig, BookValidationStats, BookValidator, OrderBook};
+
+// Kill-switch and session exits: market, limit ladder or passive
+pub mod liquidation;
+
+pub use liquidation::{liquidate, LiquidationConfig, LiquidationReport, LiquidationStyle};
+
+// Delta hedging of crypto-linked binary markets with perps
+pub mod hedging;
+
+pub use hedging::{BinaryDirection, HedgeAdjustment, HedgeConfig, HedgedMarket, Hedger, ImpliedCurve, PerpContract};
+
+// OMS vs venue reconciliation status
+pub mod reconcile;
+
+pub use reconcile::{
+    Discrepancy, DiscrepancyKind, ReconciliationConfig, ReconciliationReport, Reconciler, VenueReconciliation,
+};
+
+// Order status polling for venues without streaming updates
+pub mod status_poll;
+
+pub use status_poll::{OrderUpdate, StatusPollConfig, StatusPoller};
+
+// CLOB price history for indicator warm-up
+pub mod price_history;
+
+pub use price_history::{to_bars, HistoryRange, PriceBar, PriceHistoryClient, PricePoint, POLYMARKET_CLOB_ENDPOINT};
+
+// Shutdown verification of cancelled orders
+pub mod shutdown;
+
+pub use shutdown::{verify_shutdown, ShutdownCheckConfig, ShutdownReport, VenueShutdown};
+
+// Venue health monitoring and order flow pausing
+pub mod venue_status;
+
+pub use venue_status::{VenueState, VenueStatus, VenueStatusChange, VenueStatusConfig, VenueStatusMonitor};
+
+// Net positions with lock-free reads for the order path
+pub mod positions;
+
+pub use positions::{PositionBook, PositionSnapshot};
+
+// Position import from venues on startup
+pub mod bootstrap;
+
+pub use bootstrap::{
+    bootstrap_positions, BootstrapReport, ImportedPosition, PositionBootstrapConfig, PositionSource, VenueBootstrap,
+};
+
+// Versioned wire protocol for streaming metrics to the monitor
+pub mod monitor;
+
+pub use monitor::{MetricMessage, MetricType, MonitorClient, PROTOCOL_VERSION};
@@ -0,0 +1,273 @@
+//! Position liquidation algorithms
+//!
+//! When a kill-switch fires or a session closes, positions are closed with a
+//! configurable [`LiquidationStyle`]: immediate market orders, an aggressive
+//! limit ladder that crosses the spread and walks deeper into the book each
+//! step, or a passive exit resting at the touch until a time limit. Every
+//! style falls back to market orders when it runs out of steps or time.
+//!
+//! [`liquidate`] drives one market to flat with reduce-only orders
+//! (accepted under a kill-switch), re-checking the position every
+//! `step_interval_ms` until it is flat or `timeout_secs` elapse.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::engine::ExecutionEngine;
+use crate::error::ExecResult;
+use crate::oms::post_only::Touch;
+use crate::order::{MarketId, Order, OrderId, OrderStatus, OrderType, Side, TimeInForce};
+
+/// Positions below this size count as flat
+pub(crate) const FLAT_EPSILON: f64 = 1e-8;
+
+/// How positions are closed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiquidationStyle {
+    /// Market (IOC) orders, resent each step until flat
+    #[default]
+    Market,
+    /// Limit orders crossing the spread, repriced `step_bps` deeper into the
+    /// book each step; market orders after `max_steps`
+    LimitLadder { step_bps: f64, max_steps: u32 },
+    /// Limit orders resting at the touch on the position's side, re-pegged
+    /// each step; market orders after `exit_within_secs`
+    Passive { exit_within_secs: u64 },
+}
+
+impl LiquidationStyle {
+    /// Limit price for the exit order at `step` (0-based), `elapsed` after the
+    /// liquidation started; `None` means a market order
+    pub fn limit_price(&self, side: Side, touch: Option<&Touch>, step: u32, elapsed: Duration) -> Option<f64> {
+        let touch = touch?;
+        match *self {
+            LiquidationStyle::Market => None,
+            LiquidationStyle::LimitLadder { step_bps, max_steps } => {
+                if step >= max_steps {
+                    return None;
+                }
+                let offset = step_bps * step as f64 / 10_000.0;
+                match side {
+                    Side::Sell => touch.best_bid.map(|bid| bid * (1.0 - offset)),
+                    Side::Buy => touch.best_ask.map(|ask| ask * (1.0 + offset)),
+                }
+            }
+            LiquidationStyle::Passive { exit_within_secs } => {
+                if elapsed >= Duration::from_secs(exit_within_secs) {
+                    return None;
+                }
+                match side {
+                    Side::Sell => touch.best_ask.or(touch.best_bid),
+                    Side::Buy => touch.best_bid.or(touch.best_ask),
+                }
+            }
+        }
+    }
+}
+
+/// Liquidation settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LiquidationConfig {
+    /// How exit orders are priced
+    pub style: LiquidationStyle,
+
+    /// Time between steps (repricing, resending and position checks)
+    pub step_interval_ms: u64,
+
+    /// Stop tracking a position that is not flat after this long
+    pub timeout_secs: u64,
+}
+
+impl Default for LiquidationConfig {
+    fn default() -> Self {
+        Self {
+            style: LiquidationStyle::Market,
+            step_interval_ms: 1_000,
+            timeout_secs: 300,
+        }
+    }
+}
+
+impl LiquidationConfig {
+    /// Time between steps
+    pub fn step_interval(&self) -> Duration {
+        Duration::from_millis(self.step_interval_ms)
+    }
+
+    /// Give-up time
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+/// Outcome of a liquidation
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidationReport {
+    /// Market liquidated
+    pub market: MarketId,
+    /// Position when the liquidation started
+    pub initial_position: f64,
+    /// Position when it ended (zero unless it timed out)
+    pub final_position: f64,
+    /// Exit orders submitted
+    pub orders_sent: u32,
+    /// Whether market orders were used (style or fallback)
+    pub used_market_orders: bool,
+    /// Time from start to flat (or timeout)
+    pub elapsed: Duration,
+}
+
+impl LiquidationReport {
+    /// Whether the position was closed
+    pub fn is_flat(&self) -> bool {
+        self.final_position.abs() < FLAT_EPSILON
+    }
+}
+
+/// Close the position in `market`, tracking it until flat or timed out
+///
+/// Each step cancels the previous exit order, re-reads the position and
+/// submits a reduce-only order for the remainder priced by the style. Fills
+/// reach the position through [`ExecutionEngine::record_fill`] as usual.
+pub async fn liquidate(
+    engine: &ExecutionEngine,
+    market: &MarketId,
+    config: &LiquidationConfig,
+) -> ExecResult<LiquidationReport> {
+    let started = Instant::now();
+    let initial_position = engine.get_position(market.as_str()).await;
+    let mut report = LiquidationReport {
+        market: market.clone(),
+        initial_position,
+        final_position: initial_position,
+        orders_sent: 0,
+        used_market_orders: false,
+        elapsed: Duration::ZERO,
+    };
+    if initial_position.abs() < FLAT_EPSILON {
+        return Ok(report);
+    }
+
+    let venue = engine.position_venue(market)?;
+    info!("Liquidating {} in {} ({:?})", initial_position, market, config.style);
+
+    let mut working: Option<OrderId> = None;
+    let mut step = 0;
+    loop {
+        // A cancel racing a fill fails harmlessly; the position re-read
+        // below reflects whatever filled
+        if let Some(order_id) = working.take() {
+            let _ = engine.cancel_order(order_id).await;
+        }
+
+        let position = engine.get_position(market.as_str()).await;
+        report.final_position = position;
+        report.elapsed = started.elapsed();
+        if position.abs() < FLAT_EPSILON {
+            info!("Liquidated {} in {:?} with {} orders", market, report.elapsed, report.orders_sent);
+            return Ok(report);
+        }
+        if report.elapsed >= config.timeout() {
+            warn!("Liquidation of {} timed out with position {}", market, position);
+            return Ok(report);
+        }
+
+        let side = if position > 0.0 { Side::Sell } else { Side::Buy };
+        let touch = engine.get_touch(market).await;
+        let mut order = match config.style.limit_price(side, touch.as_ref(), step, report.elapsed) {
+            Some(price) => Order::new(
+                venue.clone(),
+                market.clone(),
+                side,
+                OrderType::Limit,
+                Some(price),
+                position.abs(),
+                TimeInForce::GTC,
+                String::new(),
+            ),
+            None => {
+                report.used_market_orders = true;
+                Order::new(
+                    venue.clone(),
+                    market.clone(),
+                    side,
+                    OrderType::Market,
+                    None,
+                    position.abs(),
+                    TimeInForce::IOC,
+                    String::new(),
+                )
+            }
+        }
+        .stamped(engine.clock().as_ref(), engine.id_generator().as_ref())
+        .with_reduce_only();
+        order.client_order_id = format!("liquidate-{}", order.id);
+
+        match engine.submit_order(order).await {
+            Ok(ack) => {
+                report.orders_sent += 1;
+                if matches!(ack.status, OrderStatus::Pending | OrderStatus::Working | OrderStatus::PartiallyFilled) {
+                    working = Some(ack.order_id);
+                }
+            }
+            Err(e) => warn!("Liquidation order for {} failed: {}", market, e),
+        }
+
+        step += 1;
+        tokio::time::sleep(config.step_interval()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch() -> Touch {
+        Touch {
+            best_bid: Some(0.50),
+            best_ask: Some(0.52),
+        }
+    }
+
+    #[test]
+    fn test_limit_ladder_walks_the_book() {
+        let style = LiquidationStyle::LimitLadder {
+            step_bps: 100.0,
+            max_steps: 3,
+        };
+        let price = |step| style.limit_price(Side::Sell, Some(&touch()), step, Duration::ZERO);
+
+        assert_eq!(price(0), Some(0.50));
+        assert!((price(2).unwrap() - 0.49).abs() < 1e-12);
+        assert_eq!(price(3), None);
+        assert_eq!(style.limit_price(Side::Sell, None, 0, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_passive_exit_times_out() {
+        let style = LiquidationStyle::Passive { exit_within_secs: 60 };
+
+        assert_eq!(style.limit_price(Side::Sell, Some(&touch()), 5, Duration::from_secs(10)), Some(0.52));
+        assert_eq!(style.limit_price(Side::Buy, Some(&touch()), 5, Duration::from_secs(10)), Some(0.50));
+        assert_eq!(style.limit_price(Side::Sell, Some(&touch()), 5, Duration::from_secs(60)), None);
+        assert_eq!(LiquidationStyle::Market.limit_price(Side::Sell, Some(&touch()), 0, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_config_deserialize() {
+        let config: LiquidationConfig =
+            serde_json::from_str(r#"{"style": {"type": "limit_ladder", "step_bps": 25.0, "max_steps": 4}}"#).unwrap();
+        assert_eq!(
+            config.style,
+            LiquidationStyle::LimitLadder {
+                step_bps: 25.0,
+                max_steps: 4
+            }
+        );
+        assert_eq!(config.timeout_secs, 300);
+    }
+}
@@ -0,0 +1,334 @@
+//! Per-market quality metrics
+//!
+//! The [`MarketQualityRecorder`] keeps a rolling window of book and trade
+//! observations for every subscribed market and summarizes it as a
+//! [`MarketQuality`]: current and average spread, depth over the top
+//! levels, trade frequency and realized volatility of the mid.
+//!
+//! The summaries feed the [`MarketScanner`](crate::scanner::MarketScanner)
+//! ranking (observed spread and depth instead of catalog quotes) and
+//! slippage estimates for backtests (`half_spread_bps`).
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::oms::BookDepth;
+use crate::order::MarketId;
+
+/// Recorder settings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketQualityConfig {
+    /// Rolling window in seconds for averages, trade frequency and volatility
+    pub window_secs: u64,
+
+    /// Book levels counted per side for depth
+    pub depth_levels: usize,
+}
+
+impl Default for MarketQualityConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 300,
+            depth_levels: 5,
+        }
+    }
+}
+
+impl MarketQualityConfig {
+    /// Rolling window as a [`Duration`]
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+}
+
+/// Quality summary for one market over the recorder window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketQuality {
+    /// Market identifier
+    pub market_id: MarketId,
+    /// Time of the summary
+    pub timestamp: DateTime<Utc>,
+    /// Latest mid price
+    pub mid: Option<f64>,
+    /// Latest spread (ask - bid)
+    pub spread: Option<f64>,
+    /// Latest spread in basis points of the mid
+    pub spread_bps: Option<f64>,
+    /// Average spread over the window
+    pub avg_spread: Option<f64>,
+    /// Bid size over the top `depth_levels`
+    pub bid_depth: f64,
+    /// Ask size over the top `depth_levels`
+    pub ask_depth: f64,
+    /// Notional (price * size) over the top `depth_levels` on both sides
+    pub depth_usd: f64,
+    /// Trades in the window
+    pub trade_count: usize,
+    /// Trades per minute over the window
+    pub trades_per_minute: f64,
+    /// Traded size in the window
+    pub traded_volume: f64,
+    /// Realized volatility of the mid over the window
+    /// (square root of the summed squared log returns)
+    pub realized_volatility: f64,
+}
+
+impl MarketQuality {
+    /// Half the average (or latest) spread in basis points of the mid,
+    /// a baseline slippage estimate for crossing orders
+    pub fn half_spread_bps(&self) -> Option<f64> {
+        let spread = self.avg_spread.or(self.spread)?;
+        let mid = self.mid.filter(|m| *m > 0.0)?;
+        Some(spread / mid * 10_000.0 / 2.0)
+    }
+
+    /// Named values for metric export
+    pub fn metric_values(&self) -> Vec<(&'static str, f64)> {
+        let mut values = vec![
+            ("market.bid_depth", self.bid_depth),
+            ("market.ask_depth", self.ask_depth),
+            ("market.depth_usd", self.depth_usd),
+            ("market.trades_per_minute", self.trades_per_minute),
+            ("market.traded_volume", self.traded_volume),
+            ("market.realized_volatility", self.realized_volatility),
+        ];
+        if let Some(mid) = self.mid {
+            values.push(("market.mid", mid));
+        }
+        if let Some(spread) = self.spread {
+            values.push(("market.spread", spread));
+        }
+        if let Some(spread_bps) = self.spread_bps {
+            values.push(("market.spread_bps", spread_bps));
+        }
+        if let Some(avg_spread) = self.avg_spread {
+            values.push(("market.avg_spread", avg_spread));
+        }
+        values
+    }
+}
+
+/// Rolling observations for one market
+#[derive(Debug, Default)]
+struct MarketWindow {
+    book: BookDepth,
+    /// (time, spread) samples
+    spreads: VecDeque<(DateTime<Utc>, f64)>,
+    /// (time, mid) samples
+    mids: VecDeque<(DateTime<Utc>, f64)>,
+    /// (time, size) trade prints
+    trades: VecDeque<(DateTime<Utc>, f64)>,
+}
+
+impl MarketWindow {
+    fn prune(&mut self, cutoff: DateTime<Utc>) {
+        while self.spreads.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.spreads.pop_front();
+        }
+        while self.mids.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.mids.pop_front();
+        }
+        while self.trades.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.trades.pop_front();
+        }
+    }
+}
+
+/// Rolling per-market quality recorder
+#[derive(Debug, Default)]
+pub struct MarketQualityRecorder {
+    config: MarketQualityConfig,
+    markets: HashMap<MarketId, MarketWindow>,
+}
+
+impl MarketQualityRecorder {
+    /// Create a recorder
+    pub fn new(config: MarketQualityConfig) -> Self {
+        Self {
+            config,
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Recorder settings
+    pub fn config(&self) -> &MarketQualityConfig {
+        &self.config
+    }
+
+    /// Record a book update
+    pub fn on_book(&mut self, market: &MarketId, book: &BookDepth, at: DateTime<Utc>) {
+        let cutoff = self.cutoff(at);
+        let levels = self.config.depth_levels;
+        let window = self.markets.entry(market.clone()).or_default();
+
+        window.book = BookDepth {
+            bids: book.bids.iter().take(levels).copied().collect(),
+            asks: book.asks.iter().take(levels).copied().collect(),
+        };
+
+        if let (Some((bid, _)), Some((ask, _))) = (book.bids.first(), book.asks.first()) {
+            if ask >= bid {
+                window.spreads.push_back((at, ask - bid));
+                let mid = (bid + ask) / 2.0;
+                if mid > 0.0 {
+                    window.mids.push_back((at, mid));
+                }
+            }
+        }
+        window.prune(cutoff);
+    }
+
+    /// Record a trade print
+    pub fn on_trade(&mut self, market: &MarketId, size: f64, at: DateTime<Utc>) {
+        let cutoff = self.cutoff(at);
+        let window = self.markets.entry(market.clone()).or_default();
+        window.trades.push_back((at, size.abs()));
+        window.prune(cutoff);
+    }
+
+    /// Stop tracking a market
+    pub fn remove_market(&mut self, market: &MarketId) {
+        self.markets.remove(market);
+    }
+
+    /// Tracked markets
+    pub fn markets(&self) -> Vec<MarketId> {
+        self.markets.keys().cloned().collect()
+    }
+
+    /// Quality summary for a market at `now`
+    pub fn quality(&self, market: &MarketId, now: DateTime<Utc>) -> Option<MarketQuality> {
+        let window = self.markets.get(market)?;
+        let cutoff = self.cutoff(now);
+
+        let spreads: Vec<f64> = window
+            .spreads
+            .iter()
+            .filter(|(t, _)| *t >= cutoff)
+            .map(|(_, s)| *s)
+            .collect();
+        let mids: Vec<f64> = window
+            .mids
+            .iter()
+            .filter(|(t, _)| *t >= cutoff)
+            .map(|(_, m)| *m)
+            .collect();
+        let trades: Vec<f64> = window
+            .trades
+            .iter()
+            .filter(|(t, _)| *t >= cutoff)
+            .map(|(_, s)| *s)
+            .collect();
+
+        let touch = match (window.book.bids.first(), window.book.asks.first()) {
+            (Some((bid, _)), Some((ask, _))) if ask >= bid => Some((*bid, *ask)),
+            _ => None,
+        };
+        let spread = touch.map(|(bid, ask)| ask - bid);
+        let mid = touch.map(|(bid, ask)| (bid + ask) / 2.0);
+        let spread_bps = spread.zip(mid.filter(|m| *m > 0.0)).map(|(s, m)| s / m * 10_000.0);
+        let avg_spread = (!spreads.is_empty()).then(|| spreads.iter().sum::<f64>() / spreads.len() as f64);
+
+        let realized_volatility = mids
+            .windows(2)
+            .map(|pair| (pair[1] / pair[0]).ln().powi(2))
+            .sum::<f64>()
+            .sqrt();
+
+        let minutes = self.config.window_secs as f64 / 60.0;
+        let trades_per_minute = if minutes > 0.0 {
+            trades.len() as f64 / minutes
+        } else {
+            0.0
+        };
+
+        Some(MarketQuality {
+            market_id: market.clone(),
+            timestamp: now,
+            mid,
+            spread,
+            spread_bps,
+            avg_spread,
+            bid_depth: window.book.bids.iter().map(|(_, size)| size).sum(),
+            ask_depth: window.book.asks.iter().map(|(_, size)| size).sum(),
+            depth_usd: window
+                .book
+                .bids
+                .iter()
+                .chain(window.book.asks.iter())
+                .map(|(price, size)| price * size)
+                .sum(),
+            trade_count: trades.len(),
+            trades_per_minute,
+            traded_volume: trades.iter().sum(),
+            realized_volatility,
+        })
+    }
+
+    /// Quality summaries for every tracked market
+    pub fn snapshot(&self, now: DateTime<Utc>) -> Vec<MarketQuality> {
+        let mut qualities: Vec<MarketQuality> = self
+            .markets
+            .keys()
+            .filter_map(|market| self.quality(market, now))
+            .collect();
+        qualities.sort_by(|a, b| a.market_id.as_str().cmp(b.market_id.as_str()));
+        qualities
+    }
+
+    fn cutoff(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        at - chrono::Duration::seconds(self.config.window_secs as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_quality_window() {
+        let mut recorder = MarketQualityRecorder::new(MarketQualityConfig {
+            window_secs: 60,
+            depth_levels: 2,
+        });
+        let market = MarketId::new("m1");
+        let t0 = Utc::now();
+
+        let book = BookDepth {
+            bids: vec![(0.49, 100.0), (0.48, 200.0), (0.47, 1000.0)],
+            asks: vec![(0.51, 150.0), (0.52, 50.0)],
+        };
+        recorder.on_book(&market, &book, t0);
+
+        let wider = BookDepth {
+            bids: vec![(0.48, 100.0), (0.47, 200.0)],
+            asks: vec![(0.52, 150.0), (0.53, 50.0)],
+        };
+        recorder.on_book(&market, &wider, t0 + chrono::Duration::seconds(10));
+        recorder.on_trade(&market, 25.0, t0 + chrono::Duration::seconds(20));
+        recorder.on_trade(&market, -5.0, t0 + chrono::Duration::seconds(30));
+
+        let quality = recorder.quality(&market, t0 + chrono::Duration::seconds(30)).unwrap();
+        assert!((quality.spread.unwrap() - 0.04).abs() < 1e-9);
+        assert!((quality.avg_spread.unwrap() - 0.03).abs() < 1e-9);
+        assert_eq!(quality.bid_depth, 300.0);
+        assert_eq!(quality.ask_depth, 200.0);
+        assert_eq!(quality.trade_count, 2);
+        assert_eq!(quality.traded_volume, 30.0);
+        assert_eq!(quality.trades_per_minute, 2.0);
+        assert_eq!(quality.realized_volatility, 0.0);
+        assert!((quality.half_spread_bps().unwrap() - 300.0).abs() < 1e-6);
+
+        // Samples older than the window drop out
+        let later = recorder.quality(&market, t0 + chrono::Duration::seconds(65)).unwrap();
+        assert_eq!(later.trade_count, 2);
+        assert!((later.avg_spread.unwrap() - 0.04).abs() < 1e-9);
+
+        recorder.remove_market(&market);
+        assert!(recorder.snapshot(t0).is_empty());
+    }
+}
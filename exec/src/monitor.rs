@@ -0,0 +1,369 @@
+//! Monitor metrics wire protocol
+//!
+//! Bots stream metrics to the Go monitor over a websocket (`/metrics`). The
+//! frames are JSON objects tagged by `type`, and the types here are the
+//! Rust side of the schema specified in `monitor/protocol/README.md`; the
+//! monitor's `internal/protocol` package is the Go side. Both test suites
+//! decode the golden frames in `monitor/protocol/v1/`, so a change to one
+//! side that the other can't read fails a build instead of silently
+//! dropping metrics.
+//!
+//! A session starts with a handshake: the client sends [`Hello`] with its
+//! [`PROTOCOL_VERSION`] and the server answers `welcome` with the version
+//! both will speak, or `rejected` with the range it supports and closes.
+//! [`MonitorClient::connect`] performs the handshake and fails with the
+//! server's reason on rejection.
+//!
+//! Evolution rules:
+//!
+//! - Within a version, fields are only ever added, and added fields are
+//!   optional; receivers ignore fields they don't know
+//! - Removing or retyping a field, or changing its meaning, bumps
+//!   [`PROTOCOL_VERSION`]; the server keeps accepting versions down to
+//!   its minimum and answers with the lower of the two
+//! - Frames without a `type` are version 0 metrics, the format from before
+//!   the handshake, still accepted by the monitor
+//!
+//! ```rust
+//! use ag_exec::monitor::{decode_client_message, ClientMessage, MetricMessage, MetricType};
+//!
+//! let metric = MetricMessage::new("polymarket.rtds.lag_ms", MetricType::Gauge, 45.3).with_label("topic", "market");
+//! let frame = serde_json::to_string(&ClientMessage::Metric(metric.clone())).unwrap();
+//! assert!(frame.starts_with(r#"{"type":"metric","#));
+//! assert_eq!(decode_client_message(&frame).unwrap(), ClientMessage::Metric(metric));
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::info;
+
+use crate::error::{ExecError, ExecResult};
+
+/// Protocol name carried in [`Hello`]
+pub const PROTOCOL_NAME: &str = "ag-monitor";
+
+/// Latest protocol version, spoken by this client
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest protocol version this client can speak
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Version of frames sent without a handshake (untagged metrics)
+pub const LEGACY_PROTOCOL_VERSION: u32 = 0;
+
+/// How long [`MonitorClient::connect`] waits for the server's answer
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Kind of metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+/// One metric sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricMessage {
+    /// Sample time, milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub metric_type: MetricType,
+    pub metric_name: String,
+    pub value: f64,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+impl MetricMessage {
+    /// Sample taken now, without labels
+    pub fn new(metric_name: impl Into<String>, metric_type: MetricType, value: f64) -> Self {
+        Self {
+            timestamp: Utc::now().timestamp_millis().max(0) as u64,
+            metric_type,
+            metric_name: metric_name.into(),
+            value,
+            labels: HashMap::new(),
+        }
+    }
+
+    /// Add a label
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Handshake opening a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hello {
+    /// Always [`PROTOCOL_NAME`]
+    pub protocol: String,
+    /// Highest version the client speaks
+    pub version: u32,
+    /// Client name and version, for the monitor's logs
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub client: String,
+}
+
+impl Hello {
+    /// Hello for the latest version
+    pub fn new(client: impl Into<String>) -> Self {
+        Self {
+            protocol: PROTOCOL_NAME.to_string(),
+            version: PROTOCOL_VERSION,
+            client: client.into(),
+        }
+    }
+}
+
+/// Frame sent by a bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Hello(Hello),
+    Metric(MetricMessage),
+}
+
+/// Frame sent by the monitor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Handshake accepted; `version` is the version spoken from now on
+    Welcome {
+        version: u32,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        server: String,
+    },
+    /// Handshake refused; the server closes the connection
+    Rejected {
+        reason: String,
+        min_version: u32,
+        max_version: u32,
+    },
+}
+
+/// Version a server supporting `min_version..=max_version` speaks with a
+/// client, or the reason it refuses the handshake
+///
+/// Mirrors `protocol.Negotiate` in the monitor.
+pub fn negotiate(hello: &Hello, min_version: u32, max_version: u32) -> Result<u32, String> {
+    if hello.protocol != PROTOCOL_NAME {
+        return Err(format!(
+            "unknown protocol {:?}, expected {:?}",
+            hello.protocol, PROTOCOL_NAME
+        ));
+    }
+    if hello.version < min_version {
+        return Err(format!(
+            "protocol version {} is older than the supported range {}-{}",
+            hello.version, min_version, max_version
+        ));
+    }
+    Ok(hello.version.min(max_version))
+}
+
+/// Decode a client frame, reading untagged frames as version 0 metrics
+pub fn decode_client_message(text: &str) -> Result<ClientMessage, serde_json::Error> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+    if value.get("type").is_some() {
+        serde_json::from_value(value)
+    } else {
+        serde_json::from_value(value).map(ClientMessage::Metric)
+    }
+}
+
+/// Metrics connection to the monitor, opened with a handshake
+pub struct MonitorClient {
+    ws: Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    version: u32,
+}
+
+impl MonitorClient {
+    /// Connect to the monitor's metrics endpoint and negotiate the protocol
+    /// version; `client` identifies the bot in the monitor's logs
+    pub async fn connect(endpoint: &str, client: &str) -> ExecResult<Self> {
+        let (mut ws, _) = connect_async(endpoint)
+            .await
+            .map_err(|e| ExecError::WebSocketError(format!("connect to {}: {}", endpoint, e)))?;
+
+        let hello = serde_json::to_string(&ClientMessage::Hello(Hello::new(client)))?;
+        ws.send(Message::Text(hello))
+            .await
+            .map_err(|e| ExecError::WebSocketError(e.to_string()))?;
+
+        let answer = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
+            loop {
+                match ws.next().await {
+                    Some(Ok(Message::Text(text))) => return Ok(text),
+                    Some(Ok(Message::Close(_))) | None => {
+                        return Err(ExecError::WebSocketError(
+                            "monitor closed the connection during the handshake".to_string(),
+                        ))
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(ExecError::WebSocketError(e.to_string())),
+                }
+            }
+        })
+        .await
+        .map_err(|_| {
+            ExecError::Timeout(format!(
+                "no handshake answer from {} within {:?} (monitor predates protocol v1?)",
+                endpoint, HANDSHAKE_TIMEOUT
+            ))
+        })??;
+
+        match serde_json::from_str::<ServerMessage>(&answer) {
+            Ok(ServerMessage::Welcome { version, .. })
+                if (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) =>
+            {
+                info!("Connected to monitor at {} (protocol v{})", endpoint, version);
+                Ok(Self {
+                    ws: Mutex::new(ws),
+                    version,
+                })
+            }
+            Ok(ServerMessage::Welcome { version, .. }) => Err(ExecError::InvalidResponse(format!(
+                "monitor chose protocol v{}, client supports {}-{}",
+                version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+            ))),
+            Ok(ServerMessage::Rejected {
+                reason,
+                min_version,
+                max_version,
+            }) => Err(ExecError::ConfigError(format!(
+                "monitor rejected protocol v{} (supports {}-{}): {}",
+                PROTOCOL_VERSION, min_version, max_version, reason
+            ))),
+            Err(e) => Err(ExecError::InvalidResponse(format!(
+                "unexpected handshake answer {:?}: {}",
+                answer, e
+            ))),
+        }
+    }
+
+    /// Protocol version negotiated with the monitor
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Send one metric
+    pub async fn send(&self, metric: MetricMessage) -> ExecResult<()> {
+        let frame = serde_json::to_string(&ClientMessage::Metric(metric))?;
+        self.ws
+            .lock()
+            .await
+            .send(Message::Text(frame))
+            .await
+            .map_err(|e| ExecError::WebSocketError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: &str = include_str!("../../monitor/protocol/v1/hello.json");
+    const WELCOME: &str = include_str!("../../monitor/protocol/v1/welcome.json");
+    const REJECTED: &str = include_str!("../../monitor/protocol/v1/rejected.json");
+    const METRIC: &str = include_str!("../../monitor/protocol/v1/metric.json");
+    const LEGACY_METRIC: &str = include_str!("../../monitor/protocol/v1/legacy_metric.json");
+
+    /// Encoding a decoded golden frame must reproduce it field for field
+    fn assert_round_trip<T: Serialize>(decoded: &T, golden: &str) {
+        let encoded = serde_json::to_value(decoded).unwrap();
+        let golden: serde_json::Value = serde_json::from_str(golden).unwrap();
+        assert_eq!(encoded, golden);
+    }
+
+    #[test]
+    fn test_golden_frames() {
+        let hello = decode_client_message(HELLO).unwrap();
+        assert_eq!(hello, ClientMessage::Hello(Hello::new("minibot/0.1.0")));
+        assert_round_trip(&hello, HELLO);
+
+        let metric = decode_client_message(METRIC).unwrap();
+        let ClientMessage::Metric(sample) = &metric else {
+            panic!("expected a metric, got {:?}", metric)
+        };
+        assert_eq!(sample.metric_type, MetricType::Gauge);
+        assert_eq!(sample.labels["topic"], "market");
+        assert_round_trip(&metric, METRIC);
+
+        // Untagged frames are version 0 metrics
+        let ClientMessage::Metric(legacy) = decode_client_message(LEGACY_METRIC).unwrap() else {
+            panic!()
+        };
+        assert_eq!(legacy.metric_type, MetricType::Counter);
+        assert_eq!(legacy.value, 12.0);
+
+        let welcome: ServerMessage = serde_json::from_str(WELCOME).unwrap();
+        assert_eq!(
+            welcome,
+            ServerMessage::Welcome {
+                version: 1,
+                server: PROTOCOL_NAME.to_string()
+            }
+        );
+        assert_round_trip(&welcome, WELCOME);
+
+        let rejected: ServerMessage = serde_json::from_str(REJECTED).unwrap();
+        assert_round_trip(&rejected, REJECTED);
+
+        // Receivers ignore fields added later within the version
+        let extended = r#"{"type":"welcome","version":1,"server":"ag-monitor","compression":"none"}"#;
+        assert!(serde_json::from_str::<ServerMessage>(extended).is_ok());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let hello = Hello::new("test");
+        assert_eq!(negotiate(&hello, 1, 1), Ok(1));
+        // Newer server answers with the client's version
+        assert_eq!(negotiate(&hello, 1, 3), Ok(1));
+        // Newer client is answered with the server's latest
+        assert_eq!(
+            negotiate(
+                &Hello {
+                    version: 2,
+                    ..hello.clone()
+                },
+                1,
+                1
+            ),
+            Ok(1)
+        );
+
+        let ServerMessage::Rejected { reason, .. } = serde_json::from_str(REJECTED).unwrap() else {
+            panic!()
+        };
+        assert_eq!(
+            negotiate(
+                &Hello {
+                    version: 0,
+                    ..hello.clone()
+                },
+                1,
+                1
+            ),
+            Err(reason)
+        );
+        assert!(negotiate(
+            &Hello {
+                protocol: "other".to_string(),
+                ..hello
+            },
+            1,
+            1
+        )
+        .is_err());
+    }
+}
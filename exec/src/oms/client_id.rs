@@ -0,0 +1,265 @@
+//! Client order ID generation
+//!
+//! IDs have the form `{prefix}-{strategy}-{sequence}`, e.g. `ag-mm_1-2s`:
+//!
+//! - `prefix` tags the bot instance / account (`[A-Za-z0-9]`, up to 8 chars)
+//! - `strategy` is the owning strategy ID (`[A-Za-z0-9_]`)
+//! - `sequence` is a monotonic counter in lowercase base36
+//!
+//! The character set and length limit are accepted by Polymarket and common
+//! CEX APIs. Ownership can be recovered from an ID alone with
+//! [`ClientOrderIdGenerator::parse`], e.g. when reconciling open orders
+//! after a restart.
+//!
+//! With a store path, sequence numbers are reserved in blocks and the
+//! high-water mark is persisted, so IDs never repeat across restarts (a
+//! crash skips at most the rest of one block).
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::{ExecError, ExecResult};
+
+/// Default maximum ID length
+pub const DEFAULT_MAX_LEN: usize = 36;
+
+/// Sequence numbers reserved per store write
+const RESERVE_BLOCK: u64 = 1_000;
+
+/// Components of a generated client order ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedClientOrderId {
+    /// Instance / account prefix
+    pub prefix: String,
+    /// Owning strategy ID
+    pub strategy_id: String,
+    /// Monotonic sequence number
+    pub sequence: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredState {
+    /// First sequence number not yet reserved
+    next_unreserved: u64,
+}
+
+#[derive(Debug)]
+struct State {
+    next: u64,
+    reserved_until: u64,
+}
+
+/// Generator of compact, collision-free client order IDs
+#[derive(Debug)]
+pub struct ClientOrderIdGenerator {
+    prefix: String,
+    max_len: usize,
+    store_path: Option<PathBuf>,
+    state: Mutex<State>,
+}
+
+impl ClientOrderIdGenerator {
+    /// Create an in-memory generator starting at sequence 1
+    pub fn new(prefix: impl Into<String>) -> ExecResult<Self> {
+        let prefix = prefix.into();
+        if prefix.is_empty() || prefix.len() > 8 || !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(ExecError::ConfigError(format!(
+                "Client order ID prefix must be 1-8 alphanumeric chars (got '{}')",
+                prefix
+            )));
+        }
+
+        Ok(Self {
+            prefix,
+            max_len: DEFAULT_MAX_LEN,
+            store_path: None,
+            state: Mutex::new(State {
+                next: 1,
+                reserved_until: u64::MAX,
+            }),
+        })
+    }
+
+    /// Create a generator whose sequence persists in `path`
+    pub fn with_store(prefix: impl Into<String>, path: impl AsRef<Path>) -> ExecResult<Self> {
+        let mut generator = Self::new(prefix)?;
+        let path = path.as_ref().to_path_buf();
+
+        let next = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let stored: StoredState = serde_json::from_str(&content)?;
+            stored.next_unreserved.max(1)
+        } else {
+            1
+        };
+
+        generator.store_path = Some(path);
+        generator.state = Mutex::new(State {
+            next,
+            reserved_until: next,
+        });
+        Ok(generator)
+    }
+
+    /// Set the maximum ID length required by the venue
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Instance / account prefix
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Generate the next ID for a strategy
+    pub fn next_id(&self, strategy_id: &str) -> ExecResult<String> {
+        if strategy_id.is_empty() || !strategy_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(ExecError::ValidationError(format!(
+                "Strategy ID '{}' must be non-empty [A-Za-z0-9_] to encode in a client order ID",
+                strategy_id
+            )));
+        }
+
+        let sequence = {
+            let mut state = self
+                .state
+                .lock()
+                .map_err(|e| ExecError::InternalError(format!("Failed to acquire lock: {}", e)))?;
+
+            if state.next >= state.reserved_until {
+                let reserved_until = state.next + RESERVE_BLOCK;
+                self.persist(reserved_until)?;
+                debug!("Reserved client order IDs up to {}", reserved_until);
+                state.reserved_until = reserved_until;
+            }
+
+            let sequence = state.next;
+            state.next += 1;
+            sequence
+        };
+
+        let id = format!("{}-{}-{}", self.prefix, strategy_id, to_base36(sequence));
+        if id.len() > self.max_len {
+            return Err(ExecError::ValidationError(format!(
+                "Client order ID '{}' exceeds {} chars; use a shorter strategy ID or prefix",
+                id, self.max_len
+            )));
+        }
+        Ok(id)
+    }
+
+    /// Whether an ID was generated with this generator's prefix
+    pub fn owns(&self, client_order_id: &str) -> bool {
+        Self::parse(client_order_id).is_some_and(|parsed| parsed.prefix == self.prefix)
+    }
+
+    /// Parse a generated ID; `None` for IDs in any other format
+    pub fn parse(client_order_id: &str) -> Option<ParsedClientOrderId> {
+        let mut parts = client_order_id.splitn(3, '-');
+        let prefix = parts.next()?;
+        let strategy_id = parts.next()?;
+        let sequence = parts.next()?;
+
+        let valid = !prefix.is_empty()
+            && prefix.len() <= 8
+            && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+            && !strategy_id.is_empty()
+            && strategy_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !sequence.is_empty()
+            && sequence.chars().all(|c| c.is_ascii_digit() || c.is_ascii_lowercase());
+        if !valid {
+            return None;
+        }
+
+        Some(ParsedClientOrderId {
+            prefix: prefix.to_string(),
+            strategy_id: strategy_id.to_string(),
+            sequence: u64::from_str_radix(sequence, 36).ok()?,
+        })
+    }
+
+    /// Write the reservation high-water mark (atomic replace)
+    fn persist(&self, next_unreserved: u64) -> ExecResult<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+
+        let json = serde_json::to_string(&StoredState { next_unreserved })?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+fn to_base36(mut value: u64) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    if value == 0 {
+        return "0".to_string();
+    }
+
+    let mut digits = Vec::new();
+    while value > 0 {
+        digits.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("base36 digits are ASCII")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderId;
+
+    #[test]
+    fn test_generate_and_parse() {
+        let generator = ClientOrderIdGenerator::new("ag").unwrap();
+        assert_eq!(generator.next_id("mm_1").unwrap(), "ag-mm_1-1");
+
+        for _ in 0..34 {
+            generator.next_id("arb").unwrap();
+        }
+        let id = generator.next_id("arb").unwrap();
+        assert_eq!(id, "ag-arb-10");
+
+        let parsed = ClientOrderIdGenerator::parse(&id).unwrap();
+        assert_eq!(parsed.strategy_id, "arb");
+        assert_eq!(parsed.sequence, 36);
+        assert!(generator.owns(&id));
+        assert!(!generator.owns("bot2-arb-10"));
+        assert!(ClientOrderIdGenerator::parse("my-order-1").is_some());
+        assert!(ClientOrderIdGenerator::parse("client-123").is_none());
+
+        assert!(ClientOrderIdGenerator::new("bad-prefix").is_err());
+        assert!(generator.next_id("mm-1").is_err());
+        assert!(ClientOrderIdGenerator::new("ag")
+            .unwrap()
+            .with_max_len(8)
+            .next_id("market_maker")
+            .is_err());
+    }
+
+    #[test]
+    fn test_sequence_survives_restart() {
+        let path = std::env::temp_dir().join(format!("ag-exec-client-ids-{}.json", OrderId::new()));
+
+        let last = {
+            let generator = ClientOrderIdGenerator::with_store("ag", &path).unwrap();
+            generator.next_id("mm_1").unwrap();
+            generator.next_id("mm_1").unwrap()
+        };
+
+        let generator = ClientOrderIdGenerator::with_store("ag", &path).unwrap();
+        let next = generator.next_id("mm_1").unwrap();
+        let last_seq = ClientOrderIdGenerator::parse(&last).unwrap().sequence;
+        let next_seq = ClientOrderIdGenerator::parse(&next).unwrap().sequence;
+        assert!(next_seq > last_seq);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+//! Idempotent order submission
+//!
+//! Callers retrying after an ambiguous failure (a timeout, a dropped
+//! connection) cannot know whether the first attempt reached the venue.
+//! Submissions carrying the same idempotency key within the cache TTL are
+//! deduplicated: the retry gets the original acknowledgement (or the
+//! original order's current state if it is still in flight) instead of
+//! placing a second order.
+//!
+//! A key is released early only when its order provably never reached the
+//! venue (rejected by validation, risk or rate limiting, or rejected
+//! outright by the venue), so a corrected retry can go through.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use ag_risk::clock::{system_clock, SharedClock};
+use chrono::{DateTime, Utc};
+
+use crate::order::{OrderAck, OrderId};
+
+/// Default time a key is remembered
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(300);
+
+/// Result of claiming an idempotency key
+#[derive(Debug, Clone)]
+pub enum IdempotencyClaim {
+    /// First use of the key: submit the order
+    New,
+    /// The key's order was already acknowledged
+    Completed(OrderAck),
+    /// The key's order is still being submitted (or its outcome is unknown)
+    InFlight(OrderId),
+}
+
+#[derive(Debug)]
+struct Entry {
+    order_id: OrderId,
+    ack: Option<OrderAck>,
+    claimed_at: DateTime<Utc>,
+}
+
+/// Short-lived cache of submitted idempotency keys
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    ttl: Duration,
+    clock: SharedClock,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+impl IdempotencyCache {
+    /// Create a cache remembering keys for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_clock(ttl, system_clock())
+    }
+
+    /// Create a cache remembering keys for `ttl` as measured by `clock`
+    pub fn with_clock(ttl: Duration, clock: SharedClock) -> Self {
+        Self {
+            ttl,
+            clock,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Measure key lifetimes with `clock`
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Time a key is remembered
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Claim `key` for `order_id`, or report the order already holding it
+    pub fn claim(&self, key: &str, order_id: OrderId) -> IdempotencyClaim {
+        let mut entries = self.entries.lock().unwrap();
+        let now = self.clock.now();
+        entries.retain(|_, entry| (now - entry.claimed_at).to_std().map_or(true, |age| age < self.ttl));
+
+        if let Some(entry) = entries.get(key) {
+            return match &entry.ack {
+                Some(ack) => IdempotencyClaim::Completed(ack.clone()),
+                None => IdempotencyClaim::InFlight(entry.order_id),
+            };
+        }
+
+        entries.insert(
+            key.to_string(),
+            Entry {
+                order_id,
+                ack: None,
+                claimed_at: now,
+            },
+        );
+        IdempotencyClaim::New
+    }
+
+    /// Record the acknowledgement returned for a claimed key
+    pub fn complete(&self, key: &str, ack: OrderAck) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(key) {
+            entry.ack = Some(ack);
+        }
+    }
+
+    /// Forget a key whose order never reached the venue
+    pub fn release(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    /// Keys currently remembered (including expired ones not yet purged)
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether no keys are remembered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderStatus;
+    use ag_risk::clock::MockClock;
+    use std::sync::Arc;
+
+    fn ack(order_id: OrderId) -> OrderAck {
+        OrderAck {
+            order_id,
+            venue_order_id: Some("v-1".to_string()),
+            status: OrderStatus::Working,
+            timestamp: chrono::Utc::now(),
+            message: None,
+            tif_emulated: false,
+        }
+    }
+
+    #[test]
+    fn test_claim_lifecycle() {
+        let cache = IdempotencyCache::default();
+        let first = OrderId::new();
+        let retry = OrderId::new();
+
+        assert!(matches!(cache.claim("k1", first), IdempotencyClaim::New));
+        assert!(matches!(cache.claim("k1", retry), IdempotencyClaim::InFlight(id) if id == first));
+
+        cache.complete("k1", ack(first));
+        match cache.claim("k1", retry) {
+            IdempotencyClaim::Completed(ack) => assert_eq!(ack.order_id, first),
+            other => panic!("expected completed claim, got {:?}", other),
+        }
+
+        cache.release("k1");
+        assert!(matches!(cache.claim("k1", retry), IdempotencyClaim::New));
+    }
+
+    #[test]
+    fn test_keys_expire() {
+        let clock = MockClock::new(Utc::now());
+        let cache = IdempotencyCache::with_clock(Duration::from_secs(10), Arc::new(clock.clone()));
+        assert!(matches!(cache.claim("k1", OrderId::new()), IdempotencyClaim::New));
+        clock.advance(chrono::Duration::seconds(9));
+        assert!(matches!(cache.claim("k1", OrderId::new()), IdempotencyClaim::InFlight(_)));
+        clock.advance(chrono::Duration::seconds(1));
+        assert!(matches!(cache.claim("k1", OrderId::new()), IdempotencyClaim::New));
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,153 @@
+//! Post-only enforcement and crossing protection
+//!
+//! Post-only orders must rest on the book as maker liquidity. Before
+//! submission the order price is checked against the opposite touch: a buy
+//! at or above the best ask (or a sell at or below the best bid) would take
+//! liquidity, so it is either repriced one tick behind the touch or rejected.
+//! The venue-side post-only flag remains the backstop for stale touches.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExecError, ExecResult};
+use crate::order::{Order, OrderType, Side};
+
+/// What to do with a post-only order that would cross the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrossingAction {
+    /// Move the price one tick behind the opposite touch
+    Reprice,
+    /// Reject the order
+    Reject,
+}
+
+/// Best bid/ask for a market
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Touch {
+    /// Best bid price
+    pub best_bid: Option<f64>,
+    /// Best ask price
+    pub best_ask: Option<f64>,
+}
+
+impl Touch {
+    /// Midpoint of a two-sided touch
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid? + self.best_ask?) / 2.0)
+    }
+}
+
+/// Post-only crossing protection policy
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PostOnlyPolicy {
+    /// Action for crossing orders
+    pub on_cross: CrossingAction,
+    /// Venue tick size used when repricing
+    pub tick_size: f64,
+}
+
+impl Default for PostOnlyPolicy {
+    fn default() -> Self {
+        Self {
+            on_cross: CrossingAction::Reprice,
+            tick_size: 0.01,
+        }
+    }
+}
+
+impl PostOnlyPolicy {
+    /// Enforce post-only semantics against the opposite touch
+    ///
+    /// Returns `Ok(true)` if the order was repriced. Non post-only orders
+    /// and markets without a known touch pass through unchanged.
+    pub fn enforce(&self, order: &mut Order, touch: Option<&Touch>) -> ExecResult<bool> {
+        if order.order_type != OrderType::PostOnly {
+            return Ok(false);
+        }
+        let (Some(price), Some(touch)) = (order.price, touch) else {
+            return Ok(false);
+        };
+
+        let tick = self.tick_size;
+        let repriced = match order.side {
+            Side::Buy => match touch.best_ask {
+                // Highest tick strictly below the ask
+                Some(ask) if price >= ask => ((ask / tick - 1e-9).ceil() - 1.0) * tick,
+                _ => return Ok(false),
+            },
+            Side::Sell => match touch.best_bid {
+                // Lowest tick strictly above the bid
+                Some(bid) if price <= bid => ((bid / tick + 1e-9).floor() + 1.0) * tick,
+                _ => return Ok(false),
+            },
+        };
+
+        if self.on_cross == CrossingAction::Reject || repriced <= 0.0 {
+            return Err(ExecError::PostOnlyRejected {
+                venue: order.venue.to_string(),
+                message: format!(
+                    "{} post-only at {} would cross touch {:?}",
+                    order.side, price, touch
+                ),
+            });
+        }
+
+        order.price = Some(repriced);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{MarketId, TimeInForce, VenueId};
+
+    fn post_only(side: Side, price: f64) -> Order {
+        Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            side,
+            OrderType::PostOnly,
+            Some(price),
+            100.0,
+            TimeInForce::GTC,
+            "client-po".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_crossing_orders_repriced_or_rejected() {
+        let touch = Touch {
+            best_bid: Some(0.48),
+            best_ask: Some(0.52),
+        };
+        let policy = PostOnlyPolicy::default();
+
+        // Resting orders pass through
+        let mut order = post_only(Side::Buy, 0.50);
+        assert!(!policy.enforce(&mut order, Some(&touch)).unwrap());
+        assert_eq!(order.price, Some(0.50));
+
+        // Crossing buy moves one tick below the ask
+        let mut order = post_only(Side::Buy, 0.55);
+        assert!(policy.enforce(&mut order, Some(&touch)).unwrap());
+        assert!((order.price.unwrap() - 0.51).abs() < 1e-9);
+
+        // Crossing sell moves one tick above the bid
+        let mut order = post_only(Side::Sell, 0.48);
+        assert!(policy.enforce(&mut order, Some(&touch)).unwrap());
+        assert!((order.price.unwrap() - 0.49).abs() < 1e-9);
+
+        // Unknown touch cannot be checked
+        let mut order = post_only(Side::Buy, 0.99);
+        assert!(!policy.enforce(&mut order, None).unwrap());
+
+        let reject = PostOnlyPolicy {
+            on_cross: CrossingAction::Reject,
+            ..policy
+        };
+        let mut order = post_only(Side::Sell, 0.40);
+        let err = reject.enforce(&mut order, Some(&touch)).unwrap_err();
+        assert!(err.is_post_only_reject());
+        assert_eq!(order.price, Some(0.40));
+    }
+}
@@ -0,0 +1,108 @@
+//! Time-in-force emulation for venues without native IOC/FOK
+//!
+//! When a venue adapter does not support a limit order's time in force, the
+//! execution engine emulates it:
+//!
+//! - **IOC**: submit as GTC, then immediately cancel any unfilled remainder
+//! - **FOK**: pre-check visible book depth and reject unless the full size is
+//!   fillable at the limit price, then handle the remainder like IOC
+//!
+//! Emulated orders are flagged with `OrderAck::tif_emulated`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExecError, ExecResult};
+use crate::order::{Order, Side};
+
+/// Visible book depth for a market as `(price, size)` levels
+///
+/// Bids are expected best (highest) first and asks best (lowest) first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookDepth {
+    /// Bid levels
+    pub bids: Vec<(f64, f64)>,
+    /// Ask levels
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl BookDepth {
+    /// Depth from top-of-book quotes only
+    pub fn top_of_book(bid: Option<(f64, f64)>, ask: Option<(f64, f64)>) -> Self {
+        Self {
+            bids: bid.into_iter().collect(),
+            asks: ask.into_iter().collect(),
+        }
+    }
+
+    /// Size an order on `side` could take at or better than `limit`
+    pub fn fillable_size(&self, side: Side, limit: Option<f64>) -> f64 {
+        match side {
+            Side::Buy => self
+                .asks
+                .iter()
+                .filter(|(price, _)| limit.is_none_or(|l| *price <= l))
+                .map(|(_, size)| size)
+                .sum(),
+            Side::Sell => self
+                .bids
+                .iter()
+                .filter(|(price, _)| limit.is_none_or(|l| *price >= l))
+                .map(|(_, size)| size)
+                .sum(),
+        }
+    }
+}
+
+/// Check that a FOK order is fully fillable against visible depth
+pub fn check_fok_fillable(order: &Order, depth: Option<&BookDepth>) -> ExecResult<()> {
+    let Some(depth) = depth else {
+        return Err(ExecError::ValidationError(format!(
+            "FOK order {} cannot be emulated: no book depth for {}",
+            order.id, order.market
+        )));
+    };
+
+    let available = depth.fillable_size(order.side, order.price);
+    if available + f64::EPSILON < order.size {
+        return Err(ExecError::ValidationError(format!(
+            "FOK order {} not fully fillable: {} available of {}",
+            order.id, available, order.size
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{MarketId, OrderType, TimeInForce, VenueId};
+
+    #[test]
+    fn test_fok_depth_check() {
+        let depth = BookDepth {
+            bids: vec![(0.48, 50.0), (0.47, 100.0)],
+            asks: vec![(0.52, 40.0), (0.53, 80.0)],
+        };
+        assert_eq!(depth.fillable_size(Side::Buy, Some(0.52)), 40.0);
+        assert_eq!(depth.fillable_size(Side::Sell, Some(0.47)), 150.0);
+
+        let order = |side, price, size| {
+            Order::new(
+                VenueId::new("polymarket"),
+                MarketId::new("0x123abc"),
+                side,
+                OrderType::Limit,
+                Some(price),
+                size,
+                TimeInForce::FOK,
+                "client-fok".to_string(),
+            )
+        };
+
+        assert!(check_fok_fillable(&order(Side::Buy, 0.53, 120.0), Some(&depth)).is_ok());
+        assert!(check_fok_fillable(&order(Side::Buy, 0.52, 50.0), Some(&depth)).is_err());
+        assert!(check_fok_fillable(&order(Side::Sell, 0.48, 50.0), Some(&depth)).is_ok());
+        assert!(check_fok_fillable(&order(Side::Sell, 0.48, 50.0), None).is_err());
+    }
+}
@@ -5,6 +5,8 @@
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+use ag_risk::clock::{system_clock, SharedClock};
+
 use crate::error::{ExecError, ExecResult};
 use crate::order::{Fill, Order, OrderId, OrderStatus};
 
@@ -12,17 +14,29 @@ use crate::order::{Fill, Order, OrderId, OrderStatus};
 pub struct OrderTracker {
     orders: Arc<RwLock<HashMap<OrderId, Order>>>,
     fills: Arc<RwLock<HashMap<OrderId, Vec<Fill>>>>,
+    clock: SharedClock,
 }
 
 impl OrderTracker {
     /// Create a new order tracker
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create an order tracker stamping status changes with `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             orders: Arc::new(RwLock::new(HashMap::new())),
             fills: Arc::new(RwLock::new(HashMap::new())),
+            clock,
         }
     }
 
+    /// Stamp later status changes with `clock`
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
     /// Track a new order
     pub fn track_order(&self, order: Order) -> ExecResult<()> {
         let mut orders = self.orders.write().map_err(|e| {
@@ -55,7 +69,7 @@ impl OrderTracker {
             .get_mut(order_id)
             .ok_or_else(|| ExecError::OrderNotFound(*order_id))?;
 
-        order.update_status(status);
+        order.update_status_at(status, self.clock.now());
         Ok(())
     }
 
@@ -71,7 +85,7 @@ impl OrderTracker {
                 .get_mut(order_id)
                 .ok_or_else(|| ExecError::OrderNotFound(*order_id))?;
 
-            order.record_fill(fill.size, fill.price);
+            order.record_fill_at(fill.size, fill.price, self.clock.now());
         }
 
         // Store fill record
@@ -62,8 +62,30 @@ impl OrderValidator {
             ));
         }
 
+        // Validate stop trigger price (kept on released child orders, so
+        // only checked for stop types)
+        if order.order_type.is_stop() {
+            match order.stop_price {
+                Some(stop_price) if stop_price < self.min_price || stop_price > self.max_price => {
+                    return Err(ExecError::ValidationError(format!(
+                        "Stop price {} outside [{}, {}]",
+                        stop_price, self.min_price, self.max_price
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(ExecError::ValidationError(
+                        "Stop order must have a stop price".to_string(),
+                    ));
+                }
+            }
+        }
+
         // Validate price for limit orders
-        if order.order_type == OrderType::Limit || order.order_type == OrderType::PostOnly {
+        if matches!(
+            order.order_type,
+            OrderType::Limit | OrderType::PostOnly | OrderType::StopLimit
+        ) {
             match order.price {
                 Some(price) => {
                     if price < self.min_price {
@@ -95,7 +117,7 @@ impl OrderValidator {
         }
 
         // Market orders should not have a price
-        if order.order_type == OrderType::Market && order.price.is_some() {
+        if matches!(order.order_type, OrderType::Market | OrderType::StopMarket) && order.price.is_some() {
             return Err(ExecError::ValidationError(
                 "Market order should not have a price".to_string(),
             ));
@@ -118,6 +140,16 @@ impl OrderValidator {
         Ok(())
     }
 
+    /// Minimum order size
+    pub fn min_size(&self) -> f64 {
+        self.min_size
+    }
+
+    /// Maximum order size
+    pub fn max_size(&self) -> f64 {
+        self.max_size
+    }
+
     /// Set minimum size
     pub fn set_min_size(&mut self, min_size: f64) {
         self.min_size = min_size;
@@ -179,6 +211,26 @@ mod tests {
         assert!(validator.validate(&order).is_ok());
     }
 
+    #[test]
+    fn test_stop_orders() {
+        let validator = OrderValidator::new();
+
+        let stop = create_test_order(100.0, None, OrderType::StopMarket).with_stop_price(0.6);
+        assert!(validator.validate(&stop).is_ok());
+
+        let stop_limit = create_test_order(100.0, Some(0.61), OrderType::StopLimit).with_stop_price(0.6);
+        assert!(validator.validate(&stop_limit).is_ok());
+
+        // Missing stop price, missing limit price, out-of-range trigger
+        assert!(validator.validate(&create_test_order(100.0, None, OrderType::StopMarket)).is_err());
+        assert!(validator
+            .validate(&create_test_order(100.0, None, OrderType::StopLimit).with_stop_price(0.6))
+            .is_err());
+        assert!(validator
+            .validate(&create_test_order(100.0, None, OrderType::StopMarket).with_stop_price(1.5))
+            .is_err());
+    }
+
     #[test]
     fn test_size_too_small() {
         let validator = OrderValidator::new();
@@ -3,10 +3,16 @@
 //! This module defines the core order types used throughout the execution gateway.
 //! All order types are venue-agnostic and normalized to a common representation.
 
+use std::collections::BTreeMap;
+
+use ag_risk::clock::{Clock, IdGenerator};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::adapters::rounding::AppliedRounding;
+use crate::pegs::Peg;
+
 /// Unique identifier for an order
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct OrderId(pub Uuid);
@@ -17,6 +23,11 @@ impl OrderId {
         Self(Uuid::new_v4())
     }
 
+    /// Take the next ID from a generator
+    pub fn generate(ids: &dyn IdGenerator) -> Self {
+        Self(Uuid::from_u128(ids.next_id()))
+    }
+
     /// Create an OrderId from a UUID
     pub fn from_uuid(uuid: Uuid) -> Self {
         Self(uuid)
@@ -112,6 +123,18 @@ pub enum OrderType {
     Market,
     /// Post-only order (always maker, never taker)
     PostOnly,
+    /// Stop order: releases a market order once `stop_price` is crossed
+    StopMarket,
+    /// Stop-limit order: releases a limit order at `price` once `stop_price`
+    /// is crossed
+    StopLimit,
+}
+
+impl OrderType {
+    /// Whether the order is held locally until its stop price triggers
+    pub fn is_stop(&self) -> bool {
+        matches!(self, OrderType::StopMarket | OrderType::StopLimit)
+    }
 }
 
 impl std::fmt::Display for OrderType {
@@ -120,6 +143,8 @@ impl std::fmt::Display for OrderType {
             OrderType::Limit => write!(f, "LIMIT"),
             OrderType::Market => write!(f, "MARKET"),
             OrderType::PostOnly => write!(f, "POST_ONLY"),
+            OrderType::StopMarket => write!(f, "STOP_MARKET"),
+            OrderType::StopLimit => write!(f, "STOP_LIMIT"),
         }
     }
 }
@@ -205,6 +230,10 @@ pub struct Order {
     /// Limit price (None for market orders)
     pub price: Option<f64>,
 
+    /// Trigger price for stop orders
+    #[serde(default)]
+    pub stop_price: Option<f64>,
+
     /// Order size
     pub size: f64,
 
@@ -214,6 +243,32 @@ pub struct Order {
     /// Client-specified order ID for tracking
     pub client_order_id: String,
 
+    /// Strategy that placed the order (for per-strategy rate limit quotas)
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+
+    /// Free-form labels carried through to storage (e.g. the signal behind
+    /// the order, used for PnL attribution)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+
+    /// Only allowed to shrink the position (liquidation and flatten orders)
+    #[serde(default)]
+    pub reduce_only: bool,
+
+    /// Deduplication key for retried submissions (defaults to the client
+    /// order ID)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+
+    /// Tick/lot rounding applied at the venue boundary
+    #[serde(default)]
+    pub rounding: Option<AppliedRounding>,
+
+    /// Price peg; the engine prices and reprices the order from the touch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peg: Option<Peg>,
+
     /// Current order status
     pub status: OrderStatus,
 
@@ -251,9 +306,16 @@ impl Order {
             side,
             order_type,
             price,
+            stop_price: None,
             size,
             time_in_force,
             client_order_id,
+            strategy_id: None,
+            tags: BTreeMap::new(),
+            reduce_only: false,
+            idempotency_key: None,
+            rounding: None,
+            peg: None,
             status: OrderStatus::Pending,
             filled_size: 0.0,
             avg_fill_price: None,
@@ -262,6 +324,59 @@ impl Order {
         }
     }
 
+    /// Replace the random ID and system-clock timestamps given by
+    /// [`new`](Self::new) with ones from injected sources
+    pub fn stamped(mut self, clock: &dyn Clock, ids: &dyn IdGenerator) -> Self {
+        self.id = OrderId::generate(ids);
+        self.created_at = clock.now();
+        self.updated_at = self.created_at;
+        self
+    }
+
+    /// Set the trigger price (for `StopMarket` / `StopLimit` orders)
+    pub fn with_stop_price(mut self, stop_price: f64) -> Self {
+        self.stop_price = Some(stop_price);
+        self
+    }
+
+    /// Attribute the order to a strategy
+    pub fn with_strategy(mut self, strategy_id: impl Into<String>) -> Self {
+        self.strategy_id = Some(strategy_id.into());
+        self
+    }
+
+    /// Label the order
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Mark the order reduce-only
+    ///
+    /// The engine rejects a reduce-only order that would grow or flip the
+    /// position and skips pre-trade risk checks (including the kill-switch)
+    /// for one that shrinks it.
+    pub fn with_reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    /// Deduplicate retried submissions by `key` instead of the client
+    /// order ID
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Peg the limit price to the touch
+    ///
+    /// The engine ignores `price` on submission and keeps the order at the
+    /// peg price as the touch moves (see [`crate::pegs`]).
+    pub fn with_peg(mut self, peg: Peg) -> Self {
+        self.peg = Some(peg);
+        self
+    }
+
     /// Check if order is in a terminal state
     pub fn is_terminal(&self) -> bool {
         matches!(
@@ -280,12 +395,22 @@ impl Order {
 
     /// Update order status
     pub fn update_status(&mut self, status: OrderStatus) {
+        self.update_status_at(status, Utc::now());
+    }
+
+    /// Update order status as of `at`
+    pub fn update_status_at(&mut self, status: OrderStatus, at: DateTime<Utc>) {
         self.status = status;
-        self.updated_at = Utc::now();
+        self.updated_at = at;
     }
 
     /// Record a fill
     pub fn record_fill(&mut self, fill_size: f64, fill_price: f64) {
+        self.record_fill_at(fill_size, fill_price, Utc::now());
+    }
+
+    /// Record a fill as of `at`
+    pub fn record_fill_at(&mut self, fill_size: f64, fill_price: f64, at: DateTime<Utc>) {
         self.filled_size += fill_size;
 
         // Update average fill price
@@ -303,7 +428,7 @@ impl Order {
             self.status = OrderStatus::PartiallyFilled;
         }
 
-        self.updated_at = Utc::now();
+        self.updated_at = at;
     }
 
     /// Get remaining size
@@ -329,6 +454,11 @@ pub struct OrderAck {
 
     /// Optional message from venue
     pub message: Option<String>,
+
+    /// Whether the time in force was emulated by the engine rather than
+    /// enforced natively by the venue
+    #[serde(default)]
+    pub tif_emulated: bool,
 }
 
 /// Cancel acknowledgement from venue
@@ -348,6 +478,42 @@ pub struct CancelAck {
 
     /// Optional message from venue
     pub message: Option<String>,
+
+    /// How the cancel was resolved; set by the execution engine after
+    /// reconciling failed or ambiguous cancels with the venue
+    #[serde(default)]
+    pub outcome: CancelOutcome,
+}
+
+/// How a cancel request was resolved
+///
+/// A cancel can fail or time out without saying whether the order is
+/// still working. The execution engine then queries the order's status
+/// and moves the tracked order to the state the venue reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelOutcome {
+    /// The venue cancelled the order
+    #[default]
+    Cancelled,
+    /// The order filled before the cancel reached the venue
+    AlreadyFilled,
+    /// The order had already expired or been rejected at the venue
+    AlreadyClosed,
+    /// The venue does not know the order; it is treated as cancelled
+    UnknownOrder,
+    /// The venue refused the cancel and the order is still working
+    StillWorking,
+    /// Neither the cancel nor the status query was answered; the order
+    /// keeps its previous status and the cancel can be retried
+    Unresolved,
+}
+
+impl CancelOutcome {
+    /// Whether the order no longer works at the venue
+    pub fn is_closed(&self) -> bool {
+        !matches!(self, CancelOutcome::StillWorking | CancelOutcome::Unresolved)
+    }
 }
 
 /// Fill notification
@@ -0,0 +1,478 @@
+//! Pegged limit orders
+//!
+//! A pegged order carries a [`Peg`] instead of a fixed price: "best bid + 1
+//! tick", "best ask", "mid - 2 ticks". The execution engine prices it from
+//! the latest touch on submission and, as the touch moves, replaces it at
+//! the new peg price:
+//!
+//! - Positive offsets are more aggressive: up from the reference for buys,
+//!   down for sells
+//! - Peg prices never cross the opposite side of the touch and never go
+//!   beyond the peg's `limit_price`
+//! - Orders are only repriced when the peg price moves more than
+//!   `tolerance_ticks`, and at most `max_reprices` times per `window_secs`
+//!
+//! Each reprice cancels the working order and places a replacement for the
+//! remaining size under a new order ID. The [`PegManager`] keeps every peg
+//! under the ID of the order first submitted (the peg ID) and follows it to
+//! the replacement currently working.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::{ExecError, ExecResult};
+use crate::oms::post_only::Touch;
+use crate::order::{MarketId, Order, OrderId, OrderStatus, OrderType, Side};
+
+/// Tolerance for float noise when comparing prices against the tick grid
+const TICK_EPSILON: f64 = 1e-9;
+
+/// Price a pegged order follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PegReference {
+    /// Best bid
+    BestBid,
+    /// Best ask
+    BestAsk,
+    /// Midpoint of the touch
+    Mid,
+}
+
+/// Peg definition carried on an order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Peg {
+    /// Reference price
+    pub reference: PegReference,
+
+    /// Ticks from the reference; positive is more aggressive
+    #[serde(default)]
+    pub offset_ticks: i32,
+
+    /// Worst price the peg may reach: a cap for buys, a floor for sells
+    #[serde(default)]
+    pub limit_price: Option<f64>,
+}
+
+impl Peg {
+    /// Peg to `reference` offset by `offset_ticks`
+    pub fn new(reference: PegReference, offset_ticks: i32) -> Self {
+        Self {
+            reference,
+            offset_ticks,
+            limit_price: None,
+        }
+    }
+
+    /// Never price beyond `limit_price`
+    pub fn with_limit(mut self, limit_price: f64) -> Self {
+        self.limit_price = Some(limit_price);
+        self
+    }
+
+    /// Peg price of a `side` order for a touch, on a `tick_size` grid
+    ///
+    /// Off-grid prices round away from the opposite side (buys down, sells
+    /// up). `None` if the reference is missing from the touch or the price
+    /// is not positive.
+    pub fn price(&self, side: Side, touch: &Touch, tick_size: f64) -> Option<f64> {
+        if tick_size <= 0.0 {
+            return None;
+        }
+
+        let reference = match self.reference {
+            PegReference::BestBid => touch.best_bid?,
+            PegReference::BestAsk => touch.best_ask?,
+            PegReference::Mid => touch.mid()?,
+        };
+        let offset = self.offset_ticks as f64 * tick_size;
+        let ticks = match side {
+            Side::Buy => ((reference + offset) / tick_size + TICK_EPSILON).floor(),
+            Side::Sell => ((reference - offset) / tick_size - TICK_EPSILON).ceil(),
+        };
+        let mut price = ticks * tick_size;
+
+        match side {
+            Side::Buy => {
+                if let Some(ask) = touch.best_ask {
+                    price = price.min(ask - tick_size);
+                }
+                if let Some(limit) = self.limit_price {
+                    price = price.min(limit);
+                }
+            }
+            Side::Sell => {
+                if let Some(bid) = touch.best_bid {
+                    price = price.max(bid + tick_size);
+                }
+                if let Some(limit) = self.limit_price {
+                    price = price.max(limit);
+                }
+            }
+        }
+
+        (price > TICK_EPSILON).then_some(price)
+    }
+}
+
+/// Repricing limits for pegged orders
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PegConfig {
+    /// Leave the order alone while the peg price stays within this many
+    /// ticks of its current price
+    pub tolerance_ticks: u32,
+
+    /// Reprices allowed per order within `window_secs`
+    pub max_reprices: u32,
+
+    /// Rolling window of the reprice budget in seconds
+    pub window_secs: u64,
+
+    /// Tick size for venues without tick rounding rules
+    pub default_tick_size: f64,
+}
+
+impl Default for PegConfig {
+    fn default() -> Self {
+        Self {
+            tolerance_ticks: 0,
+            max_reprices: 10,
+            window_secs: 60,
+            default_tick_size: 0.01,
+        }
+    }
+}
+
+/// A pegged order and its repricing history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeggedOrder {
+    /// ID of the order first submitted with the peg
+    pub peg_id: OrderId,
+
+    /// Order currently working for the peg
+    pub order: Order,
+
+    /// Venue tick size the peg price is rounded to
+    pub tick_size: f64,
+
+    /// Reprices so far
+    pub reprices: u64,
+
+    /// Reprices skipped because the budget was spent
+    pub throttled: u64,
+
+    /// Times of reprices within the budget window
+    #[serde(skip)]
+    recent: VecDeque<DateTime<Utc>>,
+}
+
+impl PeggedOrder {
+    /// Peg definition
+    pub fn peg(&self) -> Option<Peg> {
+        self.order.peg
+    }
+}
+
+/// A pegged order due for repricing
+#[derive(Debug, Clone)]
+pub struct Reprice {
+    /// Peg being repriced
+    pub peg_id: OrderId,
+
+    /// Order currently working, to be cancelled
+    pub order: Order,
+
+    /// New peg price
+    pub price: f64,
+
+    /// Number of this reprice for the peg, starting at 1
+    pub sequence: u64,
+}
+
+impl Reprice {
+    /// Replacement for the remaining size at the new price, under `id`
+    pub fn replacement(&self, id: OrderId) -> Order {
+        let mut replacement = self.order.clone();
+        replacement.id = id;
+        replacement.price = Some(self.price);
+        replacement.size = self.order.remaining_size();
+        replacement.filled_size = 0.0;
+        replacement.avg_fill_price = None;
+        replacement.rounding = None;
+        replacement.idempotency_key = None;
+        if !self.order.client_order_id.is_empty() {
+            replacement.client_order_id = format!("{}-p{}", self.order.client_order_id, self.sequence);
+        }
+        replacement.update_status(OrderStatus::Pending);
+        replacement
+    }
+}
+
+/// Tracks pegged orders and decides when they need repricing
+#[derive(Debug, Default)]
+pub struct PegManager {
+    config: PegConfig,
+    pegs: HashMap<OrderId, PeggedOrder>,
+}
+
+impl PegManager {
+    /// Create a manager with the given repricing limits
+    pub fn new(config: PegConfig) -> Self {
+        Self {
+            config,
+            pegs: HashMap::new(),
+        }
+    }
+
+    /// Repricing limits
+    pub fn config(&self) -> &PegConfig {
+        &self.config
+    }
+
+    /// Start tracking a submitted pegged order
+    pub fn add(&mut self, order: Order, tick_size: f64) -> ExecResult<()> {
+        check_peggable(&order)?;
+
+        debug!("Tracking pegged order {:?} at {:?}", order.id, order.price);
+        self.pegs.insert(
+            order.id,
+            PeggedOrder {
+                peg_id: order.id,
+                order,
+                tick_size,
+                reprices: 0,
+                throttled: 0,
+                recent: VecDeque::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Peg an order belongs to, by peg ID or the ID of its working order
+    pub fn peg_id_of(&self, order_id: &OrderId) -> Option<OrderId> {
+        if self.pegs.contains_key(order_id) {
+            return Some(*order_id);
+        }
+        self.pegs
+            .values()
+            .find(|peg| peg.order.id == *order_id)
+            .map(|peg| peg.peg_id)
+    }
+
+    /// Stop tracking a peg (by peg ID or working order ID)
+    pub fn remove(&mut self, order_id: &OrderId) -> Option<PeggedOrder> {
+        let peg_id = self.peg_id_of(order_id)?;
+        self.pegs.remove(&peg_id)
+    }
+
+    /// Peg by peg ID
+    pub fn get(&self, peg_id: &OrderId) -> Option<&PeggedOrder> {
+        self.pegs.get(peg_id)
+    }
+
+    /// All tracked pegs
+    pub fn pegs(&self) -> Vec<PeggedOrder> {
+        self.pegs.values().cloned().collect()
+    }
+
+    /// Number of tracked pegs
+    pub fn len(&self) -> usize {
+        self.pegs.len()
+    }
+
+    /// Whether no pegs are tracked
+    pub fn is_empty(&self) -> bool {
+        self.pegs.is_empty()
+    }
+
+    /// Record the replacement now working for a peg
+    pub fn replaced(&mut self, peg_id: &OrderId, order: Order) {
+        if let Some(peg) = self.pegs.get_mut(peg_id) {
+            peg.order = order;
+        }
+    }
+
+    /// Apply a fill to the working order; the peg is dropped once filled
+    pub fn on_fill(&mut self, order_id: &OrderId, size: f64, price: f64) {
+        let Some(peg_id) = self.peg_id_of(order_id) else {
+            return;
+        };
+        let Some(peg) = self.pegs.get_mut(&peg_id) else {
+            return;
+        };
+        if peg.order.id != *order_id {
+            return;
+        }
+
+        peg.order.record_fill(size, price);
+        if peg.order.remaining_size() <= TICK_EPSILON {
+            debug!("Pegged order {:?} filled", peg_id);
+            self.pegs.remove(&peg_id);
+        }
+    }
+
+    /// Apply a touch update; returns the pegs in `market` to reprice
+    ///
+    /// A returned reprice counts against the peg's budget.
+    pub fn on_touch(&mut self, market: &MarketId, touch: &Touch, now: DateTime<Utc>) -> Vec<Reprice> {
+        let window = Duration::seconds(self.config.window_secs as i64);
+        let mut reprices = Vec::new();
+
+        for peg in self.pegs.values_mut() {
+            if peg.order.market != *market {
+                continue;
+            }
+            let Some(spec) = peg.order.peg else {
+                continue;
+            };
+            let Some(price) = spec.price(peg.order.side, touch, peg.tick_size) else {
+                continue;
+            };
+
+            let tolerance = self.config.tolerance_ticks as f64 * peg.tick_size + TICK_EPSILON;
+            if peg.order.price.is_some_and(|current| (price - current).abs() <= tolerance) {
+                continue;
+            }
+
+            while peg.recent.front().is_some_and(|at| now - *at >= window) {
+                peg.recent.pop_front();
+            }
+            if peg.recent.len() >= self.config.max_reprices as usize {
+                peg.throttled += 1;
+                continue;
+            }
+
+            peg.recent.push_back(now);
+            peg.reprices += 1;
+            reprices.push(Reprice {
+                peg_id: peg.peg_id,
+                order: peg.order.clone(),
+                price,
+                sequence: peg.reprices,
+            });
+        }
+
+        reprices
+    }
+}
+
+/// Check that an order carries a peg and is a limit order
+pub fn check_peggable(order: &Order) -> ExecResult<()> {
+    if order.peg.is_none() {
+        return Err(ExecError::ValidationError("Order has no peg".to_string()));
+    }
+    if !matches!(order.order_type, OrderType::Limit | OrderType::PostOnly) {
+        return Err(ExecError::ValidationError(format!(
+            "Cannot peg {} order",
+            order.order_type
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{TimeInForce, VenueId};
+
+    fn touch(bid: f64, ask: f64) -> Touch {
+        Touch {
+            best_bid: Some(bid),
+            best_ask: Some(ask),
+        }
+    }
+
+    fn pegged(side: Side, peg: Peg) -> Order {
+        let mut order = Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            side,
+            OrderType::Limit,
+            Some(0.40),
+            100.0,
+            TimeInForce::GTC,
+            "client-peg".to_string(),
+        );
+        order.peg = Some(peg);
+        order
+    }
+
+    #[test]
+    fn test_peg_price() {
+        let join_bid = Peg::new(PegReference::BestBid, 0);
+        let improve_bid = Peg::new(PegReference::BestBid, 1);
+        let improve_ask = Peg::new(PegReference::BestAsk, 1);
+
+        let wide = touch(0.40, 0.45);
+        assert!((join_bid.price(Side::Buy, &wide, 0.01).unwrap() - 0.40).abs() < 1e-9);
+        assert!((improve_bid.price(Side::Buy, &wide, 0.01).unwrap() - 0.41).abs() < 1e-9);
+        assert!((improve_ask.price(Side::Sell, &wide, 0.01).unwrap() - 0.44).abs() < 1e-9);
+
+        // Never crosses: one tick inside a one-tick market stays at the bid
+        let tight = touch(0.40, 0.41);
+        assert!((improve_bid.price(Side::Buy, &tight, 0.01).unwrap() - 0.40).abs() < 1e-9);
+
+        // Mid rounds away from the other side; limits cap the price
+        let mid = Peg::new(PegReference::Mid, 0);
+        assert!((mid.price(Side::Buy, &touch(0.40, 0.43), 0.01).unwrap() - 0.41).abs() < 1e-9);
+        assert!((mid.price(Side::Sell, &touch(0.40, 0.43), 0.01).unwrap() - 0.42).abs() < 1e-9);
+        let capped = improve_bid.with_limit(0.405);
+        assert!((capped.price(Side::Buy, &wide, 0.01).unwrap() - 0.405).abs() < 1e-9);
+
+        let one_sided = Touch { best_bid: None, best_ask: Some(0.45) };
+        assert!(join_bid.price(Side::Buy, &one_sided, 0.01).is_none());
+    }
+
+    #[test]
+    fn test_reprice_tolerance_budget_and_fills() {
+        let mut manager = PegManager::new(PegConfig {
+            tolerance_ticks: 1,
+            max_reprices: 2,
+            window_secs: 60,
+            ..PegConfig::default()
+        });
+        let market = MarketId::new("0x123abc");
+        let order = pegged(Side::Buy, Peg::new(PegReference::BestBid, 0));
+        let peg_id = order.id;
+        manager.add(order, 0.01).unwrap();
+        assert!(manager.add(Order { peg: None, ..pegged(Side::Buy, Peg::new(PegReference::Mid, 0)) }, 0.01).is_err());
+
+        let now = Utc::now();
+
+        // Within one tick: left alone
+        assert!(manager.on_touch(&market, &touch(0.41, 0.45), now).is_empty());
+        assert!(manager.on_touch(&MarketId::new("other"), &touch(0.50, 0.55), now).is_empty());
+
+        // Bid moves two ticks: reprice to 0.42
+        let reprices = manager.on_touch(&market, &touch(0.42, 0.45), now);
+        assert_eq!(reprices.len(), 1);
+        assert!((reprices[0].price - 0.42).abs() < 1e-9);
+
+        let replacement = reprices[0].replacement(OrderId::new());
+        assert_eq!(replacement.client_order_id, "client-peg-p1");
+        assert_eq!(replacement.price, Some(reprices[0].price));
+        manager.replaced(&peg_id, replacement.clone());
+        assert_eq!(manager.peg_id_of(&replacement.id), Some(peg_id));
+
+        // Second reprice uses up the budget, the third is throttled
+        assert_eq!(manager.on_touch(&market, &touch(0.38, 0.45), now).len(), 1);
+        manager.replaced(&peg_id, Order { price: Some(0.38), ..replacement.clone() });
+        assert!(manager.on_touch(&market, &touch(0.35, 0.45), now).is_empty());
+        assert_eq!(manager.get(&peg_id).unwrap().throttled, 1);
+
+        // Budget frees up after the window
+        let later = now + Duration::seconds(61);
+        assert_eq!(manager.on_touch(&market, &touch(0.35, 0.45), later).len(), 1);
+
+        // Fills on the working order shrink the replacement; a full fill drops the peg
+        manager.on_fill(&replacement.id, 40.0, 0.38);
+        let reprice = &manager.on_touch(&market, &touch(0.30, 0.45), later)[0];
+        assert!((reprice.replacement(OrderId::new()).size - 60.0).abs() < 1e-9);
+        manager.on_fill(&replacement.id, 60.0, 0.38);
+        assert!(manager.is_empty());
+    }
+}
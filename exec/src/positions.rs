@@ -0,0 +1,135 @@
+//! Net positions with lock-free reads
+//!
+//! Every order on the submit path reads the position in its market and the
+//! total inventory for the pre-trade risk check, while positions only change
+//! on fills, imports and settlements. [`PositionBook`] publishes each change
+//! as an immutable [`PositionSnapshot`]: readers load the current snapshot
+//! without taking a lock or waiting on a writer, writers serialize among
+//! themselves and swap in a new snapshot. A write copies the book, which is
+//! cheap for the few dozen markets a bot holds and keeps the order path
+//! free of contention with fills.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use arc_swap::ArcSwap;
+
+/// Positions at one point in time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PositionSnapshot {
+    sizes: HashMap<String, f64>,
+    net: f64,
+}
+
+impl PositionSnapshot {
+    /// Position in a market (0 when flat)
+    pub fn get(&self, market: &str) -> f64 {
+        self.sizes.get(market).copied().unwrap_or(0.0)
+    }
+
+    /// Sum of the positions across markets
+    pub fn net(&self) -> f64 {
+        self.net
+    }
+
+    /// Positions by market
+    pub fn sizes(&self) -> &HashMap<String, f64> {
+        &self.sizes
+    }
+}
+
+/// Positions by market, read without locking
+#[derive(Debug, Default)]
+pub struct PositionBook {
+    current: ArcSwap<PositionSnapshot>,
+    writer: Mutex<()>,
+}
+
+impl PositionBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current positions
+    pub fn snapshot(&self) -> Arc<PositionSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Position in a market (0 when flat)
+    pub fn get(&self, market: &str) -> f64 {
+        self.current.load().get(market)
+    }
+
+    /// Add `delta` to the position in `market`, returning the new position
+    pub fn add(&self, market: &str, delta: f64) -> f64 {
+        self.update(|sizes| {
+            let size = sizes.entry(market.to_string()).or_insert(0.0);
+            *size += delta;
+            *size
+        })
+    }
+
+    /// Replace the position in `market`, returning the previous one
+    pub fn set(&self, market: &str, size: f64) -> f64 {
+        self.update(|sizes| sizes.insert(market.to_string(), size).unwrap_or(0.0))
+    }
+
+    /// Clear the position in `market`, returning it
+    pub fn remove(&self, market: &str) -> f64 {
+        self.update(|sizes| sizes.remove(market).unwrap_or(0.0))
+    }
+
+    fn update<R>(&self, change: impl FnOnce(&mut HashMap<String, f64>) -> R) -> R {
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let mut sizes = self.current.load().sizes.clone();
+        let result = change(&mut sizes);
+        let net = sizes.values().sum();
+        self.current.store(Arc::new(PositionSnapshot { sizes, net }));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_book() {
+        let book = PositionBook::new();
+        assert_eq!(book.get("0x1"), 0.0);
+
+        assert_eq!(book.add("0x1", 100.0), 100.0);
+        assert_eq!(book.add("0x1", -30.0), 70.0);
+        assert_eq!(book.set("0x2", -20.0), 0.0);
+        let before = book.snapshot();
+        assert_eq!(before.net(), 50.0);
+
+        // Snapshots taken earlier are unaffected by later writes
+        assert_eq!(book.remove("0x1"), 70.0);
+        assert_eq!(book.get("0x1"), 0.0);
+        assert_eq!(book.snapshot().net(), -20.0);
+        assert_eq!(before.get("0x1"), 70.0);
+        assert_eq!(before.sizes().len(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_reads_see_whole_updates() {
+        let book = Arc::new(PositionBook::new());
+        let writer = {
+            let book = book.clone();
+            std::thread::spawn(move || {
+                for _ in 0..10_000 {
+                    book.add("0x1", 1.0);
+                    book.add("0x2", -1.0);
+                }
+            })
+        };
+        while !writer.is_finished() {
+            let snapshot = book.snapshot();
+            assert_eq!(snapshot.net(), snapshot.get("0x1") + snapshot.get("0x2"));
+        }
+        writer.join().unwrap();
+        assert_eq!(book.get("0x1"), 10_000.0);
+        assert_eq!(book.snapshot().net(), 0.0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Pre-flight simulation of order submissions
+//!
+//! [`ExecutionEngine::preflight`](crate::ExecutionEngine::preflight) runs
+//! the checks a submission goes through (pegging, post-only protection,
+//! validation, rounding, the pre-trade risk check, rate limit availability
+//! and the venue's free balance) without placing, tracking or arming
+//! anything, and reports what would happen. The risk check is a
+//! [`RiskEngine::dry_run`](ag_risk::RiskEngine::dry_run), so probing does
+//! not count towards rejection statistics or trip breach recovery.
+//!
+//! [`PreflightReport::max_size`] is the largest size up to the requested
+//! one that would pass validation, risk and balance checks, so a strategy
+//! can shrink an order rather than have it rejected.
+
+use std::time::Duration;
+
+use ag_risk::RiskDecision;
+use serde::{Deserialize, Serialize};
+
+use crate::order::Order;
+use crate::venue_status::VenueState;
+
+/// Tolerance when comparing required cash with free capital
+const BALANCE_EPSILON: f64 = 1e-9;
+
+/// Request slots of a venue's rate limiter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitCheck {
+    /// Requests that could be granted right now
+    pub tokens_remaining: f64,
+
+    /// Wait before the next request would be granted (zero if one is
+    /// available)
+    pub wait: Duration,
+}
+
+impl RateLimitCheck {
+    /// Whether a request would be granted without waiting
+    pub fn available(&self) -> bool {
+        self.wait.is_zero()
+    }
+}
+
+/// Cash a buy needs against the venue's free capital
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceCheck {
+    /// Notional of the order (limit price times size, before fees)
+    pub required: f64,
+
+    /// Cash available for new orders on the venue
+    pub free: f64,
+}
+
+impl BalanceCheck {
+    /// Whether the venue has the cash for the order
+    pub fn passed(&self) -> bool {
+        self.required <= self.free + BALANCE_EPSILON
+    }
+}
+
+/// What submitting an order would do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    /// Order as it would be placed: pegged, repriced for post-only
+    /// protection and rounded to the venue's tick and lot sizes
+    pub order: Order,
+
+    /// Why the order would be rejected before reaching the venue, other
+    /// than by the risk check (invalid order, crossing post-only order,
+    /// unsupported or paused venue, ...)
+    pub validation_error: Option<String>,
+
+    /// Pre-trade risk decision (`None` when risk checks are off, no risk
+    /// engine is set or the order skips them: reduce-only and stop orders)
+    pub risk: Option<RiskDecision>,
+
+    /// Rate limit availability (`None` without a rate limiter for the
+    /// venue, or for stop orders, which are armed locally). A submission
+    /// waits for a slot rather than failing.
+    pub rate_limit: Option<RateLimitCheck>,
+
+    /// Free balance check (`None` for sells, orders without a price and
+    /// venues without a tracked balance)
+    pub balance: Option<BalanceCheck>,
+
+    /// Current state of the venue
+    pub venue_state: VenueState,
+
+    /// Largest size up to the order's that passes validation, risk and
+    /// balance checks, rounded down to the venue's lot size (`None` if no
+    /// size does)
+    ///
+    /// Found by bisection, assuming a smaller order passes whenever a
+    /// larger one does.
+    pub max_size: Option<f64>,
+}
+
+impl PreflightReport {
+    /// Whether the order would be accepted (possibly after waiting for a
+    /// rate limit slot)
+    pub fn passed(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// Reasons the order would be rejected
+    pub fn failures(&self) -> Vec<String> {
+        let mut failures: Vec<String> = self.validation_error.iter().cloned().collect();
+        if let Some(risk) = self.risk.as_ref().filter(|risk| !risk.allowed) {
+            failures.push(format!("risk check: {}", risk));
+        }
+        if let Some(balance) = self.balance.as_ref().filter(|balance| !balance.passed()) {
+            failures.push(format!(
+                "insufficient balance: {:.2} required, {:.2} free",
+                balance.required, balance.free
+            ));
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{MarketId, OrderType, Side, TimeInForce, VenueId};
+    use ag_risk::Violation;
+
+    #[test]
+    fn test_report_failures() {
+        let order = Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.5),
+            100.0,
+            TimeInForce::GTC,
+            "c1".to_string(),
+        );
+        let mut report = PreflightReport {
+            order,
+            validation_error: None,
+            risk: Some(RiskDecision::allow()),
+            rate_limit: Some(RateLimitCheck {
+                tokens_remaining: 0.0,
+                wait: Duration::from_millis(100),
+            }),
+            balance: Some(BalanceCheck {
+                required: 50.0,
+                free: 50.0,
+            }),
+            venue_state: VenueState::Up,
+            max_size: Some(100.0),
+        };
+        // Waiting for a rate limit slot is not a failure
+        assert!(report.passed());
+
+        report.balance = Some(BalanceCheck {
+            required: 50.0,
+            free: 20.0,
+        });
+        report.risk = Some(RiskDecision::reject(vec![Violation::new("KillSwitch", "KillSwitch (active)")]));
+        assert_eq!(
+            report.failures(),
+            vec![
+                "risk check: rejected: KillSwitch (active)".to_string(),
+                "insufficient balance: 50.00 required, 20.00 free".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Historical prices for signal bootstrapping
+//!
+//! Indicators such as SMA or RSI need tens of bars before they produce a
+//! value, which at live tick rates can take hours. [`PriceHistoryClient`]
+//! reads the Polymarket CLOB `prices-history` endpoint for an outcome token
+//! and [`to_bars`] turns the samples into fixed-width OHLC [`PriceBar`]s whose
+//! closes can be fed to an indicator before the strategy sees its first tick.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::{ExecError, ExecResult};
+use crate::venues::polymarket::classify_error;
+
+/// Default Polymarket CLOB API endpoint
+pub const POLYMARKET_CLOB_ENDPOINT: &str = "https://clob.polymarket.com";
+
+/// Time span of a price history request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryRange {
+    /// Trailing interval ending now
+    LastHour,
+    LastSixHours,
+    LastDay,
+    LastWeek,
+    LastMonth,
+    /// Full history of the token
+    Max,
+    /// Explicit window
+    Between { start: DateTime<Utc>, end: DateTime<Utc> },
+}
+
+impl HistoryRange {
+    /// Query string parameters selecting the range
+    fn query(&self) -> String {
+        let interval = match self {
+            HistoryRange::LastHour => "1h",
+            HistoryRange::LastSixHours => "6h",
+            HistoryRange::LastDay => "1d",
+            HistoryRange::LastWeek => "1w",
+            HistoryRange::LastMonth => "1m",
+            HistoryRange::Max => "max",
+            HistoryRange::Between { start, end } => {
+                return format!("startTs={}&endTs={}", start.timestamp(), end.timestamp());
+            }
+        };
+        format!("interval={}", interval)
+    }
+}
+
+/// One historical price sample
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    pub timestamp: DateTime<Utc>,
+    pub price: f64,
+}
+
+/// OHLC bar built from price samples
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceBar {
+    /// Bar open time (aligned to a multiple of the bar width)
+    pub start: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Samples in the bar (0 for a bar carried forward over a gap)
+    pub samples: usize,
+}
+
+/// Polymarket CLOB price history client
+pub struct PriceHistoryClient {
+    endpoint: String,
+    client: Client,
+}
+
+impl PriceHistoryClient {
+    /// Create a client for a CLOB API endpoint
+    pub fn new(endpoint: impl Into<String>) -> ExecResult<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| ExecError::ConfigError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    /// Fetch price samples of an outcome token, one per `fidelity_minutes`
+    pub async fn fetch(
+        &self,
+        token_id: &str,
+        range: HistoryRange,
+        fidelity_minutes: u32,
+    ) -> ExecResult<Vec<PricePoint>> {
+        let url = format!(
+            "{}/prices-history?market={}&{}&fidelity={}",
+            self.endpoint,
+            token_id,
+            range.query(),
+            fidelity_minutes.max(1)
+        );
+
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ExecError::VenueError {
+                venue: "polymarket".to_string(),
+                message: format!("Price history request failed: {}", error_text),
+                code: Some(status.as_str().to_string()),
+                kind: classify_error(status.as_u16(), &error_text),
+            });
+        }
+
+        let history: HistoryResponse = response.json().await?;
+        let points = history.into_points();
+        debug!("Fetched {} price samples for token {}", points.len(), token_id);
+        Ok(points)
+    }
+
+    /// Fetch price history as bars of width `bar`, sampled at the bar width
+    pub async fn fetch_bars(&self, token_id: &str, range: HistoryRange, bar: Duration) -> ExecResult<Vec<PriceBar>> {
+        let fidelity_minutes = (bar.as_secs() / 60).clamp(1, u32::MAX as u64) as u32;
+        let points = self.fetch(token_id, range, fidelity_minutes).await?;
+        Ok(to_bars(&points, bar))
+    }
+}
+
+/// `prices-history` response body
+#[derive(Debug, Deserialize)]
+struct HistoryResponse {
+    #[serde(default)]
+    history: Vec<HistorySample>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistorySample {
+    /// Unix seconds
+    t: i64,
+    p: f64,
+}
+
+impl HistoryResponse {
+    fn into_points(self) -> Vec<PricePoint> {
+        let mut points: Vec<PricePoint> = self
+            .history
+            .into_iter()
+            .filter_map(|s| {
+                Utc.timestamp_opt(s.t, 0).single().map(|timestamp| PricePoint {
+                    timestamp,
+                    price: s.p,
+                })
+            })
+            .collect();
+        points.sort_by_key(|p| p.timestamp);
+        points
+    }
+}
+
+/// Aggregate price samples into bars of width `bar`
+///
+/// Bars are aligned to multiples of the width since the Unix epoch. Gaps
+/// between samples are filled with flat bars at the previous close so that
+/// an indicator's period keeps matching wall-clock time.
+pub fn to_bars(points: &[PricePoint], bar: Duration) -> Vec<PriceBar> {
+    let width = bar.as_secs().max(1) as i64;
+    let mut bars: Vec<PriceBar> = Vec::new();
+
+    for point in points {
+        let start_ts = point.timestamp.timestamp().div_euclid(width) * width;
+        let Some(start) = Utc.timestamp_opt(start_ts, 0).single() else {
+            continue;
+        };
+
+        match bars.last_mut() {
+            Some(last) if last.start == start => {
+                last.high = last.high.max(point.price);
+                last.low = last.low.min(point.price);
+                last.close = point.price;
+                last.samples += 1;
+                continue;
+            }
+            Some(last) if last.start > start => continue,
+            _ => {}
+        }
+
+        if let Some(last) = bars.last().copied() {
+            let mut gap_ts = last.start.timestamp() + width;
+            while gap_ts < start_ts {
+                if let Some(gap_start) = Utc.timestamp_opt(gap_ts, 0).single() {
+                    bars.push(PriceBar {
+                        start: gap_start,
+                        open: last.close,
+                        high: last.close,
+                        low: last.close,
+                        close: last.close,
+                        samples: 0,
+                    });
+                }
+                gap_ts += width;
+            }
+        }
+
+        bars.push(PriceBar {
+            start,
+            open: point.price,
+            high: point.price,
+            low: point.price,
+            close: point.price,
+            samples: 1,
+        });
+    }
+
+    bars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history() {
+        let body = r#"{"history":[{"t":1700000120,"p":0.52},{"t":1700000060,"p":0.51}]}"#;
+        let points = serde_json::from_str::<HistoryResponse>(body).unwrap().into_points();
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].timestamp.timestamp(), 1700000060);
+        assert_eq!(points[1].price, 0.52);
+        assert!(serde_json::from_str::<HistoryResponse>("{}").unwrap().into_points().is_empty());
+
+        assert_eq!(HistoryRange::LastDay.query(), "interval=1d");
+        let start = Utc.timestamp_opt(1700000000, 0).unwrap();
+        let end = Utc.timestamp_opt(1700003600, 0).unwrap();
+        assert_eq!(HistoryRange::Between { start, end }.query(), "startTs=1700000000&endTs=1700003600");
+    }
+
+    #[test]
+    fn test_to_bars() {
+        let point = |t: i64, price: f64| PricePoint {
+            timestamp: Utc.timestamp_opt(t, 0).unwrap(),
+            price,
+        };
+        // 60s bars: three samples in the first, none in the second, one in the third
+        let points = vec![point(600, 0.50), point(630, 0.55), point(650, 0.48), point(720, 0.60)];
+        let bars = to_bars(&points, Duration::from_secs(60));
+
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars[0].start.timestamp(), 600);
+        assert_eq!((bars[0].open, bars[0].high, bars[0].low, bars[0].close), (0.50, 0.55, 0.48, 0.48));
+        assert_eq!(bars[0].samples, 3);
+        assert_eq!(bars[1].start.timestamp(), 660);
+        assert_eq!((bars[1].open, bars[1].close, bars[1].samples), (0.48, 0.48, 0));
+        assert_eq!((bars[2].open, bars[2].close, bars[2].samples), (0.60, 0.60, 1));
+        assert!(to_bars(&[], Duration::from_secs(60)).is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! Adaptive cooldown after venue throttling
+//!
+//! The local [`RateLimiter`](super::RateLimiter) keeps requests under the
+//! venue's published limits, but venues also throttle on limits they do
+//! not publish (per account, per endpoint, during load shedding). When a
+//! venue answers with a rate limit error (HTTP 429), the engine stops
+//! sending it new orders for a cooldown that starts at `base_ms` and
+//! doubles with every consecutive throttle up to `max_ms`. A request that
+//! succeeds resets the streak. Cancels are never held back.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::order::VenueId;
+
+/// Cooldown settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CooldownConfig {
+    /// Cooldown after the first throttle
+    pub base_ms: u64,
+
+    /// Cooldown cap
+    pub max_ms: u64,
+
+    /// Factor applied for each consecutive throttle
+    pub multiplier: f64,
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        Self {
+            base_ms: 1_000,
+            max_ms: 60_000,
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl CooldownConfig {
+    /// Cooldown after `strikes` consecutive throttles (1 for the first)
+    pub fn cooldown(&self, strikes: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(strikes.saturating_sub(1).min(64) as i32);
+        let ms = (self.base_ms as f64 * factor).min(self.max_ms as f64);
+        Duration::from_millis(ms as u64)
+    }
+}
+
+/// Throttle streak of one venue
+#[derive(Debug, Clone, Copy)]
+struct Throttle {
+    strikes: u32,
+    until: DateTime<Utc>,
+}
+
+/// Cooldowns per venue
+#[derive(Debug, Clone, Default)]
+pub struct VenueCooldowns {
+    config: CooldownConfig,
+    venues: HashMap<VenueId, Throttle>,
+}
+
+impl VenueCooldowns {
+    pub fn new(config: CooldownConfig) -> Self {
+        Self {
+            config,
+            venues: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &CooldownConfig {
+        &self.config
+    }
+
+    /// Record a throttle at `now`, returning the cooldown it started
+    pub fn throttled(&mut self, venue: &VenueId, now: DateTime<Utc>) -> Duration {
+        let strikes = self.venues.get(venue).map_or(0, |throttle| throttle.strikes) + 1;
+        let cooldown = self.config.cooldown(strikes);
+        let until = now + chrono::Duration::from_std(cooldown).unwrap_or_default();
+        self.venues.insert(venue.clone(), Throttle { strikes, until });
+        cooldown
+    }
+
+    /// Record a request the venue accepted, ending the throttle streak
+    pub fn succeeded(&mut self, venue: &VenueId) {
+        self.venues.remove(venue);
+    }
+
+    /// Time left in the venue's cooldown (None when not cooling down)
+    pub fn remaining(&self, venue: &VenueId, now: DateTime<Utc>) -> Option<Duration> {
+        let throttle = self.venues.get(venue)?;
+        (throttle.until > now).then(|| (throttle.until - now).to_std().unwrap_or_default())
+    }
+
+    /// Consecutive throttles of the venue
+    pub fn strikes(&self, venue: &VenueId) -> u32 {
+        self.venues.get(venue).map_or(0, |throttle| throttle.strikes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_cooldown_backoff() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let venue = VenueId::new("polymarket");
+        let mut cooldowns = VenueCooldowns::new(CooldownConfig {
+            base_ms: 1_000,
+            max_ms: 5_000,
+            multiplier: 2.0,
+        });
+        assert_eq!(cooldowns.remaining(&venue, start), None);
+
+        // Consecutive throttles double the cooldown up to the cap
+        assert_eq!(cooldowns.throttled(&venue, start), Duration::from_secs(1));
+        assert_eq!(cooldowns.remaining(&venue, start), Some(Duration::from_secs(1)));
+        assert_eq!(cooldowns.throttled(&venue, start), Duration::from_secs(2));
+        assert_eq!(cooldowns.throttled(&venue, start), Duration::from_secs(4));
+        assert_eq!(cooldowns.throttled(&venue, start), Duration::from_secs(5));
+        assert_eq!(cooldowns.strikes(&venue), 4);
+        assert_eq!(cooldowns.remaining(&venue, start + chrono::Duration::seconds(5)), None);
+
+        // A success resets the streak
+        cooldowns.succeeded(&venue);
+        assert_eq!(cooldowns.throttled(&venue, start), Duration::from_secs(1));
+        assert_eq!(cooldowns.remaining(&VenueId::new("other"), start), None);
+    }
+}
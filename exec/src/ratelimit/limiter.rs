@@ -2,19 +2,205 @@
 //!
 //! This module provides rate limiting functionality to prevent API violations
 //! when communicating with exchanges. It uses a token bucket algorithm.
+//!
+//! When several strategies share one venue limiter, requests are scheduled by
+//! [`RequestPriority`]: a waiting cancel is always served before a waiting
+//! risk-reducing order, which is served before new quotes. Lower priorities
+//! that have waited longer than the starvation timeout are promoted so they
+//! still make progress. New quotes can additionally be capped per strategy
+//! with a [`StrategyQuota`].
+//!
+//! [`RateLimiter::stats`] reports tokens remaining, time to the next token,
+//! queued requests and delayed/rejected counts per priority class, so missed
+//! quotes can be attributed to the rate limit.
 
 use governor::{DefaultDirectRateLimiter, Quota, RateLimiter as GovRateLimiter};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
 
 use crate::error::{ExecError, ExecResult};
 use crate::order::VenueId;
 
+/// Priority class of a venue request (highest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RequestPriority {
+    /// Order cancellation
+    Cancel = 0,
+    /// Order that reduces an existing position
+    RiskReducing = 1,
+    /// New quote or position-increasing order
+    NewQuote = 2,
+}
+
+impl RequestPriority {
+    /// All priorities, highest first
+    pub const ALL: [RequestPriority; 3] = [
+        RequestPriority::Cancel,
+        RequestPriority::RiskReducing,
+        RequestPriority::NewQuote,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for RequestPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestPriority::Cancel => write!(f, "cancel"),
+            RequestPriority::RiskReducing => write!(f, "risk_reducing"),
+            RequestPriority::NewQuote => write!(f, "new_quote"),
+        }
+    }
+}
+
+/// Per-strategy cap on new-quote requests
+///
+/// Cancels and risk-reducing orders are never limited by quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrategyQuota {
+    /// Requests per second
+    pub requests_per_second: u32,
+    /// Burst capacity
+    pub burst_size: u32,
+}
+
+/// Counters for one priority class
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PriorityStats {
+    /// Requests granted
+    pub granted: u64,
+    /// Requests that had to wait (delayed)
+    pub waited: u64,
+    /// Non-blocking requests refused: venue limit reached, strategy quota
+    /// exhausted or deferred to higher-priority waiters
+    pub rejected: u64,
+    /// Total time spent waiting
+    pub total_wait: Duration,
+    /// Longest single wait
+    pub max_wait: Duration,
+    /// Waits promoted past higher priorities by starvation protection
+    pub starvation_promotions: u64,
+    /// Requests currently waiting
+    pub waiting: usize,
+}
+
+/// Snapshot of rate limiter metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimiterStats {
+    /// Requests per second limit
+    pub requests_per_second: u32,
+    /// Burst capacity
+    pub burst_size: u32,
+    /// Requests that can be granted right now without waiting
+    pub tokens_remaining: f64,
+    /// Time until the next token is available (zero if one is available)
+    pub time_to_next_token: Duration,
+    /// Counters per priority class
+    pub by_priority: HashMap<RequestPriority, PriorityStats>,
+    /// Non-blocking requests rejected by a strategy quota, per strategy
+    pub quota_rejections: HashMap<String, u64>,
+}
+
+impl RateLimiterStats {
+    /// Requests currently queued across all priority classes
+    pub fn queued(&self) -> usize {
+        self.by_priority.values().map(|s| s.waiting).sum()
+    }
+
+    /// Requests that had to wait, across all priority classes
+    pub fn delayed(&self) -> u64 {
+        self.by_priority.values().map(|s| s.waited).sum()
+    }
+
+    /// Non-blocking requests refused, across all priority classes
+    pub fn rejected(&self) -> u64 {
+        self.by_priority.values().map(|s| s.rejected).sum()
+    }
+
+    /// Metric name/value pairs (`ratelimit.*`), per priority class as
+    /// `ratelimit.<priority>.*`
+    pub fn metric_values(&self) -> Vec<(String, f64)> {
+        let mut values = vec![
+            ("ratelimit.tokens_remaining".to_string(), self.tokens_remaining),
+            (
+                "ratelimit.time_to_next_token_ms".to_string(),
+                self.time_to_next_token.as_secs_f64() * 1000.0,
+            ),
+            ("ratelimit.queued".to_string(), self.queued() as f64),
+            ("ratelimit.delayed".to_string(), self.delayed() as f64),
+            ("ratelimit.rejected".to_string(), self.rejected() as f64),
+            (
+                "ratelimit.quota_rejections".to_string(),
+                self.quota_rejections.values().sum::<u64>() as f64,
+            ),
+        ];
+        for priority in RequestPriority::ALL {
+            let Some(stats) = self.by_priority.get(&priority) else {
+                continue;
+            };
+            let name = |metric: &str| format!("ratelimit.{}.{}", priority, metric);
+            values.extend([
+                (name("granted"), stats.granted as f64),
+                (name("delayed"), stats.waited as f64),
+                (name("rejected"), stats.rejected as f64),
+                (name("queued"), stats.waiting as f64),
+                (name("max_wait_ms"), stats.max_wait.as_secs_f64() * 1000.0),
+            ]);
+        }
+        values
+    }
+}
+
+#[derive(Default)]
+struct PriorityCounters {
+    granted: AtomicU64,
+    waited: AtomicU64,
+    total_wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+    promotions: AtomicU64,
+    waiting: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+/// Token bucket mirroring the limiter's state for reporting
+///
+/// The governor limiter doesn't expose its remaining capacity, so grants
+/// are replayed into an equivalent bucket (`burst_size` capacity refilled
+/// at `requests_per_second`).
+struct TokenGauge {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenGauge {
+    fn tokens_at(&self, now: Instant, requests_per_second: u32, burst_size: u32) -> f64 {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        (self.tokens + elapsed * requests_per_second as f64).min(burst_size as f64)
+    }
+}
+
+struct StrategyLimiter {
+    limiter: DefaultDirectRateLimiter,
+    rejections: AtomicU64,
+}
+
 /// Rate limiter for API requests
 pub struct RateLimiter {
     venue_id: VenueId,
     limiter: DefaultDirectRateLimiter,
     requests_per_second: u32,
     burst_size: u32,
+    starvation_timeout: Duration,
+    counters: [PriorityCounters; 3],
+    strategies: HashMap<String, StrategyLimiter>,
+    gauge: Mutex<TokenGauge>,
 }
 
 impl RateLimiter {
@@ -25,29 +211,83 @@ impl RateLimiter {
     /// * `requests_per_second` - Maximum requests per second
     /// * `burst_size` - Maximum burst capacity
     pub fn new(venue_id: VenueId, requests_per_second: u32, burst_size: u32) -> Self {
-        let quota = Quota::per_second(NonZeroU32::new(requests_per_second).unwrap())
-            .allow_burst(NonZeroU32::new(burst_size).unwrap());
-
         Self {
             venue_id,
-            limiter: GovRateLimiter::direct(quota),
+            limiter: GovRateLimiter::direct(quota(requests_per_second, burst_size)),
             requests_per_second,
             burst_size,
+            starvation_timeout: Duration::from_secs(2),
+            counters: Default::default(),
+            strategies: HashMap::new(),
+            gauge: Mutex::new(TokenGauge {
+                tokens: burst_size as f64,
+                refilled_at: Instant::now(),
+            }),
         }
     }
 
+    /// Set how long a lower-priority request may wait before it is served
+    /// ahead of higher-priority waiters
+    pub fn with_starvation_timeout(mut self, timeout: Duration) -> Self {
+        self.starvation_timeout = timeout;
+        self
+    }
+
+    /// Cap new-quote requests from one strategy
+    pub fn with_strategy_quota(mut self, strategy: impl Into<String>, quota_config: StrategyQuota) -> Self {
+        self.strategies.insert(
+            strategy.into(),
+            StrategyLimiter {
+                limiter: GovRateLimiter::direct(quota(
+                    quota_config.requests_per_second,
+                    quota_config.burst_size,
+                )),
+                rejections: AtomicU64::new(0),
+            },
+        );
+        self
+    }
+
     /// Check if a request is allowed and wait if necessary
     ///
     /// This method will block until the rate limit allows the request.
+    /// Equivalent to [`acquire`](Self::acquire) at `NewQuote` priority.
     pub async fn check(&self) -> ExecResult<()> {
-        match self.limiter.check() {
-            Ok(_) => Ok(()),
-            Err(_) => {
-                // Wait for the next available slot
-                self.limiter.until_ready().await;
-                Ok(())
+        self.acquire(RequestPriority::NewQuote, None).await
+    }
+
+    /// Wait for a request slot at the given priority
+    ///
+    /// New quotes from a strategy with a quota first wait for the quota.
+    pub async fn acquire(&self, priority: RequestPriority, strategy: Option<&str>) -> ExecResult<()> {
+        if priority == RequestPriority::NewQuote {
+            if let Some(limiter) = strategy.and_then(|s| self.strategies.get(s)) {
+                limiter.limiter.until_ready().await;
             }
         }
+
+        // Fast path: nothing more urgent is queued
+        if !self.higher_waiting(priority) && self.limiter.check().is_ok() {
+            self.record_grant(priority, None, false);
+            return Ok(());
+        }
+
+        let counters = &self.counters[priority.index()];
+        counters.waiting.fetch_add(1, Ordering::SeqCst);
+        let start = Instant::now();
+        let poll = self.poll_interval();
+
+        let promoted = loop {
+            let starving = start.elapsed() >= self.starvation_timeout;
+            if (starving || !self.higher_waiting(priority)) && self.limiter.check().is_ok() {
+                break starving && self.higher_waiting(priority);
+            }
+            tokio::time::sleep(poll).await;
+        };
+
+        counters.waiting.fetch_sub(1, Ordering::SeqCst);
+        self.record_grant(priority, Some(start.elapsed()), promoted);
+        Ok(())
     }
 
     /// Try to acquire permission without waiting
@@ -56,13 +296,88 @@ impl RateLimiter {
     /// * `Ok(())` - Permission granted
     /// * `Err(ExecError::RateLimitExceeded)` - Rate limit exceeded
     pub fn try_check(&self) -> ExecResult<()> {
-        self.limiter.check().map_err(|_| ExecError::RateLimitExceeded {
-            venue: self.venue_id.to_string(),
-            message: format!(
+        self.try_acquire(RequestPriority::NewQuote, None)
+    }
+
+    /// Try to acquire a slot at the given priority without waiting
+    ///
+    /// Fails if a higher-priority request is waiting, the strategy's quota
+    /// is exhausted (new quotes only) or the venue limit is reached.
+    pub fn try_acquire(&self, priority: RequestPriority, strategy: Option<&str>) -> ExecResult<()> {
+        let result = self.try_grant(priority, strategy);
+        if result.is_err() {
+            self.counters[priority.index()].rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn try_grant(&self, priority: RequestPriority, strategy: Option<&str>) -> ExecResult<()> {
+        if self.higher_waiting(priority) {
+            return Err(self.exceeded(format!(
+                "{} request deferred to higher-priority waiters",
+                priority
+            )));
+        }
+
+        if priority == RequestPriority::NewQuote {
+            if let Some((name, limiter)) = strategy.and_then(|s| self.strategies.get_key_value(s)) {
+                if limiter.limiter.check().is_err() {
+                    limiter.rejections.fetch_add(1, Ordering::Relaxed);
+                    return Err(self.exceeded(format!("Strategy {} quote quota exhausted", name)));
+                }
+            }
+        }
+
+        self.limiter.check().map_err(|_| {
+            self.exceeded(format!(
                 "Rate limit exceeded: {} requests/sec, burst {}",
                 self.requests_per_second, self.burst_size
-            ),
-        })
+            ))
+        })?;
+        self.record_grant(priority, None, false);
+        Ok(())
+    }
+
+    /// Snapshot of per-priority and per-strategy metrics
+    pub fn stats(&self) -> RateLimiterStats {
+        let by_priority = RequestPriority::ALL
+            .iter()
+            .map(|&priority| {
+                let c = &self.counters[priority.index()];
+                let stats = PriorityStats {
+                    granted: c.granted.load(Ordering::Relaxed),
+                    waited: c.waited.load(Ordering::Relaxed),
+                    total_wait: Duration::from_micros(c.total_wait_us.load(Ordering::Relaxed)),
+                    max_wait: Duration::from_micros(c.max_wait_us.load(Ordering::Relaxed)),
+                    starvation_promotions: c.promotions.load(Ordering::Relaxed),
+                    waiting: c.waiting.load(Ordering::Relaxed),
+                    rejected: c.rejected.load(Ordering::Relaxed),
+                };
+                (priority, stats)
+            })
+            .collect();
+
+        let quota_rejections = self
+            .strategies
+            .iter()
+            .map(|(name, s)| (name.clone(), s.rejections.load(Ordering::Relaxed)))
+            .collect();
+
+        let tokens_remaining = self.tokens_remaining();
+        let time_to_next_token = if tokens_remaining >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - tokens_remaining) / self.requests_per_second.max(1) as f64)
+        };
+
+        RateLimiterStats {
+            requests_per_second: self.requests_per_second,
+            burst_size: self.burst_size,
+            tokens_remaining,
+            time_to_next_token,
+            by_priority,
+            quota_rejections,
+        }
     }
 
     /// Get venue ID
@@ -79,6 +394,59 @@ impl RateLimiter {
     pub fn burst_size(&self) -> u32 {
         self.burst_size
     }
+
+    /// Requests that can be granted right now without waiting
+    pub fn tokens_remaining(&self) -> f64 {
+        let gauge = self.gauge.lock().unwrap_or_else(|e| e.into_inner());
+        gauge.tokens_at(Instant::now(), self.requests_per_second, self.burst_size)
+    }
+
+    /// Whether any request more urgent than `priority` is waiting
+    fn higher_waiting(&self, priority: RequestPriority) -> bool {
+        self.counters[..priority.index()]
+            .iter()
+            .any(|c| c.waiting.load(Ordering::SeqCst) > 0)
+    }
+
+    /// Re-check interval while queued: a quarter of one token interval,
+    /// bounded to [1ms, 10ms]
+    fn poll_interval(&self) -> Duration {
+        let token = Duration::from_secs(1) / self.requests_per_second.max(1);
+        (token / 4).clamp(Duration::from_millis(1), Duration::from_millis(10))
+    }
+
+    fn record_grant(&self, priority: RequestPriority, waited: Option<Duration>, promoted: bool) {
+        {
+            let mut gauge = self.gauge.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            gauge.tokens = (gauge.tokens_at(now, self.requests_per_second, self.burst_size) - 1.0).max(0.0);
+            gauge.refilled_at = now;
+        }
+
+        let c = &self.counters[priority.index()];
+        c.granted.fetch_add(1, Ordering::Relaxed);
+        if let Some(waited) = waited {
+            let us = waited.as_micros() as u64;
+            c.waited.fetch_add(1, Ordering::Relaxed);
+            c.total_wait_us.fetch_add(us, Ordering::Relaxed);
+            c.max_wait_us.fetch_max(us, Ordering::Relaxed);
+        }
+        if promoted {
+            c.promotions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn exceeded(&self, message: String) -> ExecError {
+        ExecError::RateLimitExceeded {
+            venue: self.venue_id.to_string(),
+            message,
+        }
+    }
+}
+
+fn quota(requests_per_second: u32, burst_size: u32) -> Quota {
+    Quota::per_second(NonZeroU32::new(requests_per_second).unwrap())
+        .allow_burst(NonZeroU32::new(burst_size).unwrap())
 }
 
 /// Rate limiter configuration
@@ -88,6 +456,10 @@ pub struct RateLimiterConfig {
     pub requests_per_second: u32,
     /// Burst capacity
     pub burst_size: u32,
+    /// Wait after which lower priorities are served ahead of higher ones
+    pub starvation_timeout: Duration,
+    /// New-quote quotas by strategy ID
+    pub strategy_quotas: HashMap<String, StrategyQuota>,
 }
 
 impl RateLimiterConfig {
@@ -96,28 +468,33 @@ impl RateLimiterConfig {
         Self {
             requests_per_second,
             burst_size,
+            ..Self::default()
         }
     }
 
+    /// Cap new-quote requests from one strategy
+    pub fn with_strategy_quota(mut self, strategy: impl Into<String>, quota: StrategyQuota) -> Self {
+        self.strategy_quotas.insert(strategy.into(), quota);
+        self
+    }
+
     /// Default configuration for Polymarket CLOB
     pub fn polymarket_default() -> Self {
-        Self {
-            requests_per_second: 10,
-            burst_size: 20,
-        }
+        Self::new(10, 20)
     }
 
     /// Default configuration for Binance
     pub fn binance_default() -> Self {
-        Self {
-            requests_per_second: 20,
-            burst_size: 50,
-        }
+        Self::new(20, 50)
     }
 
     /// Build a rate limiter with this configuration
     pub fn build(&self, venue_id: VenueId) -> RateLimiter {
-        RateLimiter::new(venue_id, self.requests_per_second, self.burst_size)
+        self.strategy_quotas.iter().fold(
+            RateLimiter::new(venue_id, self.requests_per_second, self.burst_size)
+                .with_starvation_timeout(self.starvation_timeout),
+            |limiter, (strategy, quota)| limiter.with_strategy_quota(strategy.clone(), *quota),
+        )
     }
 }
 
@@ -126,6 +503,8 @@ impl Default for RateLimiterConfig {
         Self {
             requests_per_second: 10,
             burst_size: 20,
+            starvation_timeout: Duration::from_secs(2),
+            strategy_quotas: HashMap::new(),
         }
     }
 }
@@ -133,8 +512,7 @@ impl Default for RateLimiterConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
-    use tokio::time::Instant;
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_rate_limiter_allows_requests() {
@@ -196,4 +574,112 @@ mod tests {
         assert_eq!(limiter.requests_per_second(), 5);
         assert_eq!(limiter.burst_size(), 10);
     }
+
+    #[tokio::test]
+    async fn test_cancel_served_before_queued_quotes() {
+        let limiter = Arc::new(RateLimiter::new(VenueId::new("test"), 20, 1));
+        limiter.try_check().unwrap();
+
+        // Queue a quote, then a cancel behind it
+        let quote = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                limiter.acquire(RequestPriority::NewQuote, None).await.unwrap();
+                Instant::now()
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let cancel = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                limiter.acquire(RequestPriority::Cancel, None).await.unwrap();
+                Instant::now()
+            })
+        };
+
+        let (quote_at, cancel_at) = (quote.await.unwrap(), cancel.await.unwrap());
+        assert!(cancel_at <= quote_at);
+
+        let stats = limiter.stats();
+        assert_eq!(stats.by_priority[&RequestPriority::Cancel].granted, 1);
+        assert_eq!(stats.by_priority[&RequestPriority::NewQuote].granted, 2);
+        assert_eq!(stats.by_priority[&RequestPriority::NewQuote].waiting, 0);
+    }
+
+    #[tokio::test]
+    async fn test_strategy_quota_and_priority_bypass() {
+        let limiter = RateLimiterConfig::new(100, 100)
+            .with_strategy_quota(
+                "mm_1",
+                StrategyQuota {
+                    requests_per_second: 1,
+                    burst_size: 2,
+                },
+            )
+            .build(VenueId::new("test"));
+
+        assert!(limiter.try_acquire(RequestPriority::NewQuote, Some("mm_1")).is_ok());
+        assert!(limiter.try_acquire(RequestPriority::NewQuote, Some("mm_1")).is_ok());
+        assert!(limiter.try_acquire(RequestPriority::NewQuote, Some("mm_1")).is_err());
+
+        // Quotas never block cancels or risk-reducing orders, or other strategies
+        assert!(limiter.try_acquire(RequestPriority::Cancel, Some("mm_1")).is_ok());
+        assert!(limiter.try_acquire(RequestPriority::RiskReducing, Some("mm_1")).is_ok());
+        assert!(limiter.try_acquire(RequestPriority::NewQuote, Some("arb_1")).is_ok());
+
+        assert_eq!(limiter.stats().quota_rejections["mm_1"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_stats_report_tokens_and_rejections() {
+        let limiter = RateLimiter::new(VenueId::new("test"), 2, 3);
+        let stats = limiter.stats();
+        assert_eq!(stats.tokens_remaining, 3.0);
+        assert_eq!(stats.time_to_next_token, Duration::ZERO);
+
+        for _ in 0..3 {
+            limiter.try_acquire(RequestPriority::NewQuote, None).unwrap();
+        }
+        assert!(limiter.try_acquire(RequestPriority::NewQuote, None).is_err());
+        assert!(limiter.try_acquire(RequestPriority::Cancel, None).is_err());
+
+        let stats = limiter.stats();
+        assert!(stats.tokens_remaining < 1.0);
+        assert!(stats.time_to_next_token > Duration::ZERO);
+        assert!(stats.time_to_next_token <= Duration::from_millis(500));
+        assert_eq!(stats.by_priority[&RequestPriority::NewQuote].granted, 3);
+        assert_eq!(stats.by_priority[&RequestPriority::NewQuote].rejected, 1);
+        assert_eq!(stats.rejected(), 2);
+        assert_eq!(stats.queued(), 0);
+
+        let metrics: HashMap<String, f64> = stats.metric_values().into_iter().collect();
+        assert_eq!(metrics["ratelimit.rejected"], 2.0);
+        assert_eq!(metrics["ratelimit.new_quote.granted"], 3.0);
+        assert_eq!(metrics["ratelimit.cancel.rejected"], 1.0);
+
+        // Tokens refill at the configured rate
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(limiter.stats().tokens_remaining >= 1.0);
+        assert!(limiter.try_acquire(RequestPriority::NewQuote, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_starvation_promotion() {
+        let limiter = RateLimiter::new(VenueId::new("test"), 50, 1)
+            .with_starvation_timeout(Duration::from_millis(30));
+        limiter.try_check().unwrap();
+
+        // Simulate a cancel that stays queued forever
+        limiter.counters[RequestPriority::Cancel.index()]
+            .waiting
+            .fetch_add(1, Ordering::SeqCst);
+
+        let start = Instant::now();
+        limiter.acquire(RequestPriority::NewQuote, None).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(
+            limiter.stats().by_priority[&RequestPriority::NewQuote].starvation_promotions,
+            1
+        );
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! This module provides rate limiting functionality to prevent API violations.
 
+pub mod cooldown;
 pub mod limiter;
 
+pub use cooldown::{CooldownConfig, VenueCooldowns};
 pub use limiter::{RateLimiter, RateLimiterConfig};
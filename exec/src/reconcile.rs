@@ -0,0 +1,513 @@
+//! OMS vs venue reconciliation
+//!
+//! [`Reconciler::reconcile`] compares the engine's view of each venue with
+//! what the venue itself reports and lists every disagreement:
+//!
+//! - orders open locally but not at the venue, and orders open at the venue
+//!   that are unknown (or already closed) locally
+//! - venue fills with no local record
+//! - positions differing by more than `position_tolerance`
+//!
+//! Every discrepancy carries the time it was first seen, so a transient
+//! mismatch (an order in flight, a fill not yet delivered) can be told apart
+//! from one that persists. Venues whose adapters don't report positions or
+//! fills skip those checks and list them as unsupported.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::engine::ExecutionEngine;
+use crate::error::{ExecError, ExecResult};
+use crate::order::{Fill, MarketId, Order, VenueId};
+
+/// Reconciliation settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconciliationConfig {
+    /// Largest position difference treated as agreement
+    pub position_tolerance: f64,
+
+    /// How far back venue fills are compared, in seconds
+    pub fill_lookback_secs: u64,
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            position_tolerance: 1e-6,
+            fill_lookback_secs: 3600,
+        }
+    }
+}
+
+/// Kind of disagreement between the OMS and a venue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// Order open locally but not at the venue
+    MissingAtVenue,
+    /// Order open at the venue but unknown or closed locally
+    UnknownLocally,
+    /// Fill reported by the venue but not recorded locally
+    UnmatchedFill,
+    /// Local and venue positions differ
+    PositionMismatch,
+}
+
+/// A single disagreement between the OMS and a venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Discrepancy {
+    /// What disagrees
+    pub kind: DiscrepancyKind,
+    /// Canonical market, if known
+    pub market: Option<MarketId>,
+    /// Order ID, client order ID, fill ID or market the discrepancy is about
+    pub reference: String,
+    /// Local size or position (`None` if unknown locally)
+    pub local: Option<f64>,
+    /// Venue size or position (`None` if absent at the venue)
+    pub venue: Option<f64>,
+    /// When the discrepancy was first seen
+    pub first_seen: DateTime<Utc>,
+    /// Seconds since `first_seen` when the report was generated
+    pub age_secs: f64,
+}
+
+/// Reconciliation status of one venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueReconciliation {
+    /// Venue reconciled
+    pub venue: VenueId,
+    /// Orders open locally
+    pub local_open_orders: usize,
+    /// Orders open at the venue
+    pub venue_open_orders: usize,
+    /// Checks the adapter does not support ("positions", "fills")
+    pub unsupported: Vec<String>,
+    /// Venue query failure, if any check could not run
+    pub error: Option<String>,
+    /// Disagreements, oldest first
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl VenueReconciliation {
+    /// Whether every check ran and found no disagreement
+    pub fn is_consistent(&self) -> bool {
+        self.error.is_none() && self.discrepancies.is_empty()
+    }
+
+    /// Number of discrepancies of a kind
+    pub fn count(&self, kind: DiscrepancyKind) -> usize {
+        self.discrepancies.iter().filter(|d| d.kind == kind).count()
+    }
+
+    /// Age of the oldest discrepancy in seconds (0 if none)
+    pub fn oldest_age_secs(&self) -> f64 {
+        self.discrepancies.iter().map(|d| d.age_secs).fold(0.0, f64::max)
+    }
+
+    /// Status as `(metric name, value)` pairs
+    pub fn metric_values(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            (
+                "reconciliation.consistent",
+                if self.is_consistent() { 1.0 } else { 0.0 },
+            ),
+            ("reconciliation.local_open_orders", self.local_open_orders as f64),
+            ("reconciliation.venue_open_orders", self.venue_open_orders as f64),
+            (
+                "reconciliation.orders_missing_at_venue",
+                self.count(DiscrepancyKind::MissingAtVenue) as f64,
+            ),
+            (
+                "reconciliation.orders_unknown_locally",
+                self.count(DiscrepancyKind::UnknownLocally) as f64,
+            ),
+            (
+                "reconciliation.unmatched_fills",
+                self.count(DiscrepancyKind::UnmatchedFill) as f64,
+            ),
+            (
+                "reconciliation.position_mismatches",
+                self.count(DiscrepancyKind::PositionMismatch) as f64,
+            ),
+            ("reconciliation.oldest_discrepancy_secs", self.oldest_age_secs()),
+        ]
+    }
+}
+
+/// Reconciliation status of every venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    /// When the venues were queried
+    pub generated_at: DateTime<Utc>,
+    /// Per-venue status, sorted by venue
+    pub venues: Vec<VenueReconciliation>,
+}
+
+impl ReconciliationReport {
+    /// Whether every venue is consistent
+    pub fn is_consistent(&self) -> bool {
+        self.venues.iter().all(VenueReconciliation::is_consistent)
+    }
+
+    /// Status of one venue
+    pub fn venue(&self, venue: &VenueId) -> Option<&VenueReconciliation> {
+        self.venues.iter().find(|v| &v.venue == venue)
+    }
+
+    /// Every discrepancy across venues
+    pub fn discrepancies(&self) -> impl Iterator<Item = (&VenueId, &Discrepancy)> {
+        self.venues
+            .iter()
+            .flat_map(|v| v.discrepancies.iter().map(move |d| (&v.venue, d)))
+    }
+}
+
+/// What a venue reported
+#[derive(Debug, Clone, Default)]
+struct VenueView {
+    open_orders: Vec<Order>,
+    /// Net position per canonical market (`None` if not reported)
+    positions: Option<HashMap<String, f64>>,
+    /// Recent fills (`None` if not reported)
+    fills: Option<Vec<Fill>>,
+}
+
+/// A discrepancy before its age is known
+type Finding = (DiscrepancyKind, Option<MarketId>, String, Option<f64>, Option<f64>);
+
+/// Compares the OMS with venues, remembering when each discrepancy appeared
+#[derive(Debug, Clone, Default)]
+pub struct Reconciler {
+    config: ReconciliationConfig,
+    first_seen: HashMap<(VenueId, DiscrepancyKind, String), DateTime<Utc>>,
+    latest: Option<ReconciliationReport>,
+}
+
+impl Reconciler {
+    /// Create a reconciler
+    pub fn new(config: ReconciliationConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Most recent report
+    pub fn latest(&self) -> Option<&ReconciliationReport> {
+        self.latest.as_ref()
+    }
+
+    /// Query every registered venue and compare it with the OMS
+    pub async fn reconcile(&mut self, engine: &ExecutionEngine) -> ExecResult<ReconciliationReport> {
+        let now = Utc::now();
+        let orders = engine.order_tracker().get_all_orders()?;
+        let positions = engine.get_all_positions().await;
+        let symbols = engine.symbol_mapper();
+
+        let mut adapters: Vec<_> = engine.adapters().iter().collect();
+        adapters.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+
+        let mut venues = Vec::with_capacity(adapters.len());
+        let mut seen = HashSet::new();
+        let mut failed = HashSet::new();
+        for (venue_id, adapter) in adapters {
+            let mut status = VenueReconciliation {
+                venue: venue_id.clone(),
+                local_open_orders: 0,
+                venue_open_orders: 0,
+                unsupported: Vec::new(),
+                error: None,
+                discrepancies: Vec::new(),
+            };
+
+            let mut view = VenueView::default();
+            {
+                let mut adapter = adapter.lock().await;
+                match adapter.get_open_orders().await {
+                    Ok(open_orders) => view.open_orders = open_orders,
+                    Err(e) => {
+                        warn!("Reconciliation of {} failed: {}", venue_id, e);
+                        status.error = Some(e.to_string());
+                        failed.insert(venue_id.clone());
+                        venues.push(status);
+                        continue;
+                    }
+                }
+                match adapter.get_positions().await {
+                    Ok(reported) => view.positions = Some(reported),
+                    Err(ExecError::VenueNotSupported(_)) => status.unsupported.push("positions".to_string()),
+                    Err(e) => {
+                        warn!("Position query for {} failed: {}", venue_id, e);
+                        status.error = Some(e.to_string());
+                        failed.insert(venue_id.clone());
+                    }
+                }
+                let since = now - chrono::Duration::seconds(self.config.fill_lookback_secs as i64);
+                match adapter.get_fills(since).await {
+                    Ok(fills) => view.fills = Some(fills),
+                    Err(ExecError::VenueNotSupported(_)) => status.unsupported.push("fills".to_string()),
+                    Err(e) => {
+                        warn!("Fill query for {} failed: {}", venue_id, e);
+                        status.error = Some(e.to_string());
+                        failed.insert(venue_id.clone());
+                    }
+                }
+            }
+
+            // Venues report their own symbols
+            {
+                let symbols = symbols.read().await;
+                let canonical = |market: &str| {
+                    symbols
+                        .to_canonical(venue_id, market)
+                        .map(|m| m.as_str().to_string())
+                        .unwrap_or_else(|| market.to_string())
+                };
+                for order in &mut view.open_orders {
+                    order.market = MarketId::new(canonical(order.market.as_str()));
+                }
+                view.positions = view.positions.map(|reported| {
+                    let mut merged: HashMap<String, f64> = HashMap::new();
+                    for (market, size) in reported {
+                        *merged.entry(canonical(&market)).or_insert(0.0) += size;
+                    }
+                    merged
+                });
+            }
+
+            let local_orders: Vec<&Order> = orders.iter().filter(|o| &o.venue == venue_id).collect();
+            status.local_open_orders = local_orders.iter().filter(|o| o.is_active()).count();
+            status.venue_open_orders = view.open_orders.len();
+
+            let mut local_fill_ids = HashSet::new();
+            for order in &local_orders {
+                for fill in engine.order_tracker().get_fills(&order.id)? {
+                    local_fill_ids.insert(fill.fill_id);
+                }
+            }
+            let local_positions = venue_positions(venue_id, &orders, &positions);
+
+            for (kind, market, reference, local, venue) in
+                compare(&local_orders, &local_fill_ids, &local_positions, &view, &self.config)
+            {
+                let key = (venue_id.clone(), kind, reference.clone());
+                let first_seen = *self.first_seen.entry(key.clone()).or_insert(now);
+                seen.insert(key);
+                status.discrepancies.push(Discrepancy {
+                    kind,
+                    market,
+                    reference,
+                    local,
+                    venue,
+                    first_seen,
+                    age_secs: (now - first_seen).num_milliseconds() as f64 / 1000.0,
+                });
+            }
+            status.discrepancies.sort_by_key(|d| d.first_seen);
+            debug!("Reconciled {}: {} discrepancies", venue_id, status.discrepancies.len());
+            venues.push(status);
+        }
+
+        // Resolved discrepancies are forgotten; venues that failed a query keep their ages
+        self.first_seen
+            .retain(|key, _| seen.contains(key) || failed.contains(&key.0));
+
+        let report = ReconciliationReport {
+            generated_at: now,
+            venues,
+        };
+        self.latest = Some(report.clone());
+        Ok(report)
+    }
+}
+
+/// Local positions whose most recent order went to `venue`
+fn venue_positions(venue: &VenueId, orders: &[Order], positions: &HashMap<String, f64>) -> HashMap<String, f64> {
+    let mut latest: HashMap<&str, &Order> = HashMap::new();
+    for order in orders {
+        let entry = latest.entry(order.market.as_str()).or_insert(order);
+        if order.updated_at > entry.updated_at {
+            *entry = order;
+        }
+    }
+
+    positions
+        .iter()
+        .filter(|(market, _)| latest.get(market.as_str()).is_some_and(|o| &o.venue == venue))
+        .map(|(market, size)| (market.clone(), *size))
+        .collect()
+}
+
+/// Discrepancies between one venue's local state and what it reported
+fn compare(
+    local_orders: &[&Order],
+    local_fill_ids: &HashSet<String>,
+    local_positions: &HashMap<String, f64>,
+    view: &VenueView,
+    config: &ReconciliationConfig,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let same_order = |local: &Order, venue: &Order| {
+        local.id == venue.id || (!local.client_order_id.is_empty() && local.client_order_id == venue.client_order_id)
+    };
+
+    for local in local_orders.iter().filter(|o| o.is_active()) {
+        if !view.open_orders.iter().any(|venue| same_order(local, venue)) {
+            findings.push((
+                DiscrepancyKind::MissingAtVenue,
+                Some(local.market.clone()),
+                local.id.to_string(),
+                Some(local.remaining_size()),
+                None,
+            ));
+        }
+    }
+
+    for venue in &view.open_orders {
+        if !local_orders
+            .iter()
+            .any(|local| local.is_active() && same_order(local, venue))
+        {
+            let reference = if venue.client_order_id.is_empty() {
+                venue.id.to_string()
+            } else {
+                venue.client_order_id.clone()
+            };
+            findings.push((
+                DiscrepancyKind::UnknownLocally,
+                Some(venue.market.clone()),
+                reference,
+                None,
+                Some(venue.remaining_size()),
+            ));
+        }
+    }
+
+    for fill in view.fills.iter().flatten() {
+        if !local_fill_ids.contains(&fill.fill_id) {
+            let market = local_orders
+                .iter()
+                .find(|o| o.id == fill.order_id)
+                .map(|o| o.market.clone());
+            findings.push((
+                DiscrepancyKind::UnmatchedFill,
+                market,
+                fill.fill_id.clone(),
+                None,
+                Some(fill.size),
+            ));
+        }
+    }
+
+    if let Some(reported) = &view.positions {
+        let mut markets: Vec<&String> = local_positions.keys().chain(reported.keys()).collect();
+        markets.sort();
+        markets.dedup();
+        for market in markets {
+            let local = local_positions.get(market).copied().unwrap_or(0.0);
+            let venue = reported.get(market).copied().unwrap_or(0.0);
+            if (local - venue).abs() > config.position_tolerance {
+                findings.push((
+                    DiscrepancyKind::PositionMismatch,
+                    Some(MarketId::new(market.clone())),
+                    market.clone(),
+                    Some(local),
+                    Some(venue),
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{OrderStatus, OrderType, Side, TimeInForce};
+
+    fn order(client_id: &str, status: OrderStatus) -> Order {
+        let mut order = Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("m1"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.50),
+            100.0,
+            TimeInForce::GTC,
+            client_id.to_string(),
+        );
+        order.status = status;
+        order
+    }
+
+    fn fill(fill_id: &str) -> Fill {
+        Fill {
+            fill_id: fill_id.to_string(),
+            order_id: crate::order::OrderId::new(),
+            venue_order_id: None,
+            price: 0.50,
+            size: 10.0,
+            fee: 0.0,
+            fee_currency: "USD".to_string(),
+            timestamp: Utc::now(),
+            liquidity: None,
+        }
+    }
+
+    #[test]
+    fn test_compare_finds_each_kind() {
+        let matched = order("c-1", OrderStatus::Working);
+        let missing = order("c-2", OrderStatus::Working);
+        let closed = order("c-3", OrderStatus::Cancelled);
+        let local_orders = vec![&matched, &missing, &closed];
+
+        // The venue still has the locally cancelled order open
+        let view = VenueView {
+            open_orders: vec![
+                Order {
+                    id: crate::order::OrderId::new(),
+                    ..matched.clone()
+                },
+                closed.clone(),
+            ],
+            positions: Some(HashMap::from([("m1".to_string(), 40.0), ("m2".to_string(), 0.0)])),
+            fills: Some(vec![fill("f-1"), fill("f-2")]),
+        };
+        let local_fill_ids = HashSet::from(["f-1".to_string()]);
+        let local_positions = HashMap::from([("m1".to_string(), 50.0)]);
+
+        let findings = compare(
+            &local_orders,
+            &local_fill_ids,
+            &local_positions,
+            &view,
+            &Default::default(),
+        );
+        let kinds: Vec<(DiscrepancyKind, &str)> = findings.iter().map(|f| (f.0, f.2.as_str())).collect();
+        let missing_id = missing.id.to_string();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiscrepancyKind::MissingAtVenue, missing_id.as_str()),
+                (DiscrepancyKind::UnknownLocally, "c-3"),
+                (DiscrepancyKind::UnmatchedFill, "f-2"),
+                (DiscrepancyKind::PositionMismatch, "m1"),
+            ]
+        );
+        assert_eq!(findings[3].3, Some(50.0));
+        assert_eq!(findings[3].4, Some(40.0));
+    }
+
+    #[test]
+    fn test_unreported_checks_are_skipped() {
+        let view = VenueView::default();
+        let local_positions = HashMap::from([("m1".to_string(), 50.0)]);
+        assert!(compare(&[], &HashSet::new(), &local_positions, &view, &Default::default()).is_empty());
+    }
+}
@@ -0,0 +1,642 @@
+//! Market scanner and tradable universe selection
+//!
+//! The [`MarketScanner`] pulls a venue's market catalog, drops markets that
+//! fail the configured [`ScannerFilters`] (liquidity, spread, 24h volume,
+//! time to resolution, category), ranks the rest and diffs the result
+//! against the previous scan. Each [`UniverseUpdate`] lists the ranked
+//! universe plus the markets that were onboarded and retired, so the
+//! coordinator can subscribe to new markets and drop resolved ones.
+//!
+//! [`PolymarketCatalog`] reads the Polymarket Gamma markets API; any other
+//! source can implement [`MarketCatalog`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ag_sched::{Schedule, Ticker};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::error::{ExecError, ExecResult};
+use crate::market_quality::{MarketQuality, MarketQualityRecorder};
+use crate::order::MarketId;
+use crate::venues::polymarket::classify_error;
+
+/// Default Polymarket Gamma API endpoint
+pub const POLYMARKET_GAMMA_ENDPOINT: &str = "https://gamma-api.polymarket.com";
+
+/// Markets requested per catalog page
+const CATALOG_PAGE_SIZE: usize = 500;
+
+/// One market listed in a venue catalog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogMarket {
+    /// Market identifier (Polymarket condition ID)
+    pub market_id: MarketId,
+    /// Market question / title
+    pub question: String,
+    /// Category, if the venue provides one
+    pub category: Option<String>,
+    /// Scheduled resolution time
+    pub end_date: Option<DateTime<Utc>>,
+    /// Whether the market is accepting orders
+    pub active: bool,
+    /// Whether the market is closed / resolved
+    pub closed: bool,
+    /// Resting liquidity in USD
+    pub liquidity_usd: f64,
+    /// Traded volume over the last 24 hours in USD
+    pub volume_24h_usd: f64,
+    /// Best bid, if quoted
+    pub best_bid: Option<f64>,
+    /// Best ask, if quoted
+    pub best_ask: Option<f64>,
+    /// Outcome token IDs
+    pub token_ids: Vec<String>,
+}
+
+impl CatalogMarket {
+    /// Bid-ask spread, if both sides are quoted
+    pub fn spread(&self) -> Option<f64> {
+        match (self.best_bid, self.best_ask) {
+            (Some(bid), Some(ask)) => Some(ask - bid),
+            _ => None,
+        }
+    }
+
+    /// Whether the market is resolved or past its end date
+    pub fn is_resolved(&self, now: DateTime<Utc>) -> bool {
+        self.closed || self.end_date.is_some_and(|end| end <= now)
+    }
+}
+
+/// Source of a venue's market catalog
+#[async_trait]
+pub trait MarketCatalog: Send + Sync {
+    /// Fetch all currently listed markets
+    async fn fetch_markets(&self) -> ExecResult<Vec<CatalogMarket>>;
+}
+
+/// Polymarket Gamma API market catalog
+pub struct PolymarketCatalog {
+    endpoint: String,
+    client: Client,
+}
+
+impl PolymarketCatalog {
+    /// Create a catalog client for a Gamma API endpoint
+    pub fn new(endpoint: impl Into<String>) -> ExecResult<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| ExecError::ConfigError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl MarketCatalog for PolymarketCatalog {
+    async fn fetch_markets(&self) -> ExecResult<Vec<CatalogMarket>> {
+        let mut markets = Vec::new();
+
+        for page in 0.. {
+            let url = format!(
+                "{}/markets?active=true&closed=false&limit={}&offset={}",
+                self.endpoint,
+                CATALOG_PAGE_SIZE,
+                page * CATALOG_PAGE_SIZE
+            );
+
+            let response = self.client.get(&url).send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(ExecError::VenueError {
+                    venue: "polymarket".to_string(),
+                    message: format!("Market catalog request failed: {}", error_text),
+                    code: Some(status.as_str().to_string()),
+                    kind: classify_error(status.as_u16(), &error_text),
+                });
+            }
+
+            let batch: Vec<GammaMarket> = response.json().await?;
+            let done = batch.len() < CATALOG_PAGE_SIZE;
+            markets.extend(batch.into_iter().map(CatalogMarket::from));
+            if done {
+                break;
+            }
+        }
+
+        debug!("Fetched {} markets from {}", markets.len(), self.endpoint);
+        Ok(markets)
+    }
+}
+
+/// Gamma API market (only the fields the scanner uses)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GammaMarket {
+    condition_id: String,
+    #[serde(default)]
+    question: String,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    end_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    active: bool,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    liquidity_num: Option<f64>,
+    #[serde(default, rename = "volume24hr")]
+    volume_24hr: Option<f64>,
+    #[serde(default)]
+    best_bid: Option<f64>,
+    #[serde(default)]
+    best_ask: Option<f64>,
+    /// JSON-encoded array of token IDs
+    #[serde(default, deserialize_with = "string_list")]
+    clob_token_ids: Vec<String>,
+}
+
+impl From<GammaMarket> for CatalogMarket {
+    fn from(market: GammaMarket) -> Self {
+        Self {
+            market_id: MarketId::new(market.condition_id),
+            question: market.question,
+            category: market.category,
+            end_date: market.end_date,
+            active: market.active,
+            closed: market.closed,
+            liquidity_usd: market.liquidity_num.unwrap_or(0.0),
+            volume_24h_usd: market.volume_24hr.unwrap_or(0.0),
+            best_bid: market.best_bid,
+            best_ask: market.best_ask,
+            token_ids: market.clob_token_ids,
+        }
+    }
+}
+
+/// Gamma encodes some lists as JSON strings (`"[\"1\", \"2\"]"`)
+fn string_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    match raw {
+        Some(raw) if !raw.is_empty() => serde_json::from_str(&raw).map_err(serde::de::Error::custom),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Universe selection criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScannerFilters {
+    /// Minimum resting liquidity in USD
+    pub min_liquidity_usd: f64,
+    /// Maximum bid-ask spread (price units); markets without a two-sided
+    /// quote are excluded when set
+    pub max_spread: Option<f64>,
+    /// Minimum 24h volume in USD
+    pub min_volume_24h_usd: f64,
+    /// Exclude markets resolving sooner than this many seconds from now
+    pub min_time_to_end_secs: Option<u64>,
+    /// Exclude markets resolving later than this many seconds from now
+    pub max_time_to_end_secs: Option<u64>,
+    /// Only include these categories (empty = all)
+    pub categories: Vec<String>,
+    /// Never include these categories
+    pub exclude_categories: Vec<String>,
+    /// Maximum universe size (highest ranked kept)
+    pub max_markets: usize,
+}
+
+impl Default for ScannerFilters {
+    fn default() -> Self {
+        Self {
+            min_liquidity_usd: 0.0,
+            max_spread: None,
+            min_volume_24h_usd: 0.0,
+            min_time_to_end_secs: None,
+            max_time_to_end_secs: None,
+            categories: Vec::new(),
+            exclude_categories: Vec::new(),
+            max_markets: 50,
+        }
+    }
+}
+
+impl ScannerFilters {
+    /// Whether a market passes every filter at `now`
+    pub fn accepts(&self, market: &CatalogMarket, now: DateTime<Utc>) -> bool {
+        if !market.active || market.is_resolved(now) {
+            return false;
+        }
+        if market.liquidity_usd < self.min_liquidity_usd || market.volume_24h_usd < self.min_volume_24h_usd {
+            return false;
+        }
+        if let Some(max_spread) = self.max_spread {
+            if !market.spread().is_some_and(|spread| spread <= max_spread) {
+                return false;
+            }
+        }
+
+        if let Some(end) = market.end_date {
+            let secs_to_end = (end - now).num_seconds().max(0) as u64;
+            if self.min_time_to_end_secs.is_some_and(|min| secs_to_end < min)
+                || self.max_time_to_end_secs.is_some_and(|max| secs_to_end > max)
+            {
+                return false;
+            }
+        } else if self.max_time_to_end_secs.is_some() {
+            return false;
+        }
+
+        let category = market.category.as_deref().unwrap_or_default();
+        let matches = |list: &[String]| list.iter().any(|c| c.eq_ignore_ascii_case(category));
+        if !self.categories.is_empty() && !matches(&self.categories) {
+            return false;
+        }
+        !matches(&self.exclude_categories)
+    }
+}
+
+/// Weights of the ranking score
+///
+/// `score = volume * ln(1 + volume_24h_usd) + liquidity * ln(1 + liquidity_usd)
+///          - spread * spread_in_cents + depth * ln(1 + depth_usd)`
+///
+/// With recorded [`MarketQuality`], the observed average spread replaces the
+/// catalog spread and `depth_usd` is the observed top-of-book notional;
+/// without it the depth term is zero.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RankingWeights {
+    pub volume: f64,
+    pub liquidity: f64,
+    pub spread: f64,
+    pub depth: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            liquidity: 1.0,
+            spread: 0.5,
+            depth: 0.0,
+        }
+    }
+}
+
+impl RankingWeights {
+    /// Ranking score of a market (higher is better)
+    pub fn score(&self, market: &CatalogMarket) -> f64 {
+        self.score_observed(market, None)
+    }
+
+    /// Ranking score using recorded market quality where available
+    pub fn score_observed(&self, market: &CatalogMarket, quality: Option<&MarketQuality>) -> f64 {
+        let spread = quality
+            .and_then(|q| q.avg_spread.or(q.spread))
+            .or_else(|| market.spread())
+            .unwrap_or(0.0);
+        let depth_usd = quality.map_or(0.0, |q| q.depth_usd);
+
+        self.volume * market.volume_24h_usd.max(0.0).ln_1p()
+            + self.liquidity * market.liquidity_usd.max(0.0).ln_1p()
+            - self.spread * spread * 100.0
+            + self.depth * depth_usd.max(0.0).ln_1p()
+    }
+}
+
+/// A market selected into the universe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedMarket {
+    /// 1-based rank
+    pub rank: usize,
+    /// Ranking score
+    pub score: f64,
+    /// Catalog entry
+    pub market: CatalogMarket,
+}
+
+/// Why a market left the universe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetireReason {
+    /// Closed or past its end date
+    Resolved,
+    /// Still listed but no longer passes the filters or ranks too low
+    Filtered,
+    /// No longer in the catalog
+    Delisted,
+}
+
+/// Result of one scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniverseUpdate {
+    /// When the scan ran
+    pub scanned_at: DateTime<Utc>,
+    /// Ranked tradable universe, best first
+    pub universe: Vec<RankedMarket>,
+    /// Markets that joined the universe
+    pub added: Vec<MarketId>,
+    /// Markets that left the universe
+    pub retired: Vec<(MarketId, RetireReason)>,
+}
+
+impl UniverseUpdate {
+    /// Market IDs in rank order
+    pub fn market_ids(&self) -> Vec<MarketId> {
+        self.universe.iter().map(|m| m.market.market_id.clone()).collect()
+    }
+
+    /// Whether the universe membership changed
+    pub fn is_changed(&self) -> bool {
+        !self.added.is_empty() || !self.retired.is_empty()
+    }
+}
+
+/// Periodic catalog scanner producing a ranked universe
+pub struct MarketScanner {
+    catalog: Box<dyn MarketCatalog>,
+    filters: ScannerFilters,
+    weights: RankingWeights,
+    quality: Option<Arc<RwLock<MarketQualityRecorder>>>,
+    current: Vec<MarketId>,
+}
+
+impl MarketScanner {
+    /// Create a scanner with default ranking weights
+    pub fn new(catalog: Box<dyn MarketCatalog>, filters: ScannerFilters) -> Self {
+        Self {
+            catalog,
+            filters,
+            weights: RankingWeights::default(),
+            quality: None,
+            current: Vec::new(),
+        }
+    }
+
+    /// Set the ranking weights
+    pub fn with_weights(mut self, weights: RankingWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Rank with observed spread and depth from a market quality recorder
+    pub fn with_market_quality(mut self, recorder: Arc<RwLock<MarketQualityRecorder>>) -> Self {
+        self.quality = Some(recorder);
+        self
+    }
+
+    /// Markets in the current universe, in rank order
+    pub fn universe(&self) -> &[MarketId] {
+        &self.current
+    }
+
+    /// Pull the catalog and compute the new universe
+    pub async fn scan(&mut self) -> ExecResult<UniverseUpdate> {
+        let markets = self.catalog.fetch_markets().await?;
+        let now = Utc::now();
+        let quality = match &self.quality {
+            Some(recorder) => recorder
+                .read()
+                .await
+                .snapshot(now)
+                .into_iter()
+                .map(|q| (q.market_id.clone(), q))
+                .collect(),
+            None => HashMap::new(),
+        };
+        let update = self.select(markets, &quality, now);
+        self.current = update.market_ids();
+
+        if update.is_changed() {
+            info!(
+                "Universe: {} markets ({} added, {} retired)",
+                update.universe.len(),
+                update.added.len(),
+                update.retired.len()
+            );
+        }
+        Ok(update)
+    }
+
+    /// Scan every `interval`, starting immediately, and send each update
+    /// until the receiver is dropped; failed scans are logged and retried
+    /// on the next interval
+    pub async fn run(self, interval: Duration, updates: mpsc::Sender<UniverseUpdate>) {
+        self.run_with(Ticker::interval(interval), updates).await
+    }
+
+    /// Scan on `schedule` (e.g. a cron expression) and send each update
+    /// until the receiver is dropped or the schedule is exhausted
+    pub async fn run_scheduled(self, schedule: impl Into<Schedule>, updates: mpsc::Sender<UniverseUpdate>) {
+        self.run_with(Ticker::new(schedule), updates).await
+    }
+
+    async fn run_with(mut self, mut ticker: Ticker, updates: mpsc::Sender<UniverseUpdate>) {
+        while ticker.tick().await.is_some() {
+            match self.scan().await {
+                Ok(update) => {
+                    if updates.send(update).await.is_err() {
+                        debug!("Universe receiver dropped, stopping scanner");
+                        return;
+                    }
+                }
+                Err(e) => warn!("Market scan failed: {}", e),
+            }
+        }
+    }
+
+    /// Filter, rank and diff a catalog snapshot against the current universe
+    fn select(
+        &self,
+        markets: Vec<CatalogMarket>,
+        quality: &HashMap<MarketId, MarketQuality>,
+        now: DateTime<Utc>,
+    ) -> UniverseUpdate {
+        let mut ranked: Vec<(f64, CatalogMarket)> = markets
+            .iter()
+            .filter(|m| self.filters.accepts(m, now))
+            .map(|m| (self.weights.score_observed(m, quality.get(&m.market_id)), m.clone()))
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| a.1.market_id.as_str().cmp(b.1.market_id.as_str()))
+        });
+        ranked.truncate(self.filters.max_markets);
+
+        let universe: Vec<RankedMarket> = ranked
+            .into_iter()
+            .enumerate()
+            .map(|(i, (score, market))| RankedMarket {
+                rank: i + 1,
+                score,
+                market,
+            })
+            .collect();
+
+        let selected: Vec<&MarketId> = universe.iter().map(|m| &m.market.market_id).collect();
+        let added = selected
+            .iter()
+            .copied()
+            .filter(|id| !self.current.contains(id))
+            .cloned()
+            .collect();
+
+        let listed: HashMap<&MarketId, &CatalogMarket> = markets.iter().map(|m| (&m.market_id, m)).collect();
+        let retired = self
+            .current
+            .iter()
+            .filter(|id| !selected.contains(id))
+            .map(|id| {
+                let reason = match listed.get(id) {
+                    None => RetireReason::Delisted,
+                    Some(market) if market.is_resolved(now) => RetireReason::Resolved,
+                    Some(_) => RetireReason::Filtered,
+                };
+                (id.clone(), reason)
+            })
+            .collect();
+
+        UniverseUpdate {
+            scanned_at: now,
+            universe,
+            added,
+            retired,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct StaticCatalog(Arc<Mutex<Vec<CatalogMarket>>>);
+
+    #[async_trait]
+    impl MarketCatalog for StaticCatalog {
+        async fn fetch_markets(&self) -> ExecResult<Vec<CatalogMarket>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+    }
+
+    fn market(id: &str, liquidity: f64, volume: f64, spread: f64, category: &str) -> CatalogMarket {
+        CatalogMarket {
+            market_id: MarketId::new(id),
+            question: format!("{}?", id),
+            category: Some(category.to_string()),
+            end_date: Some(Utc::now() + chrono::Duration::days(30)),
+            active: true,
+            closed: false,
+            liquidity_usd: liquidity,
+            volume_24h_usd: volume,
+            best_bid: Some(0.50 - spread / 2.0),
+            best_ask: Some(0.50 + spread / 2.0),
+            token_ids: vec![format!("{}-yes", id), format!("{}-no", id)],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_filters_ranks_and_diffs() {
+        let catalog = Arc::new(Mutex::new(vec![
+            market("deep", 50_000.0, 100_000.0, 0.01, "Politics"),
+            market("mid", 10_000.0, 5_000.0, 0.02, "Politics"),
+            market("thin", 100.0, 50.0, 0.01, "Politics"),
+            market("wide", 50_000.0, 100_000.0, 0.20, "Politics"),
+            market("sports", 50_000.0, 100_000.0, 0.01, "Sports"),
+        ]));
+        let filters = ScannerFilters {
+            min_liquidity_usd: 1_000.0,
+            max_spread: Some(0.05),
+            exclude_categories: vec!["sports".to_string()],
+            ..Default::default()
+        };
+        let mut scanner = MarketScanner::new(Box::new(StaticCatalog(catalog.clone())), filters);
+
+        let update = scanner.scan().await.unwrap();
+        assert_eq!(update.market_ids(), vec![MarketId::new("deep"), MarketId::new("mid")]);
+        assert_eq!(update.universe[0].rank, 1);
+        assert_eq!(update.added.len(), 2);
+        assert!(update.retired.is_empty());
+
+        // "deep" resolves, "mid" is delisted, a new market appears
+        {
+            let mut markets = catalog.lock().unwrap();
+            markets[0].closed = true;
+            markets.remove(1);
+            markets.push(market("new", 20_000.0, 20_000.0, 0.01, "Crypto"));
+        }
+        let update = scanner.scan().await.unwrap();
+        assert_eq!(update.added, vec![MarketId::new("new")]);
+        assert!(update.retired.contains(&(MarketId::new("deep"), RetireReason::Resolved)));
+        assert!(update.retired.contains(&(MarketId::new("mid"), RetireReason::Delisted)));
+        assert_eq!(scanner.universe(), &[MarketId::new("new")]);
+
+        // An unchanged catalog yields no membership changes
+        assert!(!scanner.scan().await.unwrap().is_changed());
+    }
+
+    #[tokio::test]
+    async fn test_scan_ranks_by_observed_quality() {
+        use crate::oms::BookDepth;
+
+        let catalog = Arc::new(Mutex::new(vec![
+            market("a", 10_000.0, 10_000.0, 0.01, "Politics"),
+            market("b", 10_000.0, 10_000.0, 0.01, "Politics"),
+        ]));
+        let recorder = Arc::new(RwLock::new(MarketQualityRecorder::default()));
+        {
+            let mut recorder = recorder.write().await;
+            let book = |spread: f64| BookDepth {
+                bids: vec![(0.50 - spread / 2.0, 100.0)],
+                asks: vec![(0.50 + spread / 2.0, 100.0)],
+            };
+            recorder.on_book(&MarketId::new("a"), &book(0.10), Utc::now());
+            recorder.on_book(&MarketId::new("b"), &book(0.02), Utc::now());
+        }
+
+        let mut scanner = MarketScanner::new(Box::new(StaticCatalog(catalog)), ScannerFilters::default())
+            .with_market_quality(recorder);
+        let update = scanner.scan().await.unwrap();
+        assert_eq!(update.market_ids(), vec![MarketId::new("b"), MarketId::new("a")]);
+    }
+
+    #[test]
+    fn test_gamma_market_parsing() {
+        let json = r#"[{
+            "conditionId": "0xabc",
+            "question": "Will it rain?",
+            "category": "Weather",
+            "endDate": "2030-01-01T00:00:00Z",
+            "active": true,
+            "closed": false,
+            "liquidityNum": 1234.5,
+            "volume24hr": 99.0,
+            "bestBid": 0.41,
+            "bestAsk": 0.43,
+            "clobTokenIds": "[\"111\", \"222\"]"
+        }]"#;
+
+        let markets: Vec<GammaMarket> = serde_json::from_str(json).unwrap();
+        let market = CatalogMarket::from(markets.into_iter().next().unwrap());
+        assert_eq!(market.market_id, MarketId::new("0xabc"));
+        assert_eq!(market.token_ids, vec!["111".to_string(), "222".to_string()]);
+        assert!((market.spread().unwrap() - 0.02).abs() < 1e-9);
+        assert_eq!(market.liquidity_usd, 1234.5);
+    }
+}
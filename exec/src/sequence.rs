@@ -0,0 +1,227 @@
+//! Market data sequence tracking
+//!
+//! The [`SequenceTracker`] follows the sequence number (when the feed
+//! provides one) and timestamp of every message per topic (usually a
+//! market) and classifies each message as in order, a gap, a duplicate or
+//! out of order. Without sequence numbers, gaps are inferred from silence
+//! longer than `max_time_gap_ms`. Counters per topic are kept so dropped
+//! messages show up in metrics instead of leaving books silently stale.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Tracker settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SequenceConfig {
+    /// Treat silence longer than this as a gap (unset = sequence numbers only)
+    pub max_time_gap_ms: Option<u64>,
+
+    /// Discard duplicate and out-of-order messages instead of applying them
+    pub drop_out_of_order: bool,
+
+    /// Resynchronize the book (e.g. request a snapshot) after a gap
+    pub resync_on_gap: bool,
+}
+
+impl Default for SequenceConfig {
+    fn default() -> Self {
+        Self {
+            max_time_gap_ms: None,
+            drop_out_of_order: true,
+            resync_on_gap: false,
+        }
+    }
+}
+
+/// Classification of one message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceEvent {
+    /// First message seen for the topic
+    First,
+    /// Next expected message
+    InOrder,
+    /// Sequence numbers were skipped (`missing` messages lost)
+    Gap { expected: u64, received: u64, missing: u64 },
+    /// No message for longer than the configured maximum
+    TimeGap { silence: Duration },
+    /// Same sequence number (or timestamp) as an earlier message
+    Duplicate,
+    /// Older than the latest message
+    OutOfOrder,
+}
+
+impl SequenceEvent {
+    /// Whether messages may have been lost before this one
+    pub fn is_gap(&self) -> bool {
+        matches!(self, SequenceEvent::Gap { .. } | SequenceEvent::TimeGap { .. })
+    }
+
+    /// Whether the message is stale and should not be applied
+    pub fn is_stale(&self) -> bool {
+        matches!(self, SequenceEvent::Duplicate | SequenceEvent::OutOfOrder)
+    }
+}
+
+/// Counters for one topic
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SequenceStats {
+    /// Messages observed
+    pub received: u64,
+    /// Sequence and time gaps detected
+    pub gaps: u64,
+    /// Messages known to be missing (sequence gaps only)
+    pub missing: u64,
+    /// Duplicate messages
+    pub duplicates: u64,
+    /// Out-of-order messages
+    pub out_of_order: u64,
+    /// Highest sequence number seen
+    pub last_sequence: Option<u64>,
+    /// Latest message timestamp
+    pub last_timestamp: Option<DateTime<Utc>>,
+}
+
+impl SequenceStats {
+    /// Counters as `(metric name, value)` pairs
+    pub fn metric_values(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("market_data.messages", self.received as f64),
+            ("market_data.gaps", self.gaps as f64),
+            ("market_data.missing_messages", self.missing as f64),
+            ("market_data.duplicates", self.duplicates as f64),
+            ("market_data.out_of_order", self.out_of_order as f64),
+        ]
+    }
+}
+
+/// Per-topic sequence and timestamp tracker
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    config: SequenceConfig,
+    topics: HashMap<String, SequenceStats>,
+}
+
+impl SequenceTracker {
+    /// Create a tracker
+    pub fn new(config: SequenceConfig) -> Self {
+        Self {
+            config,
+            topics: HashMap::new(),
+        }
+    }
+
+    /// Tracker settings
+    pub fn config(&self) -> &SequenceConfig {
+        &self.config
+    }
+
+    /// Observe a message and classify it
+    pub fn observe(&mut self, topic: &str, sequence: Option<u64>, timestamp: DateTime<Utc>) -> SequenceEvent {
+        let max_gap = self.config.max_time_gap_ms.map(|ms| Duration::milliseconds(ms as i64));
+        let stats = self.topics.entry(topic.to_string()).or_default();
+        stats.received += 1;
+
+        let event = match (sequence, stats.last_sequence, stats.last_timestamp) {
+            (_, None, None) => SequenceEvent::First,
+            (Some(seq), Some(last), _) if seq == last => SequenceEvent::Duplicate,
+            (Some(seq), Some(last), _) if seq < last => SequenceEvent::OutOfOrder,
+            (Some(seq), Some(last), _) if seq > last + 1 => SequenceEvent::Gap {
+                expected: last + 1,
+                received: seq,
+                missing: seq - last - 1,
+            },
+            (Some(_), Some(_), _) => SequenceEvent::InOrder,
+            (_, _, Some(last_ts)) if timestamp < last_ts => SequenceEvent::OutOfOrder,
+            (_, _, Some(last_ts)) => match max_gap {
+                Some(max_gap) if timestamp - last_ts > max_gap => SequenceEvent::TimeGap {
+                    silence: timestamp - last_ts,
+                },
+                _ => SequenceEvent::InOrder,
+            },
+            // Sequence numbers seen before but not on this message
+            (None, Some(_), None) => SequenceEvent::InOrder,
+        };
+
+        match event {
+            SequenceEvent::Gap { missing, .. } => {
+                stats.gaps += 1;
+                stats.missing += missing;
+            }
+            SequenceEvent::TimeGap { .. } => stats.gaps += 1,
+            SequenceEvent::Duplicate => stats.duplicates += 1,
+            SequenceEvent::OutOfOrder => stats.out_of_order += 1,
+            SequenceEvent::First | SequenceEvent::InOrder => {}
+        }
+        if !event.is_stale() {
+            if let Some(seq) = sequence {
+                stats.last_sequence = Some(seq);
+            }
+            stats.last_timestamp = Some(timestamp);
+        }
+        event
+    }
+
+    /// Counters for one topic
+    pub fn stats(&self, topic: &str) -> Option<&SequenceStats> {
+        self.topics.get(topic)
+    }
+
+    /// Counters for every topic
+    pub fn all_stats(&self) -> &HashMap<String, SequenceStats> {
+        &self.topics
+    }
+
+    /// Forget a topic (e.g. after unsubscribing or a resync)
+    pub fn reset(&mut self, topic: &str) -> Option<SequenceStats> {
+        self.topics.remove(topic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_gaps() {
+        let now = Utc::now();
+        let mut tracker = SequenceTracker::new(SequenceConfig::default());
+
+        assert_eq!(tracker.observe("m1", Some(1), now), SequenceEvent::First);
+        assert_eq!(tracker.observe("m1", Some(2), now), SequenceEvent::InOrder);
+        assert_eq!(
+            tracker.observe("m1", Some(6), now),
+            SequenceEvent::Gap {
+                expected: 3,
+                received: 6,
+                missing: 3
+            }
+        );
+        assert_eq!(tracker.observe("m1", Some(6), now), SequenceEvent::Duplicate);
+        assert_eq!(tracker.observe("m1", Some(4), now), SequenceEvent::OutOfOrder);
+        assert_eq!(tracker.observe("m1", Some(7), now), SequenceEvent::InOrder);
+
+        let stats = tracker.stats("m1").unwrap();
+        assert_eq!(stats.received, 6);
+        assert_eq!((stats.gaps, stats.missing), (1, 3));
+        assert_eq!((stats.duplicates, stats.out_of_order), (1, 1));
+        assert_eq!(stats.last_sequence, Some(7));
+    }
+
+    #[test]
+    fn test_timestamp_tracking() {
+        let now = Utc::now();
+        let mut tracker = SequenceTracker::new(SequenceConfig {
+            max_time_gap_ms: Some(5_000),
+            ..SequenceConfig::default()
+        });
+
+        tracker.observe("m1", None, now);
+        assert_eq!(tracker.observe("m1", None, now + Duration::seconds(1)), SequenceEvent::InOrder);
+        assert_eq!(tracker.observe("m1", None, now), SequenceEvent::OutOfOrder);
+        assert!(tracker.observe("m1", None, now + Duration::seconds(10)).is_gap());
+        assert_eq!(tracker.stats("m1").unwrap().gaps, 1);
+    }
+}
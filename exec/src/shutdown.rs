@@ -0,0 +1,166 @@
+//! Shutdown verification
+//!
+//! Cancelling orders on shutdown is fire-and-forget: a cancel can be lost,
+//! rejected or race a fill, and an order the OMS never heard of (placed by a
+//! crashed predecessor, say) is never cancelled at all. [`verify_shutdown`]
+//! asks every venue for the account's open orders after the cancels went
+//! out, cancels whatever survived and asks again, up to `max_attempts`
+//! times. The resulting [`ShutdownReport`] lists orders still open at each
+//! venue and the positions left behind.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::engine::ExecutionEngine;
+use crate::liquidation::FLAT_EPSILON;
+use crate::order::{MarketId, Order, VenueId};
+
+/// Shutdown verification settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownCheckConfig {
+    /// Venue queries before giving up on surviving orders
+    pub max_attempts: u32,
+
+    /// Wait between cancelling survivors and querying again
+    pub retry_delay_ms: u64,
+}
+
+impl Default for ShutdownCheckConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_delay_ms: 1_000,
+        }
+    }
+}
+
+impl ShutdownCheckConfig {
+    /// Wait between attempts
+    pub fn retry_delay(&self) -> Duration {
+        Duration::from_millis(self.retry_delay_ms)
+    }
+}
+
+/// Shutdown state of one venue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VenueShutdown {
+    pub venue: VenueId,
+    /// Orders still open at the venue after the last attempt
+    pub residual_orders: Vec<Order>,
+    /// Cancels sent for surviving orders
+    pub cancels_sent: usize,
+    /// Last error querying or cancelling at the venue
+    pub error: Option<String>,
+}
+
+/// Outcome of shutdown verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Venue queries made
+    pub attempts: u32,
+    /// Per-venue results, sorted by venue
+    pub venues: Vec<VenueShutdown>,
+    /// Non-flat positions left behind
+    pub residual_positions: HashMap<MarketId, f64>,
+}
+
+impl ShutdownReport {
+    /// Whether every venue confirmed zero open orders
+    pub fn is_clean(&self) -> bool {
+        self.venues.iter().all(|v| v.residual_orders.is_empty() && v.error.is_none())
+    }
+
+    /// Orders still open across all venues
+    pub fn residual_order_count(&self) -> usize {
+        self.venues.iter().map(|v| v.residual_orders.len()).sum()
+    }
+}
+
+/// Confirm every venue shows zero open orders, cancelling survivors
+///
+/// Surviving orders the OMS still considers open are cancelled through the
+/// engine (so their local state follows); unknown or locally closed ones
+/// directly at the venue.
+pub async fn verify_shutdown(engine: &ExecutionEngine, config: &ShutdownCheckConfig) -> ShutdownReport {
+    let started_at = Utc::now();
+    let mut adapters: Vec<_> = engine.adapters().iter().collect();
+    adapters.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+
+    let mut venues: Vec<VenueShutdown> = adapters
+        .iter()
+        .map(|(venue, _)| VenueShutdown {
+            venue: (*venue).clone(),
+            residual_orders: Vec::new(),
+            cancels_sent: 0,
+            error: None,
+        })
+        .collect();
+
+    let max_attempts = config.max_attempts.max(1);
+    let mut attempts = 0;
+    while attempts < max_attempts {
+        attempts += 1;
+        for ((_, adapter), status) in adapters.iter().zip(venues.iter_mut()) {
+            let result = adapter.lock().await.get_open_orders().await;
+            match result {
+                Ok(open_orders) => {
+                    status.residual_orders = open_orders;
+                    status.error = None;
+                }
+                Err(e) => {
+                    warn!("Shutdown check of {} failed: {}", status.venue, e);
+                    status.error = Some(e.to_string());
+                }
+            }
+        }
+
+        let pending = venues.iter().any(|v| !v.residual_orders.is_empty() || v.error.is_some());
+        if !pending || attempts == max_attempts {
+            break;
+        }
+
+        for ((_, adapter), status) in adapters.iter().zip(venues.iter_mut()) {
+            for order in &status.residual_orders {
+                warn!("Order {} survived shutdown on {}, cancelling again", order.id, status.venue);
+                let open_locally = engine.get_order(&order.id).map(|o| !o.is_terminal()).unwrap_or(false);
+                let result = if open_locally {
+                    engine.cancel_order(order.id).await.map(|_| ())
+                } else {
+                    adapter.lock().await.cancel_order(&order.id).await.map(|_| ())
+                };
+                status.cancels_sent += 1;
+                if let Err(e) = result {
+                    status.error = Some(e.to_string());
+                }
+            }
+        }
+        tokio::time::sleep(config.retry_delay()).await;
+    }
+
+    let residual_positions = engine
+        .get_all_positions()
+        .await
+        .into_iter()
+        .filter(|(_, size)| size.abs() > FLAT_EPSILON)
+        .map(|(market, size)| (MarketId::new(market), size))
+        .collect();
+
+    let report = ShutdownReport {
+        started_at,
+        completed_at: Utc::now(),
+        attempts,
+        venues,
+        residual_positions,
+    };
+    if report.is_clean() {
+        info!("Shutdown verified: no open orders at {} venues", report.venues.len());
+    }
+    report
+}
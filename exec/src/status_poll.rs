@@ -0,0 +1,348 @@
+//! Order status polling for venues without streaming updates
+//!
+//! When a venue (or session) has no websocket order updates, working orders
+//! are polled with `get_order_status` instead. Polling every order at a fixed
+//! rate either wastes the venue's rate limit or detects fills late, so the
+//! [`StatusPoller`] adapts each order's interval:
+//!
+//! - Orders near an expected fill (limit price within `near_fill_bps` of the
+//!   opposite touch, partially filled orders and market orders) are polled
+//!   every `min_interval_ms`
+//! - Other orders back off by `backoff_factor` after each unchanged poll, up
+//!   to `max_interval_ms`, and drop back to the minimum when their status
+//!   changes
+//!
+//! Polls per venue are capped at `budget_fraction` of its rate limit, most
+//! overdue orders first, so quoting and cancels keep their headroom.
+//! [`ExecutionEngine::poll_order_statuses`](crate::ExecutionEngine::poll_order_statuses)
+//! runs one round and publishes detected changes as [`OrderUpdate`]s.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::oms::post_only::Touch;
+use crate::order::{MarketId, Order, OrderId, OrderStatus, OrderType, Side, VenueId};
+
+fn default_tick_interval_ms() -> u64 {
+    250
+}
+
+fn default_min_interval_ms() -> u64 {
+    500
+}
+
+fn default_max_interval_ms() -> u64 {
+    15_000
+}
+
+fn default_backoff_factor() -> f64 {
+    2.0
+}
+
+fn default_near_fill_bps() -> f64 {
+    10.0
+}
+
+fn default_budget_fraction() -> f64 {
+    0.25
+}
+
+/// Status polling settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusPollConfig {
+    /// Venues to poll (empty = every registered venue)
+    #[serde(default)]
+    pub venues: Vec<VenueId>,
+
+    /// How often a polling round runs
+    #[serde(default = "default_tick_interval_ms")]
+    pub tick_interval_ms: u64,
+
+    /// Poll interval of orders near a fill (and after a status change)
+    #[serde(default = "default_min_interval_ms")]
+    pub min_interval_ms: u64,
+
+    /// Longest poll interval
+    #[serde(default = "default_max_interval_ms")]
+    pub max_interval_ms: u64,
+
+    /// Interval growth after an unchanged poll
+    #[serde(default = "default_backoff_factor")]
+    pub backoff_factor: f64,
+
+    /// Distance from the opposite touch, in bps of the limit price, within
+    /// which an order is expected to fill soon
+    #[serde(default = "default_near_fill_bps")]
+    pub near_fill_bps: f64,
+
+    /// Share of each venue's rate limit polling may use
+    #[serde(default = "default_budget_fraction")]
+    pub budget_fraction: f64,
+}
+
+impl Default for StatusPollConfig {
+    fn default() -> Self {
+        Self {
+            venues: Vec::new(),
+            tick_interval_ms: default_tick_interval_ms(),
+            min_interval_ms: default_min_interval_ms(),
+            max_interval_ms: default_max_interval_ms(),
+            backoff_factor: default_backoff_factor(),
+            near_fill_bps: default_near_fill_bps(),
+            budget_fraction: default_budget_fraction(),
+        }
+    }
+}
+
+impl StatusPollConfig {
+    /// How often a polling round runs
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_millis(self.tick_interval_ms)
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::from_millis(self.min_interval_ms)
+    }
+
+    fn max_interval(&self) -> Duration {
+        Duration::from_millis(self.max_interval_ms.max(self.min_interval_ms))
+    }
+}
+
+/// An order status change detected outside the submit/cancel path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderUpdate {
+    pub order_id: OrderId,
+    pub venue: VenueId,
+    pub market: MarketId,
+    pub strategy_id: Option<String>,
+    pub previous: OrderStatus,
+    pub status: OrderStatus,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl OrderUpdate {
+    pub(crate) fn new(order: &Order, status: OrderStatus) -> Self {
+        Self {
+            order_id: order.id,
+            venue: order.venue.clone(),
+            market: order.market.clone(),
+            strategy_id: order.strategy_id.clone(),
+            previous: order.status,
+            status,
+            detected_at: Utc::now(),
+        }
+    }
+}
+
+/// Whether an order is expected to fill soon
+pub fn near_fill(order: &Order, touch: Option<&Touch>, near_fill_bps: f64) -> bool {
+    if order.order_type == OrderType::Market || order.status == OrderStatus::PartiallyFilled {
+        return true;
+    }
+    let (Some(price), Some(touch)) = (order.price, touch) else {
+        return false;
+    };
+    let opposite = match order.side {
+        Side::Buy => touch.best_ask.map(|ask| ask - price),
+        Side::Sell => touch.best_bid.map(|bid| price - bid),
+    };
+    opposite.is_some_and(|distance| price > 0.0 && distance / price * 10_000.0 <= near_fill_bps)
+}
+
+#[derive(Debug, Clone)]
+struct PollState {
+    venue: VenueId,
+    interval: Duration,
+    next_due: Instant,
+}
+
+/// Token bucket limiting one venue's polls
+#[derive(Debug, Clone)]
+struct PollBudget {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Adaptive per-order poll schedule
+#[derive(Debug, Clone)]
+pub struct StatusPoller {
+    config: StatusPollConfig,
+    orders: HashMap<OrderId, PollState>,
+    budgets: HashMap<VenueId, PollBudget>,
+}
+
+impl StatusPoller {
+    pub fn new(config: StatusPollConfig) -> Self {
+        Self {
+            config,
+            orders: HashMap::new(),
+            budgets: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &StatusPollConfig {
+        &self.config
+    }
+
+    /// Whether orders on a venue are polled
+    pub fn polls_venue(&self, venue: &VenueId) -> bool {
+        self.config.venues.is_empty() || self.config.venues.contains(venue)
+    }
+
+    /// Number of scheduled orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Current poll interval of an order
+    pub fn interval(&self, order_id: &OrderId) -> Option<Duration> {
+        self.orders.get(order_id).map(|s| s.interval)
+    }
+
+    /// Track exactly the given working orders: new ones are scheduled one
+    /// minimum interval out, orders no longer working are dropped
+    pub fn sync(&mut self, working: &[(OrderId, VenueId)], now: Instant) {
+        let ids: HashSet<&OrderId> = working.iter().map(|(id, _)| id).collect();
+        self.orders.retain(|id, _| ids.contains(id));
+        let min_interval = self.config.min_interval();
+        for (order_id, venue) in working {
+            self.orders.entry(*order_id).or_insert_with(|| PollState {
+                venue: venue.clone(),
+                interval: min_interval,
+                next_due: now + min_interval,
+            });
+        }
+    }
+
+    /// Orders to poll now, most overdue first, within each venue's budget of
+    /// `budget_fraction * requests_per_second` polls per second
+    pub fn due(&mut self, now: Instant, requests_per_second: &HashMap<VenueId, u32>) -> Vec<OrderId> {
+        let mut due: Vec<(&OrderId, &PollState)> = self.orders.iter().filter(|(_, s)| s.next_due <= now).collect();
+        due.sort_by_key(|(_, s)| s.next_due);
+
+        let mut selected = Vec::new();
+        for (order_id, state) in due {
+            let rate = requests_per_second.get(&state.venue).copied().unwrap_or(1) as f64 * self.config.budget_fraction;
+            let capacity = rate.max(1.0);
+            let budget = self.budgets.entry(state.venue.clone()).or_insert(PollBudget {
+                tokens: capacity,
+                refilled_at: now,
+            });
+            let elapsed = now.saturating_duration_since(budget.refilled_at).as_secs_f64();
+            budget.tokens = (budget.tokens + elapsed * rate).min(capacity);
+            budget.refilled_at = now;
+            if budget.tokens >= 1.0 {
+                budget.tokens -= 1.0;
+                selected.push(*order_id);
+            }
+        }
+        selected
+    }
+
+    /// Reschedule an order after a poll
+    pub fn record(&mut self, order_id: &OrderId, changed: bool, near_fill: bool, now: Instant) {
+        let min_interval = self.config.min_interval();
+        let max_interval = self.config.max_interval();
+        let factor = self.config.backoff_factor.max(1.0);
+        if let Some(state) = self.orders.get_mut(order_id) {
+            state.interval = if changed || near_fill {
+                min_interval
+            } else {
+                state.interval.mul_f64(factor).min(max_interval)
+            };
+            state.next_due = now + state.interval;
+        }
+    }
+
+    /// Give back the budget of a poll that could not be sent
+    pub fn refund(&mut self, order_id: &OrderId) {
+        if let Some(budget) = self.orders.get(order_id).and_then(|s| self.budgets.get_mut(&s.venue)) {
+            budget.tokens += 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn venue() -> VenueId {
+        VenueId::new("polymarket")
+    }
+
+    #[test]
+    fn test_adaptive_intervals() {
+        let mut poller = StatusPoller::new(StatusPollConfig::default());
+        let start = Instant::now();
+        let order = OrderId::new();
+        poller.sync(&[(order, venue())], start);
+        assert!(poller.due(start, &HashMap::new()).is_empty());
+
+        // Unchanged polls back off: 0.5s, 1s, 2s, ... capped at 15s
+        let mut now = start + Duration::from_millis(500);
+        let mut intervals = Vec::new();
+        for _ in 0..7 {
+            now += Duration::from_secs(60);
+            assert_eq!(poller.due(now, &HashMap::from([(venue(), 100)])), vec![order]);
+            poller.record(&order, false, false, now);
+            intervals.push(poller.interval(&order).unwrap().as_millis());
+        }
+        assert_eq!(intervals, vec![1000, 2000, 4000, 8000, 15000, 15000, 15000]);
+
+        // A change or a nearby touch resets to the minimum
+        poller.record(&order, true, false, now);
+        assert_eq!(poller.interval(&order), Some(Duration::from_millis(500)));
+        poller.record(&order, false, false, now);
+        poller.record(&order, false, true, now);
+        assert_eq!(poller.interval(&order), Some(Duration::from_millis(500)));
+
+        // Orders no longer working are dropped
+        poller.sync(&[], now);
+        assert!(poller.is_empty());
+    }
+
+    #[test]
+    fn test_budget_limits_polls() {
+        let mut poller = StatusPoller::new(StatusPollConfig::default());
+        let start = Instant::now();
+        let orders: Vec<(OrderId, VenueId)> = (0..10).map(|_| (OrderId::new(), venue())).collect();
+        poller.sync(&orders, start);
+
+        // 8 req/s * 0.25 = 2 polls per second
+        let rates = HashMap::from([(venue(), 8)]);
+        let now = start + Duration::from_secs(1);
+        assert_eq!(poller.due(now, &rates).len(), 2);
+        assert_eq!(poller.due(now, &rates).len(), 0);
+        assert_eq!(poller.due(now + Duration::from_millis(500), &rates).len(), 1);
+    }
+
+    #[test]
+    fn test_near_fill() {
+        let mut order = Order::new(
+            venue(),
+            MarketId::new("m"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.50),
+            10.0,
+            crate::order::TimeInForce::GTC,
+            "c1".to_string(),
+        );
+        let touch = |bid: f64, ask: f64| Touch { best_bid: Some(bid), best_ask: Some(ask) };
+
+        assert!(near_fill(&order, Some(&touch(0.49, 0.5004)), 10.0));
+        assert!(!near_fill(&order, Some(&touch(0.49, 0.51)), 10.0));
+        assert!(!near_fill(&order, None, 10.0));
+        order.status = OrderStatus::PartiallyFilled;
+        assert!(near_fill(&order, None, 10.0));
+    }
+}
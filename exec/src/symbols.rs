@@ -0,0 +1,273 @@
+//! Per-venue symbol mapping
+//!
+//! Strategies, positions and storage use a canonical internal market ID
+//! (e.g. `trump-2028-yes`). Each venue identifies the same instrument
+//! differently: Polymarket by condition and token ID, a CEX by its symbol.
+//! The [`SymbolMapper`] translates between the two in both directions so
+//! cross-venue strategies never hard-code venue IDs.
+//!
+//! The execution engine translates canonical markets to venue symbols just
+//! before calling the adapter; tracked orders, fills and positions keep the
+//! canonical ID. Markets without a mapping pass through unchanged.
+//!
+//! With a cache path, mappings are written to a JSON file on every change
+//! and loaded on startup.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::error::{ExecError, ExecResult};
+use crate::order::{MarketId, VenueId};
+
+/// A market's identity on one venue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueSymbol {
+    /// ID the venue's order API expects (Polymarket token ID, CEX symbol)
+    pub symbol: String,
+
+    /// Venue-level parent market, if any (Polymarket condition ID)
+    #[serde(default)]
+    pub venue_market: Option<String>,
+}
+
+impl VenueSymbol {
+    /// Create a symbol without a parent market
+    pub fn new(symbol: impl Into<String>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            venue_market: None,
+        }
+    }
+
+    /// Polymarket outcome token within a condition
+    pub fn polymarket(condition_id: impl Into<String>, token_id: impl Into<String>) -> Self {
+        Self {
+            symbol: token_id.into(),
+            venue_market: Some(condition_id.into()),
+        }
+    }
+}
+
+/// All venue symbols for one canonical market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketMapping {
+    /// Canonical internal market ID
+    pub canonical: MarketId,
+
+    /// Venue symbols keyed by venue
+    pub venues: HashMap<VenueId, VenueSymbol>,
+}
+
+/// Bidirectional canonical <-> venue symbol map
+#[derive(Debug, Default)]
+pub struct SymbolMapper {
+    mappings: HashMap<MarketId, MarketMapping>,
+    reverse: HashMap<(VenueId, String), MarketId>,
+    cache_path: Option<PathBuf>,
+}
+
+impl SymbolMapper {
+    /// Create an empty in-memory mapper
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a mapper backed by an on-disk cache, loading existing mappings
+    pub fn with_cache(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut mapper = Self {
+            cache_path: Some(path.clone()),
+            ..Self::default()
+        };
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let mappings: Vec<MarketMapping> = serde_json::from_str(&content)?;
+            for mapping in mappings {
+                for (venue, symbol) in mapping.venues {
+                    mapper.insert_entry(mapping.canonical.clone(), venue, symbol)?;
+                }
+            }
+            info!("Loaded {} market mappings from {}", mapper.len(), path.display());
+        }
+
+        Ok(mapper)
+    }
+
+    /// Map a canonical market to a venue symbol
+    ///
+    /// Replaces any previous symbol for the same market and venue. Fails if
+    /// the venue symbol is already mapped to a different canonical market.
+    pub fn insert(&mut self, canonical: MarketId, venue: VenueId, symbol: VenueSymbol) -> ExecResult<()> {
+        self.insert_entry(canonical, venue, symbol)?;
+        self.save()
+    }
+
+    /// Remove a canonical market and all of its venue symbols
+    pub fn remove(&mut self, canonical: &MarketId) -> ExecResult<Option<MarketMapping>> {
+        let removed = self.mappings.remove(canonical);
+        if let Some(mapping) = &removed {
+            for (venue, symbol) in &mapping.venues {
+                self.reverse.remove(&(venue.clone(), symbol.symbol.clone()));
+            }
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// Venue symbol for a canonical market
+    pub fn to_venue(&self, canonical: &MarketId, venue: &VenueId) -> Option<&VenueSymbol> {
+        self.mappings.get(canonical)?.venues.get(venue)
+    }
+
+    /// Canonical market for a venue symbol
+    pub fn to_canonical(&self, venue: &VenueId, symbol: &str) -> Option<&MarketId> {
+        self.reverse.get(&(venue.clone(), symbol.to_string()))
+    }
+
+    /// Venue symbol for a canonical market, or the canonical ID itself when
+    /// no mapping exists
+    pub fn resolve(&self, canonical: &MarketId, venue: &VenueId) -> MarketId {
+        self.to_venue(canonical, venue)
+            .map(|s| MarketId::new(s.symbol.clone()))
+            .unwrap_or_else(|| canonical.clone())
+    }
+
+    /// Canonical market for a venue symbol, or the symbol itself when no
+    /// mapping exists
+    pub fn resolve_canonical(&self, venue: &VenueId, symbol: &str) -> MarketId {
+        self.to_canonical(venue, symbol)
+            .cloned()
+            .unwrap_or_else(|| MarketId::new(symbol))
+    }
+
+    /// Full mapping for a canonical market
+    pub fn get(&self, canonical: &MarketId) -> Option<&MarketMapping> {
+        self.mappings.get(canonical)
+    }
+
+    /// Canonical markets listed on a venue
+    pub fn markets_on(&self, venue: &VenueId) -> Vec<MarketId> {
+        let mut markets: Vec<MarketId> = self
+            .mappings
+            .values()
+            .filter(|m| m.venues.contains_key(venue))
+            .map(|m| m.canonical.clone())
+            .collect();
+        markets.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        markets
+    }
+
+    /// Number of canonical markets
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    /// Whether no markets are mapped
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    fn insert_entry(&mut self, canonical: MarketId, venue: VenueId, symbol: VenueSymbol) -> ExecResult<()> {
+        let key = (venue.clone(), symbol.symbol.clone());
+        if let Some(existing) = self.reverse.get(&key) {
+            if *existing != canonical {
+                return Err(ExecError::ConfigError(format!(
+                    "{} symbol {} already mapped to {}",
+                    venue, symbol.symbol, existing
+                )));
+            }
+        }
+
+        debug!("Mapping {} -> {}:{}", canonical, venue, symbol.symbol);
+        let mapping = self
+            .mappings
+            .entry(canonical.clone())
+            .or_insert_with(|| MarketMapping {
+                canonical: canonical.clone(),
+                venues: HashMap::new(),
+            });
+        if let Some(previous) = mapping.venues.insert(venue.clone(), symbol) {
+            self.reverse.remove(&(venue, previous.symbol));
+        }
+        self.reverse.insert(key, canonical);
+        Ok(())
+    }
+
+    /// Write mappings to the cache (atomic replace)
+    fn save(&self) -> ExecResult<()> {
+        let Some(path) = &self.cache_path else {
+            return Ok(());
+        };
+
+        let mut mappings: Vec<&MarketMapping> = self.mappings.values().collect();
+        mappings.sort_by(|a, b| a.canonical.as_str().cmp(b.canonical.as_str()));
+
+        let json = serde_json::to_string_pretty(&mappings)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderId;
+
+    #[test]
+    fn test_bidirectional_mapping() {
+        let mut mapper = SymbolMapper::new();
+        let btc = MarketId::new("btc-100k-2025");
+        let polymarket = VenueId::new("polymarket");
+        let binance = VenueId::new("binance");
+
+        mapper
+            .insert(btc.clone(), polymarket.clone(), VenueSymbol::polymarket("0xcond", "7123"))
+            .unwrap();
+        mapper.insert(btc.clone(), binance.clone(), VenueSymbol::new("BTCUSDT")).unwrap();
+
+        assert_eq!(mapper.to_venue(&btc, &polymarket).unwrap().symbol, "7123");
+        assert_eq!(mapper.to_canonical(&binance, "BTCUSDT"), Some(&btc));
+        assert_eq!(mapper.resolve(&btc, &binance), MarketId::new("BTCUSDT"));
+        assert_eq!(mapper.resolve(&MarketId::new("raw"), &binance), MarketId::new("raw"));
+        assert_eq!(mapper.markets_on(&polymarket), vec![btc.clone()]);
+
+        // A venue symbol can't belong to two canonical markets
+        assert!(mapper
+            .insert(MarketId::new("other"), binance.clone(), VenueSymbol::new("BTCUSDT"))
+            .is_err());
+
+        // Remapping replaces the reverse entry
+        mapper.insert(btc.clone(), binance.clone(), VenueSymbol::new("BTCUSDC")).unwrap();
+        assert!(mapper.to_canonical(&binance, "BTCUSDT").is_none());
+
+        mapper.remove(&btc).unwrap();
+        assert!(mapper.to_canonical(&binance, "BTCUSDC").is_none());
+        assert!(mapper.is_empty());
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let path = std::env::temp_dir().join(format!("ag-exec-symbols-{}.json", OrderId::new()));
+        let market = MarketId::new("eth-etf-approved");
+
+        {
+            let mut mapper = SymbolMapper::with_cache(&path).unwrap();
+            mapper
+                .insert(market.clone(), VenueId::new("polymarket"), VenueSymbol::polymarket("0xabc", "42"))
+                .unwrap();
+        }
+
+        let mapper = SymbolMapper::with_cache(&path).unwrap();
+        let symbol = mapper.to_venue(&market, &VenueId::new("polymarket")).unwrap();
+        assert_eq!(symbol.venue_market.as_deref(), Some("0xabc"));
+        assert_eq!(mapper.to_canonical(&VenueId::new("polymarket"), "42"), Some(&market));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
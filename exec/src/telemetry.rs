@@ -0,0 +1,180 @@
+//! OpenTelemetry export (feature `otel`)
+//!
+//! This module installs an OTLP exporter for traces and metrics so the
+//! existing `tracing` spans and engine counters can be shipped to any
+//! OpenTelemetry-compatible backend (Jaeger, Tempo, Grafana Cloud, Datadog, ...)
+//! instead of, or in addition to, the custom monitor protocol.
+
+use std::time::Duration;
+
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::metrics::MeterProvider;
+use opentelemetry_sdk::{runtime, trace, Resource};
+
+use crate::error::{ExecError, ExecResult};
+use crate::order::{Order, VenueId};
+
+/// OTLP exporter configuration
+#[derive(Debug, Clone)]
+pub struct OtelConfig {
+    /// OTLP gRPC endpoint (e.g. "http://localhost:4317")
+    pub endpoint: String,
+    /// Value of the `service.name` resource attribute
+    pub service_name: String,
+    /// Value of the `service.namespace` resource attribute, separating
+    /// deployments that share a backend
+    pub service_namespace: Option<String>,
+    /// Additional resource attributes attached to every exported span and
+    /// metric (e.g. `deployment.environment`, `service.instance.id`)
+    pub attributes: Vec<(String, String)>,
+    /// Metric export interval
+    pub metrics_interval: Duration,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "ag-exec".to_string(),
+            service_namespace: None,
+            attributes: Vec::new(),
+            metrics_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Handle keeping the exporters alive; flushes and shuts them down on drop
+pub struct OtelGuard {
+    meter_provider: MeterProvider,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.meter_provider.shutdown() {
+            eprintln!("Failed to shut down OTel meter provider: {}", e);
+        }
+        global::shutdown_tracer_provider();
+    }
+}
+
+/// Initialize tracing with an OTLP exporter layer
+///
+/// Installs the same fmt + EnvFilter subscriber as [`crate::init_tracing`],
+/// plus a `tracing-opentelemetry` layer exporting spans, and registers a
+/// global OTLP meter provider used by [`ExecMetrics`].
+pub fn init_tracing_otel(config: &OtelConfig) -> ExecResult<OtelGuard> {
+    use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+    let mut attributes = vec![KeyValue::new("service.name", config.service_name.clone())];
+    if let Some(namespace) = &config.service_namespace {
+        attributes.push(KeyValue::new("service.namespace", namespace.clone()));
+    }
+    attributes.extend(
+        config
+            .attributes
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+    );
+    let resource = Resource::new(attributes);
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.endpoint.clone()),
+        )
+        .with_trace_config(trace::config().with_resource(resource.clone()))
+        .install_batch(runtime::Tokio)
+        .map_err(|e| ExecError::ConfigError(format!("Failed to install OTLP tracer: {}", e)))?;
+
+    let meter_provider = opentelemetry_otlp::new_pipeline()
+        .metrics(runtime::Tokio)
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.endpoint.clone()),
+        )
+        .with_resource(resource)
+        .with_period(config.metrics_interval)
+        .build()
+        .map_err(|e| ExecError::ConfigError(format!("Failed to build OTLP meter provider: {}", e)))?;
+
+    global::set_meter_provider(meter_provider.clone());
+
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(EnvFilter::from_default_env())
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+        .map_err(|e| ExecError::ConfigError(format!("Failed to install subscriber: {}", e)))?;
+
+    Ok(OtelGuard { meter_provider })
+}
+
+/// Execution engine counters exported through OpenTelemetry
+///
+/// Instruments are created from the global meter provider, so they are
+/// no-ops until [`init_tracing_otel`] (or another provider) is installed.
+pub struct ExecMetrics {
+    orders_submitted: Counter<u64>,
+    orders_rejected: Counter<u64>,
+    orders_cancelled: Counter<u64>,
+    fills: Counter<u64>,
+    fill_size: Histogram<f64>,
+}
+
+impl ExecMetrics {
+    /// Create instruments on the `ag-exec` meter
+    pub fn new() -> Self {
+        let meter = global::meter("ag-exec");
+        Self {
+            orders_submitted: meter.u64_counter("exec.orders_submitted").init(),
+            orders_rejected: meter.u64_counter("exec.orders_rejected").init(),
+            orders_cancelled: meter.u64_counter("exec.orders_cancelled").init(),
+            fills: meter.u64_counter("exec.fills").init(),
+            fill_size: meter.f64_histogram("exec.fill_size").init(),
+        }
+    }
+
+    /// Record a successfully submitted order
+    pub fn order_submitted(&self, order: &Order) {
+        self.orders_submitted.add(1, &order_attributes(order));
+    }
+
+    /// Record an order rejected before reaching the venue
+    pub fn order_rejected(&self, order: &Order, reason: &'static str) {
+        let mut attrs = order_attributes(order);
+        attrs.push(KeyValue::new("reason", reason));
+        self.orders_rejected.add(1, &attrs);
+    }
+
+    /// Record a successful cancel
+    pub fn order_cancelled(&self, venue: &VenueId) {
+        self.orders_cancelled
+            .add(1, &[KeyValue::new("venue", venue.to_string())]);
+    }
+
+    /// Record a fill against an order
+    pub fn fill(&self, order: &Order, size: f64) {
+        let attrs = order_attributes(order);
+        self.fills.add(1, &attrs);
+        self.fill_size.record(size, &attrs);
+    }
+}
+
+impl Default for ExecMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn order_attributes(order: &Order) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new("venue", order.venue.to_string()),
+        KeyValue::new("market", order.market.to_string()),
+        KeyValue::new("side", order.side.to_string()),
+    ]
+}
@@ -0,0 +1,267 @@
+//! Local trigger engine for stop orders
+//!
+//! Venues like Polymarket have no native stop orders. `StopMarket` and
+//! `StopLimit` orders are held here ("armed") and released as a `Market` /
+//! `Limit` child order once the market trades through the stop price:
+//!
+//! - Buy stops trigger when the price rises to or above `stop_price`
+//! - Sell stops trigger when the price falls to or below `stop_price`
+//!
+//! Child orders keep the parent's ID, client order ID and stop price, so
+//! the stop and its child are one order from the caller's point of view.
+//!
+//! With a store path, armed triggers are written to a JSON file on every
+//! change and reloaded on startup, so stops survive restarts.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::error::{ExecError, ExecResult};
+use crate::order::{MarketId, Order, OrderId, OrderStatus, OrderType, Side};
+
+/// A stop order waiting for its trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmedStop {
+    /// The stop order as submitted
+    pub order: Order,
+
+    /// When the stop was armed
+    pub armed_at: DateTime<Utc>,
+}
+
+impl ArmedStop {
+    /// Whether `price` crosses the stop price
+    pub fn is_triggered(&self, price: f64) -> bool {
+        match (self.order.side, self.order.stop_price) {
+            (Side::Buy, Some(stop)) => price >= stop,
+            (Side::Sell, Some(stop)) => price <= stop,
+            (_, None) => false,
+        }
+    }
+
+    /// Child order released when the stop triggers
+    pub fn child_order(&self) -> Order {
+        let mut child = self.order.clone();
+        child.order_type = match self.order.order_type {
+            OrderType::StopLimit => OrderType::Limit,
+            _ => OrderType::Market,
+        };
+        if child.order_type == OrderType::Market {
+            child.price = None;
+        }
+        child.update_status(OrderStatus::Pending);
+        child
+    }
+}
+
+/// Holds armed stop orders and releases them on price updates
+#[derive(Debug, Default)]
+pub struct TriggerEngine {
+    armed: HashMap<OrderId, ArmedStop>,
+    store_path: Option<PathBuf>,
+}
+
+impl TriggerEngine {
+    /// Create an in-memory trigger engine
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a trigger engine persisted to `path`, loading any triggers
+    /// armed before a restart
+    pub fn with_store(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let armed = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let stops: Vec<ArmedStop> = serde_json::from_str(&content)?;
+            stops.into_iter().map(|s| (s.order.id, s)).collect()
+        } else {
+            HashMap::new()
+        };
+
+        if !armed.is_empty() {
+            info!("Restored {} armed stop orders from {}", armed.len(), path.display());
+        }
+
+        Ok(Self {
+            armed,
+            store_path: Some(path),
+        })
+    }
+
+    /// Arm a stop order
+    pub fn arm(&mut self, order: Order) -> ExecResult<()> {
+        if !order.order_type.is_stop() {
+            return Err(ExecError::ValidationError(format!(
+                "Cannot arm {} order as a stop",
+                order.order_type
+            )));
+        }
+        if order.stop_price.is_none() {
+            return Err(ExecError::ValidationError(
+                "Stop order must have a stop price".to_string(),
+            ));
+        }
+
+        debug!("Arming stop {:?} at {:?}", order.id, order.stop_price);
+        self.armed.insert(
+            order.id,
+            ArmedStop {
+                order,
+                armed_at: Utc::now(),
+            },
+        );
+        self.persist()
+    }
+
+    /// Disarm a stop order; returns it if it was armed
+    pub fn disarm(&mut self, order_id: &OrderId) -> ExecResult<Option<Order>> {
+        let removed = self.armed.remove(order_id).map(|s| s.order);
+        if removed.is_some() {
+            self.persist()?;
+        }
+        Ok(removed)
+    }
+
+    /// Whether an order is armed
+    pub fn is_armed(&self, order_id: &OrderId) -> bool {
+        self.armed.contains_key(order_id)
+    }
+
+    /// All armed stops, oldest first
+    pub fn armed(&self) -> Vec<ArmedStop> {
+        let mut stops: Vec<ArmedStop> = self.armed.values().cloned().collect();
+        stops.sort_by_key(|s| s.armed_at);
+        stops
+    }
+
+    /// Number of armed stops
+    pub fn len(&self) -> usize {
+        self.armed.len()
+    }
+
+    /// Whether no stops are armed
+    pub fn is_empty(&self) -> bool {
+        self.armed.is_empty()
+    }
+
+    /// Apply a market price; returns child orders for triggered stops,
+    /// oldest first
+    pub fn on_price(&mut self, market: &MarketId, price: f64) -> ExecResult<Vec<Order>> {
+        let mut triggered: Vec<ArmedStop> = Vec::new();
+        self.armed.retain(|_, stop| {
+            if stop.order.market == *market && stop.is_triggered(price) {
+                triggered.push(stop.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        if triggered.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.persist()?;
+        triggered.sort_by_key(|s| s.armed_at);
+
+        for stop in &triggered {
+            info!(
+                "Stop {:?} triggered at {} (stop {:?}) on {}",
+                stop.order.id, price, stop.order.stop_price, market
+            );
+        }
+
+        Ok(triggered.iter().map(ArmedStop::child_order).collect())
+    }
+
+    /// Write armed stops to the store (atomic replace)
+    fn persist(&self) -> ExecResult<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+
+        let json = serde_json::to_string_pretty(&self.armed())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{TimeInForce, VenueId};
+
+    fn stop(side: Side, order_type: OrderType, price: Option<f64>, stop_price: f64) -> Order {
+        Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            side,
+            order_type,
+            price,
+            100.0,
+            TimeInForce::GTC,
+            "client-stop".to_string(),
+        )
+        .with_stop_price(stop_price)
+    }
+
+    #[test]
+    fn test_trigger_direction_and_children() {
+        let mut engine = TriggerEngine::new();
+        let buy = stop(Side::Buy, OrderType::StopMarket, None, 0.60);
+        let sell = stop(Side::Sell, OrderType::StopLimit, Some(0.39), 0.40);
+        engine.arm(buy.clone()).unwrap();
+        engine.arm(sell.clone()).unwrap();
+        assert_eq!(engine.len(), 2);
+
+        // Limit orders cannot be armed
+        let limit = Order { order_type: OrderType::Limit, ..buy.clone() };
+        assert!(engine.arm(limit).is_err());
+
+        // Nothing crosses; other markets are ignored
+        assert!(engine.on_price(&MarketId::new("0x123abc"), 0.50).unwrap().is_empty());
+        assert!(engine.on_price(&MarketId::new("other"), 0.90).unwrap().is_empty());
+
+        let children = engine.on_price(&MarketId::new("0x123abc"), 0.61).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, buy.id);
+        assert_eq!(children[0].order_type, OrderType::Market);
+        assert_eq!(children[0].price, None);
+
+        let children = engine.on_price(&MarketId::new("0x123abc"), 0.40).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].order_type, OrderType::Limit);
+        assert_eq!(children[0].price, Some(0.39));
+        assert!(engine.is_empty());
+    }
+
+    #[test]
+    fn test_persistence_across_restart() {
+        let path = std::env::temp_dir().join(format!("ag-exec-triggers-{}.json", OrderId::new()));
+
+        let order = stop(Side::Sell, OrderType::StopMarket, None, 0.45);
+        {
+            let mut engine = TriggerEngine::with_store(&path).unwrap();
+            engine.arm(order.clone()).unwrap();
+            engine.arm(stop(Side::Buy, OrderType::StopMarket, None, 0.55)).unwrap();
+            assert!(engine.disarm(&order.id).unwrap().is_some());
+        }
+
+        let mut restored = TriggerEngine::with_store(&path).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert!(!restored.is_armed(&order.id));
+        assert_eq!(restored.on_price(&MarketId::new("0x123abc"), 0.55).unwrap().len(), 1);
+
+        let reloaded = TriggerEngine::with_store(&path).unwrap();
+        assert!(reloaded.is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,366 @@
+//! Venue status and maintenance awareness
+//!
+//! Exchanges go into maintenance, shed load and fail partially. The
+//! [`VenueStatusMonitor`] tracks each venue's health from two signals:
+//!
+//! - **Health checks**: `VenueAdapter::health_check` polled every
+//!   `health_interval_ms`; a failed check degrades the venue and
+//!   `down_after_failed_checks` consecutive failures take it down
+//! - **Error rate**: the share of orders and cancels that failed at the
+//!   venue (network errors, timeouts, venue errors) over the last
+//!   `error_window_secs`, once at least `min_requests` were sent
+//!
+//! A venue gets worse at once but only recovers after looking better for
+//! `recovery_secs` without interruption, so a flapping venue stays paused.
+//! While a venue is [`VenueState::Degraded`] the engine only sends
+//! reduce-only orders to it; while it is [`VenueState::Down`] it sends no
+//! orders at all. Cancels are always attempted.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::order::VenueId;
+
+/// Venue status monitoring settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VenueStatusConfig {
+    /// How often venues' health endpoints are polled
+    pub health_interval_ms: u64,
+
+    /// Window the error rate is measured over, in seconds
+    pub error_window_secs: u64,
+
+    /// Requests in the window before the error rate counts
+    pub min_requests: usize,
+
+    /// Error rate at which a venue is degraded
+    pub degraded_error_rate: f64,
+
+    /// Error rate at which a venue is down
+    pub down_error_rate: f64,
+
+    /// Consecutive failed health checks after which a venue is down
+    pub down_after_failed_checks: u32,
+
+    /// How long a venue must look better before it is upgraded, in seconds
+    pub recovery_secs: u64,
+}
+
+impl Default for VenueStatusConfig {
+    fn default() -> Self {
+        Self {
+            health_interval_ms: 10_000,
+            error_window_secs: 60,
+            min_requests: 10,
+            degraded_error_rate: 0.2,
+            down_error_rate: 0.5,
+            down_after_failed_checks: 3,
+            recovery_secs: 60,
+        }
+    }
+}
+
+impl VenueStatusConfig {
+    /// Interval between health checks
+    pub fn health_interval(&self) -> Duration {
+        Duration::from_millis(self.health_interval_ms)
+    }
+
+    fn error_window(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.error_window_secs as i64)
+    }
+
+    fn recovery(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.recovery_secs as i64)
+    }
+}
+
+/// Health of a venue, from best to worst
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VenueState {
+    /// Accepting all orders
+    #[default]
+    Up,
+    /// Accepting reduce-only orders
+    Degraded,
+    /// Accepting no orders
+    Down,
+}
+
+impl VenueState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VenueState::Up => "up",
+            VenueState::Degraded => "degraded",
+            VenueState::Down => "down",
+        }
+    }
+
+    /// Whether an order may be sent to a venue in this state
+    pub fn accepts(&self, reduce_only: bool) -> bool {
+        match self {
+            VenueState::Up => true,
+            VenueState::Degraded => reduce_only,
+            VenueState::Down => false,
+        }
+    }
+}
+
+impl fmt::Display for VenueState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Current status of one venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueStatus {
+    pub state: VenueState,
+
+    /// When the venue entered its state
+    pub since: DateTime<Utc>,
+
+    /// Share of failed requests in the error window (None below
+    /// `min_requests`)
+    pub error_rate: Option<f64>,
+
+    /// Consecutive failed health checks
+    pub failed_checks: u32,
+
+    /// Why the venue is in its state
+    pub reason: String,
+}
+
+/// Transition of a venue between states
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueStatusChange {
+    pub venue: VenueId,
+    pub from: VenueState,
+    pub to: VenueState,
+    pub at: DateTime<Utc>,
+    pub reason: String,
+}
+
+/// Health history of one venue
+#[derive(Debug, Clone)]
+struct VenueHealth {
+    state: VenueState,
+    since: DateTime<Utc>,
+    reason: String,
+    /// Request outcomes in the error window (true = failed)
+    requests: VecDeque<(DateTime<Utc>, bool)>,
+    failed_checks: u32,
+    /// Better state the venue has looked like, and since when
+    improving: Option<(VenueState, DateTime<Utc>)>,
+}
+
+impl VenueHealth {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            state: VenueState::Up,
+            since: now,
+            reason: String::new(),
+            requests: VecDeque::new(),
+            failed_checks: 0,
+            improving: None,
+        }
+    }
+
+    fn error_rate(&self, config: &VenueStatusConfig) -> Option<f64> {
+        let total = self.requests.len();
+        if total == 0 || total < config.min_requests {
+            return None;
+        }
+        let failed = self.requests.iter().filter(|(_, failed)| *failed).count();
+        Some(failed as f64 / total as f64)
+    }
+
+    /// State the current evidence points to, with the reason
+    fn assess(&self, config: &VenueStatusConfig) -> (VenueState, String) {
+        let error_rate = self.error_rate(config).unwrap_or(0.0);
+        if self.failed_checks >= config.down_after_failed_checks.max(1) {
+            (VenueState::Down, format!("{} consecutive failed health checks", self.failed_checks))
+        } else if error_rate >= config.down_error_rate {
+            (VenueState::Down, format!("error rate {:.0}%", error_rate * 100.0))
+        } else if self.failed_checks > 0 {
+            (VenueState::Degraded, "health check failed".to_string())
+        } else if error_rate >= config.degraded_error_rate {
+            (VenueState::Degraded, format!("error rate {:.0}%", error_rate * 100.0))
+        } else {
+            (VenueState::Up, "healthy".to_string())
+        }
+    }
+}
+
+/// Tracks venue health and decides when order flow pauses and resumes
+#[derive(Debug, Clone, Default)]
+pub struct VenueStatusMonitor {
+    config: VenueStatusConfig,
+    venues: HashMap<VenueId, VenueHealth>,
+}
+
+impl VenueStatusMonitor {
+    pub fn new(config: VenueStatusConfig) -> Self {
+        Self {
+            config,
+            venues: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &VenueStatusConfig {
+        &self.config
+    }
+
+    /// Record the outcome of an order or cancel sent to a venue
+    pub fn record_request(&mut self, venue: &VenueId, failed: bool, now: DateTime<Utc>) -> Option<VenueStatusChange> {
+        let health = self.venues.entry(venue.clone()).or_insert_with(|| VenueHealth::new(now));
+        health.requests.push_back((now, failed));
+        self.update(venue, now)
+    }
+
+    /// Record the result of a venue health check
+    pub fn record_health_check(
+        &mut self,
+        venue: &VenueId,
+        healthy: bool,
+        now: DateTime<Utc>,
+    ) -> Option<VenueStatusChange> {
+        let health = self.venues.entry(venue.clone()).or_insert_with(|| VenueHealth::new(now));
+        health.failed_checks = if healthy { 0 } else { health.failed_checks + 1 };
+        self.update(venue, now)
+    }
+
+    /// Current state of a venue (unknown venues are up)
+    pub fn state(&self, venue: &VenueId) -> VenueState {
+        self.venues.get(venue).map(|h| h.state).unwrap_or_default()
+    }
+
+    /// Status of a venue, if anything was recorded for it
+    pub fn status(&self, venue: &VenueId) -> Option<VenueStatus> {
+        self.venues.get(venue).map(|health| VenueStatus {
+            state: health.state,
+            since: health.since,
+            error_rate: health.error_rate(&self.config),
+            failed_checks: health.failed_checks,
+            reason: health.reason.clone(),
+        })
+    }
+
+    /// Status of every venue with recorded activity
+    pub fn statuses(&self) -> HashMap<VenueId, VenueStatus> {
+        self.venues
+            .keys()
+            .filter_map(|venue| self.status(venue).map(|status| (venue.clone(), status)))
+            .collect()
+    }
+
+    /// Re-assess a venue, worsening at once and improving only after
+    /// `recovery_secs` of better evidence
+    fn update(&mut self, venue: &VenueId, now: DateTime<Utc>) -> Option<VenueStatusChange> {
+        let config = &self.config;
+        let health = self.venues.get_mut(venue)?;
+        let cutoff = now - config.error_window();
+        while health.requests.front().is_some_and(|(at, _)| *at < cutoff) {
+            health.requests.pop_front();
+        }
+
+        let (assessed, reason) = health.assess(config);
+        if assessed == health.state {
+            health.improving = None;
+            return None;
+        }
+        if assessed < health.state {
+            // Any change in the evidence restarts the recovery clock
+            let since = match health.improving {
+                Some((state, since)) if state == assessed => since,
+                _ => now,
+            };
+            health.improving = Some((assessed, since));
+            if now - since < config.recovery() {
+                return None;
+            }
+        }
+
+        let change = VenueStatusChange {
+            venue: venue.clone(),
+            from: health.state,
+            to: assessed,
+            at: now,
+            reason: reason.clone(),
+        };
+        health.state = assessed;
+        health.since = now;
+        health.reason = reason;
+        health.improving = None;
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_health_checks_and_recovery() {
+        let mut monitor = VenueStatusMonitor::new(VenueStatusConfig {
+            down_after_failed_checks: 2,
+            recovery_secs: 30,
+            ..Default::default()
+        });
+        let venue = VenueId::new("polymarket");
+        let t0 = Utc.timestamp_opt(1_000, 0).unwrap();
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+
+        assert!(monitor.record_health_check(&venue, true, at(0)).is_none());
+        let change = monitor.record_health_check(&venue, false, at(10)).unwrap();
+        assert_eq!((change.from, change.to), (VenueState::Up, VenueState::Degraded));
+        let change = monitor.record_health_check(&venue, false, at(20)).unwrap();
+        assert_eq!(change.to, VenueState::Down);
+        assert!(!monitor.state(&venue).accepts(true));
+
+        // Recovery must be sustained; a relapse restarts the clock
+        assert!(monitor.record_health_check(&venue, true, at(30)).is_none());
+        assert!(monitor.record_health_check(&venue, false, at(40)).is_none());
+        assert!(monitor.record_health_check(&venue, true, at(50)).is_none());
+        assert!(monitor.record_health_check(&venue, true, at(70)).is_none());
+        let change = monitor.record_health_check(&venue, true, at(80)).unwrap();
+        assert_eq!((change.from, change.to), (VenueState::Down, VenueState::Up));
+    }
+
+    #[test]
+    fn test_error_rate() {
+        let mut monitor = VenueStatusMonitor::new(VenueStatusConfig {
+            min_requests: 4,
+            error_window_secs: 60,
+            ..Default::default()
+        });
+        let venue = VenueId::new("kalshi");
+        let t0 = Utc.timestamp_opt(1_000, 0).unwrap();
+
+        // Too few requests to judge
+        for failed in [true, true, false] {
+            assert!(monitor.record_request(&venue, failed, t0).is_none());
+        }
+        assert_eq!(monitor.status(&venue).unwrap().error_rate, None);
+
+        let change = monitor.record_request(&venue, false, t0).unwrap();
+        assert_eq!(change.to, VenueState::Down);
+        assert_eq!(change.reason, "error rate 50%");
+        assert!(monitor.state(&venue) > VenueState::Degraded);
+
+        // Old failures age out of the window
+        let later = t0 + chrono::Duration::seconds(120);
+        for _ in 0..4 {
+            monitor.record_request(&venue, false, later);
+        }
+        assert_eq!(monitor.status(&venue).unwrap().error_rate, Some(0.0));
+        assert_eq!(monitor.state(&venue), VenueState::Down);
+    }
+}
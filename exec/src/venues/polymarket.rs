@@ -2,6 +2,9 @@
 //!
 //! This module implements the VenueAdapter trait for Polymarket CLOB API.
 //! Documentation: https://docs.polymarket.com
+//!
+//! Failed requests are returned as [`ExecError::VenueError`] classified by
+//! [`classify_error`] from the HTTP status and the error body.
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -11,10 +14,11 @@ use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use std::collections::HashMap;
 
+use crate::adapters::rounding::VenueRounding;
 use crate::adapters::venue_adapter::{VenueAdapter, VenueConfig};
-use crate::error::{ExecError, ExecResult};
+use crate::error::{ExecError, ExecResult, VenueErrorKind};
 use crate::order::{
-    CancelAck, Order, OrderAck, OrderId, OrderStatus, OrderType, Side,
+    CancelAck, CancelOutcome, Order, OrderAck, OrderId, OrderStatus, OrderType, Side,
     VenueId,
 };
 
@@ -70,7 +74,12 @@ impl PolymarketAdapter {
         let order_type = match order.order_type {
             OrderType::Limit => "GTC",
             OrderType::Market => "FOK", // Polymarket uses FOK for market orders
-            OrderType::PostOnly => "GTD",
+            OrderType::PostOnly => "GTC",
+            OrderType::StopMarket | OrderType::StopLimit => {
+                return Err(ExecError::ValidationError(
+                    "Polymarket has no native stop orders; they are triggered locally by the execution engine".to_string(),
+                ));
+            }
         };
 
         Ok(PolymarketOrderRequest {
@@ -79,10 +88,29 @@ impl PolymarketAdapter {
             price: order.price.map(|p| p.to_string()),
             size: order.size.to_string(),
             order_type: order_type.to_string(),
+            post_only: order.order_type == OrderType::PostOnly,
             client_order_id: Some(order.client_order_id.clone()),
         })
     }
 
+    /// Classified error for a failed request
+    fn venue_error(&self, status: reqwest::StatusCode, message: String, error_text: &str) -> ExecError {
+        ExecError::VenueError {
+            venue: self.venue_id().to_string(),
+            message,
+            code: Some(status.as_str().to_string()),
+            kind: classify_error(status.as_u16(), error_text),
+        }
+    }
+
+    /// Whether an error body is a post-only (would cross) rejection
+    fn is_post_only_reject(error_text: &str) -> bool {
+        let text = error_text.to_lowercase();
+        ["post-only", "post only", "post_only", "crosses book", "cross the book"]
+            .iter()
+            .any(|marker| text.contains(marker))
+    }
+
     /// Convert Polymarket order status to our status
     fn from_polymarket_status(status: &str) -> OrderStatus {
         match status {
@@ -104,6 +132,10 @@ impl VenueAdapter for PolymarketAdapter {
         self.config.venue_id.clone()
     }
 
+    fn rounding(&self) -> Option<VenueRounding> {
+        self.config.rounding
+    }
+
     async fn place_order(&mut self, order: &Order) -> ExecResult<OrderAck> {
         let api_key = self
             .config
@@ -137,11 +169,13 @@ impl VenueAdapter for PolymarketAdapter {
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ExecError::VenueError {
-                venue: self.venue_id().to_string(),
-                message: format!("Order placement failed: {}", error_text),
-                code: Some(status.as_str().to_string()),
-            });
+            if order.order_type == OrderType::PostOnly && Self::is_post_only_reject(&error_text) {
+                return Err(ExecError::PostOnlyRejected {
+                    venue: self.venue_id().to_string(),
+                    message: error_text,
+                });
+            }
+            return Err(self.venue_error(status, format!("Order placement failed: {}", error_text), &error_text));
         }
 
         let pm_response: PolymarketOrderResponse = response.json().await?;
@@ -155,6 +189,7 @@ impl VenueAdapter for PolymarketAdapter {
             status: Self::from_polymarket_status(&pm_response.status),
             timestamp: Utc::now(),
             message: None,
+            tif_emulated: false,
         })
     }
 
@@ -201,6 +236,7 @@ impl VenueAdapter for PolymarketAdapter {
             success,
             timestamp: Utc::now(),
             message,
+            outcome: if success { CancelOutcome::Cancelled } else { CancelOutcome::StillWorking },
         })
     }
 
@@ -234,11 +270,9 @@ impl VenueAdapter for PolymarketAdapter {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ExecError::VenueError {
-                venue: self.venue_id().to_string(),
-                message: "Failed to get order status".to_string(),
-                code: Some(response.status().as_str().to_string()),
-            });
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(self.venue_error(status, "Failed to get order status".to_string(), &error_text));
         }
 
         let pm_response: PolymarketOrderResponse = response.json().await?;
@@ -269,11 +303,9 @@ impl VenueAdapter for PolymarketAdapter {
             .await?;
 
         if !response.status().is_success() {
-            return Err(ExecError::VenueError {
-                venue: self.venue_id().to_string(),
-                message: "Failed to get open orders".to_string(),
-                code: Some(response.status().as_str().to_string()),
-            });
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(self.venue_error(status, "Failed to get open orders".to_string(), &error_text));
         }
 
         let pm_orders: Vec<PolymarketOrderResponse> = response.json().await?;
@@ -307,6 +339,7 @@ impl VenueAdapter for PolymarketAdapter {
                 venue: self.venue_id().to_string(),
                 message: "Failed to cancel order for modification".to_string(),
                 code: None,
+                kind: VenueErrorKind::Unknown,
             });
         }
 
@@ -316,6 +349,7 @@ impl VenueAdapter for PolymarketAdapter {
             venue: self.venue_id().to_string(),
             message: "Order modification requires full order context - not yet implemented".to_string(),
             code: None,
+            kind: VenueErrorKind::Unknown,
         })
     }
 
@@ -329,6 +363,29 @@ impl VenueAdapter for PolymarketAdapter {
     }
 }
 
+/// Classify a failed Polymarket request by HTTP status and error body
+///
+/// Polymarket reports most order rejections as HTTP 400 with a message,
+/// e.g. `not enough balance / allowance` or `invalid price (1.2), min:
+/// 0.001 - max: 0.999`; throttling as HTTP 429; and maintenance or
+/// cancel-only mode as HTTP 503 or a "trading is currently disabled"
+/// message.
+pub(crate) fn classify_error(status: u16, error_text: &str) -> VenueErrorKind {
+    let text = error_text.to_lowercase();
+    let mentions = |markers: &[&str]| markers.iter().any(|marker| text.contains(marker));
+    if status == 429 || mentions(&["too many requests", "rate limit"]) {
+        VenueErrorKind::RateLimited
+    } else if status == 503 || mentions(&["maintenance", "trading is currently disabled", "cancel-only", "cancel only"]) {
+        VenueErrorKind::Maintenance
+    } else if mentions(&["not enough balance", "insufficient balance", "insufficient funds", "allowance"]) {
+        VenueErrorKind::InsufficientBalance
+    } else if mentions(&["invalid price", "tick size", "tick_size", "price must be"]) {
+        VenueErrorKind::InvalidPrice
+    } else {
+        VenueErrorKind::Unknown
+    }
+}
+
 // Polymarket API request/response types
 
 #[derive(Debug, Serialize)]
@@ -339,6 +396,8 @@ struct PolymarketOrderRequest {
     size: String,
     #[serde(rename = "type")]
     order_type: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    post_only: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     client_order_id: Option<String>,
 }
@@ -413,5 +472,58 @@ mod tests {
         assert_eq!(pm_order.market, "0x123abc");
         assert_eq!(pm_order.side, "BUY");
         assert_eq!(pm_order.size, "100");
+        assert!(!pm_order.post_only);
+    }
+
+    #[test]
+    fn test_post_only_conversion() {
+        let config = VenueConfig::new(
+            VenueId::new("polymarket"),
+            "https://clob.polymarket.com".to_string(),
+        );
+        let adapter = PolymarketAdapter::new(config).unwrap();
+
+        let order = Order::new(
+            VenueId::new("polymarket"),
+            MarketId::new("0x123abc"),
+            Side::Sell,
+            OrderType::PostOnly,
+            Some(0.55),
+            100.0,
+            TimeInForce::GTC,
+            "client-456".to_string(),
+        );
+
+        let pm_order = adapter.to_polymarket_order(&order).unwrap();
+        assert_eq!(pm_order.order_type, "GTC");
+        assert!(pm_order.post_only);
+
+        assert!(PolymarketAdapter::is_post_only_reject(
+            "invalid post-only order: order crosses book"
+        ));
+        assert!(!PolymarketAdapter::is_post_only_reject("insufficient balance"));
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert_eq!(classify_error(429, ""), VenueErrorKind::RateLimited);
+        assert_eq!(classify_error(503, "Service Unavailable"), VenueErrorKind::Maintenance);
+        assert_eq!(
+            classify_error(425, "Trading is currently disabled. Check status.polymarket.com"),
+            VenueErrorKind::Maintenance
+        );
+        assert_eq!(
+            classify_error(400, r#"{"error":"not enough balance / allowance"}"#),
+            VenueErrorKind::InsufficientBalance
+        );
+        assert_eq!(
+            classify_error(400, "invalid price (1.2), min: 0.001 - max: 0.999"),
+            VenueErrorKind::InvalidPrice
+        );
+        assert_eq!(
+            classify_error(400, "order 0xabc is invalid. Price (0.523) breaks minimum tick size rule: 0.01"),
+            VenueErrorKind::InvalidPrice
+        );
+        assert_eq!(classify_error(500, "internal error"), VenueErrorKind::Unknown);
     }
 }
@@ -1,24 +1,52 @@
 //! Integration tests for ExecutionEngine
 
 use ag_exec::{
-    adapters::{VenueAdapter, VenueConfig},
-    error::ExecResult,
-    order::{CancelAck, MarketId, Order, OrderAck, OrderId, OrderStatus, OrderType, Side, TimeInForce, VenueId},
+    adapters::{VenueAdapter, VenueRounding},
+    error::{ExecError, ExecResult, VenueErrorKind},
+    oms::BookDepth,
+    order::{CancelAck, CancelOutcome, MarketId, Order, OrderAck, OrderId, OrderStatus, OrderType, Side, TimeInForce, VenueId},
     ratelimit::RateLimiterConfig,
     ExecutionEngine, ExecutionEngineConfig,
 };
-use ag_risk::RiskEngine;
+use ag_risk::{KillSwitchSource, RiskEngine};
 use async_trait::async_trait;
 use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Mock venue adapter for testing
 struct MockVenueAdapter {
     venue_id: VenueId,
+    rounding: Option<VenueRounding>,
+    /// Orders the venue reports as open
+    open_orders: Arc<Mutex<Vec<Order>>>,
+    /// Acknowledge cancels without removing the order from `open_orders`
+    ignore_cancels: bool,
+    /// Let cancels time out
+    cancel_timeout: Arc<Mutex<bool>>,
+    /// Status the venue reports for every order (None = unknown order)
+    order_status: Arc<Mutex<Option<OrderStatus>>>,
+    /// What the health endpoint reports
+    healthy: Arc<Mutex<bool>>,
+    /// Net positions the venue reports (None = unsupported)
+    positions: Option<HashMap<String, f64>>,
+    /// Class of error returned for placements (None = accept)
+    place_error: Arc<Mutex<Option<VenueErrorKind>>>,
 }
 
 impl MockVenueAdapter {
     fn new(venue_id: VenueId) -> Self {
-        Self { venue_id }
+        Self {
+            venue_id,
+            rounding: None,
+            open_orders: Arc::new(Mutex::new(Vec::new())),
+            ignore_cancels: false,
+            cancel_timeout: Arc::new(Mutex::new(false)),
+            order_status: Arc::new(Mutex::new(Some(OrderStatus::Working))),
+            healthy: Arc::new(Mutex::new(true)),
+            positions: None,
+            place_error: Arc::new(Mutex::new(None)),
+        }
     }
 }
 
@@ -28,32 +56,52 @@ impl VenueAdapter for MockVenueAdapter {
         self.venue_id.clone()
     }
 
+    fn rounding(&self) -> Option<VenueRounding> {
+        self.rounding
+    }
+
     async fn place_order(&mut self, order: &Order) -> ExecResult<OrderAck> {
+        if let Some(kind) = *self.place_error.lock().unwrap() {
+            return Err(ExecError::VenueError {
+                venue: self.venue_id.to_string(),
+                message: format!("Order placement failed: {}", kind),
+                code: Some("400".to_string()),
+                kind,
+            });
+        }
         Ok(OrderAck {
             order_id: order.id,
             venue_order_id: Some("venue-123".to_string()),
             status: OrderStatus::Working,
             timestamp: Utc::now(),
             message: None,
+            tif_emulated: false,
         })
     }
 
     async fn cancel_order(&mut self, order_id: &OrderId) -> ExecResult<CancelAck> {
+        if *self.cancel_timeout.lock().unwrap() {
+            return Err(ExecError::Timeout("cancel".to_string()));
+        }
+        if !self.ignore_cancels {
+            self.open_orders.lock().unwrap().retain(|o| o.id != *order_id);
+        }
         Ok(CancelAck {
             order_id: *order_id,
             venue_order_id: Some("venue-123".to_string()),
             success: true,
             timestamp: Utc::now(),
             message: None,
+            outcome: CancelOutcome::Cancelled,
         })
     }
 
-    async fn get_order_status(&mut self, _order_id: &OrderId) -> ExecResult<OrderStatus> {
-        Ok(OrderStatus::Working)
+    async fn get_order_status(&mut self, order_id: &OrderId) -> ExecResult<OrderStatus> {
+        self.order_status.lock().unwrap().ok_or(ExecError::OrderNotFound(*order_id))
     }
 
     async fn get_open_orders(&mut self) -> ExecResult<Vec<Order>> {
-        Ok(Vec::new())
+        Ok(self.open_orders.lock().unwrap().clone())
     }
 
     async fn modify_order(
@@ -68,11 +116,18 @@ impl VenueAdapter for MockVenueAdapter {
             status: OrderStatus::Working,
             timestamp: Utc::now(),
             message: None,
+            tif_emulated: false,
         })
     }
 
     async fn health_check(&mut self) -> ExecResult<bool> {
-        Ok(true)
+        Ok(*self.healthy.lock().unwrap())
+    }
+
+    async fn get_positions(&mut self) -> ExecResult<HashMap<String, f64>> {
+        self.positions
+            .clone()
+            .ok_or_else(|| ExecError::VenueNotSupported("positions".to_string()))
     }
 }
 
@@ -209,6 +264,68 @@ async fn test_cancel_order() {
     assert!(cancel_ack.success);
 }
 
+#[tokio::test]
+async fn test_ambiguous_cancel_converges() {
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    let cancel_timeout = adapter.cancel_timeout.clone();
+    let order_status = adapter.order_status.clone();
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let submit = |client_id: &str| {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.52),
+            100.0,
+            TimeInForce::GTC,
+            client_id.to_string(),
+        )
+    };
+    *cancel_timeout.lock().unwrap() = true;
+
+    // Timed out, but the order had filled
+    let filled = engine.submit_order(submit("client-filled")).await.unwrap().order_id;
+    *order_status.lock().unwrap() = Some(OrderStatus::Filled);
+    let ack = engine.cancel_order(filled).await.unwrap();
+    assert_eq!(ack.outcome, CancelOutcome::AlreadyFilled);
+    assert!(!ack.success);
+    assert_eq!(engine.get_order(&filled).unwrap().status, OrderStatus::Filled);
+
+    // Timed out and the venue no longer knows the order
+    let unknown = engine.submit_order(submit("client-unknown")).await.unwrap().order_id;
+    *order_status.lock().unwrap() = None;
+    let ack = engine.cancel_order(unknown).await.unwrap();
+    assert_eq!(ack.outcome, CancelOutcome::UnknownOrder);
+    assert!(ack.success);
+    assert_eq!(engine.get_order(&unknown).unwrap().status, OrderStatus::Cancelled);
+
+    // A retry of a completed cancel is acknowledged again
+    assert!(engine.cancel_order(unknown).await.unwrap().success);
+
+    // Timed out with the order still working: not left in Cancelling
+    let working = engine.submit_order(submit("client-working")).await.unwrap().order_id;
+    *order_status.lock().unwrap() = Some(OrderStatus::Working);
+    let ack = engine.cancel_order(working).await.unwrap();
+    assert_eq!(ack.outcome, CancelOutcome::StillWorking);
+    assert_eq!(engine.get_order(&working).unwrap().status, OrderStatus::Working);
+
+    // The retry goes through once the venue answers
+    *cancel_timeout.lock().unwrap() = false;
+    let ack = engine.cancel_order(working).await.unwrap();
+    assert_eq!(ack.outcome, CancelOutcome::Cancelled);
+    assert_eq!(engine.get_order(&working).unwrap().status, OrderStatus::Cancelled);
+}
+
 #[tokio::test]
 async fn test_position_tracking() {
     let config = ExecutionEngineConfig {
@@ -278,7 +395,7 @@ async fn test_validation_error() {
     engine.register_adapter(Box::new(adapter), rate_limiter);
 
     // Create invalid order (negative size)
-    let mut order = Order::new(
+    let order = Order::new(
         venue_id,
         MarketId::new("market-1"),
         Side::Buy,
@@ -293,3 +410,863 @@ async fn test_validation_error() {
     let result = engine.submit_order(order).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_tif_emulation() {
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+
+    let mut engine = ExecutionEngine::new(config);
+
+    // Mock venue only supports GTC natively
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    let cancel_timeout = adapter.cancel_timeout.clone();
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let order = |tif, client_id: &str| {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.52),
+            100.0,
+            tif,
+            client_id.to_string(),
+        )
+    };
+
+    // GTC is native
+    let ack = engine.submit_order(order(TimeInForce::GTC, "client-gtc")).await.unwrap();
+    assert!(!ack.tif_emulated);
+    assert_eq!(ack.status, OrderStatus::Working);
+
+    // IOC: resting remainder is cancelled immediately
+    let ack = engine.submit_order(order(TimeInForce::IOC, "client-ioc")).await.unwrap();
+    assert!(ack.tif_emulated);
+    assert_eq!(ack.status, OrderStatus::Cancelled);
+    assert_eq!(engine.get_order(&ack.order_id).unwrap().status, OrderStatus::Cancelled);
+
+    // A failed remainder cancel still returns the live order's ack, and the
+    // submission counts as done
+    *cancel_timeout.lock().unwrap() = true;
+    let ack = engine.submit_order(order(TimeInForce::IOC, "client-ioc-2")).await.unwrap();
+    assert_eq!(ack.status, OrderStatus::Working);
+    assert!(ack.message.as_deref().unwrap().contains("may still rest"));
+    let retry = engine.submit_order(order(TimeInForce::IOC, "client-ioc-2")).await.unwrap();
+    assert_eq!(retry.order_id, ack.order_id);
+    *cancel_timeout.lock().unwrap() = false;
+
+    // FOK: rejected without depth, or when depth can't fill the full size
+    assert!(engine.submit_order(order(TimeInForce::FOK, "client-fok-1")).await.is_err());
+
+    let market = MarketId::new("market-1");
+    engine
+        .update_book_depth(&market, BookDepth::top_of_book(Some((0.50, 500.0)), Some((0.52, 60.0))))
+        .await;
+    assert!(engine.submit_order(order(TimeInForce::FOK, "client-fok-2")).await.is_err());
+
+    engine
+        .update_book_depth(&market, BookDepth::top_of_book(Some((0.50, 500.0)), Some((0.52, 150.0))))
+        .await;
+    let ack = engine.submit_order(order(TimeInForce::FOK, "client-fok-3")).await.unwrap();
+    assert!(ack.tif_emulated);
+}
+
+#[tokio::test]
+async fn test_liquidation_under_kill_switch() {
+    use ag_exec::{liquidate, Fill, LiquidationConfig, LiquidationStyle};
+    use std::sync::Arc;
+
+    let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+    engine.set_risk_engine(RiskEngine::from_yaml("policies:\n  - type: KillSwitch\n    enabled: false\n").unwrap());
+
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let market = MarketId::new("market-1");
+    let buy = Order::new(
+        venue_id.clone(),
+        market.clone(),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.50),
+        100.0,
+        TimeInForce::GTC,
+        "buy-1".to_string(),
+    );
+    let ack = engine.submit_order(buy).await.unwrap();
+    engine
+        .record_fill(Fill {
+            fill_id: "fill-1".to_string(),
+            order_id: ack.order_id,
+            venue_order_id: None,
+            price: 0.50,
+            size: 100.0,
+            fee: 0.0,
+            fee_currency: "USD".to_string(),
+            timestamp: Utc::now(),
+            liquidity: None,
+        })
+        .await
+        .unwrap();
+    engine.update_touch(&market, Some(0.49), Some(0.51)).await;
+    engine
+        .risk_engine()
+        .unwrap()
+        .lock()
+        .await
+        .trigger_kill_switch(KillSwitchSource::Manual, "test");
+
+    // Reduce-only orders must shrink the position; those that do bypass the kill-switch
+    let grow = Order::new(
+        venue_id.clone(),
+        market.clone(),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.50),
+        10.0,
+        TimeInForce::GTC,
+        "grow".to_string(),
+    )
+    .with_reduce_only();
+    assert!(engine.submit_order(grow).await.is_err());
+
+    let engine = Arc::new(engine);
+    let filler = {
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+            let exit = engine
+                .get_active_orders()
+                .unwrap()
+                .into_iter()
+                .find(|o| o.reduce_only)
+                .unwrap();
+            assert_eq!(exit.price, Some(0.49));
+            engine
+                .record_fill(Fill {
+                    fill_id: "fill-2".to_string(),
+                    order_id: exit.id,
+                    venue_order_id: None,
+                    price: 0.49,
+                    size: 100.0,
+                    fee: 0.0,
+                    fee_currency: "USD".to_string(),
+                    timestamp: Utc::now(),
+                    liquidity: None,
+                })
+                .await
+                .unwrap();
+        })
+    };
+
+    let config = LiquidationConfig {
+        style: LiquidationStyle::LimitLadder {
+            step_bps: 100.0,
+            max_steps: 10,
+        },
+        step_interval_ms: 50,
+        timeout_secs: 5,
+    };
+    let report = liquidate(&engine, &market, &config).await.unwrap();
+    filler.await.unwrap();
+
+    assert!(report.is_flat());
+    assert_eq!(report.initial_position, 100.0);
+    assert!(!report.used_market_orders);
+}
+
+#[tokio::test]
+async fn test_idempotent_resubmission() {
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+
+    let mut engine = ExecutionEngine::new(config);
+
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let order = || {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.52),
+            100.0,
+            TimeInForce::GTC,
+            "client-retry".to_string(),
+        )
+    };
+
+    // A retry after an ambiguous failure reuses the client order ID
+    let first = engine.submit_order(order()).await.unwrap();
+    let retry = engine.submit_order(order()).await.unwrap();
+    assert_eq!(retry.order_id, first.order_id);
+    assert_eq!(retry.venue_order_id, first.venue_order_id);
+    assert_eq!(engine.get_active_orders().unwrap().len(), 1);
+
+    // An explicit key takes precedence over the client order ID
+    let keyed = engine.submit_order(order().with_idempotency_key("signal-42")).await.unwrap();
+    assert_ne!(keyed.order_id, first.order_id);
+    assert_eq!(engine.get_active_orders().unwrap().len(), 2);
+
+    // Orders rejected before reaching the venue release their key
+    let invalid = Order { size: -1.0, ..order().with_idempotency_key("signal-43") };
+    assert!(engine.submit_order(invalid).await.is_err());
+    let corrected = engine.submit_order(order().with_idempotency_key("signal-43")).await.unwrap();
+    assert_eq!(engine.get_active_orders().unwrap().len(), 3);
+    assert_eq!(engine.get_order(&corrected.order_id).unwrap().status, OrderStatus::Working);
+}
+
+#[tokio::test]
+async fn test_perp_hedge_rebalance() {
+    use ag_exec::{Fill, HedgeConfig, Hedger};
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    for venue in ["polymarket", "binance"] {
+        let venue_id = VenueId::new(venue);
+        let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+        engine.register_adapter(Box::new(MockVenueAdapter::new(venue_id)), rate_limiter);
+    }
+
+    // P(BTC above K) falls 0.2 per 10k around 100k: 0.00002 per USD
+    for (market, mid) in [("btc-90k", 0.70), ("btc-100k", 0.50), ("btc-110k", 0.30)] {
+        engine.update_touch(&MarketId::new(market), Some(mid - 0.01), Some(mid + 0.01)).await;
+    }
+
+    let fill = |order_id, price, size| Fill {
+        fill_id: format!("fill-{}", order_id),
+        order_id,
+        venue_order_id: None,
+        price,
+        size,
+        fee: 0.0,
+        fee_currency: "USD".to_string(),
+        timestamp: Utc::now(),
+        liquidity: None,
+    };
+
+    let buy = Order::new(
+        VenueId::new("polymarket"),
+        MarketId::new("btc-100k"),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.50),
+        100.0,
+        TimeInForce::GTC,
+        "buy-1".to_string(),
+    );
+    let ack = engine.submit_order(buy).await.unwrap();
+    engine.record_fill(fill(ack.order_id, 0.50, 100.0)).await.unwrap();
+
+    let hedger = Hedger::new(
+        serde_json::from_str::<HedgeConfig>(
+            r#"{
+                "venue": "binance",
+                "hedge_ratio": 0.5,
+                "perps": {"BTC": {"market": "BTCUSDT", "contract_size": 0.001}},
+                "markets": [
+                    {"market": "btc-90k", "underlying": "BTC", "strike": 90000.0},
+                    {"market": "btc-100k", "underlying": "BTC", "strike": 100000.0},
+                    {"market": "btc-110k", "underlying": "BTC", "strike": 110000.0}
+                ]
+            }"#,
+        )
+        .unwrap(),
+    );
+
+    // 100 shares * 0.00002 = 0.002 BTC long; half hedged = 1 contract short
+    let exposures = hedger.exposures(&engine).await;
+    assert!((exposures["BTC"] - 0.002).abs() < 1e-12);
+
+    let adjustments = hedger.rebalance(&engine).await.unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert!((adjustments[0].trade_size() + 1.0).abs() < 1e-9);
+    let hedge_ack = adjustments[0].ack.clone().unwrap();
+    let hedge = engine.get_order(&hedge_ack.order_id).unwrap();
+    assert_eq!(hedge.venue, VenueId::new("binance"));
+    assert_eq!(hedge.side, Side::Sell);
+    assert!(!hedge.reduce_only);
+
+    // Once the hedge fills, the next rebalance has nothing to do
+    engine.record_fill(fill(hedge.id, 100_000.0, hedge.size)).await.unwrap();
+    let adjustments = hedger.rebalance(&engine).await.unwrap();
+    assert!(adjustments[0].ack.is_none());
+}
+
+#[tokio::test]
+async fn test_reconciliation_report() {
+    use ag_exec::{DiscrepancyKind, Reconciler};
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    let venue_id = VenueId::new("mock_venue");
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(MockVenueAdapter::new(venue_id.clone())), rate_limiter);
+
+    let mut reconciler = Reconciler::default();
+    assert!(reconciler.reconcile(&engine).await.unwrap().is_consistent());
+
+    // The mock venue never lists open orders, so a working order is missing there
+    let order = Order::new(
+        venue_id.clone(),
+        MarketId::new("market-1"),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.52),
+        100.0,
+        TimeInForce::GTC,
+        "client-recon".to_string(),
+    );
+    let ack = engine.submit_order(order).await.unwrap();
+
+    let first = reconciler.reconcile(&engine).await.unwrap();
+    let status = first.venue(&venue_id).unwrap();
+    assert!(!first.is_consistent());
+    assert_eq!(status.local_open_orders, 1);
+    assert_eq!(status.venue_open_orders, 0);
+    assert_eq!(status.unsupported, vec!["positions", "fills"]);
+    assert_eq!(status.count(DiscrepancyKind::MissingAtVenue), 1);
+    assert_eq!(status.discrepancies[0].reference, ack.order_id.to_string());
+
+    // A persisting discrepancy keeps its first-seen time
+    let second = reconciler.reconcile(&engine).await.unwrap();
+    assert_eq!(second.venues[0].discrepancies[0].first_seen, status.discrepancies[0].first_seen);
+    assert_eq!(reconciler.latest(), Some(&second));
+
+    engine.cancel_order(ack.order_id).await.unwrap();
+    assert!(reconciler.reconcile(&engine).await.unwrap().is_consistent());
+}
+
+#[tokio::test]
+async fn test_status_polling_publishes_updates() {
+    use ag_exec::StatusPollConfig;
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    let venue_id = VenueId::new("mock_venue");
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(MockVenueAdapter::new(venue_id.clone())), rate_limiter);
+
+    // Disabled by default
+    assert!(engine.poll_order_statuses().await.unwrap().is_empty());
+
+    engine.enable_status_polling(StatusPollConfig {
+        min_interval_ms: 0,
+        ..StatusPollConfig::default()
+    });
+    let mut updates = engine.subscribe_order_updates();
+
+    let order = Order::new(
+        venue_id.clone(),
+        MarketId::new("market-1"),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.52),
+        100.0,
+        TimeInForce::GTC,
+        "client-poll".to_string(),
+    );
+    let ack = engine.submit_order(order).await.unwrap();
+
+    // The venue agrees with the local status: nothing to publish
+    assert!(engine.poll_order_statuses().await.unwrap().is_empty());
+
+    // A stale local status is corrected from the venue
+    engine.order_tracker().update_status(&ack.order_id, OrderStatus::PartiallyFilled).unwrap();
+    let polled = engine.poll_order_statuses().await.unwrap();
+    assert_eq!(polled.len(), 1);
+    assert_eq!(polled[0].previous, OrderStatus::PartiallyFilled);
+    assert_eq!(polled[0].status, OrderStatus::Working);
+    assert_eq!(engine.get_order(&ack.order_id).unwrap().status, OrderStatus::Working);
+    assert_eq!(updates.try_recv().unwrap(), polled[0]);
+
+    // Terminal orders are no longer polled
+    engine.cancel_order(ack.order_id).await.unwrap();
+    assert!(engine.poll_order_statuses().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_rounding_at_venue_boundary() {
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter {
+        rounding: Some(VenueRounding::default()),
+        ..MockVenueAdapter::new(venue_id.clone())
+    };
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let order = |side, price, client_id: &str| {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            side,
+            OrderType::Limit,
+            Some(price),
+            100.004,
+            TimeInForce::GTC,
+            client_id.to_string(),
+        )
+    };
+
+    // The tracked order carries the rounded values and what was requested
+    let ack = engine.submit_order(order(Side::Buy, 0.527, "client-round-1")).await.unwrap();
+    let tracked = engine.get_order(&ack.order_id).unwrap();
+    assert_eq!((tracked.price, tracked.size), (Some(0.52), 100.0));
+    let rounding = tracked.rounding.unwrap();
+    assert_eq!((rounding.requested_price, rounding.requested_size), (Some(0.527), 100.004));
+
+    // A sell that would round up to 1 never reaches the venue
+    assert!(engine.submit_order(order(Side::Sell, 0.996, "client-round-2")).await.is_err());
+    assert_eq!(engine.get_active_orders().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_risk_check_counts_placed_orders_only() {
+    let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+    engine.set_risk_engine(RiskEngine::from_yaml("policies:\n  - type: PositionLimit\n    max_size: 1000.0\n").unwrap());
+    let risk_engine = engine.risk_engine().unwrap();
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter {
+        rounding: Some(VenueRounding::default()),
+        ..MockVenueAdapter::new(venue_id.clone())
+    };
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let order = |side, price, client_id: &str| {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            side,
+            OrderType::Limit,
+            Some(price),
+            100.0,
+            TimeInForce::GTC,
+            client_id.to_string(),
+        )
+    };
+
+    // Refused by rounding before the risk check sees it
+    assert!(engine.submit_order(order(Side::Sell, 0.996, "client-risk-1")).await.is_err());
+    assert!(risk_engine.lock().await.policy_counters().is_empty());
+
+    engine.submit_order(order(Side::Buy, 0.52, "client-risk-2")).await.unwrap();
+    assert_eq!(risk_engine.lock().await.policy_counters()["PositionLimit"].evaluations, 1);
+}
+
+#[tokio::test]
+async fn test_pegged_order_follows_touch() {
+    use ag_exec::{Fill, Peg, PegConfig, PegReference};
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    engine.set_peg_config(PegConfig {
+        max_reprices: 2,
+        ..PegConfig::default()
+    });
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter {
+        rounding: Some(VenueRounding::default()),
+        ..MockVenueAdapter::new(venue_id.clone())
+    };
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let market = MarketId::new("market-1");
+    let order = Order::new(
+        venue_id.clone(),
+        market.clone(),
+        Side::Buy,
+        OrderType::Limit,
+        None,
+        100.0,
+        TimeInForce::GTC,
+        "client-peg-1".to_string(),
+    )
+    .with_peg(Peg::new(PegReference::BestBid, 1));
+
+    // Nothing to peg to yet
+    assert!(engine.submit_order(order.clone()).await.is_err());
+
+    engine.update_touch(&market, Some(0.40), Some(0.45)).await;
+    let order = Order { id: OrderId::new(), client_order_id: "client-peg-2".to_string(), ..order };
+    let ack = engine.submit_order(order.clone()).await.unwrap();
+    assert_eq!(engine.get_order(&ack.order_id).unwrap().price, Some(0.41));
+
+    // Unchanged touch: no reprice
+    assert!(engine.on_touch(&market, Some(0.40), Some(0.45)).await.unwrap().is_empty());
+
+    // Bid moves up: the order is cancelled and replaced one tick above it
+    let results = engine.on_touch(&market, Some(0.42), Some(0.45)).await.unwrap();
+    assert_eq!(results.len(), 1);
+    let replacement = results[0].as_ref().unwrap().order_id;
+    assert_eq!(engine.get_order(&order.id).unwrap().status, OrderStatus::Cancelled);
+    let working = engine.get_order(&replacement).unwrap();
+    assert_eq!(working.price, Some(0.43));
+    assert_eq!(working.client_order_id, "client-peg-2-p1");
+
+    // Partial fill: the next replacement only carries the remainder
+    engine
+        .record_fill(Fill {
+            fill_id: "fill-peg-1".to_string(),
+            order_id: replacement,
+            venue_order_id: None,
+            price: 0.43,
+            size: 40.0,
+            fee: 0.0,
+            fee_currency: "USDC".to_string(),
+            timestamp: Utc::now(),
+            liquidity: None,
+        })
+        .await
+        .unwrap();
+    let results = engine.on_touch(&market, Some(0.38), Some(0.45)).await.unwrap();
+    let working = engine.get_order(&results[0].as_ref().unwrap().order_id).unwrap();
+    assert_eq!((working.price, working.size), (Some(0.39), 60.0));
+
+    // Reprice budget spent
+    assert!(engine.on_touch(&market, Some(0.30), Some(0.45)).await.unwrap().is_empty());
+    assert_eq!(engine.get_pegged_orders().await[0].throttled, 1);
+
+    // Cancelling by the original ID cancels the working replacement
+    assert!(engine.cancel_order(order.id).await.unwrap().success);
+    assert_eq!(engine.get_order(&working.id).unwrap().status, OrderStatus::Cancelled);
+    assert!(engine.get_pegged_orders().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_preflight_sizes_to_largest_passing_order() {
+    use ag_exec::VenueBalance;
+
+    let mut engine = ExecutionEngine::new(ExecutionEngineConfig::default());
+    engine.set_risk_engine(
+        RiskEngine::from_yaml("policies:\n  - type: PositionLimit\n    max_size: 200.0\n").unwrap(),
+    );
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter {
+        rounding: Some(VenueRounding::default()),
+        ..MockVenueAdapter::new(venue_id.clone())
+    };
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+    engine.set_balance(VenueBalance::new(venue_id.clone(), 1000.0)).await;
+
+    let order = Order::new(
+        venue_id.clone(),
+        MarketId::new("market-1"),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.50),
+        300.0,
+        TimeInForce::GTC,
+        "client-preflight-1".to_string(),
+    );
+
+    // Too large for the position limit: the risk check caps the size
+    let report = engine.preflight(&order).await;
+    assert!(!report.passed());
+    assert!(report.validation_error.is_none());
+    assert_eq!(report.risk.as_ref().unwrap().violations[0].policy, "PositionLimit");
+    assert!(report.balance.as_ref().unwrap().passed());
+    assert!(report.rate_limit.as_ref().unwrap().available());
+    assert!((report.max_size.unwrap() - 200.0).abs() < 1e-9);
+
+    // Less cash than the capped size needs: the balance caps it further
+    engine.set_balance(VenueBalance::new(venue_id.clone(), 60.0)).await;
+    let report = engine.preflight(&order).await;
+    assert_eq!(report.failures().len(), 2);
+    assert!((report.max_size.unwrap() - 120.0).abs() < 1e-9);
+
+    // The suggested size passes
+    let sized = Order { size: report.max_size.unwrap(), ..order.clone() };
+    assert!(engine.preflight(&sized).await.passed());
+
+    // Nothing was submitted or counted
+    assert!(engine.get_active_orders().unwrap().is_empty());
+    let risk_engine = engine.risk_engine().unwrap();
+    assert!(risk_engine.lock().await.policy_counters().is_empty());
+
+    // Invalid prices fail validation at any size
+    let invalid = Order { price: Some(1.5), ..order };
+    let report = engine.preflight(&invalid).await;
+    assert!(report.validation_error.is_some());
+    assert_eq!(report.max_size, None);
+}
+
+#[tokio::test]
+async fn test_shutdown_verification() {
+    use ag_exec::{verify_shutdown, ShutdownCheckConfig};
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let check = ShutdownCheckConfig {
+        max_attempts: 3,
+        retry_delay_ms: 0,
+    };
+    let stray = Order::new(
+        VenueId::new("mock_venue"),
+        MarketId::new("market-1"),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.52),
+        100.0,
+        TimeInForce::GTC,
+        "client-stray".to_string(),
+    );
+
+    // An order unknown to the OMS is cancelled at the venue and confirmed gone
+    let mut engine = ExecutionEngine::new(config.clone());
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    adapter.open_orders.lock().unwrap().push(stray.clone());
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let report = verify_shutdown(&engine, &check).await;
+    assert!(report.is_clean());
+    assert_eq!(report.attempts, 2);
+    assert_eq!(report.venues[0].cancels_sent, 1);
+    assert!(report.residual_positions.is_empty());
+
+    // An order that ignores cancels is reported after the last attempt
+    let mut engine = ExecutionEngine::new(config);
+    let adapter = MockVenueAdapter {
+        ignore_cancels: true,
+        ..MockVenueAdapter::new(venue_id.clone())
+    };
+    adapter.open_orders.lock().unwrap().push(stray.clone());
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let report = verify_shutdown(&engine, &check).await;
+    assert!(!report.is_clean());
+    assert_eq!(report.attempts, 3);
+    assert_eq!(report.residual_order_count(), 1);
+    assert_eq!(report.venues[0].residual_orders[0].id, stray.id);
+}
+
+#[tokio::test]
+async fn test_venue_status_pauses_order_flow() {
+    use ag_exec::{VenueState, VenueStatusConfig};
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    engine.enable_venue_status(VenueStatusConfig {
+        down_after_failed_checks: 2,
+        recovery_secs: 0,
+        ..Default::default()
+    });
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    let healthy = adapter.healthy.clone();
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+    let mut changes = engine.subscribe_venue_status();
+
+    let order = |client_id: &str| {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.52),
+            100.0,
+            TimeInForce::GTC,
+            client_id.to_string(),
+        )
+    };
+
+    // A failed health check degrades the venue and holds back new entries
+    *healthy.lock().unwrap() = false;
+    let degraded = engine.check_venue_health().await;
+    assert_eq!(degraded[0].to, VenueState::Degraded);
+    let err = engine.submit_order(order("client-1")).await.unwrap_err();
+    assert!(matches!(err, ExecError::VenueUnavailable { .. }));
+
+    engine.check_venue_health().await;
+    assert_eq!(engine.venue_state(&venue_id), VenueState::Down);
+    assert_eq!(engine.venue_statuses()[&venue_id].failed_checks, 2);
+
+    // Recovery resumes order flow
+    *healthy.lock().unwrap() = true;
+    let recovered = engine.check_venue_health().await;
+    assert_eq!((recovered[0].from, recovered[0].to), (VenueState::Down, VenueState::Up));
+    assert!(engine.submit_order(order("client-2")).await.is_ok());
+
+    let published: Vec<VenueState> = std::iter::from_fn(|| changes.try_recv().ok()).map(|c| c.to).collect();
+    assert_eq!(published, vec![VenueState::Degraded, VenueState::Down, VenueState::Up]);
+}
+
+#[tokio::test]
+async fn test_bootstrap_positions_from_venue() {
+    use ag_exec::{bootstrap_positions, PositionBootstrapConfig, PositionSource};
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+
+    // A predecessor left a position and a resting order behind
+    let venue_id = VenueId::new("mock_venue");
+    let mut adapter = MockVenueAdapter::new(venue_id.clone());
+    adapter.positions = Some(HashMap::from([("market-1".to_string(), -40.0), ("market-2".to_string(), 0.0)]));
+    let mut resting = Order::new(
+        venue_id.clone(),
+        MarketId::new("market-3"),
+        Side::Buy,
+        OrderType::Limit,
+        Some(0.40),
+        10.0,
+        TimeInForce::GTC,
+        "resting-1".to_string(),
+    );
+    resting.status = OrderStatus::Working;
+    adapter.open_orders.lock().unwrap().push(resting);
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+
+    let report = bootstrap_positions(&engine, &PositionBootstrapConfig::default()).await.unwrap();
+    assert!(report.is_complete());
+    let venue = report.venue(&venue_id).unwrap();
+    assert_eq!(venue.source, Some(PositionSource::Positions));
+    assert_eq!((venue.open_orders, venue.imported_orders), (1, 1));
+
+    // Flat markets are not imported
+    assert_eq!(report.positions.len(), 1);
+    assert_eq!(engine.get_position("market-1").await, -40.0);
+    assert_eq!(engine.get_active_orders().unwrap().len(), 1);
+
+    // The imported position can be flattened at its venue
+    assert_eq!(engine.position_venue(&MarketId::new("market-1")).unwrap(), venue_id);
+    assert!(engine.flatten_position(&MarketId::new("market-1")).await.unwrap().is_some());
+
+    // Bootstrapping again imports the same orders only once
+    let again = bootstrap_positions(&engine, &PositionBootstrapConfig::default()).await.unwrap();
+    assert_eq!(again.venue(&venue_id).unwrap().imported_orders, 0);
+}
+
+#[tokio::test]
+async fn test_venue_error_handling() {
+    use ag_exec::ratelimit::CooldownConfig;
+    use ag_exec::MockClock;
+
+    let config = ExecutionEngineConfig {
+        enable_risk_checks: false,
+        enable_validation: true,
+        enable_metrics: false,
+    };
+    let mut engine = ExecutionEngine::new(config);
+    let clock = MockClock::new(Utc::now());
+    engine.set_clock(Arc::new(clock.clone()));
+    engine.set_cooldown_config(CooldownConfig {
+        base_ms: 1_000,
+        max_ms: 10_000,
+        multiplier: 2.0,
+    });
+    let venue_id = VenueId::new("mock_venue");
+    let adapter = MockVenueAdapter::new(venue_id.clone());
+    let place_error = adapter.place_error.clone();
+    let rate_limiter = RateLimiterConfig::new(100, 200).build(venue_id.clone());
+    engine.register_adapter(Box::new(adapter), rate_limiter);
+    let mut errors = engine.subscribe_venue_errors();
+
+    let order = |client_id: &str| {
+        Order::new(
+            venue_id.clone(),
+            MarketId::new("market-1"),
+            Side::Buy,
+            OrderType::Limit,
+            Some(0.52),
+            100.0,
+            TimeInForce::GTC,
+            client_id.to_string(),
+        )
+    };
+
+    // A throttle holds back new orders, for longer on each repeat
+    *place_error.lock().unwrap() = Some(VenueErrorKind::RateLimited);
+    let throttled = order("client-1");
+    let err = engine.submit_order(throttled.clone()).await.unwrap_err();
+    assert!(err.is_rate_limit());
+    assert_eq!(engine.get_order(&throttled.id).unwrap().status, OrderStatus::Rejected);
+    assert_eq!(engine.venue_cooldown(&venue_id), Some(std::time::Duration::from_secs(1)));
+    let err = engine.submit_order(order("client-2")).await.unwrap_err();
+    assert!(matches!(err, ExecError::RateLimitExceeded { .. }));
+
+    clock.advance(chrono::Duration::seconds(1));
+    assert!(engine.submit_order(order("client-3")).await.is_err());
+    assert_eq!(engine.venue_cooldown(&venue_id), Some(std::time::Duration::from_secs(2)));
+
+    // An accepted order ends the streak
+    *place_error.lock().unwrap() = None;
+    clock.advance(chrono::Duration::seconds(2));
+    assert!(engine.submit_order(order("client-4")).await.is_ok());
+    assert_eq!(engine.venue_cooldown(&venue_id), None);
+
+    // Unrecognized errors leave the order's outcome open
+    *place_error.lock().unwrap() = Some(VenueErrorKind::Unknown);
+    let unknown = order("client-5");
+    assert!(engine.submit_order(unknown.clone()).await.is_err());
+    assert_eq!(engine.get_order(&unknown.id).unwrap().status, OrderStatus::Submitting);
+
+    *place_error.lock().unwrap() = Some(VenueErrorKind::InsufficientBalance);
+    assert!(engine.submit_order(order("client-6")).await.is_err());
+
+    let published: Vec<VenueErrorKind> = std::iter::from_fn(|| errors.try_recv().ok()).map(|e| e.kind).collect();
+    assert_eq!(
+        published,
+        vec![
+            VenueErrorKind::RateLimited,
+            VenueErrorKind::RateLimited,
+            VenueErrorKind::Unknown,
+            VenueErrorKind::InsufficientBalance
+        ]
+    );
+}
@@ -0,0 +1,141 @@
+// Package protocol defines the wire schema of the /metrics endpoint.
+//
+// It is the Go side of the protocol specified in monitor/protocol/README.md;
+// ag_exec::monitor is the Rust side. Both test suites decode the golden
+// frames in monitor/protocol/v1, so the monitor and the bots cannot drift
+// apart silently.
+package protocol
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+const (
+	// Name is the protocol name carried in a hello
+	Name = "ag-monitor"
+
+	// Version is the latest protocol version the monitor speaks
+	Version = 1
+
+	// MinVersion is the oldest version accepted in a hello
+	MinVersion = 1
+
+	// LegacyVersion is assumed for clients that send metrics without a hello
+	LegacyVersion = 0
+)
+
+// Message types
+const (
+	TypeHello    = "hello"
+	TypeMetric   = "metric"
+	TypeWelcome  = "welcome"
+	TypeRejected = "rejected"
+)
+
+// Hello opens a session (client to server)
+type Hello struct {
+	Protocol string `json:"protocol"`
+	Version  int    `json:"version"`
+	Client   string `json:"client,omitempty"`
+}
+
+// Metric is one metric sample (client to server)
+type Metric struct {
+	Timestamp  int64             `json:"timestamp"`
+	MetricType string            `json:"metric_type"`
+	MetricName string            `json:"metric_name"`
+	Value      float64           `json:"value"`
+	Labels     map[string]string `json:"labels"`
+}
+
+// Welcome accepts a hello (server to client)
+type Welcome struct {
+	Type    string `json:"type"`
+	Version int    `json:"version"`
+	Server  string `json:"server,omitempty"`
+}
+
+// Rejected refuses a hello; the server closes the connection after it
+type Rejected struct {
+	Type       string `json:"type"`
+	Reason     string `json:"reason"`
+	MinVersion int    `json:"min_version"`
+	MaxVersion int    `json:"max_version"`
+}
+
+// ClientMessage is a decoded client frame: exactly one field is set
+type ClientMessage struct {
+	Hello  *Hello
+	Metric *Metric
+}
+
+// NewWelcome answers a hello with the negotiated version
+func NewWelcome(version int) Welcome {
+	return Welcome{Type: TypeWelcome, Version: version, Server: Name}
+}
+
+// NewRejected refuses a hello with the reason returned by Negotiate
+func NewRejected(reason error) Rejected {
+	return Rejected{Type: TypeRejected, Reason: reason.Error(), MinVersion: MinVersion, MaxVersion: Version}
+}
+
+// DecodeClient decodes a client frame. Frames without a type are legacy
+// (version 0) metrics. Unknown fields are ignored.
+func DecodeClient(data []byte) (ClientMessage, error) {
+	var envelope struct {
+		Type string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &envelope); err != nil {
+		return ClientMessage{}, err
+	}
+
+	switch envelope.Type {
+	case TypeHello:
+		var hello Hello
+		if err := json.Unmarshal(data, &hello); err != nil {
+			return ClientMessage{}, err
+		}
+		return ClientMessage{Hello: &hello}, nil
+	case TypeMetric, "":
+		var metric Metric
+		if err := json.Unmarshal(data, &metric); err != nil {
+			return ClientMessage{}, err
+		}
+		if err := metric.Validate(); err != nil {
+			return ClientMessage{}, err
+		}
+		return ClientMessage{Metric: &metric}, nil
+	default:
+		return ClientMessage{}, fmt.Errorf("unknown message type %q", envelope.Type)
+	}
+}
+
+// Validate checks the fields every metric must carry
+func (m *Metric) Validate() error {
+	switch m.MetricType {
+	case "counter", "gauge", "histogram":
+	default:
+		return fmt.Errorf("unknown metric_type %q", m.MetricType)
+	}
+	if m.MetricName == "" {
+		return fmt.Errorf("missing metric_name")
+	}
+	return nil
+}
+
+// Negotiate returns the version to speak with a client, or the reason the
+// hello is refused. It mirrors ag_exec::monitor::negotiate.
+func Negotiate(hello Hello) (int, error) {
+	if hello.Protocol != Name {
+		return 0, fmt.Errorf("unknown protocol %q, expected %q", hello.Protocol, Name)
+	}
+	if hello.Version < MinVersion {
+		return 0, fmt.Errorf("protocol version %d is older than the supported range %d-%d",
+			hello.Version, MinVersion, Version)
+	}
+	if hello.Version > Version {
+		return Version, nil
+	}
+	return hello.Version, nil
+}
@@ -0,0 +1,109 @@
+package protocol
+
+import (
+	"encoding/json"
+	"os"
+	"reflect"
+	"testing"
+)
+
+// golden reads a frame shared with the Rust tests
+func golden(t *testing.T, name string) []byte {
+	t.Helper()
+	data, err := os.ReadFile("../../protocol/v1/" + name)
+	if err != nil {
+		t.Fatalf("Failed to read golden frame %s: %v", name, err)
+	}
+	return data
+}
+
+// assertEncodes checks that v encodes to the golden frame, field for field
+func assertEncodes(t *testing.T, v interface{}, name string) {
+	t.Helper()
+	encoded, err := json.Marshal(v)
+	if err != nil {
+		t.Fatalf("Failed to encode %s: %v", name, err)
+	}
+	var got, want map[string]interface{}
+	json.Unmarshal(encoded, &got)
+	json.Unmarshal(golden(t, name), &want)
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("Encoded %s = %s, want %s", name, encoded, golden(t, name))
+	}
+}
+
+func TestDecodeClient_Golden(t *testing.T) {
+	msg, err := DecodeClient(golden(t, "hello.json"))
+	if err != nil || msg.Hello == nil {
+		t.Fatalf("Expected hello, got %+v (err: %v)", msg, err)
+	}
+	if msg.Hello.Protocol != Name || msg.Hello.Version != 1 || msg.Hello.Client != "minibot/0.1.0" {
+		t.Errorf("Unexpected hello: %+v", msg.Hello)
+	}
+
+	msg, err = DecodeClient(golden(t, "metric.json"))
+	if err != nil || msg.Metric == nil {
+		t.Fatalf("Expected metric, got %+v (err: %v)", msg, err)
+	}
+	if msg.Metric.MetricType != "gauge" || msg.Metric.Value != 45.3 || msg.Metric.Labels["topic"] != "market" {
+		t.Errorf("Unexpected metric: %+v", msg.Metric)
+	}
+
+	// Untagged frames are legacy metrics
+	msg, err = DecodeClient(golden(t, "legacy_metric.json"))
+	if err != nil || msg.Metric == nil {
+		t.Fatalf("Expected legacy metric, got %+v (err: %v)", msg, err)
+	}
+	if msg.Metric.MetricType != "counter" || msg.Metric.Value != 12 {
+		t.Errorf("Unexpected legacy metric: %+v", msg.Metric)
+	}
+}
+
+func TestDecodeClient_Invalid(t *testing.T) {
+	frames := []string{
+		`{"type":"subscribe"}`,
+		`{"type":"metric","metric_type":"summary","metric_name":"x","value":1}`,
+		`{"type":"metric","metric_type":"gauge","value":1}`,
+		`not json`,
+	}
+	for _, frame := range frames {
+		if _, err := DecodeClient([]byte(frame)); err == nil {
+			t.Errorf("Expected error decoding %s", frame)
+		}
+	}
+
+	// Fields added later within the version are ignored
+	extended := `{"type":"metric","timestamp":1,"metric_type":"gauge","metric_name":"x","value":1,"unit":"ms"}`
+	if _, err := DecodeClient([]byte(extended)); err != nil {
+		t.Errorf("Unexpected error decoding extended metric: %v", err)
+	}
+}
+
+func TestServerMessages_Golden(t *testing.T) {
+	assertEncodes(t, NewWelcome(1), "welcome.json")
+
+	_, err := Negotiate(Hello{Protocol: Name, Version: 0})
+	if err == nil {
+		t.Fatal("Expected version 0 hello to be rejected")
+	}
+	assertEncodes(t, NewRejected(err), "rejected.json")
+}
+
+func TestNegotiate(t *testing.T) {
+	tests := []struct {
+		hello   Hello
+		version int
+		ok      bool
+	}{
+		{Hello{Protocol: Name, Version: 1}, 1, true},
+		{Hello{Protocol: Name, Version: Version + 1}, Version, true},
+		{Hello{Protocol: Name, Version: 0}, 0, false},
+		{Hello{Protocol: "other", Version: 1}, 0, false},
+	}
+	for _, tt := range tests {
+		version, err := Negotiate(tt.hello)
+		if (err == nil) != tt.ok || version != tt.version {
+			t.Errorf("Negotiate(%+v) = %d, %v; want %d, ok=%v", tt.hello, version, err, tt.version, tt.ok)
+		}
+	}
+}
@@ -7,6 +7,7 @@ import (
 	"sync"
 	"time"
 
+	"github.com/ag-botkit/monitor/internal/protocol"
 	"github.com/ag-botkit/monitor/internal/storage"
 	"github.com/gorilla/websocket"
 )
@@ -109,6 +110,7 @@ func (h *Hub) HandleMetricsWS(w http.ResponseWriter, r *http.Request) {
 
 	log.Printf("Metrics client connected from %s", r.RemoteAddr)
 
+	version := -1 // protocol version, set by the hello or the first metric
 	for {
 		_, message, err := conn.ReadMessage()
 		if err != nil {
@@ -118,13 +120,42 @@ func (h *Hub) HandleMetricsWS(w http.ResponseWriter, r *http.Request) {
 			break
 		}
 
-		// Parse metric
-		var metric storage.MetricPoint
-		if err := json.Unmarshal(message, &metric); err != nil {
+		msg, err := protocol.DecodeClient(message)
+		if err != nil {
 			log.Printf("Error parsing metric: %v (message: %s)", err, string(message))
 			continue
 		}
 
+		// Handshake: answer with the negotiated version, or refuse and close
+		if msg.Hello != nil {
+			negotiated, err := protocol.Negotiate(*msg.Hello)
+			if err != nil {
+				log.Printf("Rejected metrics client %s (%s): %v", r.RemoteAddr, msg.Hello.Client, err)
+				conn.WriteJSON(protocol.NewRejected(err))
+				break
+			}
+			version = negotiated
+			if err := conn.WriteJSON(protocol.NewWelcome(version)); err != nil {
+				log.Printf("Error sending welcome: %v", err)
+				break
+			}
+			log.Printf("Metrics client %s (%s) speaks protocol v%d", r.RemoteAddr, msg.Hello.Client, version)
+			continue
+		}
+
+		if version < 0 {
+			version = protocol.LegacyVersion
+			log.Printf("Warning: metrics client %s sent no hello, assuming legacy protocol v%d", r.RemoteAddr, version)
+		}
+
+		metric := storage.MetricPoint{
+			Timestamp:  msg.Metric.Timestamp,
+			MetricType: msg.Metric.MetricType,
+			MetricName: msg.Metric.MetricName,
+			Value:      msg.Metric.Value,
+			Labels:     msg.Metric.Labels,
+		}
+
 		// Store metric
 		h.store.Append(metric)
 
@@ -0,0 +1,138 @@
+"""Python bindings for ag-botkit backtesting and risk analytics."""
+
+from typing import Mapping, Sequence, TypedDict
+
+from typing_extensions import NotRequired
+
+__version__: str
+
+class BotkitError(Exception):
+    """Error raised by ag-botkit."""
+
+class Tick(TypedDict):
+    market: str
+    timestamp: float
+    bid: NotRequired[float | None]
+    ask: NotRequired[float | None]
+    bid_size: NotRequired[float | None]
+    ask_size: NotRequired[float | None]
+    last: NotRequired[float | None]
+    volume_24h: NotRequired[float | None]
+
+class TradeDict(TypedDict):
+    id: str
+    market: str
+    price: float
+    size: float
+    side: str
+    fee: float
+    timestamp: float
+
+class PositionDetails(TypedDict):
+    size: float
+    avg_price: float
+    current_price: float
+    invested_capital: float
+    unrealized_pnl: float
+
+class VarEngine:
+    def __init__(
+        self,
+        min_observations: int = 30,
+        simulations: int = 10_000,
+        seed: int | None = None,
+        returns: Sequence[float] | None = None,
+    ) -> None: ...
+    def add_return(self, value: float) -> None: ...
+    def update_returns(self, returns: Sequence[float]) -> None: ...
+    def historical_var(
+        self, portfolio_value: float, confidence: float = 0.95, horizon_days: int = 1
+    ) -> float: ...
+    def parametric_var(
+        self,
+        portfolio_value: float,
+        volatility: float,
+        confidence: float = 0.95,
+        horizon_days: int = 1,
+    ) -> float: ...
+    def monte_carlo_var(
+        self,
+        portfolio_value: float,
+        mean_return: float,
+        volatility: float,
+        confidence: float = 0.95,
+        horizon_days: int = 1,
+        simulations: int | None = None,
+    ) -> float: ...
+    def cvar(
+        self, portfolio_value: float, confidence: float = 0.95, horizon_days: int = 1
+    ) -> float: ...
+
+class PerformanceMetrics:
+    def __init__(self, returns: Sequence[float], risk_free_rate: float = 0.0) -> None: ...
+    def sharpe_ratio(self) -> float: ...
+    def sortino_ratio(self, minimum_acceptable_return: float = 0.0) -> float: ...
+    def max_drawdown(self) -> float: ...
+    def calmar_ratio(self) -> float: ...
+    def beta(self, market_returns: Sequence[float]) -> float: ...
+    def alpha(self, market_returns: Sequence[float]) -> float: ...
+    def win_rate(self) -> float: ...
+    def profit_factor(self) -> float: ...
+    def tracking_error(self, market_returns: Sequence[float]) -> float: ...
+
+class PolymarketSimulator:
+    def __init__(self) -> None: ...
+    def update_position(self, market_id: str, size: float, price: float) -> None: ...
+    def get_position(self, market_id: str) -> float: ...
+    def get_avg_price(self, market_id: str) -> float: ...
+    def get_unrealized_pnl(self, market_id: str) -> float: ...
+    def get_inventory_value_usd(self) -> float: ...
+    def get_total_pnl(self) -> float: ...
+    def get_active_markets(self) -> list[str]: ...
+    def get_position_details(self, market_id: str) -> PositionDetails | None: ...
+    def reset(self) -> None: ...
+
+class BacktestResult:
+    @property
+    def total_return(self) -> float: ...
+    @property
+    def total_return_pct(self) -> float: ...
+    @property
+    def sharpe_ratio(self) -> float: ...
+    @property
+    def max_drawdown(self) -> float: ...
+    @property
+    def max_drawdown_pct(self) -> float: ...
+    @property
+    def win_rate(self) -> float: ...
+    @property
+    def num_trades(self) -> int: ...
+    @property
+    def avg_trade_pnl(self) -> float: ...
+    @property
+    def final_capital(self) -> float: ...
+    @property
+    def pnl_by_day(self) -> list[tuple[float, float]]: ...
+    @property
+    def trades(self) -> list[TradeDict]: ...
+
+class BacktestEngine:
+    def __init__(
+        self,
+        initial_capital: float = 10_000.0,
+        risk_policy_yaml: str | None = None,
+        slippage_bps: float = 5.0,
+        fill_probability: float = 0.8,
+        taker_fee_bps: float = 10.0,
+        maker_fee_bps: float = -5.0,
+        adverse_selection: float = 0.0,
+        adverse_horizon_ticks: int = 5,
+    ) -> None: ...
+    def strategies(self) -> list[str]: ...
+    def run(
+        self,
+        strategy: str,
+        markets: Sequence[str],
+        ticks: Sequence[Tick],
+        params: Mapping[str, object] | None = None,
+    ) -> BacktestResult: ...
@@ -0,0 +1,246 @@
+//! Backtesting bindings
+
+// pyo3 0.20 expands #[pymethods] into impls nested in a const item
+#![allow(non_local_definitions)]
+
+use crate::to_py_err;
+use ag_strategies::backtest::{BacktestConfig, BacktestEngine, BacktestResult, FillSimulatorConfig};
+use ag_strategies::{MarketTick, StrategyParams, StrategyRegistry};
+use chrono::{TimeZone, Utc};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::HashMap;
+
+/// Event-driven backtesting engine
+///
+/// Strategies are created by name from the built-in registry
+/// (`market_maker`, `cross_market_arb`).
+#[pyclass(name = "BacktestEngine", module = "agbotkit")]
+pub struct PyBacktestEngine {
+    inner: BacktestEngine,
+    registry: StrategyRegistry,
+}
+
+#[pymethods]
+impl PyBacktestEngine {
+    #[new]
+    #[pyo3(signature = (
+        initial_capital = 10_000.0,
+        risk_policy_yaml = None,
+        slippage_bps = 5.0,
+        fill_probability = 0.8,
+        taker_fee_bps = 10.0,
+        maker_fee_bps = -5.0,
+        adverse_selection = 0.0,
+        adverse_horizon_ticks = 5,
+    ))]
+    #[allow(clippy::too_many_arguments)] // Python keyword arguments
+    fn new(
+        initial_capital: f64,
+        risk_policy_yaml: Option<String>,
+        slippage_bps: f64,
+        fill_probability: f64,
+        taker_fee_bps: f64,
+        maker_fee_bps: f64,
+        adverse_selection: f64,
+        adverse_horizon_ticks: usize,
+    ) -> PyResult<Self> {
+        let defaults = BacktestConfig::default();
+        let config = BacktestConfig {
+            initial_capital,
+            fill_simulator: FillSimulatorConfig {
+                slippage_bps,
+                fill_probability,
+                taker_fee_bps,
+                maker_fee_bps,
+                adverse_selection,
+                adverse_horizon_ticks,
+                seed: None,
+            },
+            risk_policy_yaml: risk_policy_yaml.unwrap_or(defaults.risk_policy_yaml),
+            hygiene: defaults.hygiene,
+        };
+
+        Ok(Self {
+            inner: BacktestEngine::new(config).map_err(to_py_err)?,
+            registry: StrategyRegistry::with_builtins(),
+        })
+    }
+
+    /// Names of strategies available to `run`
+    fn strategies(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
+    /// Run a backtest
+    ///
+    /// `ticks` is a list of dicts with `market`, `timestamp` (epoch seconds)
+    /// and optional `bid`, `ask`, `bid_size`, `ask_size`, `last`,
+    /// `volume_24h`. Parameter values are converted with `str()`.
+    /// Releases the GIL while the backtest runs.
+    #[pyo3(signature = (strategy, markets, ticks, params = None))]
+    fn run(
+        &mut self,
+        py: Python<'_>,
+        strategy: &str,
+        markets: Vec<String>,
+        ticks: Vec<&PyDict>,
+        params: Option<HashMap<String, &PyAny>>,
+    ) -> PyResult<PyBacktestResult> {
+        let ticks = ticks
+            .into_iter()
+            .map(tick_from_dict)
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let mut strategy_params = StrategyParams::new();
+        for (key, value) in params.unwrap_or_default() {
+            strategy_params.set(key, value.str()?.to_string());
+        }
+
+        let strategy = self
+            .registry
+            .create(strategy, &strategy_params, &markets)
+            .map_err(to_py_err)?;
+
+        let engine = &mut self.inner;
+        let result = py.allow_threads(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(to_py_err)?;
+            runtime
+                .block_on(engine.run_backtest(strategy, ticks, strategy_params))
+                .map_err(to_py_err)
+        })?;
+
+        Ok(PyBacktestResult { inner: result })
+    }
+}
+
+/// Backtest result
+#[pyclass(name = "BacktestResult", module = "agbotkit")]
+pub struct PyBacktestResult {
+    inner: BacktestResult,
+}
+
+#[pymethods]
+impl PyBacktestResult {
+    #[getter]
+    fn total_return(&self) -> f64 {
+        self.inner.total_return
+    }
+
+    #[getter]
+    fn total_return_pct(&self) -> f64 {
+        self.inner.total_return_pct
+    }
+
+    #[getter]
+    fn sharpe_ratio(&self) -> f64 {
+        self.inner.sharpe_ratio
+    }
+
+    #[getter]
+    fn max_drawdown(&self) -> f64 {
+        self.inner.max_drawdown
+    }
+
+    #[getter]
+    fn max_drawdown_pct(&self) -> f64 {
+        self.inner.max_drawdown_pct
+    }
+
+    #[getter]
+    fn win_rate(&self) -> f64 {
+        self.inner.win_rate
+    }
+
+    #[getter]
+    fn num_trades(&self) -> usize {
+        self.inner.num_trades
+    }
+
+    #[getter]
+    fn avg_trade_pnl(&self) -> f64 {
+        self.inner.avg_trade_pnl
+    }
+
+    #[getter]
+    fn final_capital(&self) -> f64 {
+        self.inner.final_capital
+    }
+
+    /// Daily PnL as `(epoch_seconds, pnl)` tuples
+    #[getter]
+    fn pnl_by_day(&self) -> Vec<(f64, f64)> {
+        self.inner
+            .pnl_by_day
+            .iter()
+            .map(|(day, pnl)| (epoch_seconds(day), *pnl))
+            .collect()
+    }
+
+    /// Trades as dicts (`id`, `market`, `price`, `size`, `side`, `fee`, `timestamp`)
+    #[getter]
+    fn trades<'py>(&self, py: Python<'py>) -> PyResult<&'py PyList> {
+        let list = PyList::empty(py);
+        for trade in &self.inner.trades {
+            let dict = PyDict::new(py);
+            dict.set_item("id", &trade.id)?;
+            dict.set_item("market", &trade.market)?;
+            dict.set_item("price", trade.price)?;
+            dict.set_item("size", trade.size)?;
+            dict.set_item("side", trade.side.to_string().to_lowercase())?;
+            dict.set_item("fee", trade.fee)?;
+            dict.set_item("timestamp", epoch_seconds(&trade.timestamp))?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BacktestResult(total_return={:.2}, sharpe_ratio={:.3}, max_drawdown_pct={:.2}, num_trades={})",
+            self.inner.total_return,
+            self.inner.sharpe_ratio,
+            self.inner.max_drawdown_pct,
+            self.inner.num_trades,
+        )
+    }
+}
+
+fn tick_from_dict(dict: &PyDict) -> PyResult<MarketTick> {
+    let required = |key: &str| {
+        dict.get_item(key)?
+            .ok_or_else(|| PyValueError::new_err(format!("tick is missing '{}'", key)))
+    };
+    let optional = |key: &str| -> PyResult<Option<f64>> {
+        match dict.get_item(key)? {
+            Some(value) if !value.is_none() => Ok(Some(value.extract()?)),
+            _ => Ok(None),
+        }
+    };
+
+    let seconds: f64 = required("timestamp")?.extract()?;
+    let timestamp = Utc
+        .timestamp_millis_opt((seconds * 1000.0) as i64)
+        .single()
+        .ok_or_else(|| PyValueError::new_err(format!("invalid tick timestamp {}", seconds)))?;
+
+    Ok(MarketTick {
+        market: required("market")?.extract()?,
+        timestamp,
+        bid: optional("bid")?,
+        bid_size: optional("bid_size")?,
+        ask: optional("ask")?,
+        ask_size: optional("ask_size")?,
+        last: optional("last")?,
+        volume_24h: optional("volume_24h")?,
+        sequence: None,
+    })
+}
+
+fn epoch_seconds(timestamp: &chrono::DateTime<Utc>) -> f64 {
+    timestamp.timestamp_millis() as f64 / 1000.0
+}
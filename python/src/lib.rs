@@ -0,0 +1,38 @@
+//! # agbotkit - Python bindings for ag-botkit
+//!
+//! Exposes the backtesting engine and risk analytics to Python so research
+//! notebooks run the exact same code as production bots:
+//!
+//! - [`VarEngine`](risk::PyVarEngine): Historical, Parametric, Monte Carlo VaR and CVaR
+//! - [`PerformanceMetrics`](risk::PyPerformanceMetrics): Sharpe, Sortino, drawdown, ...
+//! - [`PolymarketSimulator`](risk::PyPolymarketSimulator): position and PnL tracking
+//! - [`BacktestEngine`](backtest::PyBacktestEngine): event-driven strategy backtests
+//!
+//! Build a wheel with `maturin build --release` (see `pyproject.toml`); typed
+//! stubs live in `agbotkit.pyi`.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+mod backtest;
+mod risk;
+
+create_exception!(agbotkit, BotkitError, PyException, "Error raised by ag-botkit");
+
+/// Convert any displayable core error into a Python `BotkitError`
+pub(crate) fn to_py_err(err: impl std::fmt::Display) -> PyErr {
+    BotkitError::new_err(err.to_string())
+}
+
+#[pymodule]
+fn agbotkit(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("BotkitError", py.get_type::<BotkitError>())?;
+    m.add_class::<risk::PyVarEngine>()?;
+    m.add_class::<risk::PyPerformanceMetrics>()?;
+    m.add_class::<risk::PyPolymarketSimulator>()?;
+    m.add_class::<backtest::PyBacktestEngine>()?;
+    m.add_class::<backtest::PyBacktestResult>()?;
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Risk analytics bindings
+
+// pyo3 0.20 expands #[pymethods] into impls nested in a const item
+#![allow(non_local_definitions)]
+
+use crate::to_py_err;
+use ag_risk::advanced::{PerformanceMetrics, VarConfig, VarEngine};
+use ag_risk::PolymarketSimulator;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Value at Risk engine
+#[pyclass(name = "VarEngine", module = "agbotkit")]
+pub struct PyVarEngine {
+    inner: VarEngine,
+    simulations: usize,
+}
+
+#[pymethods]
+impl PyVarEngine {
+    #[new]
+    #[pyo3(signature = (min_observations = 30, simulations = 10_000, seed = None, returns = None))]
+    fn new(
+        min_observations: usize,
+        simulations: usize,
+        seed: Option<u64>,
+        returns: Option<Vec<f64>>,
+    ) -> Self {
+        let config = VarConfig {
+            default_simulations: simulations,
+            min_observations,
+            random_seed: seed,
+        };
+        Self {
+            inner: VarEngine::with_historical_returns(config, returns.unwrap_or_default()),
+            simulations,
+        }
+    }
+
+    /// Append a single return observation
+    fn add_return(&mut self, value: f64) {
+        self.inner.add_return(value);
+    }
+
+    /// Replace the historical return window
+    fn update_returns(&mut self, returns: Vec<f64>) {
+        self.inner.update_historical_returns(returns);
+    }
+
+    /// Historical VaR amount (positive = potential loss)
+    #[pyo3(signature = (portfolio_value, confidence = 0.95, horizon_days = 1))]
+    fn historical_var(&self, portfolio_value: f64, confidence: f64, horizon_days: u32) -> PyResult<f64> {
+        self.inner
+            .calculate_historical_var(portfolio_value, confidence, horizon_days)
+            .map(|r| r.var_amount)
+            .map_err(to_py_err)
+    }
+
+    /// Parametric (variance-covariance) VaR amount
+    #[pyo3(signature = (portfolio_value, volatility, confidence = 0.95, horizon_days = 1))]
+    fn parametric_var(
+        &self,
+        portfolio_value: f64,
+        volatility: f64,
+        confidence: f64,
+        horizon_days: u32,
+    ) -> PyResult<f64> {
+        self.inner
+            .calculate_parametric_var(portfolio_value, volatility, confidence, horizon_days)
+            .map(|r| r.var_amount)
+            .map_err(to_py_err)
+    }
+
+    /// Monte Carlo VaR amount; `simulations` defaults to the engine setting
+    ///
+    /// Releases the GIL while sampling.
+    #[pyo3(signature = (portfolio_value, mean_return, volatility, confidence = 0.95, horizon_days = 1, simulations = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn monte_carlo_var(
+        &self,
+        py: Python<'_>,
+        portfolio_value: f64,
+        mean_return: f64,
+        volatility: f64,
+        confidence: f64,
+        horizon_days: u32,
+        simulations: Option<usize>,
+    ) -> PyResult<f64> {
+        py.allow_threads(|| {
+            self.inner.calculate_monte_carlo_var(
+                portfolio_value,
+                mean_return,
+                volatility,
+                confidence,
+                horizon_days,
+                simulations.unwrap_or(self.simulations),
+            )
+        })
+        .map(|r| r.var_amount)
+        .map_err(to_py_err)
+    }
+
+    /// Conditional VaR (expected shortfall) from historical returns
+    #[pyo3(signature = (portfolio_value, confidence = 0.95, horizon_days = 1))]
+    fn cvar(&self, portfolio_value: f64, confidence: f64, horizon_days: u32) -> PyResult<f64> {
+        self.inner
+            .calculate_cvar(portfolio_value, confidence, horizon_days)
+            .map_err(to_py_err)
+    }
+}
+
+/// Performance metrics over a return series
+#[pyclass(name = "PerformanceMetrics", module = "agbotkit")]
+pub struct PyPerformanceMetrics {
+    inner: PerformanceMetrics,
+}
+
+#[pymethods]
+impl PyPerformanceMetrics {
+    #[new]
+    #[pyo3(signature = (returns, risk_free_rate = 0.0))]
+    fn new(returns: Vec<f64>, risk_free_rate: f64) -> Self {
+        Self {
+            inner: PerformanceMetrics::new(returns, risk_free_rate),
+        }
+    }
+
+    fn sharpe_ratio(&self) -> PyResult<f64> {
+        self.inner.sharpe_ratio().map_err(to_py_err)
+    }
+
+    #[pyo3(signature = (minimum_acceptable_return = 0.0))]
+    fn sortino_ratio(&self, minimum_acceptable_return: f64) -> PyResult<f64> {
+        self.inner
+            .sortino_ratio(minimum_acceptable_return)
+            .map_err(to_py_err)
+    }
+
+    fn max_drawdown(&self) -> PyResult<f64> {
+        self.inner.max_drawdown().map_err(to_py_err)
+    }
+
+    fn calmar_ratio(&self) -> PyResult<f64> {
+        self.inner.calmar_ratio().map_err(to_py_err)
+    }
+
+    fn beta(&self, market_returns: Vec<f64>) -> PyResult<f64> {
+        self.inner.beta(&market_returns).map_err(to_py_err)
+    }
+
+    fn alpha(&self, market_returns: Vec<f64>) -> PyResult<f64> {
+        self.inner.alpha(&market_returns).map_err(to_py_err)
+    }
+
+    fn win_rate(&self) -> PyResult<f64> {
+        self.inner.win_rate().map_err(to_py_err)
+    }
+
+    fn profit_factor(&self) -> PyResult<f64> {
+        self.inner.profit_factor().map_err(to_py_err)
+    }
+
+    fn tracking_error(&self, market_returns: Vec<f64>) -> PyResult<f64> {
+        self.inner.tracking_error(&market_returns).map_err(to_py_err)
+    }
+}
+
+/// Polymarket position and PnL simulator
+#[pyclass(name = "PolymarketSimulator", module = "agbotkit")]
+#[derive(Default)]
+pub struct PyPolymarketSimulator {
+    inner: PolymarketSimulator,
+}
+
+#[pymethods]
+impl PyPolymarketSimulator {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a fill (positive size = buy YES, negative = sell)
+    fn update_position(&mut self, market_id: &str, size: f64, price: f64) {
+        self.inner.update_position(market_id, size, price);
+    }
+
+    fn get_position(&self, market_id: &str) -> f64 {
+        self.inner.get_position(market_id)
+    }
+
+    fn get_avg_price(&self, market_id: &str) -> f64 {
+        self.inner.get_avg_price(market_id)
+    }
+
+    fn get_unrealized_pnl(&self, market_id: &str) -> f64 {
+        self.inner.get_unrealized_pnl(market_id)
+    }
+
+    fn get_inventory_value_usd(&self) -> f64 {
+        self.inner.get_inventory_value_usd()
+    }
+
+    fn get_total_pnl(&self) -> f64 {
+        self.inner.get_total_pnl()
+    }
+
+    fn get_active_markets(&self) -> Vec<String> {
+        self.inner.get_active_markets()
+    }
+
+    /// Position details as a dict, or None if the market has no position
+    fn get_position_details<'py>(&self, py: Python<'py>, market_id: &str) -> PyResult<Option<&'py PyDict>> {
+        let Some(details) = self.inner.get_position_details(market_id) else {
+            return Ok(None);
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("size", details.size)?;
+        dict.set_item("avg_price", details.avg_price)?;
+        dict.set_item("current_price", details.current_price)?;
+        dict.set_item("invested_capital", details.invested_capital)?;
+        dict.set_item("unrealized_pnl", details.unrealized_pnl)?;
+        Ok(Some(dict))
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
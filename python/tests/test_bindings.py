@@ -0,0 +1,73 @@
+"""Smoke tests for the agbotkit extension (run after `maturin develop`)."""
+
+import math
+
+import pytest
+
+import agbotkit
+
+
+def test_var_engine():
+    returns = [0.01 * math.sin(i) for i in range(100)]
+    engine = agbotkit.VarEngine(seed=42, returns=returns)
+
+    assert engine.historical_var(10_000.0, 0.95) > 0.0
+    assert engine.parametric_var(10_000.0, 0.02) > 0.0
+    assert engine.monte_carlo_var(10_000.0, 0.0, 0.02, simulations=1_000) > 0.0
+    assert engine.cvar(10_000.0, 0.95) >= engine.historical_var(10_000.0, 0.95)
+
+
+def test_var_engine_insufficient_data():
+    engine = agbotkit.VarEngine(returns=[0.01, -0.02])
+    with pytest.raises(agbotkit.BotkitError):
+        engine.historical_var(10_000.0)
+
+
+def test_performance_metrics():
+    metrics = agbotkit.PerformanceMetrics([0.01, -0.005, 0.02, -0.01, 0.015])
+
+    assert metrics.sharpe_ratio() > 0.0
+    assert 0.0 <= metrics.win_rate() <= 1.0
+    assert metrics.max_drawdown() < 0.0
+
+
+def test_polymarket_simulator():
+    sim = agbotkit.PolymarketSimulator()
+    sim.update_position("0xabc", 100.0, 0.6)
+
+    assert sim.get_position("0xabc") == pytest.approx(100.0)
+    assert sim.get_active_markets() == ["0xabc"]
+    assert sim.get_position_details("0xabc")["avg_price"] == pytest.approx(0.6)
+    assert sim.get_position_details("missing") is None
+
+    sim.reset()
+    assert sim.get_active_markets() == []
+
+
+def test_backtest_market_maker():
+    ticks = [
+        {
+            "market": "m1",
+            "timestamp": 1_700_000_000.0 + i,
+            "bid": 0.49 + 0.001 * (i % 5),
+            "ask": 0.51 + 0.001 * (i % 5),
+            "bid_size": 100.0,
+            "ask_size": 100.0,
+        }
+        for i in range(200)
+    ]
+
+    engine = agbotkit.BacktestEngine(initial_capital=5_000.0)
+    assert "market_maker" in engine.strategies()
+
+    result = engine.run("market_maker", ["m1"], ticks, {"quote_size": 10})
+    assert result.final_capital > 0.0
+    assert result.num_trades == len(result.trades)
+
+
+def test_backtest_errors():
+    engine = agbotkit.BacktestEngine()
+    with pytest.raises(agbotkit.BotkitError):
+        engine.run("does_not_exist", ["m1"], [{"market": "m1", "timestamp": 0.0}])
+    with pytest.raises(ValueError):
+        engine.run("market_maker", ["m1"], [{"market": "m1"}])
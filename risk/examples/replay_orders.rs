@@ -0,0 +1,65 @@
+//! Replay a recorded order history through a policy file
+//!
+//! Usage: cargo run --example replay_orders -- <policy.yaml> <history.jsonl> [--all]
+//!
+//! Prints each order whose replayed decision differs from the recorded one
+//! (every order with `--all`), then a summary. To check a past policy
+//! version, export it first, e.g.
+//! `git show <commit>:config/risk.yaml > /tmp/risk.yaml`.
+
+use ag_risk::replay::{self, ReplayVerdict};
+use ag_risk::RiskPolicyConfig;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("replay_orders: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let all = args.iter().any(|arg| arg == "--all");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--all").collect();
+    let [policy_path, history_path] = paths.as_slice() else {
+        return Err("usage: replay_orders <policy.yaml> <history.jsonl> [--all]".to_string());
+    };
+
+    let policy = std::fs::read_to_string(policy_path).map_err(|e| format!("{}: {}", policy_path, e))?;
+    let config: RiskPolicyConfig = serde_yaml::from_str(&policy).map_err(|e| format!("Failed to parse YAML: {}", e))?;
+    let history = std::fs::read_to_string(history_path).map_err(|e| format!("{}: {}", history_path, e))?;
+    let records = replay::parse_jsonl(&history)?;
+
+    let report = replay::replay(config, &records);
+    for outcome in &report.outcomes {
+        if !all
+            && !matches!(
+                outcome.verdict,
+                ReplayVerdict::NowRejected | ReplayVerdict::NowAllowed | ReplayVerdict::DifferentViolations
+            )
+        {
+            continue;
+        }
+        let violations: Vec<&str> = outcome.replayed.violations.iter().map(|v| v.message.as_str()).collect();
+        println!(
+            "{} {:<12} {} {:+} (position {}) {:?}: {}",
+            outcome.at.to_rfc3339(),
+            outcome.order_id.as_deref().unwrap_or("-"),
+            outcome.context.market_id,
+            outcome.context.proposed_size,
+            outcome.context.current_position,
+            outcome.verdict,
+            if outcome.replayed.allowed { "allowed".to_string() } else { violations.join("; ") }
+        );
+    }
+    println!(
+        "{} orders: {} unchanged, {} now rejected, {} now allowed, {} different violations, {} unrecorded",
+        report.outcomes.len(),
+        report.count(ReplayVerdict::Unchanged),
+        report.count(ReplayVerdict::NowRejected),
+        report.count(ReplayVerdict::NowAllowed),
+        report.count(ReplayVerdict::DifferentViolations),
+        report.count(ReplayVerdict::Unrecorded)
+    );
+    Ok(())
+}
@@ -6,9 +6,10 @@
 //!
 //! ## Modules
 //!
-//! - `var`: Value at Risk (Historical, Parametric, Monte Carlo, CVaR)
+//! - `var`: Value at Risk (Historical, Parametric, Monte Carlo, CVaR, marginal CVaR)
 //! - `greeks`: Options Greeks calculation (Delta, Gamma, Vega, Theta, Rho)
 //! - `portfolio`: Portfolio analytics and risk decomposition
+//! - `optimizer`: Mean-variance / CVaR-capped allocation across markets
 //! - `stress`: Stress testing and scenario analysis
 //! - `metrics`: Performance metrics (Sharpe, Sortino, drawdown)
 //! - `error`: Advanced risk error types
@@ -17,12 +18,15 @@ mod error;
 mod var;
 mod greeks;
 mod portfolio;
+mod optimizer;
 mod stress;
 mod metrics;
 
 pub use error::AdvancedRiskError;
-pub use var::{VarEngine, VarConfig, VarResult, VarMethod, VarBacktestResult};
+pub use var::{VarEngine, VarConfig, VarResult, VarMethod, VarBacktestResult, MarginalCvar};
 pub use greeks::{GreeksEngine, GreeksConfig, Greeks, PortfolioGreeks, HedgeRecommendation};
+pub use greeks::{MarketData, Option as OptionContract, OptionPosition, OptionType};
 pub use portfolio::{PortfolioAnalyzer, PortfolioConfig, RiskContribution, MarginalVarResult};
+pub use optimizer::{Allocation, AllocationLimits, CvarLimit, OptimizerConfig, PortfolioOptimizer, TargetWeight};
 pub use stress::{StressTestEngine, StressScenario, StressTestResult, StressTestReport};
 pub use metrics::PerformanceMetrics;
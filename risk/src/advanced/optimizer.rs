@@ -0,0 +1,467 @@
+//! Portfolio optimization across markets
+//!
+//! Turns expected edges per market (e.g. from signals) and a return
+//! covariance estimate into target position weights:
+//!
+//! - Mean-variance: maximize μᵀw − (λ/2)·wᵀΣw by projected gradient ascent
+//! - Limits: per-market weight bounds and a gross exposure cap, derived from
+//!   the risk policies by [`AllocationLimits::from_policies`]
+//! - Optional parametric CVaR cap: weights are scaled down until the
+//!   portfolio's expected shortfall fits the budget
+//!
+//! A weight is the signed position value (size × price) as a fraction of
+//! capital; positive weights are long YES.
+
+use crate::advanced::error::{AdvancedRiskError, Result};
+use crate::policy::{PolicyRule, RiskPolicyConfig};
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{Continuous, ContinuousCDF, Normal as StatrsNormal};
+use std::collections::HashMap;
+
+/// Bisection steps of the constraint projection
+const PROJECTION_STEPS: usize = 100;
+
+/// Parametric CVaR budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CvarLimit {
+    /// Confidence level (e.g. 0.95)
+    pub confidence_level: f64,
+
+    /// Maximum expected shortfall as a fraction of capital
+    pub max_cvar: f64,
+}
+
+/// Optimizer configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizerConfig {
+    /// Risk aversion λ (higher = smaller, more diversified weights)
+    pub risk_aversion: f64,
+
+    /// Cap on the portfolio's parametric CVaR (None = mean-variance only)
+    pub cvar_limit: Option<CvarLimit>,
+
+    /// Maximum gradient steps
+    pub max_iterations: usize,
+
+    /// Convergence threshold on the weight change per step
+    pub tolerance: f64,
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self {
+            risk_aversion: 1.0,
+            cvar_limit: None,
+            max_iterations: 10_000,
+            tolerance: 1e-10,
+        }
+    }
+}
+
+/// Weight bounds the optimizer must respect
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AllocationLimits {
+    /// Lowest weight per market (≤ 0)
+    pub lower: Vec<f64>,
+
+    /// Highest weight per market (≥ 0)
+    pub upper: Vec<f64>,
+
+    /// Maximum sum of absolute weights
+    pub max_gross: f64,
+}
+
+impl AllocationLimits {
+    /// Symmetric bound on every market and a gross cap
+    pub fn uniform(markets: usize, max_weight: f64, max_gross: f64) -> Self {
+        Self {
+            lower: vec![-max_weight.abs(); markets],
+            upper: vec![max_weight.abs(); markets],
+            max_gross: max_gross.abs(),
+        }
+    }
+
+    /// Limits implied by risk policies for `markets` (market ID and current
+    /// price) and `capital_usd`
+    ///
+    /// - `PositionLimit`: ±`max_size` × price per market
+    /// - `PayoutLimit`: longs below `price_band` and shorts above
+    ///   `1 - price_band` capped at `max_payout_usd` × price
+    /// - `InventoryLimit`: gross ≤ `max_value_usd`
+    /// - `BankrollLimit`: gross ≤ `max_fraction`
+    /// - an enabled `KillSwitch`: all weights 0
+    ///
+    /// Other policies do not bound weights. Without any gross limit the
+    /// portfolio may use the whole capital (gross 1).
+    pub fn from_policies(policies: &RiskPolicyConfig, markets: &[(String, f64)], capital_usd: f64) -> Result<Self> {
+        if !capital_usd.is_finite() || capital_usd <= 0.0 {
+            return Err(AdvancedRiskError::InvalidParameter(format!(
+                "Capital must be positive, got {}",
+                capital_usd
+            )));
+        }
+
+        if let Some((market_id, price)) = markets.iter().find(|(_, p)| !p.is_finite() || *p < 0.0) {
+            return Err(AdvancedRiskError::InvalidParameter(format!(
+                "Price of {} must be non-negative, got {}",
+                market_id, price
+            )));
+        }
+
+        let mut limits = Self::uniform(markets.len(), f64::INFINITY, 1.0);
+        for rule in &policies.policies {
+            match rule {
+                PolicyRule::InventoryLimit { max_value_usd, .. } => {
+                    limits.max_gross = limits.max_gross.min(max_value_usd / capital_usd);
+                }
+                PolicyRule::BankrollLimit { max_fraction, .. } => {
+                    limits.max_gross = limits.max_gross.min(*max_fraction);
+                }
+                PolicyRule::KillSwitch { enabled: true, .. } => {
+                    limits.max_gross = 0.0;
+                }
+                _ => {}
+            }
+
+            for (i, (market_id, price)) in markets.iter().enumerate() {
+                if !rule.applies_to_market(market_id) {
+                    continue;
+                }
+                match rule {
+                    PolicyRule::PositionLimit { max_size, .. } => {
+                        let bound = max_size * price / capital_usd;
+                        limits.upper[i] = limits.upper[i].min(bound);
+                        limits.lower[i] = limits.lower[i].max(-bound);
+                    }
+                    PolicyRule::PayoutLimit {
+                        max_payout_usd,
+                        price_band,
+                        ..
+                    } => {
+                        let bound = max_payout_usd * price / capital_usd;
+                        if *price < *price_band {
+                            limits.upper[i] = limits.upper[i].min(bound);
+                        }
+                        if *price > 1.0 - price_band {
+                            limits.lower[i] = limits.lower[i].max(-bound);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(limits)
+    }
+
+    /// Euclidean projection of `v` onto the bounds and the gross cap
+    ///
+    /// The solution soft-thresholds `v` by the τ that meets the gross cap
+    /// (found by bisection) and clamps it to the bounds.
+    fn project(&self, v: &DVector<f64>) -> DVector<f64> {
+        let at = |tau: f64| {
+            DVector::from_iterator(
+                v.len(),
+                v.iter().enumerate().map(|(i, x)| {
+                    let shrunk = x.signum() * (x.abs() - tau).max(0.0);
+                    shrunk.clamp(self.lower[i], self.upper[i])
+                }),
+            )
+        };
+
+        let clamped = at(0.0);
+        if clamped.lp_norm(1) <= self.max_gross {
+            return clamped;
+        }
+
+        let (mut lo, mut hi) = (0.0, v.amax());
+        for _ in 0..PROJECTION_STEPS {
+            let mid = 0.5 * (lo + hi);
+            if at(mid).lp_norm(1) > self.max_gross {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        at(hi)
+    }
+}
+
+/// Target weight of one market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetWeight {
+    pub market_id: String,
+
+    /// Signed fraction of capital (positive = long YES)
+    pub weight: f64,
+}
+
+/// Optimized allocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Allocation {
+    /// Target weights in input market order
+    pub weights: Vec<TargetWeight>,
+
+    /// Expected return μᵀw (fraction of capital)
+    pub expected_return: f64,
+
+    /// Portfolio volatility √(wᵀΣw) (fraction of capital)
+    pub volatility: f64,
+
+    /// Parametric CVaR at the configured confidence (None without a limit)
+    pub cvar: Option<f64>,
+
+    /// Gradient steps taken
+    pub iterations: usize,
+
+    /// Whether the weights converged within `max_iterations`
+    pub converged: bool,
+}
+
+impl Allocation {
+    /// Weight of a market
+    pub fn weight(&self, market_id: &str) -> Option<f64> {
+        self.weights.iter().find(|w| w.market_id == market_id).map(|w| w.weight)
+    }
+
+    /// Sum of absolute weights
+    pub fn gross(&self) -> f64 {
+        self.weights.iter().map(|w| w.weight.abs()).sum()
+    }
+
+    /// Weights by market ID
+    pub fn weight_map(&self) -> HashMap<String, f64> {
+        self.weights.iter().map(|w| (w.market_id.clone(), w.weight)).collect()
+    }
+}
+
+/// Mean-variance portfolio optimizer
+pub struct PortfolioOptimizer {
+    config: OptimizerConfig,
+}
+
+impl PortfolioOptimizer {
+    /// Create a new optimizer
+    pub fn new(config: OptimizerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Optimize target weights for `markets`
+    ///
+    /// `expected_edges` are expected returns per unit of weight over the
+    /// horizon of `covariance` (e.g. from
+    /// [`PortfolioAnalyzer::calculate_covariance_matrix`](crate::advanced::PortfolioAnalyzer::calculate_covariance_matrix)).
+    pub fn optimize(
+        &self,
+        markets: &[String],
+        expected_edges: &[f64],
+        covariance: &DMatrix<f64>,
+        limits: &AllocationLimits,
+    ) -> Result<Allocation> {
+        let n = markets.len();
+        self.validate(n, expected_edges, covariance, limits)?;
+
+        let mu = DVector::from_column_slice(expected_edges);
+        let lambda = self.config.risk_aversion;
+
+        // Step size 1/L with L the Lipschitz constant of the gradient
+        let max_eigenvalue = covariance
+            .clone()
+            .symmetric_eigen()
+            .eigenvalues
+            .iter()
+            .fold(0.0_f64, |max, e| max.max(e.abs()));
+        let step = 1.0 / (lambda * max_eigenvalue).max(1e-8);
+
+        let mut w = DVector::zeros(n);
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < self.config.max_iterations {
+            iterations += 1;
+            let gradient = &mu - covariance * &w * lambda;
+            let next = limits.project(&(&w + gradient * step));
+            let change = (&next - &w).norm();
+            w = next;
+            if change <= self.config.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        let mut cvar = None;
+        if let Some(limit) = &self.config.cvar_limit {
+            let current = parametric_cvar(&w, &mu, covariance, limit.confidence_level)?;
+            if current > limit.max_cvar && current > 0.0 {
+                // CVaR is homogeneous in w, so scaling hits the budget exactly
+                w *= limit.max_cvar.max(0.0) / current;
+            }
+            cvar = Some(parametric_cvar(&w, &mu, covariance, limit.confidence_level)?);
+        }
+
+        Ok(Allocation {
+            weights: markets
+                .iter()
+                .zip(w.iter())
+                .map(|(market_id, weight)| TargetWeight {
+                    market_id: market_id.clone(),
+                    weight: *weight,
+                })
+                .collect(),
+            expected_return: mu.dot(&w),
+            volatility: (w.transpose() * covariance * &w)[(0, 0)].max(0.0).sqrt(),
+            cvar,
+            iterations,
+            converged,
+        })
+    }
+
+    fn validate(
+        &self,
+        n: usize,
+        expected_edges: &[f64],
+        covariance: &DMatrix<f64>,
+        limits: &AllocationLimits,
+    ) -> Result<()> {
+        if n == 0 {
+            return Err(AdvancedRiskError::InsufficientData("No markets provided".to_string()));
+        }
+        if expected_edges.len() != n || limits.lower.len() != n || limits.upper.len() != n {
+            return Err(AdvancedRiskError::InvalidParameter(
+                "Expected edges and limits must have one entry per market".to_string(),
+            ));
+        }
+        if (0..n).any(|i| !(limits.lower[i] <= 0.0 && limits.upper[i] >= 0.0)) || limits.max_gross.is_nan() {
+            return Err(AdvancedRiskError::InvalidParameter(
+                "Limits must allow a zero weight in every market".to_string(),
+            ));
+        }
+        if covariance.nrows() != n || covariance.ncols() != n {
+            return Err(AdvancedRiskError::MatrixError(
+                "Covariance matrix dimensions don't match markets".to_string(),
+            ));
+        }
+        if expected_edges.iter().chain(covariance.iter()).any(|x| !x.is_finite()) {
+            return Err(AdvancedRiskError::InvalidParameter(
+                "Expected edges and covariance must be finite".to_string(),
+            ));
+        }
+        if !self.config.risk_aversion.is_finite() || self.config.risk_aversion <= 0.0 {
+            return Err(AdvancedRiskError::InvalidParameter(format!(
+                "Risk aversion must be positive, got {}",
+                self.config.risk_aversion
+            )));
+        }
+        if let Some(limit) = &self.config.cvar_limit {
+            if limit.confidence_level <= 0.0 || limit.confidence_level >= 1.0 {
+                return Err(AdvancedRiskError::InvalidConfidenceLevel(limit.confidence_level));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Expected shortfall of normally distributed portfolio returns:
+/// −μᵀw + σ_p·φ(z_c)/(1 − c)
+fn parametric_cvar(w: &DVector<f64>, mu: &DVector<f64>, covariance: &DMatrix<f64>, confidence_level: f64) -> Result<f64> {
+    let normal = StatrsNormal::new(0.0, 1.0).map_err(|e| AdvancedRiskError::CalculationError(e.to_string()))?;
+    let z = normal.inverse_cdf(confidence_level);
+    let volatility = (w.transpose() * covariance * w)[(0, 0)].max(0.0).sqrt();
+    Ok(-mu.dot(w) + volatility * normal.pdf(z) / (1.0 - confidence_level))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markets(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_unconstrained_mean_variance() {
+        // Uncorrelated markets: w = μ / (λσ²)
+        let cov = DMatrix::from_diagonal(&DVector::from_vec(vec![0.04, 0.01]));
+        let optimizer = PortfolioOptimizer::new(OptimizerConfig {
+            risk_aversion: 2.0,
+            ..Default::default()
+        });
+        let limits = AllocationLimits::uniform(2, 10.0, 10.0);
+
+        let allocation = optimizer.optimize(&markets(&["a", "b"]), &[0.02, -0.01], &cov, &limits).unwrap();
+        assert!(allocation.converged);
+        assert!((allocation.weight("a").unwrap() - 0.25).abs() < 1e-6);
+        assert!((allocation.weight("b").unwrap() + 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_policy_limits_bind() {
+        let policies: RiskPolicyConfig = serde_yaml::from_str(
+            r#"
+policies:
+  - type: PositionLimit
+    market_id: a
+    max_size: 1000.0
+  - type: BankrollLimit
+    max_fraction: 0.5
+  - type: PayoutLimit
+    max_payout_usd: 2000.0
+    price_band: 0.05
+"#,
+        )
+        .unwrap();
+        let prices = vec![("a".to_string(), 0.5), ("b".to_string(), 0.02), ("c".to_string(), 0.5)];
+        let limits = AllocationLimits::from_policies(&policies, &prices, 10_000.0).unwrap();
+        assert_eq!(limits.upper[0], 0.05);
+        assert_eq!(limits.lower[0], -0.05);
+        // Long payout cap near 0; shorts there are unaffected
+        assert!((limits.upper[1] - 0.004).abs() < 1e-12);
+        assert_eq!(limits.lower[1], f64::NEG_INFINITY);
+        assert_eq!(limits.max_gross, 0.5);
+
+        let cov = DMatrix::from_diagonal(&DVector::from_vec(vec![0.01, 0.01, 0.01]));
+        let allocation = PortfolioOptimizer::new(OptimizerConfig::default())
+            .optimize(&markets(&["a", "b", "c"]), &[0.05, 0.05, 0.05], &cov, &limits)
+            .unwrap();
+        assert!((allocation.weight("a").unwrap() - 0.05).abs() < 1e-6);
+        assert!((allocation.weight("b").unwrap() - 0.004).abs() < 1e-6);
+        // c takes the rest of the gross budget
+        assert!((allocation.gross() - 0.5).abs() < 1e-6);
+
+        let killed: RiskPolicyConfig = serde_yaml::from_str("policies:\n  - type: KillSwitch\n    enabled: true\n").unwrap();
+        let limits = AllocationLimits::from_policies(&killed, &prices, 10_000.0).unwrap();
+        let allocation = PortfolioOptimizer::new(OptimizerConfig::default())
+            .optimize(&markets(&["a", "b", "c"]), &[0.05, 0.05, 0.05], &cov, &limits)
+            .unwrap();
+        assert_eq!(allocation.gross(), 0.0);
+    }
+
+    #[test]
+    fn test_cvar_limit_scales_weights() {
+        let cov = DMatrix::from_row_slice(2, 2, &[0.04, 0.018, 0.018, 0.09]);
+        let limits = AllocationLimits::uniform(2, 1.0, 1.0);
+        let edges = [0.03, 0.04];
+
+        let free = PortfolioOptimizer::new(OptimizerConfig::default())
+            .optimize(&markets(&["a", "b"]), &edges, &cov, &limits)
+            .unwrap();
+        let capped = PortfolioOptimizer::new(OptimizerConfig {
+            cvar_limit: Some(CvarLimit {
+                confidence_level: 0.95,
+                max_cvar: 0.05,
+            }),
+            ..Default::default()
+        })
+        .optimize(&markets(&["a", "b"]), &edges, &cov, &limits)
+        .unwrap();
+
+        assert!((capped.cvar.unwrap() - 0.05).abs() < 1e-9);
+        assert!(capped.gross() < free.gross());
+        let ratio = capped.weight("a").unwrap() / free.weight("a").unwrap();
+        assert!((capped.weight("b").unwrap() / free.weight("b").unwrap() - ratio).abs() < 1e-9);
+
+        assert!(PortfolioOptimizer::new(OptimizerConfig::default())
+            .optimize(&markets(&["a"]), &edges, &cov, &limits)
+            .is_err());
+    }
+}
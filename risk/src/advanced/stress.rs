@@ -171,7 +171,7 @@ impl StressTestEngine {
             worst_position,
             worst_position_impact,
             position_impacts,
-            timestamp: Utc::now(),
+            timestamp: crate::clock::now(),
         })
     }
 
@@ -237,7 +237,7 @@ impl StressTestEngine {
             best_scenario,
             max_gain,
             average_impact,
-            timestamp: Utc::now(),
+            timestamp: crate::clock::now(),
         })
     }
 
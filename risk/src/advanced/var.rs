@@ -5,6 +5,7 @@
 //! - Parametric VaR: Assumes normal distribution (VaR = -μ + σ * Z_α * √T)
 //! - Monte Carlo VaR: Simulation-based approach
 //! - Conditional VaR (CVaR/Expected Shortfall): Average loss beyond VaR
+//! - Marginal CVaR: Change in a portfolio's historical CVaR from a candidate trade
 
 use crate::advanced::error::{AdvancedRiskError, Result};
 use chrono::{DateTime, Utc};
@@ -12,6 +13,7 @@ use rand::SeedableRng;
 use rand_distr::{Distribution, Normal};
 use serde::{Deserialize, Serialize};
 use statrs::distribution::{ContinuousCDF, Normal as StatrsNormal};
+use std::collections::HashMap;
 
 /// VaR calculation method
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -82,6 +84,29 @@ pub struct VarBacktestResult {
     pub validated: bool,
 }
 
+/// CVaR of a portfolio before and after a candidate trade
+///
+/// Produced by [`VarEngine::calculate_marginal_cvar`]. Amounts are in USD
+/// over one period of the return series (positive = loss).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MarginalCvar {
+    /// CVaR of the current portfolio
+    pub cvar_before: f64,
+
+    /// CVaR with the trade added
+    pub cvar_after: f64,
+
+    /// Confidence level (e.g., 0.95, 0.99)
+    pub confidence_level: f64,
+}
+
+impl MarginalCvar {
+    /// CVaR added by the trade (negative if the trade hedges)
+    pub fn impact(&self) -> f64 {
+        self.cvar_after - self.cvar_before
+    }
+}
+
 /// VaR calculation engine
 pub struct VarEngine {
     config: VarConfig,
@@ -152,7 +177,7 @@ impl VarEngine {
             confidence_level,
             time_horizon_days,
             method: VarMethod::Historical,
-            timestamp: Utc::now(),
+            timestamp: crate::clock::now(),
         })
     }
 
@@ -188,7 +213,7 @@ impl VarEngine {
             confidence_level,
             time_horizon_days,
             method: VarMethod::Parametric,
-            timestamp: Utc::now(),
+            timestamp: crate::clock::now(),
         })
     }
 
@@ -252,7 +277,7 @@ impl VarEngine {
             confidence_level,
             time_horizon_days,
             method: VarMethod::MonteCarlo,
-            timestamp: Utc::now(),
+            timestamp: crate::clock::now(),
         })
     }
 
@@ -304,6 +329,56 @@ impl VarEngine {
         Ok(cvar)
     }
 
+    /// Calculate the marginal CVaR of a candidate trade
+    ///
+    /// `asset_returns` are per-market return series of equal length and
+    /// `values` the signed USD value held per market. Each historical
+    /// period is replayed as a portfolio P&L scenario (Σ value × return)
+    /// and CVaR is calculated from the scenarios with and without
+    /// `trade_value_usd` added to `market_id`. Held markets without a
+    /// return series are left out; the traded market needs one.
+    pub fn calculate_marginal_cvar(
+        &self,
+        asset_returns: &HashMap<String, Vec<f64>>,
+        values: &HashMap<String, f64>,
+        market_id: &str,
+        trade_value_usd: f64,
+        confidence_level: f64,
+    ) -> Result<MarginalCvar> {
+        let trade_returns = asset_returns.get(market_id).ok_or_else(|| {
+            AdvancedRiskError::InsufficientData(format!("No return history for {}", market_id))
+        })?;
+        if asset_returns.values().any(|returns| returns.len() != trade_returns.len()) {
+            return Err(AdvancedRiskError::InvalidParameter(
+                "Return series must have equal length".to_string()
+            ));
+        }
+
+        let mut pnl_before = vec![0.0; trade_returns.len()];
+        for (market, value) in values {
+            if let Some(returns) = asset_returns.get(market) {
+                for (pnl, r) in pnl_before.iter_mut().zip(returns) {
+                    *pnl += value * r;
+                }
+            }
+        }
+        let pnl_after: Vec<f64> = pnl_before
+            .iter()
+            .zip(trade_returns)
+            .map(|(pnl, r)| pnl + trade_value_usd * r)
+            .collect();
+
+        // Scenarios are already in USD, so the portfolio value is 1
+        let cvar = |pnl: Vec<f64>| {
+            Self::with_historical_returns(self.config.clone(), pnl).calculate_cvar(1.0, confidence_level, 1)
+        };
+        Ok(MarginalCvar {
+            cvar_before: cvar(pnl_before)?,
+            cvar_after: cvar(pnl_after)?,
+            confidence_level,
+        })
+    }
+
     /// Backtest VaR model by comparing predictions to actual losses
     pub fn backtest_var(
         &self,
@@ -453,6 +528,28 @@ mod tests {
         assert!(cvar > 0.0);
     }
 
+    #[test]
+    fn test_marginal_cvar() {
+        let engine = VarEngine::new(VarConfig::default());
+        let returns = create_test_returns();
+        let inverse: Vec<f64> = returns.iter().map(|r| -r).collect();
+        let asset_returns = HashMap::from([("a".to_string(), returns), ("b".to_string(), inverse)]);
+        let values = HashMap::from([("a".to_string(), 1000.0)]);
+
+        // Adding to the position adds risk in proportion
+        let more = engine.calculate_marginal_cvar(&asset_returns, &values, "a", 500.0, 0.95).unwrap();
+        assert!(more.cvar_before > 0.0);
+        assert!((more.cvar_after - 1.5 * more.cvar_before).abs() < 1e-9);
+        assert!(more.impact() > 0.0);
+
+        // A perfectly offsetting market hedges it away
+        let hedge = engine.calculate_marginal_cvar(&asset_returns, &values, "b", 1000.0, 0.95).unwrap();
+        assert!(hedge.cvar_after.abs() < 1e-9);
+        assert!((hedge.impact() + hedge.cvar_before).abs() < 1e-9);
+
+        assert!(engine.calculate_marginal_cvar(&asset_returns, &values, "c", 100.0, 0.95).is_err());
+    }
+
     #[test]
     fn test_var_backtest() {
         let engine = VarEngine::new(VarConfig::default());
@@ -463,7 +560,7 @@ mod tests {
                 confidence_level: 0.95,
                 time_horizon_days: 1,
                 method: VarMethod::Parametric,
-                timestamp: Utc::now(),
+                timestamp: crate::clock::now(),
             };
             100
         ];
@@ -0,0 +1,172 @@
+//! Wall-clock access and injectable time and ID sources
+//!
+//! Result timestamps are read through [`now`] so the analytics can be built
+//! without chrono's `clock` feature (e.g. for `wasm32-unknown-unknown`
+//! without JS bindings). With the `clock` feature disabled, [`now`] returns
+//! the Unix epoch and callers should stamp results themselves.
+//!
+//! Components that stamp orders, acks and metrics take a [`Clock`] and an
+//! [`IdGenerator`] instead, defaulting to [`SystemClock`] and
+//! [`RandomIdGenerator`]. Tests and backtests inject a [`MockClock`] and a
+//! [`SequentialIdGenerator`] to make timestamps and IDs reproducible:
+//!
+//! ```rust
+//! use ag_risk::clock::{Clock, IdGenerator, MockClock, SequentialIdGenerator};
+//! use chrono::{TimeZone, Utc};
+//!
+//! let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+//! let clock = MockClock::new(start);
+//! clock.advance(chrono::Duration::seconds(5));
+//! assert_eq!(clock.now(), start + chrono::Duration::seconds(5));
+//!
+//! let ids = SequentialIdGenerator::new(1);
+//! assert_eq!((ids.next_id(), ids.next_id()), (1, 2));
+//! ```
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+/// Current UTC time
+#[cfg(feature = "clock")]
+pub fn now() -> DateTime<Utc> {
+    Utc::now()
+}
+
+/// Current UTC time (Unix epoch: built without the `clock` feature)
+#[cfg(not(feature = "clock"))]
+pub fn now() -> DateTime<Utc> {
+    use chrono::TimeZone;
+    Utc.timestamp_opt(0, 0).unwrap()
+}
+
+/// Source of the current time
+pub trait Clock: Send + Sync + Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Shared clock handle
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Source of unique 128-bit IDs
+pub trait IdGenerator: Send + Sync + Debug {
+    fn next_id(&self) -> u128;
+}
+
+/// Shared ID generator handle
+pub type SharedIdGenerator = Arc<dyn IdGenerator>;
+
+/// The system clock (see [`now`])
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        now()
+    }
+}
+
+/// Shared handle to the system clock
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Manually driven clock; clones share the same time
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// Jump to `time`
+    pub fn set(&self, time: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = time;
+    }
+
+    /// Move the time forward by `by`
+    pub fn advance(&self, by: chrono::Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Random IDs, formatted as version 4 UUIDs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn next_id(&self) -> u128 {
+        let id = rand::random::<u128>();
+        // Version 4, RFC 4122 variant
+        (id & !(0xF << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62)
+    }
+}
+
+/// Shared handle to the random ID generator
+pub fn random_ids() -> SharedIdGenerator {
+    Arc::new(RandomIdGenerator)
+}
+
+/// Counter IDs starting at a fixed value; clones share the counter
+#[derive(Debug, Clone)]
+pub struct SequentialIdGenerator {
+    next: Arc<AtomicU64>,
+}
+
+impl SequentialIdGenerator {
+    pub fn new(start: u64) -> Self {
+        Self {
+            next: Arc::new(AtomicU64::new(start)),
+        }
+    }
+}
+
+impl Default for SequentialIdGenerator {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self) -> u128 {
+        self.next.fetch_add(1, Ordering::Relaxed) as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_sources() {
+        use chrono::TimeZone;
+
+        let clock = MockClock::new(Utc.timestamp_opt(0, 0).unwrap());
+        let shared: SharedClock = Arc::new(clock.clone());
+        clock.advance(chrono::Duration::milliseconds(250));
+        assert_eq!(shared.now().timestamp_millis(), 250);
+
+        let ids = SequentialIdGenerator::new(7);
+        let shared: SharedIdGenerator = Arc::new(ids.clone());
+        assert_eq!(ids.next_id(), 7);
+        assert_eq!(shared.next_id(), 8);
+
+        let id = RandomIdGenerator.next_id();
+        assert_eq!((id >> 76) & 0xF, 4);
+        assert_eq!((id >> 62) & 0x3, 2);
+    }
+}
@@ -3,30 +3,359 @@
 //! This module implements the core risk evaluation logic that checks
 //! trading decisions against loaded policies.
 
+use crate::advanced::{AdvancedRiskError, MarginalCvar, VarConfig, VarEngine};
+use crate::clock::{self, SharedClock};
+use crate::greeks::{self, GreeksProvider};
+use crate::kill_switch::{KillSwitchAction, KillSwitchEvent, KillSwitchSource, MAX_KILL_SWITCH_HISTORY};
+use crate::margin::MarginEstimator;
+use crate::payout;
 use crate::policy::{PolicyRule, RiskPolicyConfig};
-use crate::{RiskContext, RiskDecision};
-use std::sync::RwLock;
+use crate::recovery::{RecoveryEvent, RecoveryStage, RecoveryState};
+use crate::state::{PolicyCounters, RiskStateSnapshot, SNAPSHOT_VERSION};
+use crate::telemetry::{self, RiskMetricsSink};
+use crate::{RiskAction, RiskContext, RiskDecision, RiskLeg, Violation};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+/// Counter label for rejections by the runtime kill-switch
+const KILL_SWITCH_LABEL: &str = "KillSwitch (active)";
+
+/// Counter label for entries blocked by a breach cooldown
+const RECOVERY_LABEL: &str = "RecoveryCooldown";
+
+/// Most recent losses retained per market
+const MAX_LOSS_HISTORY: usize = 256;
+
+/// Rolling window of the loss checked by `DailyLossLimit`, in hours
+const DAILY_LOSS_WINDOW_HOURS: i64 = 24;
+
+/// Window over which `OrderRateLimit` counts orders, in seconds
+const ORDER_RATE_WINDOW_SECS: i64 = 60;
+
+/// Undrained recovery events retained
+const MAX_RECOVERY_EVENTS: usize = 256;
+
+/// Outcome of replaying contexts against a candidate policy set
+///
+/// Produced by [`RiskEngine::simulate`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    /// Number of contexts evaluated
+    pub total: usize,
+
+    /// Number of contexts that would be allowed
+    pub allowed: usize,
+
+    /// Number of contexts that would be rejected
+    pub rejected: usize,
+
+    /// Rejection count per rule (market-scoped rules are listed separately,
+    /// e.g. `PositionLimit (market: 0x123)`)
+    pub rejections_by_rule: BTreeMap<String, usize>,
+
+    /// Indices into the input contexts that would be rejected
+    pub rejected_indices: Vec<usize>,
+}
+
+impl SimulationReport {
+    /// Fraction of contexts rejected (0.0 for an empty batch)
+    pub fn rejection_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.rejected as f64 / self.total as f64
+        }
+    }
+
+    /// Contexts rejected here but allowed in `baseline`
+    ///
+    /// Both reports must come from the same context batch.
+    pub fn newly_rejected(&self, baseline: &SimulationReport) -> Vec<usize> {
+        self.rejected_indices
+            .iter()
+            .filter(|i| baseline.rejected_indices.binary_search(i).is_err())
+            .copied()
+            .collect()
+    }
+
+    /// Contexts allowed here but rejected in `baseline`
+    ///
+    /// Both reports must come from the same context batch.
+    pub fn newly_allowed(&self, baseline: &SimulationReport) -> Vec<usize> {
+        baseline.newly_rejected(self)
+    }
+}
+
+/// Decisions for a batch of contexts (e.g. the levels of a quote ladder)
+///
+/// Produced by [`RiskEngine::evaluate_batch`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchDecision {
+    /// Decision per context, in input order
+    pub decisions: Vec<RiskDecision>,
+
+    /// Allowed only if every context is allowed; lists each distinct
+    /// violation once
+    pub aggregate: RiskDecision,
+}
+
+impl BatchDecision {
+    /// Indices of the rejected contexts
+    pub fn rejected_indices(&self) -> Vec<usize> {
+        self.decisions
+            .iter()
+            .enumerate()
+            .filter(|(_, decision)| !decision.allowed)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Number of allowed contexts
+    pub fn allowed_count(&self) -> usize {
+        self.decisions.iter().filter(|decision| decision.allowed).count()
+    }
+}
+
+/// Decision for an atomic multi-leg submission
+///
+/// Produced by [`RiskEngine::evaluate_group`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupDecision {
+    /// Decision per leg on its own checks, in input order
+    pub legs: Vec<RiskDecision>,
+
+    /// Allowed only if every leg is allowed and the group passes its
+    /// group-wide checks; lists each distinct violation once
+    pub aggregate: RiskDecision,
+
+    /// Hedge-ratio-weighted sum of the legs' proposed sizes
+    pub net_exposure: f64,
+
+    /// Whether the group nets out under a `HedgedGroupLimit`, waiving the
+    /// legs' per-market size limits
+    pub hedged: bool,
+}
 
 /// Risk evaluation engine
 ///
 /// The RiskEngine loads policies and evaluates trading decisions
-/// against them. It maintains state for the kill-switch.
+/// against them. It maintains state for the kill-switch and its audit
+/// trail, the per-market
+/// trade outcomes used by `LossStreakCooldown`, the realized PnL used by
+/// `DailyLossLimit`, the breach recovery stage and per-policy counters;
+/// see [`snapshot`](Self::snapshot) and [`restore`](Self::restore). Metrics are emitted to an optional
+/// [`RiskMetricsSink`] (see [`set_metrics_sink`](Self::set_metrics_sink)). The
+/// bankroll used by `BankrollLimit`, the unrealized PnL used by
+/// `DailyLossLimit`, the order counts used by `OrderRateLimit`, the
+/// market close times used by `TradingWindow`, the margin book used by `CapitalAtRiskLimit`, the
+/// venue positions used by `ExposureAcrossVenues`, the Greeks provider
+/// used by `GreeksLimit` and the return history used by
+/// [`marginal_cvar`](Self::marginal_cvar) are reported by the caller and
+/// not snapshotted; nor are policies disabled at runtime with
+/// [`set_policy_enabled`](Self::set_policy_enabled) or parameters changed
+/// with [`set_policy_param`](Self::set_policy_param).
+///
+/// Policies are evaluated in the order set by the config's `evaluation`
+/// section (file order by default), optionally stopping at the first
+/// rejecting violation. They can be replaced while running with
+/// [`reload`](Self::reload), or from a watched file with `watch_file`
+/// (feature `watch`), keeping the runtime state.
 pub struct RiskEngine {
-    config: RiskPolicyConfig,
+    /// Active policies, replaced as a whole on reload; shared with file
+    /// watchers
+    policies: SharedPolicies,
     kill_switch_active: RwLock<bool>,
+    /// Most recent kill-switch activations and resets, oldest first
+    kill_switch_history: RwLock<Vec<KillSwitchEvent>>,
+    /// Close times of the current losing streak, per market
+    loss_streaks: RwLock<HashMap<String, Vec<DateTime<Utc>>>>,
+    /// Realized PnL of round trips closed in the daily loss window, oldest
+    /// first
+    realized_pnl: RwLock<Vec<(DateTime<Utc>, f64)>>,
+    /// Unrealized PnL of open positions reported by the caller
+    unrealized_pnl: RwLock<f64>,
+    /// Orders allowed per market, as (unix second, count) buckets in the
+    /// rate window, oldest first
+    order_rates: RwLock<HashMap<String, VecDeque<(i64, u32)>>>,
+    /// Evaluation counters keyed by policy label
+    counters: RwLock<BTreeMap<String, PolicyCounters>>,
+    /// Cash capital reported by the capital tracker
+    bankroll: RwLock<Option<f64>>,
+    /// Scheduled close times per market
+    market_closes: RwLock<HashMap<String, DateTime<Utc>>>,
+    /// Outcome positions grouped by event
+    margin: RwLock<MarginEstimator>,
+    /// Position per (market, venue)
+    venue_positions: RwLock<HashMap<(String, String), f64>>,
+    /// Asset of each market, where it differs from the market ID
+    market_assets: RwLock<HashMap<String, String>>,
+    /// Per-market returns and position values for marginal CVaR
+    return_history: RwLock<ReturnHistory>,
+    /// Source of portfolio Greeks for `GreeksLimit`
+    greeks_provider: RwLock<Option<Arc<dyn GreeksProvider>>>,
+    /// Stage of the breach recovery workflow
+    recovery: RwLock<RecoveryState>,
+    /// Stage changes not yet drained by `poll_recovery`
+    recovery_events: RwLock<Vec<RecoveryEvent>>,
+    /// Destination of emitted metrics
+    metrics_sink: RwLock<Option<Arc<dyn RiskMetricsSink>>>,
+    /// State sampled by `publish_metrics`
+    telemetry: RwLock<Telemetry>,
+    /// Source of the time used by time-based policies and timestamps
+    /// (shared with policy watchers, which stamp reloads with it)
+    clock: Arc<RwLock<SharedClock>>,
+}
+
+/// Slot holding the active policy set
+pub(crate) type SharedPolicies = Arc<RwLock<Arc<PolicySet>>>;
+
+/// Policies with their evaluation order and enabled flags
+///
+/// Evaluations work on one set from start to finish, so a reload never
+/// mixes old and new policies in a decision.
+#[derive(Debug, Clone)]
+pub(crate) struct PolicySet {
+    config: RiskPolicyConfig,
+    /// Policy indices in evaluation order
+    order: Vec<usize>,
+    /// Whether each policy (by index) is evaluated
+    enabled: Vec<bool>,
+    /// Counter label of each policy (by index), built once rather than on
+    /// every evaluation
+    labels: Vec<String>,
+}
+
+impl PolicySet {
+    fn new(config: RiskPolicyConfig) -> Self {
+        let order = config.evaluation.ordered(&config.policies);
+        let enabled = config
+            .policies
+            .iter()
+            .map(|policy| !config.evaluation.disabled.iter().any(|reference| policy.matches(reference)))
+            .collect();
+        let labels = config.policies.iter().map(PolicyRule::label).collect();
+        Self {
+            config,
+            order,
+            enabled,
+            labels,
+        }
+    }
+
+    /// Check a config before it is loaded or replaces the active policies
+    ///
+    /// Beyond parsing, a config must name only configured policies in its
+    /// `evaluation` section and have at least one policy, since an empty
+    /// file would silently disable every check.
+    pub(crate) fn validated(config: RiskPolicyConfig) -> Result<Self, String> {
+        if config.policies.is_empty() {
+            return Err("policy config has no policies".to_string());
+        }
+        let unknown = config.unknown_policy_references();
+        if !unknown.is_empty() {
+            return Err(format!("no policy matches {}", unknown.join(", ")));
+        }
+        Ok(Self::new(config))
+    }
+
+    /// Parse and check a YAML policy document
+    pub(crate) fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let config: RiskPolicyConfig =
+            serde_yaml::from_str(yaml).map_err(|e| format!("Failed to parse YAML: {}", e))?;
+        Self::validated(config)
+    }
+
+    #[cfg(feature = "watch")]
+    pub(crate) fn len(&self) -> usize {
+        self.config.policies.len()
+    }
+
+    /// Indices of the enabled policies, in evaluation order
+    fn active_indices(&self) -> Vec<usize> {
+        self.order.iter().copied().filter(|&index| self.enabled[index]).collect()
+    }
+
+    /// Enabled policies, in evaluation order
+    fn active(&self) -> impl Iterator<Item = &PolicyRule> + '_ {
+        self.labeled().map(|(policy, _)| policy)
+    }
+
+    /// Enabled policies with their counter labels, in evaluation order
+    fn labeled(&self) -> impl Iterator<Item = (&PolicyRule, &str)> + '_ {
+        self.order
+            .iter()
+            .filter(|&&index| self.enabled[index])
+            .map(|&index| (&self.config.policies[index], self.labels[index].as_str()))
+    }
+
+    /// Whether evaluation stops at this violation
+    fn short_circuits(&self, violation: &Violation) -> bool {
+        self.config.evaluation.short_circuit && violation.severity >= RiskAction::Reject
+    }
+}
+
+/// Return history reported by the caller
+#[derive(Debug, Default)]
+struct ReturnHistory {
+    /// Aligned return series per market
+    asset_returns: HashMap<String, Vec<f64>>,
+    /// Signed USD value held per market
+    values: HashMap<String, f64>,
+}
+
+/// Evaluation activity since the last `publish_metrics`
+#[derive(Debug, Default)]
+struct Telemetry {
+    /// Position last evaluated per market
+    positions: HashMap<String, f64>,
+    /// Contexts evaluated since the last publish
+    evaluations: u64,
+    /// Time of the last publish
+    last_publish: Option<DateTime<Utc>>,
 }
 
 impl RiskEngine {
     /// Create a new RiskEngine from a configuration
+    ///
+    /// The config is used as given; [`from_yaml`](Self::from_yaml) and
+    /// [`from_json`](Self::from_json) check it as [`reload`](Self::reload)
+    /// does.
     pub fn new(config: RiskPolicyConfig) -> Self {
+        Self::with_policies(PolicySet::new(config))
+    }
+
+    fn with_policies(policies: PolicySet) -> Self {
         Self {
-            config,
+            policies: Arc::new(RwLock::new(Arc::new(policies))),
             kill_switch_active: RwLock::new(false),
+            kill_switch_history: RwLock::new(Vec::new()),
+            loss_streaks: RwLock::new(HashMap::new()),
+            realized_pnl: RwLock::new(Vec::new()),
+            unrealized_pnl: RwLock::new(0.0),
+            order_rates: RwLock::new(HashMap::new()),
+            counters: RwLock::new(BTreeMap::new()),
+            bankroll: RwLock::new(None),
+            market_closes: RwLock::new(HashMap::new()),
+            margin: RwLock::new(MarginEstimator::new()),
+            venue_positions: RwLock::new(HashMap::new()),
+            market_assets: RwLock::new(HashMap::new()),
+            return_history: RwLock::new(ReturnHistory::default()),
+            greeks_provider: RwLock::new(None),
+            recovery: RwLock::new(RecoveryState::default()),
+            recovery_events: RwLock::new(Vec::new()),
+            metrics_sink: RwLock::new(None),
+            telemetry: RwLock::new(Telemetry::default()),
+            clock: Arc::new(RwLock::new(clock::system_clock())),
         }
     }
 
     /// Load policies from YAML string
     ///
+    /// Fails if the document doesn't parse, has no policies or its
+    /// `evaluation` section names a policy it doesn't have.
+    ///
     /// # Example
     ///
     /// ```
@@ -41,12 +370,10 @@ impl RiskEngine {
     /// let engine = RiskEngine::from_yaml(yaml).unwrap();
     /// ```
     pub fn from_yaml(yaml: &str) -> Result<Self, String> {
-        let config: RiskPolicyConfig =
-            serde_yaml::from_str(yaml).map_err(|e| format!("Failed to parse YAML: {}", e))?;
-        Ok(Self::new(config))
+        PolicySet::from_yaml(yaml).map(Self::with_policies)
     }
 
-    /// Load policies from JSON string
+    /// Load policies from JSON string, checked as [`from_yaml`](Self::from_yaml)
     ///
     /// # Example
     ///
@@ -67,7 +394,7 @@ impl RiskEngine {
     pub fn from_json(json: &str) -> Result<Self, String> {
         let config: RiskPolicyConfig =
             serde_json::from_str(json).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-        Ok(Self::new(config))
+        PolicySet::validated(config).map(Self::with_policies)
     }
 
     /// Evaluate if an action is allowed based on current policies
@@ -98,336 +425,2802 @@ impl RiskEngine {
     /// assert!(!decision.allowed); // Would result in 1100.0, exceeds limit of 1000.0
     /// ```
     pub fn evaluate(&self, ctx: &RiskContext) -> RiskDecision {
-        let mut violated_policies = Vec::new();
+        let mut violations = Vec::new();
+        self.observe(std::slice::from_ref(ctx));
 
         // Check if kill-switch is active
         if *self.kill_switch_active.read().unwrap() {
-            violated_policies.push("KillSwitch (active)".to_string());
-            return RiskDecision::reject(violated_policies);
+            self.count(KILL_SWITCH_LABEL, true);
+            violations.push(self.kill_switch_violation());
+            return RiskDecision::reject(violations);
+        }
+
+        // Entries are blocked while recovering from a breach
+        self.advance_recovery();
+        if let Some(violation) = self.recovery_violation(ctx) {
+            self.count(RECOVERY_LABEL, true);
+            return RiskDecision::reject(vec![violation]);
         }
 
-        // Evaluate each policy
-        for policy in &self.config.policies {
+        // Evaluate each policy, counting them under one lock afterwards
+        let policies = self.policies();
+        let mut counts = Vec::with_capacity(policies.order.len());
+        for (policy, label) in policies.labeled() {
             // Skip policies that don't apply to this market
-            if !policy.applies_to_market(&ctx.market_id) {
+            if !policy.applies_to_market(&ctx.market_id) || is_group_only(policy) {
                 continue;
             }
 
             // Evaluate policy
-            if let Some(violation) = self.evaluate_policy(policy, ctx) {
-                violated_policies.push(violation);
+            let violation = self.evaluate_policy(policy, ctx);
+            counts.push((label, rejects(&violation)));
+            if let Some(violation) = violation {
+                self.trip_on_violation(policy, &violation);
+                let stop = policies.short_circuits(&violation);
+                violations.push(violation);
+                if stop {
+                    break;
+                }
             }
         }
 
-        // Return decision
-        if violated_policies.is_empty() {
-            RiskDecision::allow()
-        } else {
-            RiskDecision::reject(violated_policies)
-        }
-    }
+        self.record_counts(counts);
 
-    /// Trigger the kill-switch, blocking all future trades
-    pub fn trigger_kill_switch(&self) {
-        *self.kill_switch_active.write().unwrap() = true;
+        // Return decision
+        let decision = RiskDecision::from_violations(violations);
+        self.record_order(ctx, &decision);
+        decision
     }
 
-    /// Reset the kill-switch, allowing trades again
-    pub fn reset_kill_switch(&self) {
-        *self.kill_switch_active.write().unwrap() = false;
-    }
+    /// Evaluate a context without recording the evaluation
+    ///
+    /// Returns the decision [`evaluate`](Self::evaluate) would return, but
+    /// leaves the policy counters and telemetry untouched and never trips
+    /// breach recovery, so callers can probe hypothetical orders (e.g. to
+    /// find the largest size that passes) without skewing rejection
+    /// statistics or starting a cooldown.
+    pub fn dry_run(&self, ctx: &RiskContext) -> RiskDecision {
+        if self.is_kill_switch_active() {
+            return RiskDecision::reject(vec![self.kill_switch_violation()]);
+        }
 
-    /// Check if kill-switch is currently active
-    pub fn is_kill_switch_active(&self) -> bool {
-        *self.kill_switch_active.read().unwrap()
-    }
+        self.advance_recovery();
+        if let Some(violation) = self.recovery_violation(ctx) {
+            return RiskDecision::reject(vec![violation]);
+        }
 
-    /// Evaluate a single policy against the context
-    ///
-    /// Returns Some(violation_message) if policy is violated, None otherwise
-    fn evaluate_policy(&self, policy: &PolicyRule, ctx: &RiskContext) -> Option<String> {
-        match policy {
-            PolicyRule::PositionLimit { market_id, max_size } => {
-                let new_position = ctx.current_position + ctx.proposed_size;
-                if new_position.abs() > *max_size {
-                    let market_str = market_id
-                        .as_ref()
-                        .map(|m| format!(" (market: {})", m))
-                        .unwrap_or_default();
-                    Some(format!(
-                        "PositionLimit{}: new position {:.2} exceeds max {:.2}",
-                        market_str, new_position.abs(), max_size
-                    ))
-                } else {
-                    None
-                }
-            }
-            PolicyRule::InventoryLimit { max_value_usd } => {
-                if ctx.inventory_value_usd > *max_value_usd {
-                    Some(format!(
-                        "InventoryLimit: inventory {:.2} USD exceeds max {:.2} USD",
-                        ctx.inventory_value_usd, max_value_usd
-                    ))
-                } else {
-                    None
-                }
+        let policies = self.policies();
+        let mut violations = Vec::new();
+        for policy in policies.active() {
+            if !policy.applies_to_market(&ctx.market_id) || is_group_only(policy) {
+                continue;
             }
-            PolicyRule::KillSwitch { enabled } => {
-                if *enabled {
-                    Some("KillSwitch: enabled in policy".to_string())
-                } else {
-                    None
+            if let Some(violation) = self.evaluate_policy(policy, ctx) {
+                let stop = policies.short_circuits(&violation);
+                violations.push(violation);
+                if stop {
+                    break;
                 }
             }
         }
+        RiskDecision::from_violations(violations)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Evaluate several contexts at once, e.g. every level of a quote ladder
+    ///
+    /// Each context is evaluated as by [`evaluate`](Self::evaluate) and
+    /// independently of the others (a ladder's contexts should carry the
+    /// cumulative position if levels are meant to stack). Work shared by the
+    /// batch is done once: the kill-switch check, policies that do not
+    /// depend on the context, the per-market policy selection and the
+    /// counter update.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ag_risk::{RiskContext, RiskEngine};
+    ///
+    /// let engine = RiskEngine::from_yaml(r#"
+    /// policies:
+    ///   - type: PositionLimit
+    ///     max_size: 250.0
+    /// "#).unwrap();
+    ///
+    /// let ladder: Vec<RiskContext> = (0..3)
+    ///     .map(|level| RiskContext {
+    ///         market_id: "0x123".to_string(),
+    ///         current_position: 100.0 * level as f64,
+    ///         proposed_size: 100.0,
+    ///         inventory_value_usd: 0.0,
+    ///     })
+    ///     .collect();
+    ///
+    /// let batch = engine.evaluate_batch(&ladder);
+    /// assert!(!batch.aggregate.allowed);
+    /// assert_eq!(batch.rejected_indices(), vec![2]);
+    /// ```
+    pub fn evaluate_batch(&self, contexts: &[RiskContext]) -> BatchDecision {
+        let Some(first) = contexts.first() else {
+            return BatchDecision {
+                decisions: Vec::new(),
+                aggregate: RiskDecision::allow(),
+            };
+        };
+        let mut counts = Vec::new();
+        self.observe(contexts);
 
-    #[test]
-    fn test_position_limit_global() {
-        let yaml = r#"
-policies:
-  - type: PositionLimit
-    max_size: 1000.0
-"#;
-        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let policies = self.policies();
+        let decisions: Vec<RiskDecision> = if self.is_kill_switch_active() {
+            contexts
+                .iter()
+                .map(|_| {
+                    counts.push((KILL_SWITCH_LABEL, true));
+                    RiskDecision::reject(vec![self.kill_switch_violation()])
+                })
+                .collect()
+        } else {
+            self.advance_recovery();
 
-        // Should allow within limit
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 500.0,
-            proposed_size: 400.0,
-            inventory_value_usd: 5000.0,
-        };
-        let decision = engine.evaluate(&ctx);
-        assert!(decision.allowed);
+            // Policies that ignore the context are evaluated once
+            let active = policies.active_indices();
+            let shared: HashMap<usize, Option<Violation>> = active
+                .iter()
+                .map(|&index| (index, &policies.config.policies[index]))
+                .filter(|(_, policy)| is_context_independent(policy))
+                .map(|(index, policy)| (index, self.evaluate_policy(policy, first)))
+                .collect();
 
-        // Should reject when exceeding limit
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 800.0,
-            proposed_size: 300.0,
-            inventory_value_usd: 5000.0,
-        };
-        let decision = engine.evaluate(&ctx);
-        assert!(!decision.allowed);
-        assert_eq!(decision.violated_policies.len(), 1);
-        assert!(decision.violated_policies[0].contains("PositionLimit"));
-    }
+            let mut applicable: HashMap<&str, Vec<usize>> = HashMap::new();
+            contexts
+                .iter()
+                .map(|ctx| {
+                    let indices = applicable.entry(ctx.market_id.as_str()).or_insert_with(|| {
+                        active
+                            .iter()
+                            .copied()
+                            .filter(|&index| {
+                                let policy = &policies.config.policies[index];
+                                policy.applies_to_market(&ctx.market_id) && !is_group_only(policy)
+                            })
+                            .collect()
+                    });
 
-    #[test]
-    fn test_position_limit_per_market() {
-        let yaml = r#"
-policies:
-  - type: PositionLimit
-    market_id: "0x123"
-    max_size: 500.0
-  - type: PositionLimit
-    market_id: "0x456"
-    max_size: 1000.0
-"#;
-        let engine = RiskEngine::from_yaml(yaml).unwrap();
+                    if let Some(violation) = self.recovery_violation(ctx) {
+                        counts.push((RECOVERY_LABEL, true));
+                        return RiskDecision::reject(vec![violation]);
+                    }
 
-        // Should apply 500 limit to 0x123
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 400.0,
-            proposed_size: 200.0,
-            inventory_value_usd: 5000.0,
-        };
-        let decision = engine.evaluate(&ctx);
-        assert!(!decision.allowed);
+                    let mut violations = Vec::new();
+                    for &index in indices.iter() {
+                        let policy = &policies.config.policies[index];
+                        let violation = match shared.get(&index) {
+                            // Attributed to this context's market, as
+                            // `evaluate_policy` would
+                            Some(violation) => violation.clone().map(|violation| match violation.market_id {
+                                Some(_) => violation.in_market(&ctx.market_id),
+                                None => violation,
+                            }),
+                            None => self.evaluate_policy(policy, ctx),
+                        };
+                        counts.push((policies.labels[index].as_str(), rejects(&violation)));
+                        if let Some(violation) = violation {
+                            self.trip_on_violation(policy, &violation);
+                            let stop = policies.short_circuits(&violation);
+                            violations.push(violation);
+                            if stop {
+                                break;
+                            }
+                        }
+                    }
 
-        // Should apply 1000 limit to 0x456
-        let ctx = RiskContext {
-            market_id: "0x456".to_string(),
-            current_position: 400.0,
-            proposed_size: 200.0,
-            inventory_value_usd: 5000.0,
+                    RiskDecision::from_violations(violations)
+                })
+                // Recorded as each context is decided, so later levels
+                // count the earlier ones
+                .zip(contexts)
+                .map(|(decision, ctx)| {
+                    self.record_order(ctx, &decision);
+                    decision
+                })
+                .collect()
         };
-        let decision = engine.evaluate(&ctx);
-        assert!(decision.allowed);
-    }
 
-    #[test]
-    fn test_inventory_limit() {
-        let yaml = r#"
-policies:
-  - type: InventoryLimit
-    max_value_usd: 10000.0
-"#;
-        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        self.record_counts(counts);
 
-        // Should allow within limit
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 500.0,
-            proposed_size: 100.0,
-            inventory_value_usd: 8000.0,
-        };
-        let decision = engine.evaluate(&ctx);
-        assert!(decision.allowed);
+        let mut violations: Vec<Violation> = Vec::new();
+        for violation in decisions.iter().flat_map(|decision| &decision.violations) {
+            if !violations.contains(violation) {
+                violations.push(violation.clone());
+            }
+        }
+        let aggregate = RiskDecision::from_violations(violations);
 
-        // Should reject when exceeding limit
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 500.0,
-            proposed_size: 100.0,
-            inventory_value_usd: 12000.0,
-        };
-        let decision = engine.evaluate(&ctx);
-        assert!(!decision.allowed);
-        assert!(decision.violated_policies[0].contains("InventoryLimit"));
+        BatchDecision { decisions, aggregate }
     }
 
-    #[test]
-    fn test_kill_switch_in_policy() {
-        let yaml = r#"
-policies:
-  - type: KillSwitch
-    enabled: true
-"#;
-        let engine = RiskEngine::from_yaml(yaml).unwrap();
+    /// Evaluate the legs of an atomic multi-leg submission together
+    ///
+    /// Legs are checked as by [`evaluate`](Self::evaluate), except that
+    /// `CapitalAtRiskLimit` is checked once for all legs traded together
+    /// and, when a `HedgedGroupLimit` policy finds the group's net exposure
+    /// within its limit, the legs' `PositionLimit` and `PayoutLimit`
+    /// checks are replaced by its `max_leg_position`. Without a
+    /// `HedgedGroupLimit` policy every leg must pass on its own. The group
+    /// should only be submitted if the aggregate decision allows it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ag_risk::{RiskContext, RiskEngine, RiskLeg};
+    ///
+    /// let engine = RiskEngine::from_yaml(r#"
+    /// policies:
+    ///   - type: PositionLimit
+    ///     max_size: 100.0
+    ///   - type: HedgedGroupLimit
+    ///     max_net_exposure: 10.0
+    ///     max_leg_position: 500.0
+    /// "#).unwrap();
+    ///
+    /// let leg = |market_id: &str, proposed_size: f64| RiskLeg::new(RiskContext {
+    ///     market_id: market_id.to_string(),
+    ///     current_position: 0.0,
+    ///     proposed_size,
+    ///     inventory_value_usd: 0.0,
+    /// });
+    ///
+    /// // Buy 200 on one venue, sell 200 of the same outcome on another
+    /// let arb = engine.evaluate_group(&[leg("0xa", 200.0), leg("0xb", -200.0)]);
+    /// assert!(arb.aggregate.allowed);
+    ///
+    /// // A naked 200 lot still breaches the position limit
+    /// let naked = engine.evaluate_group(&[leg("0xa", 200.0)]);
+    /// assert!(!naked.aggregate.allowed);
+    /// ```
+    pub fn evaluate_group(&self, legs: &[RiskLeg]) -> GroupDecision {
+        let net_exposure: f64 = legs.iter().map(RiskLeg::exposure).sum();
+        let contexts: Vec<RiskContext> = legs.iter().map(|leg| leg.context.clone()).collect();
+        self.observe(&contexts);
 
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 100.0,
-            proposed_size: 50.0,
-            inventory_value_usd: 1000.0,
-        };
+        if self.is_kill_switch_active() {
+            let mut decisions = Vec::new();
+            for _ in legs {
+                self.count(KILL_SWITCH_LABEL, true);
+                decisions.push(RiskDecision::reject(vec![self.kill_switch_violation()]));
+            }
+            return group_decision(decisions, Vec::new(), net_exposure, false);
+        }
+        self.advance_recovery();
 
-        let decision = engine.evaluate(&ctx);
+        let policies = self.policies();
+        let scale = self.limit_scale();
+        let probation = if scale < 1.0 { " (probation)" } else { "" };
+        let mut group_violations = Vec::new();
+
+        // Net exposure decides whether per-market size limits are waived
+        let mut hedged = false;
+        let mut max_leg_position = None;
+        for (policy, label) in policies.labeled() {
+            let PolicyRule::HedgedGroupLimit {
+                max_net_exposure,
+                max_leg_position: leg_cap,
+                ..
+            } = policy
+            else {
+                continue;
+            };
+            let max_net_exposure = max_net_exposure * scale;
+            let within = net_exposure.abs() <= max_net_exposure;
+            self.count(label, !within);
+            if within {
+                hedged = true;
+                max_leg_position = leg_cap.map(|cap| cap * scale);
+            } else {
+                let violation = Violation::new(
+                    policy.name(),
+                    format!(
+                        "HedgedGroupLimit: net exposure {:.2} of {} legs exceeds max {:.2}{}",
+                        net_exposure.abs(),
+                        legs.len(),
+                        max_net_exposure,
+                        probation
+                    ),
+                )
+                .with_values(max_net_exposure, net_exposure.abs())
+                .with_severity(policy.action());
+                self.trip_on_violation(policy, &violation);
+                group_violations.push(violation);
+            }
+        }
+
+        // Capital at risk of all legs traded together
+        for (policy, label) in policies.labeled() {
+            let PolicyRule::CapitalAtRiskLimit { max_loss_usd, .. } = policy else {
+                continue;
+            };
+            let trades: Vec<(&str, f64)> = legs
+                .iter()
+                .map(|leg| (leg.context.market_id.as_str(), leg.context.proposed_size))
+                .collect();
+            let (current, after) = {
+                let margin = self.margin.read().unwrap();
+                (margin.capital_at_risk(), margin.capital_at_risk_after_all(&trades))
+            };
+            let max_loss_usd = max_loss_usd * scale;
+            let rejected = after > max_loss_usd && after > current;
+            self.count(label, rejected);
+            if rejected {
+                let violation = Violation::new(
+                    policy.name(),
+                    format!(
+                        "CapitalAtRiskLimit: worst-case loss {:.2} USD of {} legs exceeds max {:.2} USD{}",
+                        after,
+                        legs.len(),
+                        max_loss_usd,
+                        probation
+                    ),
+                )
+                .with_values(max_loss_usd, after)
+                .with_severity(policy.action());
+                self.trip_on_violation(policy, &violation);
+                group_violations.push(violation);
+            }
+        }
+
+        let decisions = legs
+            .iter()
+            .map(|leg| {
+                let ctx = &leg.context;
+                if let Some(violation) = self.recovery_violation(ctx) {
+                    self.count(RECOVERY_LABEL, true);
+                    return RiskDecision::reject(vec![violation]);
+                }
+
+                let mut violations = Vec::new();
+                for (policy, label) in policies.labeled() {
+                    if !policy.applies_to_market(&ctx.market_id) || is_group_only(policy) {
+                        continue;
+                    }
+                    let waived = hedged
+                        && matches!(policy, PolicyRule::PositionLimit { .. } | PolicyRule::PayoutLimit { .. });
+                    if waived || matches!(policy, PolicyRule::CapitalAtRiskLimit { .. }) {
+                        continue;
+                    }
+                    let violation = self.evaluate_policy(policy, ctx);
+                    self.count(label, rejects(&violation));
+                    if let Some(violation) = violation {
+                        self.trip_on_violation(policy, &violation);
+                        let stop = policies.short_circuits(&violation);
+                        violations.push(violation);
+                        if stop {
+                            break;
+                        }
+                    }
+                }
+
+                if let (true, Some(max_leg_position)) = (hedged, max_leg_position) {
+                    let new_position = ctx.current_position + ctx.proposed_size;
+                    if new_position.abs() > max_leg_position {
+                        let message = format!(
+                            "HedgedGroupLimit: leg position {:.2} in {} exceeds max {:.2}{}",
+                            new_position.abs(),
+                            ctx.market_id,
+                            max_leg_position,
+                            probation
+                        );
+                        violations.push(
+                            Violation::new("HedgedGroupLimit", message)
+                                .with_values(max_leg_position, new_position.abs())
+                                .in_market(&ctx.market_id),
+                        );
+                    }
+                }
+
+                RiskDecision::from_violations(violations)
+            })
+            .collect();
+
+        let decision = group_decision(decisions, group_violations, net_exposure, hedged);
+        // The legs are submitted together or not at all
+        if decision.aggregate.allowed {
+            for leg in legs {
+                self.record_order(&leg.context, &decision.aggregate);
+            }
+        }
+        decision
+    }
+
+    /// Evaluate a batch of contexts against a candidate policy set
+    ///
+    /// Runs without touching any live engine (the runtime kill-switch and
+    /// recorded trade outcomes are not applied), so operators can replay historical or hypothetical
+    /// contexts and see what a policy change would reject before
+    /// deploying it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ag_risk::{RiskContext, RiskEngine, RiskPolicyConfig};
+    ///
+    /// let candidate: RiskPolicyConfig = serde_yaml::from_str(r#"
+    /// policies:
+    ///   - type: PositionLimit
+    ///     max_size: 500.0
+    /// "#).unwrap();
+    ///
+    /// let contexts = vec![RiskContext {
+    ///     market_id: "0x123".to_string(),
+    ///     current_position: 400.0,
+    ///     proposed_size: 200.0,
+    ///     inventory_value_usd: 1000.0,
+    /// }];
+    ///
+    /// let report = RiskEngine::simulate(&candidate, &contexts);
+    /// assert_eq!(report.rejected, 1);
+    /// assert_eq!(report.rejections_by_rule["PositionLimit"], 1);
+    /// ```
+    pub fn simulate(policies: &RiskPolicyConfig, contexts: &[RiskContext]) -> SimulationReport {
+        let engine = Self::new(policies.clone());
+        let mut report = SimulationReport {
+            total: contexts.len(),
+            ..Default::default()
+        };
+
+        let active = engine.policies();
+        for (index, ctx) in contexts.iter().enumerate() {
+            let mut rejected = false;
+            for policy in active.active() {
+                if !policy.applies_to_market(&ctx.market_id) || is_group_only(policy) {
+                    continue;
+                }
+                if let Some(violation) = engine.evaluate_policy(policy, ctx) {
+                    if violation.severity < RiskAction::Reject {
+                        continue;
+                    }
+                    rejected = true;
+                    *report.rejections_by_rule.entry(policy.label()).or_insert(0) += 1;
+                    if active.short_circuits(&violation) {
+                        break;
+                    }
+                }
+            }
+
+            if rejected {
+                report.rejected += 1;
+                report.rejected_indices.push(index);
+            } else {
+                report.allowed += 1;
+            }
+        }
+
+        report
+    }
+
+    /// Record the realized PnL of a closed round trip
+    ///
+    /// Losses extend the market's losing streak; a profitable (or flat)
+    /// round trip resets it. Feeds `LossStreakCooldown` and
+    /// `DailyLossLimit` policies.
+    pub fn record_trade_outcome(&self, market_id: &str, realized_pnl: f64) {
+        self.record_trade_outcome_at(market_id, realized_pnl, self.now());
+    }
+
+    /// Record a round trip that closed at `closed_at`
+    ///
+    /// Use this when replaying history or when built without the `clock`
+    /// feature.
+    pub fn record_trade_outcome_at(&self, market_id: &str, realized_pnl: f64, closed_at: DateTime<Utc>) {
+        {
+            let mut pnl = self.realized_pnl.write().unwrap();
+            let window_start = closed_at - Duration::hours(DAILY_LOSS_WINDOW_HOURS);
+            pnl.retain(|(at, _)| *at > window_start);
+            let index = pnl.partition_point(|(at, _)| *at <= closed_at);
+            pnl.insert(index, (closed_at, realized_pnl));
+        }
+
+        let mut streaks = self.loss_streaks.write().unwrap();
+        if realized_pnl < 0.0 {
+            let streak = streaks.entry(market_id.to_string()).or_default();
+            streak.push(closed_at);
+            if streak.len() > MAX_LOSS_HISTORY {
+                streak.remove(0);
+            }
+        } else {
+            streaks.remove(market_id);
+        }
+    }
+
+    /// Report the unrealized PnL of all open positions (mark to market);
+    /// feeds `DailyLossLimit` policies
+    pub fn set_unrealized_pnl(&self, pnl_usd: f64) {
+        *self.unrealized_pnl.write().unwrap() = pnl_usd;
+    }
+
+    /// PnL over the rolling 24 hours ending now: realized PnL of round
+    /// trips closed in the window plus the last reported unrealized PnL
+    pub fn daily_pnl(&self) -> f64 {
+        let window_start = self.now() - Duration::hours(DAILY_LOSS_WINDOW_HOURS);
+        let realized: f64 = self
+            .realized_pnl
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(at, _)| *at > window_start)
+            .map(|(_, pnl)| pnl)
+            .sum();
+        realized + *self.unrealized_pnl.read().unwrap()
+    }
+
+    /// Orders allowed in a market over the last minute, as counted by
+    /// `OrderRateLimit` policies
+    pub fn order_rate(&self, market_id: &str) -> u32 {
+        let window_start = self.now().timestamp() - ORDER_RATE_WINDOW_SECS;
+        self.order_rates
+            .read()
+            .unwrap()
+            .get(market_id)
+            .map(|buckets| buckets.iter().filter(|(second, _)| *second > window_start).map(|(_, count)| count).sum())
+            .unwrap_or(0)
+    }
+
+    /// Report the cash capital (free plus locked collateral) backing the
+    /// inventory; feeds `BankrollLimit` policies
+    pub fn set_bankroll(&self, cash_usd: f64) {
+        *self.bankroll.write().unwrap() = Some(cash_usd);
+    }
+
+    /// Last reported cash capital
+    pub fn bankroll(&self) -> Option<f64> {
+        *self.bankroll.read().unwrap()
+    }
+
+    /// Report when a market closes; feeds `TradingWindow` policies
+    pub fn set_market_close(&self, market_id: &str, close_at: DateTime<Utc>) {
+        self.market_closes.write().unwrap().insert(market_id.to_string(), close_at);
+    }
+
+    /// Forget a market's close time (e.g. after it reopens)
+    pub fn clear_market_close(&self, market_id: &str) -> Option<DateTime<Utc>> {
+        self.market_closes.write().unwrap().remove(market_id)
+    }
+
+    /// Reported close time of a market
+    pub fn market_close(&self, market_id: &str) -> Option<DateTime<Utc>> {
+        self.market_closes.read().unwrap().get(market_id).copied()
+    }
+
+    /// Group outcome markets into an event, exactly one of which resolves
+    /// YES; feeds `CapitalAtRiskLimit` policies
+    pub fn register_event<I, S>(&self, event_id: &str, markets: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.margin.write().unwrap().register_event(event_id, markets);
+    }
+
+    /// Apply a fill (signed size: +buy, -sell) to the margin book
+    pub fn record_fill(&self, market_id: &str, size: f64, price: f64) {
+        self.margin.write().unwrap().record_fill(market_id, size, price);
+    }
+
+    /// Report a market's current price, used to price proposed trades in
+    /// `CapitalAtRiskLimit` checks and to find extreme prices for
+    /// `PayoutLimit`
+    pub fn set_mark(&self, market_id: &str, price: f64) {
+        self.margin.write().unwrap().set_mark(market_id, price);
+    }
+
+    /// Group a market under an asset traded on several venues; feeds
+    /// `ExposureAcrossVenues` policies
+    ///
+    /// Markets without an asset are their own asset.
+    pub fn set_market_asset(&self, market_id: &str, asset: &str) {
+        self.market_assets.write().unwrap().insert(market_id.to_string(), asset.to_string());
+    }
+
+    /// Asset a market is grouped under
+    pub fn asset_of(&self, market_id: &str) -> String {
+        self.market_assets
+            .read()
+            .unwrap()
+            .get(market_id)
+            .cloned()
+            .unwrap_or_else(|| market_id.to_string())
+    }
+
+    /// Report the position held in a market on one venue; feeds
+    /// `ExposureAcrossVenues` policies
+    pub fn set_venue_position(&self, market_id: &str, venue: &str, size: f64) {
+        let key = (market_id.to_string(), venue.to_string());
+        let mut positions = self.venue_positions.write().unwrap();
+        if size == 0.0 {
+            positions.remove(&key);
+        } else {
+            positions.insert(key, size);
+        }
+    }
+
+    /// Apply a fill (signed size: +buy, -sell) to a market's position on
+    /// one venue
+    pub fn record_venue_fill(&self, market_id: &str, venue: &str, size: f64) {
+        let key = (market_id.to_string(), venue.to_string());
+        let mut positions = self.venue_positions.write().unwrap();
+        let position = positions.entry(key.clone()).or_insert(0.0);
+        *position += size;
+        if position.abs() < 1e-9 {
+            positions.remove(&key);
+        }
+    }
+
+    /// Forget a market's positions on every venue (e.g. after it resolved)
+    pub fn clear_venue_positions(&self, market_id: &str) {
+        self.venue_positions.write().unwrap().retain(|(market, _), _| market != market_id);
+    }
+
+    /// Positions of an asset per venue, summed over the asset's markets
+    pub fn venue_positions(&self, asset: &str) -> BTreeMap<String, f64> {
+        let assets = self.market_assets.read().unwrap();
+        let mut by_venue = BTreeMap::new();
+        for ((market, venue), size) in self.venue_positions.read().unwrap().iter() {
+            if assets.get(market).map_or(market.as_str(), String::as_str) == asset {
+                *by_venue.entry(venue.clone()).or_insert(0.0) += size;
+            }
+        }
+        by_venue
+    }
+
+    /// Aggregate position of an asset across all venues
+    pub fn asset_exposure(&self, asset: &str) -> f64 {
+        self.venue_positions(asset).values().sum()
+    }
+
+    /// Report per-market return series of equal length and the signed USD
+    /// value held per market; feeds [`marginal_cvar`](Self::marginal_cvar)
+    pub fn set_return_history(&self, asset_returns: HashMap<String, Vec<f64>>, values: HashMap<String, f64>) {
+        *self.return_history.write().unwrap() = ReturnHistory { asset_returns, values };
+    }
+
+    /// CVaR of the reported portfolio before and after adding
+    /// `trade_value_usd` (signed: +buy, -sell) to a market
+    ///
+    /// Uses historical CVaR over the reported return history (see
+    /// [`VarEngine::calculate_marginal_cvar`]); fails with
+    /// `InsufficientData` when the market has no history.
+    pub fn marginal_cvar(
+        &self,
+        market_id: &str,
+        trade_value_usd: f64,
+        confidence_level: f64,
+    ) -> Result<MarginalCvar, AdvancedRiskError> {
+        let history = self.return_history.read().unwrap();
+        VarEngine::new(VarConfig::default()).calculate_marginal_cvar(
+            &history.asset_returns,
+            &history.values,
+            market_id,
+            trade_value_usd,
+            confidence_level,
+        )
+    }
+
+    /// Worst-case loss of the margin book across all events, in USD
+    pub fn capital_at_risk(&self) -> f64 {
+        self.margin.read().unwrap().capital_at_risk()
+    }
+
+    /// Copy of the margin book
+    pub fn margin(&self) -> MarginEstimator {
+        self.margin.read().unwrap().clone()
+    }
+
+    /// Replace the margin book (e.g. after reconciling positions)
+    pub fn set_margin(&self, margin: MarginEstimator) {
+        *self.margin.write().unwrap() = margin;
+    }
+
+    /// End of the active loss-streak cooldown for a market, if any
+    pub fn cooldown_until(&self, market_id: &str) -> Option<DateTime<Utc>> {
+        let now = self.now();
+        self.policies()
+            .active()
+            .filter(|policy| policy.applies_to_market(market_id))
+            .filter_map(|policy| self.loss_cooldown_end(policy, market_id))
+            .filter(|until| *until > now)
+            .max()
+    }
+
+    /// Report a hard limit breach (e.g. the daily loss limit), starting
+    /// the staged recovery workflow
+    ///
+    /// Entries are blocked for the configured cooldown, then allowed at
+    /// reduced limits for the probation period, then full limits return.
+    /// A breach during cooldown or probation restarts the cooldown. Does
+    /// nothing if the policy file has no `recovery` section.
+    pub fn trip_limit(&self, reason: &str) {
+        self.trip_limit_at(reason, self.now());
+    }
+
+    /// Report a hard limit breach that happened at `at`
+    pub fn trip_limit_at(&self, reason: &str, at: DateTime<Utc>) {
+        let policies = self.policies();
+        let Some(config) = &policies.config.recovery else {
+            return;
+        };
+        let event = self.recovery.write().unwrap().trip(config, reason, at);
+        self.push_recovery_events(vec![event]);
+    }
+
+    /// Current stage of the recovery workflow
+    pub fn recovery_state(&self) -> RecoveryState {
+        self.advance_recovery();
+        self.recovery.read().unwrap().clone()
+    }
+
+    /// Drain the recovery stage changes since the last poll, oldest first
+    pub fn poll_recovery(&self) -> Vec<RecoveryEvent> {
+        self.advance_recovery();
+        std::mem::take(&mut *self.recovery_events.write().unwrap())
+    }
+
+    /// Restore full limits immediately, ending any cooldown or probation
+    pub fn reset_recovery(&self) {
+        let event = self.recovery.write().unwrap().reset(self.now());
+        self.push_recovery_events(event.into_iter().collect());
+    }
+
+    /// Evaluation counters per policy, keyed by policy label
+    /// (e.g. `PositionLimit (market: 0x123)`, `KillSwitch (active)`)
+    pub fn policy_counters(&self) -> BTreeMap<String, PolicyCounters> {
+        self.counters.read().unwrap().clone()
+    }
+
+    /// Enable or disable the policies `reference` names, by type (e.g.
+    /// `LossStreakCooldown`) or label (e.g. `PositionLimit (market: 0x123)`)
+    ///
+    /// Disabled policies are skipped by every evaluation until re-enabled.
+    /// Returns the number of policies matched.
+    pub fn set_policy_enabled(&self, reference: &str, enabled: bool) -> usize {
+        let mut slot = self.policies.write().unwrap();
+        let set = Arc::make_mut(&mut slot);
+        let mut matched = 0;
+        for (index, policy) in set.config.policies.iter().enumerate() {
+            if policy.matches(reference) {
+                set.enabled[index] = enabled;
+                matched += 1;
+            }
+        }
+        matched
+    }
+
+    /// Active policy configuration, including parameters changed with
+    /// [`set_policy_param`](Self::set_policy_param)
+    pub fn policy_config(&self) -> RiskPolicyConfig {
+        self.policies().config.clone()
+    }
+
+    /// Replace the policies, keeping the runtime state
+    ///
+    /// Counters, loss streaks, PnL, the recovery stage and the kill-switch
+    /// carry over. Policies enabled or disabled at runtime and parameters
+    /// changed with [`set_policy_param`](Self::set_policy_param) are
+    /// replaced by the new config. Evaluations already running finish
+    /// against the previous policies. Fails without changing anything if
+    /// the config has no policies or its `evaluation` section names a
+    /// policy it doesn't have.
+    pub fn reload(&self, config: RiskPolicyConfig) -> Result<(), String> {
+        let set = PolicySet::validated(config)?;
+        *self.policies.write().unwrap() = Arc::new(set);
+        Ok(())
+    }
+
+    /// Replace the policies from a YAML document, as [`reload`](Self::reload)
+    pub fn reload_yaml(&self, yaml: &str) -> Result<(), String> {
+        let set = PolicySet::from_yaml(yaml)?;
+        *self.policies.write().unwrap() = Arc::new(set);
+        Ok(())
+    }
+
+    /// Reload the policies whenever the YAML file at `path` changes
+    ///
+    /// The file is loaded right away, failing if it can't be read or is
+    /// invalid. After that, a write that doesn't parse or validate leaves
+    /// the active policies in place until the next valid write; outcomes
+    /// are reported by [`PolicyWatcher::poll`](crate::watch::PolicyWatcher::poll).
+    /// Watching stops when the returned watcher is dropped.
+    #[cfg(feature = "watch")]
+    pub fn watch_file(&self, path: impl AsRef<std::path::Path>) -> Result<crate::watch::PolicyWatcher, String> {
+        crate::watch::PolicyWatcher::start(path.as_ref(), self.policies.clone(), self.clock.clone())
+    }
+
+    /// Change a numeric parameter (e.g. `max_size`) of the policies
+    /// `reference` names, by type or label
+    ///
+    /// Returns the number of policies changed. Fails without changing any
+    /// policy if none matches, a matching policy has no such numeric
+    /// parameter, or the value is out of range: negative, zero for a limit
+    /// (`max_*`), or fractional for a count. Changed parameters are not
+    /// snapshotted, and the next [`reload`](Self::reload) (or change to a
+    /// watched file) replaces them with the new config's values.
+    pub fn set_policy_param(&self, reference: &str, param: &str, value: f64) -> Result<usize, String> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("{} must be a non-negative number (got {})", param, value));
+        }
+        if param.starts_with("max_") && value == 0.0 {
+            return Err(format!("{} is a limit and must be positive", param));
+        }
+        let mut slot = self.policies.write().unwrap();
+        let mut updated = Vec::new();
+        for (index, policy) in slot.config.policies.iter().enumerate() {
+            if !policy.matches(reference) {
+                continue;
+            }
+            let mut fields = serde_json::to_value(policy).map_err(|e| e.to_string())?;
+            match fields.get_mut(param) {
+                // Counts and durations stay integers
+                Some(field) if field.is_u64() => {
+                    if value.fract() != 0.0 {
+                        return Err(format!("{} of {} must be a whole number (got {})", param, policy.label(), value));
+                    }
+                    *field = serde_json::json!(value as u64);
+                }
+                Some(field) if field.is_number() => *field = serde_json::json!(value),
+                _ => return Err(format!("{} has no numeric parameter '{}'", policy.label(), param)),
+            }
+            let policy: PolicyRule = serde_json::from_value(fields).map_err(|e| e.to_string())?;
+            updated.push((index, policy));
+        }
+        if updated.is_empty() {
+            return Err(format!("no policy matches '{}'", reference));
+        }
+
+        let changed = updated.len();
+        let set = Arc::make_mut(&mut slot);
+        for (index, policy) in updated {
+            set.labels[index] = policy.label();
+            set.config.policies[index] = policy;
+        }
+        Ok(changed)
+    }
+
+    /// Labels of the policies currently disabled
+    pub fn disabled_policies(&self) -> Vec<String> {
+        let set = self.policies();
+        set.config
+            .policies
+            .iter()
+            .zip(set.enabled.iter())
+            .filter(|(_, enabled)| !**enabled)
+            .map(|(policy, _)| policy.label())
+            .collect()
+    }
+
+    /// Capture the engine's runtime state
+    pub fn snapshot(&self) -> RiskStateSnapshot {
+        RiskStateSnapshot {
+            version: SNAPSHOT_VERSION,
+            taken_at: self.now(),
+            kill_switch_active: self.is_kill_switch_active(),
+            kill_switch_history: self.kill_switch_history(),
+            loss_streaks: self.loss_streaks.read().unwrap().clone(),
+            realized_pnl: self.realized_pnl.read().unwrap().clone(),
+            counters: self.policy_counters(),
+            recovery: self.recovery.read().unwrap().clone(),
+        }
+    }
+
+    /// Replace the engine's runtime state with a snapshot
+    ///
+    /// Policies are unchanged; counters for policies no longer configured
+    /// are kept so history is not lost across config changes.
+    pub fn restore(&self, snapshot: &RiskStateSnapshot) {
+        *self.kill_switch_active.write().unwrap() = snapshot.kill_switch_active;
+        *self.kill_switch_history.write().unwrap() = snapshot.kill_switch_history.clone();
+        self.emit_kill_switch();
+        *self.loss_streaks.write().unwrap() = snapshot.loss_streaks.clone();
+        *self.realized_pnl.write().unwrap() = snapshot.realized_pnl.clone();
+        *self.counters.write().unwrap() = snapshot.counters.clone();
+        *self.recovery.write().unwrap() = snapshot.recovery.clone();
+    }
+
+    /// Trigger the kill-switch, blocking all future trades
+    ///
+    /// The activation is recorded with its source and reason, which are
+    /// quoted in every rejection while the switch is on. Triggering an
+    /// active kill-switch records the new reason.
+    pub fn trigger_kill_switch(&self, source: KillSwitchSource, reason: impl Into<String>) {
+        *self.kill_switch_active.write().unwrap() = true;
+        self.record_kill_switch(KillSwitchAction::Triggered, source, reason.into());
+    }
+
+    /// Reset the kill-switch, allowing trades again
+    pub fn reset_kill_switch(&self, source: KillSwitchSource, reason: impl Into<String>) {
+        *self.kill_switch_active.write().unwrap() = false;
+        self.record_kill_switch(KillSwitchAction::Reset, source, reason.into());
+    }
+
+    /// Check if kill-switch is currently active
+    pub fn is_kill_switch_active(&self) -> bool {
+        *self.kill_switch_active.read().unwrap()
+    }
+
+    /// The activation in force while the kill-switch is on
+    pub fn kill_switch_activation(&self) -> Option<KillSwitchEvent> {
+        if !self.is_kill_switch_active() {
+            return None;
+        }
+        self.kill_switch_history
+            .read()
+            .unwrap()
+            .last()
+            .filter(|event| event.action == KillSwitchAction::Triggered)
+            .cloned()
+    }
+
+    /// Recent kill-switch activations and resets, oldest first (at most
+    /// [`MAX_KILL_SWITCH_HISTORY`])
+    pub fn kill_switch_history(&self) -> Vec<KillSwitchEvent> {
+        self.kill_switch_history.read().unwrap().clone()
+    }
+
+    /// Check `GreeksLimit` policies against `provider`'s Greeks (see
+    /// [`crate::greeks`])
+    pub fn set_greeks_provider(&self, provider: Arc<dyn GreeksProvider>) {
+        *self.greeks_provider.write().unwrap() = Some(provider);
+    }
+
+    /// Stop checking Greeks; `GreeksLimit` policies allow everything
+    pub fn clear_greeks_provider(&self) {
+        *self.greeks_provider.write().unwrap() = None;
+    }
+
+    /// Emit metrics to `sink` from now on (see [`crate::telemetry`])
+    pub fn set_metrics_sink(&self, sink: Arc<dyn RiskMetricsSink>) {
+        *self.metrics_sink.write().unwrap() = Some(sink);
+        *self.telemetry.write().unwrap() = Telemetry {
+            last_publish: Some(self.now()),
+            ..Default::default()
+        };
+        self.emit_kill_switch();
+    }
+
+    /// Stop emitting metrics
+    pub fn clear_metrics_sink(&self) {
+        *self.metrics_sink.write().unwrap() = None;
+    }
+
+    /// Read the time from `clock` instead of the system clock
+    ///
+    /// Time-based policies (cooldowns, trading windows, the daily loss
+    /// window), breach recovery, kill-switch events and policy reloads all
+    /// use it, so a [`MockClock`](crate::clock::MockClock) makes them
+    /// deterministic in backtests and replays.
+    pub fn set_clock(&self, clock: SharedClock) {
+        *self.clock.write().unwrap() = clock;
+    }
+
+    /// Emit the sampled gauges: evaluation rate since the last call,
+    /// kill-switch state and `PositionLimit` headroom per market
+    ///
+    /// Call on the metrics interval. Does nothing without a sink.
+    pub fn publish_metrics(&self) {
+        let Some(sink) = self.metrics_sink() else {
+            return;
+        };
+        let now = self.now();
+        let (positions, evaluations, since) = {
+            let mut telemetry = self.telemetry.write().unwrap();
+            let evaluations = std::mem::take(&mut telemetry.evaluations);
+            let since = telemetry.last_publish.replace(now);
+            (telemetry.positions.clone(), evaluations, since)
+        };
+
+        sink.gauge(telemetry::KILL_SWITCH, &[], self.is_kill_switch_active() as u8 as f64);
+        if let Some(since) = since {
+            let secs = (now - since).num_milliseconds() as f64 / 1000.0;
+            if secs > 0.0 {
+                sink.gauge(telemetry::EVALUATIONS_PER_SEC, &[], evaluations as f64 / secs);
+            }
+        }
+
+        let policies = self.policies();
+        let scale = self.limit_scale();
+        for (market, position) in positions {
+            let limit = policies
+                .active()
+                .filter(|policy| policy.applies_to_market(&market))
+                .filter_map(|policy| match policy {
+                    PolicyRule::PositionLimit { max_size, .. } => Some(max_size * scale),
+                    _ => None,
+                })
+                .reduce(f64::min);
+            if let Some(limit) = limit {
+                sink.gauge(telemetry::HEADROOM, &[("market", &market)], limit - position.abs());
+            }
+        }
+    }
+
+    /// Evaluate a single policy against the context
+    ///
+    /// Returns Some(violation) if policy is violated, None otherwise
+    fn evaluate_policy(&self, policy: &PolicyRule, ctx: &RiskContext) -> Option<Violation> {
+        // Size limits are reduced during probation after a breach
+        let scale = self.limit_scale();
+        let probation = if scale < 1.0 { " (probation)" } else { "" };
+        let name = policy.name();
+        let violation = match policy {
+            PolicyRule::PositionLimit { market_id, max_size, .. } => {
+                let new_position = ctx.current_position + ctx.proposed_size;
+                let max_size = max_size * scale;
+                if new_position.abs() > max_size {
+                    let market_str = market_id
+                        .as_ref()
+                        .map(|m| format!(" (market: {})", m))
+                        .unwrap_or_default();
+                    let message = format!(
+                        "PositionLimit{}: new position {:.2} exceeds max {:.2}{}",
+                        market_str,
+                        new_position.abs(),
+                        max_size,
+                        probation
+                    );
+                    Some(Violation::new(name, message).with_values(max_size, new_position.abs()))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::InventoryLimit { max_value_usd, .. } => {
+                let max_value_usd = max_value_usd * scale;
+                if ctx.inventory_value_usd > max_value_usd {
+                    let message = format!(
+                        "InventoryLimit: inventory {:.2} USD exceeds max {:.2} USD{}",
+                        ctx.inventory_value_usd, max_value_usd, probation
+                    );
+                    Some(Violation::new(name, message).with_values(max_value_usd, ctx.inventory_value_usd))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::KillSwitch { enabled, .. } => {
+                if *enabled {
+                    Some(Violation::new(name, "KillSwitch: enabled in policy"))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::LossStreakCooldown { max_losses, .. } => {
+                let new_position = ctx.current_position + ctx.proposed_size;
+                if new_position.abs() <= ctx.current_position.abs() {
+                    // Reducing or closing is always allowed
+                    return None;
+                }
+
+                let until = self.loss_cooldown_end(policy, &ctx.market_id)?;
+                if until > self.now() {
+                    let message = format!(
+                        "LossStreakCooldown: {} consecutive losses in {}, entries blocked until {}",
+                        max_losses,
+                        ctx.market_id,
+                        until.to_rfc3339()
+                    );
+                    Some(Violation::new(name, message))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::BankrollLimit { max_fraction, .. } => {
+                let bankroll = self.bankroll()? + ctx.inventory_value_usd;
+                let max_fraction = max_fraction * scale;
+                let max_value_usd = max_fraction * bankroll.max(0.0);
+                if ctx.inventory_value_usd > max_value_usd {
+                    let message = format!(
+                        "BankrollLimit: inventory {:.2} USD exceeds {:.0}% of bankroll {:.2} USD{}",
+                        ctx.inventory_value_usd,
+                        max_fraction * 100.0,
+                        bankroll,
+                        probation
+                    );
+                    Some(Violation::new(name, message).with_values(max_value_usd, ctx.inventory_value_usd))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::TradingWindow { close_buffer_secs, .. } => {
+                let new_position = ctx.current_position + ctx.proposed_size;
+                if new_position.abs() <= ctx.current_position.abs() {
+                    // Reducing or closing is always allowed
+                    return None;
+                }
+
+                let close_at = self.market_close(&ctx.market_id)?;
+                let blocked_from = close_at - Duration::seconds(*close_buffer_secs as i64);
+                let now = self.now();
+                if now >= blocked_from {
+                    let message = format!(
+                        "TradingWindow: {} closes at {}, entries blocked from {}",
+                        ctx.market_id,
+                        close_at.to_rfc3339(),
+                        blocked_from.to_rfc3339()
+                    );
+                    // Seconds to close against the buffer
+                    let to_close = (close_at - now).num_milliseconds() as f64 / 1000.0;
+                    Some(Violation::new(name, message).with_values(*close_buffer_secs as f64, to_close))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::CapitalAtRiskLimit { max_loss_usd, .. } => {
+                let margin = self.margin.read().unwrap();
+                let current = margin.capital_at_risk();
+                let after = margin.capital_at_risk_after(&ctx.market_id, ctx.proposed_size);
+                let max_loss_usd = max_loss_usd * scale;
+                if after > max_loss_usd && after > current {
+                    let message = format!(
+                        "CapitalAtRiskLimit: worst-case loss {:.2} USD exceeds max {:.2} USD{}",
+                        after, max_loss_usd, probation
+                    );
+                    Some(Violation::new(name, message).with_values(max_loss_usd, after))
+                } else {
+                    None
+                }
+            }
+            // Evaluated for the whole group by `evaluate_group`
+            PolicyRule::HedgedGroupLimit { .. } => None,
+            PolicyRule::ExposureAcrossVenues { asset, max_exposure, .. } => {
+                let market_asset = self.asset_of(&ctx.market_id);
+                if asset.as_ref().is_some_and(|asset| *asset != market_asset) {
+                    return None;
+                }
+
+                let current = self.asset_exposure(&market_asset);
+                let after = current + ctx.proposed_size;
+                let max_exposure = max_exposure * scale;
+                if after.abs() <= current.abs() {
+                    // Reducing the aggregate exposure is always allowed
+                    return None;
+                }
+                if after.abs() > max_exposure {
+                    let venues = self.venue_positions(&market_asset).len();
+                    let message = format!(
+                        "ExposureAcrossVenues: exposure {:.2} to {} across {} venues exceeds max {:.2}{}",
+                        after.abs(),
+                        market_asset,
+                        venues,
+                        max_exposure,
+                        probation
+                    );
+                    Some(Violation::new(name, message).with_values(max_exposure, after.abs()))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::PayoutLimit {
+                max_payout_usd,
+                price_band,
+                ..
+            } => {
+                let new_position = ctx.current_position + ctx.proposed_size;
+                if new_position.abs() <= ctx.current_position.abs() {
+                    // Reducing or closing is always allowed
+                    return None;
+                }
+
+                let price = self.margin.read().unwrap().mark(&ctx.market_id)?;
+                let max_payout_usd = max_payout_usd * scale;
+                let capped = payout::cap_payout(new_position, price, *price_band, max_payout_usd);
+                if capped != new_position {
+                    let message = format!(
+                        "PayoutLimit: payout {:.2} USD at price {:.4} ({:.0}x leverage) exceeds max {:.2} USD{}",
+                        new_position.abs(),
+                        price,
+                        payout::effective_leverage(new_position, price),
+                        max_payout_usd,
+                        probation
+                    );
+                    Some(Violation::new(name, message).with_values(max_payout_usd, new_position.abs()))
+                } else {
+                    None
+                }
+            }
+            PolicyRule::GreeksLimit {
+                max_delta,
+                max_vega,
+                max_theta,
+                ..
+            } => {
+                let provider = self.greeks_provider.read().unwrap().clone()?;
+                let per_share = provider.market_greeks(&ctx.market_id)?;
+                let current = provider.portfolio_greeks()?;
+                let after = greeks::projected(&current, &per_share, ctx.proposed_size);
+                [
+                    ("delta", max_delta, current.delta, after.delta),
+                    ("vega", max_vega, current.vega, after.vega),
+                    ("theta", max_theta, current.theta, after.theta),
+                ]
+                .into_iter()
+                .find_map(|(greek, max, current, after)| {
+                    let max = (*max)? * scale;
+                    // Trades that do not increase the Greek are always allowed
+                    (after.abs() > max && after.abs() > current.abs()).then(|| {
+                        let message = format!(
+                            "GreeksLimit: portfolio {} {:.2} exceeds max {:.2}{}",
+                            greek,
+                            after.abs(),
+                            max,
+                            probation
+                        );
+                        Violation::new(name, message).with_values(max, after.abs())
+                    })
+                })
+            }
+            PolicyRule::DailyLossLimit { max_loss_usd, .. } => {
+                let loss = -self.daily_pnl();
+                if loss < *max_loss_usd {
+                    return None;
+                }
+                let message = format!(
+                    "DailyLossLimit: loss {:.2} USD over 24h reached max {:.2} USD",
+                    loss, max_loss_usd
+                );
+                if policy.action() == RiskAction::KillSwitch {
+                    // The kill-switch blocks reductions too
+                    Some(Violation::new(name, message).with_values(*max_loss_usd, loss))
+                } else if (ctx.current_position + ctx.proposed_size).abs() <= ctx.current_position.abs() {
+                    // Reducing or closing is always allowed
+                    None
+                } else {
+                    Some(Violation::new(name, message).with_values(*max_loss_usd, loss))
+                }
+            }
+            PolicyRule::OrderRateLimit { max_orders_per_minute, .. } => {
+                let orders = self.order_rate(&ctx.market_id);
+                if orders < *max_orders_per_minute {
+                    return None;
+                }
+                let message = format!(
+                    "OrderRateLimit: {} orders in {} in the last minute reached max {}",
+                    orders, ctx.market_id, max_orders_per_minute
+                );
+                Some(Violation::new(name, message).with_values(*max_orders_per_minute as f64, orders as f64))
+            }
+        }?
+        .with_severity(policy.action());
+
+        // The policy kill-switch is engine-wide and shared across a batch
+        if violation.severity == RiskAction::KillSwitch {
+            Some(violation)
+        } else {
+            Some(violation.in_market(&ctx.market_id))
+        }
+    }
+
+    /// Multiplier applied to size limits in the current recovery stage
+    fn limit_scale(&self) -> f64 {
+        match &self.policies().config.recovery {
+            Some(config) => self.recovery.read().unwrap().limit_scale(config),
+            None => 1.0,
+        }
+    }
+
+    /// Move the recovery workflow past every stage that has ended
+    fn advance_recovery(&self) {
+        let policies = self.policies();
+        let Some(config) = &policies.config.recovery else {
+            return;
+        };
+        let now = self.now();
+        if self.recovery.read().unwrap().until.is_none_or(|until| until > now) {
+            return;
+        }
+        let events = self.recovery.write().unwrap().advance(config, now);
+        self.push_recovery_events(events);
+    }
+
+    /// Rejection of an entry during a breach cooldown
+    fn recovery_violation(&self, ctx: &RiskContext) -> Option<Violation> {
+        self.policies().config.recovery.as_ref()?;
+        let state = self.recovery.read().unwrap();
+        if state.stage != RecoveryStage::Cooldown {
+            return None;
+        }
+        let new_position = ctx.current_position + ctx.proposed_size;
+        if new_position.abs() <= ctx.current_position.abs() {
+            // Reducing or closing is always allowed
+            return None;
+        }
+        let message = format!(
+            "RecoveryCooldown: {}, entries blocked until {}",
+            state.reason.as_deref().unwrap_or("limit breached"),
+            state.until.map(|until| until.to_rfc3339()).unwrap_or_default()
+        );
+        Some(Violation::new(RECOVERY_LABEL, message).in_market(&ctx.market_id))
+    }
+
+    /// Trip recovery when a trigger policy rejects at full limits, and the
+    /// kill-switch when a policy's action is `KillSwitch`
+    ///
+    /// Rejections against the reduced probation limits do not trip, or
+    /// probation could never end on its own. Violations of policies whose
+    /// action is `Allow` trip nothing.
+    fn trip_on_violation(&self, policy: &PolicyRule, violation: &Violation) {
+        if violation.severity < RiskAction::Reject {
+            return;
+        }
+        let is_kill_switch_policy = matches!(policy, PolicyRule::KillSwitch { .. });
+        if violation.severity == RiskAction::KillSwitch && !is_kill_switch_policy && !self.is_kill_switch_active() {
+            self.trigger_kill_switch(KillSwitchSource::Policy(policy.name().to_string()), violation.message.clone());
+        }
+
+        let policies = self.policies();
+        let Some(config) = &policies.config.recovery else {
+            return;
+        };
+        if config.is_trigger(policy.name()) && self.recovery.read().unwrap().stage == RecoveryStage::Normal {
+            self.trip_limit(&violation.message);
+        }
+    }
+
+    fn push_recovery_events(&self, events: Vec<RecoveryEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let mut pending = self.recovery_events.write().unwrap();
+        pending.extend(events);
+        let excess = pending.len().saturating_sub(MAX_RECOVERY_EVENTS);
+        pending.drain(..excess);
+    }
+
+    /// Active policy set, for the duration of one evaluation
+    fn policies(&self) -> Arc<PolicySet> {
+        self.policies.read().unwrap().clone()
+    }
+
+    /// Update the counters for one policy evaluation
+    fn count(&self, label: &str, rejected: bool) {
+        self.record_counts([(label, rejected)]);
+    }
+
+    /// Update the counters for several policy evaluations under one lock
+    ///
+    /// Labels are only allocated the first time a policy is counted.
+    fn record_counts<'a>(&self, counts: impl IntoIterator<Item = (&'a str, bool)>) {
+        let sink = self.metrics_sink();
+        let mut now = None;
+        let mut counters = self.counters.write().unwrap();
+        for (label, rejected) in counts {
+            if let (Some(sink), true) = (&sink, rejected) {
+                sink.counter(telemetry::REJECTIONS, &[("policy", label)], 1);
+            }
+            if !counters.contains_key(label) {
+                counters.insert(label.to_string(), PolicyCounters::default());
+            }
+            let counter = counters.get_mut(label).unwrap();
+            counter.evaluations += 1;
+            if rejected {
+                counter.rejections += 1;
+                counter.last_rejection = Some(*now.get_or_insert_with(|| self.now()));
+            }
+        }
+    }
+
+    fn metrics_sink(&self) -> Option<Arc<dyn RiskMetricsSink>> {
+        self.metrics_sink.read().unwrap().clone()
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.read().unwrap().now()
+    }
+
+    /// Count an allowed order towards `OrderRateLimit` policies
+    fn record_order(&self, ctx: &RiskContext, decision: &RiskDecision) {
+        if !decision.allowed
+            || !self.policies().config.policies.iter().any(|policy| matches!(policy, PolicyRule::OrderRateLimit { .. }))
+        {
+            return;
+        }
+        let second = self.now().timestamp();
+        let mut rates = self.order_rates.write().unwrap();
+        let buckets = rates.entry(ctx.market_id.clone()).or_default();
+        while buckets.front().is_some_and(|(start, _)| *start <= second - ORDER_RATE_WINDOW_SECS) {
+            buckets.pop_front();
+        }
+        match buckets.back_mut() {
+            Some((last, count)) if *last >= second => *count += 1,
+            _ => buckets.push_back((second, 1)),
+        }
+    }
+
+    /// Count evaluated contexts and remember their positions for
+    /// `publish_metrics`
+    fn observe(&self, contexts: &[RiskContext]) {
+        let Some(sink) = self.metrics_sink() else {
+            return;
+        };
+        sink.counter(telemetry::EVALUATIONS, &[], contexts.len() as u64);
+        let mut telemetry = self.telemetry.write().unwrap();
+        telemetry.evaluations += contexts.len() as u64;
+        for ctx in contexts {
+            telemetry.positions.insert(ctx.market_id.clone(), ctx.current_position);
+        }
+    }
+
+    fn record_kill_switch(&self, action: KillSwitchAction, source: KillSwitchSource, reason: String) {
+        if let Some(sink) = self.metrics_sink() {
+            sink.counter(
+                telemetry::KILL_SWITCH_EVENTS,
+                &[("action", action.as_str()), ("source", source.kind())],
+                1,
+            );
+        }
+        {
+            let mut history = self.kill_switch_history.write().unwrap();
+            history.push(KillSwitchEvent {
+                action,
+                source,
+                reason,
+                at: self.now(),
+            });
+            if history.len() > MAX_KILL_SWITCH_HISTORY {
+                history.remove(0);
+            }
+        }
+        self.emit_kill_switch();
+    }
+
+    /// Rejection by the runtime kill-switch, quoting the activation
+    fn kill_switch_violation(&self) -> Violation {
+        let message = match self.kill_switch_activation() {
+            Some(event) => format!(
+                "{} since {} ({}): {}",
+                KILL_SWITCH_LABEL,
+                event.at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                event.source,
+                event.reason
+            ),
+            None => KILL_SWITCH_LABEL.to_string(),
+        };
+        Violation::new("KillSwitch", message).with_severity(RiskAction::KillSwitch)
+    }
+
+    fn emit_kill_switch(&self) {
+        if let Some(sink) = self.metrics_sink() {
+            sink.gauge(telemetry::KILL_SWITCH, &[], self.is_kill_switch_active() as u8 as f64);
+        }
+    }
+
+    /// End of the most recent cooldown a `LossStreakCooldown` policy
+    /// triggered for a market (which may already have passed)
+    ///
+    /// Walks the losing streak in order; each time `max_losses` losses
+    /// fall within the window a cooldown starts and those losses are
+    /// consumed, so only fresh losses can trigger the next one.
+    fn loss_cooldown_end(&self, policy: &PolicyRule, market_id: &str) -> Option<DateTime<Utc>> {
+        let PolicyRule::LossStreakCooldown {
+            max_losses,
+            window_secs,
+            cooldown_secs,
+            ..
+        } = policy
+        else {
+            return None;
+        };
+
+        let streaks = self.loss_streaks.read().unwrap();
+        let streak = streaks.get(market_id)?;
+        let max_losses = (*max_losses).max(1) as usize;
+        let window = Duration::seconds(*window_secs as i64);
+        let cooldown = Duration::seconds(*cooldown_secs as i64);
+
+        let mut start = 0;
+        let mut cooldown_end = None;
+        for (i, &closed_at) in streak.iter().enumerate() {
+            if i + 1 < start + max_losses {
+                continue;
+            }
+            if closed_at - streak[i + 1 - max_losses] <= window {
+                cooldown_end = Some(closed_at + cooldown);
+                start = i + 1;
+            }
+        }
+        cooldown_end
+    }
+}
+
+/// Whether a policy's outcome is the same for every context
+fn is_context_independent(policy: &PolicyRule) -> bool {
+    matches!(policy, PolicyRule::KillSwitch { .. })
+}
+
+/// Whether the policy only applies to multi-leg groups
+fn is_group_only(policy: &PolicyRule) -> bool {
+    matches!(policy, PolicyRule::HedgedGroupLimit { .. })
+}
+
+/// Whether a policy evaluation counts as a rejection (violations of
+/// policies whose action is `Allow` only warn)
+fn rejects(violation: &Option<Violation>) -> bool {
+    violation.as_ref().is_some_and(|violation| violation.severity >= RiskAction::Reject)
+}
+
+/// Combine leg decisions with group-wide violations
+fn group_decision(
+    legs: Vec<RiskDecision>,
+    group_violations: Vec<Violation>,
+    net_exposure: f64,
+    hedged: bool,
+) -> GroupDecision {
+    let mut violations = group_violations;
+    for violation in legs.iter().flat_map(|decision| &decision.violations) {
+        if !violations.contains(violation) {
+            violations.push(violation.clone());
+        }
+    }
+    let aggregate = RiskDecision::from_violations(violations);
+    GroupDecision {
+        legs,
+        aggregate,
+        net_exposure,
+        hedged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_limit_global() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 1000.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        // Should allow within limit
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 500.0,
+            proposed_size: 400.0,
+            inventory_value_usd: 5000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(decision.allowed);
+
+        // Should reject when exceeding limit
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 800.0,
+            proposed_size: 300.0,
+            inventory_value_usd: 5000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed);
+        assert_eq!(decision.violations.len(), 1);
+        assert!(decision.violations[0].message.contains("PositionLimit"));
+
+        let violation = &decision.violations[0];
+        assert_eq!(violation.policy, "PositionLimit");
+        assert_eq!(violation.limit, Some(1000.0));
+        assert_eq!(violation.observed, Some(1100.0));
+        assert_eq!(violation.market_id.as_deref(), Some("0x123"));
+        assert_eq!(violation.severity, RiskAction::Reject);
+    }
+
+    #[test]
+    fn test_position_limit_per_market() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    market_id: "0x123"
+    max_size: 500.0
+  - type: PositionLimit
+    market_id: "0x456"
+    max_size: 1000.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        // Should apply 500 limit to 0x123
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 400.0,
+            proposed_size: 200.0,
+            inventory_value_usd: 5000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed);
+
+        // Should apply 1000 limit to 0x456
+        let ctx = RiskContext {
+            market_id: "0x456".to_string(),
+            current_position: 400.0,
+            proposed_size: 200.0,
+            inventory_value_usd: 5000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(decision.allowed);
+    }
+
+    #[test]
+    fn test_inventory_limit() {
+        let yaml = r#"
+policies:
+  - type: InventoryLimit
+    max_value_usd: 10000.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        // Should allow within limit
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 500.0,
+            proposed_size: 100.0,
+            inventory_value_usd: 8000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(decision.allowed);
+
+        // Should reject when exceeding limit
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 500.0,
+            proposed_size: 100.0,
+            inventory_value_usd: 12000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("InventoryLimit"));
+    }
+
+    #[test]
+    fn test_kill_switch_in_policy() {
+        let yaml = r#"
+policies:
+  - type: KillSwitch
+    enabled: true
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 100.0,
+            proposed_size: 50.0,
+            inventory_value_usd: 1000.0,
+        };
+
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("KillSwitch"));
+    }
+
+    #[test]
+    fn test_kill_switch_trigger() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 10000.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 100.0,
+            proposed_size: 50.0,
+            inventory_value_usd: 1000.0,
+        };
+
+        // Should allow initially
+        assert!(engine.evaluate(&ctx).allowed);
+
+        // Trigger kill-switch
+        engine.trigger_kill_switch(KillSwitchSource::External("pager".to_string()), "venue outage");
+        assert!(engine.is_kill_switch_active());
+
+        // Should now reject, quoting the activation
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed);
+        let message = &decision.violations[0].message;
+        assert!(message.starts_with("KillSwitch (active) since "));
+        assert!(message.ends_with("(external pager): venue outage"));
+        assert_eq!(decision.severity(), RiskAction::KillSwitch);
+        assert_eq!(decision.violations[0].market_id, None);
+
+        // Re-triggering records the new reason
+        engine.trigger_kill_switch(KillSwitchSource::Policy("CapitalAtRiskLimit".to_string()), "loss limit");
+        assert_eq!(engine.kill_switch_activation().unwrap().reason, "loss limit");
+
+        // Reset kill-switch
+        engine.reset_kill_switch(KillSwitchSource::Manual, "venue back up");
+        assert!(!engine.is_kill_switch_active());
+        assert_eq!(engine.kill_switch_activation(), None);
+
+        // Should allow again
+        assert!(engine.evaluate(&ctx).allowed);
+
+        let history = engine.kill_switch_history();
+        let actions: Vec<_> = history.iter().map(|e| (e.action, e.source.kind())).collect();
+        assert_eq!(
+            actions,
+            vec![
+                (KillSwitchAction::Triggered, "external"),
+                (KillSwitchAction::Triggered, "policy"),
+                (KillSwitchAction::Reset, "manual"),
+            ]
+        );
+        assert!(history.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[test]
+    fn test_dry_run_leaves_no_trace() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 100.0
+recovery:
+  cooldown_secs: 60
+  probation_secs: 60
+  triggers: [PositionLimit]
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 80.0,
+            proposed_size: 50.0,
+            inventory_value_usd: 0.0,
+        };
+
+        let decision = engine.dry_run(&ctx);
+        assert!(!decision.allowed);
+        assert_eq!(decision, engine.evaluate(&ctx));
+
+        // Only the real evaluation was counted and tripped recovery
+        assert_eq!(engine.policy_counters()["PositionLimit"].evaluations, 1);
+        assert_eq!(engine.recovery_state().stage, RecoveryStage::Cooldown);
+    }
+
+    #[test]
+    fn test_multiple_violations() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 100.0
+  - type: InventoryLimit
+    max_value_usd: 500.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 80.0,
+            proposed_size: 50.0,
+            inventory_value_usd: 1000.0,
+        };
+
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed);
+        assert_eq!(decision.violations.len(), 2);
+    }
+
+    #[test]
+    fn test_negative_positions() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 1000.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+
+        // Short position
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: -800.0,
+            proposed_size: -300.0,
+            inventory_value_usd: 5000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(!decision.allowed); // |-1100| > 1000
+
+        // Reducing short position
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: -800.0,
+            proposed_size: 200.0,
+            inventory_value_usd: 5000.0,
+        };
+        let decision = engine.evaluate(&ctx);
+        assert!(decision.allowed); // |-600| < 1000
+    }
+
+    #[test]
+    fn test_from_json() {
+        let json = r#"{
+  "policies": [
+    {
+      "type": "PositionLimit",
+      "max_size": 2000.0
+    }
+  ]
+}"#;
+        let engine = RiskEngine::from_json(json).unwrap();
+
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 1000.0,
+            proposed_size: 500.0,
+            inventory_value_usd: 5000.0,
+        };
+
+        let decision = engine.evaluate(&ctx);
+        assert!(decision.allowed);
+    }
+
+    #[test]
+    fn test_invalid_yaml() {
+        let yaml = "invalid: {yaml: [structure";
+        let result = RiskEngine::from_yaml(yaml);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let json = "{invalid json}";
+        let result = RiskEngine::from_json(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_evaluation_order_and_short_circuit() {
+        let yaml = |evaluation: &str| {
+            format!(
+                r#"
+policies:
+  - type: InventoryLimit
+    max_value_usd: 1000.0
+  - type: PositionLimit
+    max_size: 100.0
+{}"#,
+                evaluation
+            )
+        };
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 0.0,
+            proposed_size: 200.0,
+            inventory_value_usd: 5000.0,
+        };
+        let policies = |decision: &RiskDecision| -> Vec<String> {
+            decision.violations.iter().map(|v| v.policy.clone()).collect()
+        };
+
+        // Default: file order, every violation collected
+        let engine = RiskEngine::from_yaml(&yaml("")).unwrap();
+        assert_eq!(policies(&engine.evaluate(&ctx)), vec!["InventoryLimit", "PositionLimit"]);
+
+        // The cheap limit first, stopping there
+        let engine = RiskEngine::from_yaml(&yaml(
+            "evaluation:\n  order: [PositionLimit]\n  short_circuit: true\n",
+        ))
+        .unwrap();
+        assert_eq!(policies(&engine.evaluate(&ctx)), vec!["PositionLimit"]);
+        assert_eq!(policies(&engine.dry_run(&ctx)), vec!["PositionLimit"]);
+        let counters = engine.policy_counters();
+        assert_eq!(counters["PositionLimit"].rejections, 1);
+        assert!(!counters.contains_key("InventoryLimit"));
+        let batch = engine.evaluate_batch(std::slice::from_ref(&ctx));
+        assert_eq!(policies(&batch.aggregate), vec!["PositionLimit"]);
+
+        // Disabled in the config, re-enabled at runtime
+        let engine = RiskEngine::from_yaml(&yaml("evaluation:\n  disabled: [InventoryLimit]\n")).unwrap();
+        assert_eq!(engine.disabled_policies(), vec!["InventoryLimit".to_string()]);
+        assert_eq!(policies(&engine.evaluate(&ctx)), vec!["PositionLimit"]);
+        assert_eq!(engine.set_policy_enabled("PositionLimit", false), 1);
+        assert!(engine.evaluate(&ctx).allowed);
+        assert_eq!(engine.set_policy_enabled("InventoryLimit", true), 1);
+        assert_eq!(policies(&engine.evaluate(&ctx)), vec!["InventoryLimit"]);
+        assert_eq!(engine.set_policy_enabled("PayoutLimit", false), 0);
+    }
+
+    #[test]
+    fn test_simulate_policy_change() {
+        let current: RiskPolicyConfig = serde_yaml::from_str(
+            r#"
+policies:
+  - type: PositionLimit
+    max_size: 1000.0
+"#,
+        )
+        .unwrap();
+        let candidate: RiskPolicyConfig = serde_yaml::from_str(
+            r#"
+policies:
+  - type: PositionLimit
+    market_id: "0x123"
+    max_size: 500.0
+  - type: InventoryLimit
+    max_value_usd: 5000.0
+"#,
+        )
+        .unwrap();
+
+        let ctx = |market: &str, position: f64, size: f64, inventory: f64| RiskContext {
+            market_id: market.to_string(),
+            current_position: position,
+            proposed_size: size,
+            inventory_value_usd: inventory,
+        };
+        let contexts = vec![
+            ctx("0x123", 400.0, 200.0, 1000.0),
+            ctx("0x456", 400.0, 200.0, 1000.0),
+            ctx("0x123", 900.0, 200.0, 6000.0),
+            ctx("0x456", 100.0, 100.0, 6000.0),
+        ];
+
+        let baseline = RiskEngine::simulate(&current, &contexts);
+        assert_eq!(baseline.rejected, 1);
+        assert_eq!(baseline.rejected_indices, vec![2]);
+
+        let report = RiskEngine::simulate(&candidate, &contexts);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.allowed, 1);
+        assert_eq!(report.rejected, 3);
+        assert_eq!(report.rejections_by_rule["PositionLimit (market: 0x123)"], 2);
+        assert_eq!(report.rejections_by_rule["InventoryLimit"], 2);
+        assert_eq!(report.rejection_rate(), 0.75);
+        assert_eq!(report.newly_rejected(&baseline), vec![0, 3]);
+        assert!(report.newly_allowed(&baseline).is_empty());
+
+        // The live engine's kill-switch does not leak into simulations
+        let engine = RiskEngine::new(current.clone());
+        engine.trigger_kill_switch(KillSwitchSource::Manual, "test");
+        assert_eq!(RiskEngine::simulate(&current, &contexts).rejected, 1);
+    }
+
+    #[test]
+    fn test_loss_streak_cooldown() {
+        let yaml = r#"
+policies:
+  - type: LossStreakCooldown
+    max_losses: 3
+    window_secs: 3600
+    cooldown_secs: 1800
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let entry = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 100.0,
+            proposed_size: 50.0,
+            inventory_value_usd: 1000.0,
+        };
+        let exit = RiskContext {
+            proposed_size: -100.0,
+            ..entry.clone()
+        };
+
+        // Two losses and a win: streak resets
+        engine.record_trade_outcome("0x123", -10.0);
+        engine.record_trade_outcome("0x123", -5.0);
+        engine.record_trade_outcome("0x123", 2.0);
+        engine.record_trade_outcome("0x123", -1.0);
+        assert!(engine.evaluate(&entry).allowed);
+
+        // Three consecutive losses: entries blocked, exits allowed
+        engine.record_trade_outcome("0x123", -1.0);
+        engine.record_trade_outcome("0x123", -1.0);
+        let decision = engine.evaluate(&entry);
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("LossStreakCooldown"));
+        assert!(engine.evaluate(&exit).allowed);
+        assert!(engine.cooldown_until("0x123").is_some());
+
+        // Other markets are unaffected
+        let other = RiskContext {
+            market_id: "0x456".to_string(),
+            ..entry.clone()
+        };
+        assert!(engine.evaluate(&other).allowed);
+
+        // Losses spread wider than the window don't trigger; an expired
+        // cooldown no longer blocks
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let now = crate::clock::now();
+        for hours_ago in [5, 3, 1] {
+            engine.record_trade_outcome_at("0x123", -1.0, now - Duration::hours(hours_ago));
+        }
+        assert!(engine.evaluate(&entry).allowed);
+        engine.record_trade_outcome_at("0x123", -1.0, now - Duration::minutes(59));
+        engine.record_trade_outcome_at("0x123", -1.0, now - Duration::minutes(58));
+        assert!(engine.evaluate(&entry).allowed);
+        assert!(engine.cooldown_until("0x123").is_none());
+    }
+
+    #[test]
+    fn test_breach_recovery() {
+        let yaml = r#"
+recovery:
+  cooldown_secs: 3600
+  probation_secs: 3600
+  probation_scale: 0.5
+policies:
+  - type: PositionLimit
+    max_size: 100.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let entry = |proposed_size: f64| RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 0.0,
+            proposed_size,
+            inventory_value_usd: 0.0,
+        };
+        let exit = RiskContext {
+            current_position: 50.0,
+            proposed_size: -50.0,
+            ..entry(0.0)
+        };
+        assert!(engine.evaluate(&entry(80.0)).allowed);
+
+        // Cooldown: entries blocked, exits allowed
+        engine.trip_limit("daily loss");
+        let decision = engine.evaluate(&entry(10.0));
         assert!(!decision.allowed);
-        assert!(decision.violated_policies[0].contains("KillSwitch"));
+        assert!(decision.violations[0].message.starts_with("RecoveryCooldown: daily loss"));
+        assert!(engine.evaluate(&exit).allowed);
+        assert_eq!(engine.recovery_state().stage, RecoveryStage::Cooldown);
+
+        // Probation: limits halved
+        let now = crate::clock::now();
+        engine.trip_limit_at("daily loss", now - Duration::minutes(90));
+        let decision = engine.evaluate(&entry(80.0));
+        assert!(decision.violations[0].message.ends_with("exceeds max 50.00 (probation)"));
+        assert!(engine.evaluate(&entry(40.0)).allowed);
+
+        // Full limits restored
+        engine.trip_limit_at("daily loss", now - Duration::hours(3));
+        assert!(engine.evaluate(&entry(80.0)).allowed);
+        let stages: Vec<RecoveryStage> = engine.poll_recovery().into_iter().map(|e| e.stage).collect();
+        assert_eq!(
+            stages,
+            vec![
+                RecoveryStage::Cooldown,
+                RecoveryStage::Cooldown,
+                RecoveryStage::Probation,
+                RecoveryStage::Cooldown,
+                RecoveryStage::Probation,
+                RecoveryStage::Normal,
+            ]
+        );
+        assert!(engine.poll_recovery().is_empty());
+
+        // Trigger policies trip recovery when they reject
+        let engine = RiskEngine::from_yaml(&yaml.replace("probation_scale: 0.5", "triggers: [PositionLimit]")).unwrap();
+        assert!(!engine.evaluate(&entry(150.0)).allowed);
+        assert_eq!(engine.recovery_state().stage, RecoveryStage::Cooldown);
+        assert!(engine.snapshot().recovery.reason.unwrap().starts_with("PositionLimit"));
+        engine.reset_recovery();
+        assert!(engine.evaluate(&entry(80.0)).allowed);
+        assert_eq!(engine.poll_recovery().last().unwrap().stage, RecoveryStage::Normal);
     }
 
     #[test]
-    fn test_kill_switch_trigger() {
+    fn test_snapshot_restore() {
         let yaml = r#"
 policies:
   - type: PositionLimit
-    max_size: 10000.0
+    max_size: 100.0
+  - type: LossStreakCooldown
+    max_losses: 2
+    window_secs: 3600
+    cooldown_secs: 3600
 "#;
         let engine = RiskEngine::from_yaml(yaml).unwrap();
-
         let ctx = RiskContext {
             market_id: "0x123".to_string(),
-            current_position: 100.0,
+            current_position: 80.0,
             proposed_size: 50.0,
             inventory_value_usd: 1000.0,
         };
 
-        // Should allow initially
+        engine.evaluate(&ctx);
+        engine.record_trade_outcome("0x456", -1.0);
+        engine.record_trade_outcome("0x456", -1.0);
+        engine.trigger_kill_switch(KillSwitchSource::Manual, "maintenance");
+        engine.evaluate(&ctx);
+
+        let counters = engine.policy_counters();
+        assert_eq!(counters["PositionLimit"].evaluations, 1);
+        assert_eq!(counters["PositionLimit"].rejections, 1);
+        assert_eq!(counters["LossStreakCooldown"].rejections, 0);
+        assert_eq!(counters["KillSwitch (active)"].rejections, 1);
+
+        let json = engine.snapshot().to_json().unwrap();
+        let restored = RiskEngine::from_yaml(yaml).unwrap();
+        restored.restore(&RiskStateSnapshot::from_json(&json).unwrap());
+
+        assert!(restored.is_kill_switch_active());
+        assert_eq!(restored.kill_switch_activation().unwrap().reason, "maintenance");
+        assert_eq!(restored.policy_counters(), counters);
+        assert!(restored.cooldown_until("0x456").is_some());
+
+        // Snapshots from a newer format are refused
+        let mut future = engine.snapshot();
+        future.version = SNAPSHOT_VERSION + 1;
+        assert!(RiskStateSnapshot::from_json(&future.to_json().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_bankroll_limit() {
+        let yaml = r#"
+policies:
+  - type: BankrollLimit
+    max_fraction: 0.5
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let ctx = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 0.0,
+            proposed_size: 100.0,
+            inventory_value_usd: 600.0,
+        };
+
+        // No bankroll reported yet
         assert!(engine.evaluate(&ctx).allowed);
 
-        // Trigger kill-switch
-        engine.trigger_kill_switch();
-        assert!(engine.is_kill_switch_active());
+        // 600 of a 600 + 1000 bankroll is within 50%
+        engine.set_bankroll(1000.0);
+        assert!(engine.evaluate(&ctx).allowed);
 
-        // Should now reject
+        // 600 of a 600 + 400 bankroll is not
+        engine.set_bankroll(400.0);
         let decision = engine.evaluate(&ctx);
         assert!(!decision.allowed);
-        assert!(decision.violated_policies[0].contains("KillSwitch (active)"));
+        assert!(decision.violations[0].message.contains("BankrollLimit"));
+    }
 
-        // Reset kill-switch
-        engine.reset_kill_switch();
-        assert!(!engine.is_kill_switch_active());
+    #[test]
+    fn test_trading_window() {
+        let yaml = r#"
+policies:
+  - type: TradingWindow
+    close_buffer_secs: 600
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let entry = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 50.0,
+            proposed_size: 100.0,
+            inventory_value_usd: 0.0,
+        };
+        let exit = RiskContext {
+            proposed_size: -50.0,
+            ..entry.clone()
+        };
 
-        // Should allow again
-        assert!(engine.evaluate(&ctx).allowed);
+        // No close reported
+        assert!(engine.evaluate(&entry).allowed);
+
+        // Close is more than the buffer away
+        engine.set_market_close("0x123", Utc::now() + Duration::hours(1));
+        assert!(engine.evaluate(&entry).allowed);
+
+        // Inside the buffer only exits pass
+        engine.set_market_close("0x123", Utc::now() + Duration::minutes(5));
+        let decision = engine.evaluate(&entry);
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("TradingWindow"));
+        assert!(engine.evaluate(&exit).allowed);
+
+        assert!(engine.clear_market_close("0x123").is_some());
+        assert!(engine.evaluate(&entry).allowed);
     }
 
     #[test]
-    fn test_multiple_violations() {
+    fn test_capital_at_risk_limit() {
         let yaml = r#"
 policies:
-  - type: PositionLimit
-    max_size: 100.0
-  - type: InventoryLimit
-    max_value_usd: 500.0
+  - type: CapitalAtRiskLimit
+    max_loss_usd: 100.0
 "#;
         let engine = RiskEngine::from_yaml(yaml).unwrap();
+        engine.register_event("election", ["yes", "no"]);
+        engine.record_fill("yes", 150.0, 0.60);
+        engine.set_mark("no", 0.38);
+        assert!((engine.capital_at_risk() - 90.0).abs() < 1e-9);
 
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 80.0,
+        // Another 50 YES would put 120 USD at risk
+        let more_yes = RiskContext {
+            market_id: "yes".to_string(),
+            current_position: 150.0,
             proposed_size: 50.0,
-            inventory_value_usd: 1000.0,
+            inventory_value_usd: 90.0,
         };
+        let decision = engine.evaluate(&more_yes);
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("CapitalAtRiskLimit"));
 
-        let decision = engine.evaluate(&ctx);
+        // Buying NO hedges the book despite adding notional
+        let hedge = RiskContext {
+            market_id: "no".to_string(),
+            current_position: 0.0,
+            proposed_size: 150.0,
+            inventory_value_usd: 90.0,
+        };
+        assert!(engine.evaluate(&hedge).allowed);
+    }
+
+    #[test]
+    fn test_payout_limit() {
+        let yaml = r#"
+policies:
+  - type: PayoutLimit
+    max_payout_usd: 2500.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let buy = |market_id: &str, current_position: f64, proposed_size: f64| RiskContext {
+            market_id: market_id.to_string(),
+            current_position,
+            proposed_size,
+            inventory_value_usd: 0.0,
+        };
+
+        // No mark yet: no cap
+        assert!(engine.evaluate(&buy("longshot", 0.0, 10_000.0)).allowed);
+
+        // 10,000 shares at 0.02 is 10,000 USD of payout
+        engine.set_mark("longshot", 0.02);
+        let decision = engine.evaluate(&buy("longshot", 0.0, 10_000.0));
         assert!(!decision.allowed);
-        assert_eq!(decision.violated_policies.len(), 2);
+        assert!(decision.violations[0].message.contains("50x leverage"));
+        assert!(engine.evaluate(&buy("longshot", 0.0, 2_000.0)).allowed);
+        // Reducing an oversized position is allowed
+        assert!(engine.evaluate(&buy("longshot", 10_000.0, -5_000.0)).allowed);
+
+        // The same shares at 0.50, or shorted at 0.02, are not leveraged
+        engine.set_mark("coinflip", 0.50);
+        assert!(engine.evaluate(&buy("coinflip", 0.0, 10_000.0)).allowed);
+        assert!(engine.evaluate(&buy("longshot", 0.0, -10_000.0)).allowed);
+
+        // Near 1 the short side is capped
+        engine.set_mark("favourite", 0.97);
+        assert!(!engine.evaluate(&buy("favourite", 0.0, -10_000.0)).allowed);
+        assert!(engine.evaluate(&buy("favourite", 0.0, 10_000.0)).allowed);
     }
 
     #[test]
-    fn test_negative_positions() {
+    fn test_daily_loss_limit() {
         let yaml = r#"
 policies:
-  - type: PositionLimit
-    max_size: 1000.0
+  - type: DailyLossLimit
+    max_loss_usd: 100.0
 "#;
         let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let entry = RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 10.0,
+            proposed_size: 5.0,
+            inventory_value_usd: 0.0,
+        };
+        let exit = RiskContext {
+            proposed_size: -5.0,
+            ..entry.clone()
+        };
 
-        // Short position
+        // Losses older than 24h no longer count
+        let now = crate::clock::now();
+        engine.record_trade_outcome_at("0x123", -500.0, now - Duration::hours(25));
+        engine.record_trade_outcome_at("0x456", -50.0, now - Duration::hours(1));
+        engine.set_unrealized_pnl(-30.0);
+        assert!((engine.daily_pnl() + 80.0).abs() < 1e-9);
+        assert!(engine.evaluate(&entry).allowed);
+
+        engine.set_unrealized_pnl(-60.0);
+        let decision = engine.evaluate(&entry);
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("DailyLossLimit"));
+        assert!(engine.evaluate(&exit).allowed);
+
+        // Gains in the window offset losses
+        engine.record_trade_outcome_at("0x456", 20.0, now);
+        assert!(engine.evaluate(&entry).allowed);
+
+        // Realized PnL survives a restart; unrealized PnL is reported again
+        let restored = RiskEngine::from_yaml(yaml).unwrap();
+        restored.restore(&engine.snapshot());
+        assert!((restored.daily_pnl() + 30.0).abs() < 1e-9);
+
+        // Optionally the breach trips the kill-switch
+        let engine = RiskEngine::from_yaml(&yaml.replace("100.0", "100.0\n    action: KillSwitch")).unwrap();
+        engine.record_trade_outcome_at("0x123", -150.0, now);
+        let decision = engine.evaluate(&exit);
+        assert!(!decision.allowed);
+        assert_eq!(decision.severity(), RiskAction::KillSwitch);
+        assert!(engine.is_kill_switch_active());
+        assert_eq!(
+            engine.kill_switch_activation().unwrap().source,
+            KillSwitchSource::Policy("DailyLossLimit".to_string())
+        );
+    }
+
+    #[test]
+    fn test_policy_actions() {
+        let engine = RiskEngine::from_yaml(
+            r#"
+policies:
+  - type: InventoryLimit
+    max_value_usd: 1000.0
+    action: Allow
+  - type: PositionLimit
+    max_size: 100.0
+    action: KillSwitch
+"#,
+        )
+        .unwrap();
         let ctx = RiskContext {
             market_id: "0x123".to_string(),
-            current_position: -800.0,
-            proposed_size: -300.0,
-            inventory_value_usd: 5000.0,
+            current_position: 50.0,
+            proposed_size: 20.0,
+            inventory_value_usd: 1500.0,
         };
+
+        // An `Allow` policy only warns, and doesn't count as a rejection
         let decision = engine.evaluate(&ctx);
-        assert!(!decision.allowed); // |-1100| > 1000
+        assert!(decision.allowed);
+        assert_eq!(decision.action, RiskAction::Allow);
+        assert_eq!(decision.violations.len(), 1);
+        assert_eq!(engine.policy_counters()["InventoryLimit"].rejections, 0);
 
-        // Reducing short position
+        // A `KillSwitch` policy rejects and arms the kill-switch
+        let decision = engine.evaluate(&RiskContext {
+            proposed_size: 60.0,
+            ..ctx.clone()
+        });
+        assert!(!decision.allowed);
+        assert_eq!(decision.action, RiskAction::KillSwitch);
+        assert!(engine.is_kill_switch_active());
+        assert_eq!(
+            engine.kill_switch_activation().unwrap().source,
+            KillSwitchSource::Policy("PositionLimit".to_string())
+        );
+        assert!(!engine.evaluate(&ctx).allowed);
+    }
+
+    #[test]
+    fn test_order_rate_limit() {
+        let engine = RiskEngine::from_yaml(
+            r#"
+policies:
+  - type: OrderRateLimit
+    max_orders_per_minute: 3
+"#,
+        )
+        .unwrap();
+        let clock = crate::clock::MockClock::new(crate::clock::now());
+        engine.set_clock(Arc::new(clock.clone()));
+        let ctx = |market_id: &str| RiskContext {
+            market_id: market_id.to_string(),
+            current_position: 0.0,
+            proposed_size: 10.0,
+            inventory_value_usd: 0.0,
+        };
+
+        // Dry runs don't count; markets are counted separately
+        assert!(engine.dry_run(&ctx("0x123")).allowed);
+        for _ in 0..3 {
+            assert!(engine.evaluate(&ctx("0x123")).allowed);
+            clock.advance(Duration::seconds(10));
+        }
+        assert_eq!(engine.order_rate("0x123"), 3);
+        let decision = engine.evaluate(&ctx("0x123"));
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("OrderRateLimit"));
+        assert!(engine.evaluate(&ctx("0x456")).allowed);
+
+        // Rejected orders don't count, so the first order ages out
+        clock.advance(Duration::seconds(31));
+        assert_eq!(engine.order_rate("0x123"), 2);
+        assert!(engine.evaluate(&ctx("0x123")).allowed);
+
+        // Later ladder levels count the earlier ones
+        clock.advance(Duration::seconds(120));
+        let batch = engine.evaluate_batch(&vec![ctx("0x123"); 5]);
+        assert_eq!(batch.rejected_indices(), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_set_policy_param() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    market_id: "0x123"
+    max_size: 100.0
+  - type: PositionLimit
+    max_size: 500.0
+  - type: OrderRateLimit
+    max_orders_per_minute: 60
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
         let ctx = RiskContext {
             market_id: "0x123".to_string(),
-            current_position: -800.0,
+            current_position: 0.0,
             proposed_size: 200.0,
-            inventory_value_usd: 5000.0,
+            inventory_value_usd: 0.0,
         };
-        let decision = engine.evaluate(&ctx);
-        assert!(decision.allowed); // |-600| < 1000
+        assert!(!engine.evaluate(&ctx).allowed);
+
+        assert_eq!(engine.set_policy_param("PositionLimit (market: 0x123)", "max_size", 250.0), Ok(1));
+        assert!(engine.evaluate(&ctx).allowed);
+        assert_eq!(engine.set_policy_param("PositionLimit", "max_size", 150.0), Ok(2));
+        assert!(!engine.evaluate(&ctx).allowed);
+
+        // Nothing changes on a bad reference or parameter
+        assert!(engine.set_policy_param("InventoryLimit", "max_value_usd", 1.0).is_err());
+        assert!(engine.set_policy_param("PositionLimit", "market_id", 1.0).is_err());
+        assert!(engine.set_policy_param("PositionLimit", "max_sise", 1.0).is_err());
+        assert!(engine.set_policy_param("PositionLimit", "max_size", f64::NAN).is_err());
+        assert!(engine.set_policy_param("PositionLimit", "max_size", -5.0).is_err());
+        assert!(engine.set_policy_param("PositionLimit", "max_size", 0.0).is_err());
+        assert!(engine.set_policy_param("OrderRateLimit", "max_orders_per_minute", 2.5).is_err());
+        assert_eq!(engine.set_policy_param("OrderRateLimit", "max_orders_per_minute", 30.0), Ok(1));
+        match &engine.policy_config().policies[1] {
+            PolicyRule::PositionLimit { max_size, .. } => assert_eq!(*max_size, 150.0),
+            other => panic!("unexpected policy {:?}", other),
+        }
     }
 
     #[test]
-    fn test_from_json() {
-        let json = r#"{
-  "policies": [
-    {
-      "type": "PositionLimit",
-      "max_size": 2000.0
+    fn test_greeks_limit() {
+        use crate::advanced::Greeks;
+        use crate::greeks::GreeksBook;
+
+        let yaml = r#"
+policies:
+  - type: GreeksLimit
+    max_delta: 100.0
+    max_vega: 50.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let buy = |market_id: &str, proposed_size: f64| RiskContext {
+            market_id: market_id.to_string(),
+            current_position: 0.0,
+            proposed_size,
+            inventory_value_usd: 0.0,
+        };
+
+        // No provider yet: no limit
+        assert!(engine.evaluate(&buy("call", 1_000.0)).allowed);
+
+        let book = Arc::new(GreeksBook::new());
+        book.set_market_greeks(
+            "call",
+            Greeks {
+                delta: 0.5,
+                gamma: 0.01,
+                vega: 0.2,
+                theta: -0.05,
+                rho: 0.0,
+            },
+        );
+        book.record_fill("call", 150.0);
+        engine.set_greeks_provider(book.clone());
+
+        // Delta 75 + 0.5 * 100 = 125 is over the band
+        let decision = engine.evaluate(&buy("call", 100.0));
+        assert!(!decision.allowed);
+        assert!(decision.violations[0].message.contains("portfolio delta 125.00"));
+        assert!(engine.evaluate(&buy("call", 40.0)).allowed);
+        // Selling brings delta back in; markets without Greeks are unaffected
+        assert!(engine.evaluate(&buy("call", -100.0)).allowed);
+        assert!(engine.evaluate(&buy("spot", 1_000.0)).allowed);
+
+        // Vega is checked too: 0.2 * 300 = 60
+        book.set_market_greeks(
+            "straddle",
+            Greeks {
+                delta: 0.0,
+                gamma: 0.02,
+                vega: 0.2,
+                theta: -0.1,
+                rho: 0.0,
+            },
+        );
+        let decision = engine.evaluate(&buy("straddle", 300.0));
+        assert!(decision.violations[0].message.contains("vega"));
+
+        engine.clear_greeks_provider();
+        assert!(engine.evaluate(&buy("straddle", 300.0)).allowed);
     }
-  ]
-}"#;
-        let engine = RiskEngine::from_json(json).unwrap();
 
-        let ctx = RiskContext {
-            market_id: "0x123".to_string(),
-            current_position: 1000.0,
-            proposed_size: 500.0,
-            inventory_value_usd: 5000.0,
+    #[test]
+    fn test_exposure_across_venues() {
+        let yaml = r#"
+policies:
+  - type: ExposureAcrossVenues
+    max_exposure: 500.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        engine.set_market_asset("pm-btc-100k", "btc-100k");
+        engine.set_market_asset("kalshi-btc-100k", "btc-100k");
+        engine.set_venue_position("pm-btc-100k", "polymarket", 300.0);
+        engine.record_venue_fill("kalshi-btc-100k", "kalshi", 150.0);
+        engine.record_venue_fill("kalshi-btc-100k", "kalshi", 50.0);
+        assert_eq!(engine.asset_exposure("btc-100k"), 500.0);
+        assert_eq!(engine.venue_positions("btc-100k")["kalshi"], 200.0);
+
+        // Within each venue's own position, but not in aggregate
+        let entry = RiskContext {
+            market_id: "pm-btc-100k".to_string(),
+            current_position: 300.0,
+            proposed_size: 100.0,
+            inventory_value_usd: 0.0,
         };
+        let decision = engine.evaluate(&entry);
+        assert!(!decision.allowed);
+        assert_eq!(decision.violations[0].limit, Some(500.0));
+        assert_eq!(decision.violations[0].observed, Some(600.0));
+        assert!(decision.violations[0].message.contains("across 2 venues"));
 
-        let decision = engine.evaluate(&ctx);
-        assert!(decision.allowed);
+        // Reducing on either venue is allowed
+        let exit = RiskContext {
+            market_id: "kalshi-btc-100k".to_string(),
+            current_position: 200.0,
+            proposed_size: -100.0,
+            inventory_value_usd: 0.0,
+        };
+        assert!(engine.evaluate(&exit).allowed);
+
+        // Other assets are limited on their own
+        let other = RiskContext {
+            market_id: "eth-5k".to_string(),
+            current_position: 0.0,
+            ..entry.clone()
+        };
+        assert!(engine.evaluate(&other).allowed);
+
+        engine.clear_venue_positions("pm-btc-100k");
+        assert_eq!(engine.asset_exposure("btc-100k"), 200.0);
+        assert!(engine.evaluate(&entry).allowed);
     }
 
     #[test]
-    fn test_invalid_yaml() {
-        let yaml = "invalid: {yaml: [structure";
-        let result = RiskEngine::from_yaml(yaml);
-        assert!(result.is_err());
+    fn test_marginal_cvar() {
+        let engine = RiskEngine::new(RiskPolicyConfig { policies: vec![], recovery: None, evaluation: Default::default() });
+        assert!(engine.marginal_cvar("m1", 100.0, 0.95).is_err());
+
+        let returns: Vec<f64> = (0..100).map(|i| ((i * 37) % 21) as f64 / 100.0 - 0.1).collect();
+        let inverse: Vec<f64> = returns.iter().map(|r| -r).collect();
+        engine.set_return_history(
+            HashMap::from([("m1".to_string(), returns), ("m2".to_string(), inverse)]),
+            HashMap::from([("m1".to_string(), 1000.0)]),
+        );
+
+        let adding = engine.marginal_cvar("m1", 1000.0, 0.95).unwrap();
+        assert!(adding.impact() > 0.0);
+        let hedging = engine.marginal_cvar("m2", 500.0, 0.95).unwrap();
+        assert!(hedging.impact() < 0.0);
+        assert_eq!(hedging.cvar_before, adding.cvar_before);
     }
 
     #[test]
-    fn test_invalid_json() {
-        let json = "{invalid json}";
-        let result = RiskEngine::from_json(json);
-        assert!(result.is_err());
+    fn test_evaluate_batch() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 250.0
+  - type: PositionLimit
+    market_id: "0x456"
+    max_size: 50.0
+  - type: KillSwitch
+    enabled: false
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let level = |market_id: &str, current_position: f64| RiskContext {
+            market_id: market_id.to_string(),
+            current_position,
+            proposed_size: 100.0,
+            inventory_value_usd: 0.0,
+        };
+        let ladder = vec![level("0x123", 0.0), level("0x123", 100.0), level("0x123", 200.0), level("0x456", 0.0)];
+
+        let batch = engine.evaluate_batch(&ladder);
+        assert_eq!(batch.rejected_indices(), vec![2, 3]);
+        assert_eq!(batch.allowed_count(), 2);
+        assert!(!batch.aggregate.allowed);
+        assert_eq!(batch.aggregate.violations.len(), 2);
+
+        // Same decisions and counters as evaluating one by one
+        let single = RiskEngine::from_yaml(yaml).unwrap();
+        for (ctx, decision) in ladder.iter().zip(&batch.decisions) {
+            assert_eq!(&single.evaluate(ctx), decision);
+        }
+        let batch_counters = engine.policy_counters();
+        let single_counters = single.policy_counters();
+        assert_eq!(batch_counters.len(), single_counters.len());
+        for (label, counter) in &batch_counters {
+            assert_eq!(counter.evaluations, single_counters[label].evaluations);
+            assert_eq!(counter.rejections, single_counters[label].rejections);
+        }
+
+        engine.trigger_kill_switch(KillSwitchSource::Manual, "test");
+        let batch = engine.evaluate_batch(&ladder);
+        assert_eq!(batch.allowed_count(), 0);
+        assert_eq!(batch.aggregate.violations, vec![engine.kill_switch_violation()]);
+        assert!(engine.evaluate_batch(&[]).aggregate.allowed);
+    }
+
+    #[test]
+    fn test_evaluate_batch_shared_policy_markets() {
+        let yaml = "policies:\n  - type: KillSwitch\n    enabled: true\n    action: Reject\n";
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let order = |market_id: &str| RiskContext {
+            market_id: market_id.to_string(),
+            current_position: 0.0,
+            proposed_size: 10.0,
+            inventory_value_usd: 0.0,
+        };
+        let orders = [order("0x123"), order("0x456"), order("0x123")];
+
+        // Evaluated once, reported against each order's own market
+        let batch = engine.evaluate_batch(&orders);
+        let markets: Vec<_> = batch.decisions.iter().map(|d| d.violations[0].market_id.as_deref()).collect();
+        assert_eq!(markets, vec![Some("0x123"), Some("0x456"), Some("0x123")]);
+        let single = RiskEngine::from_yaml(yaml).unwrap();
+        for (ctx, decision) in orders.iter().zip(&batch.decisions) {
+            assert_eq!(&single.evaluate(ctx), decision);
+        }
+        assert!(!engine.is_kill_switch_active());
+    }
+
+    #[test]
+    fn test_evaluate_group() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 100.0
+  - type: HedgedGroupLimit
+    max_net_exposure: 10.0
+    max_leg_position: 300.0
+  - type: CapitalAtRiskLimit
+    max_loss_usd: 100.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        engine.register_event("election", ["0xyes", "0xno"]);
+        engine.set_mark("0xyes", 0.6);
+        engine.set_mark("0xno", 0.4);
+        let ctx = |market_id: &str, proposed_size: f64| RiskContext {
+            market_id: market_id.to_string(),
+            current_position: 0.0,
+            proposed_size,
+            inventory_value_usd: 0.0,
+        };
+
+        // Buying YES and NO of one event is hedged: 200 each pays out 200
+        // for 200 USD, so nothing is at risk
+        let pair = [RiskLeg::new(ctx("0xyes", 200.0)), RiskLeg::complement(ctx("0xno", 200.0))];
+        let decision = engine.evaluate_group(&pair);
+        assert!(decision.hedged);
+        assert_eq!(decision.net_exposure, 0.0);
+        assert!(decision.aggregate.allowed, "{:?}", decision.aggregate);
+
+        // Each leg alone breaches the position and capital limits
+        assert!(!engine.evaluate(&pair[0].context).allowed);
+        let naked = engine.evaluate_group(&pair[..1]);
+        assert!(!naked.hedged);
+        assert!(naked.aggregate.violations.iter().any(|v| v.message.starts_with("HedgedGroupLimit: net")));
+        assert!(naked.aggregate.violations.iter().any(|v| v.message.starts_with("PositionLimit")));
+        assert!(naked.aggregate.violations.iter().any(|v| v.message.starts_with("CapitalAtRiskLimit")));
+
+        // Hedged legs are still capped
+        let large = [RiskLeg::new(ctx("0xyes", 400.0)), RiskLeg::complement(ctx("0xno", 400.0))];
+        let decision = engine.evaluate_group(&large);
+        assert!(decision.hedged);
+        assert_eq!(decision.legs.iter().filter(|leg| !leg.allowed).count(), 2);
+
+        // Without a group policy every leg must pass on its own
+        let strict = RiskEngine::from_yaml("policies:\n  - type: PositionLimit\n    max_size: 100.0\n").unwrap();
+        let decision = strict.evaluate_group(&pair);
+        assert!(!decision.hedged);
+        assert!(!decision.aggregate.allowed);
+        assert!(decision.legs.iter().all(|leg| !leg.allowed));
+
+        // Group-only policies are not evaluated for single contexts
+        let before = engine.policy_counters()["HedgedGroupLimit"].evaluations;
+        assert!(engine.evaluate(&ctx("0xyes", 10.0)).allowed);
+        assert_eq!(engine.policy_counters()["HedgedGroupLimit"].evaluations, before);
+    }
+
+    #[test]
+    fn test_metrics_sink() {
+        use crate::telemetry::{RecordingSink, EVALUATIONS, HEADROOM, KILL_SWITCH, KILL_SWITCH_EVENTS, REJECTIONS};
+
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 1000.0
+  - type: PositionLimit
+    market_id: "0x456"
+    max_size: 50.0
+"#;
+        let engine = RiskEngine::from_yaml(yaml).unwrap();
+        let ctx = |market_id: &str, current_position: f64, proposed_size: f64| RiskContext {
+            market_id: market_id.to_string(),
+            current_position,
+            proposed_size,
+            inventory_value_usd: 0.0,
+        };
+
+        // Nothing is recorded before a sink is attached
+        engine.evaluate(&ctx("0x123", 0.0, 2000.0));
+        let sink = Arc::new(RecordingSink::new());
+        engine.set_metrics_sink(sink.clone());
+        assert_eq!(sink.latest(KILL_SWITCH, &[]), Some(0.0));
+        assert_eq!(sink.total(EVALUATIONS, &[]), 0);
+
+        engine.evaluate(&ctx("0x123", 400.0, 100.0));
+        engine.evaluate_batch(&[ctx("0x456", 30.0, 10.0), ctx("0x456", 40.0, 20.0)]);
+        assert_eq!(sink.total(EVALUATIONS, &[]), 3);
+        assert_eq!(sink.total(REJECTIONS, &[("policy", "PositionLimit (market: 0x456)")]), 1);
+        assert_eq!(sink.total(REJECTIONS, &[("policy", "PositionLimit")]), 0);
+
+        engine.publish_metrics();
+        assert_eq!(sink.latest(HEADROOM, &[("market", "0x123")]), Some(600.0));
+        // The tighter market limit applies, from the last evaluated position
+        assert_eq!(sink.latest(HEADROOM, &[("market", "0x456")]), Some(10.0));
+
+        engine.trigger_kill_switch(KillSwitchSource::Manual, "test");
+        assert_eq!(sink.latest(KILL_SWITCH, &[]), Some(1.0));
+        assert_eq!(sink.total(KILL_SWITCH_EVENTS, &[("action", "triggered"), ("source", "manual")]), 1);
+        engine.evaluate(&ctx("0x123", 0.0, 1.0));
+        assert_eq!(sink.total(REJECTIONS, &[("policy", KILL_SWITCH_LABEL)]), 1);
+
+        engine.clear_metrics_sink();
+        engine.reset_kill_switch(KillSwitchSource::Manual, "test");
+        assert_eq!(sink.latest(KILL_SWITCH, &[]), Some(1.0));
+    }
+
+    #[test]
+    fn test_reload() {
+        let engine = RiskEngine::from_yaml(
+            r#"
+evaluation:
+  disabled: [InventoryLimit]
+policies:
+  - type: PositionLimit
+    max_size: 100.0
+  - type: InventoryLimit
+    max_value_usd: 10.0
+"#,
+        )
+        .unwrap();
+        let order = |size: f64| RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 0.0,
+            proposed_size: size,
+            inventory_value_usd: 50.0,
+        };
+        engine.set_policy_param("PositionLimit", "max_size", 300.0).unwrap();
+        assert!(engine.evaluate(&order(200.0)).allowed);
+
+        // Invalid configs change nothing
+        assert!(engine.reload_yaml("policies: [").is_err());
+        assert!(engine.reload_yaml("policies: []").is_err());
+        let unknown = "evaluation:\n  order: [KillSwitch]\npolicies:\n  - type: PositionLimit\n    max_size: 1.0\n";
+        assert_eq!(engine.reload_yaml(unknown).unwrap_err(), "no policy matches KillSwitch");
+        assert_eq!(engine.disabled_policies(), vec!["InventoryLimit"]);
+
+        // The file replaces runtime changes; state carries over
+        let limits = "policies:\n  - type: PositionLimit\n    max_size: 100.0\n  - type: InventoryLimit\n    max_value_usd: 10.0\n";
+        engine.reload_yaml(limits).unwrap();
+        let decision = engine.evaluate(&order(200.0));
+        assert_eq!(decision.violations.len(), 2);
+        assert!(engine.disabled_policies().is_empty());
+        assert_eq!(engine.policy_counters()["PositionLimit"].evaluations, 2);
+    }
+
+    #[test]
+    fn test_load_validates_like_reload() {
+        assert_eq!(
+            RiskEngine::from_yaml("policies: []").err().as_deref(),
+            Some("policy config has no policies")
+        );
+        let unknown = r#"{"evaluation": {"order": ["KillSwitch"]}, "policies": [{"type": "PositionLimit", "max_size": 1.0}]}"#;
+        assert_eq!(RiskEngine::from_json(unknown).err().as_deref(), Some("no policy matches KillSwitch"));
+        assert!(RiskEngine::from_json(r#"{"policies": [{"type": "KillSwitch", "enabled": false}]}"#).is_ok());
     }
 }
@@ -0,0 +1,183 @@
+//! Portfolio Greeks for `GreeksLimit`
+//!
+//! The engine does not price options itself: it asks a [`GreeksProvider`]
+//! for the portfolio's current Greeks and for the Greeks of one share of
+//! the market being traded, and projects the trade's effect from those.
+//! [`GreeksBook`] is a provider that keeps per-share Greeks and positions
+//! per market, filled from [`GreeksEngine::calculate_greeks`] or any other
+//! model (e.g. one for binary payoffs):
+//!
+//! ```rust
+//! use ag_risk::advanced::Greeks;
+//! use ag_risk::greeks::{GreeksBook, GreeksProvider};
+//!
+//! let book = GreeksBook::new();
+//! book.set_market_greeks("0xcall", Greeks { delta: 0.6, gamma: 0.02, vega: 0.15, theta: -0.03, rho: 0.0 });
+//! book.record_fill("0xcall", 100.0);
+//!
+//! let portfolio = book.portfolio_greeks().unwrap();
+//! assert!((portfolio.delta - 60.0).abs() < 1e-9);
+//! ```
+//!
+//! [`GreeksEngine::calculate_greeks`]: crate::advanced::GreeksEngine::calculate_greeks
+
+use crate::advanced::Greeks;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Source of the Greeks checked by `GreeksLimit`
+pub trait GreeksProvider: Send + Sync {
+    /// Current Greeks of the whole portfolio (None if unknown)
+    fn portfolio_greeks(&self) -> Option<Greeks>;
+
+    /// Greeks of one long share of `market_id` (None if the market carries
+    /// no option-like exposure)
+    fn market_greeks(&self, market_id: &str) -> Option<Greeks>;
+}
+
+/// Greeks of a portfolio after adding `size` shares with `per_share` Greeks
+pub fn projected(current: &Greeks, per_share: &Greeks, size: f64) -> Greeks {
+    Greeks {
+        delta: current.delta + per_share.delta * size,
+        gamma: current.gamma + per_share.gamma * size,
+        vega: current.vega + per_share.vega * size,
+        theta: current.theta + per_share.theta * size,
+        rho: current.rho + per_share.rho * size,
+    }
+}
+
+fn zero() -> Greeks {
+    Greeks {
+        delta: 0.0,
+        gamma: 0.0,
+        vega: 0.0,
+        theta: 0.0,
+        rho: 0.0,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MarketGreeks {
+    per_share: Greeks,
+    position: f64,
+}
+
+/// Per-market Greeks and positions, summed into portfolio Greeks
+///
+/// Per-share Greeks change with the underlying and time, so refresh them
+/// with [`set_market_greeks`](Self::set_market_greeks) on every repricing.
+#[derive(Debug, Default)]
+pub struct GreeksBook {
+    markets: RwLock<HashMap<String, MarketGreeks>>,
+}
+
+impl GreeksBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the Greeks of one long share of a market
+    pub fn set_market_greeks(&self, market_id: &str, per_share: Greeks) {
+        let mut markets = self.markets.write().unwrap();
+        match markets.get_mut(market_id) {
+            Some(market) => market.per_share = per_share,
+            None => {
+                markets.insert(market_id.to_string(), MarketGreeks { per_share, position: 0.0 });
+            }
+        }
+    }
+
+    /// Set a market's position (signed: +long, -short)
+    ///
+    /// Positions in markets without Greeks are ignored.
+    pub fn set_position(&self, market_id: &str, position: f64) {
+        if let Some(market) = self.markets.write().unwrap().get_mut(market_id) {
+            market.position = position;
+        }
+    }
+
+    /// Apply a fill (signed size: +buy, -sell)
+    pub fn record_fill(&self, market_id: &str, size: f64) {
+        if let Some(market) = self.markets.write().unwrap().get_mut(market_id) {
+            market.position += size;
+        }
+    }
+
+    /// Position held in a market
+    pub fn position(&self, market_id: &str) -> f64 {
+        self.markets.read().unwrap().get(market_id).map_or(0.0, |market| market.position)
+    }
+
+    /// Forget a market (e.g. after it expires)
+    pub fn remove_market(&self, market_id: &str) {
+        self.markets.write().unwrap().remove(market_id);
+    }
+}
+
+impl GreeksProvider for GreeksBook {
+    fn portfolio_greeks(&self) -> Option<Greeks> {
+        let markets = self.markets.read().unwrap();
+        Some(
+            markets
+                .values()
+                .fold(zero(), |total, market| projected(&total, &market.per_share, market.position)),
+        )
+    }
+
+    fn market_greeks(&self, market_id: &str) -> Option<Greeks> {
+        self.markets.read().unwrap().get(market_id).map(|market| market.per_share.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advanced::{GreeksConfig, GreeksEngine, OptionContract, OptionType};
+
+    #[test]
+    fn test_book_sums_positions() {
+        let book = GreeksBook::new();
+        book.set_market_greeks(
+            "call",
+            Greeks {
+                delta: 0.5,
+                gamma: 0.1,
+                vega: 0.2,
+                theta: -0.05,
+                rho: 0.0,
+            },
+        );
+        book.set_market_greeks(
+            "put",
+            Greeks {
+                delta: -0.4,
+                gamma: 0.1,
+                vega: 0.2,
+                theta: -0.04,
+                rho: 0.0,
+            },
+        );
+        book.record_fill("call", 100.0);
+        book.set_position("put", -50.0);
+        book.record_fill("spot", 1000.0);
+
+        let total = book.portfolio_greeks().unwrap();
+        assert!((total.delta - 70.0).abs() < 1e-9);
+        assert!((total.vega - 10.0).abs() < 1e-9);
+        assert!((total.theta + 3.0).abs() < 1e-9);
+        assert_eq!(book.position("spot"), 0.0);
+        assert!(book.market_greeks("spot").is_none());
+
+        // Greeks from the options engine plug straight in
+        let engine = GreeksEngine::new(GreeksConfig::default());
+        let option = OptionContract {
+            option_type: OptionType::Call,
+            strike: 100.0,
+            time_to_expiry: 0.25,
+            contract_size: 1.0,
+        };
+        let greeks = engine.calculate_greeks(&option, 100.0, 0.2, 0.05).unwrap();
+        book.set_market_greeks("call", greeks.clone());
+        assert!((book.portfolio_greeks().unwrap().delta - (100.0 * greeks.delta + 20.0)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,91 @@
+//! Kill-switch audit trail
+//!
+//! Every `RiskEngine::trigger_kill_switch` and `reset_kill_switch` records
+//! a [`KillSwitchEvent`] with who flipped the switch ([`KillSwitchSource`]),
+//! why and when. The engine keeps the most recent
+//! [`MAX_KILL_SWITCH_HISTORY`] events (see `RiskEngine::kill_switch_history`),
+//! includes them in state snapshots, and quotes the activation in the
+//! rejections it issues while the switch is on:
+//!
+//! ```text
+//! KillSwitch (active) since 2024-03-01T14:02:11Z (policy CapitalAtRiskLimit): loss limit breached
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of kill-switch events kept by the engine
+pub const MAX_KILL_SWITCH_HISTORY: usize = 100;
+
+/// Who flipped the kill-switch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "name", rename_all = "snake_case")]
+pub enum KillSwitchSource {
+    /// An operator
+    Manual,
+    /// A risk policy, by type (e.g. `CapitalAtRiskLimit`)
+    Policy(String),
+    /// A signal from outside the engine (e.g. a monitor alert or a halt file)
+    External(String),
+}
+
+impl KillSwitchSource {
+    /// Short label for metrics (`manual`, `policy` or `external`)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            KillSwitchSource::Manual => "manual",
+            KillSwitchSource::Policy(_) => "policy",
+            KillSwitchSource::External(_) => "external",
+        }
+    }
+}
+
+impl fmt::Display for KillSwitchSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KillSwitchSource::Manual => write!(f, "manual"),
+            KillSwitchSource::Policy(name) => write!(f, "policy {}", name),
+            KillSwitchSource::External(name) => write!(f, "external {}", name),
+        }
+    }
+}
+
+/// Whether the kill-switch was turned on or off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillSwitchAction {
+    Triggered,
+    Reset,
+}
+
+impl KillSwitchAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KillSwitchAction::Triggered => "triggered",
+            KillSwitchAction::Reset => "reset",
+        }
+    }
+}
+
+/// A kill-switch activation or reset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KillSwitchEvent {
+    pub action: KillSwitchAction,
+    pub source: KillSwitchSource,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+impl fmt::Display for KillSwitchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Kill-switch {} at {} ({}): {}",
+            self.action.as_str(),
+            self.at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            self.source,
+            self.reason
+        )
+    }
+}
@@ -8,6 +8,8 @@
 //! - **RiskEngine**: Policy evaluation engine for trading decisions
 //! - **PolymarketSimulator**: Position and PnL tracking for binary markets
 //! - **Policy System**: Flexible YAML/JSON-based risk policies
+//! - **MarginEstimator**: Worst-case loss of YES/NO books grouped by event
+//! - **RiskMetricsSink**: Pluggable destination for the engine's counters and gauges
 //!
 //! ## Example Usage
 //!
@@ -41,21 +43,54 @@
 mod policy;
 mod engine;
 mod simulator;
+mod state;
+mod margin;
+mod recovery;
+mod kill_switch;
+
+// Metrics emission
+pub mod telemetry;
+
+// Payout exposure near price extremes
+pub mod payout;
+
+// Portfolio Greeks checked by GreeksLimit
+pub mod greeks;
+
+// Wall-clock access (feature-gated for WASM builds)
+pub mod clock;
+
+// Forensic replay of recorded orders
+pub mod replay;
 
 // Advanced risk models
 pub mod advanced;
 
-pub use policy::{PolicyRule, RiskPolicyConfig};
-pub use engine::RiskEngine;
+// Hot reload of policy files
+#[cfg(feature = "watch")]
+pub mod watch;
+
+pub use policy::{EvaluationConfig, PolicyRule, RiskPolicyConfig};
+pub use engine::{BatchDecision, GroupDecision, RiskEngine, SimulationReport};
 pub use simulator::PolymarketSimulator;
+pub use margin::{BookPosition, MarginEstimator};
+pub use kill_switch::{KillSwitchAction, KillSwitchEvent, KillSwitchSource, MAX_KILL_SWITCH_HISTORY};
+pub use recovery::{RecoveryConfig, RecoveryEvent, RecoveryStage, RecoveryState};
+pub use state::{PolicyCounters, RiskStateSnapshot, SNAPSHOT_VERSION};
+pub use greeks::{GreeksBook, GreeksProvider};
+pub use telemetry::{RecordingSink, RiskMetricsSink, SeriesValue};
+pub use replay::{ReplayEvent, ReplayRecord, ReplayReport, ReplayVerdict};
+#[cfg(feature = "watch")]
+pub use watch::{PolicyReload, PolicyWatcher};
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Context information for risk evaluation
 ///
 /// This structure contains all necessary information to evaluate
 /// whether a proposed trading action should be allowed.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RiskContext {
     /// Market identifier (e.g., "0x123abc")
     pub market_id: String,
@@ -70,17 +105,63 @@ pub struct RiskContext {
     pub inventory_value_usd: f64,
 }
 
+/// One leg of an atomic multi-leg submission
+///
+/// Legs are evaluated together by [`RiskEngine::evaluate_group`], which
+/// nets their proposed sizes weighted by `hedge_ratio`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RiskLeg {
+    /// Context of the leg on its own
+    pub context: RiskContext,
+
+    /// Exposure to the group's common outcome per share: 1.0 when the leg
+    /// trades that outcome (e.g. the same question on two venues), -1.0 when
+    /// it trades the complement (e.g. NO against a YES leg)
+    pub hedge_ratio: f64,
+}
+
+impl RiskLeg {
+    /// Leg trading the group's common outcome
+    pub fn new(context: RiskContext) -> Self {
+        Self {
+            context,
+            hedge_ratio: 1.0,
+        }
+    }
+
+    /// Leg trading the complement of the group's common outcome
+    pub fn complement(context: RiskContext) -> Self {
+        Self {
+            context,
+            hedge_ratio: -1.0,
+        }
+    }
+
+    /// Signed exposure the leg adds to the group
+    pub fn exposure(&self) -> f64 {
+        self.hedge_ratio * self.context.proposed_size
+    }
+}
+
 /// Result of risk evaluation
 ///
-/// Contains the decision (allowed/rejected) and details about
-/// which policies were violated, if any.
+/// Contains the decision (allowed/rejected), the most severe action the
+/// violations call for and details about which policies were violated, if
+/// any. An allowed decision can still carry violations of policies whose
+/// `action` is `Allow`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RecordedDecision")]
 pub struct RiskDecision {
     /// Whether the action is allowed
     pub allowed: bool,
 
-    /// List of policy names that were violated
-    pub violated_policies: Vec<String>,
+    /// Most severe action the violations call for (`KillSwitch` means the
+    /// engine's kill-switch was triggered)
+    pub action: RiskAction,
+
+    /// Policy violations behind a rejection, or reported by policies
+    /// that only warn
+    pub violations: Vec<Violation>,
 }
 
 impl RiskDecision {
@@ -88,31 +169,167 @@ impl RiskDecision {
     pub fn allow() -> Self {
         Self {
             allowed: true,
-            violated_policies: Vec::new(),
+            action: RiskAction::Allow,
+            violations: Vec::new(),
         }
     }
 
     /// Create a decision that rejects the action
-    pub fn reject(violated_policies: Vec<String>) -> Self {
+    pub fn reject(violations: Vec<Violation>) -> Self {
         Self {
             allowed: false,
-            violated_policies,
+            action: most_severe(&violations).max(RiskAction::Reject),
+            violations,
+        }
+    }
+
+    /// Decide on the violations found: allowed unless one of them calls
+    /// for more than `Allow`
+    pub fn from_violations(violations: Vec<Violation>) -> Self {
+        let action = most_severe(&violations);
+        Self {
+            allowed: action == RiskAction::Allow,
+            action,
+            violations,
+        }
+    }
+
+    /// Most severe action the violations call for (same as `action`)
+    pub fn severity(&self) -> RiskAction {
+        self.action
+    }
+
+    /// Log messages of the violations
+    pub fn messages(&self) -> Vec<String> {
+        self.violations.iter().map(ToString::to_string).collect()
+    }
+}
+
+impl fmt::Display for RiskDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.allowed, self.violations.is_empty()) {
+            (true, true) => write!(f, "allowed"),
+            (true, false) => write!(f, "allowed with warnings: {}", self.messages().join("; ")),
+            (false, _) => write!(f, "rejected: {}", self.messages().join("; ")),
+        }
+    }
+}
+
+/// Serialized decision; `action` is missing from decisions recorded before
+/// it was added
+#[derive(Deserialize)]
+struct RecordedDecision {
+    allowed: bool,
+    #[serde(default)]
+    action: Option<RiskAction>,
+    violations: Vec<Violation>,
+}
+
+impl From<RecordedDecision> for RiskDecision {
+    fn from(recorded: RecordedDecision) -> Self {
+        let action = recorded.action.unwrap_or_else(|| {
+            let action = most_severe(&recorded.violations);
+            if recorded.allowed {
+                action
+            } else {
+                action.max(RiskAction::Reject)
+            }
+        });
+        Self {
+            allowed: recorded.allowed,
+            action,
+            violations: recorded.violations,
+        }
+    }
+}
+
+fn most_severe(violations: &[Violation]) -> RiskAction {
+    violations.iter().map(|violation| violation.severity).max().unwrap_or(RiskAction::Allow)
+}
+
+/// Why a policy rejected an action
+///
+/// Carries the policy type, the limit in force (after any probation
+/// scaling) and the value observed against it, so callers and dashboards
+/// can react to a rejection without parsing its message. `Display` gives
+/// the human-readable message for logs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    /// Policy type, e.g. "PositionLimit", "KillSwitch" or "RecoveryCooldown"
+    pub policy: String,
+
+    /// Limit in force, in the policy's unit
+    pub limit: Option<f64>,
+
+    /// Value observed against the limit, in the same unit
+    pub observed: Option<f64>,
+
+    /// Market the rejected action was in (`None` for engine-wide checks
+    /// such as the kill-switch or group-level limits)
+    pub market_id: Option<String>,
+
+    /// Action the violation calls for
+    pub severity: RiskAction,
+
+    /// Human-readable explanation
+    pub message: String,
+}
+
+impl Violation {
+    /// Rejection by `policy`, without limit or market details
+    pub fn new(policy: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            policy: policy.into(),
+            limit: None,
+            observed: None,
+            market_id: None,
+            severity: RiskAction::Reject,
+            message: message.into(),
         }
     }
+
+    /// Set the limit in force and the value observed against it
+    pub fn with_values(mut self, limit: f64, observed: f64) -> Self {
+        self.limit = Some(limit);
+        self.observed = Some(observed);
+        self
+    }
+
+    /// Set the market the rejected action was in
+    pub fn in_market(mut self, market_id: impl Into<String>) -> Self {
+        self.market_id = Some(market_id.into());
+        self
+    }
+
+    /// Set the action the violation calls for
+    pub fn with_severity(mut self, severity: RiskAction) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
 /// Risk action types for different violation severities
 ///
-/// This enum represents the action to take based on risk evaluation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// This enum represents the action to take based on risk evaluation, and
+/// is configured per policy with its `action` field.
+///
+/// Variants are ordered by severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum RiskAction {
-    /// Allow the action to proceed
+    /// Allow the action to proceed (a violation is only reported)
     Allow,
 
     /// Reject this specific action
     Reject,
 
-    /// Emergency stop - halt all trading
+    /// Emergency stop - halt all trading (triggers the engine's
+    /// kill-switch)
     KillSwitch,
 }
 
@@ -137,14 +354,43 @@ mod tests {
     fn test_risk_decision_allow() {
         let decision = RiskDecision::allow();
         assert!(decision.allowed);
-        assert!(decision.violated_policies.is_empty());
+        assert!(decision.violations.is_empty());
+        assert_eq!(decision.severity(), RiskAction::Allow);
     }
 
     #[test]
     fn test_risk_decision_reject() {
-        let decision = RiskDecision::reject(vec!["PositionLimit".to_string()]);
+        let violation = Violation::new("PositionLimit", "PositionLimit: new position 1100.00 exceeds max 1000.00")
+            .with_values(1000.0, 1100.0)
+            .in_market("0x123");
+        let decision = RiskDecision::reject(vec![violation]);
+        assert!(!decision.allowed);
+        assert_eq!(decision.violations.len(), 1);
+        assert_eq!(decision.violations[0].policy, "PositionLimit");
+        assert_eq!(decision.violations[0].limit, Some(1000.0));
+        assert_eq!(decision.severity(), RiskAction::Reject);
+        assert_eq!(
+            decision.to_string(),
+            "rejected: PositionLimit: new position 1100.00 exceeds max 1000.00"
+        );
+
+        // Decisions recorded before `action` existed still load
+        let recorded = r#"{"allowed":false,"violations":[{"policy":"PositionLimit","message":"limit","severity":"Reject"}]}"#;
+        let decision: RiskDecision = serde_json::from_str(recorded).unwrap();
+        assert_eq!(decision.action, RiskAction::Reject);
+    }
+
+    #[test]
+    fn test_risk_decision_from_violations() {
+        let warning = Violation::new("PositionLimit", "limit").with_severity(RiskAction::Allow);
+        let decision = RiskDecision::from_violations(vec![warning.clone()]);
+        assert!(decision.allowed);
+        assert_eq!(decision.action, RiskAction::Allow);
+        assert_eq!(decision.to_string(), "allowed with warnings: limit");
+
+        let kill = Violation::new("DailyLossLimit", "loss").with_severity(RiskAction::KillSwitch);
+        let decision = RiskDecision::from_violations(vec![warning, kill]);
         assert!(!decision.allowed);
-        assert_eq!(decision.violated_policies.len(), 1);
-        assert_eq!(decision.violated_policies[0], "PositionLimit");
+        assert_eq!(decision.action, RiskAction::KillSwitch);
     }
 }
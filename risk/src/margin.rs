@@ -0,0 +1,278 @@
+//! Worst-case loss estimation for binary outcome books
+//!
+//! A binary share pays 1 USD if its outcome resolves YES and nothing
+//! otherwise, so the loss on a book of YES/NO positions is bounded and can
+//! be computed exactly by settling the book under every resolution. Notional
+//! overstates the risk of hedged books: 100 YES at 0.60 plus 100 NO at 0.35
+//! is 95 USD of inventory but can lose nothing.
+//!
+//! Outcome markets are grouped into events with [`MarginEstimator::register_event`];
+//! exactly one market of an event resolves YES (a YES/NO pair, or the
+//! outcomes of a multi-outcome event). A market that belongs to no event is
+//! settled on its own, either winning or losing.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Positions below this size count as flat
+const FLAT_EPSILON: f64 = 1e-10;
+
+/// Position in one outcome market
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookPosition {
+    /// Net shares (positive = long, negative = short)
+    pub size: f64,
+
+    /// Average entry price per share (0.0 to 1.0)
+    pub avg_price: f64,
+}
+
+impl BookPosition {
+    /// Profit or loss when the market resolves (`payout` 1.0 for YES, 0.0 for NO)
+    fn settle(&self, payout: f64) -> f64 {
+        self.size * (payout - self.avg_price)
+    }
+
+    /// Apply a fill (signed size: +buy, -sell)
+    fn apply_fill(&mut self, size: f64, price: f64) {
+        let new_size = self.size + size;
+        if new_size.abs() < FLAT_EPSILON {
+            *self = Self::default();
+        } else if self.size.abs() < FLAT_EPSILON || self.size.signum() != new_size.signum() {
+            // Opening or reversing
+            *self = Self { size: new_size, avg_price: price };
+        } else if size.signum() == self.size.signum() {
+            // Adding: blend the entry price
+            self.avg_price = (self.size * self.avg_price + size * price) / new_size;
+            self.size = new_size;
+        } else {
+            // Reducing keeps the entry price
+            self.size = new_size;
+        }
+    }
+}
+
+/// Worst-case loss estimator for YES/NO positions grouped by event
+#[derive(Debug, Clone, Default)]
+pub struct MarginEstimator {
+    /// Event of each registered outcome market
+    market_events: HashMap<String, String>,
+    /// Outcome markets of each event
+    events: HashMap<String, Vec<String>>,
+    /// Positions per outcome market
+    positions: HashMap<String, BookPosition>,
+    /// Last known price per outcome market
+    marks: HashMap<String, f64>,
+}
+
+impl MarginEstimator {
+    /// Create an estimator with no events or positions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Group outcome markets into an event, exactly one of which resolves YES
+    ///
+    /// Replaces any previous registration of the event; markets move out of
+    /// events they were registered with before.
+    pub fn register_event<I, S>(&mut self, event_id: &str, markets: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        if let Some(previous) = self.events.remove(event_id) {
+            for market in previous {
+                self.market_events.remove(&market);
+            }
+        }
+        let markets: Vec<String> = markets.into_iter().map(Into::into).collect();
+        for market in &markets {
+            if let Some(old_event) = self.market_events.insert(market.clone(), event_id.to_string()) {
+                if let Some(outcomes) = self.events.get_mut(&old_event) {
+                    outcomes.retain(|m| m != market);
+                }
+            }
+        }
+        self.events.insert(event_id.to_string(), markets);
+    }
+
+    /// Event an outcome market belongs to
+    pub fn event_of(&self, market_id: &str) -> Option<&str> {
+        self.market_events.get(market_id).map(String::as_str)
+    }
+
+    /// Apply a fill (signed size: +buy, -sell) to a market's position
+    pub fn record_fill(&mut self, market_id: &str, size: f64, price: f64) {
+        self.marks.insert(market_id.to_string(), price);
+        let position = self.positions.entry(market_id.to_string()).or_default();
+        position.apply_fill(size, price);
+        if position.size == 0.0 {
+            self.positions.remove(market_id);
+        }
+    }
+
+    /// Replace a market's position (e.g. after reconciling with the venue)
+    pub fn set_position(&mut self, market_id: &str, size: f64, avg_price: f64) {
+        if size.abs() < FLAT_EPSILON {
+            self.positions.remove(market_id);
+        } else {
+            self.positions.insert(market_id.to_string(), BookPosition { size, avg_price });
+        }
+    }
+
+    /// Report a market's current price, used to price proposed trades
+    pub fn set_mark(&mut self, market_id: &str, price: f64) {
+        self.marks.insert(market_id.to_string(), price);
+    }
+
+    /// Last known price of a market
+    pub fn mark(&self, market_id: &str) -> Option<f64> {
+        self.marks.get(market_id).copied()
+    }
+
+    /// Position in a market, if any
+    pub fn position(&self, market_id: &str) -> Option<BookPosition> {
+        self.positions.get(market_id).copied()
+    }
+
+    /// Worst-case loss of the event containing `market_id` (or of the market
+    /// alone if it belongs to no event), in USD
+    pub fn worst_case_loss(&self, market_id: &str) -> f64 {
+        match self.event_of(market_id) {
+            Some(event_id) => Self::event_loss(&self.events[event_id], &self.positions),
+            None => Self::standalone_loss(self.positions.get(market_id)),
+        }
+    }
+
+    /// Total worst-case loss across all events and standalone markets: the
+    /// capital actually at risk, in USD
+    pub fn capital_at_risk(&self) -> f64 {
+        self.total_loss(&self.positions)
+    }
+
+    /// Capital at risk if `size` more shares (signed: +buy, -sell) were
+    /// traded in `market_id`
+    ///
+    /// The trade is priced at the market's mark; without one, buys are
+    /// assumed at 1.0 and sells at 0.0, the most conservative prices.
+    pub fn capital_at_risk_after(&self, market_id: &str, size: f64) -> f64 {
+        self.capital_at_risk_after_all(&[(market_id, size)])
+    }
+
+    /// Capital at risk if all of `trades` (market, signed size) were
+    /// traded together, e.g. the legs of an arb
+    ///
+    /// Priced as in [`capital_at_risk_after`](Self::capital_at_risk_after).
+    pub fn capital_at_risk_after_all(&self, trades: &[(&str, f64)]) -> f64 {
+        let mut positions = self.positions.clone();
+        for &(market_id, size) in trades {
+            let price = self
+                .marks
+                .get(market_id)
+                .copied()
+                .unwrap_or(if size > 0.0 { 1.0 } else { 0.0 });
+            positions.entry(market_id.to_string()).or_default().apply_fill(size, price);
+        }
+        self.total_loss(&positions)
+    }
+
+    /// Remove all positions and marks, keeping event registrations
+    pub fn clear_positions(&mut self) {
+        self.positions.clear();
+        self.marks.clear();
+    }
+
+    /// Worst-case loss of `positions` summed over events and standalone markets
+    fn total_loss(&self, positions: &HashMap<String, BookPosition>) -> f64 {
+        let event_losses: f64 = self
+            .events
+            .values()
+            .map(|markets| Self::event_loss(markets, positions))
+            .sum();
+        let standalone_losses: f64 = positions
+            .iter()
+            .filter(|(market_id, _)| !self.market_events.contains_key(*market_id))
+            .map(|(_, position)| Self::standalone_loss(Some(position)))
+            .sum();
+        event_losses + standalone_losses
+    }
+
+    /// Loss under the worst resolution of an event (each outcome winning in turn)
+    fn event_loss(markets: &[String], positions: &HashMap<String, BookPosition>) -> f64 {
+        let legs: Vec<(usize, BookPosition)> = markets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, market)| positions.get(market).map(|position| (i, *position)))
+            .collect();
+        if legs.is_empty() {
+            return 0.0;
+        }
+
+        let worst_pnl = (0..markets.len())
+            .map(|winner| {
+                legs.iter()
+                    .map(|(i, position)| position.settle(if *i == winner { 1.0 } else { 0.0 }))
+                    .sum::<f64>()
+            })
+            .fold(f64::INFINITY, f64::min);
+        (-worst_pnl).max(0.0)
+    }
+
+    /// Loss under the worst resolution of a market outside any event
+    fn standalone_loss(position: Option<&BookPosition>) -> f64 {
+        let Some(position) = position else {
+            return 0.0;
+        };
+        (-position.settle(1.0).min(position.settle(0.0))).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hedged_pair_has_bounded_loss() {
+        let mut estimator = MarginEstimator::new();
+        estimator.register_event("election", ["yes", "no"]);
+
+        estimator.record_fill("yes", 100.0, 0.60);
+        assert!((estimator.capital_at_risk() - 60.0).abs() < 1e-9);
+
+        // 100 NO at 0.35 locks in a 5 USD profit whichever side wins
+        estimator.record_fill("no", 100.0, 0.35);
+        assert!(estimator.capital_at_risk().abs() < 1e-9);
+
+        // Partial hedge: 100 YES at 0.60 and 50 NO at 0.35 loses most if NO wins
+        estimator.record_fill("no", -50.0, 0.40);
+        assert!((estimator.worst_case_loss("no") - 27.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_standalone_and_short_positions() {
+        let mut estimator = MarginEstimator::new();
+        estimator.record_fill("a", 100.0, 0.30);
+        estimator.record_fill("b", -50.0, 0.80);
+
+        // Long loses its cost; short loses (1 - price) per share
+        assert!((estimator.worst_case_loss("a") - 30.0).abs() < 1e-9);
+        assert!((estimator.worst_case_loss("b") - 10.0).abs() < 1e-9);
+        assert!((estimator.capital_at_risk() - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_capital_at_risk_after_trade() {
+        let mut estimator = MarginEstimator::new();
+        estimator.register_event("e", ["yes", "no"]);
+        estimator.record_fill("yes", 100.0, 0.60);
+        estimator.set_mark("no", 0.38);
+
+        let hedged = estimator.capital_at_risk_after("no", 100.0);
+        assert!((hedged - 0.0).abs() < 1e-9);
+
+        // Unknown price: a buy is assumed to cost 1.0 per share
+        let unpriced = estimator.capital_at_risk_after("other", 10.0);
+        assert!((unpriced - 70.0).abs() < 1e-9);
+        assert_eq!(estimator.position("no"), None);
+    }
+}
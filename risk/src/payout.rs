@@ -0,0 +1,55 @@
+//! Payout exposure of binary positions near price extremes
+//!
+//! A binary share pays 1 USD if its outcome resolves YES. A long position
+//! ties up its price per share and a short one `1 - price`, so the payout
+//! per dollar committed, the position's effective leverage, explodes as the
+//! price nears 0 (longs) or 1 (shorts): 10,000 shares at 0.02 cost 200 USD
+//! but move 10,000 USD of payout on resolution, while at 0.50 the same
+//! 200 USD buys 400 shares. Share-count limits treat both alike; these
+//! helpers measure and cap payout instead.
+//!
+//! ```rust
+//! use ag_risk::payout::{cap_payout, effective_leverage, in_extreme_band};
+//!
+//! assert_eq!(effective_leverage(10_000.0, 0.02), 50.0);
+//! assert!(in_extreme_band(10_000.0, 0.02, 0.05));
+//! assert!(!in_extreme_band(-10_000.0, 0.02, 0.05)); // short at 0.02 risks 0.98 per share
+//! assert_eq!(cap_payout(10_000.0, 0.02, 0.05, 2_500.0), 2_500.0);
+//! assert_eq!(cap_payout(10_000.0, 0.50, 0.05, 2_500.0), 10_000.0);
+//! ```
+
+/// Price distance from 0 or 1 treated as extreme by default
+pub const DEFAULT_PRICE_BAND: f64 = 0.05;
+
+/// Capital a position of `size` shares (signed: +long, -short) ties up per
+/// share at `price`: the price for longs, `1 - price` for shorts
+pub fn capital_per_share(size: f64, price: f64) -> f64 {
+    let price = price.clamp(0.0, 1.0);
+    if size >= 0.0 {
+        price
+    } else {
+        1.0 - price
+    }
+}
+
+/// Payout per dollar committed (infinite at a price of exactly 0 or 1)
+pub fn effective_leverage(size: f64, price: f64) -> f64 {
+    1.0 / capital_per_share(size, price)
+}
+
+/// Whether a position sits on the leveraged side of an extreme price:
+/// long below `band`, or short above `1 - band`
+pub fn in_extreme_band(size: f64, price: f64, band: f64) -> bool {
+    size != 0.0 && capital_per_share(size, price) < band
+}
+
+/// Cap a signed size so its payout (1 USD per share) stays within
+/// `max_payout_usd` when the price is in the extreme band; sizes outside
+/// the band are returned unchanged
+pub fn cap_payout(size: f64, price: f64, band: f64, max_payout_usd: f64) -> f64 {
+    if in_extreme_band(size, price, band) {
+        size.clamp(-max_payout_usd.max(0.0), max_payout_usd.max(0.0))
+    } else {
+        size
+    }
+}
@@ -5,14 +5,88 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::payout::DEFAULT_PRICE_BAND;
+use crate::RiskAction;
+use crate::recovery::RecoveryConfig;
+
 /// Complete risk policy configuration
 ///
 /// This structure represents a full risk policy document,
 /// typically loaded from YAML or JSON files.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct RiskPolicyConfig {
     /// List of policy rules to evaluate
     pub policies: Vec<PolicyRule>,
+
+    /// Staged re-enable after a hard limit breach (None = disabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryConfig>,
+
+    /// Evaluation order, short-circuiting and disabled policies
+    #[serde(default, skip_serializing_if = "EvaluationConfig::is_default")]
+    pub evaluation: EvaluationConfig,
+}
+
+impl RiskPolicyConfig {
+    /// Entries of `evaluation.order` and `evaluation.disabled` that match
+    /// no policy (see [`PolicyRule::matches`])
+    pub fn unknown_policy_references(&self) -> Vec<String> {
+        self.evaluation
+            .order
+            .iter()
+            .chain(&self.evaluation.disabled)
+            .filter(|reference| !self.policies.iter().any(|policy| policy.matches(reference)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// How the engine walks the policy list
+///
+/// Policies are named by type (e.g. `PositionLimit`, matching every
+/// position limit) or by label (e.g. `PositionLimit (market: 0x123)`), as
+/// in the engine's per-policy counters.
+///
+/// ```yaml
+/// evaluation:
+///   order: [KillSwitch, PositionLimit, InventoryLimit]
+///   short_circuit: true
+///   disabled: [LossStreakCooldown]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EvaluationConfig {
+    /// Policies evaluated first, in this order; the rest follow in file
+    /// order
+    pub order: Vec<String>,
+
+    /// Stop at the first violation that rejects the action, leaving later
+    /// policies unevaluated (and uncounted); otherwise every violation is
+    /// collected
+    pub short_circuit: bool,
+
+    /// Policies skipped until re-enabled with
+    /// `RiskEngine::set_policy_enabled`
+    pub disabled: Vec<String>,
+}
+
+impl EvaluationConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Policy indices in evaluation order
+    pub fn ordered(&self, policies: &[PolicyRule]) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..policies.len()).collect();
+        indices.sort_by_key(|&index| {
+            self.order
+                .iter()
+                .position(|reference| policies[index].matches(reference))
+                .unwrap_or(self.order.len())
+        });
+        indices
+    }
 }
 
 /// Individual policy rule types
@@ -20,7 +94,7 @@ pub struct RiskPolicyConfig {
 /// Each variant represents a different type of risk check
 /// with specific parameters.
 #[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(tag = "type")]
+#[serde(tag = "type", deny_unknown_fields)]
 pub enum PolicyRule {
     /// Limit maximum position size
     ///
@@ -33,6 +107,9 @@ pub enum PolicyRule {
 
         /// Maximum absolute position size
         max_size: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
     },
 
     /// Limit total inventory value
@@ -41,6 +118,9 @@ pub enum PolicyRule {
     InventoryLimit {
         /// Maximum inventory value in USD
         max_value_usd: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
     },
 
     /// Emergency kill switch
@@ -49,7 +129,215 @@ pub enum PolicyRule {
     KillSwitch {
         /// Whether kill switch is enabled
         enabled: bool,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Pause entries in a market after a streak of losing round trips
+    ///
+    /// When `max_losses` consecutive losing round trips (recorded with
+    /// `RiskEngine::record_trade_outcome`) close within `window_secs`, new
+    /// entries in that market are blocked for `cooldown_secs`. Trades that
+    /// reduce the position are still allowed. Can be applied globally
+    /// (market_id = None) or per-market.
+    LossStreakCooldown {
+        /// Optional market ID filter (None = apply to all markets)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        market_id: Option<String>,
+
+        /// Consecutive losses that trigger the cooldown
+        max_losses: u32,
+
+        /// Window the losses must fall within, in seconds
+        window_secs: u64,
+
+        /// How long entries stay blocked, in seconds
+        cooldown_secs: u64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Cap inventory at a fraction of the bankroll
+    ///
+    /// The bankroll is the cash capital reported through
+    /// `RiskEngine::set_bankroll` plus the current inventory value. Until a
+    /// bankroll has been reported the policy allows everything.
+    BankrollLimit {
+        /// Maximum inventory value as a fraction of the bankroll (0, 1]
+        max_fraction: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Block new entries shortly before a market closes
+    ///
+    /// Close times are reported through `RiskEngine::set_market_close`
+    /// (e.g. by a session manager). From `close_buffer_secs` before the
+    /// close until the close is cleared, only trades that reduce the
+    /// position are allowed. Markets without a close time are unaffected.
+    TradingWindow {
+        /// Optional market ID filter (None = apply to all markets)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        market_id: Option<String>,
+
+        /// How long before the close entries are blocked, in seconds
+        close_buffer_secs: u64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Cap the book's worst-case loss at resolution
+    ///
+    /// Capital at risk is computed by the engine's `MarginEstimator` from
+    /// fills reported through `RiskEngine::record_fill`: each event's YES/NO
+    /// positions are settled under every outcome and the worst loss counted,
+    /// so hedged books use less of the limit than their notional. Trades
+    /// that do not increase the capital at risk are always allowed.
+    CapitalAtRiskLimit {
+        /// Maximum worst-case loss in USD
+        max_loss_usd: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Cap payout exposure rather than share count near price extremes
+    ///
+    /// When a market's mark (reported through `RiskEngine::set_mark` or
+    /// `record_fill`) is below `price_band`, long positions are limited to
+    /// `max_payout_usd` shares (1 USD payout each); above `1 - price_band`
+    /// short positions are. Trades that reduce the position are always
+    /// allowed, and markets without a mark are unaffected. Can be applied
+    /// globally (market_id = None) or per-market.
+    PayoutLimit {
+        /// Optional market ID filter (None = apply to all markets)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        market_id: Option<String>,
+
+        /// Maximum payout of the position in USD
+        max_payout_usd: f64,
+
+        /// Distance from 0 or 1 within which the cap applies
+        #[serde(default = "default_price_band")]
+        price_band: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Evaluate atomic multi-leg submissions on their net exposure
+    ///
+    /// Only applies to `RiskEngine::evaluate_group`. A group whose legs net
+    /// to at most `max_net_exposure` shares is hedged: the per-market
+    /// `PositionLimit` and `PayoutLimit` checks of its legs are waived, up
+    /// to `max_leg_position` per leg, so an arb is not rejected leg by leg
+    /// where a naked leg would be. Groups with a larger net exposure are
+    /// rejected.
+    HedgedGroupLimit {
+        /// Maximum absolute net exposure of a group, in shares
+        max_net_exposure: f64,
+
+        /// Maximum absolute position of each leg of a hedged group
+        /// (None = no cap beyond the group's own limits)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_leg_position: Option<f64>,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Limit the aggregate exposure to one asset across venues
+    ///
+    /// Positions are reported per market and venue through
+    /// `RiskEngine::set_venue_position` / `record_venue_fill` and grouped
+    /// by asset: a market's asset is set with `RiskEngine::set_market_asset`
+    /// (e.g. from the symbol mapper's canonical markets) and defaults to the
+    /// market ID. Checks that |summed position of the asset on all venues +
+    /// proposed_size| <= max_exposure; trades that reduce the aggregate
+    /// exposure are always allowed. Can be applied globally (asset = None)
+    /// or per-asset.
+    ExposureAcrossVenues {
+        /// Optional asset filter (None = apply to all assets)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        asset: Option<String>,
+
+        /// Maximum absolute aggregate position of an asset
+        max_exposure: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Bound the portfolio's option-like exposure
+    ///
+    /// Current portfolio Greeks and the Greeks of one share of the traded
+    /// market come from the engine's `GreeksProvider` (see
+    /// `RiskEngine::set_greeks_provider`). Checks that |portfolio Greek +
+    /// per-share Greek × proposed_size| <= the bound for each configured
+    /// Greek; trades that do not increase a breached Greek's magnitude are
+    /// always allowed. Without a provider, or for markets without Greeks,
+    /// the policy allows everything.
+    GreeksLimit {
+        /// Maximum absolute portfolio delta
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_delta: Option<f64>,
+
+        /// Maximum absolute portfolio vega (per 1% volatility)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_vega: Option<f64>,
+
+        /// Maximum absolute portfolio theta (per day)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_theta: Option<f64>,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
     },
+
+    /// Stop trading after losing too much in a rolling 24 hours
+    ///
+    /// The loss is the realized PnL of round trips closed in the last 24
+    /// hours (recorded with `RiskEngine::record_trade_outcome`) plus the
+    /// unrealized PnL last reported with `RiskEngine::set_unrealized_pnl`.
+    /// Once it reaches `max_loss_usd`, entries are blocked and trades that
+    /// reduce the position are still allowed; with `action: KillSwitch`
+    /// the engine's kill-switch is triggered instead, blocking everything
+    /// until it is reset.
+    DailyLossLimit {
+        /// Maximum loss over the window in USD
+        max_loss_usd: f64,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+
+    /// Throttle the rate at which orders are accepted in a market
+    ///
+    /// Orders allowed by the engine in the last 60 seconds are counted per
+    /// market; once `max_orders_per_minute` is reached, further orders in
+    /// that market are rejected, reducing ones included. Rejected orders
+    /// do not count, so a runaway strategy resumes once its rate falls
+    /// back under the limit. Can be applied globally (market_id = None,
+    /// still counted per market) or per-market.
+    OrderRateLimit {
+        /// Optional market ID filter (None = apply to all markets)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        market_id: Option<String>,
+
+        /// Orders accepted per market in any 60 second window
+        max_orders_per_minute: u32,
+        /// Action a violation calls for (None = the policy's default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<RiskAction>,
+    },
+}
+
+fn default_price_band() -> f64 {
+    DEFAULT_PRICE_BAND
 }
 
 impl PolicyRule {
@@ -59,10 +347,71 @@ impl PolicyRule {
             PolicyRule::PositionLimit { .. } => "PositionLimit",
             PolicyRule::InventoryLimit { .. } => "InventoryLimit",
             PolicyRule::KillSwitch { .. } => "KillSwitch",
+            PolicyRule::LossStreakCooldown { .. } => "LossStreakCooldown",
+            PolicyRule::BankrollLimit { .. } => "BankrollLimit",
+            PolicyRule::TradingWindow { .. } => "TradingWindow",
+            PolicyRule::CapitalAtRiskLimit { .. } => "CapitalAtRiskLimit",
+            PolicyRule::PayoutLimit { .. } => "PayoutLimit",
+            PolicyRule::HedgedGroupLimit { .. } => "HedgedGroupLimit",
+            PolicyRule::ExposureAcrossVenues { .. } => "ExposureAcrossVenues",
+            PolicyRule::GreeksLimit { .. } => "GreeksLimit",
+            PolicyRule::DailyLossLimit { .. } => "DailyLossLimit",
+            PolicyRule::OrderRateLimit { .. } => "OrderRateLimit",
+        }
+    }
+
+    /// Label used to group counters and rejections by rule, e.g.
+    /// `PositionLimit (market: 0x123)`
+    pub fn label(&self) -> String {
+        match self {
+            PolicyRule::PositionLimit {
+                market_id: Some(market_id),
+                ..
+            } => format!("{} (market: {})", self.name(), market_id),
+            PolicyRule::ExposureAcrossVenues {
+                asset: Some(asset), ..
+            } => format!("{} (asset: {})", self.name(), asset),
+            _ => self.name().to_string(),
         }
     }
 
+    /// Whether `reference` names this policy, by type or by label
+    pub fn matches(&self, reference: &str) -> bool {
+        reference == self.name() || reference == self.label()
+    }
+
+    /// Action a violation of this policy calls for
+    ///
+    /// The configured `action`; by default `KillSwitch` for the
+    /// `KillSwitch` policy and `Reject` for everything else. With
+    /// `Allow` violations are reported without rejecting, e.g. to watch a
+    /// new limit in production before enforcing it.
+    pub fn action(&self) -> RiskAction {
+        let configured = match self {
+            PolicyRule::PositionLimit { action, .. }
+            | PolicyRule::InventoryLimit { action, .. }
+            | PolicyRule::KillSwitch { action, .. }
+            | PolicyRule::LossStreakCooldown { action, .. }
+            | PolicyRule::BankrollLimit { action, .. }
+            | PolicyRule::TradingWindow { action, .. }
+            | PolicyRule::CapitalAtRiskLimit { action, .. }
+            | PolicyRule::PayoutLimit { action, .. }
+            | PolicyRule::HedgedGroupLimit { action, .. }
+            | PolicyRule::ExposureAcrossVenues { action, .. }
+            | PolicyRule::GreeksLimit { action, .. }
+            | PolicyRule::DailyLossLimit { action, .. }
+            | PolicyRule::OrderRateLimit { action, .. } => *action,
+        };
+        configured.unwrap_or(match self {
+            PolicyRule::KillSwitch { .. } => RiskAction::KillSwitch,
+            _ => RiskAction::Reject,
+        })
+    }
+
     /// Check if this policy applies to the given market ID
+    ///
+    /// `ExposureAcrossVenues` is filtered by asset, which only the engine
+    /// can resolve, so it applies to every market here.
     pub fn applies_to_market(&self, market_id: &str) -> bool {
         match self {
             PolicyRule::PositionLimit {
@@ -72,6 +421,32 @@ impl PolicyRule {
             PolicyRule::PositionLimit { market_id: None, .. } => true,
             PolicyRule::InventoryLimit { .. } => true,
             PolicyRule::KillSwitch { .. } => true,
+            PolicyRule::LossStreakCooldown {
+                market_id: Some(policy_market_id),
+                ..
+            } => policy_market_id == market_id,
+            PolicyRule::LossStreakCooldown { market_id: None, .. } => true,
+            PolicyRule::BankrollLimit { .. } => true,
+            PolicyRule::TradingWindow {
+                market_id: Some(policy_market_id),
+                ..
+            } => policy_market_id == market_id,
+            PolicyRule::TradingWindow { market_id: None, .. } => true,
+            PolicyRule::CapitalAtRiskLimit { .. } => true,
+            PolicyRule::PayoutLimit {
+                market_id: Some(policy_market_id),
+                ..
+            } => policy_market_id == market_id,
+            PolicyRule::PayoutLimit { market_id: None, .. } => true,
+            PolicyRule::HedgedGroupLimit { .. } => true,
+            PolicyRule::ExposureAcrossVenues { .. } => true,
+            PolicyRule::GreeksLimit { .. } => true,
+            PolicyRule::DailyLossLimit { .. } => true,
+            PolicyRule::OrderRateLimit {
+                market_id: Some(policy_market_id),
+                ..
+            } => policy_market_id == market_id,
+            PolicyRule::OrderRateLimit { market_id: None, .. } => true,
         }
     }
 }
@@ -97,7 +472,7 @@ policies:
         assert_eq!(config.policies.len(), 3);
 
         match &config.policies[0] {
-            PolicyRule::PositionLimit { market_id, max_size } => {
+            PolicyRule::PositionLimit { market_id, max_size, .. } => {
                 assert_eq!(market_id.as_ref().unwrap(), "0x123abc");
                 assert_eq!(*max_size, 1000.0);
             }
@@ -105,20 +480,33 @@ policies:
         }
 
         match &config.policies[1] {
-            PolicyRule::InventoryLimit { max_value_usd } => {
+            PolicyRule::InventoryLimit { max_value_usd, .. } => {
                 assert_eq!(*max_value_usd, 10000.0);
             }
             _ => panic!("Expected InventoryLimit"),
         }
 
         match &config.policies[2] {
-            PolicyRule::KillSwitch { enabled } => {
+            PolicyRule::KillSwitch { enabled, .. } => {
                 assert!(!enabled);
             }
             _ => panic!("Expected KillSwitch"),
         }
     }
 
+    #[test]
+    fn test_policy_rejects_unknown_fields() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 1000.0
+    market: "0x123abc"
+"#;
+
+        let err = serde_yaml::from_str::<RiskPolicyConfig>(yaml).unwrap_err();
+        assert!(err.to_string().contains("unknown field `market`"));
+    }
+
     #[test]
     fn test_policy_deserialization_json() {
         let json = r#"{
@@ -145,9 +533,12 @@ policies:
                 PolicyRule::PositionLimit {
                     market_id: Some("0x456".to_string()),
                     max_size: 2000.0,
+                    action: None,
                 },
-                PolicyRule::KillSwitch { enabled: true },
+                PolicyRule::KillSwitch { enabled: true, action: None },
             ],
+            recovery: None,
+            evaluation: EvaluationConfig::default(),
         };
 
         let yaml = serde_yaml::to_string(&config).unwrap();
@@ -160,15 +551,17 @@ policies:
         let pos_limit = PolicyRule::PositionLimit {
             market_id: None,
             max_size: 100.0,
+            action: None,
         };
         assert_eq!(pos_limit.name(), "PositionLimit");
 
         let inv_limit = PolicyRule::InventoryLimit {
             max_value_usd: 1000.0,
+            action: None,
         };
         assert_eq!(inv_limit.name(), "InventoryLimit");
 
-        let kill_switch = PolicyRule::KillSwitch { enabled: false };
+        let kill_switch = PolicyRule::KillSwitch { enabled: false, action: None };
         assert_eq!(kill_switch.name(), "KillSwitch");
     }
 
@@ -177,22 +570,60 @@ policies:
         let global_limit = PolicyRule::PositionLimit {
             market_id: None,
             max_size: 100.0,
+            action: None,
         };
         assert!(global_limit.applies_to_market("any_market"));
 
         let market_limit = PolicyRule::PositionLimit {
             market_id: Some("0x123".to_string()),
             max_size: 100.0,
+            action: None,
         };
         assert!(market_limit.applies_to_market("0x123"));
         assert!(!market_limit.applies_to_market("0x456"));
 
         let inv_limit = PolicyRule::InventoryLimit {
             max_value_usd: 1000.0,
+            action: None,
         };
         assert!(inv_limit.applies_to_market("any_market"));
     }
 
+    #[test]
+    fn test_evaluation_config() {
+        let yaml = r#"
+policies:
+  - type: LossStreakCooldown
+    max_losses: 3
+    window_secs: 3600
+    cooldown_secs: 600
+  - type: InventoryLimit
+    max_value_usd: 10000.0
+  - type: PositionLimit
+    market_id: "0x123"
+    max_size: 100.0
+  - type: PositionLimit
+    max_size: 500.0
+evaluation:
+  order: ["PositionLimit (market: 0x123)", InventoryLimit, PositionLimit]
+  short_circuit: true
+  disabled: [LossStreakCooldown, TradingWindow]
+"#;
+
+        let config: RiskPolicyConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.evaluation.short_circuit);
+        assert_eq!(config.evaluation.ordered(&config.policies), vec![2, 1, 3, 0]);
+        assert_eq!(config.unknown_policy_references(), vec!["TradingWindow".to_string()]);
+
+        // Omitted when default
+        let yaml = serde_yaml::to_string(&RiskPolicyConfig {
+            evaluation: EvaluationConfig::default(),
+            ..config
+        })
+        .unwrap();
+        assert!(!yaml.contains("evaluation"));
+    }
+
     #[test]
     fn test_global_position_limit_no_market_id() {
         let yaml = r#"
@@ -203,7 +634,7 @@ policies:
 
         let config: RiskPolicyConfig = serde_yaml::from_str(yaml).unwrap();
         match &config.policies[0] {
-            PolicyRule::PositionLimit { market_id, max_size } => {
+            PolicyRule::PositionLimit { market_id, max_size, .. } => {
                 assert!(market_id.is_none());
                 assert_eq!(*max_size, 500.0);
             }
@@ -0,0 +1,224 @@
+//! Staged recovery after a hard limit breach
+//!
+//! When a hard limit trips (a daily loss limit, say) the engine does not
+//! return to full size as soon as the breach clears. It moves through three
+//! [`RecoveryStage`]s instead:
+//!
+//! 1. **Cooldown**: new entries are blocked for `cooldown_secs`; trades that
+//!    reduce a position are still allowed
+//! 2. **Probation**: entries are allowed for `probation_secs`, but every
+//!    size limit (`PositionLimit`, `InventoryLimit`, `BankrollLimit`,
+//!    `CapitalAtRiskLimit`, `PayoutLimit`) is scaled by `probation_scale`
+//! 3. **Normal**: full limits are restored
+//!
+//! A breach is reported with `RiskEngine::trip_limit`, or trips
+//! automatically when a policy listed in `triggers` rejects a trade. A trip
+//! during cooldown or probation restarts the cooldown. Each stage change is
+//! recorded as a [`RecoveryEvent`] drained with `RiskEngine::poll_recovery`.
+//!
+//! ```yaml
+//! recovery:
+//!   cooldown_secs: 3600
+//!   probation_secs: 14400
+//!   probation_scale: 0.25
+//!   triggers: [CapitalAtRiskLimit]
+//! policies:
+//!   - type: CapitalAtRiskLimit
+//!     max_loss_usd: 500.0
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Staged recovery settings, configured in the policy file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecoveryConfig {
+    /// How long entries stay blocked after a breach, in seconds
+    pub cooldown_secs: u64,
+
+    /// How long limits stay reduced after the cooldown, in seconds
+    pub probation_secs: u64,
+
+    /// Multiplier applied to size limits during probation (0, 1]
+    pub probation_scale: f64,
+
+    /// Policy types whose rejections trip recovery (e.g. `CapitalAtRiskLimit`)
+    pub triggers: Vec<String>,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            cooldown_secs: 3600,
+            probation_secs: 3600,
+            probation_scale: 0.5,
+            triggers: Vec::new(),
+        }
+    }
+}
+
+impl RecoveryConfig {
+    pub fn cooldown(&self) -> Duration {
+        Duration::seconds(self.cooldown_secs as i64)
+    }
+
+    pub fn probation(&self) -> Duration {
+        Duration::seconds(self.probation_secs as i64)
+    }
+
+    /// Whether rejections by a policy type trip recovery
+    pub fn is_trigger(&self, policy_name: &str) -> bool {
+        self.triggers.iter().any(|t| t == policy_name)
+    }
+}
+
+/// Stage of the recovery workflow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryStage {
+    /// Full limits
+    #[default]
+    Normal,
+    /// Entries blocked
+    Cooldown,
+    /// Entries allowed at reduced limits
+    Probation,
+}
+
+impl RecoveryStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoveryStage::Normal => "normal",
+            RecoveryStage::Cooldown => "cooldown",
+            RecoveryStage::Probation => "probation",
+        }
+    }
+}
+
+/// Stage change of the recovery workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryEvent {
+    /// Stage entered
+    pub stage: RecoveryStage,
+
+    /// When the stage was entered
+    pub at: DateTime<Utc>,
+
+    /// When the stage ends (None for Normal)
+    pub until: Option<DateTime<Utc>>,
+
+    /// Breach that started the workflow
+    pub reason: String,
+}
+
+/// Current position in the recovery workflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryState {
+    pub stage: RecoveryStage,
+
+    /// When the current stage ends (None for Normal)
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Breach that started the workflow
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl RecoveryState {
+    /// Enter cooldown after a breach at `at`
+    pub fn trip(&mut self, config: &RecoveryConfig, reason: &str, at: DateTime<Utc>) -> RecoveryEvent {
+        self.stage = RecoveryStage::Cooldown;
+        self.until = Some(at + config.cooldown());
+        self.reason = Some(reason.to_string());
+        self.event(at)
+    }
+
+    /// Move through every stage that ended by `now`
+    ///
+    /// Events are stamped with the scheduled end of the previous stage, so
+    /// the history is the same however often this is called.
+    pub fn advance(&mut self, config: &RecoveryConfig, now: DateTime<Utc>) -> Vec<RecoveryEvent> {
+        let mut events = Vec::new();
+        while let Some(until) = self.until.filter(|until| *until <= now) {
+            match self.stage {
+                RecoveryStage::Cooldown => {
+                    self.stage = RecoveryStage::Probation;
+                    self.until = Some(until + config.probation());
+                }
+                RecoveryStage::Probation | RecoveryStage::Normal => {
+                    self.stage = RecoveryStage::Normal;
+                    self.until = None;
+                }
+            }
+            events.push(self.event(until));
+        }
+        events
+    }
+
+    /// Return to full limits immediately
+    pub fn reset(&mut self, at: DateTime<Utc>) -> Option<RecoveryEvent> {
+        if self.stage == RecoveryStage::Normal {
+            return None;
+        }
+        self.stage = RecoveryStage::Normal;
+        self.until = None;
+        Some(self.event(at))
+    }
+
+    /// Multiplier applied to size limits in the current stage
+    pub fn limit_scale(&self, config: &RecoveryConfig) -> f64 {
+        match self.stage {
+            RecoveryStage::Probation => config.probation_scale.clamp(0.0, 1.0),
+            RecoveryStage::Normal | RecoveryStage::Cooldown => 1.0,
+        }
+    }
+
+    fn event(&self, at: DateTime<Utc>) -> RecoveryEvent {
+        RecoveryEvent {
+            stage: self.stage,
+            at,
+            until: self.until,
+            reason: self.reason.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_stages() {
+        let config = RecoveryConfig {
+            cooldown_secs: 60,
+            probation_secs: 120,
+            probation_scale: 0.25,
+            triggers: vec!["CapitalAtRiskLimit".to_string()],
+        };
+        let start = Utc.timestamp_opt(1_000, 0).unwrap();
+        let mut state = RecoveryState::default();
+
+        let tripped = state.trip(&config, "daily loss", start);
+        assert_eq!(tripped.stage, RecoveryStage::Cooldown);
+        assert_eq!(tripped.until, Some(start + Duration::seconds(60)));
+        assert!(state.advance(&config, start + Duration::seconds(59)).is_empty());
+        assert_eq!(state.limit_scale(&config), 1.0);
+
+        // Both stages can end between two polls
+        let events = state.advance(&config, start + Duration::seconds(500));
+        let stages: Vec<_> = events.iter().map(|e| (e.stage, e.at)).collect();
+        assert_eq!(
+            stages,
+            vec![
+                (RecoveryStage::Probation, start + Duration::seconds(60)),
+                (RecoveryStage::Normal, start + Duration::seconds(180)),
+            ]
+        );
+        assert!(events.iter().all(|e| e.reason == "daily loss"));
+        assert_eq!(state.stage, RecoveryStage::Normal);
+        assert!(state.reset(start).is_none());
+    }
+}
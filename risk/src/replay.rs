@@ -0,0 +1,328 @@
+//! Forensic replay of recorded orders
+//!
+//! Answers "why was this allowed?" after an incident: a recorded history of
+//! orders and the state updates around them (fills, marks, PnL, bankroll,
+//! kill-switch changes) is replayed through a fresh [`RiskEngine`] loaded
+//! with a chosen policy file, and each order's replayed decision is
+//! compared with the one recorded at the time. The engine's clock follows
+//! the recorded timestamps, so cooldowns, trading windows and the daily
+//! loss window evaluate as they would have then.
+//!
+//! - [`ReplayVerdict::Unchanged`] on an allowed bad trade: the policy file
+//!   did not cover it (a policy gap)
+//! - [`ReplayVerdict::NowRejected`] under the policy version that was
+//!   live: the policy covers it, so the live engine was fed different
+//!   state or misevaluated it (an engine or integration bug)
+//! - [`ReplayVerdict::NowRejected`] under a candidate version: the fix
+//!   would have stopped it
+//!
+//! Histories are JSON Lines, one [`ReplayRecord`] per line:
+//!
+//! ```rust
+//! use ag_risk::replay::{self, ReplayVerdict};
+//! use ag_risk::RiskPolicyConfig;
+//!
+//! let history = r#"
+//! {"at":"2026-03-02T14:00:00Z","event":"fill","market_id":"0x123","size":800.0,"price":0.5}
+//! {"at":"2026-03-02T14:00:05Z","event":"order","order_id":"o-2","context":{"market_id":"0x123","current_position":800.0,"proposed_size":400.0,"inventory_value_usd":400.0},"decision":{"allowed":true,"violations":[]}}
+//! "#;
+//! let records = replay::parse_jsonl(history).unwrap();
+//!
+//! let policies: RiskPolicyConfig =
+//!     serde_yaml::from_str("policies:\n  - type: PositionLimit\n    max_size: 1000.0\n").unwrap();
+//! let report = replay::replay(policies, &records);
+//!
+//! let outcome = report.find("o-2").unwrap();
+//! assert_eq!(outcome.verdict, ReplayVerdict::NowRejected);
+//! assert_eq!(outcome.replayed.violations[0].policy, "PositionLimit");
+//! ```
+//!
+//! Runtime changes that are not in the history (policies disabled or
+//! re-parameterised through the control API, Greeks providers, return
+//! history) are not replayed.
+
+use crate::clock::MockClock;
+use crate::kill_switch::KillSwitchSource;
+use crate::policy::RiskPolicyConfig;
+use crate::{RiskContext, RiskDecision, RiskEngine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// One timestamped entry of a recorded history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayRecord {
+    /// When the event happened
+    pub at: DateTime<Utc>,
+
+    #[serde(flatten)]
+    pub event: ReplayEvent,
+}
+
+/// Recorded order or engine state update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ReplayEvent {
+    /// Order evaluated by the engine, with the decision it got (if known)
+    Order {
+        #[serde(default)]
+        order_id: Option<String>,
+        context: RiskContext,
+        #[serde(default)]
+        decision: Option<RiskDecision>,
+    },
+    /// Fill (signed size: +buy, -sell); see `RiskEngine::record_fill`
+    Fill { market_id: String, size: f64, price: f64 },
+    /// Mark price; see `RiskEngine::set_mark`
+    Mark { market_id: String, price: f64 },
+    /// Closed round trip; see `RiskEngine::record_trade_outcome`
+    TradeOutcome { market_id: String, realized_pnl: f64 },
+    /// Cash capital; see `RiskEngine::set_bankroll`
+    Bankroll { cash_usd: f64 },
+    /// Unrealized PnL; see `RiskEngine::set_unrealized_pnl`
+    UnrealizedPnl { pnl_usd: f64 },
+    /// Scheduled close; see `RiskEngine::set_market_close`
+    MarketClose { market_id: String, close_at: DateTime<Utc> },
+    /// Position at one venue; see `RiskEngine::set_venue_position`
+    VenuePosition { market_id: String, venue: String, size: f64 },
+    /// Kill-switch turned on or off from outside the engine
+    KillSwitch { active: bool, source: KillSwitchSource, reason: String },
+}
+
+/// How a replayed decision compares with the recorded one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayVerdict {
+    /// Same outcome, rejected by the same policies
+    Unchanged,
+    /// Recorded as allowed, rejected on replay
+    NowRejected,
+    /// Recorded as rejected, allowed on replay
+    NowAllowed,
+    /// Rejected both times, but by different policies
+    DifferentViolations,
+    /// No decision was recorded
+    Unrecorded,
+}
+
+/// Replayed decision of one recorded order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    /// Position of the order's record in the history
+    pub index: usize,
+    pub at: DateTime<Utc>,
+    pub order_id: Option<String>,
+    pub context: RiskContext,
+    /// Decision recorded at the time
+    pub recorded: Option<RiskDecision>,
+    /// Decision under the replayed policies
+    pub replayed: RiskDecision,
+    pub verdict: ReplayVerdict,
+}
+
+/// Outcome of [`replay`], one entry per recorded order in time order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub outcomes: Vec<ReplayOutcome>,
+}
+
+impl ReplayReport {
+    /// Outcome of the order with `order_id`
+    pub fn find(&self, order_id: &str) -> Option<&ReplayOutcome> {
+        self.outcomes.iter().find(|outcome| outcome.order_id.as_deref() == Some(order_id))
+    }
+
+    /// Orders whose replayed decision differs from the recorded one
+    pub fn divergent(&self) -> Vec<&ReplayOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| !matches!(outcome.verdict, ReplayVerdict::Unchanged | ReplayVerdict::Unrecorded))
+            .collect()
+    }
+
+    /// Number of orders with `verdict`
+    pub fn count(&self, verdict: ReplayVerdict) -> usize {
+        self.outcomes.iter().filter(|outcome| outcome.verdict == verdict).count()
+    }
+}
+
+/// Replay `records` through a fresh engine loaded with `config`
+///
+/// Records are applied in time order (ties keep their recorded order);
+/// the engine's clock is set to each record's time before it is applied.
+pub fn replay(config: RiskPolicyConfig, records: &[ReplayRecord]) -> ReplayReport {
+    let mut ordered: Vec<(usize, &ReplayRecord)> = records.iter().enumerate().collect();
+    ordered.sort_by_key(|(_, record)| record.at);
+
+    let engine = RiskEngine::new(config);
+    let clock = MockClock::new(ordered.first().map_or_else(crate::clock::now, |(_, record)| record.at));
+    engine.set_clock(Arc::new(clock.clone()));
+
+    let mut report = ReplayReport::default();
+    for (index, record) in ordered {
+        clock.set(record.at);
+        match &record.event {
+            ReplayEvent::Order { order_id, context, decision } => {
+                let replayed = engine.evaluate(context);
+                report.outcomes.push(ReplayOutcome {
+                    index,
+                    at: record.at,
+                    order_id: order_id.clone(),
+                    context: context.clone(),
+                    verdict: verdict(decision.as_ref(), &replayed),
+                    recorded: decision.clone(),
+                    replayed,
+                });
+            }
+            ReplayEvent::Fill { market_id, size, price } => engine.record_fill(market_id, *size, *price),
+            ReplayEvent::Mark { market_id, price } => engine.set_mark(market_id, *price),
+            ReplayEvent::TradeOutcome { market_id, realized_pnl } => {
+                engine.record_trade_outcome_at(market_id, *realized_pnl, record.at)
+            }
+            ReplayEvent::Bankroll { cash_usd } => engine.set_bankroll(*cash_usd),
+            ReplayEvent::UnrealizedPnl { pnl_usd } => engine.set_unrealized_pnl(*pnl_usd),
+            ReplayEvent::MarketClose { market_id, close_at } => engine.set_market_close(market_id, *close_at),
+            ReplayEvent::VenuePosition { market_id, venue, size } => engine.set_venue_position(market_id, venue, *size),
+            ReplayEvent::KillSwitch { active, source, reason } => {
+                if *active {
+                    engine.trigger_kill_switch(source.clone(), reason.clone());
+                } else {
+                    engine.reset_kill_switch(source.clone(), reason.clone());
+                }
+            }
+        }
+    }
+    report
+}
+
+fn verdict(recorded: Option<&RiskDecision>, replayed: &RiskDecision) -> ReplayVerdict {
+    let Some(recorded) = recorded else {
+        return ReplayVerdict::Unrecorded;
+    };
+    match (recorded.allowed, replayed.allowed) {
+        (true, true) => ReplayVerdict::Unchanged,
+        (true, false) => ReplayVerdict::NowRejected,
+        (false, true) => ReplayVerdict::NowAllowed,
+        (false, false) if policies(recorded) == policies(replayed) => ReplayVerdict::Unchanged,
+        (false, false) => ReplayVerdict::DifferentViolations,
+    }
+}
+
+fn policies(decision: &RiskDecision) -> BTreeSet<&str> {
+    decision.violations.iter().map(|violation| violation.policy.as_str()).collect()
+}
+
+/// Parse a JSON Lines history, skipping blank lines
+pub fn parse_jsonl(input: &str) -> Result<Vec<ReplayRecord>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Failed to parse record on line {}: {}", number + 1, e))
+        })
+        .collect()
+}
+
+/// Write records as JSON Lines
+pub fn to_jsonl(records: &[ReplayRecord]) -> Result<String, String> {
+    let mut output = String::new();
+    for record in records {
+        output.push_str(&serde_json::to_string(record).map_err(|e| format!("Failed to serialize record: {}", e))?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn order(at: DateTime<Utc>, id: &str, position: f64, size: f64, allowed: bool) -> ReplayRecord {
+        ReplayRecord {
+            at,
+            event: ReplayEvent::Order {
+                order_id: Some(id.to_string()),
+                context: RiskContext {
+                    market_id: "0x123".to_string(),
+                    current_position: position,
+                    proposed_size: size,
+                    inventory_value_usd: 0.0,
+                },
+                decision: Some(if allowed {
+                    RiskDecision::allow()
+                } else {
+                    RiskDecision::reject(vec![crate::Violation::new("KillSwitch", "Kill-switch is active")])
+                }),
+            },
+        }
+    }
+
+    #[test]
+    fn test_replay_compares_decisions() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 2, 14, 0, 0).unwrap();
+        let records = vec![
+            order(start, "o-1", 0.0, 200.0, true),
+            // Recorded in the wrong position: replay sorts by time
+            order(start + Duration::minutes(10), "o-4", 0.0, 10.0, true),
+            ReplayRecord {
+                at: start + Duration::minutes(1),
+                event: ReplayEvent::TradeOutcome { market_id: "0x123".to_string(), realized_pnl: -600.0 },
+            },
+            order(start + Duration::minutes(2), "o-2", 200.0, 100.0, true),
+            ReplayRecord {
+                at: start + Duration::minutes(3),
+                event: ReplayEvent::KillSwitch {
+                    active: true,
+                    source: KillSwitchSource::Manual,
+                    reason: "halt".to_string(),
+                },
+            },
+            order(start + Duration::minutes(4), "o-3", 200.0, 100.0, false),
+            ReplayRecord {
+                at: start + Duration::minutes(5),
+                event: ReplayEvent::KillSwitch {
+                    active: false,
+                    source: KillSwitchSource::Manual,
+                    reason: "resume".to_string(),
+                },
+            },
+        ];
+        let text = to_jsonl(&records).unwrap();
+        assert_eq!(parse_jsonl(&format!("\n{}\n", text)).unwrap(), records);
+        assert!(parse_jsonl("{\"at\":\"2026-03-02T14:00:00Z\",\"event\":\"nope\"}").unwrap_err().contains("line 1"));
+
+        // The policy version that was live allowed everything
+        let live: RiskPolicyConfig =
+            serde_yaml::from_str("policies:\n  - type: PositionLimit\n    max_size: 1000.0\n").unwrap();
+        let report = replay(live, &records);
+        assert_eq!(report.outcomes.len(), 4);
+        assert_eq!(report.count(ReplayVerdict::Unchanged), 4);
+        assert!(report.divergent().is_empty());
+        assert_eq!(report.outcomes[3].order_id.as_deref(), Some("o-4"));
+        assert_eq!(report.outcomes[3].index, 1);
+
+        // A daily loss limit would have stopped o-2 after the loss (and
+        // o-4 after the kill-switch reset)
+        let candidate: RiskPolicyConfig = serde_yaml::from_str(
+            "policies:\n  - type: PositionLimit\n    max_size: 1000.0\n  - type: DailyLossLimit\n    max_loss_usd: 500.0\n",
+        )
+        .unwrap();
+        let report = replay(candidate, &records);
+        assert_eq!(report.find("o-1").unwrap().verdict, ReplayVerdict::Unchanged);
+        assert_eq!(report.find("o-2").unwrap().verdict, ReplayVerdict::NowRejected);
+        assert_eq!(report.find("o-2").unwrap().replayed.violations[0].policy, "DailyLossLimit");
+        assert_eq!(report.find("o-3").unwrap().verdict, ReplayVerdict::Unchanged);
+        assert_eq!(report.find("o-4").unwrap().verdict, ReplayVerdict::NowRejected);
+        assert_eq!(report.divergent().len(), 2);
+
+        // The loss drops out of the window a day later
+        let mut later = records.clone();
+        later.push(order(start + Duration::hours(25), "o-5", 0.0, 10.0, true));
+        let candidate: RiskPolicyConfig =
+            serde_yaml::from_str("policies:\n  - type: DailyLossLimit\n    max_loss_usd: 500.0\n").unwrap();
+        assert_eq!(replay(candidate, &later).find("o-5").unwrap().verdict, ReplayVerdict::Unchanged);
+    }
+}
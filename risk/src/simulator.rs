@@ -190,6 +190,31 @@ impl PolymarketSimulator {
             .sum()
     }
 
+    /// Settle a resolved market at `payout` per share
+    ///
+    /// Removes the market's position and returns the PnL realized by the
+    /// settlement (market value at the payout minus invested capital), or
+    /// `None` if the market had no position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ag_risk::PolymarketSimulator;
+    ///
+    /// let mut sim = PolymarketSimulator::new();
+    /// sim.update_position("0x123", 100.0, 0.55);
+    ///
+    /// // Resolved YES: each share pays 1.0
+    /// let pnl = sim.resolve_market("0x123", 1.0).unwrap();
+    /// assert!((pnl - 45.0).abs() < 1e-10);
+    /// assert_eq!(sim.get_position("0x123"), 0.0);
+    /// ```
+    pub fn resolve_market(&mut self, market_id: &str, payout: f64) -> Option<f64> {
+        self.positions
+            .remove(market_id)
+            .map(|position| position.size * payout - position.invested_capital)
+    }
+
     /// Reset all positions to zero
     pub fn reset(&mut self) {
         self.positions.clear();
@@ -433,4 +458,17 @@ mod tests {
         assert_eq!(sim.get_position("0x123"), -100.0);
         assert_eq!(sim.get_avg_price("0x123"), 0.60);
     }
+
+    #[test]
+    fn test_resolve_market() {
+        let mut sim = PolymarketSimulator::new();
+        sim.update_position("0x123", -100.0, 0.40);
+        sim.update_position("0x456", 50.0, 0.30);
+
+        // Short YES resolving NO keeps the premium
+        let pnl = sim.resolve_market("0x123", 0.0).unwrap();
+        assert!((pnl - 40.0).abs() < 1e-10);
+        assert_eq!(sim.get_active_markets(), vec!["0x456".to_string()]);
+        assert!(sim.resolve_market("0x123", 0.0).is_none());
+    }
 }
@@ -0,0 +1,85 @@
+//! Risk engine state snapshots
+//!
+//! A [`RiskStateSnapshot`] captures everything the engine accumulates at
+//! runtime (kill-switch status and history, loss streaks for stateful policies,
+//! per-policy counters and the breach recovery stage). Persist it periodically and restore it on
+//! startup so a restarted bot resumes with the same risk posture.
+//! Policies themselves are not included; they come from configuration.
+
+use crate::kill_switch::KillSwitchEvent;
+use crate::recovery::RecoveryState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Evaluation counters for one policy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyCounters {
+    /// Contexts the policy was evaluated against
+    pub evaluations: u64,
+
+    /// Contexts the policy rejected
+    pub rejections: u64,
+
+    /// Time of the most recent rejection
+    #[serde(default)]
+    pub last_rejection: Option<DateTime<Utc>>,
+}
+
+/// Serializable risk engine runtime state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskStateSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+
+    /// Runtime kill-switch status
+    pub kill_switch_active: bool,
+
+    /// Recent kill-switch activations and resets, oldest first
+    #[serde(default)]
+    pub kill_switch_history: Vec<KillSwitchEvent>,
+
+    /// Close times of the current losing streak per market
+    /// (`LossStreakCooldown`)
+    #[serde(default)]
+    pub loss_streaks: HashMap<String, Vec<DateTime<Utc>>>,
+
+    /// Realized PnL of round trips closed in the last 24 hours, with close
+    /// times (`DailyLossLimit`)
+    #[serde(default)]
+    pub realized_pnl: Vec<(DateTime<Utc>, f64)>,
+
+    /// Counters keyed by policy label (e.g. `PositionLimit (market: 0x123)`)
+    #[serde(default)]
+    pub counters: BTreeMap<String, PolicyCounters>,
+
+    /// Stage of the limit breach recovery workflow
+    #[serde(default)]
+    pub recovery: RecoveryState,
+}
+
+impl RiskStateSnapshot {
+    /// Serialize to JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("Failed to serialize snapshot: {}", e))
+    }
+
+    /// Parse from JSON, rejecting unsupported versions
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let snapshot: Self =
+            serde_json::from_str(json).map_err(|e| format!("Failed to parse snapshot: {}", e))?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(format!(
+                "Snapshot version {} is newer than supported version {}",
+                snapshot.version, SNAPSHOT_VERSION
+            ));
+        }
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,137 @@
+//! Risk engine metrics emission
+//!
+//! A [`RiskMetricsSink`] attached with `RiskEngine::set_metrics_sink`
+//! receives the engine's metrics as decisions are made, so callers don't
+//! have to instrument every `evaluate` themselves:
+//!
+//! - [`EVALUATIONS`] (counter): contexts evaluated
+//! - [`REJECTIONS`] (counter, `policy` label): rejections per policy label,
+//!   e.g. `PositionLimit (market: 0x123)` or `KillSwitch (active)`
+//! - [`KILL_SWITCH`] (gauge): 1 while the runtime kill-switch is active,
+//!   emitted on every change
+//! - [`KILL_SWITCH_EVENTS`] (counter, `action` and `source` labels):
+//!   kill-switch activations and resets, by source kind (`manual`,
+//!   `policy`, `external`)
+//!
+//! Rates and headroom are sampled by `RiskEngine::publish_metrics`, called
+//! on the caller's metrics interval:
+//!
+//! - [`EVALUATIONS_PER_SEC`] (gauge): evaluation rate since the last publish
+//! - [`HEADROOM`] (gauge, `market` label): size the tightest applicable
+//!   `PositionLimit` still allows, from the position last evaluated in the
+//!   market (scaled during recovery probation; negative once over the limit)
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use ag_risk::telemetry::{HEADROOM, REJECTIONS};
+//! use ag_risk::{RecordingSink, RiskContext, RiskEngine};
+//!
+//! let engine = RiskEngine::from_yaml("policies:\n  - type: PositionLimit\n    max_size: 100.0\n").unwrap();
+//! let sink = Arc::new(RecordingSink::new());
+//! engine.set_metrics_sink(sink.clone());
+//!
+//! engine.evaluate(&RiskContext {
+//!     market_id: "0x123".to_string(),
+//!     current_position: 40.0,
+//!     proposed_size: 80.0,
+//!     inventory_value_usd: 0.0,
+//! });
+//! engine.publish_metrics();
+//!
+//! assert_eq!(sink.total(REJECTIONS, &[("policy", "PositionLimit")]), 1);
+//! assert_eq!(sink.latest(HEADROOM, &[("market", "0x123")]), Some(60.0));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Contexts evaluated (counter)
+pub const EVALUATIONS: &str = "risk.evaluations";
+
+/// Rejections per policy label (counter)
+pub const REJECTIONS: &str = "risk.rejections";
+
+/// Runtime kill-switch state, 0 or 1 (gauge)
+pub const KILL_SWITCH: &str = "risk.kill_switch";
+
+/// Kill-switch activations and resets (counter)
+pub const KILL_SWITCH_EVENTS: &str = "risk.kill_switch_events";
+
+/// Evaluations per second since the previous publish (gauge)
+pub const EVALUATIONS_PER_SEC: &str = "risk.evaluations_per_sec";
+
+/// Remaining `PositionLimit` size per market (gauge)
+pub const HEADROOM: &str = "risk.headroom";
+
+/// Destination of risk engine metrics
+///
+/// Implementations forward to the caller's metrics pipeline (a monitor
+/// socket, a storage buffer). Calls are made while the engine evaluates, so
+/// they should not block.
+pub trait RiskMetricsSink: Send + Sync {
+    /// Add `delta` to a counter
+    fn counter(&self, name: &str, labels: &[(&str, &str)], delta: u64);
+
+    /// Set a gauge
+    fn gauge(&self, name: &str, labels: &[(&str, &str)], value: f64);
+}
+
+/// Metric name and sorted labels
+type SeriesKey = (String, Vec<(String, String)>);
+
+/// Metric name, sorted labels and value, as listed by [`RecordingSink::values`]
+pub type SeriesValue = (String, Vec<(String, String)>, f64);
+
+/// Sink keeping counter totals and the latest gauge values in memory
+///
+/// Useful in tests, or as a buffer read by a periodic metrics flush.
+#[derive(Debug, Default)]
+pub struct RecordingSink {
+    counters: Mutex<BTreeMap<SeriesKey, u64>>,
+    gauges: Mutex<BTreeMap<SeriesKey, f64>>,
+}
+
+impl RecordingSink {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total of a counter series (0 if never incremented)
+    pub fn total(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.counters.lock().unwrap().get(&series_key(name, labels)).copied().unwrap_or(0)
+    }
+
+    /// Latest value of a gauge series
+    pub fn latest(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.gauges.lock().unwrap().get(&series_key(name, labels)).copied()
+    }
+
+    /// Every series as `(name, labels, value)`: counter totals, then
+    /// latest gauge values
+    pub fn values(&self) -> Vec<SeriesValue> {
+        let counters = self.counters.lock().unwrap();
+        let gauges = self.gauges.lock().unwrap();
+        counters
+            .iter()
+            .map(|((name, labels), total)| (name.clone(), labels.clone(), *total as f64))
+            .chain(gauges.iter().map(|((name, labels), value)| (name.clone(), labels.clone(), *value)))
+            .collect()
+    }
+}
+
+impl RiskMetricsSink for RecordingSink {
+    fn counter(&self, name: &str, labels: &[(&str, &str)], delta: u64) {
+        *self.counters.lock().unwrap().entry(series_key(name, labels)).or_insert(0) += delta;
+    }
+
+    fn gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.gauges.lock().unwrap().insert(series_key(name, labels), value);
+    }
+}
+
+fn series_key(name: &str, labels: &[(&str, &str)]) -> SeriesKey {
+    let mut labels: Vec<(String, String)> = labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    labels.sort();
+    (name.to_string(), labels)
+}
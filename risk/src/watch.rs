@@ -0,0 +1,244 @@
+//! Hot reload of a policy file
+//!
+//! [`RiskEngine::watch_file`](crate::RiskEngine::watch_file) watches a YAML
+//! policy file and swaps the engine's policies whenever the file's contents
+//! change, without touching its runtime state (positions, counters, loss
+//! streaks, kill-switch). The swap is atomic: an evaluation runs against
+//! either the old or the new policies, never a mix.
+//!
+//! The file's directory is watched rather than the file itself, so files
+//! replaced by rename (editors, config management, Kubernetes ConfigMap
+//! updates) keep being followed. A write that fails to parse or validate
+//! leaves the active policies in place; the failure is reported by
+//! [`PolicyWatcher::poll`] and the next valid write is applied. Reloads are
+//! stamped with the engine's clock.
+//!
+//! ```rust,no_run
+//! use ag_risk::watch::PolicyReload;
+//! use ag_risk::RiskEngine;
+//!
+//! let engine = RiskEngine::from_yaml(&std::fs::read_to_string("policies.yaml").unwrap()).unwrap();
+//! let watcher = engine.watch_file("policies.yaml").unwrap();
+//!
+//! // Later, e.g. on each pass of the event loop
+//! for reload in watcher.poll() {
+//!     if let PolicyReload::Rejected { error, .. } = reload {
+//!         eprintln!("kept previous risk policies: {}", error);
+//!     }
+//! }
+//! ```
+
+use crate::clock::SharedClock;
+use crate::engine::{PolicySet, SharedPolicies};
+use chrono::{DateTime, Utc};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Undrained reload outcomes retained
+const MAX_RELOAD_EVENTS: usize = 64;
+
+/// Outcome of a change to the watched file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PolicyReload {
+    /// The file's policies are active
+    Applied { at: DateTime<Utc>, policies: usize },
+    /// The file was unreadable or invalid; the previous policies stay
+    /// active
+    Rejected { at: DateTime<Utc>, error: String },
+}
+
+/// Watch on a policy file; dropping it stops the reloads
+pub struct PolicyWatcher {
+    reloader: Arc<Reloader>,
+    _watcher: RecommendedWatcher,
+}
+
+/// Applies new contents of the file to the engine's policies
+struct Reloader {
+    path: PathBuf,
+    policies: SharedPolicies,
+    clock: Arc<RwLock<SharedClock>>,
+    /// Contents last acted on, so each version is applied or rejected once
+    loaded: Mutex<String>,
+    events: Mutex<Vec<PolicyReload>>,
+}
+
+impl Reloader {
+    fn check(&self) -> Option<PolicyReload> {
+        let mut loaded = self.loaded.lock().unwrap();
+        // Writers truncate before writing and editors save in several
+        // steps; act once per content, and not on a truncated file
+        let contents = match read(&self.path) {
+            Ok(contents) if contents == *loaded || contents.trim().is_empty() => return None,
+            Ok(contents) => contents,
+            // Removed or mid-replace; keep waiting for the new file
+            Err(_) => return None,
+        };
+
+        let at = self.clock.read().unwrap().now();
+        let outcome = match PolicySet::from_yaml(&contents) {
+            Ok(set) => {
+                let policies = set.len();
+                *self.policies.write().unwrap() = Arc::new(set);
+                PolicyReload::Applied { at, policies }
+            }
+            Err(error) => PolicyReload::Rejected { at, error },
+        };
+        *loaded = contents;
+
+        let mut pending = self.events.lock().unwrap();
+        pending.push(outcome.clone());
+        let excess = pending.len().saturating_sub(MAX_RELOAD_EVENTS);
+        pending.drain(..excess);
+        Some(outcome)
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+impl PolicyWatcher {
+    /// Load `path` into `policies`, then reload it on every change
+    pub(crate) fn start(
+        path: &Path,
+        policies: SharedPolicies,
+        clock: Arc<RwLock<SharedClock>>,
+    ) -> Result<Self, String> {
+        let loaded = read(path)?;
+        let set = PolicySet::from_yaml(&loaded).map_err(|e| format!("{}: {}", path.display(), e))?;
+        *policies.write().unwrap() = Arc::new(set);
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| format!("{} is not a file", path.display()))?
+            .to_os_string();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let reloader = Arc::new(Reloader {
+            path: path.to_path_buf(),
+            policies,
+            clock,
+            loaded: Mutex::new(loaded),
+            events: Mutex::new(Vec::new()),
+        });
+
+        let on_change = reloader.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let changed = matches!(event.kind, EventKind::Any | EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|path| path.file_name() == Some(file_name.as_os_str()));
+            if changed {
+                on_change.check();
+            }
+        })
+        .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+
+        Ok(Self {
+            reloader,
+            _watcher: watcher,
+        })
+    }
+
+    /// Watched file
+    pub fn path(&self) -> &Path {
+        &self.reloader.path
+    }
+
+    /// Re-read the file now rather than on its next change event, e.g. on
+    /// SIGHUP or on filesystems without reliable events
+    ///
+    /// Returns the outcome if the contents changed since they were last
+    /// acted on; it is also reported by [`poll`](Self::poll).
+    pub fn check(&self) -> Option<PolicyReload> {
+        self.reloader.check()
+    }
+
+    /// Reloads since the previous poll, oldest first
+    pub fn poll(&self) -> Vec<PolicyReload> {
+        std::mem::take(&mut *self.reloader.events.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, MockClock};
+    use crate::{PolicyRule, RiskContext, RiskEngine};
+
+    fn order(size: f64) -> RiskContext {
+        RiskContext {
+            market_id: "0x123".to_string(),
+            current_position: 0.0,
+            proposed_size: size,
+            inventory_value_usd: 0.0,
+        }
+    }
+
+    /// Outcome for the file's current contents, whether the change event
+    /// or the explicit check got to it first
+    fn reload(watcher: &PolicyWatcher) -> PolicyReload {
+        watcher.check();
+        watcher.poll().pop().expect("contents were not reloaded")
+    }
+
+    #[test]
+    fn test_watch_file() {
+        let dir = std::env::temp_dir().join(format!("ag-risk-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("policies.yaml");
+        let limit = |max_size: f64| format!("policies:\n  - type: PositionLimit\n    max_size: {}\n", max_size);
+        std::fs::write(&path, limit(100.0)).unwrap();
+
+        let engine = RiskEngine::from_yaml(&limit(1000.0)).unwrap();
+        let clock = MockClock::new(crate::clock::now());
+        engine.set_clock(Arc::new(clock.clone()));
+        assert!(engine.watch_file(dir.join("missing.yaml")).is_err());
+
+        // Watching loads the file right away
+        let watcher = engine.watch_file(&path).unwrap();
+        engine.evaluate(&order(50.0));
+        assert!(!engine.evaluate(&order(200.0)).allowed);
+        assert_eq!(watcher.check(), None);
+
+        clock.advance(chrono::Duration::seconds(30));
+        std::fs::write(&path, limit(500.0)).unwrap();
+        assert_eq!(
+            reload(&watcher),
+            PolicyReload::Applied {
+                at: clock.now(),
+                policies: 1
+            }
+        );
+        assert!(engine.evaluate(&order(200.0)).allowed);
+        // Counters carry over the reload
+        assert_eq!(engine.policy_counters()["PositionLimit"].evaluations, 3);
+
+        // A malformed file keeps the previous policies
+        std::fs::write(&path, "policies:\n  - type: PositionLimit\n    max_sise: 10.0\n").unwrap();
+        assert!(matches!(reload(&watcher), PolicyReload::Rejected { .. }));
+        std::fs::write(&path, "policies: []\n").unwrap();
+        assert!(matches!(reload(&watcher), PolicyReload::Rejected { error, .. } if error.contains("no policies")));
+        assert!(engine.evaluate(&order(200.0)).allowed);
+
+        // Replacing the file by rename is followed
+        let staged = dir.join("policies.yaml.tmp");
+        std::fs::write(&staged, limit(150.0)).unwrap();
+        std::fs::rename(&staged, &path).unwrap();
+        assert!(matches!(reload(&watcher), PolicyReload::Applied { .. }));
+        assert!(matches!(engine.policy_config().policies[0], PolicyRule::PositionLimit { max_size, .. } if max_size == 150.0));
+
+        drop(watcher);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
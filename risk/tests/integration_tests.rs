@@ -3,7 +3,7 @@
 //! These tests verify end-to-end functionality including policy loading,
 //! risk evaluation, and simulator integration.
 
-use ag_risk::{PolymarketSimulator, RiskContext, RiskEngine};
+use ag_risk::{KillSwitchSource, PolymarketSimulator, RiskContext, RiskEngine};
 use std::fs;
 
 #[test]
@@ -41,7 +41,7 @@ fn test_conservative_policy() {
 
     let decision = engine.evaluate(&ctx);
     assert!(!decision.allowed);
-    assert!(decision.violated_policies[0].contains("PositionLimit"));
+    assert!(decision.violations[0].message.contains("PositionLimit"));
 }
 
 #[test]
@@ -154,15 +154,15 @@ policies:
     assert!(engine.evaluate(&ctx).allowed);
 
     // Trigger kill-switch
-    engine.trigger_kill_switch();
+    engine.trigger_kill_switch(KillSwitchSource::Manual, "integration test");
 
     // Should now block
     let decision = engine.evaluate(&ctx);
     assert!(!decision.allowed);
-    assert!(decision.violated_policies[0].contains("KillSwitch"));
+    assert!(decision.violations[0].message.contains("KillSwitch"));
 
     // Reset
-    engine.reset_kill_switch();
+    engine.reset_kill_switch(KillSwitchSource::Manual, "integration test");
 
     // Should allow again
     assert!(engine.evaluate(&ctx).allowed);
@@ -332,5 +332,5 @@ policies:
     let decision = engine.evaluate(&ctx);
     assert!(!decision.allowed);
     // Both policies should be violated
-    assert!(decision.violated_policies.len() >= 2);
+    assert!(decision.violations.len() >= 2);
 }
@@ -0,0 +1,256 @@
+//! Cron expressions
+//!
+//! Standard five-field expressions (`minute hour day-of-month month
+//! day-of-week`) evaluated in UTC, with `*`, lists (`1,15`), ranges
+//! (`9-17`), steps (`*/5`, `0-30/10`), month and weekday names (`JAN`,
+//! `MON-FRI`) and the `@hourly`, `@daily`, `@weekly`, `@monthly` and
+//! `@yearly` shorthands. As in cron, when both day fields are restricted
+//! (neither starts with `*`) a day matches if either does.
+
+use crate::error::{ScheduleError, ScheduleResult};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Years searched for a match before giving up (e.g. `0 0 30 2 *`)
+const MAX_SEARCH_YEARS: i32 = 5;
+
+const MONTH_NAMES: &[&str] = &["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronExpr {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronExpr {
+    /// Parse a cron expression
+    pub fn parse(expr: &str) -> ScheduleResult<Self> {
+        let invalid = |reason: String| ScheduleError::InvalidCron {
+            expr: expr.to_string(),
+            reason,
+        };
+
+        let expanded = match expr.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => return Err(invalid(format!("unknown shorthand {}", other))),
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(format!("expected 5 fields, got {}", fields.len())));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, &[]).map_err(|e| invalid(format!("minute: {}", e)))?;
+        let hours = parse_field(fields[1], 0, 23, &[]).map_err(|e| invalid(format!("hour: {}", e)))?;
+        let days_of_month = parse_field(fields[2], 1, 31, &[]).map_err(|e| invalid(format!("day of month: {}", e)))?;
+        let months = parse_field(fields[3], 1, 12, MONTH_NAMES).map_err(|e| invalid(format!("month: {}", e)))?;
+        let mut days_of_week =
+            parse_field(fields[4], 0, 7, WEEKDAY_NAMES).map_err(|e| invalid(format!("day of week: {}", e)))?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            source: expr.trim().to_string(),
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            dom_restricted: !fields[2].starts_with('*'),
+            dow_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    /// The expression as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// First matching minute strictly after `after`
+    ///
+    /// Returns `None` if nothing matches within five years (e.g. February 30th).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let truncated = Utc
+            .with_ymd_and_hms(after.year(), after.month(), after.day(), after.hour(), after.minute(), 0)
+            .single()?;
+        let mut t = truncated + Duration::minutes(1);
+        let limit = after.year() + MAX_SEARCH_YEARS;
+
+        while t.year() <= limit {
+            if !has(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(t.date_naive()) {
+                let next = t.date_naive().succ_opt()?;
+                t = Utc.from_utc_datetime(&next.and_hms_opt(0, 0, 0)?);
+                continue;
+            }
+            if !has(self.hours, t.hour()) {
+                t = Utc.with_ymd_and_hms(t.year(), t.month(), t.day(), t.hour(), 0, 0).single()? + Duration::hours(1);
+                continue;
+            }
+            if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = has(self.days_of_month, date.day());
+        let dow = has(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = ScheduleError;
+
+    fn from_str(s: &str) -> ScheduleResult<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for CronExpr {
+    type Error = ScheduleError;
+
+    fn try_from(s: String) -> ScheduleResult<Self> {
+        Self::parse(&s)
+    }
+}
+
+impl From<CronExpr> for String {
+    fn from(expr: CronExpr) -> String {
+        expr.source
+    }
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parse one field into a bitset of allowed values
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be > 0".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, max, names)?, parse_value(b, min, max, names)?)
+        } else {
+            let start = parse_value(range, min, max, names)?;
+            // `a/n` runs from a to the end of the range
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(format!("empty range '{}'", range));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let upper = value.to_ascii_uppercase();
+    // Names index from the field minimum (JAN = 1, SUN = 0)
+    let parsed = match names.iter().position(|name| *name == upper) {
+        Some(index) => index as u32 + min,
+        None => value.parse().map_err(|_| format!("invalid value '{}'", value))?,
+    };
+    if parsed < min || parsed > max {
+        return Err(format!("{} is outside {}-{}", parsed, min, max));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        let every_5 = CronExpr::parse("*/5 * * * *").unwrap();
+        assert_eq!(every_5.next_after(at(2026, 1, 1, 10, 2)), Some(at(2026, 1, 1, 10, 5)));
+        assert_eq!(every_5.next_after(at(2026, 1, 1, 10, 5)), Some(at(2026, 1, 1, 10, 10)));
+
+        // Weekdays at 14:30; 2026-01-02 is a Friday
+        let weekdays = CronExpr::parse("30 14 * * MON-FRI").unwrap();
+        assert_eq!(weekdays.next_after(at(2026, 1, 2, 15, 0)), Some(at(2026, 1, 5, 14, 30)));
+
+        let daily = CronExpr::parse("@daily").unwrap();
+        assert_eq!(daily.next_after(at(2026, 12, 31, 23, 59)), Some(at(2027, 1, 1, 0, 0)));
+
+        // Either day field matches when both are restricted
+        let either = CronExpr::parse("0 0 15 * SUN").unwrap();
+        assert_eq!(either.next_after(at(2026, 1, 1, 0, 0)), Some(at(2026, 1, 4, 0, 0)));
+
+        // A stepped wildcard does not restrict its field: odd-day Mondays
+        let odd_mondays = CronExpr::parse("0 0 */2 * MON").unwrap();
+        assert_eq!(odd_mondays.next_after(at(2026, 1, 1, 0, 0)), Some(at(2026, 1, 5, 0, 0)));
+        assert_eq!(odd_mondays.next_after(at(2026, 1, 5, 0, 0)), Some(at(2026, 1, 19, 0, 0)));
+        assert_eq!(odd_mondays.next_after(at(2026, 1, 19, 0, 0)), Some(at(2026, 2, 9, 0, 0)));
+
+        assert_eq!(CronExpr::parse("0 0 30 2 *").unwrap().next_after(at(2026, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("0 17-9 * * *").is_err());
+        assert!(CronExpr::parse("@often").is_err());
+
+        let expr: CronExpr = serde_yaml::from_str("\"0 9 * * 1\"").unwrap();
+        assert_eq!(expr.as_str(), "0 9 * * 1");
+    }
+}
@@ -0,0 +1,18 @@
+//! Error types for schedule parsing
+
+use thiserror::Error;
+
+/// Schedule error
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// Cron expression could not be parsed
+    #[error("Invalid cron expression '{expr}': {reason}")]
+    InvalidCron { expr: String, reason: String },
+
+    /// Schedule config names no kind of schedule, or more than one
+    #[error("Invalid schedule: {reason}")]
+    InvalidSchedule { reason: String },
+}
+
+/// Result type for schedule operations
+pub type ScheduleResult<T> = Result<T, ScheduleError>;
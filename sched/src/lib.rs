@@ -0,0 +1,45 @@
+//! # ag-sched: Scheduling Utilities
+//!
+//! Shared scheduling for ag-botkit components: fixed intervals with jitter
+//! and wall-clock alignment, cron expressions and one-shot times.
+//!
+//! - [`Schedule`] computes the next deadline of a job
+//! - [`Scheduler`] keeps many keyed jobs in one deadline-ordered heap
+//!   (strategy timers, session stops, reconciliation jobs)
+//! - [`Ticker`] (feature `runtime`) drives a single async loop from a
+//!   schedule, in place of `tokio::time::interval`
+//! - [`LivenessRegistry`] collects heartbeats from long-running tasks and
+//!   detects the ones that stalled
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ag_sched::{CronExpr, Schedule, Scheduler};
+//! use chrono::{TimeZone, Utc};
+//!
+//! let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+//! let mut scheduler = Scheduler::new();
+//! scheduler.schedule("retention", CronExpr::parse("0 3 * * *").unwrap(), now);
+//! scheduler.schedule("flush", Schedule::every(std::time::Duration::from_secs(1)), now);
+//!
+//! assert_eq!(scheduler.due(now + chrono::Duration::seconds(1)), vec!["flush"]);
+//! ```
+
+pub mod cron;
+pub mod error;
+pub mod liveness;
+pub mod schedule;
+pub mod scheduler;
+#[cfg(feature = "runtime")]
+pub mod ticker;
+
+pub use cron::CronExpr;
+pub use error::{ScheduleError, ScheduleResult};
+pub use liveness::{HealthReport, Heartbeat, LivenessEvent, LivenessRegistry, SubsystemHealth};
+pub use schedule::{IntervalSchedule, Schedule};
+pub use scheduler::Scheduler;
+#[cfg(feature = "runtime")]
+pub use ticker::{next_tick, sleep_until, Ticker};
+
+/// Library version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
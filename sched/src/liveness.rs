@@ -0,0 +1,294 @@
+//! Heartbeats and stall detection for long-running tasks
+//!
+//! Each long-running task (feed supervisor, event loop, storage flusher,
+//! retention scheduler) registers with a [`LivenessRegistry`] and calls
+//! [`Heartbeat::beat`] whenever it makes progress. A supervisor checks the
+//! registry on its own interval: [`report`](LivenessRegistry::report)
+//! gives the health of every subsystem, and
+//! [`poll`](LivenessRegistry::poll) returns the stalls and recoveries since
+//! the previous poll, for alerting once per transition.
+//!
+//! A subsystem is stalled once it has gone `stall_after` without a beat, so
+//! the threshold should comfortably exceed the task's slowest normal cycle.
+//!
+//! ```rust
+//! use ag_sched::liveness::{LivenessEvent, LivenessRegistry};
+//! use chrono::{Duration, TimeZone, Utc};
+//!
+//! let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+//! let registry = LivenessRegistry::new();
+//! let flusher = registry.register("storage_flush", std::time::Duration::from_secs(30), start);
+//!
+//! flusher.beat_at(start + Duration::seconds(10));
+//! assert!(registry.report(start + Duration::seconds(35)).healthy);
+//!
+//! let events = registry.poll(start + Duration::seconds(45));
+//! assert!(matches!(&events[..], [LivenessEvent::Stalled { name, .. }] if name == "storage_flush"));
+//! assert!(!registry.report(start + Duration::seconds(45)).healthy);
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug)]
+struct Subsystem {
+    last_beat: DateTime<Utc>,
+    stall_after: Duration,
+    /// Stalled as of the last `poll`
+    reported_stalled: bool,
+    /// Registration generation, so stale handles stop beating after a
+    /// re-registration or deregistration
+    generation: u64,
+}
+
+impl Subsystem {
+    fn silent_for(&self, now: DateTime<Utc>) -> Duration {
+        (now - self.last_beat).to_std().unwrap_or_default()
+    }
+
+    fn is_stalled(&self, now: DateTime<Utc>) -> bool {
+        self.silent_for(now) > self.stall_after
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    subsystems: BTreeMap<String, Subsystem>,
+    generation: u64,
+}
+
+/// Registered subsystems and their last heartbeats; clones share state
+#[derive(Debug, Clone, Default)]
+pub struct LivenessRegistry {
+    inner: Arc<Mutex<Registry>>,
+}
+
+impl LivenessRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a subsystem, counting `now` as its first beat
+    ///
+    /// Handles from an earlier registration under the same name stop
+    /// counting.
+    pub fn register(&self, name: impl Into<String>, stall_after: Duration, now: DateTime<Utc>) -> Heartbeat {
+        let name = name.into();
+        let mut registry = self.lock();
+        registry.generation += 1;
+        let generation = registry.generation;
+        registry.subsystems.insert(
+            name.clone(),
+            Subsystem {
+                last_beat: now,
+                stall_after,
+                reported_stalled: false,
+                generation,
+            },
+        );
+        Heartbeat {
+            name: name.into(),
+            generation,
+            registry: self.clone(),
+        }
+    }
+
+    /// Stop tracking a subsystem (e.g. a task that finished normally)
+    pub fn deregister(&self, name: &str) -> bool {
+        self.lock().subsystems.remove(name).is_some()
+    }
+
+    /// Names of the registered subsystems, sorted
+    pub fn subsystems(&self) -> Vec<String> {
+        self.lock().subsystems.keys().cloned().collect()
+    }
+
+    /// Health of every subsystem as of `now`
+    pub fn report(&self, now: DateTime<Utc>) -> HealthReport {
+        let registry = self.lock();
+        let subsystems: Vec<SubsystemHealth> = registry
+            .subsystems
+            .iter()
+            .map(|(name, subsystem)| SubsystemHealth {
+                name: name.clone(),
+                last_beat: subsystem.last_beat,
+                silent_ms: subsystem.silent_for(now).as_millis() as u64,
+                stall_after_ms: subsystem.stall_after.as_millis() as u64,
+                stalled: subsystem.is_stalled(now),
+            })
+            .collect();
+        HealthReport {
+            at: now,
+            healthy: subsystems.iter().all(|subsystem| !subsystem.stalled),
+            subsystems,
+        }
+    }
+
+    /// Stalls and recoveries since the previous poll, in name order
+    pub fn poll(&self, now: DateTime<Utc>) -> Vec<LivenessEvent> {
+        let mut registry = self.lock();
+        let mut events = Vec::new();
+        for (name, subsystem) in registry.subsystems.iter_mut() {
+            let stalled = subsystem.is_stalled(now);
+            if stalled == subsystem.reported_stalled {
+                continue;
+            }
+            subsystem.reported_stalled = stalled;
+            events.push(if stalled {
+                LivenessEvent::Stalled {
+                    name: name.clone(),
+                    last_beat: subsystem.last_beat,
+                    silent_ms: subsystem.silent_for(now).as_millis() as u64,
+                }
+            } else {
+                LivenessEvent::Recovered {
+                    name: name.clone(),
+                    at: subsystem.last_beat,
+                }
+            });
+        }
+        events
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Registry> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Handle a task beats through; cheap to clone
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    name: Arc<str>,
+    generation: u64,
+    registry: LivenessRegistry,
+}
+
+impl Heartbeat {
+    /// Subsystem name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Record progress at `now`
+    ///
+    /// Ignored once the subsystem has been deregistered or registered
+    /// again.
+    pub fn beat_at(&self, now: DateTime<Utc>) {
+        let mut registry = self.registry.lock();
+        if let Some(subsystem) = registry.subsystems.get_mut(&*self.name) {
+            if subsystem.generation == self.generation && now > subsystem.last_beat {
+                subsystem.last_beat = now;
+            }
+        }
+    }
+
+    /// Record progress now
+    #[cfg(feature = "runtime")]
+    pub fn beat(&self) {
+        self.beat_at(Utc::now());
+    }
+}
+
+/// Health of one subsystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubsystemHealth {
+    pub name: String,
+    pub last_beat: DateTime<Utc>,
+    /// Time since the last beat
+    pub silent_ms: u64,
+    /// Silence after which the subsystem counts as stalled
+    pub stall_after_ms: u64,
+    pub stalled: bool,
+}
+
+/// Health of every registered subsystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub at: DateTime<Utc>,
+    /// No subsystem is stalled
+    pub healthy: bool,
+    /// Subsystems in name order
+    pub subsystems: Vec<SubsystemHealth>,
+}
+
+impl HealthReport {
+    /// Stalled subsystems
+    pub fn stalled(&self) -> Vec<&SubsystemHealth> {
+        self.subsystems.iter().filter(|subsystem| subsystem.stalled).collect()
+    }
+}
+
+/// Stall state change reported by [`LivenessRegistry::poll`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LivenessEvent {
+    /// No beat for longer than the subsystem's `stall_after`
+    Stalled {
+        name: String,
+        last_beat: DateTime<Utc>,
+        silent_ms: u64,
+    },
+    /// A stalled subsystem beat again (`at` is that beat)
+    Recovered { name: String, at: DateTime<Utc> },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration as ChronoDuration, TimeZone};
+
+    #[test]
+    fn test_stall_detection() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let at = |secs: i64| start + ChronoDuration::seconds(secs);
+        let registry = LivenessRegistry::new();
+        let feed = registry.register("feed", Duration::from_secs(10), start);
+        let event_loop = registry.register("event_loop", Duration::from_secs(5), start);
+        assert_eq!(registry.subsystems(), vec!["event_loop", "feed"]);
+
+        // Exactly at the threshold is still alive
+        event_loop.beat_at(at(3));
+        let report = registry.report(at(8));
+        assert!(report.healthy);
+        assert_eq!(report.subsystems[0].silent_ms, 5000);
+        assert!(registry.poll(at(8)).is_empty());
+
+        // Stalls are reported once, then recoveries once
+        let events = registry.poll(at(9));
+        assert_eq!(
+            events,
+            vec![LivenessEvent::Stalled {
+                name: "event_loop".to_string(),
+                last_beat: at(3),
+                silent_ms: 6000
+            }]
+        );
+        assert!(registry.poll(at(10)).is_empty());
+        let report = registry.report(at(11));
+        assert_eq!(report.stalled().iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["event_loop", "feed"]);
+
+        event_loop.beat_at(at(12));
+        feed.beat_at(at(12));
+        // Clock going backwards doesn't rewind a beat
+        feed.beat_at(at(1));
+        // The feed's stall fell between polls, so nothing is reported for it
+        let events = registry.poll(at(12));
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], LivenessEvent::Recovered { name, at: when } if name == "event_loop" && *when == at(12)));
+        assert!(registry.report(at(12)).healthy);
+
+        // Stale handles stop counting
+        let feed_again = registry.register("feed", Duration::from_secs(10), at(20));
+        feed.beat_at(at(40));
+        assert!(registry.report(at(40)).subsystems[1].stalled);
+        feed_again.beat_at(at(40));
+        assert!(!registry.report(at(40)).subsystems[1].stalled);
+        assert!(registry.deregister("feed"));
+        feed_again.beat_at(at(50));
+        assert_eq!(registry.subsystems(), vec!["event_loop"]);
+    }
+}
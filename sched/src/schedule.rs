@@ -0,0 +1,218 @@
+//! Schedules: fixed intervals, cron expressions and one-shot times
+
+use crate::cron::CronExpr;
+use crate::error::{ScheduleError, ScheduleResult};
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Fixed-interval schedule with optional jitter and wall-clock alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntervalSchedule {
+    /// Interval between deadlines
+    pub interval: Duration,
+
+    /// Maximum random delay added to each deadline (spreads load between
+    /// jobs sharing the same interval)
+    #[serde(default)]
+    pub jitter: Duration,
+
+    /// Align deadlines to wall-clock multiples of `interval`
+    /// (e.g. an hourly job fires at :00 rather than relative to registration)
+    #[serde(default)]
+    pub align: bool,
+}
+
+impl IntervalSchedule {
+    /// Create a schedule firing every `interval`
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval,
+            jitter: Duration::ZERO,
+            align: false,
+        }
+    }
+
+    /// Add random jitter of up to `jitter` to each deadline
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Align deadlines to wall-clock multiples of the interval
+    pub fn aligned(mut self) -> Self {
+        self.align = true;
+        self
+    }
+
+    /// Compute the next deadline strictly after `now`
+    pub fn next_deadline(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let interval_ms = (self.interval.as_millis() as i64).max(1);
+
+        let base = if self.align {
+            let now_ms = now.timestamp_millis();
+            let next_ms = (now_ms.div_euclid(interval_ms) + 1) * interval_ms;
+            Utc.timestamp_millis_opt(next_ms).single().unwrap_or(now)
+        } else {
+            now + ChronoDuration::milliseconds(interval_ms)
+        };
+
+        let jitter_ms = self.jitter.as_millis() as i64;
+        if jitter_ms > 0 {
+            base + ChronoDuration::milliseconds(rand::thread_rng().gen_range(0..=jitter_ms))
+        } else {
+            base
+        }
+    }
+}
+
+/// When a job runs
+///
+/// Serialized as a map with a single key, e.g. `cron: "0 * * * *"` or
+/// `at: 2026-11-03T21:00:00Z`, so it reads the same from YAML and JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ScheduleMap", into = "ScheduleMap")]
+pub enum Schedule {
+    /// Repeatedly at a fixed interval
+    Interval(IntervalSchedule),
+    /// At every time matching a cron expression (UTC)
+    Cron(CronExpr),
+    /// Once at a fixed time
+    At(DateTime<Utc>),
+}
+
+/// Map form of [`Schedule`]
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScheduleMap {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interval: Option<IntervalSchedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cron: Option<CronExpr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<ScheduleMap> for Schedule {
+    type Error = ScheduleError;
+
+    fn try_from(map: ScheduleMap) -> ScheduleResult<Self> {
+        match (map.interval, map.cron, map.at) {
+            (Some(interval), None, None) => Ok(Schedule::Interval(interval)),
+            (None, Some(expr), None) => Ok(Schedule::Cron(expr)),
+            (None, None, Some(at)) => Ok(Schedule::At(at)),
+            _ => Err(ScheduleError::InvalidSchedule {
+                reason: "expected exactly one of interval, cron or at".to_string(),
+            }),
+        }
+    }
+}
+
+impl From<Schedule> for ScheduleMap {
+    fn from(schedule: Schedule) -> Self {
+        let mut map = ScheduleMap {
+            interval: None,
+            cron: None,
+            at: None,
+        };
+        match schedule {
+            Schedule::Interval(interval) => map.interval = Some(interval),
+            Schedule::Cron(expr) => map.cron = Some(expr),
+            Schedule::At(at) => map.at = Some(at),
+        }
+        map
+    }
+}
+
+impl Schedule {
+    /// Fixed interval without jitter
+    pub fn every(interval: Duration) -> Self {
+        Schedule::Interval(IntervalSchedule::every(interval))
+    }
+
+    /// Next deadline strictly after `now` (None once a one-shot has passed
+    /// or a cron expression never matches again)
+    pub fn next_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Interval(interval) => Some(interval.next_deadline(now)),
+            Schedule::Cron(expr) => expr.next_after(now),
+            Schedule::At(at) => (*at > now).then_some(*at),
+        }
+    }
+
+    /// Whether the schedule fires at most once
+    pub fn is_one_shot(&self) -> bool {
+        matches!(self, Schedule::At(_))
+    }
+}
+
+impl From<IntervalSchedule> for Schedule {
+    fn from(interval: IntervalSchedule) -> Self {
+        Schedule::Interval(interval)
+    }
+}
+
+impl From<CronExpr> for Schedule {
+    fn from(expr: CronExpr) -> Self {
+        Schedule::Cron(expr)
+    }
+}
+
+impl From<DateTime<Utc>> for Schedule {
+    fn from(at: DateTime<Utc>) -> Self {
+        Schedule::At(at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(ms).unwrap()
+    }
+
+    #[test]
+    fn test_aligned_deadline() {
+        let schedule = IntervalSchedule::every(Duration::from_secs(3600)).aligned();
+        let now = at(3_600_000 * 10 + 1234);
+        assert_eq!(schedule.next_deadline(now), at(3_600_000 * 11));
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        let schedule = IntervalSchedule::every(Duration::from_millis(100)).with_jitter(Duration::from_millis(50));
+        let now = at(0);
+        for _ in 0..100 {
+            let deadline = schedule.next_deadline(now);
+            assert!(deadline >= at(100) && deadline <= at(150));
+        }
+    }
+
+    #[test]
+    fn test_one_shot() {
+        let schedule = Schedule::At(at(1000));
+        assert_eq!(schedule.next_after(at(0)), Some(at(1000)));
+        assert_eq!(schedule.next_after(at(1000)), None);
+    }
+
+    #[test]
+    fn test_schedule_yaml() {
+        let schedule: Schedule = serde_yaml::from_str("cron: \"0 * * * *\"").unwrap();
+        assert_eq!(schedule.next_after(at(60_000)), Some(at(3_600_000)));
+
+        let schedule: Schedule = serde_yaml::from_str("at: 2026-11-03T21:00:00Z").unwrap();
+        assert!(schedule.is_one_shot());
+
+        let schedule = Schedule::every(Duration::from_secs(60));
+        let yaml = serde_yaml::to_string(&schedule).unwrap();
+        assert!(yaml.starts_with("interval:"));
+        assert_eq!(serde_yaml::from_str::<Schedule>(&yaml).unwrap(), schedule);
+
+        // Exactly one kind of schedule
+        assert!(serde_yaml::from_str::<Schedule>("{}").is_err());
+        assert!(serde_yaml::from_str::<Schedule>("cron: \"0 * * * *\"\nat: 2026-11-03T21:00:00Z").is_err());
+        assert!(serde_yaml::from_str::<Schedule>("cron: \"0 * * *\"").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Deadline-ordered job schedule
+
+use crate::schedule::Schedule;
+use chrono::{DateTime, Utc};
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Deadline-ordered schedule of keyed jobs
+///
+/// Backed by a min-heap; cancelled or rescheduled entries are discarded
+/// lazily when they reach the top. One-shot jobs are removed once due.
+#[derive(Debug)]
+pub struct Scheduler<K> {
+    /// (deadline, generation, key) ordered earliest first
+    heap: BinaryHeap<Reverse<(DateTime<Utc>, u64, K)>>,
+
+    /// key -> (schedule, current generation)
+    jobs: HashMap<K, (Schedule, u64)>,
+
+    /// Monotonic generation counter for invalidating stale heap entries
+    generation: u64,
+}
+
+impl<K> Default for Scheduler<K> {
+    fn default() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            jobs: HashMap::new(),
+            generation: 0,
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash + Ord> Scheduler<K> {
+    /// Create an empty scheduler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a job, first firing at its next deadline after
+    /// `now`
+    ///
+    /// Returns false (and registers nothing) if the schedule never fires,
+    /// e.g. a one-shot in the past.
+    pub fn schedule(&mut self, key: K, schedule: impl Into<Schedule>, now: DateTime<Utc>) -> bool {
+        let schedule = schedule.into();
+        let Some(deadline) = schedule.next_after(now) else {
+            self.jobs.remove(&key);
+            return false;
+        };
+        self.generation += 1;
+        self.jobs.insert(key.clone(), (schedule, self.generation));
+        self.heap.push(Reverse((deadline, self.generation, key)));
+        true
+    }
+
+    /// Remove a job
+    pub fn cancel<Q>(&mut self, key: &Q) -> Option<Schedule>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.jobs.remove(key).map(|(schedule, _)| schedule)
+    }
+
+    /// Get a job's schedule
+    pub fn get<Q>(&self, key: &Q) -> Option<&Schedule>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.jobs.get(key).map(|(schedule, _)| schedule)
+    }
+
+    /// Pop all jobs due at `now` and reschedule the recurring ones
+    ///
+    /// Returned keys are ordered by deadline. A job that fell behind by
+    /// several occurrences fires once, not once per missed occurrence.
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<K> {
+        let mut due = Vec::new();
+
+        while let Some(Reverse((deadline, generation, _))) = self.heap.peek() {
+            if *deadline > now {
+                break;
+            }
+            let generation = *generation;
+            let Some(Reverse((_, _, key))) = self.heap.pop() else {
+                break;
+            };
+
+            let next = match self.jobs.get(&key) {
+                Some((schedule, current)) if *current == generation => schedule.next_after(now),
+                _ => continue, // cancelled or rescheduled
+            };
+
+            match next {
+                Some(next) => self.heap.push(Reverse((next, generation, key.clone()))),
+                None => {
+                    self.jobs.remove(&key);
+                }
+            }
+            due.push(key);
+        }
+
+        due
+    }
+
+    /// Earliest pending deadline, if any
+    pub fn next_deadline(&mut self) -> Option<DateTime<Utc>> {
+        // Drop stale entries so the caller doesn't wake up for nothing
+        while let Some(Reverse((_, generation, key))) = self.heap.peek() {
+            match self.jobs.get(key) {
+                Some((_, current)) if current == generation => break,
+                _ => {
+                    self.heap.pop();
+                }
+            }
+        }
+        self.heap.peek().map(|Reverse((deadline, _, _))| *deadline)
+    }
+
+    /// Number of scheduled jobs
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Whether no jobs are scheduled
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::CronExpr;
+    use crate::schedule::IntervalSchedule;
+    use chrono::TimeZone;
+    use std::time::Duration;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(ms).unwrap()
+    }
+
+    #[test]
+    fn test_mixed_schedules() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule("fast", IntervalSchedule::every(Duration::from_millis(100)), at(0));
+        scheduler.schedule("hourly", CronExpr::parse("@hourly").unwrap(), at(0));
+        scheduler.schedule("once", at(250), at(0));
+        assert!(!scheduler.schedule("past", at(0), at(0)));
+
+        assert!(scheduler.due(at(50)).is_empty());
+        assert_eq!(scheduler.due(at(100)), vec!["fast"]);
+        assert_eq!(scheduler.due(at(250)), vec!["fast", "once"]);
+        assert_eq!(scheduler.len(), 2);
+        assert_eq!(scheduler.next_deadline(), Some(at(350)));
+        assert_eq!(scheduler.due(at(3_600_000)), vec!["fast", "hourly"]);
+    }
+
+    #[test]
+    fn test_cancel_and_reschedule() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule("a".to_string(), Schedule::every(Duration::from_millis(100)), at(0));
+        scheduler.schedule("a".to_string(), Schedule::every(Duration::from_millis(500)), at(0));
+        assert_eq!(scheduler.len(), 1);
+        assert!(scheduler.due(at(100)).is_empty());
+        assert_eq!(scheduler.due(at(500)), vec!["a".to_string()]);
+
+        scheduler.cancel("a");
+        assert!(scheduler.is_empty());
+        assert_eq!(scheduler.next_deadline(), None);
+    }
+}
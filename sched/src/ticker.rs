@@ -0,0 +1,108 @@
+//! Async tickers driven by a [`Schedule`]
+//!
+//! A [`Ticker`] replaces `tokio::time::interval` loops with any schedule:
+//! fixed intervals (optionally jittered or wall-clock aligned), cron
+//! expressions and one-shot times. Deadlines are wall-clock (UTC) times;
+//! missed occurrences are skipped rather than fired in a burst.
+
+use crate::schedule::Schedule;
+use chrono::{DateTime, Utc};
+
+/// Async ticker firing at a schedule's deadlines
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    schedule: Schedule,
+    next: Option<DateTime<Utc>>,
+}
+
+impl Ticker {
+    /// Ticker whose first tick is the schedule's next deadline
+    pub fn new(schedule: impl Into<Schedule>) -> Self {
+        let schedule = schedule.into();
+        let next = schedule.next_after(Utc::now());
+        Self { schedule, next }
+    }
+
+    /// Ticker whose first tick completes immediately
+    pub fn immediate(schedule: impl Into<Schedule>) -> Self {
+        Self {
+            schedule: schedule.into(),
+            next: Some(Utc::now()),
+        }
+    }
+
+    /// Fixed-interval ticker whose first tick completes immediately, like
+    /// `tokio::time::interval`
+    pub fn interval(period: std::time::Duration) -> Self {
+        Self::immediate(Schedule::every(period))
+    }
+
+    /// The schedule driving this ticker
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Next deadline (None once the schedule is exhausted)
+    pub fn next_deadline(&self) -> Option<DateTime<Utc>> {
+        self.next
+    }
+
+    /// Wait for the next deadline and return it
+    ///
+    /// Returns `None` immediately once the schedule is exhausted (a one-shot
+    /// that already fired). Cancel safe: dropping the future before it
+    /// completes leaves the deadline in place.
+    pub async fn tick(&mut self) -> Option<DateTime<Utc>> {
+        let deadline = self.next?;
+        sleep_until(deadline).await;
+
+        // Follow on from the deadline to avoid drift, skipping any
+        // occurrences missed while the caller was busy
+        let now = Utc::now();
+        self.next = match self.schedule.next_after(deadline) {
+            Some(next) if next > now => Some(next),
+            _ => self.schedule.next_after(now),
+        };
+        Some(deadline)
+    }
+}
+
+/// Sleep until a wall-clock deadline (returns at once if it has passed)
+pub async fn sleep_until(deadline: DateTime<Utc>) {
+    if let Ok(wait) = (deadline - Utc::now()).to_std() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Tick an optional ticker, pending forever if there is none or it is
+/// exhausted (for optional `tokio::select!` branches)
+pub async fn next_tick(ticker: &mut Option<Ticker>) -> DateTime<Utc> {
+    if let Some(ticker) = ticker {
+        if let Some(deadline) = ticker.tick().await {
+            return deadline;
+        }
+    }
+    std::future::pending().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_ticker() {
+        let mut ticker = Ticker::interval(std::time::Duration::from_millis(20));
+        let first = ticker.tick().await.unwrap();
+        let second = ticker.tick().await.unwrap();
+        assert_eq!(second - first, Duration::milliseconds(20));
+
+        let mut once = Ticker::new(Utc::now() + Duration::milliseconds(10));
+        assert!(once.tick().await.is_some());
+        assert_eq!(once.tick().await, None);
+
+        let mut none: Option<Ticker> = None;
+        let pending = tokio::time::timeout(std::time::Duration::from_millis(10), next_tick(&mut none)).await;
+        assert!(pending.is_err());
+    }
+}
@@ -1,36 +1,63 @@
 use crate::retention::RetentionManager;
 use crate::error::Result;
+use ag_sched::{Heartbeat, Schedule, Ticker};
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use std::time::Duration;
 use tracing::{error, info};
 
 /// Automated retention policy scheduler
 pub struct RetentionScheduler {
     manager: Arc<RetentionManager>,
-    interval_hours: u64,
+    schedule: Schedule,
+    /// Run once at start, before the first scheduled deadline
+    run_at_start: bool,
+    /// Beaten after every scheduled run
+    heartbeat: Option<Heartbeat>,
 }
 
 impl RetentionScheduler {
-    /// Create a new retention scheduler
+    /// Create a new retention scheduler running at start and then every
+    /// `interval_hours`
     pub fn new(manager: Arc<RetentionManager>, interval_hours: u64) -> Self {
         Self {
             manager,
-            interval_hours,
+            schedule: Schedule::every(Duration::from_secs(interval_hours * 3600)),
+            run_at_start: true,
+            heartbeat: None,
         }
     }
 
-    /// Start the scheduler (runs in background)
-    pub async fn start(self) {
-        info!(
-            "Starting retention scheduler (interval: {} hours)",
-            self.interval_hours
-        );
+    /// Create a scheduler running on an arbitrary schedule, e.g. a cron
+    /// expression for a nightly off-peak window
+    pub fn with_schedule(manager: Arc<RetentionManager>, schedule: impl Into<Schedule>) -> Self {
+        Self {
+            manager,
+            schedule: schedule.into(),
+            run_at_start: false,
+            heartbeat: None,
+        }
+    }
 
-        let mut ticker = interval(Duration::from_secs(self.interval_hours * 3600));
+    /// Report liveness after every scheduled run, failed or not
+    ///
+    /// Register the heartbeat with a stall threshold longer than the
+    /// schedule's interval.
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
 
-        loop {
-            ticker.tick().await;
+    /// Start the scheduler (runs in background until the schedule is exhausted)
+    pub async fn start(self) {
+        info!("Starting retention scheduler ({:?})", self.schedule);
+
+        let mut ticker = if self.run_at_start {
+            Ticker::immediate(self.schedule.clone())
+        } else {
+            Ticker::new(self.schedule.clone())
+        };
 
+        while ticker.tick().await.is_some() {
             info!("Running scheduled retention cleanup");
 
             match self.manager.run_retention().await {
@@ -57,6 +84,10 @@ impl RetentionScheduler {
                     error!("Data compression failed: {}", e);
                 }
             }
+
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
         }
     }
 
@@ -97,6 +128,7 @@ mod tests {
             metrics_retention_days: 90,
             execution_retention_days: 365,
             compression_after_days: 7,
+            delete_batch_hours: 24,
         };
 
         // We can't actually create the pool without a database, but we can test the structure
@@ -15,6 +15,10 @@ CREATE TABLE IF NOT EXISTS orders (
     client_order_id TEXT UNIQUE NOT NULL,
     venue_order_id TEXT,
     time_in_force TEXT,
+    correlation_id TEXT,
+    strategy_id TEXT,
+    session_id TEXT,
+    tags JSONB NOT NULL DEFAULT '{}',
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW()
 );
@@ -41,6 +45,59 @@ CREATE INDEX IF NOT EXISTS idx_orders_client_order_id
 CREATE INDEX IF NOT EXISTS idx_orders_venue_order_id
     ON orders (venue_order_id);
 
+CREATE INDEX IF NOT EXISTS idx_orders_correlation_id
+    ON orders (correlation_id);
+
+CREATE INDEX IF NOT EXISTS idx_orders_strategy_time
+    ON orders (strategy_id, timestamp DESC);
+
+CREATE INDEX IF NOT EXISTS idx_orders_session_time
+    ON orders (session_id, timestamp DESC);
+
+CREATE INDEX IF NOT EXISTS idx_orders_tags
+    ON orders USING GIN (tags);
+
+-- Order status transitions (one row per status change)
+CREATE TABLE IF NOT EXISTS order_status_transitions (
+    order_id UUID NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    reason TEXT
+);
+
+SELECT create_hypertable('order_status_transitions', 'timestamp',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_status_transitions_order_id
+    ON order_status_transitions (order_id, timestamp);
+
+-- Pre-trade risk decisions
+CREATE TABLE IF NOT EXISTS risk_decisions (
+    id UUID NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    order_id UUID,
+    correlation_id TEXT,
+    strategy_id TEXT,
+    session_id TEXT,
+    market TEXT NOT NULL,
+    allowed BOOLEAN NOT NULL,
+    violated_policies TEXT[] NOT NULL DEFAULT '{}'
+);
+
+SELECT create_hypertable('risk_decisions', 'timestamp',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_order_id
+    ON risk_decisions (order_id, timestamp);
+
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_correlation_id
+    ON risk_decisions (correlation_id);
+
 -- Fills table
 CREATE TABLE IF NOT EXISTS fills (
     id UUID PRIMARY KEY,
@@ -55,6 +112,8 @@ CREATE TABLE IF NOT EXISTS fills (
     fee_currency TEXT NOT NULL,
     trade_id TEXT,
     liquidity TEXT,
+    quoted_price DOUBLE PRECISION,
+    price_improvement_bps DOUBLE PRECISION,
     created_at TIMESTAMPTZ DEFAULT NOW()
 );
 
@@ -106,6 +165,80 @@ CREATE INDEX IF NOT EXISTS idx_positions_venue_time
 CREATE INDEX IF NOT EXISTS idx_positions_venue_market
     ON positions (venue, market, timestamp DESC);
 
+-- Risk engine state snapshots (kill-switch, stateful policies, counters)
+CREATE TABLE IF NOT EXISTS risk_state_snapshots (
+    timestamp TIMESTAMPTZ NOT NULL,
+    bot_name TEXT NOT NULL,
+    state JSONB NOT NULL
+);
+
+SELECT create_hypertable('risk_state_snapshots', 'timestamp',
+    chunk_time_interval => INTERVAL '7 days',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_state_snapshots_bot_time
+    ON risk_state_snapshots (bot_name, timestamp DESC);
+
+-- Transaction cost analysis reports
+CREATE TABLE IF NOT EXISTS tca_reports (
+    timestamp TIMESTAMPTZ NOT NULL,
+    bot_name TEXT NOT NULL,
+    period_start TIMESTAMPTZ NOT NULL,
+    period_end TIMESTAMPTZ NOT NULL,
+    report JSONB NOT NULL
+);
+
+SELECT create_hypertable('tca_reports', 'timestamp',
+    chunk_time_interval => INTERVAL '30 days',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tca_reports_bot_time
+    ON tca_reports (bot_name, timestamp DESC);
+
+-- Settlements of positions in resolved markets (strategy_id NULL for the
+-- bot's net position in the execution engine)
+CREATE TABLE IF NOT EXISTS market_settlements (
+    timestamp TIMESTAMPTZ NOT NULL,
+    bot_name TEXT NOT NULL,
+    market TEXT NOT NULL,
+    strategy_id TEXT,
+    payout DOUBLE PRECISION NOT NULL,
+    size DOUBLE PRECISION NOT NULL,
+    avg_entry_price DOUBLE PRECISION,
+    realized_pnl DOUBLE PRECISION
+);
+
+SELECT create_hypertable('market_settlements', 'timestamp',
+    chunk_time_interval => INTERVAL '30 days',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_settlements_bot_time
+    ON market_settlements (bot_name, timestamp DESC);
+
+-- Strategy runs: parameters, code version and config hash of every start;
+-- orders link to their run through the run_id tag
+CREATE TABLE IF NOT EXISTS strategy_runs (
+    run_id TEXT PRIMARY KEY,
+    bot_name TEXT NOT NULL,
+    strategy_id TEXT NOT NULL,
+    strategy_kind TEXT NOT NULL,
+    params JSONB NOT NULL DEFAULT '{}',
+    code_version TEXT NOT NULL,
+    config_hash TEXT NOT NULL,
+    params_version INTEGER NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_runs_strategy_time
+    ON strategy_runs (strategy_id, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_runs_strategy_hash
+    ON strategy_runs (strategy_id, config_hash);
+
 -- Compression policies for execution data
 ALTER TABLE orders SET (
     timescaledb.compress,
@@ -134,6 +267,11 @@ SELECT add_compression_policy('positions', INTERVAL '30 days', if_not_exists =>
 SELECT add_retention_policy('orders', INTERVAL '365 days', if_not_exists => TRUE);
 SELECT add_retention_policy('fills', INTERVAL '365 days', if_not_exists => TRUE);
 SELECT add_retention_policy('positions', INTERVAL '365 days', if_not_exists => TRUE);
+SELECT add_retention_policy('order_status_transitions', INTERVAL '365 days', if_not_exists => TRUE);
+SELECT add_retention_policy('risk_decisions', INTERVAL '365 days', if_not_exists => TRUE);
+SELECT add_retention_policy('risk_state_snapshots', INTERVAL '30 days', if_not_exists => TRUE);
+SELECT add_retention_policy('tca_reports', INTERVAL '365 days', if_not_exists => TRUE);
+SELECT add_retention_policy('market_settlements', INTERVAL '365 days', if_not_exists => TRUE);
 
 -- Continuous aggregate for daily order statistics
 CREATE MATERIALIZED VIEW IF NOT EXISTS orders_daily_stats
@@ -236,6 +374,11 @@ COMMENT ON TABLE fills IS 'Execution fills/trades history';
 COMMENT ON TABLE positions IS 'Position snapshots over time for PnL tracking';
 
 COMMENT ON COLUMN orders.status IS 'Order status: open, partial, filled, cancelled, rejected';
+COMMENT ON COLUMN orders.correlation_id IS 'Links the order to the decision, risk checks and fills it caused';
+COMMENT ON TABLE order_status_transitions IS 'Order status changes for lifecycle reconstruction';
+COMMENT ON TABLE risk_decisions IS 'Pre-trade risk decisions with violated policies';
+COMMENT ON TABLE risk_state_snapshots IS 'Periodic risk engine state for restoring risk posture on restart';
+COMMENT ON TABLE tca_reports IS 'Transaction cost analysis: shortfall, spread capture, markouts, fill ratio';
 COMMENT ON COLUMN fills.liquidity IS 'Liquidity type: maker, taker';
 COMMENT ON COLUMN positions.unrealized_pnl IS 'Unrealized PnL based on current mark price';
 COMMENT ON COLUMN positions.realized_pnl IS 'Cumulative realized PnL from closed positions';
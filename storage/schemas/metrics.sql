@@ -6,9 +6,19 @@ CREATE TABLE IF NOT EXISTS metrics (
     timestamp TIMESTAMPTZ NOT NULL,
     metric_name TEXT NOT NULL,
     value DOUBLE PRECISION NOT NULL,
-    labels JSONB DEFAULT '{}'::jsonb
+    labels JSONB DEFAULT '{}'::jsonb,
+    label_set_id BIGINT
 );
 
+-- Label dictionary (layout.label_dictionary): distinct label sets stored once
+CREATE TABLE IF NOT EXISTS metric_label_sets (
+    id BIGSERIAL PRIMARY KEY,
+    labels JSONB NOT NULL UNIQUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_label_sets_labels
+    ON metric_label_sets USING GIN (labels);
+
 -- Create hypertable partitioned by time (1 day chunks)
 SELECT create_hypertable('metrics', 'timestamp',
     chunk_time_interval => INTERVAL '1 day',
@@ -25,6 +35,10 @@ CREATE INDEX IF NOT EXISTS idx_metrics_labels
 CREATE INDEX IF NOT EXISTS idx_metrics_time
     ON metrics (timestamp DESC);
 
+CREATE INDEX IF NOT EXISTS idx_metrics_label_set_time
+    ON metrics (label_set_id, timestamp DESC)
+    WHERE label_set_id IS NOT NULL;
+
 -- Compression policy (compress data older than 7 days)
 -- This reduces storage size by ~90% for historical data
 ALTER TABLE metrics SET (
@@ -39,6 +53,49 @@ SELECT add_compression_policy('metrics', INTERVAL '7 days', if_not_exists => TRU
 -- Automatically removes old data to manage storage
 SELECT add_retention_policy('metrics', INTERVAL '90 days', if_not_exists => TRUE);
 
+-- Audit log of targeted metric deletions and instance purges
+CREATE TABLE IF NOT EXISTS metric_purge_log (
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    selector JSONB NOT NULL,
+    reason TEXT NOT NULL,
+    rows_deleted BIGINT NOT NULL,
+    batches INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_purge_log_time
+    ON metric_purge_log (timestamp DESC);
+
+-- Recorded market data ticks (replay and shadow testing)
+CREATE TABLE IF NOT EXISTS market_ticks (
+    timestamp TIMESTAMPTZ NOT NULL,
+    market TEXT NOT NULL,
+    bid DOUBLE PRECISION,
+    bid_size DOUBLE PRECISION,
+    ask DOUBLE PRECISION,
+    ask_size DOUBLE PRECISION,
+    last DOUBLE PRECISION,
+    volume_24h DOUBLE PRECISION,
+    sequence BIGINT
+);
+
+SELECT create_hypertable('market_ticks', 'timestamp',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_ticks_market_time
+    ON market_ticks (market, timestamp DESC);
+
+ALTER TABLE market_ticks SET (
+    timescaledb.compress,
+    timescaledb.compress_segmentby = 'market',
+    timescaledb.compress_orderby = 'timestamp DESC'
+);
+
+SELECT add_compression_policy('market_ticks', INTERVAL '7 days', if_not_exists => TRUE);
+SELECT add_retention_policy('market_ticks', INTERVAL '30 days', if_not_exists => TRUE);
+
 -- Continuous aggregate for hourly metrics
 -- Pre-aggregates data for faster queries
 CREATE MATERIALIZED VIEW IF NOT EXISTS metrics_hourly
@@ -118,3 +175,4 @@ COMMENT ON COLUMN metrics.timestamp IS 'Metric timestamp in UTC';
 COMMENT ON COLUMN metrics.metric_name IS 'Metric identifier (e.g., polymarket.rtds.lag_ms)';
 COMMENT ON COLUMN metrics.value IS 'Metric value';
 COMMENT ON COLUMN metrics.labels IS 'JSONB key-value pairs for metric dimensions';
+COMMENT ON COLUMN metrics.label_set_id IS 'Label set in metric_label_sets when the label dictionary is enabled';
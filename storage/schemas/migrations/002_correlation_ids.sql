@@ -0,0 +1,61 @@
+-- Migration: 002_correlation_ids
+-- Description: Correlation, strategy and session IDs on orders; status
+--              transition and risk decision tables for order lifecycles
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS correlation_id TEXT;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS strategy_id TEXT;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS session_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_orders_correlation_id
+    ON orders (correlation_id);
+
+CREATE INDEX IF NOT EXISTS idx_orders_strategy_time
+    ON orders (strategy_id, timestamp DESC);
+
+CREATE INDEX IF NOT EXISTS idx_orders_session_time
+    ON orders (session_id, timestamp DESC);
+
+CREATE TABLE IF NOT EXISTS order_status_transitions (
+    order_id UUID NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL
+);
+
+SELECT create_hypertable('order_status_transitions', 'timestamp',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_status_transitions_order_id
+    ON order_status_transitions (order_id, timestamp);
+
+CREATE TABLE IF NOT EXISTS risk_decisions (
+    id UUID NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    order_id UUID,
+    correlation_id TEXT,
+    strategy_id TEXT,
+    session_id TEXT,
+    market TEXT NOT NULL,
+    allowed BOOLEAN NOT NULL,
+    violated_policies TEXT[] NOT NULL DEFAULT '{}'
+);
+
+SELECT create_hypertable('risk_decisions', 'timestamp',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_order_id
+    ON risk_decisions (order_id, timestamp);
+
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_correlation_id
+    ON risk_decisions (correlation_id);
+
+COMMIT;
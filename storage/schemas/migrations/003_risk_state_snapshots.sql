@@ -0,0 +1,25 @@
+-- Migration: 003_risk_state_snapshots
+-- Description: Risk engine state snapshots for restoring risk posture on restart
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS risk_state_snapshots (
+    timestamp TIMESTAMPTZ NOT NULL,
+    bot_name TEXT NOT NULL,
+    state JSONB NOT NULL
+);
+
+SELECT create_hypertable('risk_state_snapshots', 'timestamp',
+    chunk_time_interval => INTERVAL '7 days',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_state_snapshots_bot_time
+    ON risk_state_snapshots (bot_name, timestamp DESC);
+
+SELECT add_retention_policy('risk_state_snapshots', INTERVAL '30 days', if_not_exists => TRUE);
+
+COMMIT;
@@ -0,0 +1,27 @@
+-- Migration: 004_tca_reports
+-- Description: Periodic transaction cost analysis reports per bot
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS tca_reports (
+    timestamp TIMESTAMPTZ NOT NULL,
+    bot_name TEXT NOT NULL,
+    period_start TIMESTAMPTZ NOT NULL,
+    period_end TIMESTAMPTZ NOT NULL,
+    report JSONB NOT NULL
+);
+
+SELECT create_hypertable('tca_reports', 'timestamp',
+    chunk_time_interval => INTERVAL '30 days',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tca_reports_bot_time
+    ON tca_reports (bot_name, timestamp DESC);
+
+SELECT add_retention_policy('tca_reports', INTERVAL '365 days', if_not_exists => TRUE);
+
+COMMIT;
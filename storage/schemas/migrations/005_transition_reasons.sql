@@ -0,0 +1,11 @@
+-- Migration: 005_transition_reasons
+-- Description: Reason codes on order status transitions for compliance exports
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+ALTER TABLE order_status_transitions ADD COLUMN IF NOT EXISTS reason TEXT;
+
+COMMIT;
@@ -0,0 +1,38 @@
+-- Migration: 006_market_ticks
+-- Description: Recorded market data ticks for replay and shadow testing
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS market_ticks (
+    timestamp TIMESTAMPTZ NOT NULL,
+    market TEXT NOT NULL,
+    bid DOUBLE PRECISION,
+    bid_size DOUBLE PRECISION,
+    ask DOUBLE PRECISION,
+    ask_size DOUBLE PRECISION,
+    last DOUBLE PRECISION,
+    volume_24h DOUBLE PRECISION,
+    sequence BIGINT
+);
+
+SELECT create_hypertable('market_ticks', 'timestamp',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_ticks_market_time
+    ON market_ticks (market, timestamp DESC);
+
+ALTER TABLE market_ticks SET (
+    timescaledb.compress,
+    timescaledb.compress_segmentby = 'market',
+    timescaledb.compress_orderby = 'timestamp DESC'
+);
+
+SELECT add_compression_policy('market_ticks', INTERVAL '7 days', if_not_exists => TRUE);
+SELECT add_retention_policy('market_ticks', INTERVAL '30 days', if_not_exists => TRUE);
+
+COMMIT;
@@ -0,0 +1,15 @@
+-- Migration: 007_order_tags
+-- Description: Free-form order tags (e.g. the signal behind an order) for
+--              PnL attribution
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS tags JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS idx_orders_tags
+    ON orders USING GIN (tags);
+
+COMMIT;
@@ -0,0 +1,30 @@
+-- Migration: 008_strategy_runs
+-- Description: Strategy run history (parameters, code version, config hash)
+--              for experiment comparison; orders link to runs through their
+--              run_id tag
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS strategy_runs (
+    run_id TEXT PRIMARY KEY,
+    bot_name TEXT NOT NULL,
+    strategy_id TEXT NOT NULL,
+    strategy_kind TEXT NOT NULL,
+    params JSONB NOT NULL DEFAULT '{}',
+    code_version TEXT NOT NULL,
+    config_hash TEXT NOT NULL,
+    params_version INTEGER NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_runs_strategy_time
+    ON strategy_runs (strategy_id, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_strategy_runs_strategy_hash
+    ON strategy_runs (strategy_id, config_hash);
+
+COMMIT;
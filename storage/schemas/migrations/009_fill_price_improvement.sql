@@ -0,0 +1,15 @@
+-- Migration: 009_fill_price_improvement
+-- Description: Quoted (limit) price and price improvement per fill, for
+--              execution quality by venue, order type and time of day.
+--              Older fills are left NULL; TCA falls back to the order's
+--              limit price for them
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+ALTER TABLE fills ADD COLUMN IF NOT EXISTS quoted_price DOUBLE PRECISION;
+ALTER TABLE fills ADD COLUMN IF NOT EXISTS price_improvement_bps DOUBLE PRECISION;
+
+COMMIT;
@@ -0,0 +1,28 @@
+-- Migration: 010_metric_label_dictionary
+-- Description: Optional label dictionary for the metrics hypertable. Each
+--              distinct label set is stored once in metric_label_sets and
+--              metric rows reference it by id (layout.label_dictionary).
+--              Existing rows keep their inline labels; chunk interval and
+--              partial label indexes are applied from config by
+--              StorageEngine::apply_metrics_layout
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS metric_label_sets (
+    id BIGSERIAL PRIMARY KEY,
+    labels JSONB NOT NULL UNIQUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_label_sets_labels
+    ON metric_label_sets USING GIN (labels);
+
+ALTER TABLE metrics ADD COLUMN IF NOT EXISTS label_set_id BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_metrics_label_set_time
+    ON metrics (label_set_id, timestamp DESC)
+    WHERE label_set_id IS NOT NULL;
+
+COMMIT;
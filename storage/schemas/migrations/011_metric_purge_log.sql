@@ -0,0 +1,23 @@
+-- Migration: 011_metric_purge_log
+-- Description: Audit log of targeted metric deletions and instance purges
+--              (StorageEngine::delete_metrics / purge_instance). Dry runs
+--              are not logged
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS metric_purge_log (
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    selector JSONB NOT NULL,
+    reason TEXT NOT NULL,
+    rows_deleted BIGINT NOT NULL,
+    batches INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_purge_log_time
+    ON metric_purge_log (timestamp DESC);
+
+COMMIT;
@@ -0,0 +1,31 @@
+-- Migration: 012_market_settlements
+-- Description: Settlements of positions in resolved markets, recorded when
+--              the market data feed reports a resolution
+-- Created: 2026-10-16
+
+-- Idempotent and safe to run multiple times
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS market_settlements (
+    timestamp TIMESTAMPTZ NOT NULL,
+    bot_name TEXT NOT NULL,
+    market TEXT NOT NULL,
+    strategy_id TEXT,
+    payout DOUBLE PRECISION NOT NULL,
+    size DOUBLE PRECISION NOT NULL,
+    avg_entry_price DOUBLE PRECISION,
+    realized_pnl DOUBLE PRECISION
+);
+
+SELECT create_hypertable('market_settlements', 'timestamp',
+    chunk_time_interval => INTERVAL '30 days',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_settlements_bot_time
+    ON market_settlements (bot_name, timestamp DESC);
+
+SELECT add_retention_policy('market_settlements', INTERVAL '365 days', if_not_exists => TRUE);
+
+COMMIT;
@@ -0,0 +1,362 @@
+//! Compliance audit export
+//!
+//! Flattens the execution journal (status transitions, pre-trade risk
+//! decisions and fills) into one [`ComplianceRecord`] per order event, in
+//! chronological order, and writes it as CSV or JSON Lines. Every record
+//! carries the acting strategy and session and a reason code: violated
+//! policy names for risk rejections, the recorded reason for cancels and
+//! rejects.
+//!
+//! Records are built from data already loaded, so the export format can be
+//! tested without a database; [`ExecutionStore::export_compliance`](crate::ExecutionStore::export_compliance)
+//! loads a date range and writes it.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::types::{Fill, Order, OrderStatus, OrderStatusTransition, RiskDecisionRecord};
+
+/// Output format of a compliance export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Kind of audited order event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceEvent {
+    /// Pre-trade risk check passed
+    RiskApproved,
+    /// Pre-trade risk check rejected the order
+    RiskRejected,
+    /// Order accepted and working
+    Placed,
+    /// Order status changed (partial fill, fill)
+    StatusChange,
+    /// Order cancelled
+    Cancelled,
+    /// Order rejected by the venue or engine
+    Rejected,
+    /// Execution against the order
+    Fill,
+}
+
+impl ComplianceEvent {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ComplianceEvent::RiskApproved => "risk_approved",
+            ComplianceEvent::RiskRejected => "risk_rejected",
+            ComplianceEvent::Placed => "placed",
+            ComplianceEvent::StatusChange => "status_change",
+            ComplianceEvent::Cancelled => "cancelled",
+            ComplianceEvent::Rejected => "rejected",
+            ComplianceEvent::Fill => "fill",
+        }
+    }
+
+    /// Tie-break for events with equal timestamps, matching
+    /// [`OrderLifecycle`](crate::OrderLifecycle) ordering
+    fn rank(&self) -> u8 {
+        match self {
+            ComplianceEvent::RiskApproved | ComplianceEvent::RiskRejected => 0,
+            ComplianceEvent::Fill => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// One audited order event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComplianceRecord {
+    pub timestamp: DateTime<Utc>,
+    pub event: ComplianceEvent,
+    pub order_id: Option<Uuid>,
+    pub client_order_id: Option<String>,
+    pub venue: Option<String>,
+    pub market: String,
+    pub side: Option<String>,
+    pub order_type: Option<String>,
+    /// Limit price for order events, execution price for fills
+    pub price: Option<f64>,
+    /// Order size for order events, executed size for fills
+    pub size: Option<f64>,
+    /// Order status after the event
+    pub status: Option<String>,
+    /// Acting strategy
+    pub strategy_id: Option<String>,
+    pub session_id: Option<String>,
+    pub correlation_id: Option<String>,
+    /// Machine-readable reason (`;`-separated policy names for risk rejections)
+    pub reason_code: Option<String>,
+    /// Human-readable detail
+    pub detail: Option<String>,
+}
+
+const CSV_HEADER: [&str; 16] = [
+    "timestamp",
+    "event",
+    "order_id",
+    "client_order_id",
+    "venue",
+    "market",
+    "side",
+    "order_type",
+    "price",
+    "size",
+    "status",
+    "strategy_id",
+    "session_id",
+    "correlation_id",
+    "reason_code",
+    "detail",
+];
+
+impl ComplianceRecord {
+    fn for_order(order: Option<&Order>, timestamp: DateTime<Utc>, event: ComplianceEvent, market: &str) -> Self {
+        Self {
+            timestamp,
+            event,
+            order_id: order.map(|o| o.id),
+            client_order_id: order.map(|o| o.client_order_id.clone()),
+            venue: order.map(|o| o.venue.clone()),
+            market: market.to_string(),
+            side: order.map(|o| o.side.to_string()),
+            order_type: order.map(|o| o.order_type.to_string()),
+            price: order.and_then(|o| o.price),
+            size: order.map(|o| o.size),
+            status: None,
+            strategy_id: order.and_then(|o| o.strategy_id.clone()),
+            session_id: order.and_then(|o| o.session_id.clone()),
+            correlation_id: order.and_then(|o| o.correlation_id.clone()),
+            reason_code: None,
+            detail: None,
+        }
+    }
+
+    fn csv_fields(&self) -> [String; 16] {
+        let opt = |value: &Option<String>| value.clone().unwrap_or_default();
+        let num = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        [
+            self.timestamp.to_rfc3339(),
+            self.event.as_str().to_string(),
+            self.order_id.map(|id| id.to_string()).unwrap_or_default(),
+            opt(&self.client_order_id),
+            opt(&self.venue),
+            self.market.clone(),
+            opt(&self.side),
+            opt(&self.order_type),
+            num(self.price),
+            num(self.size),
+            opt(&self.status),
+            opt(&self.strategy_id),
+            opt(&self.session_id),
+            opt(&self.correlation_id),
+            opt(&self.reason_code),
+            opt(&self.detail),
+        ]
+    }
+}
+
+/// Build chronologically ordered records from journal entries
+///
+/// `orders` supplies order attributes for transitions and fills; entries
+/// whose order is missing are still exported with the fields they carry.
+pub fn build_records(
+    orders: &[Order],
+    decisions: &[RiskDecisionRecord],
+    transitions: &[OrderStatusTransition],
+    fills: &[Fill],
+) -> Vec<ComplianceRecord> {
+    let by_id: HashMap<Uuid, &Order> = orders.iter().map(|o| (o.id, o)).collect();
+    let mut records = Vec::with_capacity(decisions.len() + transitions.len() + fills.len());
+
+    for decision in decisions {
+        let order = decision.order_id.and_then(|id| by_id.get(&id).copied());
+        let event = if decision.allowed {
+            ComplianceEvent::RiskApproved
+        } else {
+            ComplianceEvent::RiskRejected
+        };
+        let mut record = ComplianceRecord::for_order(order, decision.timestamp, event, &decision.market);
+        record.order_id = decision.order_id;
+        record.strategy_id = decision.strategy_id.clone().or(record.strategy_id);
+        record.session_id = decision.session_id.clone().or(record.session_id);
+        record.correlation_id = decision.correlation_id.clone().or(record.correlation_id);
+        if !decision.violated_policies.is_empty() {
+            let policies: Vec<&str> = decision
+                .violated_policies
+                .iter()
+                .map(|v| v.split(':').next().unwrap_or(v).trim())
+                .collect();
+            record.reason_code = Some(policies.join(";"));
+            record.detail = Some(decision.violated_policies.join("; "));
+        }
+        records.push(record);
+    }
+
+    for transition in transitions {
+        let order = by_id.get(&transition.order_id).copied();
+        let event = match (transition.from_status, transition.to_status) {
+            (_, OrderStatus::Cancelled) => ComplianceEvent::Cancelled,
+            (_, OrderStatus::Rejected) => ComplianceEvent::Rejected,
+            (None, _) => ComplianceEvent::Placed,
+            _ => ComplianceEvent::StatusChange,
+        };
+        let market = order.map(|o| o.market.as_str()).unwrap_or_default();
+        let mut record = ComplianceRecord::for_order(order, transition.timestamp, event, market);
+        record.order_id = Some(transition.order_id);
+        record.status = Some(transition.to_status.to_string());
+        record.reason_code = transition.reason.clone();
+        if let Some(from) = transition.from_status {
+            record.detail = Some(format!("{} -> {}", from, transition.to_status));
+        }
+        records.push(record);
+    }
+
+    for fill in fills {
+        let order = by_id.get(&fill.order_id).copied();
+        let mut record = ComplianceRecord::for_order(order, fill.timestamp, ComplianceEvent::Fill, &fill.market);
+        record.order_id = Some(fill.order_id);
+        record.venue = Some(fill.venue.clone());
+        record.side = Some(fill.side.to_string());
+        record.price = Some(fill.price);
+        record.size = Some(fill.size);
+        record.detail = Some(format!(
+            "fee {} {}{}{}",
+            fill.fee,
+            fill.fee_currency,
+            fill.liquidity.as_deref().map(|l| format!(", {}", l)).unwrap_or_default(),
+            fill.trade_id.as_deref().map(|t| format!(", trade {}", t)).unwrap_or_default(),
+        ));
+        records.push(record);
+    }
+
+    records.sort_by_key(|r| (r.timestamp, r.event.rank()));
+    records
+}
+
+/// Write records in the given format; returns the number of records written
+pub fn write_records<W: Write>(records: &[ComplianceRecord], format: ComplianceFormat, writer: &mut W) -> Result<usize> {
+    match format {
+        ComplianceFormat::Csv => {
+            writeln!(writer, "{}", CSV_HEADER.join(","))?;
+            for record in records {
+                let fields: Vec<String> = record.csv_fields().iter().map(|f| csv_escape(f)).collect();
+                writeln!(writer, "{}", fields.join(","))?;
+            }
+        }
+        ComplianceFormat::Jsonl => {
+            for record in records {
+                serde_json::to_writer(&mut *writer, record)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(records.len())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrderType, Side};
+    use chrono::Duration;
+
+    #[test]
+    fn test_compliance_records() {
+        let t0 = Utc::now();
+        let order = Order::new("polymarket", "0x123abc", Side::Buy, OrderType::Limit, 100.0)
+            .with_price(0.52)
+            .with_strategy_id("mm_1");
+
+        let mut approved = RiskDecisionRecord::new("0x123abc", true, Vec::new()).for_order(&order);
+        approved.timestamp = t0;
+        let mut rejected = RiskDecisionRecord::new(
+            "0x123abc",
+            false,
+            vec![
+                "PositionLimit: position 1200 exceeds 1000".to_string(),
+                "KillSwitch: active".to_string(),
+            ],
+        );
+        rejected.strategy_id = Some("mm_1".to_string());
+        rejected.timestamp = t0 + Duration::seconds(1);
+
+        let transition = |from: Option<OrderStatus>, to: OrderStatus, secs: i64, reason: Option<&str>| {
+            OrderStatusTransition {
+                order_id: order.id,
+                timestamp: t0 + Duration::seconds(secs),
+                from_status: from,
+                to_status: to,
+                reason: reason.map(str::to_string),
+            }
+        };
+        let mut fill = Fill::new(order.id, "polymarket", "0x123abc", Side::Buy, 0.52, 40.0, 0.1, "USDC");
+        fill.timestamp = t0 + Duration::seconds(2);
+        fill.liquidity = Some("maker".to_string());
+
+        let records = build_records(
+            &[order.clone()],
+            &[rejected, approved],
+            &[
+                transition(Some(OrderStatus::Partial), OrderStatus::Cancelled, 3, Some("universe_exit")),
+                transition(None, OrderStatus::Open, 0, None),
+                transition(Some(OrderStatus::Open), OrderStatus::Partial, 2, None),
+            ],
+            &[fill],
+        );
+
+        let events: Vec<ComplianceEvent> = records.iter().map(|r| r.event).collect();
+        assert_eq!(
+            events,
+            vec![
+                ComplianceEvent::RiskApproved,
+                ComplianceEvent::Placed,
+                ComplianceEvent::RiskRejected,
+                ComplianceEvent::Fill,
+                ComplianceEvent::StatusChange,
+                ComplianceEvent::Cancelled,
+            ]
+        );
+        assert_eq!(records[2].reason_code.as_deref(), Some("PositionLimit;KillSwitch"));
+        assert_eq!(records[2].order_id, None);
+        assert_eq!(records[2].strategy_id.as_deref(), Some("mm_1"));
+        assert_eq!(records[3].size, Some(40.0));
+        assert_eq!(records[5].reason_code.as_deref(), Some("universe_exit"));
+        assert_eq!(records[5].strategy_id.as_deref(), Some("mm_1"));
+
+        let mut csv = Vec::new();
+        assert_eq!(write_records(&records, ComplianceFormat::Csv, &mut csv).unwrap(), 6);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("timestamp,event,order_id"));
+        assert!(lines[3].contains(",risk_rejected,"));
+        // Fields containing commas are quoted
+        assert!(lines[4].ends_with(",\"fee 0.1 USDC, maker\""));
+
+        let mut jsonl = Vec::new();
+        write_records(&records, ComplianceFormat::Jsonl, &mut jsonl).unwrap();
+        let first: ComplianceRecord = serde_json::from_str(String::from_utf8(jsonl).unwrap().lines().next().unwrap()).unwrap();
+        assert_eq!(first, records[0]);
+    }
+}
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 /// Storage engine configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StorageConfig {
     /// Database connection configuration
     pub database: DatabaseConfig,
@@ -15,10 +16,15 @@ pub struct StorageConfig {
 
     /// Query configuration
     pub query: QueryConfig,
+
+    /// Metrics table layout (chunking, label indexes, label dictionary)
+    #[serde(default)]
+    pub layout: MetricsLayoutConfig,
 }
 
 /// Database connection configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DatabaseConfig {
     /// Database host
     pub host: String,
@@ -65,6 +71,7 @@ impl DatabaseConfig {
 
 /// Ingestion configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct IngestionConfig {
     /// Batch size for bulk inserts
     #[serde(default = "default_batch_size")]
@@ -88,6 +95,7 @@ impl IngestionConfig {
 
 /// Data retention configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RetentionConfig {
     /// Metrics retention in days
     #[serde(default = "default_metrics_retention_days")]
@@ -100,10 +108,15 @@ pub struct RetentionConfig {
     /// Compression threshold in days
     #[serde(default = "default_compression_after_days")]
     pub compression_after_days: u32,
+
+    /// Time window per batch for targeted metric deletions, in hours
+    #[serde(default = "default_delete_batch_hours")]
+    pub delete_batch_hours: u32,
 }
 
 /// Query configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct QueryConfig {
     /// Maximum number of results to return
     #[serde(default = "default_max_results")]
@@ -125,6 +138,99 @@ impl QueryConfig {
     }
 }
 
+/// Metrics table layout configuration
+///
+/// Tunes the `metrics` hypertable for write throughput as label cardinality
+/// grows. Applied by [`StorageEngine::apply_metrics_layout`](crate::StorageEngine::apply_metrics_layout).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsLayoutConfig {
+    /// Hypertable chunk interval in hours (only affects chunks created afterwards)
+    #[serde(default = "default_chunk_interval_hours")]
+    pub chunk_interval_hours: u32,
+
+    /// Label keys that get a partial index covering only rows carrying the key
+    #[serde(default)]
+    pub indexed_labels: Vec<String>,
+
+    /// Store each distinct label set once in `metric_label_sets` and reference it by id
+    #[serde(default)]
+    pub label_dictionary: bool,
+}
+
+impl Default for MetricsLayoutConfig {
+    fn default() -> Self {
+        Self {
+            chunk_interval_hours: default_chunk_interval_hours(),
+            indexed_labels: Vec::new(),
+            label_dictionary: false,
+        }
+    }
+}
+
+impl MetricsLayoutConfig {
+    /// Check the chunk interval and that label keys are safe to embed in DDL
+    pub fn validate(&self) -> Result<(), crate::error::StorageError> {
+        if self.chunk_interval_hours == 0 {
+            return Err(crate::error::StorageError::ConfigError(
+                "layout.chunk_interval_hours must be positive".to_string(),
+            ));
+        }
+
+        for key in &self.indexed_labels {
+            let valid = !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-');
+            if !valid {
+                return Err(crate::error::StorageError::ConfigError(format!(
+                    "layout.indexed_labels: invalid label key '{}'",
+                    key
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Table holding label sets: `metric_label_sets` with the dictionary, `metrics` otherwise
+    pub fn label_table(&self) -> &'static str {
+        if self.label_dictionary {
+            "metric_label_sets"
+        } else {
+            "metrics"
+        }
+    }
+
+    /// DDL applying this layout; idempotent
+    ///
+    /// Label indexes go on whichever table holds the label JSON, so with the
+    /// dictionary enabled they index the (much smaller) set of distinct label
+    /// sets rather than every metric row.
+    pub fn ddl(&self) -> Result<String, crate::error::StorageError> {
+        self.validate()?;
+
+        let table = self.label_table();
+        let mut sql = format!(
+            "SELECT set_chunk_time_interval('metrics', INTERVAL '{} hours');\n",
+            self.chunk_interval_hours
+        );
+
+        for key in &self.indexed_labels {
+            let index = format!("idx_{}_label_{}", table, key.replace(['.', '-'], "_"));
+            let columns = if self.label_dictionary {
+                format!("(labels->>'{}')", key)
+            } else {
+                format!("(labels->>'{}'), metric_name, timestamp DESC", key)
+            };
+            sql.push_str(&format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {} ({}) WHERE labels ? '{}';\n",
+                index, table, columns, key
+            ));
+        }
+
+        Ok(sql)
+    }
+}
+
 // Default value functions
 fn default_max_connections() -> usize {
     10
@@ -158,6 +264,10 @@ fn default_compression_after_days() -> u32 {
     7
 }
 
+fn default_delete_batch_hours() -> u32 {
+    24
+}
+
 fn default_max_results() -> usize {
     10000
 }
@@ -170,6 +280,10 @@ fn default_enable_cache() -> bool {
     true
 }
 
+fn default_chunk_interval_hours() -> u32 {
+    24
+}
+
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
@@ -192,12 +306,14 @@ impl Default for StorageConfig {
                 metrics_retention_days: default_metrics_retention_days(),
                 execution_retention_days: default_execution_retention_days(),
                 compression_after_days: default_compression_after_days(),
+                delete_batch_hours: default_delete_batch_hours(),
             },
             query: QueryConfig {
                 max_results: default_max_results(),
                 cache_ttl_sec: default_cache_ttl_sec(),
                 enable_cache: default_enable_cache(),
             },
+            layout: MetricsLayoutConfig::default(),
         }
     }
 }
@@ -270,4 +386,44 @@ mod tests {
         let parsed: StorageConfig = serde_yaml::from_str(&yaml).unwrap();
         assert_eq!(parsed.database.host, config.database.host);
     }
+
+    #[test]
+    fn test_yaml_rejects_unknown_fields() {
+        let yaml = serde_yaml::to_string(&StorageConfig::default())
+            .unwrap()
+            .replace("batch_size", "batch_sise");
+
+        let err = StorageConfig::from_yaml(&yaml).unwrap_err();
+        assert!(err.to_string().contains("batch_sise"));
+    }
+
+    #[test]
+    fn test_metrics_layout_ddl() {
+        let mut layout = MetricsLayoutConfig {
+            chunk_interval_hours: 6,
+            indexed_labels: vec!["market".to_string(), "order.venue".to_string()],
+            label_dictionary: false,
+        };
+
+        let ddl = layout.ddl().unwrap();
+        assert!(ddl.contains("set_chunk_time_interval('metrics', INTERVAL '6 hours')"));
+        assert!(ddl.contains(
+            "idx_metrics_label_order_venue ON metrics ((labels->>'order.venue'), metric_name, timestamp DESC) \
+             WHERE labels ? 'order.venue'"
+        ));
+
+        layout.label_dictionary = true;
+        let ddl = layout.ddl().unwrap();
+        assert!(ddl.contains("idx_metric_label_sets_label_market ON metric_label_sets ((labels->>'market'))"));
+
+        layout.indexed_labels.push("x'; DROP TABLE metrics; --".to_string());
+        assert!(layout.ddl().is_err());
+
+        // Older configs without a layout section still load
+        let yaml = serde_yaml::to_string(&StorageConfig::default()).unwrap();
+        let trimmed = &yaml[..yaml.find("layout:").unwrap()];
+        let parsed = StorageConfig::from_yaml(trimmed).unwrap();
+        assert_eq!(parsed.layout.chunk_interval_hours, 24);
+        assert!(!parsed.layout.label_dictionary);
+    }
 }
@@ -1,9 +1,13 @@
 use crate::config::StorageConfig;
 use crate::error::{Result, StorageError};
+use crate::tca::TcaReport;
 use crate::timescale::ConnectionPool;
-use crate::types::{AggregatedMetric, Aggregation, MetricPoint};
+use crate::types::{
+    AggregatedMetric, Aggregation, MarketSettlementRecord, MarketTickRecord, MetricDeletionReport, MetricPoint,
+    MetricSelector,
+};
 use chrono::{DateTime, Duration, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
@@ -13,6 +17,8 @@ pub struct StorageEngine {
     pool: Arc<ConnectionPool>,
     config: StorageConfig,
     buffer: Arc<RwLock<Vec<MetricPoint>>>,
+    /// Label set id cache for the label dictionary, keyed by canonical label JSON
+    label_sets: Arc<RwLock<HashMap<String, i64>>>,
 }
 
 impl StorageEngine {
@@ -20,6 +26,8 @@ impl StorageEngine {
     pub async fn new(config: StorageConfig) -> Result<Self> {
         info!("Initializing StorageEngine");
 
+        config.layout.validate()?;
+
         let pool = ConnectionPool::new(&config.database).await?;
         let pool = Arc::new(pool);
 
@@ -31,6 +39,7 @@ impl StorageEngine {
             pool,
             config,
             buffer,
+            label_sets: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
@@ -39,12 +48,95 @@ impl StorageEngine {
         self.pool.init_schemas(metrics_sql, execution_sql).await
     }
 
+    /// Apply the configured metrics table layout
+    ///
+    /// Sets the hypertable chunk interval and creates the partial label
+    /// indexes from `layout`. Idempotent; run after the schemas (and
+    /// migration 010 when using the label dictionary).
+    pub async fn apply_metrics_layout(&self) -> Result<()> {
+        let layout = &self.config.layout;
+        info!(
+            "Applying metrics layout: {}h chunks, {} label indexes, label dictionary {}",
+            layout.chunk_interval_hours,
+            layout.indexed_labels.len(),
+            if layout.label_dictionary { "on" } else { "off" }
+        );
+
+        self.pool.execute_schema(&layout.ddl()?).await
+    }
+
+    /// Resolve label set ids through the dictionary, inserting unseen sets
+    async fn label_set_ids(&self, client: &deadpool_postgres::Client, metrics: &[MetricPoint]) -> Result<Vec<i64>> {
+        let mut ids = Vec::with_capacity(metrics.len());
+
+        for metric in metrics {
+            let canonical: BTreeMap<&String, &String> = metric.labels.iter().collect();
+            let key = serde_json::to_string(&canonical)?;
+
+            if let Some(id) = self.label_sets.read().await.get(&key) {
+                ids.push(*id);
+                continue;
+            }
+
+            let labels_json = serde_json::to_value(&canonical)?;
+            let id: i64 = client
+                .query_one(
+                    r#"
+                    INSERT INTO metric_label_sets (labels) VALUES ($1)
+                    ON CONFLICT (labels) DO UPDATE SET labels = EXCLUDED.labels
+                    RETURNING id
+                    "#,
+                    &[&labels_json],
+                )
+                .await?
+                .get(0);
+
+            self.label_sets.write().await.insert(key, id);
+            ids.push(id);
+        }
+
+        Ok(ids)
+    }
+
+    /// FROM clause and label expression for metric reads
+    ///
+    /// With the label dictionary, rows written before it was enabled keep
+    /// their inline labels, so both sources are consulted.
+    fn metrics_source(&self) -> (&'static str, &'static str) {
+        if self.config.layout.label_dictionary {
+            (
+                "metrics m LEFT JOIN metric_label_sets ls ON ls.id = m.label_set_id",
+                "COALESCE(ls.labels, m.labels)",
+            )
+        } else {
+            ("metrics m", "m.labels")
+        }
+    }
+
     /// Insert single metric point
     pub async fn insert_metric(&mut self, metric: MetricPoint) -> Result<()> {
         debug!("Inserting metric: {}", metric.metric_name);
 
         let client = self.pool.get().await?;
 
+        if self.config.layout.label_dictionary {
+            let label_set_id = self.label_set_ids(&client, std::slice::from_ref(&metric)).await?[0];
+
+            client
+                .execute(
+                    "INSERT INTO metrics (timestamp, metric_name, value, label_set_id) VALUES ($1, $2, $3, $4)",
+                    &[
+                        &metric.timestamp,
+                        &metric.metric_name,
+                        &metric.value,
+                        &label_set_id,
+                    ],
+                )
+                .await?;
+
+            return Ok(());
+        }
+
         let labels_json = serde_json::to_value(&metric.labels)?;
 
         client
@@ -94,10 +186,20 @@ impl StorageEngine {
         // This would require more complex binary encoding, so let's use multi-row INSERT instead
         drop(writer);
 
+        // Label set ids replace the labels column when the dictionary is enabled
+        let label_dictionary = self.config.layout.label_dictionary;
+        let label_set_ids = if label_dictionary {
+            self.label_set_ids(&client, &metrics).await?
+        } else {
+            Vec::new()
+        };
+
         // Build multi-row INSERT statement
-        let mut query = String::from(
+        let mut query = String::from(if label_dictionary {
+            "INSERT INTO metrics (timestamp, metric_name, value, label_set_id) VALUES "
+        } else {
             "INSERT INTO metrics (timestamp, metric_name, value, labels) VALUES "
-        );
+        });
 
         let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::new();
         let mut param_idx = 1;
@@ -128,7 +230,9 @@ impl StorageEngine {
             timestamps.push(metric.timestamp);
             names.push(metric.metric_name.clone());
             values.push(metric.value);
-            labels_json.push(serde_json::to_value(&metric.labels)?);
+            if !label_dictionary {
+                labels_json.push(serde_json::to_value(&metric.labels)?);
+            }
         }
 
         // Build params vector
@@ -136,7 +240,11 @@ impl StorageEngine {
             params.push(&timestamps[i]);
             params.push(&names[i]);
             params.push(&values[i]);
-            params.push(&labels_json[i]);
+            if label_dictionary {
+                params.push(&label_set_ids[i]);
+            } else {
+                params.push(&labels_json[i]);
+            }
         }
 
         client.execute(&query, &params).await?;
@@ -194,14 +302,16 @@ impl StorageEngine {
         let client = self.pool.get().await?;
 
         // Build query manually with proper type handling
-        let mut query = String::from(
-            "SELECT timestamp, metric_name, value, labels FROM metrics WHERE timestamp >= $1 AND timestamp <= $2 AND metric_name = $3"
+        let (source, labels_expr) = self.metrics_source();
+        let mut query = format!(
+            "SELECT timestamp, metric_name, value, {} FROM {} WHERE timestamp >= $1 AND timestamp <= $2 AND metric_name = $3",
+            labels_expr, source
         );
 
         let mut param_idx = 4;
         let label_conditions = if let Some(ref labels) = labels {
             labels.keys().map(|key| {
-                    let cond = format!(" AND labels->>'{}' = ${}", key, param_idx);
+                    let cond = format!(" AND {}->>'{}' = ${}", labels_expr, key, param_idx);
                     param_idx += 1;
                     cond
                 })
@@ -269,11 +379,12 @@ impl StorageEngine {
         // Convert bucket_size to PostgreSQL interval
         let bucket_interval = format!("{} seconds", bucket_size.num_seconds());
 
-        let query = r#"
+        let (source, labels_expr) = self.metrics_source();
+        let query = format!(r#"
             SELECT
                 time_bucket($1, timestamp) AS bucket,
                 metric_name,
-                labels,
+                {labels_expr} AS labels,
                 AVG(value) AS avg_value,
                 MIN(value) AS min_value,
                 MAX(value) AS max_value,
@@ -282,12 +393,12 @@ impl StorageEngine {
                 PERCENTILE_CONT(0.99) WITHIN GROUP (ORDER BY value) AS p99_value,
                 STDDEV(value) AS stddev_value,
                 COUNT(*) AS count
-            FROM metrics
+            FROM {source}
             WHERE timestamp >= $2 AND timestamp <= $3 AND metric_name = $4
-            GROUP BY bucket, metric_name, labels
+            GROUP BY bucket, metric_name, {labels_expr}
             ORDER BY bucket DESC
             LIMIT $5
-            "#.to_string();
+            "#);
 
         let rows = client
             .query(
@@ -330,12 +441,410 @@ impl StorageEngine {
         Ok(aggregated)
     }
 
+    /// Delete metrics matching a selector
+    ///
+    /// Deletes in `retention.delete_batch_hours` time windows, one statement
+    /// per window, so no single transaction holds locks across the whole
+    /// range. With `dry_run` only the matching rows are counted. Actual
+    /// deletions are recorded in `metric_purge_log` with `reason`.
+    ///
+    /// Rejects a selector with no criteria; use retention for bulk expiry.
+    pub async fn delete_metrics(
+        &mut self,
+        selector: &MetricSelector,
+        dry_run: bool,
+        reason: &str,
+    ) -> Result<MetricDeletionReport> {
+        if selector.is_unbounded() {
+            return Err(StorageError::InvalidParameters(
+                "Metric selector must constrain name, labels or time range".to_string(),
+            ));
+        }
+
+        let start_time = Utc::now();
+        let label_dictionary = self.config.layout.label_dictionary;
+        let condition = selector_condition(label_dictionary);
+        let labels_json = serde_json::to_value(&selector.labels)?;
+        let selector_params: [&(dyn tokio_postgres::types::ToSql + Sync); 4] =
+            [&selector.metric_name, &labels_json, &selector.start, &selector.end];
+
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                &format!("SELECT COUNT(*), MIN(timestamp), MAX(timestamp) FROM metrics m WHERE {}", condition),
+                &selector_params,
+            )
+            .await?;
+        let matched: i64 = row.get(0);
+        let first: Option<DateTime<Utc>> = row.get(1);
+        let last: Option<DateTime<Utc>> = row.get(2);
+
+        let mut deleted = 0i64;
+        let mut batches = 0usize;
+
+        if let (false, Some(first), Some(last)) = (dry_run, first, last) {
+            let window = Duration::hours(self.config.retention.delete_batch_hours.max(1) as i64);
+            let query = format!(
+                "DELETE FROM metrics m WHERE {} AND m.timestamp >= $5 AND m.timestamp < $6",
+                condition
+            );
+
+            let mut window_start = first;
+            while window_start <= last {
+                let window_end = window_start + window;
+                let mut params = selector_params.to_vec();
+                params.push(&window_start);
+                params.push(&window_end);
+
+                deleted += client.execute(&query, &params).await? as i64;
+                batches += 1;
+                window_start = window_end;
+            }
+
+            if label_dictionary && !selector.labels.is_empty() {
+                // Label sets that only described purged rows would still hold the label values
+                client
+                    .execute(
+                        r#"
+                        DELETE FROM metric_label_sets ls
+                        WHERE ls.labels @> $1
+                          AND NOT EXISTS (SELECT 1 FROM metrics m WHERE m.label_set_id = ls.id)
+                        "#,
+                        &[&labels_json],
+                    )
+                    .await?;
+                self.label_sets.write().await.clear();
+            }
+        }
+
+        let executed_at = Utc::now();
+        let report = MetricDeletionReport {
+            selector: selector.clone(),
+            matched,
+            deleted,
+            batches,
+            dry_run,
+            executed_at,
+            duration: executed_at - start_time,
+        };
+
+        if dry_run {
+            info!("Metric deletion dry run: {} rows match {:?}", matched, selector);
+            return Ok(report);
+        }
+
+        client
+            .execute(
+                r#"
+                INSERT INTO metric_purge_log (timestamp, selector, reason, rows_deleted, batches)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                &[
+                    &executed_at,
+                    &serde_json::to_value(selector)?,
+                    &reason,
+                    &deleted,
+                    &(batches as i32),
+                ],
+            )
+            .await?;
+
+        warn!(
+            "Deleted {} metric rows in {} batches ({:?}): {}",
+            deleted, batches, selector, reason
+        );
+
+        Ok(report)
+    }
+
+    /// Purge every metric recorded by a bot instance
+    ///
+    /// Instance-scoped data is the metrics botkit labels with `instance`;
+    /// execution history and bot-level snapshots are keyed by bot name and
+    /// are left to retention.
+    pub async fn purge_instance(
+        &mut self,
+        instance_id: &str,
+        dry_run: bool,
+        reason: &str,
+    ) -> Result<MetricDeletionReport> {
+        let selector = MetricSelector::new().with_label("instance", instance_id);
+        self.delete_metrics(&selector, dry_run, reason).await
+    }
+
+    /// Store a risk engine state snapshot for a bot
+    ///
+    /// The snapshot is stored as opaque JSON so storage does not depend on
+    /// the risk crate's types.
+    pub async fn store_risk_snapshot(&mut self, bot_name: &str, state: serde_json::Value) -> Result<()> {
+        debug!("Storing risk state snapshot for {}", bot_name);
+
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "INSERT INTO risk_state_snapshots (timestamp, bot_name, state) VALUES (NOW(), $1, $2)",
+                &[&bot_name, &state],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Load the most recent risk engine state snapshot for a bot
+    pub async fn load_latest_risk_snapshot(
+        &self,
+        bot_name: &str,
+    ) -> Result<Option<(DateTime<Utc>, serde_json::Value)>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                r#"
+                SELECT timestamp, state
+                FROM risk_state_snapshots
+                WHERE bot_name = $1
+                ORDER BY timestamp DESC
+                LIMIT 1
+                "#,
+                &[&bot_name],
+            )
+            .await?;
+
+        Ok(row.map(|row| (row.get(0), row.get(1))))
+    }
+
+    /// Store a TCA report for a bot
+    pub async fn store_tca_report(&mut self, bot_name: &str, report: &TcaReport) -> Result<()> {
+        debug!("Storing TCA report for {} ({} groups)", bot_name, report.groups.len());
+
+        let client = self.pool.get().await?;
+        let body = serde_json::to_value(report)?;
+
+        client
+            .execute(
+                "INSERT INTO tca_reports (timestamp, bot_name, period_start, period_end, report) VALUES ($1, $2, $3, $4, $5)",
+                &[&report.generated_at, &bot_name, &report.start, &report.end, &body],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Query TCA reports for a bot generated between `start` and `end`, oldest first
+    pub async fn query_tca_reports(
+        &self,
+        bot_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<TcaReport>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT report
+                FROM tca_reports
+                WHERE bot_name = $1 AND timestamp >= $2 AND timestamp <= $3
+                ORDER BY timestamp ASC
+                "#,
+                &[&bot_name, &start, &end],
+            )
+            .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_value(row.get::<_, serde_json::Value>(0))?))
+            .collect()
+    }
+
+    /// Store the settlements of a resolved market for a bot
+    pub async fn store_market_settlements(
+        &mut self,
+        bot_name: &str,
+        settlements: &[MarketSettlementRecord],
+    ) -> Result<()> {
+        if settlements.is_empty() {
+            return Ok(());
+        }
+
+        debug!("Storing {} market settlements for {}", settlements.len(), bot_name);
+
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "INSERT INTO market_settlements (timestamp, bot_name, market, strategy_id, payout, size, \
+                 avg_entry_price, realized_pnl) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .await?;
+
+        for settlement in settlements {
+            client
+                .execute(
+                    &stmt,
+                    &[
+                        &settlement.timestamp,
+                        &bot_name,
+                        &settlement.market,
+                        &settlement.strategy_id,
+                        &settlement.payout,
+                        &settlement.size,
+                        &settlement.avg_entry_price,
+                        &settlement.realized_pnl,
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Query market settlements of a bot between `start` and `end`, oldest first
+    pub async fn query_market_settlements(
+        &self,
+        bot_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<MarketSettlementRecord>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT timestamp, market, strategy_id, payout, size, avg_entry_price, realized_pnl
+                FROM market_settlements
+                WHERE bot_name = $1 AND timestamp >= $2 AND timestamp <= $3
+                ORDER BY timestamp ASC
+                "#,
+                &[&bot_name, &start, &end],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| MarketSettlementRecord {
+                timestamp: row.get(0),
+                market: row.get(1),
+                strategy_id: row.get(2),
+                payout: row.get(3),
+                size: row.get(4),
+                avg_entry_price: row.get(5),
+                realized_pnl: row.get(6),
+            })
+            .collect())
+    }
+
+    /// Record market data ticks for later replay
+    pub async fn insert_market_ticks(&mut self, ticks: &[MarketTickRecord]) -> Result<()> {
+        if ticks.is_empty() {
+            return Ok(());
+        }
+
+        debug!("Recording {} market ticks", ticks.len());
+
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "INSERT INTO market_ticks (timestamp, market, bid, bid_size, ask, ask_size, last, volume_24h, sequence) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .await?;
+
+        for tick in ticks {
+            client
+                .execute(
+                    &stmt,
+                    &[
+                        &tick.timestamp,
+                        &tick.market,
+                        &tick.bid,
+                        &tick.bid_size,
+                        &tick.ask,
+                        &tick.ask_size,
+                        &tick.last,
+                        &tick.volume_24h,
+                        &tick.sequence,
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Query recorded market ticks between `start` and `end`, oldest first
+    ///
+    /// An empty `markets` slice returns ticks for every market. Results are
+    /// not capped by `query.max_results`, since a replay needs every tick.
+    pub async fn query_market_ticks(
+        &self,
+        markets: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<MarketTickRecord>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT timestamp, market, bid, bid_size, ask, ask_size, last, volume_24h, sequence
+                FROM market_ticks
+                WHERE (cardinality($1::text[]) = 0 OR market = ANY($1))
+                  AND timestamp >= $2 AND timestamp <= $3
+                ORDER BY timestamp ASC, sequence ASC NULLS FIRST
+                "#,
+                &[&markets, &start, &end],
+            )
+            .await?;
+
+        let ticks: Vec<MarketTickRecord> = rows
+            .iter()
+            .map(|row| MarketTickRecord {
+                timestamp: row.get(0),
+                market: row.get(1),
+                bid: row.get(2),
+                bid_size: row.get(3),
+                ask: row.get(4),
+                ask_size: row.get(5),
+                last: row.get(6),
+                volume_24h: row.get(7),
+                sequence: row.get(8),
+            })
+            .collect();
+
+        debug!("Found {} recorded market ticks", ticks.len());
+
+        Ok(ticks)
+    }
+
     /// Get pool status
     pub fn pool_status(&self) -> String {
         self.pool.status().to_string()
     }
 }
 
+/// WHERE condition for metric deletion
+///
+/// Parameters are always `$1` name, `$2` labels (JSONB), `$3` start and
+/// `$4` end; unset criteria are skipped in SQL so every statement takes the
+/// same parameter list.
+fn selector_condition(label_dictionary: bool) -> String {
+    let labels = if label_dictionary {
+        "m.label_set_id IN (SELECT id FROM metric_label_sets WHERE labels @> $2) \
+         OR (m.label_set_id IS NULL AND m.labels @> $2)"
+    } else {
+        "m.labels @> $2"
+    };
+
+    format!(
+        "($1::TEXT IS NULL OR m.metric_name = $1) \
+         AND ($2::JSONB = '{{}}'::JSONB OR {}) \
+         AND ($3::TIMESTAMPTZ IS NULL OR m.timestamp >= $3) \
+         AND ($4::TIMESTAMPTZ IS NULL OR m.timestamp <= $4)",
+        labels
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,4 +872,18 @@ mod tests {
         assert_eq!(metric.value, 75.5);
         assert_eq!(metric.labels.len(), 2);
     }
+
+    #[test]
+    fn test_selector_condition() {
+        assert!(MetricSelector::new().is_unbounded());
+        assert!(!MetricSelector::new().with_label("instance", "mm-eu-1").is_unbounded());
+
+        let inline = selector_condition(false);
+        assert!(inline.contains("($2::JSONB = '{}'::JSONB OR m.labels @> $2)"));
+        assert!(!inline.contains("metric_label_sets"));
+
+        let dictionary = selector_condition(true);
+        assert!(dictionary.contains("SELECT id FROM metric_label_sets WHERE labels @> $2"));
+        assert!(dictionary.contains("m.label_set_id IS NULL AND m.labels @> $2"));
+    }
 }
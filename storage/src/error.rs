@@ -39,6 +39,10 @@ pub enum StorageError {
     #[error("Retention policy error: {0}")]
     RetentionError(String),
 
+    /// I/O error (exports)
+    #[error("I/O error: {0}")]
+    IoError(String),
+
     /// Generic internal error
     #[error("Internal error: {0}")]
     Internal(String),
@@ -62,6 +66,12 @@ impl From<serde_json::Error> for StorageError {
     }
 }
 
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::IoError(err.to_string())
+    }
+}
+
 impl From<serde_yaml::Error> for StorageError {
     fn from(err: serde_yaml::Error) -> Self {
         StorageError::ConfigError(err.to_string())
@@ -1,7 +1,11 @@
+use crate::compliance::{self, ComplianceFormat};
 use crate::config::StorageConfig;
 use crate::error::Result;
+use crate::runs::StrategyRun;
 use crate::timescale::ConnectionPool;
-use crate::types::{Fill, Order, OrderFilters, PositionSnapshot};
+use crate::types::{
+    Fill, Order, OrderFilters, OrderLifecycle, OrderStatusTransition, PositionSnapshot, RiskDecisionRecord,
+};
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tracing::{debug, info};
@@ -24,8 +28,20 @@ impl ExecutionStore {
         Ok(Self { pool, config })
     }
 
-    /// Store order placement
+    /// Store order placement or update
+    ///
+    /// Orders are keyed by `client_order_id`; storing an existing order
+    /// updates its status and records an [`OrderStatusTransition`] when the
+    /// status changed.
     pub async fn store_order(&mut self, order: Order) -> Result<()> {
+        self.store_order_with_reason(order, None).await
+    }
+
+    /// Store an order update, recording why its status changed
+    ///
+    /// The reason (e.g. `"kill_switch"`, `"universe_exit"`) is kept on the
+    /// status transition and exported as the reason code of the event.
+    pub async fn store_order_with_reason(&mut self, order: Order, reason: Option<&str>) -> Result<()> {
         debug!("Storing order: {}", order.id);
 
         let client = self.pool.get().await?;
@@ -33,18 +49,33 @@ impl ExecutionStore {
         let side_str = order.side.to_string();
         let order_type_str = order.order_type.to_string();
         let status_str = order.status.to_string();
+        let tags = serde_json::to_value(&order.tags)?;
 
-        client
-            .execute(
+        let previous_status: Option<String> = client
+            .query_opt(
+                "SELECT status FROM orders WHERE client_order_id = $1",
+                &[&order.client_order_id],
+            )
+            .await?
+            .map(|row| row.get(0));
+
+        let row = client
+            .query_one(
                 r#"
                 INSERT INTO orders (
                     id, timestamp, venue, market, side, order_type,
-                    price, size, status, client_order_id, venue_order_id, time_in_force
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    price, size, status, client_order_id, venue_order_id, time_in_force,
+                    correlation_id, strategy_id, session_id, tags
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
                 ON CONFLICT (client_order_id) DO UPDATE SET
                     status = EXCLUDED.status,
                     venue_order_id = EXCLUDED.venue_order_id,
+                    correlation_id = COALESCE(orders.correlation_id, EXCLUDED.correlation_id),
+                    strategy_id = COALESCE(orders.strategy_id, EXCLUDED.strategy_id),
+                    session_id = COALESCE(orders.session_id, EXCLUDED.session_id),
+                    tags = EXCLUDED.tags || orders.tags,
                     updated_at = NOW()
+                RETURNING id
                 "#,
                 &[
                     &order.id,
@@ -59,6 +90,55 @@ impl ExecutionStore {
                     &order.client_order_id,
                     &order.venue_order_id,
                     &order.time_in_force,
+                    &order.correlation_id,
+                    &order.strategy_id,
+                    &order.session_id,
+                    &tags,
+                ],
+            )
+            .await?;
+
+        if previous_status.as_deref() != Some(status_str.as_str()) {
+            let order_id: Uuid = row.get(0);
+            client
+                .execute(
+                    r#"
+                    INSERT INTO order_status_transitions (
+                        order_id, timestamp, from_status, to_status, reason
+                    ) VALUES ($1, NOW(), $2, $3, $4)
+                    "#,
+                    &[&order_id, &previous_status, &status_str, &reason],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Store a pre-trade risk decision
+    pub async fn store_risk_decision(&mut self, decision: RiskDecisionRecord) -> Result<()> {
+        debug!("Storing risk decision: {} (allowed: {})", decision.id, decision.allowed);
+
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                r#"
+                INSERT INTO risk_decisions (
+                    id, timestamp, order_id, correlation_id, strategy_id, session_id,
+                    market, allowed, violated_policies
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+                &[
+                    &decision.id,
+                    &decision.timestamp,
+                    &decision.order_id,
+                    &decision.correlation_id,
+                    &decision.strategy_id,
+                    &decision.session_id,
+                    &decision.market,
+                    &decision.allowed,
+                    &decision.violated_policies,
                 ],
             )
             .await?;
@@ -67,11 +147,21 @@ impl ExecutionStore {
     }
 
     /// Store execution fill
-    pub async fn store_fill(&mut self, fill: Fill) -> Result<()> {
+    ///
+    /// Fills without a `quoted_price` are compared against the stored
+    /// order's limit price; the price improvement is kept with the fill.
+    pub async fn store_fill(&mut self, mut fill: Fill) -> Result<()> {
         debug!("Storing fill: {}", fill.id);
 
         let client = self.pool.get().await?;
 
+        if fill.quoted_price.is_none() {
+            fill.quoted_price = client
+                .query_opt("SELECT price FROM orders WHERE id = $1", &[&fill.order_id])
+                .await?
+                .and_then(|row| row.get(0));
+        }
+
         let side_str = fill.side.to_string();
 
         client
@@ -79,8 +169,9 @@ impl ExecutionStore {
                 r#"
                 INSERT INTO fills (
                     id, timestamp, order_id, venue, market, side,
-                    price, size, fee, fee_currency, trade_id, liquidity
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    price, size, fee, fee_currency, trade_id, liquidity,
+                    quoted_price, price_improvement_bps
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
                 "#,
                 &[
                     &fill.id,
@@ -95,6 +186,8 @@ impl ExecutionStore {
                     &fill.fee_currency,
                     &fill.trade_id,
                     &fill.liquidity,
+                    &fill.quoted_price,
+                    &fill.price_improvement_bps(),
                 ],
             )
             .await?;
@@ -132,6 +225,44 @@ impl ExecutionStore {
         Ok(())
     }
 
+    /// Store a batch of position snapshots (e.g. every market's position
+    /// at one point in time)
+    pub async fn store_positions(&self, positions: &[PositionSnapshot]) -> Result<()> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+
+        debug!("Storing {} position snapshots", positions.len());
+
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "INSERT INTO positions (timestamp, market, venue, size, avg_entry_price, unrealized_pnl, realized_pnl, \
+                 mark_price) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .await?;
+
+        for position in positions {
+            client
+                .execute(
+                    &stmt,
+                    &[
+                        &position.timestamp,
+                        &position.market,
+                        &position.venue,
+                        &position.size,
+                        &position.avg_entry_price,
+                        &position.unrealized_pnl,
+                        &position.realized_pnl,
+                        &position.mark_price,
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
     /// Query order history
     pub async fn query_orders(
         &self,
@@ -147,7 +278,8 @@ impl ExecutionStore {
         let mut query = String::from(
             r#"
             SELECT id, timestamp, venue, market, side, order_type,
-                   price, size, status, client_order_id, venue_order_id, time_in_force
+                   price, size, status, client_order_id, venue_order_id, time_in_force,
+                   correlation_id, strategy_id, session_id, tags
             FROM orders
             WHERE timestamp >= $1 AND timestamp <= $2
             "#
@@ -184,9 +316,27 @@ impl ExecutionStore {
 
         if let Some(ref client_order_id) = filters.client_order_id {
             query.push_str(&format!(" AND client_order_id = ${}", param_idx));
+            param_idx += 1;
             optional_strings.push(client_order_id.clone());
         }
 
+        if let Some(ref correlation_id) = filters.correlation_id {
+            query.push_str(&format!(" AND correlation_id = ${}", param_idx));
+            param_idx += 1;
+            optional_strings.push(correlation_id.clone());
+        }
+
+        if let Some(ref strategy_id) = filters.strategy_id {
+            query.push_str(&format!(" AND strategy_id = ${}", param_idx));
+            param_idx += 1;
+            optional_strings.push(strategy_id.clone());
+        }
+
+        if let Some(ref session_id) = filters.session_id {
+            query.push_str(&format!(" AND session_id = ${}", param_idx));
+            optional_strings.push(session_id.clone());
+        }
+
         query.push_str(&format!(" ORDER BY timestamp DESC LIMIT {}", self.config.query.max_results));
 
         // Add optional params
@@ -196,29 +346,7 @@ impl ExecutionStore {
 
         let rows = client.query(&query, &params).await?;
 
-        let orders: Vec<Order> = rows
-            .iter()
-            .map(|row| {
-                let side_str: String = row.get(4);
-                let order_type_str: String = row.get(5);
-                let status_str: String = row.get(8);
-
-                Order {
-                    id: row.get(0),
-                    timestamp: row.get(1),
-                    venue: row.get(2),
-                    market: row.get(3),
-                    side: parse_side(&side_str),
-                    order_type: parse_order_type(&order_type_str),
-                    price: row.get(6),
-                    size: row.get(7),
-                    status: parse_order_status(&status_str),
-                    client_order_id: row.get(9),
-                    venue_order_id: row.get(10),
-                    time_in_force: row.get(11),
-                }
-            })
-            .collect();
+        let orders: Vec<Order> = rows.iter().map(order_from_row).collect();
 
         debug!("Found {} orders", orders.len());
 
@@ -235,8 +363,99 @@ impl ExecutionStore {
             .query(
                 r#"
                 SELECT id, timestamp, order_id, venue, market, side,
-                       price, size, fee, fee_currency, trade_id, liquidity
+                       price, size, fee, fee_currency, trade_id, liquidity, quoted_price
+                FROM fills
+                WHERE order_id = $1
+                ORDER BY timestamp ASC
+                "#,
+                &[&order_id],
+            )
+            .await?;
+
+        Ok(rows.iter().map(fill_from_row).collect())
+    }
+
+    /// Query fills executed in a time range
+    pub async fn query_fills_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Fill>> {
+        debug!("Querying fills from {} to {}", start, end);
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT id, timestamp, order_id, venue, market, side,
+                       price, size, fee, fee_currency, trade_id, liquidity, quoted_price
                 FROM fills
+                WHERE timestamp >= $1 AND timestamp <= $2
+                ORDER BY timestamp ASC
+                "#,
+                &[&start, &end],
+            )
+            .await?;
+
+        Ok(rows.iter().map(fill_from_row).collect())
+    }
+
+    /// Query risk decisions recorded for an order
+    pub async fn query_risk_decisions_by_order(&self, order_id: Uuid) -> Result<Vec<RiskDecisionRecord>> {
+        debug!("Querying risk decisions for order: {}", order_id);
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT id, timestamp, order_id, correlation_id, strategy_id, session_id,
+                       market, allowed, violated_policies
+                FROM risk_decisions
+                WHERE order_id = $1
+                ORDER BY timestamp ASC
+                "#,
+                &[&order_id],
+            )
+            .await?;
+
+        Ok(rows.iter().map(risk_decision_from_row).collect())
+    }
+
+    /// Query risk decisions made in a time range
+    pub async fn query_risk_decisions_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<RiskDecisionRecord>> {
+        debug!("Querying risk decisions from {} to {}", start, end);
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT id, timestamp, order_id, correlation_id, strategy_id, session_id,
+                       market, allowed, violated_policies
+                FROM risk_decisions
+                WHERE timestamp >= $1 AND timestamp <= $2
+                ORDER BY timestamp ASC
+                "#,
+                &[&start, &end],
+            )
+            .await?;
+
+        Ok(rows.iter().map(risk_decision_from_row).collect())
+    }
+
+    /// Query status transitions for an order
+    pub async fn query_status_transitions(&self, order_id: Uuid) -> Result<Vec<OrderStatusTransition>> {
+        debug!("Querying status transitions for order: {}", order_id);
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT order_id, timestamp, from_status, to_status, reason
+                FROM order_status_transitions
                 WHERE order_id = $1
                 ORDER BY timestamp ASC
                 "#,
@@ -244,29 +463,261 @@ impl ExecutionStore {
             )
             .await?;
 
-        let fills: Vec<Fill> = rows
+        Ok(rows.iter().map(transition_from_row).collect())
+    }
+
+    /// Query status transitions recorded in a time range
+    pub async fn query_status_transitions_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<OrderStatusTransition>> {
+        debug!("Querying status transitions from {} to {}", start, end);
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT order_id, timestamp, from_status, to_status, reason
+                FROM order_status_transitions
+                WHERE timestamp >= $1 AND timestamp <= $2
+                ORDER BY timestamp ASC
+                "#,
+                &[&start, &end],
+            )
+            .await?;
+
+        Ok(rows.iter().map(transition_from_row).collect())
+    }
+
+    /// Get orders by ID
+    pub async fn get_orders(&self, order_ids: &[Uuid]) -> Result<Vec<Order>> {
+        if order_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT id, timestamp, venue, market, side, order_type,
+                       price, size, status, client_order_id, venue_order_id, time_in_force,
+                       correlation_id, strategy_id, session_id, tags
+                FROM orders
+                WHERE id = ANY($1)
+                "#,
+                &[&order_ids],
+            )
+            .await?;
+
+        Ok(rows.iter().map(order_from_row).collect())
+    }
+
+    /// Export every order event between `start` and `end` as a compliance
+    /// audit file
+    ///
+    /// Includes status transitions, risk decisions and fills recorded in the
+    /// range, with the attributes of their orders (even if placed earlier).
+    /// Returns the number of records written.
+    pub async fn export_compliance<W: std::io::Write>(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        format: ComplianceFormat,
+        writer: &mut W,
+    ) -> Result<usize> {
+        info!("Exporting compliance records from {} to {} ({:?})", start, end, format);
+
+        let decisions = self.query_risk_decisions_between(start, end).await?;
+        let transitions = self.query_status_transitions_between(start, end).await?;
+        let fills = self.query_fills_between(start, end).await?;
+
+        let mut order_ids: Vec<Uuid> = decisions
             .iter()
-            .map(|row| {
-                let side_str: String = row.get(5);
-
-                Fill {
-                    id: row.get(0),
-                    timestamp: row.get(1),
-                    order_id: row.get(2),
-                    venue: row.get(3),
-                    market: row.get(4),
-                    side: parse_side(&side_str),
-                    price: row.get(6),
-                    size: row.get(7),
-                    fee: row.get(8),
-                    fee_currency: row.get(9),
-                    trade_id: row.get(10),
-                    liquidity: row.get(11),
-                }
-            })
+            .filter_map(|d| d.order_id)
+            .chain(transitions.iter().map(|t| t.order_id))
+            .chain(fills.iter().map(|f| f.order_id))
             .collect();
+        order_ids.sort();
+        order_ids.dedup();
+        let orders = self.get_orders(&order_ids).await?;
 
-        Ok(fills)
+        let records = compliance::build_records(&orders, &decisions, &transitions, &fills);
+        compliance::write_records(&records, format, writer)
+    }
+
+    /// Get an order with its risk decisions, status transitions and fills
+    /// in chronological order
+    ///
+    /// Returns `None` if the order is not stored.
+    pub async fn get_order_lifecycle(&self, order_id: Uuid) -> Result<Option<OrderLifecycle>> {
+        debug!("Loading lifecycle for order: {}", order_id);
+
+        let row = {
+            let client = self.pool.get().await?;
+            client
+                .query_opt(
+                    r#"
+                    SELECT id, timestamp, venue, market, side, order_type,
+                           price, size, status, client_order_id, venue_order_id, time_in_force,
+                           correlation_id, strategy_id, session_id, tags
+                    FROM orders
+                    WHERE id = $1
+                    "#,
+                    &[&order_id],
+                )
+                .await?
+        };
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let order = order_from_row(&row);
+
+        let decisions = self.query_risk_decisions_by_order(order_id).await?;
+        let transitions = self.query_status_transitions(order_id).await?;
+        let fills = self.query_fills_by_order(order_id).await?;
+
+        Ok(Some(OrderLifecycle::new(order, decisions, transitions, fills)))
+    }
+
+    /// Get orders carrying a tag (e.g. every order of a run)
+    pub async fn query_orders_by_tag(&self, key: &str, value: &str) -> Result<Vec<Order>> {
+        debug!("Querying orders tagged {}={}", key, value);
+
+        let client = self.pool.get().await?;
+        let tag = serde_json::json!({ key: value });
+
+        let rows = client
+            .query(
+                r#"
+                SELECT id, timestamp, venue, market, side, order_type,
+                       price, size, status, client_order_id, venue_order_id, time_in_force,
+                       correlation_id, strategy_id, session_id, tags
+                FROM orders
+                WHERE tags @> $1
+                ORDER BY timestamp ASC
+                "#,
+                &[&tag],
+            )
+            .await?;
+
+        Ok(rows.iter().map(order_from_row).collect())
+    }
+
+    /// Record a strategy start and assign its `params_version`
+    ///
+    /// A run repeating a configuration hash already seen for the strategy
+    /// gets that parameter set's version; a new parameter set gets the next
+    /// one.
+    pub async fn store_strategy_run(&self, run: &mut StrategyRun) -> Result<()> {
+        debug!("Storing strategy run: {}", run.run_id);
+
+        let client = self.pool.get().await?;
+        let params = serde_json::to_value(&run.params)?;
+
+        let row = client
+            .query_one(
+                r#"
+                INSERT INTO strategy_runs (
+                    run_id, bot_name, strategy_id, strategy_kind, params,
+                    code_version, config_hash, params_version, started_at, ended_at
+                ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7,
+                    COALESCE(
+                        (SELECT params_version FROM strategy_runs
+                         WHERE strategy_id = $3 AND config_hash = $7 LIMIT 1),
+                        (SELECT COALESCE(MAX(params_version), 0) + 1 FROM strategy_runs
+                         WHERE strategy_id = $3)
+                    ),
+                    $8, $9
+                )
+                RETURNING params_version
+                "#,
+                &[
+                    &run.run_id,
+                    &run.bot_name,
+                    &run.strategy_id,
+                    &run.strategy_kind,
+                    &params,
+                    &run.code_version,
+                    &run.config_hash,
+                    &run.started_at,
+                    &run.ended_at,
+                ],
+            )
+            .await?;
+
+        run.params_version = row.get(0);
+        info!(
+            "Strategy run {} started (params v{}, config {})",
+            run.run_id, run.params_version, run.config_hash
+        );
+        Ok(())
+    }
+
+    /// Mark a strategy run as ended
+    pub async fn end_strategy_run(&self, run_id: &str, ended_at: DateTime<Utc>) -> Result<()> {
+        debug!("Ending strategy run: {}", run_id);
+
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE strategy_runs SET ended_at = $2 WHERE run_id = $1",
+                &[&run_id, &ended_at],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get strategy runs by ID, oldest first
+    pub async fn get_strategy_runs(&self, run_ids: &[String]) -> Result<Vec<StrategyRun>> {
+        if run_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT run_id, bot_name, strategy_id, strategy_kind, params,
+                       code_version, config_hash, params_version, started_at, ended_at
+                FROM strategy_runs
+                WHERE run_id = ANY($1)
+                ORDER BY started_at ASC
+                "#,
+                &[&run_ids],
+            )
+            .await?;
+
+        Ok(rows.iter().map(run_from_row).collect())
+    }
+
+    /// Query the runs of a strategy, oldest first
+    pub async fn query_strategy_runs(&self, strategy_id: &str) -> Result<Vec<StrategyRun>> {
+        debug!("Querying runs of strategy: {}", strategy_id);
+
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                r#"
+                SELECT run_id, bot_name, strategy_id, strategy_kind, params,
+                       code_version, config_hash, params_version, started_at, ended_at
+                FROM strategy_runs
+                WHERE strategy_id = $1
+                ORDER BY started_at ASC
+                "#,
+                &[&strategy_id],
+            )
+            .await?;
+
+        Ok(rows.iter().map(run_from_row).collect())
     }
 
     /// Get position history
@@ -352,6 +803,97 @@ impl ExecutionStore {
 // Helper functions to parse enum types
 use crate::types::{OrderStatus, OrderType, Side};
 
+/// Map an `orders` row selected with the full column list
+fn order_from_row(row: &tokio_postgres::Row) -> Order {
+    let side_str: String = row.get(4);
+    let order_type_str: String = row.get(5);
+    let status_str: String = row.get(8);
+
+    Order {
+        id: row.get(0),
+        timestamp: row.get(1),
+        venue: row.get(2),
+        market: row.get(3),
+        side: parse_side(&side_str),
+        order_type: parse_order_type(&order_type_str),
+        price: row.get(6),
+        size: row.get(7),
+        status: parse_order_status(&status_str),
+        client_order_id: row.get(9),
+        venue_order_id: row.get(10),
+        time_in_force: row.get(11),
+        correlation_id: row.get(12),
+        strategy_id: row.get(13),
+        session_id: row.get(14),
+        tags: row
+            .get::<_, Option<serde_json::Value>>(15)
+            .and_then(|tags| serde_json::from_value(tags).ok())
+            .unwrap_or_default(),
+    }
+}
+
+fn fill_from_row(row: &tokio_postgres::Row) -> Fill {
+    let side_str: String = row.get(5);
+
+    Fill {
+        id: row.get(0),
+        timestamp: row.get(1),
+        order_id: row.get(2),
+        venue: row.get(3),
+        market: row.get(4),
+        side: parse_side(&side_str),
+        price: row.get(6),
+        size: row.get(7),
+        fee: row.get(8),
+        fee_currency: row.get(9),
+        trade_id: row.get(10),
+        liquidity: row.get(11),
+        quoted_price: row.get(12),
+    }
+}
+
+fn risk_decision_from_row(row: &tokio_postgres::Row) -> RiskDecisionRecord {
+    RiskDecisionRecord {
+        id: row.get(0),
+        timestamp: row.get(1),
+        order_id: row.get(2),
+        correlation_id: row.get(3),
+        strategy_id: row.get(4),
+        session_id: row.get(5),
+        market: row.get(6),
+        allowed: row.get(7),
+        violated_policies: row.get(8),
+    }
+}
+
+fn transition_from_row(row: &tokio_postgres::Row) -> OrderStatusTransition {
+    let from_status: Option<String> = row.get(2);
+    let to_status: String = row.get(3);
+
+    OrderStatusTransition {
+        order_id: row.get(0),
+        timestamp: row.get(1),
+        from_status: from_status.as_deref().map(parse_order_status),
+        to_status: parse_order_status(&to_status),
+        reason: row.get(4),
+    }
+}
+
+fn run_from_row(row: &tokio_postgres::Row) -> StrategyRun {
+    StrategyRun {
+        run_id: row.get(0),
+        bot_name: row.get(1),
+        strategy_id: row.get(2),
+        strategy_kind: row.get(3),
+        params: serde_json::from_value(row.get(4)).unwrap_or_default(),
+        code_version: row.get(5),
+        config_hash: row.get(6),
+        params_version: row.get(7),
+        started_at: row.get(8),
+        ended_at: row.get(9),
+    }
+}
+
 fn parse_side(s: &str) -> Side {
     match s.to_lowercase().as_str() {
         "buy" => Side::Buy,
@@ -10,6 +10,7 @@
 //! - Automatic data compression and retention policies
 //! - Continuous aggregates for downsampling
 //! - Execution history (orders, fills, positions)
+//! - Strategy run history for experiment comparison
 //! - Connection pooling for concurrent access
 //!
 //! # Example
@@ -33,10 +34,14 @@
 //! }
 //! ```
 
+pub mod attribution;
+pub mod compliance;
 pub mod config;
 pub mod engine;
 pub mod error;
 pub mod execution;
+pub mod runs;
+pub mod tca;
 pub mod types;
 
 // Include timescale module from parent directory
@@ -54,16 +59,24 @@ pub mod retention {
 }
 
 // Re-export main types
+pub use attribution::{AttributionConfig, AttributionReport, SignalAttributor, SignalStats};
+pub use compliance::{ComplianceEvent, ComplianceFormat, ComplianceRecord};
 pub use config::{
-    DatabaseConfig, IngestionConfig, QueryConfig, RetentionConfig, StorageConfig,
+    DatabaseConfig, IngestionConfig, MetricsLayoutConfig, QueryConfig, RetentionConfig, StorageConfig,
 };
 pub use engine::StorageEngine;
 pub use error::{Result, StorageError};
 pub use execution::ExecutionStore;
+pub use runs::{compare_runs, config_hash, ParamChange, RunComparison, RunResult, StrategyRun, RUN_TAG};
+pub use tca::{
+    HourlyImprovement, MidPrices, OrderTypeImprovement, PriceImprovementReport, PriceImprovementStats, TcaAnalyzer,
+    TcaConfig, TcaGroup, TcaMetrics, TcaReport,
+};
 pub use timescale::{ConnectionPool, PoolStatus, QueryBuilder};
 pub use types::{
-    AggregatedMetric, Aggregation, Fill, MetricPoint, Order, OrderFilters, OrderStatus,
-    OrderType, PositionSnapshot, RetentionReport, Side,
+    AggregatedMetric, Aggregation, Fill, LifecycleEvent, MarketSettlementRecord, MarketTickRecord,
+    MetricDeletionReport, MetricPoint, MetricSelector, Order, OrderFilters, OrderLifecycle, OrderStatus,
+    OrderStatusTransition, OrderType, PositionSnapshot, RetentionReport, RiskDecisionRecord, Side,
 };
 
 // Re-export retention types
@@ -88,6 +101,44 @@ pub fn init_tracing() {
         .init();
 }
 
+/// Initialize tracing with an OTLP span exporter (feature `otel`)
+///
+/// Same filter and formatting as [`init_tracing`], plus a
+/// `tracing-opentelemetry` layer shipping storage spans to `endpoint`.
+/// Call `opentelemetry::global::shutdown_tracer_provider()` on exit to flush.
+#[cfg(feature = "otel")]
+pub fn init_tracing_otel(endpoint: &str) -> Result<()> {
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+    use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("ag_storage=info"));
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(trace::config().with_resource(Resource::new(vec![
+            KeyValue::new("service.name", "ag-storage"),
+        ])))
+        .install_batch(runtime::Tokio)
+        .map_err(|e| StorageError::ConfigError(format!("Failed to install OTLP tracer: {}", e)))?;
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(false).with_thread_ids(true))
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+        .map_err(|e| StorageError::ConfigError(format!("Failed to install subscriber: {}", e)))?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
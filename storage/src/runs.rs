@@ -0,0 +1,266 @@
+//! Strategy run history and experiment comparison
+//!
+//! Every strategy start is recorded as a [`StrategyRun`]: the full parameter
+//! set, the code version and a hash of the strategy's configuration. Orders
+//! placed during the run carry its ID in the `run_id` tag ([`RUN_TAG`]),
+//! which links their fills to the run, and bot metrics carry it as a
+//! `run_id` label.
+//!
+//! Runs of a strategy with the same kind and parameters share a
+//! `config_hash` and a `params_version`; the first run of a new parameter
+//! set gets the next version, so "params v12 vs v13" maps to two versions
+//! of one strategy. [`compare_runs`] replays the runs' fills through the
+//! [`SignalAttributor`] keyed by run instead of signal, and
+//! [`StrategyRun::param_changes`] lists what differs between two runs.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::attribution::{AttributionConfig, SignalAttributor, SignalStats};
+use crate::tca::MidPrices;
+use crate::types::{Fill, Order};
+
+/// Order tag (and metric label) holding the run ID
+pub const RUN_TAG: &str = "run_id";
+
+/// One start of a strategy and the configuration it ran with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyRun {
+    /// Unique run identifier (`<strategy_id>-<start time>-<random>`)
+    pub run_id: String,
+    pub bot_name: String,
+    pub strategy_id: String,
+    /// Strategy kind (e.g. `market_maker`)
+    pub strategy_kind: String,
+    /// Full parameter set
+    pub params: BTreeMap<String, String>,
+    /// Version of the code that ran (crate version or git revision)
+    pub code_version: String,
+    /// Hash of the kind and parameters, see [`config_hash`]
+    pub config_hash: String,
+    /// Per-strategy parameter set number, assigned when the run is stored
+    /// (0 before)
+    pub params_version: i32,
+    pub started_at: DateTime<Utc>,
+    /// None while the run is live (or if the bot died without stopping)
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl StrategyRun {
+    /// Run starting now
+    pub fn new(
+        bot_name: impl Into<String>,
+        strategy_id: impl Into<String>,
+        strategy_kind: impl Into<String>,
+        params: impl IntoIterator<Item = (String, String)>,
+        code_version: impl Into<String>,
+    ) -> Self {
+        let strategy_id = strategy_id.into();
+        let strategy_kind = strategy_kind.into();
+        let params: BTreeMap<String, String> = params.into_iter().collect();
+        let started_at = Utc::now();
+        let suffix = Uuid::new_v4().simple().to_string();
+
+        Self {
+            run_id: format!("{}-{}-{}", strategy_id, started_at.format("%Y%m%dT%H%M%SZ"), &suffix[..8]),
+            bot_name: bot_name.into(),
+            config_hash: config_hash(&strategy_kind, &params),
+            strategy_id,
+            strategy_kind,
+            params,
+            code_version: code_version.into(),
+            params_version: 0,
+            started_at,
+            ended_at: None,
+        }
+    }
+
+    /// Parameters that differ from `self` in `other`, sorted by key
+    pub fn param_changes(&self, other: &StrategyRun) -> Vec<ParamChange> {
+        let keys: BTreeSet<&String> = self.params.keys().chain(other.params.keys()).collect();
+        keys.into_iter()
+            .filter_map(|key| {
+                let from = self.params.get(key);
+                let to = other.params.get(key);
+                (from != to).then(|| ParamChange {
+                    key: key.clone(),
+                    from: from.cloned(),
+                    to: to.cloned(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Stable hash of a strategy's kind and parameters (16 hex digits)
+///
+/// FNV-1a over the kind and the parameters in key order, so it does not
+/// depend on config file order or the Rust version.
+pub fn config_hash(strategy_kind: &str, params: &BTreeMap<String, String>) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes.iter().chain(&[0xff]) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    feed(strategy_kind.as_bytes());
+    for (key, value) in params {
+        feed(key.as_bytes());
+        feed(value.as_bytes());
+    }
+    format!("{:016x}", hash)
+}
+
+/// Parameter that differs between two runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamChange {
+    pub key: String,
+    /// Value in the first run (None = unset)
+    pub from: Option<String>,
+    /// Value in the second run (None = unset)
+    pub to: Option<String>,
+}
+
+/// Outcome of one run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunResult {
+    pub run: StrategyRun,
+    /// Orders, fills and PnL of the run's orders (`signal` is the run ID)
+    pub stats: SignalStats,
+}
+
+/// Side-by-side outcomes of several runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunComparison {
+    /// Comparison time
+    pub generated_at: DateTime<Utc>,
+    /// Runs in the order requested
+    pub runs: Vec<RunResult>,
+}
+
+impl RunComparison {
+    /// Outcome of a run
+    pub fn run(&self, run_id: &str) -> Option<&RunResult> {
+        self.runs.iter().find(|r| r.run.run_id == run_id)
+    }
+
+    /// Parameters that changed from one run to another (empty if either
+    /// is not part of the comparison)
+    pub fn param_changes(&self, from_run_id: &str, to_run_id: &str) -> Vec<ParamChange> {
+        match (self.run(from_run_id), self.run(to_run_id)) {
+            (Some(from), Some(to)) => from.run.param_changes(&to.run),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Compare runs by the fills of their orders (tagged with [`RUN_TAG`])
+///
+/// `orders` and `fills` may include other orders; only those tagged with
+/// one of the runs count. PnL is attributed as by [`SignalAttributor`]: a
+/// lot opened in one run and closed in the next credits the first.
+pub fn compare_runs(runs: Vec<StrategyRun>, orders: &[Order], fills: &[Fill], mids: &MidPrices) -> RunComparison {
+    let now = Utc::now();
+    let start = runs.iter().map(|r| r.started_at).min().unwrap_or(now);
+    let end = runs.iter().map(|r| r.ended_at.unwrap_or(now)).max().unwrap_or(now);
+
+    let attributor = SignalAttributor::new(AttributionConfig {
+        tag: RUN_TAG.to_string(),
+    });
+    let report = attributor.attribute(orders, fills, mids, start, end);
+
+    RunComparison {
+        generated_at: now,
+        runs: runs
+            .into_iter()
+            .map(|run| {
+                let stats = report.signal(Some(&run.run_id)).cloned().unwrap_or_else(|| SignalStats {
+                    signal: Some(run.run_id.clone()),
+                    ..Default::default()
+                });
+                RunResult { run, stats }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrderType, Side};
+    use chrono::Duration;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let mut v12 = StrategyRun::new("mm-bot", "mm_1", "market_maker", params(&[("spread_bps", "50")]), "0.1.0");
+        let mut v13 = StrategyRun::new(
+            "mm-bot",
+            "mm_1",
+            "market_maker",
+            params(&[("spread_bps", "40"), ("size", "10")]),
+            "0.1.0",
+        );
+        assert_ne!(v12.run_id, v13.run_id);
+        assert_ne!(v12.config_hash, v13.config_hash);
+        // The code version is not part of the configuration
+        let rerun = StrategyRun::new("mm-bot", "mm_1", "market_maker", params(&[("spread_bps", "50")]), "0.2.0");
+        assert_eq!(rerun.config_hash, v12.config_hash);
+
+        let t0 = Utc::now() - Duration::hours(2);
+        v12.started_at = t0;
+        v12.ended_at = Some(t0 + Duration::hours(1));
+        v13.started_at = t0 + Duration::hours(1);
+
+        let order = |run: &StrategyRun, side: Side, minutes: i64| {
+            let mut order = Order::new("polymarket", "m1", side, OrderType::Limit, 100.0).with_tag(RUN_TAG, &run.run_id);
+            order.timestamp = t0 + Duration::minutes(minutes);
+            order.strategy_id = Some("mm_1".to_string());
+            order
+        };
+        let fill = |order: &Order, price: f64| {
+            let mut fill = Fill::new(order.id, "polymarket", "m1", order.side, price, 100.0, 0.0, "USDC");
+            fill.timestamp = order.timestamp;
+            fill
+        };
+
+        // v12 buys at 0.50 and sells at 0.52; v13 buys at 0.50 and sells at 0.49
+        let orders = vec![
+            order(&v12, Side::Buy, 10),
+            order(&v12, Side::Sell, 20),
+            order(&v13, Side::Buy, 70),
+            order(&v13, Side::Sell, 80),
+        ];
+        let fills: Vec<Fill> = orders.iter().zip([0.50, 0.52, 0.50, 0.49]).map(|(o, p)| fill(o, p)).collect();
+
+        let comparison = compare_runs(vec![v12.clone(), v13.clone()], &orders, &fills, &MidPrices::new());
+        let first = comparison.run(&v12.run_id).unwrap();
+        let second = comparison.run(&v13.run_id).unwrap();
+        assert_eq!((first.stats.orders, first.stats.fills), (2, 2));
+        assert!((first.stats.realized_pnl - 2.0).abs() < 1e-9);
+        assert!((second.stats.realized_pnl + 1.0).abs() < 1e-9);
+
+        assert_eq!(
+            comparison.param_changes(&v12.run_id, &v13.run_id),
+            vec![
+                ParamChange {
+                    key: "size".to_string(),
+                    from: None,
+                    to: Some("10".to_string()),
+                },
+                ParamChange {
+                    key: "spread_bps".to_string(),
+                    from: Some("50".to_string()),
+                    to: Some("40".to_string()),
+                },
+            ]
+        );
+    }
+}
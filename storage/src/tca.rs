@@ -0,0 +1,645 @@
+//! Transaction cost analysis (TCA)
+//!
+//! Computes execution quality from stored orders and fills against a mid
+//! price series (typically the `market.mid` metric recorded by the bot):
+//!
+//! - **Implementation shortfall**: fill price versus the mid when the order
+//!   was placed (arrival), plus fees, in bps of the arrival mid
+//! - **Effective spread / spread capture**: fill price versus the mid at
+//!   fill time; makers capture spread, takers pay it
+//! - **Markouts**: mid move after the fill at each horizon, in the fill's
+//!   favour, in bps of the fill price (negative = adverse selection)
+//! - **Fill ratio**: filled size over ordered size
+//! - **Price improvement**: fill price versus the order's quoted (limit)
+//!   price, in bps of the quote, also broken down per venue and order type
+//!   and per venue and hour of day (UTC)
+//!
+//! All averages are size-weighted. Costs are positive (shortfall, effective
+//! spread, fees); edges are positive when they make money (spread capture,
+//! markouts, price improvement).
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Fill, Order, OrderType, Side};
+
+/// Mid price series per market
+#[derive(Debug, Clone, Default)]
+pub struct MidPrices {
+    by_market: HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+}
+
+impl MidPrices {
+    /// Create an empty series
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a mid observation
+    pub fn add(&mut self, market: impl Into<String>, timestamp: DateTime<Utc>, mid: f64) {
+        let series = self.by_market.entry(market.into()).or_default();
+        let idx = series.partition_point(|(t, _)| *t <= timestamp);
+        series.insert(idx, (timestamp, mid));
+    }
+
+    /// Latest mid at or before `at`
+    pub fn mid_at(&self, market: &str, at: DateTime<Utc>) -> Option<f64> {
+        let series = self.by_market.get(market)?;
+        let idx = series.partition_point(|(t, _)| *t <= at);
+        idx.checked_sub(1).map(|i| series[i].1)
+    }
+
+    /// First mid at or after `at`
+    pub fn mid_after(&self, market: &str, at: DateTime<Utc>) -> Option<f64> {
+        let series = self.by_market.get(market)?;
+        let idx = series.partition_point(|(t, _)| *t < at);
+        series.get(idx).map(|(_, mid)| *mid)
+    }
+
+    /// Whether no observations were added
+    pub fn is_empty(&self) -> bool {
+        self.by_market.is_empty()
+    }
+}
+
+/// TCA settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TcaConfig {
+    /// Markout horizons in seconds
+    pub markout_horizons_secs: Vec<u64>,
+}
+
+impl Default for TcaConfig {
+    fn default() -> Self {
+        Self {
+            markout_horizons_secs: vec![5, 60, 300],
+        }
+    }
+}
+
+/// Execution quality metrics for one group of orders
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TcaMetrics {
+    /// Orders placed
+    pub orders: usize,
+    /// Orders with at least one fill
+    pub filled_orders: usize,
+    /// Fills
+    pub fills: usize,
+    /// Ordered size
+    pub ordered_size: f64,
+    /// Filled size
+    pub filled_size: f64,
+    /// Filled size / ordered size
+    pub fill_ratio: f64,
+    /// Filled notional (price * size)
+    pub notional: f64,
+    /// Total fees
+    pub fees: f64,
+    /// Fees in bps of filled notional
+    pub fee_bps: f64,
+    /// Implementation shortfall vs arrival mid, including fees (cost)
+    pub implementation_shortfall_bps: Option<f64>,
+    /// Effective spread, 2 * |fill - mid| signed by side (cost)
+    pub effective_spread_bps: Option<f64>,
+    /// Spread captured vs mid at fill (edge)
+    pub spread_capture_bps: Option<f64>,
+    /// Markout per horizon in seconds (edge; negative = adverse selection)
+    pub markouts_bps: BTreeMap<u64, f64>,
+    /// Fill price vs quoted (limit) price (edge; negative = slippage)
+    #[serde(default)]
+    pub price_improvement_bps: Option<f64>,
+}
+
+impl TcaMetrics {
+    /// Markout at `horizon_secs` net of fees; positive means the fills made
+    /// money after costs over that horizon
+    pub fn net_edge_bps(&self, horizon_secs: u64) -> Option<f64> {
+        self.markouts_bps.get(&horizon_secs).map(|markout| markout - self.fee_bps)
+    }
+}
+
+/// Metrics for one strategy/market group
+///
+/// `market: None` rows are per-strategy rollups across markets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TcaGroup {
+    pub strategy_id: Option<String>,
+    pub market: Option<String>,
+    pub metrics: TcaMetrics,
+}
+
+/// TCA report over a time range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TcaReport {
+    /// Report generation time
+    pub generated_at: DateTime<Utc>,
+    /// Start of the analyzed order range
+    pub start: DateTime<Utc>,
+    /// End of the analyzed order range
+    pub end: DateTime<Utc>,
+    /// Per strategy/market groups and per-strategy rollups
+    pub groups: Vec<TcaGroup>,
+    /// All orders
+    pub overall: TcaMetrics,
+    /// Price improvement per venue and order type, and per venue and hour
+    #[serde(default)]
+    pub price_improvement: PriceImprovementReport,
+}
+
+impl TcaReport {
+    /// Metrics for a strategy in a market (`None` for the strategy rollup)
+    pub fn group(&self, strategy_id: Option<&str>, market: Option<&str>) -> Option<&TcaMetrics> {
+        self.groups
+            .iter()
+            .find(|g| g.strategy_id.as_deref() == strategy_id && g.market.as_deref() == market)
+            .map(|g| &g.metrics)
+    }
+}
+
+/// Price improvement of fills against their quoted prices
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriceImprovementStats {
+    /// Fills with a quoted price
+    pub fills: usize,
+    /// Fills better than the quote
+    pub improved_fills: usize,
+    /// Fills through the quote
+    pub slipped_fills: usize,
+    /// Filled size
+    pub filled_size: f64,
+    /// Size-weighted mean improvement (negative = slippage)
+    pub avg_improvement_bps: Option<f64>,
+    /// Improvement in quote currency, side * (quote - price) * size
+    pub improvement: f64,
+}
+
+/// Price improvement of one venue and order type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderTypeImprovement {
+    pub venue: String,
+    pub order_type: OrderType,
+    pub stats: PriceImprovementStats,
+}
+
+/// Price improvement of one venue in one hour of the day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourlyImprovement {
+    pub venue: String,
+    /// Hour of the fill (UTC, 0-23)
+    pub hour_utc: u32,
+    pub stats: PriceImprovementStats,
+}
+
+/// Price improvement breakdowns of a report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriceImprovementReport {
+    /// Per venue and order type
+    pub by_order_type: Vec<OrderTypeImprovement>,
+    /// Per venue and hour of day
+    pub by_hour: Vec<HourlyImprovement>,
+}
+
+impl PriceImprovementReport {
+    /// Stats of a venue and order type
+    pub fn order_type(&self, venue: &str, order_type: OrderType) -> Option<&PriceImprovementStats> {
+        self.by_order_type
+            .iter()
+            .find(|g| g.venue == venue && g.order_type == order_type)
+            .map(|g| &g.stats)
+    }
+
+    /// Stats of a venue in an hour of the day (UTC)
+    pub fn hour(&self, venue: &str, hour_utc: u32) -> Option<&PriceImprovementStats> {
+        self.by_hour
+            .iter()
+            .find(|g| g.venue == venue && g.hour_utc == hour_utc)
+            .map(|g| &g.stats)
+    }
+
+    /// Venue hours whose average slippage exceeds `min_slippage_bps`,
+    /// worst first
+    pub fn slipping_hours(&self, min_slippage_bps: f64) -> Vec<&HourlyImprovement> {
+        let mut hours: Vec<&HourlyImprovement> = self
+            .by_hour
+            .iter()
+            .filter(|g| g.stats.avg_improvement_bps.is_some_and(|bps| bps < -min_slippage_bps))
+            .collect();
+        hours.sort_by(|a, b| a.stats.avg_improvement_bps.partial_cmp(&b.stats.avg_improvement_bps).unwrap());
+        hours
+    }
+}
+
+/// Running price improvement totals
+#[derive(Debug, Clone, Default)]
+struct ImprovementAccumulator {
+    stats: PriceImprovementStats,
+    mean: WeightedMean,
+}
+
+impl ImprovementAccumulator {
+    fn add(&mut self, fill: &FillCosts) {
+        let Some((bps, improvement)) = fill.price_improvement else {
+            return;
+        };
+        self.stats.fills += 1;
+        self.stats.filled_size += fill.size;
+        self.stats.improvement += improvement;
+        if bps > 0.0 {
+            self.stats.improved_fills += 1;
+        } else if bps < 0.0 {
+            self.stats.slipped_fills += 1;
+        }
+        self.mean.add(bps, fill.size);
+    }
+
+    fn finish(mut self) -> PriceImprovementStats {
+        self.stats.avg_improvement_bps = self.mean.mean();
+        self.stats
+    }
+}
+
+/// Size-weighted mean accumulator
+#[derive(Debug, Clone, Copy, Default)]
+struct WeightedMean {
+    sum: f64,
+    weight: f64,
+}
+
+impl WeightedMean {
+    fn add(&mut self, value: f64, weight: f64) {
+        self.sum += value * weight;
+        self.weight += weight;
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.weight > 0.0).then(|| self.sum / self.weight)
+    }
+}
+
+/// Running totals for one group
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    orders: usize,
+    filled_orders: usize,
+    fills: usize,
+    ordered_size: f64,
+    filled_size: f64,
+    notional: f64,
+    fees: f64,
+    shortfall: WeightedMean,
+    effective_spread: WeightedMean,
+    spread_capture: WeightedMean,
+    markouts: BTreeMap<u64, WeightedMean>,
+    price_improvement: WeightedMean,
+}
+
+impl Accumulator {
+    fn add_order(&mut self, order: &Order, fills: &[FillCosts]) {
+        self.orders += 1;
+        self.ordered_size += order.size;
+        if !fills.is_empty() {
+            self.filled_orders += 1;
+        }
+
+        for fill in fills {
+            self.fills += 1;
+            self.filled_size += fill.size;
+            self.notional += fill.notional;
+            self.fees += fill.fee;
+            if let Some(shortfall) = fill.shortfall_bps {
+                self.shortfall.add(shortfall, fill.size);
+            }
+            if let Some(capture) = fill.spread_capture_bps {
+                self.spread_capture.add(capture, fill.size);
+                self.effective_spread.add(-2.0 * capture, fill.size);
+            }
+            for (horizon, markout) in &fill.markouts_bps {
+                self.markouts.entry(*horizon).or_default().add(*markout, fill.size);
+            }
+            if let Some((bps, _)) = fill.price_improvement {
+                self.price_improvement.add(bps, fill.size);
+            }
+        }
+    }
+
+    fn finish(&self) -> TcaMetrics {
+        TcaMetrics {
+            orders: self.orders,
+            filled_orders: self.filled_orders,
+            fills: self.fills,
+            ordered_size: self.ordered_size,
+            filled_size: self.filled_size,
+            fill_ratio: if self.ordered_size > 0.0 {
+                (self.filled_size / self.ordered_size).min(1.0)
+            } else {
+                0.0
+            },
+            notional: self.notional,
+            fees: self.fees,
+            fee_bps: if self.notional > 0.0 {
+                self.fees / self.notional * 10_000.0
+            } else {
+                0.0
+            },
+            implementation_shortfall_bps: self.shortfall.mean(),
+            effective_spread_bps: self.effective_spread.mean(),
+            spread_capture_bps: self.spread_capture.mean(),
+            markouts_bps: self
+                .markouts
+                .iter()
+                .filter_map(|(horizon, mean)| mean.mean().map(|m| (*horizon, m)))
+                .collect(),
+            price_improvement_bps: self.price_improvement.mean(),
+        }
+    }
+}
+
+/// Per-fill cost measurements
+#[derive(Debug, Clone)]
+struct FillCosts {
+    timestamp: DateTime<Utc>,
+    size: f64,
+    notional: f64,
+    fee: f64,
+    shortfall_bps: Option<f64>,
+    spread_capture_bps: Option<f64>,
+    markouts_bps: Vec<(u64, f64)>,
+    /// Improvement vs the quote in bps and in quote currency
+    price_improvement: Option<(f64, f64)>,
+}
+
+/// Transaction cost analyzer
+#[derive(Debug, Clone, Default)]
+pub struct TcaAnalyzer {
+    config: TcaConfig,
+}
+
+impl TcaAnalyzer {
+    /// Create an analyzer
+    pub fn new(config: TcaConfig) -> Self {
+        Self { config }
+    }
+
+    /// Analyze orders placed between `start` and `end` with their fills
+    ///
+    /// Markouts whose horizon has no later mid observation are skipped, so
+    /// recent fills only count towards the horizons that have elapsed.
+    pub fn analyze(
+        &self,
+        orders: &[Order],
+        fills: &[Fill],
+        mids: &MidPrices,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> TcaReport {
+        let mut fills_by_order: HashMap<_, Vec<&Fill>> = HashMap::new();
+        for fill in fills {
+            fills_by_order.entry(fill.order_id).or_default().push(fill);
+        }
+
+        let mut groups: BTreeMap<(Option<String>, Option<String>), Accumulator> = BTreeMap::new();
+        let mut overall = Accumulator::default();
+        let mut by_order_type: BTreeMap<(String, String), (OrderType, ImprovementAccumulator)> = BTreeMap::new();
+        let mut by_hour: BTreeMap<(String, u32), ImprovementAccumulator> = BTreeMap::new();
+
+        for order in orders.iter().filter(|o| o.timestamp >= start && o.timestamp <= end) {
+            let costs: Vec<FillCosts> = fills_by_order
+                .get(&order.id)
+                .map(|fills| fills.iter().map(|fill| self.measure(order, fill, mids)).collect())
+                .unwrap_or_default();
+
+            let strategy = order.strategy_id.clone();
+            groups
+                .entry((strategy.clone(), Some(order.market.clone())))
+                .or_default()
+                .add_order(order, &costs);
+            groups.entry((strategy, None)).or_default().add_order(order, &costs);
+            overall.add_order(order, &costs);
+
+            for fill in &costs {
+                by_order_type
+                    .entry((order.venue.clone(), order.order_type.to_string()))
+                    .or_insert_with(|| (order.order_type, ImprovementAccumulator::default()))
+                    .1
+                    .add(fill);
+                by_hour
+                    .entry((order.venue.clone(), fill.timestamp.hour()))
+                    .or_default()
+                    .add(fill);
+            }
+        }
+
+        TcaReport {
+            generated_at: Utc::now(),
+            start,
+            end,
+            groups: groups
+                .into_iter()
+                .map(|((strategy_id, market), acc)| TcaGroup {
+                    strategy_id,
+                    market,
+                    metrics: acc.finish(),
+                })
+                .collect(),
+            overall: overall.finish(),
+            price_improvement: PriceImprovementReport {
+                by_order_type: by_order_type
+                    .into_iter()
+                    .map(|((venue, _), (order_type, acc))| OrderTypeImprovement {
+                        venue,
+                        order_type,
+                        stats: acc.finish(),
+                    })
+                    .collect(),
+                by_hour: by_hour
+                    .into_iter()
+                    .map(|((venue, hour_utc), acc)| HourlyImprovement {
+                        venue,
+                        hour_utc,
+                        stats: acc.finish(),
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    fn measure(&self, order: &Order, fill: &Fill, mids: &MidPrices) -> FillCosts {
+        let sign = match fill.side {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        };
+        let notional = fill.price * fill.size;
+        let fee_bps = if notional > 0.0 {
+            fill.fee / notional * 10_000.0
+        } else {
+            0.0
+        };
+
+        let shortfall_bps = mids
+            .mid_at(&order.market, order.timestamp)
+            .filter(|mid| *mid > 0.0)
+            .map(|arrival| sign * (fill.price - arrival) / arrival * 10_000.0 + fee_bps);
+
+        let spread_capture_bps = mids
+            .mid_at(&fill.market, fill.timestamp)
+            .filter(|mid| *mid > 0.0)
+            .map(|mid| sign * (mid - fill.price) / mid * 10_000.0);
+
+        let markouts_bps = if fill.price > 0.0 {
+            self.config
+                .markout_horizons_secs
+                .iter()
+                .filter_map(|horizon| {
+                    let at = fill.timestamp + Duration::seconds(*horizon as i64);
+                    mids.mid_after(&fill.market, at)
+                        .map(|mid| (*horizon, sign * (mid - fill.price) / fill.price * 10_000.0))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Quote recorded with the fill, else the order's limit price
+        let price_improvement = fill
+            .quoted_price
+            .or(order.price)
+            .filter(|quote| *quote > 0.0)
+            .map(|quote| {
+                let improvement = sign * (quote - fill.price);
+                (improvement / quote * 10_000.0, improvement * fill.size)
+            });
+
+        FillCosts {
+            timestamp: fill.timestamp,
+            size: fill.size,
+            notional,
+            fee: fill.fee,
+            shortfall_bps,
+            spread_capture_bps,
+            markouts_bps,
+            price_improvement,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tca_report() {
+        let t0 = Utc::now() - Duration::minutes(10);
+        let mut mids = MidPrices::new();
+        mids.add("m1", t0, 0.50);
+        mids.add("m1", t0 + Duration::seconds(10), 0.50);
+        mids.add("m1", t0 + Duration::seconds(20), 0.48);
+
+        let mut order = Order::new("polymarket", "m1", Side::Buy, OrderType::Limit, 100.0);
+        order.timestamp = t0;
+        order.price = Some(0.49);
+        order.strategy_id = Some("mm_1".to_string());
+
+        let mut fill = Fill::new(order.id, "polymarket", "m1", Side::Buy, 0.49, 50.0, 0.01225, "USDC");
+        fill.timestamp = t0 + Duration::seconds(10);
+
+        let unfilled = Order {
+            id: uuid::Uuid::new_v4(),
+            ..order.clone()
+        };
+
+        let analyzer = TcaAnalyzer::new(TcaConfig {
+            markout_horizons_secs: vec![5, 3600],
+        });
+        let report = analyzer.analyze(
+            &[order, unfilled],
+            &[fill],
+            &mids,
+            t0 - Duration::minutes(1),
+            Utc::now(),
+        );
+
+        let metrics = report.group(Some("mm_1"), Some("m1")).unwrap();
+        assert_eq!(metrics.orders, 2);
+        assert_eq!(metrics.filled_orders, 1);
+        assert_eq!(metrics.fill_ratio, 0.25);
+        assert!((metrics.fee_bps - 5.0).abs() < 1e-9);
+
+        // Bought 1 cent below a 0.50 arrival mid, paid 5 bps in fees
+        assert!((metrics.implementation_shortfall_bps.unwrap() - (-200.0 + 5.0)).abs() < 1e-6);
+        assert!((metrics.spread_capture_bps.unwrap() - 200.0).abs() < 1e-6);
+        assert!((metrics.effective_spread_bps.unwrap() + 400.0).abs() < 1e-6);
+
+        // Mid drops to 0.48 five seconds later; the 1h horizon hasn't elapsed
+        let markout = metrics.markouts_bps[&5];
+        assert!((markout - (0.48 - 0.49) / 0.49 * 10_000.0).abs() < 1e-6);
+        assert!(!metrics.markouts_bps.contains_key(&3600));
+        assert!(metrics.net_edge_bps(5).unwrap() < markout);
+
+        assert_eq!(report.group(Some("mm_1"), None).unwrap().orders, 2);
+        assert_eq!(report.overall.fills, 1);
+        // Filled at the limit
+        assert_eq!(metrics.price_improvement_bps, Some(0.0));
+    }
+
+    #[test]
+    fn test_price_improvement_by_venue_and_hour() {
+        let day = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc() - Duration::days(1);
+        let at = |hour: i64| day + Duration::hours(hour);
+
+        let order = |venue: &str, side: Side, order_type: OrderType, price: Option<f64>, hour: i64| {
+            let mut order = Order::new(venue, "m1", side, order_type, 100.0);
+            order.price = price;
+            order.timestamp = at(hour);
+            order
+        };
+        let fill = |order: &Order, price: f64| {
+            let mut fill = Fill::new(order.id, order.venue.clone(), "m1", order.side, price, 100.0, 0.0, "USDC");
+            fill.timestamp = order.timestamp;
+            fill
+        };
+
+        // Limit buy at 0.50 filled at 0.49; limit sell at 0.60 filled at 0.61
+        let improved_buy = order("polymarket", Side::Buy, OrderType::Limit, Some(0.50), 9);
+        let improved_sell = order("polymarket", Side::Sell, OrderType::Limit, Some(0.60), 9);
+        // A stop-limit slips through its limit at 14:00
+        let slipped = order("polymarket", Side::Buy, OrderType::StopLimit, Some(0.40), 14);
+        // Market order with the quote recorded on the fill, on another venue
+        let market = order("kalshi", Side::Sell, OrderType::Market, None, 14);
+        let orders = vec![improved_buy.clone(), improved_sell.clone(), slipped.clone(), market.clone()];
+        let fills = vec![
+            fill(&improved_buy, 0.49),
+            fill(&improved_sell, 0.61),
+            fill(&slipped, 0.42),
+            fill(&market, 0.50).with_quoted_price(0.50),
+        ];
+        assert!(fills[0].price_improvement_bps().is_none());
+        assert!(fills[3].price_improvement_bps().unwrap().abs() < 1e-9);
+
+        let report = TcaAnalyzer::default().analyze(&orders, &fills, &MidPrices::new(), at(0), at(24));
+        let improvement = &report.price_improvement;
+
+        let limit = improvement.order_type("polymarket", OrderType::Limit).unwrap();
+        assert_eq!((limit.fills, limit.improved_fills, limit.slipped_fills), (2, 2, 0));
+        assert!((limit.improvement - 2.0).abs() < 1e-9);
+        let expected = (0.01 / 0.50 + 0.01 / 0.60) / 2.0 * 10_000.0;
+        assert!((limit.avg_improvement_bps.unwrap() - expected).abs() < 1e-6);
+
+        let stop = improvement.order_type("polymarket", OrderType::StopLimit).unwrap();
+        assert_eq!(stop.slipped_fills, 1);
+        assert!((stop.avg_improvement_bps.unwrap() + 500.0).abs() < 1e-6);
+
+        assert_eq!(improvement.hour("polymarket", 9).unwrap().fills, 2);
+        let slipping = improvement.slipping_hours(10.0);
+        assert_eq!(slipping.len(), 1);
+        assert_eq!((slipping[0].venue.as_str(), slipping[0].hour_utc), ("polymarket", 14));
+        assert_eq!(improvement.hour("kalshi", 14).unwrap().avg_improvement_bps, Some(0.0));
+        // Size-weighted over all four fills, (200 + 166.7 - 500 + 0) / 4: the
+        // slipped stop outweighs the two improved limits
+        let overall = report.overall.price_improvement_bps.unwrap();
+        assert!((overall + 100.0 / 3.0).abs() < 1e-6);
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 /// Metric data point for storage
@@ -159,6 +159,18 @@ pub struct Order {
     pub client_order_id: String,
     pub venue_order_id: Option<String>,
     pub time_in_force: Option<String>,
+    /// Correlation ID shared by everything caused by one decision
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// Owning strategy
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+    /// Bot session (one per process run)
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Free-form labels, e.g. the signal behind the order
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
 
 impl Order {
@@ -182,6 +194,10 @@ impl Order {
             client_order_id: Uuid::new_v4().to_string(),
             venue_order_id: None,
             time_in_force: None,
+            correlation_id: None,
+            strategy_id: None,
+            session_id: None,
+            tags: BTreeMap::new(),
         }
     }
 
@@ -194,6 +210,26 @@ impl Order {
         self.status = status;
         self
     }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_strategy_id(mut self, strategy_id: impl Into<String>) -> Self {
+        self.strategy_id = Some(strategy_id.into());
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
 }
 
 /// Fill/Trade record
@@ -211,6 +247,10 @@ pub struct Fill {
     pub fee_currency: String,
     pub trade_id: Option<String>,
     pub liquidity: Option<String>,
+    /// Limit price of the order when it filled (None = market order or
+    /// unknown; `store_fill` falls back to the stored order's price)
+    #[serde(default)]
+    pub quoted_price: Option<f64>,
 }
 
 impl Fill {
@@ -238,8 +278,27 @@ impl Fill {
             fee_currency: fee_currency.into(),
             trade_id: None,
             liquidity: None,
+            quoted_price: None,
         }
     }
+
+    pub fn with_quoted_price(mut self, price: f64) -> Self {
+        self.quoted_price = Some(price);
+        self
+    }
+
+    /// Fill price better than the quoted (limit) price, in bps of the quote
+    ///
+    /// Positive = improvement (bought below / sold above the limit),
+    /// negative = slippage through the limit.
+    pub fn price_improvement_bps(&self) -> Option<f64> {
+        let quoted = self.quoted_price.filter(|p| *p > 0.0)?;
+        let sign = match self.side {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        };
+        Some(sign * (quoted - self.price) / quoted * 10_000.0)
+    }
 }
 
 /// Position snapshot
@@ -281,6 +340,155 @@ impl PositionSnapshot {
     }
 }
 
+/// Settlement of a position in a resolved market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketSettlementRecord {
+    pub timestamp: DateTime<Utc>,
+    pub market: String,
+    /// Strategy whose position was settled (`None` for the bot's net
+    /// position in the execution engine)
+    pub strategy_id: Option<String>,
+    /// Payout per share (1.0 if the market resolved YES, 0.0 if NO)
+    pub payout: f64,
+    /// Settled position size (positive = long)
+    pub size: f64,
+    /// Entry price of the settled position, when known
+    pub avg_entry_price: Option<f64>,
+    /// PnL realized by the settlement, when known
+    pub realized_pnl: Option<f64>,
+}
+
+/// Recorded market data tick (top of book and last trade)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketTickRecord {
+    pub timestamp: DateTime<Utc>,
+    pub market: String,
+    pub bid: Option<f64>,
+    pub bid_size: Option<f64>,
+    pub ask: Option<f64>,
+    pub ask_size: Option<f64>,
+    pub last: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub sequence: Option<i64>,
+}
+
+/// Order status change, recorded whenever a stored order's status changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderStatusTransition {
+    pub order_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    /// Previous status (`None` for the initial placement)
+    pub from_status: Option<OrderStatus>,
+    pub to_status: OrderStatus,
+    /// Why the status changed (e.g. a cancel reason), if recorded
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Pre-trade risk decision record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskDecisionRecord {
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    /// Order the decision was made for (`None` if no order was created)
+    pub order_id: Option<Uuid>,
+    pub correlation_id: Option<String>,
+    pub strategy_id: Option<String>,
+    pub session_id: Option<String>,
+    pub market: String,
+    pub allowed: bool,
+    pub violated_policies: Vec<String>,
+}
+
+impl RiskDecisionRecord {
+    pub fn new(market: impl Into<String>, allowed: bool, violated_policies: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            order_id: None,
+            correlation_id: None,
+            strategy_id: None,
+            session_id: None,
+            market: market.into(),
+            allowed,
+            violated_policies,
+        }
+    }
+
+    /// Attribute the decision to an order, copying its correlation, strategy
+    /// and session IDs
+    pub fn for_order(mut self, order: &Order) -> Self {
+        self.order_id = Some(order.id);
+        self.correlation_id = order.correlation_id.clone();
+        self.strategy_id = order.strategy_id.clone();
+        self.session_id = order.session_id.clone();
+        self
+    }
+}
+
+/// One event in an order's lifecycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    RiskDecision(RiskDecisionRecord),
+    StatusChange(OrderStatusTransition),
+    Fill(Fill),
+}
+
+impl LifecycleEvent {
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            LifecycleEvent::RiskDecision(decision) => decision.timestamp,
+            LifecycleEvent::StatusChange(transition) => transition.timestamp,
+            LifecycleEvent::Fill(fill) => fill.timestamp,
+        }
+    }
+
+    /// Tie-break for events with equal timestamps: the risk decision comes
+    /// before the placement it allowed, fills before the status they cause
+    fn rank(&self) -> u8 {
+        match self {
+            LifecycleEvent::RiskDecision(_) => 0,
+            LifecycleEvent::Fill(_) => 1,
+            LifecycleEvent::StatusChange(_) => 2,
+        }
+    }
+}
+
+/// Full history of one order for incident forensics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderLifecycle {
+    pub order: Order,
+    /// Risk decisions, status transitions and fills in chronological order
+    pub events: Vec<LifecycleEvent>,
+}
+
+impl OrderLifecycle {
+    pub fn new(
+        order: Order,
+        decisions: Vec<RiskDecisionRecord>,
+        transitions: Vec<OrderStatusTransition>,
+        fills: Vec<Fill>,
+    ) -> Self {
+        let mut events: Vec<LifecycleEvent> = decisions
+            .into_iter()
+            .map(LifecycleEvent::RiskDecision)
+            .chain(transitions.into_iter().map(LifecycleEvent::StatusChange))
+            .chain(fills.into_iter().map(LifecycleEvent::Fill))
+            .collect();
+        events.sort_by_key(|event| (event.timestamp(), event.rank()));
+
+        Self { order, events }
+    }
+
+    pub fn fills(&self) -> impl Iterator<Item = &Fill> {
+        self.events.iter().filter_map(|event| match event {
+            LifecycleEvent::Fill(fill) => Some(fill),
+            _ => None,
+        })
+    }
+}
+
 /// Filter for querying orders
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OrderFilters {
@@ -289,6 +497,66 @@ pub struct OrderFilters {
     pub side: Option<Side>,
     pub status: Option<OrderStatus>,
     pub client_order_id: Option<String>,
+    pub correlation_id: Option<String>,
+    pub strategy_id: Option<String>,
+    pub session_id: Option<String>,
+}
+
+/// Selects metric rows for targeted deletion
+///
+/// Criteria are ANDed; label values must all match (JSONB containment).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricSelector {
+    pub metric_name: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl MetricSelector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match a single metric name
+    pub fn with_name(mut self, metric_name: impl Into<String>) -> Self {
+        self.metric_name = Some(metric_name.into());
+        self
+    }
+
+    /// Require a label value
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Restrict to `start <= timestamp <= end`
+    pub fn between(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.start = Some(start);
+        self.end = Some(end);
+        self
+    }
+
+    /// True if nothing is constrained (would match every row)
+    pub fn is_unbounded(&self) -> bool {
+        self.metric_name.is_none() && self.labels.is_empty() && self.start.is_none() && self.end.is_none()
+    }
+}
+
+/// Outcome of a targeted metric deletion or instance purge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDeletionReport {
+    pub selector: MetricSelector,
+    /// Rows matching the selector when the deletion started
+    pub matched: i64,
+    /// Rows deleted (0 for a dry run)
+    pub deleted: i64,
+    /// Time-window batches executed
+    pub batches: usize,
+    pub dry_run: bool,
+    pub executed_at: DateTime<Utc>,
+    pub duration: Duration,
 }
 
 /// Retention policy report
@@ -347,4 +615,57 @@ mod tests {
         assert_eq!(fill.price, 0.52);
         assert_eq!(fill.size, 100.0);
     }
+
+    #[test]
+    fn test_order_lifecycle_ordering() {
+        let t0 = Utc::now();
+        let order = Order::new("polymarket", "0x123abc", Side::Buy, OrderType::Limit, 100.0)
+            .with_correlation_id("corr-1")
+            .with_strategy_id("mm_1");
+
+        let mut decision = RiskDecisionRecord::new("0x123abc", true, Vec::new()).for_order(&order);
+        decision.timestamp = t0;
+
+        let transition = |from: Option<OrderStatus>, to: OrderStatus, at: DateTime<Utc>| {
+            OrderStatusTransition {
+                order_id: order.id,
+                timestamp: at,
+                from_status: from,
+                to_status: to,
+                reason: None,
+            }
+        };
+        let mut fill = Fill::new(order.id, "polymarket", "0x123abc", Side::Buy, 0.52, 100.0, 0.1, "USDC");
+        fill.timestamp = t0 + Duration::seconds(5);
+
+        let lifecycle = OrderLifecycle::new(
+            order.clone(),
+            vec![decision],
+            vec![
+                transition(Some(OrderStatus::Open), OrderStatus::Filled, t0 + Duration::seconds(5)),
+                transition(None, OrderStatus::Open, t0),
+            ],
+            vec![fill],
+        );
+
+        let kinds: Vec<&str> = lifecycle
+            .events
+            .iter()
+            .map(|event| match event {
+                LifecycleEvent::RiskDecision(_) => "risk",
+                LifecycleEvent::StatusChange(_) => "status",
+                LifecycleEvent::Fill(_) => "fill",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["risk", "status", "fill", "status"]);
+        assert_eq!(lifecycle.fills().count(), 1);
+
+        match &lifecycle.events[0] {
+            LifecycleEvent::RiskDecision(decision) => {
+                assert_eq!(decision.order_id, Some(order.id));
+                assert_eq!(decision.correlation_id.as_deref(), Some("corr-1"));
+            }
+            _ => panic!("Expected risk decision first"),
+        }
+    }
 }
@@ -0,0 +1,306 @@
+//! Fill simulator calibration from live executions
+//!
+//! Backtests are only as good as their fill model. [`FillCalibrator`]
+//! takes historical executions (typically fills read back from storage),
+//! estimates realized slippage, fee and latency distributions per market,
+//! and produces a [`FillSimulatorConfig`] that matches production
+//! execution quality.
+//!
+//! Slippage is measured against each order's reference price (its limit
+//! price or the arrival price) and signed so positive values are adverse:
+//! a buy filled above its reference, or a sell filled below it.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::fill_simulator::FillSimulatorConfig;
+use crate::types::Side;
+
+/// Default minimum fills before a market gets its own calibration
+const DEFAULT_MIN_SAMPLES: usize = 20;
+
+/// One historical execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionSample {
+    /// Market identifier
+    pub market: String,
+    /// Order side
+    pub side: Side,
+    /// Reference price (order limit price or arrival price)
+    pub reference_price: f64,
+    /// Realized fill price
+    pub fill_price: f64,
+    /// Fill size
+    pub size: f64,
+    /// Fee paid (negative for rebates)
+    pub fee: f64,
+    /// Whether the fill provided liquidity, if known
+    pub is_maker: Option<bool>,
+    /// Order submission to fill latency in milliseconds, if known
+    pub latency_ms: Option<f64>,
+}
+
+impl ExecutionSample {
+    /// Adverse slippage in basis points of the reference price
+    pub fn slippage_bps(&self) -> f64 {
+        let diff = match self.side {
+            Side::Buy => self.fill_price - self.reference_price,
+            Side::Sell => self.reference_price - self.fill_price,
+        };
+        diff / self.reference_price * 10000.0
+    }
+
+    /// Fee in basis points of fill notional
+    pub fn fee_bps(&self) -> f64 {
+        self.fee / (self.fill_price * self.size) * 10000.0
+    }
+
+    fn is_valid(&self) -> bool {
+        self.reference_price > 0.0
+            && self.fill_price > 0.0
+            && self.size > 0.0
+            && self.fee.is_finite()
+    }
+}
+
+/// Summary statistics for one measured quantity
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    /// Summarize values; `None` if there are none
+    pub fn from_values(mut values: Vec<f64>) -> Option<Self> {
+        values.retain(|v| v.is_finite());
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let quantile = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+        Some(Self {
+            count: values.len(),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            min: values[0],
+            p50: quantile(0.5),
+            p95: quantile(0.95),
+            p99: quantile(0.99),
+            max: values[values.len() - 1],
+        })
+    }
+}
+
+/// Calibrated execution quality for one market (or all markets)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketCalibration {
+    /// Market identifier (`*` for the all-markets aggregate)
+    pub market: String,
+    /// Number of fills used
+    pub fills: usize,
+    /// Fraction of fills with known liquidity that were maker fills
+    pub maker_fraction: Option<f64>,
+    /// Slippage of taker (or unknown-liquidity) fills
+    pub slippage_bps: Option<Distribution>,
+    /// Fees on maker fills
+    pub maker_fee_bps: Option<Distribution>,
+    /// Fees on taker fills
+    pub taker_fee_bps: Option<Distribution>,
+    /// Submission to fill latency
+    pub latency_ms: Option<Distribution>,
+}
+
+impl MarketCalibration {
+    fn from_samples(market: &str, samples: &[&ExecutionSample]) -> Self {
+        let with_liquidity: Vec<bool> = samples.iter().filter_map(|s| s.is_maker).collect();
+        let maker_fraction = (!with_liquidity.is_empty()).then(|| {
+            with_liquidity.iter().filter(|m| **m).count() as f64 / with_liquidity.len() as f64
+        });
+
+        let collect = |filter: fn(&ExecutionSample) -> bool, value: fn(&ExecutionSample) -> f64| {
+            Distribution::from_values(samples.iter().copied().filter(|s| filter(s)).map(value).collect())
+        };
+
+        Self {
+            market: market.to_string(),
+            fills: samples.len(),
+            maker_fraction,
+            slippage_bps: collect(|s| s.is_maker != Some(true), ExecutionSample::slippage_bps),
+            maker_fee_bps: collect(|s| s.is_maker == Some(true), ExecutionSample::fee_bps),
+            taker_fee_bps: collect(|s| s.is_maker != Some(true), ExecutionSample::fee_bps),
+            latency_ms: Distribution::from_values(samples.iter().filter_map(|s| s.latency_ms).collect()),
+        }
+    }
+
+    /// Fill simulator config using the measured means, keeping `base` for
+    /// anything that was not observed (including fill probability, which
+    /// fills alone cannot measure)
+    pub fn fill_simulator_config(&self, base: &FillSimulatorConfig) -> FillSimulatorConfig {
+        FillSimulatorConfig {
+            slippage_bps: self.slippage_bps.as_ref().map_or(base.slippage_bps, |d| d.mean),
+            fill_probability: base.fill_probability,
+            taker_fee_bps: self.taker_fee_bps.as_ref().map_or(base.taker_fee_bps, |d| d.mean),
+            maker_fee_bps: self.maker_fee_bps.as_ref().map_or(base.maker_fee_bps, |d| d.mean),
+            adverse_selection: base.adverse_selection,
+            adverse_horizon_ticks: base.adverse_horizon_ticks,
+            seed: base.seed,
+        }
+    }
+}
+
+/// Calibration result for a set of executions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    /// All markets combined
+    pub overall: MarketCalibration,
+    /// Per-market calibrations (only markets with enough fills)
+    pub markets: BTreeMap<String, MarketCalibration>,
+    /// Samples discarded as invalid (non-positive prices or size)
+    pub discarded: usize,
+}
+
+impl CalibrationReport {
+    /// Calibration for a market, falling back to the overall one
+    pub fn for_market(&self, market: &str) -> &MarketCalibration {
+        self.markets.get(market).unwrap_or(&self.overall)
+    }
+
+    /// Fill simulator config for a market (overall if the market had too
+    /// few fills)
+    pub fn fill_simulator_config(&self, market: &str, base: &FillSimulatorConfig) -> FillSimulatorConfig {
+        self.for_market(market).fill_simulator_config(base)
+    }
+}
+
+/// Estimates execution quality distributions from historical fills
+#[derive(Debug, Clone)]
+pub struct FillCalibrator {
+    samples: Vec<ExecutionSample>,
+    min_samples: usize,
+}
+
+impl FillCalibrator {
+    pub fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+            min_samples: DEFAULT_MIN_SAMPLES,
+        }
+    }
+
+    /// Minimum fills before a market gets its own calibration
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    pub fn add_sample(&mut self, sample: ExecutionSample) {
+        self.samples.push(sample);
+    }
+
+    pub fn add_samples(&mut self, samples: impl IntoIterator<Item = ExecutionSample>) {
+        self.samples.extend(samples);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn calibrate(&self) -> CalibrationReport {
+        let valid: Vec<&ExecutionSample> = self.samples.iter().filter(|s| s.is_valid()).collect();
+
+        let mut by_market: BTreeMap<&str, Vec<&ExecutionSample>> = BTreeMap::new();
+        for sample in &valid {
+            by_market.entry(sample.market.as_str()).or_default().push(sample);
+        }
+
+        let markets = by_market
+            .into_iter()
+            .filter(|(_, samples)| samples.len() >= self.min_samples)
+            .map(|(market, samples)| (market.to_string(), MarketCalibration::from_samples(market, &samples)))
+            .collect();
+
+        CalibrationReport {
+            overall: MarketCalibration::from_samples("*", &valid),
+            markets,
+            discarded: self.samples.len() - valid.len(),
+        }
+    }
+}
+
+impl Default for FillCalibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn sample(market: &str, side: Side, fill_price: f64, is_maker: bool, latency_ms: f64) -> ExecutionSample {
+        ExecutionSample {
+            market: market.to_string(),
+            side,
+            reference_price: 0.50,
+            fill_price,
+            size: 100.0,
+            fee: if is_maker { -0.025 } else { 0.10 * fill_price },
+            is_maker: Some(is_maker),
+            latency_ms: Some(latency_ms),
+        }
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut calibrator = FillCalibrator::new().with_min_samples(3);
+        calibrator.add_samples([
+            // Taker buys 10 and 20 bps above reference, sell 30 bps below
+            sample("m1", Side::Buy, 0.5005, false, 100.0),
+            sample("m1", Side::Buy, 0.5010, false, 200.0),
+            sample("m1", Side::Sell, 0.4985, false, 300.0),
+            sample("m1", Side::Buy, 0.50, true, 1000.0),
+            sample("m2", Side::Buy, 0.51, false, 50.0),
+        ]);
+        calibrator.add_sample(ExecutionSample {
+            reference_price: 0.0,
+            ..sample("m1", Side::Buy, 0.5, false, 0.0)
+        });
+
+        let report = calibrator.calibrate();
+        assert_eq!(report.discarded, 1);
+        assert_eq!(report.overall.fills, 5);
+        assert!(!report.markets.contains_key("m2"));
+
+        let m1 = report.for_market("m1");
+        assert_eq!(m1.fills, 4);
+        assert_relative_eq!(m1.maker_fraction.unwrap(), 0.25);
+        let slippage = m1.slippage_bps.as_ref().unwrap();
+        assert_eq!(slippage.count, 3);
+        assert_relative_eq!(slippage.mean, 20.0, epsilon = 1e-9);
+        assert_relative_eq!(slippage.max, 30.0, epsilon = 1e-9);
+        assert_relative_eq!(m1.taker_fee_bps.as_ref().unwrap().mean, 10.0, epsilon = 1e-9);
+        assert_relative_eq!(m1.maker_fee_bps.as_ref().unwrap().mean, -5.0, epsilon = 1e-9);
+        assert_eq!(m1.latency_ms.as_ref().unwrap().p50, 300.0);
+
+        let base = FillSimulatorConfig::default();
+        let config = report.fill_simulator_config("m1", &base);
+        assert_relative_eq!(config.slippage_bps, 20.0, epsilon = 1e-9);
+        assert_relative_eq!(config.taker_fee_bps, 10.0, epsilon = 1e-9);
+        assert_eq!(config.fill_probability, base.fill_probability);
+
+        // Unknown markets fall back to the overall calibration
+        assert_eq!(report.for_market("m2").market, "*");
+    }
+}
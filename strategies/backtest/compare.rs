@@ -0,0 +1,359 @@
+//! Baseline vs candidate backtest comparison
+//!
+//! [`compare`] diffs two [`BacktestResult`]s run over the same data, e.g.
+//! the strategy on `main` and on a branch. Besides PnL, drawdown and
+//! turnover deltas and per-market trade count changes, it runs a one-sided
+//! Welch t-test on the per-step returns of both equity curves, so a CI gate
+//! can fail on a return drop that is unlikely to be noise rather than on
+//! any drop at all.
+//!
+//! ```rust,ignore
+//! let comparison = compare(&baseline, &candidate, &CompareConfig::default());
+//! println!("{}", comparison.summary());
+//! if comparison.is_regression() {
+//!     std::process::exit(1);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use super::engine::BacktestResult;
+
+/// Regression thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareConfig {
+    /// Significance level of the return test
+    pub significance_level: f64,
+    /// Largest tolerated drop in total return (absolute, USD; unset = no limit)
+    pub max_return_drop: Option<f64>,
+    /// Largest tolerated increase in max drawdown (percentage points; unset = no limit)
+    pub max_drawdown_increase_pct: Option<f64>,
+}
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        Self {
+            significance_level: 0.05,
+            max_return_drop: None,
+            max_drawdown_increase_pct: None,
+        }
+    }
+}
+
+/// Baseline and candidate values of one metric
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub candidate: f64,
+    /// `candidate - baseline`
+    pub delta: f64,
+}
+
+impl MetricDelta {
+    pub fn new(baseline: f64, candidate: f64) -> Self {
+        Self {
+            baseline,
+            candidate,
+            delta: candidate - baseline,
+        }
+    }
+}
+
+/// Trade activity change in one market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketDelta {
+    /// Market identifier
+    pub market: String,
+    /// Number of trades
+    pub trades: MetricDelta,
+    /// Traded notional (price x size)
+    pub turnover: MetricDelta,
+}
+
+/// One-sided Welch t-test of candidate returns being lower than baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignificanceTest {
+    /// Mean per-step return of the baseline
+    pub baseline_mean: f64,
+    /// Mean per-step return of the candidate
+    pub candidate_mean: f64,
+    /// Welch t statistic (negative when the candidate is worse)
+    pub t_stat: f64,
+    /// Welch-Satterthwaite degrees of freedom
+    pub degrees_of_freedom: f64,
+    /// Probability of a t statistic at least this low if the means are equal
+    pub p_value: f64,
+    /// Whether `p_value` is below the configured significance level
+    pub significant: bool,
+}
+
+/// Result of comparing a candidate backtest against a baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestComparison {
+    /// Total return (PnL, USD)
+    pub total_return: MetricDelta,
+    /// Total return (percentage)
+    pub total_return_pct: MetricDelta,
+    /// Annualized Sharpe ratio
+    pub sharpe_ratio: MetricDelta,
+    /// Max drawdown (percentage)
+    pub max_drawdown_pct: MetricDelta,
+    /// Number of trades
+    pub num_trades: MetricDelta,
+    /// Traded notional across markets
+    pub turnover: MetricDelta,
+    /// Per-market changes, for every market traded by either run
+    pub markets: Vec<MarketDelta>,
+    /// Return difference test; `None` if either run has fewer than two returns
+    pub significance: Option<SignificanceTest>,
+    /// Reasons the candidate counts as a regression
+    pub regressions: Vec<String>,
+}
+
+impl BacktestComparison {
+    /// Whether any regression threshold was crossed
+    pub fn is_regression(&self) -> bool {
+        !self.regressions.is_empty()
+    }
+
+    /// Human-readable report
+    pub fn summary(&self) -> String {
+        let mut lines = vec![
+            format!("{:<16} {:>14} {:>14} {:>14}", "metric", "baseline", "candidate", "delta"),
+            row("total_return", &self.total_return),
+            row("total_return_pct", &self.total_return_pct),
+            row("sharpe_ratio", &self.sharpe_ratio),
+            row("max_drawdown_pct", &self.max_drawdown_pct),
+            row("num_trades", &self.num_trades),
+            row("turnover", &self.turnover),
+        ];
+        for market in self.markets.iter().filter(|m| m.trades.delta != 0.0 || m.turnover.delta != 0.0) {
+            lines.push(row(&format!("{} trades", market.market), &market.trades));
+        }
+        if let Some(test) = &self.significance {
+            lines.push(format!(
+                "return test: t = {:.3}, df = {:.1}, p = {:.4}{}",
+                test.t_stat,
+                test.degrees_of_freedom,
+                test.p_value,
+                if test.significant { " (significant)" } else { "" }
+            ));
+        }
+        if self.is_regression() {
+            lines.extend(self.regressions.iter().map(|r| format!("REGRESSION: {}", r)));
+        } else {
+            lines.push("no regression".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+fn row(name: &str, metric: &MetricDelta) -> String {
+    format!("{:<16} {:>14.4} {:>14.4} {:>+14.4}", name, metric.baseline, metric.candidate, metric.delta)
+}
+
+/// Compare a candidate backtest against a baseline
+pub fn compare(baseline: &BacktestResult, candidate: &BacktestResult, config: &CompareConfig) -> BacktestComparison {
+    let baseline_markets = market_activity(baseline);
+    let candidate_markets = market_activity(candidate);
+    let mut names: Vec<&String> = baseline_markets.keys().chain(candidate_markets.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let markets: Vec<MarketDelta> = names
+        .into_iter()
+        .map(|market| {
+            let (base_trades, base_turnover) = baseline_markets.get(market).copied().unwrap_or_default();
+            let (cand_trades, cand_turnover) = candidate_markets.get(market).copied().unwrap_or_default();
+            MarketDelta {
+                market: market.clone(),
+                trades: MetricDelta::new(base_trades as f64, cand_trades as f64),
+                turnover: MetricDelta::new(base_turnover, cand_turnover),
+            }
+        })
+        .collect();
+
+    let significance = welch_test(&returns(baseline), &returns(candidate), config.significance_level);
+
+    let total_return = MetricDelta::new(baseline.total_return, candidate.total_return);
+    let max_drawdown_pct = MetricDelta::new(baseline.max_drawdown_pct, candidate.max_drawdown_pct);
+
+    let mut regressions = Vec::new();
+    if let Some(test) = significance.as_ref().filter(|t| t.significant) {
+        regressions.push(format!(
+            "returns significantly lower (p = {:.4} < {})",
+            test.p_value, config.significance_level
+        ));
+    }
+    if let Some(max_drop) = config.max_return_drop.filter(|max| -total_return.delta > *max) {
+        regressions.push(format!("total return dropped by {:.2} (max {:.2})", -total_return.delta, max_drop));
+    }
+    if let Some(max_increase) = config.max_drawdown_increase_pct.filter(|max| max_drawdown_pct.delta > *max) {
+        regressions.push(format!("max drawdown rose by {:.2}pp (max {:.2}pp)", max_drawdown_pct.delta, max_increase));
+    }
+
+    BacktestComparison {
+        total_return,
+        total_return_pct: MetricDelta::new(baseline.total_return_pct, candidate.total_return_pct),
+        sharpe_ratio: MetricDelta::new(baseline.sharpe_ratio, candidate.sharpe_ratio),
+        max_drawdown_pct,
+        num_trades: MetricDelta::new(baseline.num_trades as f64, candidate.num_trades as f64),
+        turnover: MetricDelta::new(
+            markets.iter().map(|m| m.turnover.baseline).sum(),
+            markets.iter().map(|m| m.turnover.candidate).sum(),
+        ),
+        markets,
+        significance,
+        regressions,
+    }
+}
+
+/// Trade count and turnover per market
+fn market_activity(result: &BacktestResult) -> BTreeMap<String, (usize, f64)> {
+    let mut activity: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+    for trade in &result.trades {
+        let entry = activity.entry(trade.market.clone()).or_default();
+        entry.0 += 1;
+        entry.1 += (trade.price * trade.size).abs();
+    }
+    activity
+}
+
+/// Per-step returns of the equity curve
+fn returns(result: &BacktestResult) -> Vec<f64> {
+    result
+        .pnl_by_day
+        .windows(2)
+        .filter(|w| w[0].1 > 0.0)
+        .map(|w| (w[1].1 - w[0].1) / w[0].1)
+        .collect()
+}
+
+fn mean_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+fn welch_test(baseline: &[f64], candidate: &[f64], significance_level: f64) -> Option<SignificanceTest> {
+    if baseline.len() < 2 || candidate.len() < 2 {
+        return None;
+    }
+    let (baseline_mean, baseline_var) = mean_variance(baseline);
+    let (candidate_mean, candidate_var) = mean_variance(candidate);
+    let se_b = baseline_var / baseline.len() as f64;
+    let se_c = candidate_var / candidate.len() as f64;
+    let se = (se_b + se_c).sqrt();
+
+    let (t_stat, degrees_of_freedom, p_value) = if se < 1e-15 {
+        // Both curves are deterministic: any drop is certain
+        let p = if candidate_mean < baseline_mean { 0.0 } else { 1.0 };
+        (0.0, (baseline.len() + candidate.len() - 2) as f64, p)
+    } else {
+        let t = (candidate_mean - baseline_mean) / se;
+        let df = (se_b + se_c).powi(2)
+            / (se_b.powi(2) / (baseline.len() - 1) as f64 + se_c.powi(2) / (candidate.len() - 1) as f64);
+        let p = StudentsT::new(0.0, 1.0, df).map(|dist| dist.cdf(t)).unwrap_or(1.0);
+        (t, df, p)
+    };
+
+    Some(SignificanceTest {
+        baseline_mean,
+        candidate_mean,
+        t_stat,
+        degrees_of_freedom,
+        p_value,
+        significant: p_value < significance_level,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Side, Trade};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn result(step_returns: &[f64], trades: &[(&str, f64, f64)]) -> BacktestResult {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let mut equity = 10000.0;
+        let mut pnl_by_day = vec![(start, equity)];
+        for (i, r) in step_returns.iter().enumerate() {
+            equity *= 1.0 + r;
+            pnl_by_day.push((start + Duration::minutes(i as i64 + 1), equity));
+        }
+        BacktestResult {
+            total_return: equity - 10000.0,
+            total_return_pct: (equity - 10000.0) / 100.0,
+            sharpe_ratio: 0.0,
+            max_drawdown: 0.0,
+            max_drawdown_pct: 0.0,
+            win_rate: 0.0,
+            num_trades: trades.len(),
+            avg_trade_pnl: 0.0,
+            pnl_by_day,
+            trades: trades
+                .iter()
+                .enumerate()
+                .map(|(i, (market, price, size))| Trade {
+                    id: format!("t{}", i),
+                    market: market.to_string(),
+                    price: *price,
+                    size: *size,
+                    side: Side::Buy,
+                    fee: 0.0,
+                    timestamp: start,
+                })
+                .collect(),
+            final_capital: equity,
+            data_anomalies: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_market_deltas() {
+        let baseline = result(&[0.001, -0.001], &[("a", 0.5, 10.0), ("a", 0.5, 10.0), ("b", 0.2, 5.0)]);
+        let candidate = result(&[0.001, -0.001], &[("a", 0.5, 10.0), ("c", 0.4, 5.0)]);
+
+        let comparison = compare(&baseline, &candidate, &CompareConfig::default());
+        let markets: Vec<(&str, f64)> = comparison.markets.iter().map(|m| (m.market.as_str(), m.trades.delta)).collect();
+        assert_eq!(markets, vec![("a", -1.0), ("b", -1.0), ("c", 1.0)]);
+        assert!((comparison.turnover.baseline - 11.0).abs() < 1e-9);
+        assert!((comparison.turnover.candidate - 7.0).abs() < 1e-9);
+        assert!(!comparison.is_regression());
+    }
+
+    #[test]
+    fn test_significant_return_drop_is_regression() {
+        let noise = [
+            0.001, -0.0005, 0.0008, -0.0002, 0.0012, -0.0007, 0.0004, 0.0001, -0.0003, 0.0009,
+        ];
+        let baseline = result(&noise, &[]);
+        let worse: Vec<f64> = noise.iter().map(|r| r - 0.002).collect();
+        let candidate = result(&worse, &[]);
+
+        let comparison = compare(&baseline, &candidate, &CompareConfig::default());
+        let test = comparison.significance.as_ref().unwrap();
+        assert!(test.t_stat < 0.0);
+        assert!(test.significant);
+        assert!(comparison.is_regression());
+
+        // Same returns: no difference
+        let comparison = compare(&baseline, &baseline, &CompareConfig::default());
+        assert!(!comparison.significance.as_ref().unwrap().significant);
+        assert!(!comparison.is_regression());
+
+        // Absolute thresholds apply on their own
+        let config = CompareConfig {
+            significance_level: 0.0,
+            max_return_drop: Some(10.0),
+            ..Default::default()
+        };
+        let comparison = compare(&baseline, &candidate, &config);
+        assert_eq!(comparison.regressions.len(), 1);
+        assert!(comparison.regressions[0].starts_with("total return dropped"));
+    }
+}
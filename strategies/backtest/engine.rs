@@ -2,7 +2,10 @@
 
 use crate::{Strategy, StrategyContext, StrategyError, StrategyResult, StrategyParams};
 use crate::types::{MarketTick, Trade};
-use crate::backtest::fill_simulator::{FillSimulator, FillSimulatorConfig};
+use crate::backtest::fill_simulator::{future_mids, FillSimulator, FillSimulatorConfig};
+use crate::backtest::harness::order_key;
+use crate::hygiene::{HygieneConfig, HygieneStats, TickHygiene};
+use ag_risk::clock::MockClock;
 use ag_risk::RiskEngine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -21,6 +24,15 @@ pub struct BacktestConfig {
 
     /// Risk policy YAML
     pub risk_policy_yaml: String,
+
+    /// Duplicate and out-of-order tick filtering applied to the historical
+    /// data (None = replay ticks as given)
+    #[serde(default = "default_hygiene")]
+    pub hygiene: Option<HygieneConfig>,
+}
+
+fn default_hygiene() -> Option<HygieneConfig> {
+    Some(HygieneConfig::default())
 }
 
 impl Default for BacktestConfig {
@@ -35,6 +47,7 @@ policies:
   - type: InventoryLimit
     max_value_usd: 10000.0
 "#.to_string(),
+            hygiene: default_hygiene(),
         }
     }
 }
@@ -74,6 +87,10 @@ pub struct BacktestResult {
 
     /// Final capital
     pub final_capital: f64,
+
+    /// Ticks dropped or corrected by the hygiene filter
+    #[serde(default)]
+    pub data_anomalies: HygieneStats,
 }
 
 /// Event-driven backtesting engine
@@ -116,27 +133,58 @@ impl BacktestEngine {
             ));
         }
 
-        // Create strategy context
+        // Drop replayed and out-of-order ticks so they are not traded twice
+        let (historical_ticks, data_anomalies) = match self.config.hygiene {
+            Some(config) => {
+                let mut hygiene = TickHygiene::new(config);
+                let ticks = hygiene.filter(historical_ticks);
+                (ticks, hygiene.totals())
+            }
+            None => (historical_ticks, HygieneStats::default()),
+        };
+        if data_anomalies.dropped() > 0 {
+            tracing::debug!(
+                duplicates = data_anomalies.duplicates,
+                out_of_order = data_anomalies.out_of_order,
+                "Dropped historical ticks"
+            );
+        }
+
+        // Create strategy context, on a clock that follows the ticks
         let mut ctx = StrategyContext::new(
             "backtest_strategy".to_string(),
             self.risk_engine.clone(),
             params,
         );
+        let clock = MockClock::new(historical_ticks[0].timestamp);
+        ctx.set_clock(Arc::new(clock.clone()));
 
-        // Initialize strategy
+        // Initialize strategy; orders stay suppressed during warm-up
+        ctx.start_warmup(strategy.metadata().warmup_ticks);
         strategy.initialize(&mut ctx).await?;
 
         let mut trades = Vec::new();
         let mut equity_curve = Vec::new();
         let start_time = historical_ticks[0].timestamp;
 
+        // Where each market is headed, for adverse selection of maker fills
+        let fill_config = self.fill_simulator.config();
+        let outlook = if fill_config.adverse_selection > 0.0 {
+            future_mids(&historical_ticks, fill_config.adverse_horizon_ticks)
+        } else {
+            vec![None; historical_ticks.len()]
+        };
+
         // Process each tick
-        for tick in historical_ticks {
+        for (tick, future_mid) in historical_ticks.into_iter().zip(outlook) {
             // Update strategy with market data
+            clock.set(tick.timestamp);
+            ctx.record_warmup_tick();
             strategy.on_market_tick(&tick.market, &tick, &mut ctx).await?;
 
-            // Simulate fills for any submitted orders
-            let orders_to_fill: Vec<_> = ctx.get_open_orders()
+            // Simulate fills for any submitted orders, in submission order
+            // so seeded fill draws reproduce
+            let mut orders_to_fill: Vec<_> = ctx.get_open_orders()
                 .iter()
                 .filter_map(|o| {
                     if o.market == tick.market {
@@ -146,10 +194,16 @@ impl BacktestEngine {
                     }
                 })
                 .collect();
+            orders_to_fill.sort_by(|a, b| order_key(a).cmp(&order_key(b)));
 
             for order in orders_to_fill {
-                if let Some(fill) = self.fill_simulator.simulate_fill(&order, &tick) {
-                    // Notify strategy of fill
+                if let Some(fill) = self.fill_simulator.simulate_fill_with_outlook(&order, &tick, future_mid) {
+                    // Apply and notify strategy of fill
+                    if strategy.manages_positions() {
+                        ctx.record_order_fill(&fill);
+                    } else {
+                        ctx.apply_fill(&fill);
+                    }
                     strategy.on_fill(&fill, &mut ctx).await?;
 
                     // Record trade
@@ -162,11 +216,6 @@ impl BacktestEngine {
                         fee: fill.fee,
                         timestamp: fill.timestamp,
                     });
-
-                    // Remove filled order
-                    if let Some(order_id) = &order.id {
-                        ctx.orders.remove(order_id);
-                    }
                 }
             }
 
@@ -185,7 +234,9 @@ impl BacktestEngine {
         strategy.shutdown(&mut ctx).await?;
 
         // Calculate performance metrics
-        self.calculate_metrics(trades, equity_curve)
+        let mut result = self.calculate_metrics(trades, equity_curve)?;
+        result.data_anomalies = data_anomalies;
+        Ok(result)
     }
 
     /// Calculate performance metrics from trades and equity curve
@@ -252,6 +303,7 @@ impl BacktestEngine {
             pnl_by_day,
             trades,
             final_capital,
+            data_anomalies: HygieneStats::default(),
         })
     }
 
@@ -357,6 +409,7 @@ mod tests {
                 description: "Test".to_string(),
                 markets: vec![],
                 required_params: vec![],
+                warmup_ticks: 0,
             }
         }
     }
@@ -376,6 +429,7 @@ mod tests {
                 ask_size: Some(10.0),
                 last: Some(100.5),
                 volume_24h: Some(1000.0),
+                sequence: None,
             },
         ];
 
@@ -389,4 +443,67 @@ mod tests {
         assert_eq!(result.num_trades, 0);
         assert_eq!(result.final_capital, 10000.0);
     }
+
+    #[tokio::test]
+    async fn test_market_maker_under_adverse_selection() {
+        use crate::r#impl::market_maker::{MarketMakerConfig, MarketMakerStrategy};
+        use crate::types::Side;
+
+        // A steadily falling market, one tick per second
+        let start = Utc::now();
+        let ticks: Vec<_> = (0..40)
+            .map(|i| {
+                let mid = 0.60 - 0.002 * i as f64;
+                MarketTick {
+                    market: "m1".to_string(),
+                    timestamp: start + chrono::Duration::seconds(i),
+                    bid: Some(mid - 0.01),
+                    ask: Some(mid + 0.01),
+                    bid_size: Some(1000.0),
+                    ask_size: Some(1000.0),
+                    last: Some(mid),
+                    volume_24h: None,
+                    sequence: None,
+                }
+            })
+            .collect();
+
+        let run = |fill_probability: f64, adverse_selection: f64| {
+            let ticks = ticks.clone();
+            async move {
+                let mut engine = BacktestEngine::new(BacktestConfig {
+                    fill_simulator: FillSimulatorConfig {
+                        fill_probability,
+                        adverse_selection,
+                        adverse_horizon_ticks: 1,
+                        seed: Some(7),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .unwrap();
+                let strategy = MarketMakerStrategy::new("m1".to_string(), MarketMakerConfig::default());
+                engine
+                    .run_backtest(Box::new(strategy), ticks, StrategyParams::new())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Uniform fills: both sides trade and inventory stays flat
+        let uniform = run(1.0, 0.0).await;
+        assert!(uniform.trades.iter().any(|t| t.side == Side::Sell));
+
+        // Fully adverse fills: only the bids get hit, all the way down. The
+        // last tick has no outlook, so its fills are uniform
+        let adverse = run(0.5, 1.0).await;
+        let last_outlook = start + chrono::Duration::seconds(38);
+        assert!(adverse.num_trades > 0);
+        assert!(adverse
+            .trades
+            .iter()
+            .filter(|t| t.timestamp <= last_outlook)
+            .all(|t| t.side == Side::Buy));
+        assert!(adverse.total_return < uniform.total_return);
+    }
 }
@@ -1,7 +1,18 @@
 //! Fill simulation for backtesting
+//!
+//! Resting (maker) orders fill with probability `fill_probability`. Real
+//! passive fills are not uniform: a resting bid is most likely to be hit
+//! just before the price drops. With `adverse_selection` above zero the
+//! simulator looks at where the market's mid is `adverse_horizon_ticks`
+//! ticks later and skews the fill probability towards fills that are about
+//! to lose money, so market makers are tested against toxic flow instead of
+//! coin flips.
 
 use crate::types::{Order, Fill, MarketTick, Side};
-use chrono::Utc;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
 
 /// Fill simulator configuration
 #[derive(Debug, Clone)]
@@ -17,6 +28,20 @@ pub struct FillSimulatorConfig {
 
     /// Maker fee (bps), negative if rebate
     pub maker_fee_bps: f64,
+
+    /// How strongly passive fills favour adverse moves, 0 to 1
+    ///
+    /// 0 fills resting orders uniformly at `fill_probability`. At 1 a
+    /// resting order always fills ahead of an adverse move and never ahead
+    /// of a favourable one.
+    pub adverse_selection: f64,
+
+    /// Ticks of the same market looked ahead to judge the move
+    pub adverse_horizon_ticks: usize,
+
+    /// Seed for the resting fill draws, for reproducible runs (None seeds
+    /// from entropy)
+    pub seed: Option<u64>,
 }
 
 impl Default for FillSimulatorConfig {
@@ -26,6 +51,9 @@ impl Default for FillSimulatorConfig {
             fill_probability: 0.8,
             taker_fee_bps: 10.0,
             maker_fee_bps: -5.0, // Maker rebate
+            adverse_selection: 0.0,
+            adverse_horizon_ticks: 5,
+            seed: None,
         }
     }
 }
@@ -33,24 +61,74 @@ impl Default for FillSimulatorConfig {
 /// Fill simulator for backtesting
 pub struct FillSimulator {
     config: FillSimulatorConfig,
+    rng: Mutex<StdRng>,
 }
 
 impl FillSimulator {
     pub fn new(config: FillSimulatorConfig) -> Self {
-        Self { config }
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            config,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn config(&self) -> &FillSimulatorConfig {
+        &self.config
     }
 
     /// Simulate fill for an order given market tick
     ///
     /// Returns None if order wouldn't be filled, Some(Fill) if filled
     pub fn simulate_fill(&self, order: &Order, tick: &MarketTick) -> Option<Fill> {
+        self.simulate_fill_with_outlook(order, tick, None)
+    }
+
+    /// Simulate fill for an order, knowing the market's mid price
+    /// `adverse_horizon_ticks` ticks later (see [`future_mids`])
+    ///
+    /// Without a future mid, or with `adverse_selection` at 0, resting
+    /// orders fill uniformly as in [`simulate_fill`](Self::simulate_fill).
+    pub fn simulate_fill_with_outlook(
+        &self,
+        order: &Order,
+        tick: &MarketTick,
+        future_mid: Option<f64>,
+    ) -> Option<Fill> {
         match order.order_type {
             crate::types::OrderType::Market => self.simulate_market_order_fill(order, tick),
-            crate::types::OrderType::Limit => self.simulate_limit_order_fill(order, tick),
+            crate::types::OrderType::Limit => self.simulate_limit_order_fill(order, tick, future_mid),
             _ => None, // Stop orders not implemented yet
         }
     }
 
+    /// Probability that a resting order fills on this tick
+    ///
+    /// The base `fill_probability` is pushed towards 1 when the mid is
+    /// about to move against the order (down for a buy, up for a sell) and
+    /// towards 0 when it is about to move in the order's favour.
+    pub fn maker_fill_probability(&self, side: Side, mid: f64, future_mid: Option<f64>) -> f64 {
+        let base = self.config.fill_probability.clamp(0.0, 1.0);
+        let intensity = self.config.adverse_selection.clamp(0.0, 1.0);
+        let Some(future_mid) = future_mid else {
+            return base;
+        };
+        let adverse = match side {
+            Side::Buy => future_mid < mid,
+            Side::Sell => future_mid > mid,
+        };
+        if adverse {
+            base + intensity * (1.0 - base)
+        } else if future_mid != mid {
+            base * (1.0 - intensity)
+        } else {
+            base
+        }
+    }
+
     /// Simulate market order fill
     fn simulate_market_order_fill(&self, order: &Order, tick: &MarketTick) -> Option<Fill> {
         // Market orders always fill (in backtest)
@@ -76,12 +154,12 @@ impl FillSimulator {
             size: order.size,
             side: order.side,
             fee,
-            timestamp: Utc::now(),
+            timestamp: tick.timestamp,
         })
     }
 
     /// Simulate limit order fill
-    fn simulate_limit_order_fill(&self, order: &Order, tick: &MarketTick) -> Option<Fill> {
+    fn simulate_limit_order_fill(&self, order: &Order, tick: &MarketTick, future_mid: Option<f64>) -> Option<Fill> {
         let order_price = order.price?;
 
         // Check if order price crosses the market
@@ -97,8 +175,10 @@ impl FillSimulator {
         };
 
         if !would_fill {
-            // Order rests on book - probabilistic fill
-            if rand::random::<f64>() > self.config.fill_probability {
+            // Order rests on book - probabilistic fill, skewed towards
+            // adverse moves
+            let probability = self.maker_fill_probability(order.side, tick.mid_price(), future_mid);
+            if self.rng.lock().gen::<f64>() >= probability {
                 return None;
             }
         }
@@ -128,11 +208,29 @@ impl FillSimulator {
             size: order.size,
             side: order.side,
             fee,
-            timestamp: Utc::now(),
+            timestamp: tick.timestamp,
         })
     }
 }
 
+/// For each tick, the mid price of the same market `horizon` ticks later
+///
+/// `None` where the market has fewer than `horizon` ticks left.
+pub fn future_mids(ticks: &[MarketTick], horizon: usize) -> Vec<Option<f64>> {
+    let mut by_market: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, tick) in ticks.iter().enumerate() {
+        by_market.entry(tick.market.as_str()).or_default().push(i);
+    }
+
+    let mut mids = vec![None; ticks.len()];
+    for indices in by_market.values() {
+        for (k, &i) in indices.iter().enumerate() {
+            mids[i] = indices.get(k + horizon).map(|&j| ticks[j].mid_price());
+        }
+    }
+    mids
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,6 +247,7 @@ mod tests {
             ask_size: Some(100.0),
             last: Some((bid + ask) / 2.0),
             volume_24h: Some(1000.0),
+            sequence: None,
         }
     }
 
@@ -229,4 +328,59 @@ mod tests {
         // May or may not fill due to probability
         let _ = simulator.simulate_fill(&buy_order_low, &tick);
     }
+
+    #[test]
+    fn test_adverse_selection() {
+        let simulator = FillSimulator::new(FillSimulatorConfig {
+            fill_probability: 0.5,
+            adverse_selection: 1.0,
+            ..Default::default()
+        });
+        let tick = create_test_tick(100.0, 101.0);
+        let bid = Order {
+            id: Some("bid".to_string()),
+            venue: "test".to_string(),
+            market: "test".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price: Some(100.0),
+            size: 10.0,
+            time_in_force: TimeInForce::GTC,
+            ..Default::default()
+        };
+
+        // The bid is always hit before the price drops and never before it rises
+        for _ in 0..50 {
+            assert!(simulator.simulate_fill_with_outlook(&bid, &tick, Some(99.0)).is_some());
+            assert!(simulator.simulate_fill_with_outlook(&bid, &tick, Some(102.0)).is_none());
+        }
+        assert_eq!(simulator.maker_fill_probability(Side::Sell, 100.5, Some(102.0)), 1.0);
+        assert_eq!(simulator.maker_fill_probability(Side::Buy, 100.5, Some(100.5)), 0.5);
+        assert_eq!(simulator.maker_fill_probability(Side::Buy, 100.5, None), 0.5);
+
+        // Partial intensity moves the probability part of the way
+        let simulator = FillSimulator::new(FillSimulatorConfig {
+            fill_probability: 0.5,
+            adverse_selection: 0.5,
+            ..Default::default()
+        });
+        assert_eq!(simulator.maker_fill_probability(Side::Buy, 100.5, Some(99.0)), 0.75);
+        assert_eq!(simulator.maker_fill_probability(Side::Buy, 100.5, Some(102.0)), 0.25);
+    }
+
+    #[test]
+    fn test_future_mids() {
+        let mut ticks = vec![
+            create_test_tick(100.0, 101.0),
+            create_test_tick(101.0, 102.0),
+            create_test_tick(102.0, 103.0),
+        ];
+        let mut other = create_test_tick(50.0, 51.0);
+        other.market = "other".to_string();
+        ticks.insert(1, other);
+
+        let mids = future_mids(&ticks, 2);
+        assert_eq!(mids, vec![Some(102.5), None, None, None]);
+        assert_eq!(future_mids(&ticks, 1)[0], Some(101.5));
+    }
 }
@@ -0,0 +1,466 @@
+//! Step-by-step strategy simulation harness
+//!
+//! Drives a single strategy instance with hand-crafted ticks, fills, cancels
+//! and timer events on a mock clock, reporting the orders, fills and metrics
+//! each step produced. Faster than writing an integration test per scenario
+//! when debugging strategy logic.
+//!
+//! Steps can also be given as text, one command per line, which the
+//! `simulate` example uses as a REPL and script runner:
+//!
+//! ```text
+//! tick <market> <bid> <ask> [bid_size] [ask_size]
+//! fill <order_id> [size] [price]     # default: remaining size at the limit
+//! cancel <order_id>                  # venue-side cancel, calls on_cancel
+//! timer
+//! advance <seconds>
+//! orders | positions | metrics | time
+//! ```
+
+use crate::backtest::BacktestConfig;
+use crate::metrics::StrategyMetric;
+use crate::types::{Fill, MarketTick, Order, OrderId, Position};
+use crate::{Strategy, StrategyContext, StrategyError, StrategyParams, StrategyResult};
+use ag_risk::clock::MockClock;
+use ag_risk::RiskEngine;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// Harness configuration
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Strategy ID of the context
+    pub strategy_id: String,
+
+    /// Initial time of the mock clock
+    pub start_time: DateTime<Utc>,
+
+    /// Risk policy YAML
+    pub risk_policy_yaml: String,
+
+    /// Fee on manual fills as a fraction of notional
+    pub fee_rate: f64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            strategy_id: "sim_strategy".to_string(),
+            start_time: Utc::now(),
+            risk_policy_yaml: BacktestConfig::default().risk_policy_yaml,
+            fee_rate: 0.0,
+        }
+    }
+}
+
+/// What a single step did
+#[derive(Debug, Clone, Default)]
+pub struct StepReport {
+    /// Orders submitted during the step
+    pub placed: Vec<Order>,
+
+    /// Orders that left the book (cancelled or fully filled)
+    pub removed: Vec<OrderId>,
+
+    /// Fills applied during the step
+    pub fills: Vec<Fill>,
+
+    /// Metrics emitted during the step
+    pub metrics: Vec<StrategyMetric>,
+}
+
+impl fmt::Display for StepReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for fill in &self.fills {
+            writeln!(f, "fill {} {} {} @ {:.4} fee {:.4}", fill.order_id, fill.side, fill.size, fill.price, fill.fee)?;
+        }
+        for order_id in &self.removed {
+            writeln!(f, "- {}", order_id)?;
+        }
+        for order in &self.placed {
+            writeln!(f, "+ {}", describe_order(order))?;
+        }
+        for metric in &self.metrics {
+            writeln!(f, "{}", describe_metric(metric))?;
+        }
+        if self.fills.is_empty() && self.removed.is_empty() && self.placed.is_empty() && self.metrics.is_empty() {
+            writeln!(f, "(no changes)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Interactive driver for one strategy instance
+pub struct SimulationHarness {
+    strategy: Box<dyn Strategy>,
+    ctx: StrategyContext,
+    risk_engine: Arc<Mutex<RiskEngine>>,
+    clock: MockClock,
+    fee_rate: f64,
+    /// Last tick per market, for fill prices of market orders
+    ticks: HashMap<String, MarketTick>,
+    /// Every metric emitted so far
+    metrics: Vec<StrategyMetric>,
+}
+
+impl SimulationHarness {
+    /// Create a context on a mock clock and initialize the strategy
+    ///
+    /// Warm-up starts from the strategy's `warmup_ticks` as in a backtest.
+    pub async fn new(
+        mut strategy: Box<dyn Strategy>,
+        params: StrategyParams,
+        config: SimulationConfig,
+    ) -> StrategyResult<Self> {
+        let risk_engine = RiskEngine::from_yaml(&config.risk_policy_yaml).map_err(StrategyError::ConfigError)?;
+        let risk_engine = Arc::new(Mutex::new(risk_engine));
+        let mut ctx = StrategyContext::new(config.strategy_id, risk_engine.clone(), params);
+        let clock = MockClock::new(config.start_time);
+        ctx.set_clock(Arc::new(clock.clone()));
+
+        ctx.start_warmup(strategy.metadata().warmup_ticks);
+        strategy.initialize(&mut ctx).await?;
+
+        let mut harness = Self {
+            strategy,
+            ctx,
+            risk_engine,
+            clock,
+            fee_rate: config.fee_rate,
+            ticks: HashMap::new(),
+            metrics: Vec::new(),
+        };
+        harness.drain_metrics();
+        Ok(harness)
+    }
+
+    /// Strategy context, e.g. to set flags or allocated capital between steps
+    pub fn context(&self) -> &StrategyContext {
+        &self.ctx
+    }
+
+    pub fn context_mut(&mut self) -> &mut StrategyContext {
+        &mut self.ctx
+    }
+
+    /// Risk engine the strategy's orders are checked against
+    pub fn risk_engine(&self) -> &Arc<Mutex<RiskEngine>> {
+        &self.risk_engine
+    }
+
+    /// Current simulated time
+    pub fn now(&self) -> DateTime<Utc> {
+        self.ctx.now()
+    }
+
+    /// Open orders in submission order
+    pub fn orders(&self) -> Vec<&Order> {
+        let mut orders = self.ctx.get_open_orders();
+        orders.sort_by(|a, b| order_key(a).cmp(&order_key(b)));
+        orders
+    }
+
+    /// Positions by market
+    pub fn positions(&self) -> Vec<&Position> {
+        let mut positions: Vec<&Position> = self.ctx.positions.values().collect();
+        positions.sort_by(|a, b| a.market.cmp(&b.market));
+        positions
+    }
+
+    /// Every metric emitted since the harness was created
+    pub fn metrics(&self) -> &[StrategyMetric] {
+        &self.metrics
+    }
+
+    /// Move the clock forward
+    pub fn advance(&mut self, by: Duration) {
+        self.clock.advance(by);
+    }
+
+    /// Deliver a market tick, moving the clock to its timestamp
+    pub async fn tick(&mut self, tick: MarketTick) -> StrategyResult<StepReport> {
+        let before = self.open_order_ids();
+        self.clock.set(tick.timestamp);
+        self.ctx.record_warmup_tick();
+        self.strategy.on_market_tick(&tick.market, &tick, &mut self.ctx).await?;
+        self.ticks.insert(tick.market.clone(), tick);
+        Ok(self.finish_step(before, Vec::new()))
+    }
+
+    /// Fill `size` of an open order (default: the remaining size) at `price`
+    /// (default: the limit price, or the last mid for market orders)
+    pub async fn fill(&mut self, order_id: &str, size: Option<f64>, price: Option<f64>) -> StrategyResult<StepReport> {
+        let order = self
+            .ctx
+            .orders
+            .get(order_id)
+            .ok_or_else(|| StrategyError::OrderNotFound(order_id.to_string()))?;
+        let size = size.unwrap_or(order.size);
+        if size <= 0.0 || size > order.size + 1e-8 {
+            return Err(StrategyError::InvalidParameter(format!(
+                "fill size {} outside (0, {}] for {}",
+                size, order.size, order_id
+            )));
+        }
+        let price = price
+            .or(order.price)
+            .or_else(|| self.ticks.get(&order.market).map(|tick| tick.mid_price()))
+            .ok_or_else(|| StrategyError::InvalidParameter(format!("no price to fill {} at", order_id)))?;
+
+        let fill = Fill {
+            order_id: order_id.to_string(),
+            market: order.market.clone(),
+            price,
+            size,
+            side: order.side,
+            fee: price * size * self.fee_rate,
+            timestamp: self.now(),
+        };
+
+        let before = self.open_order_ids();
+        if self.strategy.manages_positions() {
+            self.ctx.record_order_fill(&fill);
+        } else {
+            self.ctx.apply_fill(&fill);
+        }
+        self.strategy.on_fill(&fill, &mut self.ctx).await?;
+        Ok(self.finish_step(before, vec![fill]))
+    }
+
+    /// Cancel an open order as the venue would and notify the strategy
+    pub async fn cancel(&mut self, order_id: &str) -> StrategyResult<StepReport> {
+        let before = self.open_order_ids();
+        let order_id = order_id.to_string();
+        self.ctx.cancel_order(&order_id).await?;
+        self.strategy.on_cancel(&order_id, &mut self.ctx).await?;
+        Ok(self.finish_step(before, Vec::new()))
+    }
+
+    /// Fire the strategy's timer
+    pub async fn timer(&mut self) -> StrategyResult<StepReport> {
+        let before = self.open_order_ids();
+        self.strategy.on_timer(&mut self.ctx).await?;
+        Ok(self.finish_step(before, Vec::new()))
+    }
+
+    /// Shut the strategy down
+    pub async fn shutdown(mut self) -> StrategyResult<StepReport> {
+        let before = self.open_order_ids();
+        self.strategy.shutdown(&mut self.ctx).await?;
+        Ok(self.finish_step(before, Vec::new()))
+    }
+
+    /// Run one text command (see the module docs) and return its output
+    ///
+    /// Blank lines and `#` comments produce no output.
+    pub async fn execute(&mut self, line: &str) -> StrategyResult<String> {
+        let line = line.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((command, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+
+        let report = match (*command, args) {
+            ("tick", [market, bid, ask, sizes @ ..]) if sizes.len() <= 2 => {
+                let tick = MarketTick {
+                    market: market.to_string(),
+                    timestamp: self.now(),
+                    bid: Some(parse_number(bid)?),
+                    bid_size: sizes.first().map(|s| parse_number(s)).transpose()?,
+                    ask: Some(parse_number(ask)?),
+                    ask_size: sizes.get(1).map(|s| parse_number(s)).transpose()?,
+                    last: None,
+                    volume_24h: None,
+                    sequence: None,
+                };
+                self.tick(tick).await?
+            }
+            ("fill", [order_id, rest @ ..]) if rest.len() <= 2 => {
+                let size = rest.first().map(|s| parse_number(s)).transpose()?;
+                let price = rest.get(1).map(|s| parse_number(s)).transpose()?;
+                self.fill(order_id, size, price).await?
+            }
+            ("cancel", [order_id]) => self.cancel(order_id).await?,
+            ("timer", []) => self.timer().await?,
+            ("advance", [seconds]) => {
+                let millis = (parse_number(seconds)? * 1000.0).round() as i64;
+                self.advance(Duration::milliseconds(millis));
+                return Ok(format!("{}\n", self.now().to_rfc3339()));
+            }
+            ("time", []) => return Ok(format!("{}\n", self.now().to_rfc3339())),
+            ("orders", []) => return Ok(self.orders().iter().map(|o| format!("{}\n", describe_order(o))).collect()),
+            ("positions", []) => {
+                return Ok(self
+                    .positions()
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "{} size {} entry {:.4} realized {:.4} unrealized {:.4}\n",
+                            p.market, p.size, p.entry_price, p.realized_pnl, p.unrealized_pnl
+                        )
+                    })
+                    .collect())
+            }
+            ("metrics", []) => return Ok(self.metrics.iter().map(|m| format!("{}\n", describe_metric(m))).collect()),
+            _ => {
+                return Err(StrategyError::InvalidParameter(format!(
+                    "unknown command or arguments: {}",
+                    line.trim()
+                )))
+            }
+        };
+        Ok(report.to_string())
+    }
+
+    /// Run a script of text commands, echoing each as `> command` before
+    /// its output; stops at the first failing line
+    pub async fn run_script(&mut self, script: &str) -> StrategyResult<String> {
+        let mut transcript = String::new();
+        for (number, line) in script.lines().enumerate() {
+            let output = self
+                .execute(line)
+                .await
+                .map_err(|e| StrategyError::InvalidParameter(format!("line {}: {}", number + 1, e)))?;
+            if !output.is_empty() {
+                transcript.push_str(&format!("> {}\n{}", line.trim(), output));
+            }
+        }
+        Ok(transcript)
+    }
+
+    fn open_order_ids(&self) -> HashSet<OrderId> {
+        self.ctx.orders.keys().cloned().collect()
+    }
+
+    fn drain_metrics(&mut self) -> Vec<StrategyMetric> {
+        let metrics = self.ctx.get_metrics_buffer().to_vec();
+        self.ctx.clear_metrics_buffer();
+        self.metrics.extend(metrics.iter().cloned());
+        metrics
+    }
+
+    fn finish_step(&mut self, before: HashSet<OrderId>, fills: Vec<Fill>) -> StepReport {
+        let mut placed: Vec<Order> = self
+            .ctx
+            .orders
+            .iter()
+            .filter(|(id, _)| !before.contains(*id))
+            .map(|(_, order)| order.clone())
+            .collect();
+        placed.sort_by(|a, b| order_key(a).cmp(&order_key(b)));
+
+        let mut removed: Vec<OrderId> = before.into_iter().filter(|id| !self.ctx.orders.contains_key(id)).collect();
+        removed.sort_by(|a, b| (a.len(), a).cmp(&(b.len(), b)));
+
+        StepReport {
+            placed,
+            removed,
+            fills,
+            metrics: self.drain_metrics(),
+        }
+    }
+}
+
+/// Sort key putting `order_9` before `order_10`
+pub(super) fn order_key(order: &Order) -> (usize, &str) {
+    let id = order.id.as_deref().unwrap_or_default();
+    (id.len(), id)
+}
+
+fn describe_order(order: &Order) -> String {
+    let price = order.price.map(|p| format!("{:.4}", p)).unwrap_or_else(|| "market".to_string());
+    format!(
+        "{} {} {} {} @ {} ({:?})",
+        order.id.as_deref().unwrap_or("-"),
+        order.market,
+        order.side,
+        order.size,
+        price,
+        order.status
+    )
+}
+
+fn describe_metric(metric: &StrategyMetric) -> String {
+    let mut labels: Vec<String> = metric.labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    labels.sort();
+    format!("{} {} {{{}}}", metric.metric_name, metric.value, labels.join(","))
+}
+
+fn parse_number(value: &str) -> StrategyResult<f64> {
+    value
+        .parse()
+        .map_err(|_| StrategyError::InvalidParameter(format!("not a number: {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#impl::{MarketMakerConfig, MarketMakerStrategy};
+
+    async fn market_maker() -> SimulationHarness {
+        let strategy = MarketMakerStrategy::new("m1".to_string(), MarketMakerConfig::default());
+        SimulationHarness::new(Box::new(strategy), StrategyParams::new(), SimulationConfig::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_step_by_step() {
+        let mut sim = market_maker().await;
+
+        let tick = MarketTick {
+            market: "m1".to_string(),
+            timestamp: sim.now(),
+            bid: Some(0.49),
+            bid_size: Some(100.0),
+            ask: Some(0.51),
+            ask_size: Some(100.0),
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        };
+        let report = sim.tick(tick).await.unwrap();
+        assert_eq!(report.placed.len(), 2);
+        assert!(report.removed.is_empty());
+
+        let bid = report.placed.iter().find(|o| o.side == crate::Side::Buy).unwrap();
+        let bid_id = bid.id.clone().unwrap();
+        let report = sim.fill(&bid_id, Some(40.0), None).await.unwrap();
+        assert_eq!(report.fills.len(), 1);
+        assert!(report.removed.is_empty());
+        assert_eq!(sim.positions()[0].size, 40.0);
+
+        let report = sim.fill(&bid_id, None, None).await.unwrap();
+        assert_eq!(report.removed, vec![bid_id.clone()]);
+        assert_eq!(sim.positions()[0].size, 100.0);
+        assert!(matches!(
+            sim.fill(&bid_id, None, None).await,
+            Err(StrategyError::OrderNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_script() {
+        let mut sim = market_maker().await;
+        let script = "
+            # quote, then requote after the interval
+            tick m1 0.49 0.51
+            fill order_1 50
+            advance 1
+            tick m1 0.50 0.52  # mid moved up
+            positions
+        ";
+        let transcript = sim.run_script(script).await.unwrap();
+        assert!(transcript.contains("> tick m1 0.49 0.51\n+ order_1 m1"));
+        assert!(transcript.contains("fill order_1"));
+        assert!(transcript.contains("- order_1\n- order_2\n+ order_3"));
+        assert!(transcript.contains("m1 size 50"));
+        assert_eq!(sim.orders().len(), 2);
+
+        let error = sim.run_script("tick m1 0.5\n").await.unwrap_err();
+        assert!(error.to_string().contains("line 1"));
+    }
+}
@@ -1,7 +1,15 @@
 //! Backtesting engine for strategy validation
 
+pub mod calibration;
+pub mod compare;
 pub mod engine;
 pub mod fill_simulator;
+pub mod harness;
+pub mod scenario;
 
+pub use calibration::{CalibrationReport, Distribution, ExecutionSample, FillCalibrator, MarketCalibration};
+pub use compare::{compare, BacktestComparison, CompareConfig, MarketDelta, MetricDelta, SignificanceTest};
 pub use engine::{BacktestEngine, BacktestConfig, BacktestResult};
-pub use fill_simulator::{FillSimulator, FillSimulatorConfig};
+pub use fill_simulator::{future_mids, FillSimulator, FillSimulatorConfig};
+pub use harness::{SimulationConfig, SimulationHarness, StepReport};
+pub use scenario::{Expectation, Scenario, ScenarioFailure, ScenarioReport, ScenarioRunner, ScenarioStep};
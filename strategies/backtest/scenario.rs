@@ -0,0 +1,674 @@
+//! Declarative scenario tests
+//!
+//! A scenario is a YAML file describing a strategy, the risk policies it
+//! trades under, and a sequence of steps (ticks, fills, cancels, timers,
+//! clock moves and hypothetical risk checks), each with the outcome it
+//! should have. [`ScenarioRunner`] plays the steps through a
+//! [`SimulationHarness`] (the strategy on a paper execution engine, checked
+//! against a real `RiskEngine`) and reports every expectation that did not
+//! hold, so behavioural regression tests can be added without writing Rust:
+//!
+//! ```yaml
+//! name: market maker respects its position limit
+//! strategy:
+//!   kind: market_maker
+//!   markets: [m1]
+//!   params:
+//!     quote_size: 100
+//! risk:
+//!   policies:
+//!     - type: PositionLimit
+//!       max_size: 150
+//! steps:
+//!   - tick: { market: m1, bid: 0.49, ask: 0.51 }
+//!     expect:
+//!       placed: 2
+//!       orders:
+//!         - { side: Buy, size: 100 }
+//!         - { side: Sell, size: 100 }
+//!   - fill: { order: order_1 }
+//!     expect:
+//!       positions: { m1: 100 }
+//!   - risk_check: { market: m1, side: Buy, size: 100, price: 0.5 }
+//!     expect:
+//!       allowed: false
+//!       violations: [PositionLimit]
+//! ```
+//!
+//! Each step has exactly one action:
+//!
+//! - `tick: { market, bid, ask, bid_size, ask_size, last, volume_24h }`
+//!   (sizes and the rest optional); the clock moves to `at` when given
+//! - `fill: { order, size, price }` (size defaults to the remaining size,
+//!   price to the limit)
+//! - `cancel: <order_id>`, a venue-side cancel
+//! - `timer: true`
+//! - `advance: <seconds>`
+//! - `risk_check: { market, side, size, price }`: the decision an order
+//!   would get, without recording it
+//!
+//! and an optional `expect` ([`Expectation`]), whose fields are all
+//! optional and checked only when present. Unknown keys are rejected, so a
+//! misspelt expectation fails loudly instead of passing vacuously.
+//!
+//! Strategies are created by `kind` from a [`StrategyRegistry`]
+//! (`StrategyRegistry::with_builtins()` by default).
+
+use crate::backtest::{SimulationConfig, SimulationHarness, StepReport};
+use crate::plugin::StrategyRegistry;
+use crate::types::{MarketTick, Order, OrderType, Side};
+use crate::{StrategyError, StrategyParams, StrategyResult};
+use ag_risk::{PolicyCounters, RiskDecision};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Absolute tolerance for prices, sizes and positions in expectations
+const TOLERANCE: f64 = 1e-6;
+
+/// A scenario file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    pub strategy: ScenarioStrategy,
+
+    /// Risk policy config, as in a risk policy file (default: the backtest
+    /// defaults)
+    #[serde(default)]
+    pub risk: Option<serde_yaml::Value>,
+
+    /// Fee on fills as a fraction of notional
+    #[serde(default)]
+    pub fee_rate: f64,
+
+    /// Initial clock time (default 2024-01-01T00:00:00Z, so runs are
+    /// reproducible)
+    #[serde(default)]
+    pub start_time: Option<DateTime<Utc>>,
+
+    pub steps: Vec<ScenarioStep>,
+}
+
+impl Scenario {
+    pub fn from_yaml(yaml: &str) -> StrategyResult<Self> {
+        serde_yaml::from_str(yaml).map_err(|e| StrategyError::ConfigError(format!("Invalid scenario: {}", e)))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> StrategyResult<Self> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|e| StrategyError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        Self::from_yaml(&yaml).map_err(|e| StrategyError::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Strategy under test
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioStrategy {
+    /// Registered strategy name, e.g. `market_maker`
+    pub kind: String,
+
+    pub markets: Vec<String>,
+
+    /// Strategy parameters; numbers and booleans are passed as strings
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl ScenarioStrategy {
+    fn params(&self) -> StrategyResult<StrategyParams> {
+        let mut params = StrategyParams::new();
+        for (key, value) in &self.params {
+            let value = match value {
+                serde_yaml::Value::String(s) => s.clone(),
+                serde_yaml::Value::Number(n) => n.to_string(),
+                serde_yaml::Value::Bool(b) => b.to_string(),
+                other => {
+                    return Err(StrategyError::ConfigError(format!(
+                        "param {} must be a string, number or boolean, got {:?}",
+                        key, other
+                    )))
+                }
+            };
+            params.set(key.clone(), value);
+        }
+        Ok(params)
+    }
+}
+
+/// One step: an action and what it should produce
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioStep {
+    #[serde(default)]
+    pub tick: Option<TickStep>,
+    #[serde(default)]
+    pub fill: Option<FillStep>,
+    #[serde(default)]
+    pub cancel: Option<String>,
+    #[serde(default)]
+    pub timer: Option<bool>,
+    /// Seconds to move the clock forward
+    #[serde(default)]
+    pub advance: Option<f64>,
+    #[serde(default)]
+    pub risk_check: Option<RiskCheckStep>,
+
+    #[serde(default)]
+    pub expect: Option<Expectation>,
+}
+
+impl ScenarioStep {
+    fn action_count(&self) -> usize {
+        [
+            self.tick.is_some(),
+            self.fill.is_some(),
+            self.cancel.is_some(),
+            self.timer.unwrap_or(false),
+            self.advance.is_some(),
+            self.risk_check.is_some(),
+        ]
+        .iter()
+        .filter(|set| **set)
+        .count()
+    }
+}
+
+/// Market tick; unset sizes and prices are omitted from the tick
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TickStep {
+    pub market: String,
+    #[serde(default)]
+    pub bid: Option<f64>,
+    #[serde(default)]
+    pub ask: Option<f64>,
+    #[serde(default)]
+    pub bid_size: Option<f64>,
+    #[serde(default)]
+    pub ask_size: Option<f64>,
+    #[serde(default)]
+    pub last: Option<f64>,
+    #[serde(default)]
+    pub volume_24h: Option<f64>,
+    /// Tick time (default: the current clock time)
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// Fill of an open order
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FillStep {
+    pub order: String,
+    #[serde(default)]
+    pub size: Option<f64>,
+    #[serde(default)]
+    pub price: Option<f64>,
+}
+
+/// Hypothetical limit order to run past the risk engine
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskCheckStep {
+    pub market: String,
+    pub side: Side,
+    pub size: f64,
+    pub price: f64,
+}
+
+/// Expected outcome of a step; only the fields given are checked
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    /// Orders submitted during the step
+    #[serde(default)]
+    pub placed: Option<usize>,
+
+    /// Orders that left the book during the step
+    #[serde(default)]
+    pub removed: Option<usize>,
+
+    /// The orders submitted during the step, in submission order
+    #[serde(default)]
+    pub orders: Option<Vec<OrderExpectation>>,
+
+    /// Open orders after the step
+    #[serde(default)]
+    pub open_orders: Option<usize>,
+
+    /// Position size by market after the step (0 for no position)
+    #[serde(default)]
+    pub positions: Option<BTreeMap<String, f64>>,
+
+    /// Rejections during the step by policy type (e.g. `PositionLimit`,
+    /// counting every market's limit) or full counter label (e.g.
+    /// `PositionLimit (market: m1)`)
+    #[serde(default)]
+    pub rejections: Option<BTreeMap<String, u64>>,
+
+    /// Whether the `risk_check` order is allowed
+    #[serde(default)]
+    pub allowed: Option<bool>,
+
+    /// Policies behind the `risk_check` rejection
+    #[serde(default)]
+    pub violations: Option<Vec<String>>,
+
+    /// The action must fail with an error containing this text
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Expected order; only the fields given are checked
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrderExpectation {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub market: Option<String>,
+    #[serde(default)]
+    pub side: Option<Side>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub size: Option<f64>,
+}
+
+impl OrderExpectation {
+    fn mismatches(&self, order: &Order) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if let Some(id) = &self.id {
+            if order.id.as_deref() != Some(id.as_str()) {
+                mismatches.push(format!("id {:?}, expected {}", order.id, id));
+            }
+        }
+        if let Some(market) = &self.market {
+            if &order.market != market {
+                mismatches.push(format!("market {}, expected {}", order.market, market));
+            }
+        }
+        if let Some(side) = self.side {
+            if order.side != side {
+                mismatches.push(format!("side {}, expected {}", order.side, side));
+            }
+        }
+        if let Some(price) = self.price {
+            if order.price.is_none_or(|p| (p - price).abs() > TOLERANCE) {
+                mismatches.push(format!("price {:?}, expected {}", order.price, price));
+            }
+        }
+        if let Some(size) = self.size {
+            if (order.size - size).abs() > TOLERANCE {
+                mismatches.push(format!("size {}, expected {}", order.size, size));
+            }
+        }
+        mismatches
+    }
+}
+
+/// An expectation that did not hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioFailure {
+    /// Step number, from 1
+    pub step: usize,
+    pub message: String,
+}
+
+/// Outcome of running a scenario
+#[derive(Debug, Clone)]
+pub struct ScenarioReport {
+    pub name: String,
+    /// Steps run (fewer than the scenario's if an action failed)
+    pub steps_run: usize,
+    pub failures: Vec<ScenarioFailure>,
+}
+
+impl ScenarioReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed() {
+            return writeln!(f, "PASS {} ({} steps)", self.name, self.steps_run);
+        }
+        writeln!(f, "FAIL {}", self.name)?;
+        for failure in &self.failures {
+            writeln!(f, "  step {}: {}", failure.step, failure.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// What an action produced, for checking against its expectation
+enum Outcome {
+    Step(StepReport),
+    Risk(RiskDecision),
+    Nothing,
+}
+
+/// Runs scenarios against strategies from a registry
+pub struct ScenarioRunner {
+    registry: StrategyRegistry,
+}
+
+impl Default for ScenarioRunner {
+    fn default() -> Self {
+        Self::new(StrategyRegistry::with_builtins())
+    }
+}
+
+impl ScenarioRunner {
+    pub fn new(registry: StrategyRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// Run a scenario
+    ///
+    /// Fails only if the scenario cannot be set up (unknown strategy,
+    /// invalid risk config); failed expectations are listed in the report.
+    /// An action that fails unexpectedly ends the run, since later steps
+    /// would start from an unknown state.
+    pub async fn run(&self, scenario: &Scenario) -> StrategyResult<ScenarioReport> {
+        let strategy = self
+            .registry
+            .create(&scenario.strategy.kind, &scenario.strategy.params()?, &scenario.strategy.markets)?;
+        let defaults = SimulationConfig::default();
+        let risk_policy_yaml = match &scenario.risk {
+            Some(risk) => serde_yaml::to_string(risk)
+                .map_err(|e| StrategyError::ConfigError(format!("Invalid risk config: {}", e)))?,
+            None => defaults.risk_policy_yaml,
+        };
+        let config = SimulationConfig {
+            start_time: scenario
+                .start_time
+                .unwrap_or_else(|| Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            risk_policy_yaml,
+            fee_rate: scenario.fee_rate,
+            ..defaults
+        };
+        let mut sim = SimulationHarness::new(strategy, scenario.strategy.params()?, config).await?;
+
+        let mut report = ScenarioReport {
+            name: scenario.name.clone(),
+            steps_run: 0,
+            failures: Vec::new(),
+        };
+        for (index, step) in scenario.steps.iter().enumerate() {
+            let number = index + 1;
+            report.steps_run = number;
+            let mut fail = |message: String| report.failures.push(ScenarioFailure { step: number, message });
+
+            if step.action_count() != 1 {
+                fail("a step needs exactly one of tick, fill, cancel, timer, advance or risk_check".to_string());
+                break;
+            }
+            let expect = step.expect.clone().unwrap_or_default();
+            let counters_before = sim.risk_engine().lock().policy_counters();
+
+            let outcome = match run_action(&mut sim, step).await {
+                Ok(outcome) => {
+                    if let Some(error) = &expect.error {
+                        fail(format!("expected an error containing {:?}, the step succeeded", error));
+                    }
+                    outcome
+                }
+                Err(e) => match &expect.error {
+                    Some(error) if e.to_string().contains(error.as_str()) => Outcome::Nothing,
+                    Some(error) => {
+                        fail(format!("expected an error containing {:?}, got: {}", error, e));
+                        break;
+                    }
+                    None => {
+                        fail(format!("step failed: {}", e));
+                        break;
+                    }
+                },
+            };
+
+            let counters_after = sim.risk_engine().lock().policy_counters();
+            for message in check(&sim, &expect, &outcome, &counters_before, &counters_after) {
+                fail(message);
+            }
+        }
+
+        sim.shutdown().await?;
+        Ok(report)
+    }
+
+    /// Load and run a scenario file
+    pub async fn run_file(&self, path: impl AsRef<Path>) -> StrategyResult<ScenarioReport> {
+        self.run(&Scenario::from_file(path)?).await
+    }
+
+    /// Run every `.yaml`/`.yml` scenario in a directory, in file name order
+    pub async fn run_dir(&self, dir: impl AsRef<Path>) -> StrategyResult<Vec<ScenarioReport>> {
+        let dir = dir.as_ref();
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(|e| StrategyError::ConfigError(format!("{}: {}", dir.display(), e)))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+            .collect();
+        paths.sort();
+
+        let mut reports = Vec::with_capacity(paths.len());
+        for path in paths {
+            reports.push(self.run_file(&path).await?);
+        }
+        Ok(reports)
+    }
+}
+
+async fn run_action(sim: &mut SimulationHarness, step: &ScenarioStep) -> StrategyResult<Outcome> {
+    if let Some(tick) = &step.tick {
+        let tick = MarketTick {
+            market: tick.market.clone(),
+            timestamp: tick.at.unwrap_or_else(|| sim.now()),
+            bid: tick.bid,
+            ask: tick.ask,
+            bid_size: tick.bid_size,
+            ask_size: tick.ask_size,
+            last: tick.last,
+            volume_24h: tick.volume_24h,
+            sequence: None,
+        };
+        return Ok(Outcome::Step(sim.tick(tick).await?));
+    }
+    if let Some(fill) = &step.fill {
+        return Ok(Outcome::Step(sim.fill(&fill.order, fill.size, fill.price).await?));
+    }
+    if let Some(order_id) = &step.cancel {
+        return Ok(Outcome::Step(sim.cancel(order_id).await?));
+    }
+    if step.timer == Some(true) {
+        return Ok(Outcome::Step(sim.timer().await?));
+    }
+    if let Some(seconds) = step.advance {
+        sim.advance(Duration::milliseconds((seconds * 1000.0).round() as i64));
+        return Ok(Outcome::Nothing);
+    }
+    if let Some(check) = &step.risk_check {
+        let order = Order {
+            market: check.market.clone(),
+            side: check.side,
+            order_type: OrderType::Limit,
+            price: Some(check.price),
+            size: check.size,
+            ..Default::default()
+        };
+        return Ok(Outcome::Risk(sim.context().check_risk(&order)));
+    }
+    Ok(Outcome::Nothing)
+}
+
+/// Failed expectations of one step
+fn check(
+    sim: &SimulationHarness,
+    expect: &Expectation,
+    outcome: &Outcome,
+    counters_before: &BTreeMap<String, PolicyCounters>,
+    counters_after: &BTreeMap<String, PolicyCounters>,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    let empty = StepReport::default();
+    let report = match outcome {
+        Outcome::Step(report) => report,
+        _ => &empty,
+    };
+
+    if let Some(placed) = expect.placed {
+        if report.placed.len() != placed {
+            failures.push(format!("placed {} orders, expected {}", report.placed.len(), placed));
+        }
+    }
+    if let Some(removed) = expect.removed {
+        if report.removed.len() != removed {
+            failures.push(format!("removed {} orders, expected {}", report.removed.len(), removed));
+        }
+    }
+    if let Some(orders) = &expect.orders {
+        if report.placed.len() != orders.len() {
+            failures.push(format!("placed {} orders, expected {}", report.placed.len(), orders.len()));
+        }
+        for (i, (expected, order)) in orders.iter().zip(&report.placed).enumerate() {
+            let mismatches = expected.mismatches(order);
+            if !mismatches.is_empty() {
+                failures.push(format!("order {}: {}", i + 1, mismatches.join(", ")));
+            }
+        }
+    }
+    if let Some(open_orders) = expect.open_orders {
+        let open = sim.orders().len();
+        if open != open_orders {
+            failures.push(format!("{} open orders, expected {}", open, open_orders));
+        }
+    }
+    if let Some(positions) = &expect.positions {
+        for (market, expected) in positions {
+            let size = sim.context().get_position(market).map_or(0.0, |p| p.size);
+            if (size - expected).abs() > TOLERANCE {
+                failures.push(format!("position in {} is {}, expected {}", market, size, expected));
+            }
+        }
+    }
+    if let Some(rejections) = &expect.rejections {
+        for (policy, expected) in rejections {
+            let count = |counters: &BTreeMap<String, PolicyCounters>| -> u64 {
+                counters
+                    .iter()
+                    .filter(|(label, _)| *label == policy || label.starts_with(&format!("{} (", policy)))
+                    .map(|(_, c)| c.rejections)
+                    .sum()
+            };
+            let rejected = count(counters_after) - count(counters_before);
+            if rejected != *expected {
+                failures.push(format!("{} rejected {} orders, expected {}", policy, rejected, expected));
+            }
+        }
+    }
+
+    if expect.allowed.is_some() || expect.violations.is_some() {
+        let Outcome::Risk(decision) = outcome else {
+            failures.push("allowed and violations can only be expected of a risk_check".to_string());
+            return failures;
+        };
+        if let Some(allowed) = expect.allowed {
+            if decision.allowed != allowed {
+                failures.push(format!("risk check allowed: {}, expected {}", decision.allowed, allowed));
+            }
+        }
+        if let Some(violations) = &expect.violations {
+            let actual: Vec<&str> = decision.violations.iter().map(|v| v.policy.as_str()).collect();
+            if actual != violations.iter().map(|v| v.as_str()).collect::<Vec<_>>() {
+                failures.push(format!("risk check violations {:?}, expected {:?}", actual, violations));
+            }
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+name: position limit
+strategy:
+  kind: market_maker
+  markets: [m1]
+  params:
+    quote_size: 100
+    min_quote_interval_ms: 0
+risk:
+  policies:
+    - type: PositionLimit
+      max_size: 150
+steps:
+  - tick: { market: m1, bid: 0.49, ask: 0.51, bid_size: 100, ask_size: 100 }
+    expect:
+      placed: 2
+      orders:
+        - { side: Buy, size: 100 }
+        - { side: Sell, size: 100 }
+  - fill: { order: order_1 }
+    expect:
+      removed: 1
+      positions: { m1: 100 }
+  - risk_check: { market: m1, side: Buy, size: 100, price: 0.5 }
+    expect:
+      allowed: false
+      violations: [PositionLimit]
+  - fill: { order: missing }
+    expect:
+      error: "Order not found"
+"#;
+
+    #[tokio::test]
+    async fn test_passing_scenario() {
+        let scenario = Scenario::from_yaml(SCENARIO).unwrap();
+        let report = ScenarioRunner::default().run(&scenario).await.unwrap();
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.steps_run, 4);
+    }
+
+    #[tokio::test]
+    async fn test_failed_expectations_are_reported() {
+        let yaml = SCENARIO
+            .replace("positions: { m1: 100 }", "positions: { m1: 50 }")
+            .replace("allowed: false", "allowed: true");
+        let report = ScenarioRunner::default().run(&Scenario::from_yaml(&yaml).unwrap()).await.unwrap();
+        assert_eq!(
+            report.failures,
+            vec![
+                ScenarioFailure { step: 2, message: "position in m1 is 100, expected 50".to_string() },
+                ScenarioFailure { step: 3, message: "risk check allowed: false, expected true".to_string() },
+            ]
+        );
+        assert!(report.to_string().starts_with("FAIL position limit"));
+
+        // Typos in expectations are rejected rather than ignored
+        assert!(Scenario::from_yaml(&SCENARIO.replace("placed: 2", "plced: 2")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_example_scenarios() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/scenarios");
+        let reports = ScenarioRunner::default().run_dir(dir).await.unwrap();
+        assert!(!reports.is_empty());
+        for report in reports {
+            assert!(report.passed(), "{}", report);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Tick-to-order latency of the in-process pipeline
+//!
+//! Measures the full path a market data tick takes before orders reach the
+//! execution engine: coordinator routing, the market maker's signal and
+//! quote calculation, the pre-trade risk check and order placement. Every
+//! tick requotes (two cancels, two orders), which is the worst case for a
+//! quoting strategy.
+//!
+//! Run with: cargo bench --bench tick_to_order
+//!
+//! Fails (exit code 1) when the p99 latency exceeds the budget, 1ms by
+//! default; set `TICK_BUDGET_US` to change it and `TICK_SAMPLES` for the
+//! number of measured ticks.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ag_risk::clock::{Clock, MockClock};
+use ag_risk::RiskEngine;
+use ag_strategies::r#impl::{MarketMakerConfig, MarketMakerStrategy};
+use ag_strategies::{MarketTick, MultiMarketCoordinator, StrategyContext, StrategyParams};
+use chrono::{TimeZone, Utc};
+use parking_lot::Mutex;
+
+/// Markets quoted, one market maker each
+const MARKETS: usize = 20;
+
+/// Ticks routed before measuring
+const WARMUP_TICKS: usize = 10_000;
+
+const POLICIES: &str = r#"
+policies:
+  - type: KillSwitch
+    enabled: false
+  - type: PositionLimit
+    max_size: 10000.0
+  - type: InventoryLimit
+    max_value_usd: 100000.0
+  - type: DailyLossLimit
+    max_loss_usd: 5000.0
+  - type: LossStreakCooldown
+    max_losses: 5
+    window_secs: 3600
+    cooldown_secs: 600
+"#;
+
+fn main() {
+    let budget = Duration::from_micros(env_or("TICK_BUDGET_US", 1_000));
+    let samples = env_or("TICK_SAMPLES", 100_000) as usize;
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let latencies = runtime.block_on(run(samples));
+
+    let report = LatencyReport::new(latencies);
+    println!("=== Tick-to-Order Latency ({} markets, {} ticks) ===", MARKETS, samples);
+    report.print();
+    println!("  p99 budget: {:?}", budget);
+    if report.percentile(99.0) > budget {
+        println!("FAIL: p99 {:?} exceeds the budget", report.percentile(99.0));
+        std::process::exit(1);
+    }
+    println!("OK");
+}
+
+async fn run(samples: usize) -> Vec<Duration> {
+    let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let clock = Arc::new(MockClock::new(start));
+    let risk_engine = RiskEngine::from_yaml(POLICIES).unwrap();
+    risk_engine.set_clock(clock.clone());
+    let risk_engine = Arc::new(Mutex::new(risk_engine));
+
+    let mut coordinator = MultiMarketCoordinator::new();
+    coordinator.set_clock(clock.clone());
+    let markets: Vec<String> = (0..MARKETS).map(|i| format!("0x{:040x}", i)).collect();
+    for (i, market) in markets.iter().enumerate() {
+        let config = MarketMakerConfig {
+            min_quote_interval_ms: 0,
+            max_position: 5000.0,
+            ..Default::default()
+        };
+        let strategy = MarketMakerStrategy::new(market.clone(), config);
+        let id = format!("mm_{}", i);
+        let ctx = StrategyContext::new(id.clone(), risk_engine.clone(), StrategyParams::new());
+        coordinator
+            .register_strategy(id, Box::new(strategy), ctx, vec![market.clone()])
+            .await
+            .unwrap();
+    }
+
+    let mut latencies = Vec::with_capacity(samples);
+    for n in 0..WARMUP_TICKS + samples {
+        let market = &markets[n % MARKETS];
+        let mid = 0.5 + 0.05 * ((n / MARKETS) as f64 * 0.01).sin();
+        let tick = MarketTick {
+            market: market.clone(),
+            timestamp: clock.now(),
+            bid: Some(mid - 0.01),
+            bid_size: Some(500.0),
+            ask: Some(mid + 0.01),
+            ask_size: Some(500.0),
+            last: Some(mid),
+            volume_24h: None,
+            sequence: Some(n as u64),
+        };
+        clock.advance(chrono::Duration::milliseconds(1));
+
+        let started = Instant::now();
+        coordinator.route_market_tick(market, &tick).await.unwrap();
+        let elapsed = started.elapsed();
+        if n >= WARMUP_TICKS {
+            latencies.push(elapsed);
+        }
+    }
+
+    // Every market ends up quoted both ways, so no tick stopped short of
+    // placing orders
+    for i in 0..MARKETS {
+        let ctx = coordinator.get_context(&format!("mm_{}", i)).unwrap();
+        assert_eq!(ctx.get_open_orders().len(), 2, "mm_{} is not quoting", i);
+    }
+    latencies
+}
+
+struct LatencyReport {
+    sorted: Vec<Duration>,
+}
+
+impl LatencyReport {
+    fn new(mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        Self { sorted: latencies }
+    }
+
+    fn percentile(&self, p: f64) -> Duration {
+        let rank = ((p / 100.0) * self.sorted.len() as f64).ceil() as usize;
+        self.sorted[rank.clamp(1, self.sorted.len()) - 1]
+    }
+
+    fn print(&self) {
+        let total: Duration = self.sorted.iter().sum();
+        println!("  mean:   {:?}", total / self.sorted.len() as u32);
+        for p in [50.0, 90.0, 99.0, 99.9] {
+            println!("  p{:<5} {:?}", p, self.percentile(p));
+        }
+        println!("  max:    {:?}", self.sorted[self.sorted.len() - 1]);
+        println!("  ticks/s: {:.0}", self.sorted.len() as f64 / total.as_secs_f64());
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
@@ -37,6 +37,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             ask_size: Some(100.0 + ((i + 25) % 50) as f64),
             last: Some(base_price),
             volume_24h: Some(10000.0),
+            sequence: None,
         };
 
         historical_ticks.push(tick);
@@ -56,6 +57,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         inventory_target: 0.0,
         skew_factor: 0.5,
         min_quote_interval_ms: 5000, // Every 5 seconds
+        adaptive_requote: None,
+        toxicity: None,
+        sizing: None,
     };
 
     let strategy = Box::new(MarketMakerStrategy::new(
@@ -78,6 +82,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             fill_probability: 0.7,
             taker_fee_bps: 10.0,
             maker_fee_bps: -5.0,
+            adverse_selection: 0.5,
+            adverse_horizon_ticks: 5,
+            seed: None,
         },
         risk_policy_yaml: r#"
 policies:
@@ -86,6 +93,7 @@ policies:
   - type: InventoryLimit
     max_value_usd: 10000.0
 "#.to_string(),
+        ..Default::default()
     };
 
     println!("Backtest Configuration:");
@@ -167,6 +175,12 @@ policies:
         println!("⚠ High drawdown (> 10%)");
     }
 
+    // 8. Save the result for compare_backtests
+    if let Some(path) = std::env::args().nth(1) {
+        std::fs::write(&path, serde_json::to_string_pretty(&result)?)?;
+        println!("Result written to {}", path);
+    }
+
     println!();
     println!("Backtest complete!");
 
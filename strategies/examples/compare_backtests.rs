@@ -0,0 +1,65 @@
+//! Example: Comparing a candidate backtest against a baseline
+//!
+//! Reads two `BacktestResult` JSON files (as written by the `backtest`
+//! example), prints the comparison and exits with status 1 on regression,
+//! so it can gate strategy changes in CI:
+//!
+//! ```bash
+//! cargo run --example compare_backtests -- baseline.json candidate.json \
+//!     --alpha 0.05 --max-return-drop 50 --max-drawdown-increase 2 --json
+//! ```
+
+use ag_strategies::backtest::{compare, BacktestResult, CompareConfig};
+
+const USAGE: &str = "usage: compare_backtests <baseline.json> <candidate.json> \
+                     [--alpha P] [--max-return-drop USD] [--max-drawdown-increase PP] [--json]";
+
+fn load(path: &str) -> Result<BacktestResult, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(serde_json::from_str(&content).map_err(|e| format!("{}: {}", path, e))?)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let mut files = Vec::new();
+    let mut config = CompareConfig::default();
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| -> Result<f64, Box<dyn std::error::Error>> {
+            let raw = args.next().ok_or_else(|| format!("{} needs a value\n{}", name, USAGE))?;
+            Ok(raw.parse().map_err(|_| format!("{}: not a number: {}", name, raw))?)
+        };
+        match arg.as_str() {
+            "--alpha" => config.significance_level = value("--alpha")?,
+            "--max-return-drop" => config.max_return_drop = Some(value("--max-return-drop")?),
+            "--max-drawdown-increase" => {
+                config.max_drawdown_increase_pct = Some(value("--max-drawdown-increase")?)
+            }
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => files.push(arg),
+        }
+    }
+    if files.len() != 2 {
+        return Err(USAGE.into());
+    }
+
+    let baseline = load(&files[0])?;
+    let candidate = load(&files[1])?;
+    let comparison = compare(&baseline, &candidate, &config);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&comparison)?);
+    } else {
+        println!("{}", comparison.summary());
+    }
+
+    if comparison.is_regression() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
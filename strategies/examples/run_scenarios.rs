@@ -0,0 +1,29 @@
+//! Example: Running declarative scenario tests
+//!
+//! Runs every `.yaml` scenario in a directory (default
+//! `examples/scenarios`) against the built-in strategies and exits non-zero
+//! if any expectation fails:
+//!
+//! ```bash
+//! cargo run --example run_scenarios
+//! cargo run --example run_scenarios -- path/to/scenarios
+//! ```
+
+use ag_strategies::backtest::ScenarioRunner;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::args().nth(1).unwrap_or_else(|| "examples/scenarios".to_string());
+    let reports = ScenarioRunner::default().run_dir(&dir).await?;
+
+    let failed = reports.iter().filter(|r| !r.passed()).count();
+    for report in &reports {
+        print!("{}", report);
+    }
+    println!("{} scenarios, {} failed", reports.len(), failed);
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -29,6 +29,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         inventory_target: 0.0,       // Target neutral inventory
         skew_factor: 0.5,            // 50% inventory skew adjustment
         min_quote_interval_ms: 100,  // Requote every 100ms minimum
+        adaptive_requote: None,
+        toxicity: None,
+        sizing: None,
     };
 
     println!("Market Maker Config:");
@@ -101,6 +104,7 @@ policies:
             ask_size: Some(100.0),
             last: Some(base_price + 0.1),
             volume_24h: Some(10000.0),
+            sequence: None,
         };
 
         println!("Tick {}: mid={:.2}, spread={:.3}", i+1, tick.mid_price(), tick.spread().unwrap_or(0.0));
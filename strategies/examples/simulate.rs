@@ -0,0 +1,61 @@
+//! Example: Stepping a market maker through hand-crafted events
+//!
+//! Runs a `SimulationHarness` around `MarketMakerStrategy` on market `m1`.
+//! With a script path it runs the script and prints the transcript;
+//! without one it reads commands from stdin as a REPL:
+//!
+//! ```bash
+//! cargo run --example simulate -- examples/scenarios/market_maker.sim
+//! cargo run --example simulate
+//! sim> tick m1 0.49 0.51
+//! sim> fill order_1 50
+//! sim> positions
+//! ```
+
+use ag_strategies::backtest::{SimulationConfig, SimulationHarness};
+use ag_strategies::r#impl::{MarketMakerConfig, MarketMakerStrategy};
+use ag_strategies::StrategyParams;
+use std::io::{BufRead, Write};
+
+const HELP: &str = "\
+tick <market> <bid> <ask> [bid_size] [ask_size]
+fill <order_id> [size] [price]
+cancel <order_id>
+timer
+advance <seconds>
+orders | positions | metrics | time
+quit";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let strategy = MarketMakerStrategy::new("m1".to_string(), MarketMakerConfig::default());
+    let mut sim = SimulationHarness::new(Box::new(strategy), StrategyParams::new(), SimulationConfig::default()).await?;
+
+    if let Some(path) = std::env::args().nth(1) {
+        let script = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+        print!("{}", sim.run_script(&script).await?);
+        return Ok(());
+    }
+
+    println!("Market maker on m1; 'help' lists commands");
+    let stdin = std::io::stdin();
+    loop {
+        print!("sim> ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        match line.trim() {
+            "quit" | "exit" => break,
+            "help" => println!("{}", HELP),
+            command => match sim.execute(command).await {
+                Ok(output) => print!("{}", output),
+                Err(e) => println!("error: {}", e),
+            },
+        }
+    }
+
+    print!("{}", sim.shutdown().await?);
+    Ok(())
+}
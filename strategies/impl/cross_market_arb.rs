@@ -1,6 +1,6 @@
 //! Cross-market arbitrage strategy
 
-use crate::{Strategy, StrategyContext, StrategyError, StrategyResult, StrategyMetadata};
+use crate::{Strategy, StrategyContext, StrategyError, StrategyResult, StrategyMetadata, StrategyParams};
 use crate::types::{MarketTick, Fill, OrderId, Order, Side, OrderType, TimeInForce};
 use crate::metrics::MetricBuilder;
 use async_trait::async_trait;
@@ -30,6 +30,18 @@ impl Default for CrossMarketArbConfig {
     }
 }
 
+impl CrossMarketArbConfig {
+    /// Build a config from strategy params, using defaults for missing keys
+    pub fn from_params(params: &StrategyParams) -> Self {
+        let defaults = Self::default();
+        Self {
+            min_spread_bps: params.get_typed("min_spread_bps").unwrap_or(defaults.min_spread_bps),
+            size: params.get_typed("size").unwrap_or(defaults.size),
+            max_position: params.get_typed("max_position").unwrap_or(defaults.max_position),
+        }
+    }
+}
+
 /// Cross-market arbitrage strategy
 ///
 /// Monitors two markets for price discrepancies and executes arbitrage
@@ -128,45 +140,25 @@ impl CrossMarketArbStrategy {
             ..Default::default()
         };
 
-        // Execute both legs
-        match ctx.submit_order(buy_order).await {
-            Ok(buy_order_id) => {
-                tracing::info!(
-                    market = %buy_market,
-                    price = %buy_price,
-                    size = %self.config.size,
-                    "Buy leg submitted"
-                );
-
-                if let Some(ref builder) = self.metric_builder {
-                    let metric = builder.order_placed(buy_market, "buy");
-                    ctx.emit_metric(metric).await?;
-                }
-            }
-            Err(e) => {
-                tracing::error!(error = ?e, market = %buy_market, "Buy leg failed");
-                return Err(e);
-            }
+        // Execute both legs together; the group is risk checked on its net
+        // exposure and nothing is sent unless both legs pass
+        if let Err(e) = ctx.submit_order_group(vec![buy_order, sell_order]).await {
+            tracing::error!(error = ?e, buy_market = %buy_market, sell_market = %sell_market, "Arb legs failed");
+            return Err(e);
         }
 
-        match ctx.submit_order(sell_order).await {
-            Ok(sell_order_id) => {
-                tracing::info!(
-                    market = %sell_market,
-                    price = %sell_price,
-                    size = %self.config.size,
-                    "Sell leg submitted"
-                );
-
-                if let Some(ref builder) = self.metric_builder {
-                    let metric = builder.order_placed(sell_market, "sell");
-                    ctx.emit_metric(metric).await?;
-                }
-            }
-            Err(e) => {
-                tracing::error!(error = ?e, market = %sell_market, "Sell leg failed");
-                return Err(e);
-            }
+        tracing::info!(
+            buy_market = %buy_market,
+            sell_market = %sell_market,
+            buy_price = %buy_price,
+            sell_price = %sell_price,
+            size = %self.config.size,
+            "Arb legs submitted"
+        );
+
+        if let Some(ref builder) = self.metric_builder {
+            ctx.emit_metric(builder.order_placed(buy_market, "buy")).await?;
+            ctx.emit_metric(builder.order_placed(sell_market, "sell")).await?;
         }
 
         Ok(())
@@ -268,14 +260,7 @@ impl Strategy for CrossMarketArbStrategy {
         fill: &Fill,
         ctx: &mut StrategyContext,
     ) -> StrategyResult<()> {
-        // Update position
-        let size_delta = match fill.side {
-            Side::Buy => fill.size,
-            Side::Sell => -fill.size,
-        };
-
-        ctx.update_position(&fill.market, size_delta, fill.price);
-
+        // Position already updated by the coordinator (apply_fill)
         // Emit metrics
         if let Some(ref builder) = self.metric_builder {
             let metric = builder.order_filled(&fill.market);
@@ -360,6 +345,7 @@ impl Strategy for CrossMarketArbStrategy {
                 "market_b".to_string(),
                 "min_spread_bps".to_string(),
             ],
+            warmup_ticks: 0,
         }
     }
 }
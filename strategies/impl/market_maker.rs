@@ -1,11 +1,12 @@
 //! Market making strategy with inventory skewing
 
-use crate::{Strategy, StrategyContext, StrategyError, StrategyResult, StrategyMetadata};
-use crate::types::{MarketTick, Fill, OrderId, Order, Side, OrderType, TimeInForce};
+use crate::{Strategy, StrategyContext, StrategyError, StrategyResult, StrategyMetadata, StrategyParams};
+use crate::types::{MarketTick, Fill, OrderId, OrderTemplate, Side, TimeInForce};
 use crate::metrics::MetricBuilder;
+use crate::signals::{AdaptiveRequoteConfig, ToxicityConfig, ToxicityMonitor, VolatilityEstimator};
+use crate::sizing::{DrawdownTracker, PositionSizer, SizingConfig, SizingInputs};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use chrono::Utc;
 
 /// Market maker configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,8 +26,23 @@ pub struct MarketMakerConfig {
     /// Inventory skew factor (how much to adjust quotes based on inventory)
     pub skew_factor: f64,
 
-    /// Minimum quote interval in milliseconds
+    /// Minimum quote interval in milliseconds (the base interval when
+    /// `adaptive_requote` is set)
     pub min_quote_interval_ms: u64,
+
+    /// Scale the quote interval with realized volatility and trade
+    /// intensity (fixed `min_quote_interval_ms` if None)
+    #[serde(default)]
+    pub adaptive_requote: Option<AdaptiveRequoteConfig>,
+
+    /// Widen or pull quotes on adverse fill markouts (disabled if None)
+    #[serde(default)]
+    pub toxicity: Option<ToxicityConfig>,
+
+    /// Size quotes from allocated capital and drawdown, up to `quote_size`
+    /// (fixed `quote_size` if None)
+    #[serde(default)]
+    pub sizing: Option<SizingConfig>,
 }
 
 impl Default for MarketMakerConfig {
@@ -38,6 +54,74 @@ impl Default for MarketMakerConfig {
             inventory_target: 0.0,
             skew_factor: 0.5,
             min_quote_interval_ms: 100,
+            adaptive_requote: None,
+            toxicity: None,
+            sizing: None,
+        }
+    }
+}
+
+impl MarketMakerConfig {
+    /// Build a config from strategy params, using defaults for missing keys
+    ///
+    /// The toxicity monitor is enabled with `toxicity_enabled: "true"` and
+    /// tuned with `toxicity_widen_bps`, `toxicity_pull_bps` and
+    /// `toxicity_max_multiplier`.
+    ///
+    /// Sizing is enabled with `sizing_method` (see
+    /// [`SizingConfig::from_params`]).
+    ///
+    /// The adaptive requote interval is enabled with
+    /// `adaptive_requote_enabled: "true"` and bounded by
+    /// `requote_min_interval_ms` and `requote_max_interval_ms`.
+    pub fn from_params(params: &StrategyParams) -> Self {
+        let defaults = Self::default();
+        let adaptive_requote = params
+            .get_typed::<bool>("adaptive_requote_enabled")
+            .unwrap_or(false)
+            .then(|| {
+                let defaults = AdaptiveRequoteConfig::default();
+                AdaptiveRequoteConfig {
+                    min_interval_ms: params
+                        .get_typed("requote_min_interval_ms")
+                        .unwrap_or(defaults.min_interval_ms),
+                    max_interval_ms: params
+                        .get_typed("requote_max_interval_ms")
+                        .unwrap_or(defaults.max_interval_ms),
+                    ..defaults
+                }
+            });
+        let toxicity = params
+            .get_typed::<bool>("toxicity_enabled")
+            .unwrap_or(false)
+            .then(|| {
+                let defaults = ToxicityConfig::default();
+                ToxicityConfig {
+                    widen_threshold_bps: params
+                        .get_typed("toxicity_widen_bps")
+                        .unwrap_or(defaults.widen_threshold_bps),
+                    pull_threshold_bps: params
+                        .get_typed("toxicity_pull_bps")
+                        .unwrap_or(defaults.pull_threshold_bps),
+                    max_spread_multiplier: params
+                        .get_typed("toxicity_max_multiplier")
+                        .unwrap_or(defaults.max_spread_multiplier),
+                    ..defaults
+                }
+            });
+        let sizing = SizingConfig::from_params(params);
+        Self {
+            target_spread_bps: params.get_typed("target_spread_bps").unwrap_or(defaults.target_spread_bps),
+            quote_size: params.get_typed("quote_size").unwrap_or(defaults.quote_size),
+            max_position: params.get_typed("max_position").unwrap_or(defaults.max_position),
+            inventory_target: params.get_typed("inventory_target").unwrap_or(defaults.inventory_target),
+            skew_factor: params.get_typed("skew_factor").unwrap_or(defaults.skew_factor),
+            min_quote_interval_ms: params
+                .get_typed("min_quote_interval_ms")
+                .unwrap_or(defaults.min_quote_interval_ms),
+            adaptive_requote,
+            toxicity,
+            sizing,
         }
     }
 }
@@ -46,23 +130,77 @@ impl Default for MarketMakerConfig {
 ///
 /// This strategy continuously quotes bid and ask prices around the mid price,
 /// adjusting the quotes based on current inventory to encourage mean reversion.
+/// With a toxicity config, fill markouts widen the spread or pull quotes
+/// entirely while flow is adverse. With a sizing config, quote sizes follow
+/// allocated capital and shrink as the strategy's equity draws down. With
+/// an adaptive requote config, the quote interval shortens in volatile or
+/// busy markets and stretches in quiet ones.
 pub struct MarketMakerStrategy {
     config: MarketMakerConfig,
     market_id: String,
     last_quote_time: Option<i64>,
     metric_builder: Option<MetricBuilder>,
+    toxicity: Option<ToxicityMonitor>,
+    quotes_pulled: bool,
+    sizer: Option<PositionSizer>,
+    drawdown: DrawdownTracker,
+    volatility: Option<VolatilityEstimator>,
+    requote_interval_ms: u64,
+    /// Quotes, prebuilt for the market
+    quotes: OrderTemplate,
 }
 
 impl MarketMakerStrategy {
     pub fn new(market_id: String, config: MarketMakerConfig) -> Self {
+        let toxicity = config.toxicity.clone().map(ToxicityMonitor::new);
+        let sizer = config.sizing.clone().map(PositionSizer::new);
+        let volatility = config
+            .adaptive_requote
+            .as_ref()
+            .map(|adaptive| VolatilityEstimator::new(adaptive.half_life_ms));
+        let requote_interval_ms = config.min_quote_interval_ms;
+        let quotes = OrderTemplate::limit("polymarket", market_id.clone(), TimeInForce::GTC);
         Self {
             config,
             market_id,
             last_quote_time: None,
             metric_builder: None,
+            toxicity,
+            quotes_pulled: false,
+            sizer,
+            drawdown: DrawdownTracker::new(),
+            volatility,
+            requote_interval_ms,
+            quotes,
         }
     }
 
+    /// Toxicity monitor, if enabled
+    pub fn toxicity(&self) -> Option<&ToxicityMonitor> {
+        self.toxicity.as_ref()
+    }
+
+    /// Volatility estimator, if the adaptive requote interval is enabled
+    pub fn volatility(&self) -> Option<&VolatilityEstimator> {
+        self.volatility.as_ref()
+    }
+
+    /// Requote interval currently in effect, in milliseconds
+    pub fn requote_interval_ms(&self) -> u64 {
+        self.requote_interval_ms
+    }
+
+    /// Recompute the requote interval from the volatility estimate and the
+    /// half spread being quoted
+    fn update_requote_interval(&mut self, spread_multiplier: f64) {
+        let (Some(adaptive), Some(estimator)) = (self.config.adaptive_requote.as_ref(), self.volatility.as_ref())
+        else {
+            return;
+        };
+        let half_spread_bps = self.config.target_spread_bps * spread_multiplier / 2.0;
+        self.requote_interval_ms = adaptive.interval_ms(self.config.min_quote_interval_ms, half_spread_bps, estimator);
+    }
+
     /// Calculate inventory skew
     fn calculate_inventory_skew(&self, position: f64) -> f64 {
         if self.config.max_position < 1e-8 {
@@ -72,8 +210,8 @@ impl MarketMakerStrategy {
     }
 
     /// Calculate bid and ask prices based on mid and inventory
-    fn calculate_quotes(&self, mid: f64, position: f64) -> (f64, f64) {
-        let base_spread = mid * self.config.target_spread_bps / 10000.0;
+    fn calculate_quotes(&self, mid: f64, position: f64, spread_multiplier: f64) -> (f64, f64) {
+        let base_spread = mid * self.config.target_spread_bps / 10000.0 * spread_multiplier;
         let inventory_skew = self.calculate_inventory_skew(position);
 
         // Adjust spread and skew based on inventory
@@ -90,13 +228,37 @@ impl MarketMakerStrategy {
         (bid_price, ask_price)
     }
 
+    /// Quote size: sized from allocated capital plus PnL when sizing is
+    /// enabled and capital has been allocated, capped at `quote_size`
+    ///
+    /// With a marginal CVaR budget, the size is also capped so that a fill
+    /// on either side stays within it.
+    fn quote_size(&mut self, mid: f64, ctx: &StrategyContext) -> f64 {
+        let (Some(sizer), Some(capital)) = (self.sizer.as_ref(), ctx.allocated_capital) else {
+            return self.config.quote_size;
+        };
+        let equity = capital + ctx.calculate_total_unrealized_pnl();
+        let drawdown = self.drawdown.update(equity);
+        let size = sizer.size(&SizingInputs {
+            equity,
+            drawdown,
+            signal_strength: 1.0,
+            price: mid,
+            volatility: None,
+        });
+        let confidence = sizer.config().cvar_confidence;
+        let capped = |side: f64| {
+            sizer.cap_marginal_cvar(size, equity, |s| ctx.marginal_cvar(&self.market_id, side * s, mid, confidence))
+        };
+        capped(1.0).min(capped(-1.0)).min(self.config.quote_size)
+    }
+
     /// Check if we should requote (based on time interval)
-    fn should_requote(&self) -> bool {
+    fn should_requote(&self, now: i64) -> bool {
         match self.last_quote_time {
             None => true,
             Some(last_time) => {
-                let now = Utc::now().timestamp_millis();
-                (now - last_time) >= self.config.min_quote_interval_ms as i64
+                (now - last_time) >= self.requote_interval_ms as i64
             }
         }
     }
@@ -127,8 +289,59 @@ impl Strategy for MarketMakerStrategy {
             return Ok(());
         }
 
+        let mid = tick.mid_price();
+        if let Some(estimator) = self.volatility.as_mut() {
+            estimator.on_tick(tick);
+        }
+
+        // Resolve fill markouts and apply the toxicity adjustment
+        let mut spread_multiplier = 1.0;
+        if let Some(monitor) = self.toxicity.as_mut() {
+            let markouts = if mid > 1e-8 {
+                monitor.on_mid(market_id, mid, tick.timestamp)
+            } else {
+                Vec::new()
+            };
+            let action = monitor.action(market_id);
+
+            if let Some(ref builder) = self.metric_builder {
+                for markout in &markouts {
+                    ctx.emit_metric(builder.markout(market_id, markout.horizon_ms, markout.bps)).await?;
+                }
+            }
+
+            match action.spread_multiplier() {
+                Some(multiplier) => {
+                    self.quotes_pulled = false;
+                    spread_multiplier = multiplier;
+                }
+                None => {
+                    if !self.quotes_pulled {
+                        tracing::warn!(
+                            market_id = %market_id,
+                            worst_markout_bps = ?monitor.worst_markout(market_id),
+                            "Adverse fill markouts, pulling quotes"
+                        );
+                        let open_orders: Vec<OrderId> = ctx.get_open_orders_for_market(market_id)
+                            .iter()
+                            .filter_map(|o| o.id.clone())
+                            .collect();
+                        for order_id in open_orders {
+                            ctx.cancel_order(&order_id).await?;
+                        }
+                        if let Some(ref builder) = self.metric_builder {
+                            ctx.emit_metric(builder.quotes_pulled(market_id)).await?;
+                        }
+                        self.quotes_pulled = true;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+
         // Check if we should requote
-        if !self.should_requote() {
+        self.update_requote_interval(spread_multiplier);
+        if !self.should_requote(ctx.now().timestamp_millis()) {
             return Ok(());
         }
 
@@ -148,14 +361,23 @@ impl Strategy for MarketMakerStrategy {
             return Ok(());
         }
 
-        // Calculate mid price
-        let mid = tick.mid_price();
+        // Validate mid price
         if mid < 1e-8 {
             return Ok(()); // Invalid price
         }
 
         // Calculate bid and ask prices
-        let (bid_price, ask_price) = self.calculate_quotes(mid, position);
+        let (bid_price, ask_price) = self.calculate_quotes(mid, position, spread_multiplier);
+        if self.toxicity.is_some() {
+            if let Some(ref builder) = self.metric_builder {
+                ctx.emit_metric(builder.toxicity_spread_multiplier(market_id, spread_multiplier)).await?;
+            }
+        }
+        if self.volatility.is_some() {
+            if let Some(ref builder) = self.metric_builder {
+                ctx.emit_metric(builder.requote_interval(market_id, self.requote_interval_ms as f64)).await?;
+            }
+        }
 
         // Cancel existing orders
         let open_orders: Vec<OrderId> = ctx.get_open_orders_for_market(market_id)
@@ -168,20 +390,17 @@ impl Strategy for MarketMakerStrategy {
         }
 
         // Submit new quotes if within position limits
-        let can_buy = position + self.config.quote_size <= self.config.max_position;
-        let can_sell = position - self.config.quote_size >= -self.config.max_position;
+        let quote_size = self.quote_size(mid, ctx);
+        if quote_size < 1e-8 {
+            tracing::debug!(market_id = %market_id, "Sized quote is zero, not quoting");
+            self.last_quote_time = Some(ctx.now().timestamp_millis());
+            return Ok(());
+        }
+        let can_buy = position + quote_size <= self.config.max_position;
+        let can_sell = position - quote_size >= -self.config.max_position;
 
         if can_buy {
-            let bid_order = Order {
-                venue: "polymarket".to_string(),
-                market: market_id.to_string(),
-                side: Side::Buy,
-                order_type: OrderType::Limit,
-                price: Some(bid_price),
-                size: self.config.quote_size,
-                time_in_force: TimeInForce::GTC,
-                ..Default::default()
-            };
+            let bid_order = self.quotes.order(Side::Buy, bid_price, quote_size);
 
             match ctx.submit_order(bid_order).await {
                 Ok(order_id) => {
@@ -190,10 +409,10 @@ impl Strategy for MarketMakerStrategy {
                         ctx.emit_metric(metric).await?;
                     }
                 }
-                Err(StrategyError::RiskRejected { policies }) => {
+                Err(StrategyError::RiskRejected { violations }) => {
                     tracing::warn!(
                         market_id = %market_id,
-                        policies = ?policies,
+                        violations = ?violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
                         "Bid order rejected by risk engine"
                     );
                 }
@@ -202,16 +421,7 @@ impl Strategy for MarketMakerStrategy {
         }
 
         if can_sell {
-            let ask_order = Order {
-                venue: "polymarket".to_string(),
-                market: market_id.to_string(),
-                side: Side::Sell,
-                order_type: OrderType::Limit,
-                price: Some(ask_price),
-                size: self.config.quote_size,
-                time_in_force: TimeInForce::GTC,
-                ..Default::default()
-            };
+            let ask_order = self.quotes.order(Side::Sell, ask_price, quote_size);
 
             match ctx.submit_order(ask_order).await {
                 Ok(order_id) => {
@@ -220,10 +430,10 @@ impl Strategy for MarketMakerStrategy {
                         ctx.emit_metric(metric).await?;
                     }
                 }
-                Err(StrategyError::RiskRejected { policies }) => {
+                Err(StrategyError::RiskRejected { violations }) => {
                     tracing::warn!(
                         market_id = %market_id,
-                        policies = ?policies,
+                        violations = ?violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
                         "Ask order rejected by risk engine"
                     );
                 }
@@ -231,7 +441,7 @@ impl Strategy for MarketMakerStrategy {
             }
         }
 
-        self.last_quote_time = Some(Utc::now().timestamp_millis());
+        self.last_quote_time = Some(ctx.now().timestamp_millis());
 
         Ok(())
     }
@@ -241,13 +451,10 @@ impl Strategy for MarketMakerStrategy {
         fill: &Fill,
         ctx: &mut StrategyContext,
     ) -> StrategyResult<()> {
-        // Update position
-        let size_delta = match fill.side {
-            Side::Buy => fill.size,
-            Side::Sell => -fill.size,
-        };
-
-        ctx.update_position(&fill.market, size_delta, fill.price);
+        // Position already updated by the coordinator (apply_fill)
+        if let Some(monitor) = self.toxicity.as_mut() {
+            monitor.record_fill(&fill.market, fill.side, fill.price, fill.size, fill.timestamp);
+        }
 
         // Emit metrics
         if let Some(ref builder) = self.metric_builder {
@@ -332,6 +539,7 @@ impl Strategy for MarketMakerStrategy {
                 "quote_size".to_string(),
                 "max_position".to_string(),
             ],
+            warmup_ticks: 0,
         }
     }
 }
@@ -343,6 +551,7 @@ mod tests {
     use ag_risk::RiskEngine;
     use std::sync::Arc;
     use parking_lot::Mutex;
+    use std::collections::HashMap;
 
     fn create_test_context() -> StrategyContext {
         let yaml = r#"
@@ -392,13 +601,101 @@ policies:
         let strategy = MarketMakerStrategy::new("market1".to_string(), config);
 
         // Neutral position
-        let (bid, ask) = strategy.calculate_quotes(100.0, 0.0);
+        let (bid, ask) = strategy.calculate_quotes(100.0, 0.0, 1.0);
         assert!(bid < 100.0);
         assert!(ask > 100.0);
         assert!((ask - bid - 0.2).abs() < 0.01); // Spread should be ~0.2
 
         // Long position - should encourage selling
-        let (bid_long, ask_long) = strategy.calculate_quotes(100.0, 500.0);
+        let (bid_long, ask_long) = strategy.calculate_quotes(100.0, 500.0, 1.0);
         assert!(ask_long < ask); // Lower ask to encourage selling
+
+        // Toxicity widening scales the spread
+        let (bid_wide, ask_wide) = strategy.calculate_quotes(100.0, 0.0, 2.0);
+        assert!((ask_wide - bid_wide - 0.4).abs() < 0.01);
+    }
+    #[test]
+    fn test_sized_quotes() {
+        let mut params = StrategyParams::new();
+        params.set("sizing_method".to_string(), "vol_target".to_string());
+        params.set("sizing_volatility".to_string(), "0.1".to_string());
+        params.set("quote_size".to_string(), "500".to_string());
+        let config = MarketMakerConfig::from_params(&params);
+        assert_eq!(config.sizing.as_ref().unwrap().method, crate::sizing::SizingMethod::VolatilityTarget);
+
+        let mut strategy = MarketMakerStrategy::new("market1".to_string(), config);
+        let mut ctx = create_test_context();
+
+        // Fixed size until capital is allocated
+        assert_eq!(strategy.quote_size(0.5, &ctx), 500.0);
+
+        // 0.005 / 0.1 of $2000 = $100 at 0.5 = 200 contracts
+        ctx.allocated_capital = Some(2000.0);
+        assert!((strategy.quote_size(0.5, &ctx) - 200.0).abs() < 1e-9);
+
+        // 10% drawdown halves the size; larger capital is capped at quote_size
+        ctx.allocated_capital = Some(1800.0);
+        assert!((strategy.quote_size(0.5, &ctx) - 90.0).abs() < 1e-9);
+        ctx.allocated_capital = Some(1_000_000.0);
+        assert_eq!(strategy.quote_size(0.5, &ctx), 500.0);
+    }
+
+    #[test]
+    fn test_cvar_capped_quotes() {
+        let mut params = StrategyParams::new();
+        params.set("sizing_method".to_string(), "vol_target".to_string());
+        params.set("sizing_volatility".to_string(), "0.1".to_string());
+        params.set("sizing_max_marginal_cvar_fraction".to_string(), "0.0025".to_string());
+        params.set("quote_size".to_string(), "500".to_string());
+        let config = MarketMakerConfig::from_params(&params);
+
+        let risk_engine = Arc::new(Mutex::new(RiskEngine::from_yaml("policies:\n  - type: KillSwitch\n    enabled: false\n").unwrap()));
+        let mut ctx = StrategyContext::new("test_mm".to_string(), risk_engine.clone(), StrategyParams::new());
+        ctx.allocated_capital = Some(2000.0);
+        let mut strategy = MarketMakerStrategy::new("market1".to_string(), config);
+
+        // No return history: 200 contracts as in test_sized_quotes
+        assert!((strategy.quote_size(0.5, &ctx) - 200.0).abs() < 1e-9);
+
+        // Returns of ±10%: $5 of CVaR is $50 of notional, 100 contracts
+        let returns: Vec<f64> = (0..100).map(|i| if i % 2 == 0 { 0.1 } else { -0.1 }).collect();
+        risk_engine.lock().set_return_history(
+            HashMap::from([("market1".to_string(), returns)]),
+            HashMap::new(),
+        );
+        assert!((strategy.quote_size(0.5, &ctx) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_adaptive_requote_interval() {
+        let mut params = StrategyParams::new();
+        params.set("adaptive_requote_enabled".to_string(), "true".to_string());
+        params.set("requote_min_interval_ms".to_string(), "10".to_string());
+        params.set("requote_max_interval_ms".to_string(), "1000".to_string());
+        params.set("min_quote_interval_ms".to_string(), "200".to_string());
+        let config = MarketMakerConfig::from_params(&params);
+        assert_eq!(config.adaptive_requote.as_ref().unwrap().min_interval_ms, 10);
+
+        let mut strategy = MarketMakerStrategy::new("market1".to_string(), config);
+        assert_eq!(strategy.requote_interval_ms(), 200);
+
+        // Quiet market: the interval stretches to the ceiling
+        strategy.update_requote_interval(1.0);
+        assert_eq!(strategy.requote_interval_ms(), 1_000);
+
+        // A jumpy mid against a 10 bps half spread: requote at the floor
+        let start = chrono::Utc::now();
+        for i in 0..20 {
+            let mid = if i % 2 == 0 { 0.50 } else { 0.51 };
+            let timestamp = start + chrono::Duration::milliseconds(i * 100);
+            strategy.volatility.as_mut().unwrap().observe(timestamp, Some(mid), 0);
+        }
+        strategy.update_requote_interval(1.0);
+        assert_eq!(strategy.requote_interval_ms(), 10);
+
+        // Disabled: fixed interval
+        let fixed = MarketMakerStrategy::new("market1".to_string(), MarketMakerConfig::default());
+        assert!(fixed.volatility().is_none());
+        assert_eq!(fixed.requote_interval_ms(), 100);
     }
 }
@@ -2,6 +2,8 @@
 
 pub mod market_maker;
 pub mod cross_market_arb;
+pub mod pairs_trading;
 
 pub use market_maker::{MarketMakerStrategy, MarketMakerConfig};
 pub use cross_market_arb::{CrossMarketArbStrategy, CrossMarketArbConfig};
+pub use pairs_trading::{PairsTradingStrategy, PairsTradingConfig};
@@ -0,0 +1,670 @@
+//! Statistical arbitrage (pairs trading) between two correlated markets
+//!
+//! Both markets' implied probabilities are aggregated into bars of
+//! `bar_secs`. On every bar period that closes in both markets the strategy
+//! updates the spread `price_a - hedge_ratio * price_b` over the last
+//! `lookback` bars and its z-score. When the z-score moves beyond
+//! `entry_z` it sells the rich side of the spread and buys the cheap one,
+//! `hedge_ratio` units of B per unit of A, and closes both legs once the
+//! z-score reverts within `exit_z`, or after `max_holding_secs` if it never
+//! does.
+//!
+//! The hedge ratio is fixed by config or, by default, the least-squares
+//! slope of `price_a` on `price_b` over the window, re-estimated every bar.
+
+use crate::{Strategy, StrategyContext, StrategyError, StrategyResult, StrategyMetadata, StrategyParams};
+use crate::types::{MarketTick, Fill, OrderId, Order, Side, OrderType, TimeInForce};
+use crate::metrics::MetricBuilder;
+use crate::signals::{BarAggregator, Timeframe};
+use crate::sizing::{DrawdownTracker, PositionSizer, SizingConfig, SizingInputs};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Pairs trading configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairsTradingConfig {
+    /// Bar width in seconds
+    pub bar_secs: u64,
+
+    /// Bars in the rolling z-score window
+    pub lookback: usize,
+
+    /// |z-score| at which a position is opened
+    pub entry_z: f64,
+
+    /// |z-score| within which an open position is closed
+    pub exit_z: f64,
+
+    /// Close a position after this long even if the spread has not reverted
+    pub max_holding_secs: u64,
+
+    /// Units of B per unit of A (estimated from the window if None)
+    #[serde(default)]
+    pub hedge_ratio: Option<f64>,
+
+    /// Size of the A leg (the cap when sizing is enabled)
+    pub size: f64,
+
+    /// Size the pair from allocated capital and drawdown, up to `size`
+    /// (fixed `size` if None)
+    #[serde(default)]
+    pub sizing: Option<SizingConfig>,
+}
+
+impl Default for PairsTradingConfig {
+    fn default() -> Self {
+        Self {
+            bar_secs: 60,
+            lookback: 60,
+            entry_z: 2.0,
+            exit_z: 0.5,
+            max_holding_secs: 4 * 3600,
+            hedge_ratio: None,
+            size: 100.0,
+            sizing: None,
+        }
+    }
+}
+
+impl PairsTradingConfig {
+    /// Build a config from strategy params, using defaults for missing keys
+    ///
+    /// Sizing is enabled with `sizing_method` (see
+    /// [`SizingConfig::from_params`]).
+    pub fn from_params(params: &StrategyParams) -> Self {
+        let defaults = Self::default();
+        Self {
+            bar_secs: params.get_typed("bar_secs").unwrap_or(defaults.bar_secs),
+            lookback: params.get_typed("lookback").unwrap_or(defaults.lookback),
+            entry_z: params.get_typed("entry_z").unwrap_or(defaults.entry_z),
+            exit_z: params.get_typed("exit_z").unwrap_or(defaults.exit_z),
+            max_holding_secs: params.get_typed("max_holding_secs").unwrap_or(defaults.max_holding_secs),
+            hedge_ratio: params.get_typed("hedge_ratio"),
+            size: params.get_typed("size").unwrap_or(defaults.size),
+            sizing: SizingConfig::from_params(params),
+        }
+    }
+}
+
+/// Spread statistics over the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadStats {
+    /// Units of B per unit of A
+    pub hedge_ratio: f64,
+    /// Latest spread, `price_a - hedge_ratio * price_b`
+    pub spread: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Standard deviations of the latest spread from the mean
+    pub z_score: f64,
+}
+
+impl SpreadStats {
+    /// Statistics of `(price_a, price_b)` pairs, oldest first
+    ///
+    /// None with fewer than two pairs, a flat spread, or (when the hedge
+    /// ratio is estimated) a flat `price_b`.
+    pub fn compute(prices: &VecDeque<(f64, f64)>, hedge_ratio: Option<f64>) -> Option<Self> {
+        let &(last_a, last_b) = prices.back()?;
+        if prices.len() < 2 {
+            return None;
+        }
+        let n = prices.len() as f64;
+        let hedge_ratio = match hedge_ratio {
+            Some(ratio) => ratio,
+            None => {
+                let mean_a = prices.iter().map(|(a, _)| a).sum::<f64>() / n;
+                let mean_b = prices.iter().map(|(_, b)| b).sum::<f64>() / n;
+                let covariance: f64 = prices.iter().map(|(a, b)| (a - mean_a) * (b - mean_b)).sum();
+                let variance: f64 = prices.iter().map(|(_, b)| (b - mean_b).powi(2)).sum();
+                if variance < 1e-12 {
+                    return None;
+                }
+                covariance / variance
+            }
+        };
+
+        let spreads: Vec<f64> = prices.iter().map(|(a, b)| a - hedge_ratio * b).collect();
+        let mean = spreads.iter().sum::<f64>() / n;
+        let std_dev = (spreads.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        if std_dev < 1e-9 {
+            return None;
+        }
+        let spread = last_a - hedge_ratio * last_b;
+        Some(Self {
+            hedge_ratio,
+            spread,
+            mean,
+            std_dev,
+            z_score: (spread - mean) / std_dev,
+        })
+    }
+}
+
+/// Open spread position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairPosition {
+    /// Side of the A leg: `Sell` when short the spread (z above `entry_z`)
+    pub side_a: Side,
+    pub opened_at: DateTime<Utc>,
+    /// Z-score at entry
+    pub entry_z: f64,
+}
+
+/// Pairs trading strategy
+///
+/// Trades mean reversion of the spread between two markets whose implied
+/// probabilities move together. See the module docs.
+pub struct PairsTradingStrategy {
+    config: PairsTradingConfig,
+    market_a: String,
+    market_b: String,
+    timeframe: Timeframe,
+    bars: BarAggregator,
+    /// Bar closes of both markets, oldest first
+    window: VecDeque<(f64, f64)>,
+    /// Start of the latest bar in the window
+    last_bar: Option<DateTime<Utc>>,
+    last_prices: HashMap<String, f64>,
+    stats: Option<SpreadStats>,
+    position: Option<PairPosition>,
+    sizer: Option<PositionSizer>,
+    drawdown: DrawdownTracker,
+    metric_builder: Option<MetricBuilder>,
+}
+
+impl PairsTradingStrategy {
+    pub fn new(market_a: String, market_b: String, config: PairsTradingConfig) -> Self {
+        let timeframe = Timeframe::seconds(config.bar_secs);
+        let sizer = config.sizing.clone().map(PositionSizer::new);
+        Self {
+            timeframe,
+            bars: BarAggregator::new(vec![timeframe], 1),
+            window: VecDeque::with_capacity(config.lookback),
+            last_bar: None,
+            last_prices: HashMap::new(),
+            stats: None,
+            position: None,
+            sizer,
+            drawdown: DrawdownTracker::new(),
+            metric_builder: None,
+            config,
+            market_a,
+            market_b,
+        }
+    }
+
+    /// Spread statistics as of the latest bar (None until the window is
+    /// full)
+    pub fn stats(&self) -> Option<SpreadStats> {
+        self.stats
+    }
+
+    /// Open spread position
+    pub fn position(&self) -> Option<PairPosition> {
+        self.position
+    }
+
+    /// Add the latest bars to the window if both markets closed the same
+    /// period; returns whether the window moved
+    fn update_window(&mut self) -> bool {
+        let (Some(bar_a), Some(bar_b)) = (
+            self.bars.bar_set(&self.market_a).and_then(|set| set.last(self.timeframe)),
+            self.bars.bar_set(&self.market_b).and_then(|set| set.last(self.timeframe)),
+        ) else {
+            return false;
+        };
+        if bar_a.timestamp != bar_b.timestamp || self.last_bar.is_some_and(|last| last >= bar_a.timestamp) {
+            return false;
+        }
+        self.last_bar = Some(bar_a.timestamp);
+        self.window.push_back((bar_a.close, bar_b.close));
+        if self.window.len() > self.config.lookback {
+            self.window.pop_front();
+        }
+        self.stats = if self.window.len() >= self.config.lookback {
+            SpreadStats::compute(&self.window, self.config.hedge_ratio)
+        } else {
+            None
+        };
+        true
+    }
+
+    /// Act on a new bar: exit on reversion, enter beyond the threshold
+    async fn on_bar(&mut self, ctx: &mut StrategyContext) -> StrategyResult<()> {
+        let Some(stats) = self.stats else {
+            return Ok(());
+        };
+
+        if self.metric_builder.is_some() {
+            let mut labels = HashMap::new();
+            labels.insert("market_a".to_string(), self.market_a.clone());
+            labels.insert("market_b".to_string(), self.market_b.clone());
+
+            let metric = crate::metrics::StrategyMetric::gauge(
+                ctx.strategy_id.clone(),
+                crate::metrics::metric_names::PAIR_ZSCORE.to_string(),
+                stats.z_score,
+                labels,
+            );
+            ctx.emit_metric(metric).await?;
+        }
+
+        match self.position {
+            Some(position) => {
+                let reverted = match position.side_a {
+                    Side::Sell => stats.z_score <= self.config.exit_z,
+                    Side::Buy => stats.z_score >= -self.config.exit_z,
+                };
+                if reverted {
+                    self.close(ctx, "spread reverted").await?;
+                }
+            }
+            // Unwind what an earlier exit left behind before a new entry
+            None if !self.is_flat(ctx) => self.close(ctx, "unwinding residual position").await?,
+            None if stats.z_score.abs() >= self.config.entry_z => self.open(stats, ctx).await?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Close the position once it has been held for `max_holding_secs`
+    async fn check_holding_period(&mut self, ctx: &mut StrategyContext) -> StrategyResult<()> {
+        let Some(position) = self.position else {
+            return Ok(());
+        };
+        if ctx.now() - position.opened_at >= Duration::seconds(self.config.max_holding_secs as i64) {
+            self.close(ctx, "max holding period").await?;
+        }
+        Ok(())
+    }
+
+    fn is_flat(&self, ctx: &StrategyContext) -> bool {
+        [&self.market_a, &self.market_b]
+            .iter()
+            .all(|market| ctx.get_position(market).map_or(0.0, |p| p.size).abs() < 1e-8)
+    }
+
+    /// Size of the A leg
+    ///
+    /// With sizing enabled and capital allocated, the pair (one unit of A
+    /// plus `hedge_ratio` units of B, at the price of what is bought) is
+    /// sized from equity and drawdown, with a signal strength of 0.5 at
+    /// `entry_z` growing to 1 at twice it; capped at `size`.
+    fn leg_size(&mut self, stats: &SpreadStats, unit_cost: f64, ctx: &StrategyContext) -> f64 {
+        let (Some(sizer), Some(capital)) = (self.sizer.as_ref(), ctx.allocated_capital) else {
+            return self.config.size;
+        };
+        let equity = capital + ctx.calculate_total_unrealized_pnl();
+        let drawdown = self.drawdown.update(equity);
+        let size = sizer.size(&SizingInputs {
+            equity,
+            drawdown,
+            signal_strength: stats.z_score.abs() / (2.0 * self.config.entry_z),
+            price: unit_cost,
+            volatility: None,
+        });
+        size.min(self.config.size)
+    }
+
+    /// Enter the spread: sell the rich side, buy the cheap one
+    async fn open(&mut self, stats: SpreadStats, ctx: &mut StrategyContext) -> StrategyResult<()> {
+        let (Some(&price_a), Some(&price_b)) = (self.last_prices.get(&self.market_a), self.last_prices.get(&self.market_b))
+        else {
+            return Ok(());
+        };
+        let side_a = if stats.z_score > 0.0 { Side::Sell } else { Side::Buy };
+        let side_b = if stats.hedge_ratio >= 0.0 { side_a.opposite() } else { side_a };
+        // A short is paid for by buying the opposite outcome
+        let cost = |side: Side, price: f64| if side == Side::Buy { price } else { 1.0 - price };
+        let unit_cost = cost(side_a, price_a) + stats.hedge_ratio.abs() * cost(side_b, price_b);
+
+        let size_a = self.leg_size(&stats, unit_cost, ctx);
+        let size_b = size_a * stats.hedge_ratio.abs();
+        if size_a < 1e-8 || size_b < 1e-8 {
+            tracing::debug!(z_score = %stats.z_score, "Sized pair is zero, not entering");
+            return Ok(());
+        }
+
+        let orders = vec![
+            leg_order(&self.market_a, side_a, price_a, size_a),
+            leg_order(&self.market_b, side_b, price_b, size_b),
+        ];
+        match ctx.submit_order_group(orders).await {
+            Ok(_) => {}
+            Err(StrategyError::RiskRejected { violations }) => {
+                tracing::warn!(
+                    market_a = %self.market_a,
+                    market_b = %self.market_b,
+                    violations = ?violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                    "Pair entry rejected by risk engine"
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        }
+
+        tracing::info!(
+            market_a = %self.market_a,
+            market_b = %self.market_b,
+            z_score = %stats.z_score,
+            hedge_ratio = %stats.hedge_ratio,
+            size_a = %size_a,
+            size_b = %size_b,
+            "Pair entered"
+        );
+        self.position = Some(PairPosition {
+            side_a,
+            opened_at: ctx.now(),
+            entry_z: stats.z_score,
+        });
+
+        if let Some(ref builder) = self.metric_builder {
+            ctx.emit_metric(builder.signal_generated(&self.market_a, "pairs_entry")).await?;
+            ctx.emit_metric(builder.order_placed(&self.market_a, side_name(side_a))).await?;
+            ctx.emit_metric(builder.order_placed(&self.market_b, side_name(side_b))).await?;
+        }
+        Ok(())
+    }
+
+    /// Close both legs at the last prices
+    async fn close(&mut self, ctx: &mut StrategyContext, reason: &str) -> StrategyResult<()> {
+        let orders: Vec<Order> = [&self.market_a, &self.market_b]
+            .into_iter()
+            .filter_map(|market| {
+                let position = ctx.get_position(market).map_or(0.0, |p| p.size);
+                let price = *self.last_prices.get(market.as_str())?;
+                let side = if position > 0.0 { Side::Sell } else { Side::Buy };
+                (position.abs() >= 1e-8).then(|| leg_order(market, side, price, position.abs()))
+            })
+            .collect();
+        if orders.is_empty() {
+            self.position = None;
+            return Ok(());
+        }
+
+        match ctx.submit_order_group(orders).await {
+            Ok(_) => {
+                tracing::info!(
+                    market_a = %self.market_a,
+                    market_b = %self.market_b,
+                    z_score = ?self.stats.map(|stats| stats.z_score),
+                    reason = %reason,
+                    "Pair exited"
+                );
+                self.position = None;
+                if let Some(ref builder) = self.metric_builder {
+                    ctx.emit_metric(builder.signal_generated(&self.market_a, "pairs_exit")).await?;
+                }
+            }
+            // Kept open so the exit is retried on the next bar or tick
+            Err(StrategyError::RiskRejected { violations }) => {
+                tracing::warn!(
+                    market_a = %self.market_a,
+                    market_b = %self.market_b,
+                    violations = ?violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                    "Pair exit rejected by risk engine"
+                );
+            }
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+}
+
+fn leg_order(market: &str, side: Side, price: f64, size: f64) -> Order {
+    Order {
+        venue: "polymarket".to_string(),
+        market: market.to_string(),
+        side,
+        order_type: OrderType::Limit,
+        price: Some(price),
+        size,
+        time_in_force: TimeInForce::IOC,
+        ..Default::default()
+    }
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
+    }
+}
+
+#[async_trait]
+impl Strategy for PairsTradingStrategy {
+    async fn initialize(&mut self, ctx: &mut StrategyContext) -> StrategyResult<()> {
+        self.metric_builder = Some(MetricBuilder::new(ctx.strategy_id.clone()));
+
+        tracing::info!(
+            strategy_id = %ctx.strategy_id,
+            market_a = %self.market_a,
+            market_b = %self.market_b,
+            bar_secs = %self.config.bar_secs,
+            lookback = %self.config.lookback,
+            "Pairs trading strategy initialized"
+        );
+
+        Ok(())
+    }
+
+    async fn on_market_tick(
+        &mut self,
+        market_id: &str,
+        tick: &MarketTick,
+        ctx: &mut StrategyContext,
+    ) -> StrategyResult<()> {
+        // Only process our markets
+        if market_id != self.market_a && market_id != self.market_b {
+            return Ok(());
+        }
+
+        let price = tick.mid_price();
+        if price < 1e-8 {
+            return Ok(());
+        }
+        self.last_prices.insert(market_id.to_string(), price);
+
+        if !self.bars.on_tick(tick).is_empty() && self.update_window() {
+            self.on_bar(ctx).await?;
+        }
+        self.check_holding_period(ctx).await
+    }
+
+    async fn on_fill(
+        &mut self,
+        fill: &Fill,
+        ctx: &mut StrategyContext,
+    ) -> StrategyResult<()> {
+        // Position already updated by the coordinator (apply_fill)
+        if let Some(ref builder) = self.metric_builder {
+            ctx.emit_metric(builder.order_filled(&fill.market)).await?;
+
+            let position_data = ctx.get_position(&fill.market)
+                .map(|pos| (pos.unrealized_pnl, pos.size));
+
+            if let Some((unrealized_pnl, position_size)) = position_data {
+                ctx.emit_metric(builder.pnl(&fill.market, unrealized_pnl)).await?;
+                ctx.emit_metric(builder.position_size(&fill.market, position_size)).await?;
+            }
+        }
+
+        tracing::info!(
+            market_id = %fill.market,
+            side = ?fill.side,
+            price = %fill.price,
+            size = %fill.size,
+            "Fill received"
+        );
+
+        Ok(())
+    }
+
+    async fn on_cancel(
+        &mut self,
+        order_id: &OrderId,
+        _ctx: &mut StrategyContext,
+    ) -> StrategyResult<()> {
+        tracing::debug!(order_id = %order_id, "Order cancelled");
+        Ok(())
+    }
+
+    async fn on_timer(
+        &mut self,
+        ctx: &mut StrategyContext,
+    ) -> StrategyResult<()> {
+        // Close bars of quiet markets
+        if !self.bars.close_due(ctx.now()).is_empty() && self.update_window() {
+            self.on_bar(ctx).await?;
+        }
+        self.check_holding_period(ctx).await
+    }
+
+    async fn shutdown(&mut self, ctx: &mut StrategyContext) -> StrategyResult<()> {
+        // Cancel all open orders
+        let open_orders: Vec<OrderId> = ctx.get_open_orders()
+            .iter()
+            .filter_map(|o| o.id.clone())
+            .collect();
+
+        for order_id in open_orders {
+            ctx.cancel_order(&order_id).await?;
+        }
+
+        tracing::info!(
+            strategy_id = %ctx.strategy_id,
+            "Pairs trading strategy shutdown"
+        );
+
+        Ok(())
+    }
+
+    fn metadata(&self) -> StrategyMetadata {
+        StrategyMetadata {
+            name: "PairsTrading".to_string(),
+            version: "1.0.0".to_string(),
+            description: "Mean reversion of the spread between two correlated markets".to_string(),
+            markets: vec![self.market_a.clone(), self.market_b.clone()],
+            required_params: vec![
+                "market_a".to_string(),
+                "market_b".to_string(),
+            ],
+            warmup_ticks: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ag_risk::clock::MockClock;
+    use ag_risk::RiskEngine;
+    use chrono::TimeZone;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    fn tick(market: &str, at: DateTime<Utc>, mid: f64) -> MarketTick {
+        MarketTick {
+            market: market.to_string(),
+            timestamp: at,
+            bid: Some(mid - 0.005),
+            ask: Some(mid + 0.005),
+            bid_size: Some(100.0),
+            ask_size: Some(100.0),
+            last: Some(mid),
+            volume_24h: None,
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_spread_stats() {
+        // B moves twice as much as A around a constant offset
+        let prices: VecDeque<(f64, f64)> =
+            [0.40, 0.42, 0.38, 0.44, 0.36].iter().map(|&b| (0.3 + 0.5 * b, b)).collect();
+        assert!(SpreadStats::compute(&prices, None).is_none());
+
+        let mut prices = prices;
+        prices.push_back((0.55, 0.40));
+        let stats = SpreadStats::compute(&prices, None).unwrap();
+        assert!(stats.hedge_ratio > 0.4 && stats.hedge_ratio < 0.6);
+        assert!(stats.z_score > 2.0);
+
+        // A fixed ratio is used as is
+        let stats = SpreadStats::compute(&prices, Some(1.0)).unwrap();
+        assert_eq!(stats.hedge_ratio, 1.0);
+        assert!((stats.spread - 0.15).abs() < 1e-12);
+        assert!(SpreadStats::compute(&VecDeque::from([(0.5, 0.5)]), Some(1.0)).is_none());
+    }
+
+    /// One bar period: a tick in each market at `at`
+    async fn bar(strategy: &mut PairsTradingStrategy, ctx: &mut StrategyContext, at: DateTime<Utc>, a: f64, b: f64) {
+        strategy.on_market_tick("a", &tick("a", at, a), ctx).await.unwrap();
+        strategy.on_market_tick("b", &tick("b", at, b), ctx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_entry_exit_and_holding_stop() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let at = |bar: i64| start + Duration::seconds(bar * 10);
+        let clock = MockClock::new(start);
+        let risk_engine = RiskEngine::from_yaml("policies:\n  - type: KillSwitch\n    enabled: false\n").unwrap();
+        let mut ctx = StrategyContext::new("pairs".to_string(), Arc::new(Mutex::new(risk_engine)), StrategyParams::new());
+        ctx.set_clock(Arc::new(clock.clone()));
+
+        let config = PairsTradingConfig {
+            bar_secs: 10,
+            lookback: 10,
+            max_holding_secs: 300,
+            hedge_ratio: Some(1.0),
+            size: 50.0,
+            ..Default::default()
+        };
+        let mut strategy = PairsTradingStrategy::new("a".to_string(), "b".to_string(), config);
+        strategy.initialize(&mut ctx).await.unwrap();
+
+        // A small oscillating spread; a bar enters the window when the next
+        // period's ticks close it
+        for i in 0..11 {
+            clock.set(at(i));
+            bar(&mut strategy, &mut ctx, at(i), 0.50 + 0.01 * (i % 2) as f64, 0.40).await;
+        }
+        assert!(strategy.stats().unwrap().z_score.abs() < 2.0);
+        assert!(strategy.position().is_none());
+
+        // A jumps 10 points rich: short the spread
+        clock.set(at(11));
+        bar(&mut strategy, &mut ctx, at(11), 0.60, 0.40).await;
+        clock.set(at(12));
+        bar(&mut strategy, &mut ctx, at(12), 0.60, 0.40).await;
+        assert!(strategy.stats().unwrap().z_score > 2.0);
+        let position = strategy.position().unwrap();
+        assert_eq!(position.side_a, Side::Sell);
+        let legs: Vec<(String, Side, f64)> = ctx
+            .get_open_orders()
+            .iter()
+            .map(|order| (order.market.clone(), order.side, order.size))
+            .collect();
+        assert!(legs.contains(&("a".to_string(), Side::Sell, 50.0)));
+        assert!(legs.contains(&("b".to_string(), Side::Buy, 50.0)));
+
+        // Both legs fill; the spread stays rich until the holding stop
+        ctx.update_position("a", -50.0, 0.60);
+        ctx.update_position("b", 50.0, 0.40);
+        clock.set(position.opened_at + Duration::seconds(299));
+        strategy.on_timer(&mut ctx).await.unwrap();
+        assert!(strategy.position().is_some());
+        clock.set(position.opened_at + Duration::seconds(300));
+        strategy.on_timer(&mut ctx).await.unwrap();
+        assert!(strategy.position().is_none());
+        let exits = ctx
+            .get_open_orders()
+            .iter()
+            .filter(|order| (order.market == "a" && order.side == Side::Buy) || (order.market == "b" && order.side == Side::Sell))
+            .count();
+        assert_eq!(exits, 2);
+    }
+}
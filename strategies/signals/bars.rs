@@ -0,0 +1,312 @@
+//! Multi-timeframe bar aggregation
+//!
+//! [`BarAggregator`] builds OHLC bars of several [`Timeframe`]s per market
+//! from the tick stream. A bar closes when the first tick of a later period
+//! arrives (or [`BarAggregator::close_due`] is called on a timer); each
+//! market keeps the most recent closed bars of every timeframe in a
+//! [`BarSet`], which is what multi-timeframe signal generators consume
+//! through `SignalGenerator::generate_bar_signal`.
+//!
+//! ```rust
+//! use ag_strategies::signals::{BarAggregator, Timeframe};
+//! # use ag_strategies::MarketTick;
+//! # use chrono::{TimeZone, Utc};
+//! # let tick = |secs: i64, last: f64| MarketTick {
+//! #     market: "0x123".to_string(),
+//! #     timestamp: Utc.timestamp_opt(secs, 0).unwrap(),
+//! #     bid: None, bid_size: None, ask: None, ask_size: None,
+//! #     last: Some(last), volume_24h: None, sequence: None,
+//! # };
+//!
+//! let mut bars = BarAggregator::new(vec![Timeframe::seconds(5), Timeframe::minutes(1)], 100);
+//! assert!(bars.on_tick(&tick(0, 0.50)).is_empty());
+//! assert_eq!(bars.on_tick(&tick(5, 0.52)), vec![Timeframe::seconds(5)]);
+//!
+//! let set = bars.bar_set("0x123").unwrap();
+//! assert_eq!(set.last(Timeframe::seconds(5)).unwrap().close, 0.50);
+//! assert!(set.last(Timeframe::minutes(1)).is_none());
+//! ```
+
+use crate::types::{MarketData, MarketId, MarketTick, OhlcvBar};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+/// Bar width, in whole seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timeframe(u64);
+
+impl Timeframe {
+    /// Bars of `secs` seconds (at least 1)
+    pub fn seconds(secs: u64) -> Self {
+        Self(secs.max(1))
+    }
+
+    /// Bars of `mins` minutes
+    pub fn minutes(mins: u64) -> Self {
+        Self::seconds(mins * 60)
+    }
+
+    /// Bars of `hours` hours
+    pub fn hours(hours: u64) -> Self {
+        Self::seconds(hours * 3600)
+    }
+
+    /// Width in seconds
+    pub fn as_secs(&self) -> u64 {
+        self.0
+    }
+
+    /// Width as a duration
+    pub fn duration(&self) -> Duration {
+        Duration::seconds(self.0 as i64)
+    }
+
+    /// Start of the bar containing `at` (bars align to multiples of the
+    /// width since the Unix epoch)
+    pub fn bar_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let width = self.0 as i64;
+        let secs = at.timestamp().div_euclid(width) * width;
+        Utc.timestamp_opt(secs, 0).single().unwrap_or(at)
+    }
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            secs if secs % 3600 == 0 => write!(f, "{}h", secs / 3600),
+            secs if secs % 60 == 0 => write!(f, "{}m", secs / 60),
+            secs => write!(f, "{}s", secs),
+        }
+    }
+}
+
+/// Closed bars of every timeframe for one market, oldest first
+#[derive(Debug, Clone)]
+pub struct BarSet {
+    /// Market identifier
+    pub market: MarketId,
+    bars: BTreeMap<Timeframe, VecDeque<OhlcvBar>>,
+    capacity: usize,
+}
+
+impl BarSet {
+    /// Empty set for `market`
+    pub fn new(market: impl Into<MarketId>, capacity: usize) -> Self {
+        Self {
+            market: market.into(),
+            bars: BTreeMap::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append a closed bar, dropping the oldest beyond the capacity
+    pub fn push(&mut self, timeframe: Timeframe, bar: OhlcvBar) {
+        let bars = self.bars.entry(timeframe).or_default();
+        bars.push_back(bar);
+        if bars.len() > self.capacity {
+            bars.pop_front();
+        }
+    }
+
+    /// Closed bars of a timeframe, oldest first
+    pub fn bars(&self, timeframe: Timeframe) -> impl Iterator<Item = &OhlcvBar> {
+        self.bars.get(&timeframe).into_iter().flatten()
+    }
+
+    /// Most recent closed bar of a timeframe
+    pub fn last(&self, timeframe: Timeframe) -> Option<&OhlcvBar> {
+        self.bars.get(&timeframe).and_then(|bars| bars.back())
+    }
+
+    /// End of the most recent closed bar of a timeframe
+    pub fn last_close_time(&self, timeframe: Timeframe) -> Option<DateTime<Utc>> {
+        self.last(timeframe).map(|bar| bar.timestamp + timeframe.duration())
+    }
+
+    /// Timeframes with at least one closed bar
+    pub fn timeframes(&self) -> impl Iterator<Item = Timeframe> + '_ {
+        self.bars.keys().copied()
+    }
+
+    /// One timeframe's bars as `MarketData` (no ticks), the input of
+    /// single-timeframe generators
+    pub fn market_data(&self, timeframe: Timeframe) -> MarketData {
+        MarketData {
+            market: self.market.clone(),
+            ticks: Vec::new(),
+            bars: self.bars(timeframe).cloned().collect(),
+        }
+    }
+}
+
+/// Builds bars of several timeframes per market from ticks
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    timeframes: Vec<Timeframe>,
+    capacity: usize,
+    /// Forming bar per market and timeframe
+    forming: HashMap<MarketId, BTreeMap<Timeframe, OhlcvBar>>,
+    closed: HashMap<MarketId, BarSet>,
+}
+
+impl BarAggregator {
+    /// Aggregate into `timeframes`, keeping `capacity` closed bars per
+    /// market and timeframe
+    pub fn new(mut timeframes: Vec<Timeframe>, capacity: usize) -> Self {
+        timeframes.sort();
+        timeframes.dedup();
+        Self {
+            timeframes,
+            capacity,
+            forming: HashMap::new(),
+            closed: HashMap::new(),
+        }
+    }
+
+    /// Configured timeframes, shortest first
+    pub fn timeframes(&self) -> &[Timeframe] {
+        &self.timeframes
+    }
+
+    /// Add a tick, returning the timeframes whose bar it closed (shortest
+    /// first)
+    ///
+    /// The price is the last trade, or the mid when the tick has none.
+    /// Ticks without either, or older than the forming bar, are ignored.
+    pub fn on_tick(&mut self, tick: &MarketTick) -> Vec<Timeframe> {
+        let price = match tick.last {
+            Some(last) => last,
+            None if tick.bid.is_some() || tick.ask.is_some() => tick.mid_price(),
+            None => return Vec::new(),
+        };
+
+        let forming = self.forming.entry(tick.market.clone()).or_default();
+        let mut closed = Vec::new();
+        for &timeframe in &self.timeframes {
+            let start = timeframe.bar_start(tick.timestamp);
+            match forming.get_mut(&timeframe) {
+                Some(bar) if bar.timestamp == start => {
+                    bar.high = bar.high.max(price);
+                    bar.low = bar.low.min(price);
+                    bar.close = price;
+                }
+                Some(bar) if bar.timestamp > start => {}
+                // Late tick for a bar already closed by `close_due`
+                None if self
+                    .closed
+                    .get(&tick.market)
+                    .and_then(|set| set.last(timeframe))
+                    .is_some_and(|bar| bar.timestamp >= start) => {}
+                _ => {
+                    let previous = forming.insert(timeframe, open_bar(start, price));
+                    if let Some(bar) = previous {
+                        self.closed
+                            .entry(tick.market.clone())
+                            .or_insert_with(|| BarSet::new(tick.market.clone(), self.capacity))
+                            .push(timeframe, bar);
+                        closed.push(timeframe);
+                    }
+                }
+            }
+        }
+        closed
+    }
+
+    /// Close forming bars whose period ended by `now`, for markets that
+    /// went quiet; returns the markets and timeframes closed
+    pub fn close_due(&mut self, now: DateTime<Utc>) -> Vec<(MarketId, Timeframe)> {
+        let mut closed = Vec::new();
+        for (market, forming) in &mut self.forming {
+            forming.retain(|&timeframe, bar| {
+                if bar.timestamp + timeframe.duration() > now {
+                    return true;
+                }
+                self.closed
+                    .entry(market.clone())
+                    .or_insert_with(|| BarSet::new(market.clone(), self.capacity))
+                    .push(timeframe, bar.clone());
+                closed.push((market.clone(), timeframe));
+                false
+            });
+        }
+        closed.sort();
+        closed
+    }
+
+    /// Closed bars of a market
+    pub fn bar_set(&self, market: &str) -> Option<&BarSet> {
+        self.closed.get(market)
+    }
+
+    /// Bar still forming for a market and timeframe
+    pub fn forming(&self, market: &str, timeframe: Timeframe) -> Option<&OhlcvBar> {
+        self.forming.get(market).and_then(|forming| forming.get(&timeframe))
+    }
+
+    /// Forget a market's bars (e.g. when it is unsubscribed)
+    pub fn remove_market(&mut self, market: &str) {
+        self.forming.remove(market);
+        self.closed.remove(market);
+    }
+}
+
+fn open_bar(timestamp: DateTime<Utc>, price: f64) -> OhlcvBar {
+    OhlcvBar {
+        timestamp,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume: 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(secs: i64, last: f64) -> MarketTick {
+        MarketTick {
+            market: "0x123".to_string(),
+            timestamp: Utc.timestamp_opt(secs, 0).unwrap(),
+            bid: None,
+            bid_size: None,
+            ask: None,
+            ask_size: None,
+            last: Some(last),
+            volume_24h: None,
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_bars_close_per_timeframe() {
+        let fast = Timeframe::seconds(5);
+        let slow = Timeframe::seconds(15);
+        let mut aggregator = BarAggregator::new(vec![slow, fast], 2);
+        assert_eq!(aggregator.timeframes(), &[fast, slow]);
+
+        for (secs, price) in [(0, 0.50), (2, 0.55), (4, 0.45), (6, 0.52), (11, 0.53)] {
+            aggregator.on_tick(&tick(secs, price));
+        }
+        assert_eq!(aggregator.on_tick(&tick(16, 0.60)), vec![fast, slow]);
+
+        let set = aggregator.bar_set("0x123").unwrap();
+        // Capacity keeps the two latest 5s bars
+        let closes: Vec<f64> = set.bars(fast).map(|bar| bar.close).collect();
+        assert_eq!(closes, vec![0.52, 0.53]);
+        let slow_bar = set.last(slow).unwrap();
+        assert_eq!((slow_bar.open, slow_bar.high, slow_bar.low, slow_bar.close), (0.50, 0.55, 0.45, 0.53));
+        assert_eq!(set.last_close_time(slow), Some(Utc.timestamp_opt(15, 0).unwrap()));
+
+        // Quiet market: bars close on the timer
+        let due = aggregator.close_due(Utc.timestamp_opt(21, 0).unwrap());
+        assert_eq!(due, vec![("0x123".to_string(), fast)]);
+        assert_eq!(aggregator.bar_set("0x123").unwrap().last(fast).unwrap().close, 0.60);
+        assert_eq!(Timeframe::minutes(1).to_string(), "1m");
+        assert_eq!(fast.to_string(), "5s");
+    }
+}
@@ -1,8 +1,15 @@
 //! Composite signal generation
+//!
+//! [`CompositeSignal`] combines generators fed the same data;
+//! [`MultiTimeframeSignal`] combines generators bound to different bar
+//! timeframes (see [`super::bars`]).
 
+use super::bars::{BarSet, Timeframe};
 use crate::types::{MarketData, Signal, SignalType, SignalMetadata, SignalGenerator};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use chrono::Utc;
+use ag_risk::clock;
 
 /// Composite signal combining multiple signals
 pub struct CompositeSignal {
@@ -28,7 +35,7 @@ impl SignalGenerator for CompositeSignal {
     fn generate_signal(&mut self, data: &MarketData) -> Signal {
         if self.generators.is_empty() {
             return Signal {
-                timestamp: Utc::now(),
+                timestamp: clock::now(),
                 market_id: data.market.clone(),
                 signal_type: SignalType::Neutral,
                 strength: 0.0,
@@ -71,7 +78,7 @@ impl SignalGenerator for CompositeSignal {
         };
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength: weighted_strength.abs(),
@@ -92,6 +99,197 @@ impl SignalGenerator for CompositeSignal {
     }
 }
 
+/// How [`MultiTimeframeSignal`] combines its timeframes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeframeCombine {
+    /// Weighted average of every timeframe's latest signal
+    Weighted,
+    /// The shortest timeframe triggers; every longer timeframe must point
+    /// the same way (a trend filter), otherwise the result is neutral
+    Confirm,
+}
+
+/// Generator bound to a timeframe and its latest signal
+struct TimeframeLeg {
+    timeframe: Timeframe,
+    generator: Box<dyn SignalGenerator>,
+    weight: f64,
+    /// Latest signal and the close time of the bar it was computed on
+    latest: Option<(Signal, DateTime<Utc>)>,
+}
+
+/// Combination of signals computed on different bar timeframes
+///
+/// Each generator only sees bars of its own timeframe and is re-run when
+/// one of them closes; its signal then holds until its next bar. A signal
+/// older than `max_age_bars` of its timeframe (the bars stopped arriving)
+/// counts as neutral, so a stale trend filter cannot confirm an entry.
+///
+/// ```rust
+/// use ag_strategies::signals::{MultiTimeframeSignal, SimpleMovingAverage, Timeframe, TimeframeCombine};
+///
+/// let mut signal = MultiTimeframeSignal::new(TimeframeCombine::Confirm);
+/// signal.add_generator(Timeframe::minutes(1), Box::new(SimpleMovingAverage::new(20)), 1.0);
+/// signal.add_generator(Timeframe::seconds(5), Box::new(SimpleMovingAverage::new(5)), 1.0);
+/// ```
+pub struct MultiTimeframeSignal {
+    legs: Vec<TimeframeLeg>,
+    combine: TimeframeCombine,
+    max_age_bars: u32,
+}
+
+impl MultiTimeframeSignal {
+    pub fn new(combine: TimeframeCombine) -> Self {
+        Self {
+            legs: Vec::new(),
+            combine,
+            max_age_bars: 2,
+        }
+    }
+
+    /// Bars of its timeframe after which a signal counts as neutral
+    /// (default 2)
+    pub fn with_max_age_bars(mut self, bars: u32) -> Self {
+        self.max_age_bars = bars.max(1);
+        self
+    }
+
+    pub fn add_generator(&mut self, timeframe: Timeframe, generator: Box<dyn SignalGenerator>, weight: f64) {
+        self.legs.push(TimeframeLeg {
+            timeframe,
+            generator,
+            weight,
+            latest: None,
+        });
+    }
+
+    /// Latest signal per timeframe leg, in insertion order
+    pub fn latest(&self) -> Vec<(Timeframe, Option<&Signal>)> {
+        self.legs
+            .iter()
+            .map(|leg| (leg.timeframe, leg.latest.as_ref().map(|(signal, _)| signal)))
+            .collect()
+    }
+
+    /// Combine the legs' latest signals as of `now`
+    fn combined(&self, market_id: &str, now: DateTime<Utc>) -> Signal {
+        // Signed strength and confidence of each leg; stale legs are neutral
+        let values: Vec<(Timeframe, f64, f64, f64)> = self
+            .legs
+            .iter()
+            .map(|leg| {
+                let fresh = leg.latest.as_ref().filter(|(_, closed_at)| {
+                    now - *closed_at < leg.timeframe.duration() * self.max_age_bars as i32
+                });
+                match fresh {
+                    Some((signal, _)) => (leg.timeframe, leg.weight, signed_strength(signal), signal.confidence),
+                    None => (leg.timeframe, leg.weight, 0.0, 0.0),
+                }
+            })
+            .collect();
+
+        let weighted = |values: &[&(Timeframe, f64, f64, f64)]| {
+            let total_weight: f64 = values.iter().map(|v| v.1).sum();
+            if total_weight <= 0.0 {
+                return (0.0, 0.0);
+            }
+            let strength = values.iter().map(|v| v.1 * v.2).sum::<f64>() / total_weight;
+            let confidence = values.iter().map(|v| v.1 * v.3).sum::<f64>() / total_weight;
+            (strength, confidence)
+        };
+
+        let (strength, confidence) = match self.combine {
+            TimeframeCombine::Weighted => weighted(&values.iter().collect::<Vec<_>>()),
+            TimeframeCombine::Confirm => {
+                let fastest = values.iter().map(|v| v.0).min();
+                let (triggers, filters): (Vec<_>, Vec<_>) = values.iter().partition(|v| Some(v.0) == fastest);
+                let (strength, confidence) = weighted(&triggers);
+                let confirmed = filters.iter().all(|v| v.2 * strength > 0.0);
+                if confirmed {
+                    let filter_confidence = filters.iter().map(|v| v.3).fold(1.0, f64::min);
+                    (strength, confidence * filter_confidence)
+                } else {
+                    (0.0, 0.0)
+                }
+            }
+        };
+
+        let signal_type = if strength > 0.1 {
+            SignalType::Long
+        } else if strength < -0.1 {
+            SignalType::Short
+        } else {
+            SignalType::Neutral
+        };
+        let metadata = values
+            .iter()
+            .map(|(timeframe, _, strength, _)| (format!("strength_{}", timeframe), format!("{:.4}", strength)))
+            .collect();
+
+        Signal {
+            timestamp: now,
+            market_id: market_id.to_string(),
+            signal_type,
+            strength: strength.abs(),
+            confidence,
+            metadata,
+        }
+    }
+}
+
+impl SignalGenerator for MultiTimeframeSignal {
+    /// Combine the latest signals without re-running any generator
+    fn generate_signal(&mut self, data: &MarketData) -> Signal {
+        let now = self
+            .legs
+            .iter()
+            .filter_map(|leg| leg.latest.as_ref().map(|(_, closed_at)| *closed_at))
+            .max()
+            .unwrap_or_else(clock::now);
+        self.combined(&data.market, now)
+    }
+
+    fn metadata(&self) -> SignalMetadata {
+        let mut params = HashMap::new();
+        params.insert("combine".to_string(), format!("{:?}", self.combine));
+        for leg in &self.legs {
+            let name = leg.generator.metadata().name;
+            params.insert(format!("{}_{}", leg.timeframe, name), leg.weight.to_string());
+        }
+
+        SignalMetadata {
+            name: "MultiTimeframeSignal".to_string(),
+            description: "Combination of signals across bar timeframes".to_string(),
+            params,
+        }
+    }
+
+    fn timeframes(&self) -> Vec<Timeframe> {
+        let mut timeframes: Vec<Timeframe> = self.legs.iter().map(|leg| leg.timeframe).collect();
+        timeframes.sort();
+        timeframes.dedup();
+        timeframes
+    }
+
+    fn generate_bar_signal(&mut self, closed: Timeframe, bars: &BarSet) -> Signal {
+        let closed_at = bars.last_close_time(closed).unwrap_or_else(clock::now);
+        for leg in self.legs.iter_mut().filter(|leg| leg.timeframe == closed) {
+            let signal = leg.generator.generate_bar_signal(closed, bars);
+            leg.latest = Some((signal, closed_at));
+        }
+        self.combined(&bars.market, closed_at)
+    }
+}
+
+fn signed_strength(signal: &Signal) -> f64 {
+    match signal.signal_type {
+        SignalType::Long => signal.strength,
+        SignalType::Short => -signal.strength,
+        SignalType::Neutral | SignalType::Close => 0.0,
+    }
+}
+
 /// Signal aggregator that combines signals using different strategies
 pub struct SignalAggregator;
 
@@ -100,7 +298,7 @@ impl SignalAggregator {
     pub fn consensus(signals: &[Signal]) -> Signal {
         if signals.is_empty() {
             return Signal {
-                timestamp: Utc::now(),
+                timestamp: clock::now(),
                 market_id: String::new(),
                 signal_type: SignalType::Neutral,
                 strength: 0.0,
@@ -139,7 +337,7 @@ impl SignalAggregator {
             .sum::<f64>() / total;
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: signals[0].market_id.clone(),
             signal_type,
             strength,
@@ -166,7 +364,7 @@ mod tests {
 
     fn create_test_signal(signal_type: SignalType, strength: f64, confidence: f64) -> Signal {
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: "test".to_string(),
             signal_type,
             strength,
@@ -198,4 +396,73 @@ mod tests {
         let strongest = SignalAggregator::strongest(&signals).unwrap();
         assert_eq!(strongest.signal_type, SignalType::Short);
     }
+
+    /// Long when the last bar closed up, short when down
+    struct BarDirection;
+
+    impl SignalGenerator for BarDirection {
+        fn generate_signal(&mut self, data: &MarketData) -> Signal {
+            let bar = data.bars.last().unwrap();
+            let signal_type = if bar.close > bar.open { SignalType::Long } else { SignalType::Short };
+            create_test_signal(signal_type, 0.8, 1.0)
+        }
+
+        fn metadata(&self) -> SignalMetadata {
+            SignalMetadata {
+                name: "BarDirection".to_string(),
+                description: String::new(),
+                params: HashMap::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_timeframe_confirm() {
+        use crate::types::OhlcvBar;
+        use chrono::TimeZone;
+
+        let fast = Timeframe::seconds(5);
+        let slow = Timeframe::minutes(1);
+        let bar = |start: i64, open: f64, close: f64| OhlcvBar {
+            timestamp: Utc.timestamp_opt(start, 0).unwrap(),
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            close,
+            volume: 0.0,
+        };
+
+        let mut signal = MultiTimeframeSignal::new(TimeframeCombine::Confirm);
+        signal.add_generator(slow, Box::new(BarDirection), 1.0);
+        signal.add_generator(fast, Box::new(BarDirection), 1.0);
+        assert_eq!(signal.timeframes(), vec![fast, slow]);
+
+        // Uptrend on the 1m bar confirms a 5s long trigger
+        let mut bars = BarSet::new("test", 10);
+        bars.push(slow, bar(0, 0.40, 0.45));
+        signal.generate_bar_signal(slow, &bars);
+        bars.push(fast, bar(60, 0.45, 0.46));
+        let combined = signal.generate_bar_signal(fast, &bars);
+        assert_eq!(combined.signal_type, SignalType::Long);
+        assert_eq!(combined.timestamp, Utc.timestamp_opt(65, 0).unwrap());
+
+        // ... but not a short one
+        bars.push(fast, bar(65, 0.46, 0.44));
+        assert_eq!(signal.generate_bar_signal(fast, &bars).signal_type, SignalType::Neutral);
+
+        // A trend filter that stopped updating no longer confirms
+        bars.push(fast, bar(180, 0.44, 0.47));
+        let combined = signal.generate_bar_signal(fast, &bars);
+        assert_eq!(combined.signal_type, SignalType::Neutral);
+        assert_eq!(combined.metadata["strength_1m"], "0.0000");
+
+        // Weighted, with a longer max age the 1m signal still counts
+        let mut weighted = MultiTimeframeSignal::new(TimeframeCombine::Weighted).with_max_age_bars(10);
+        weighted.add_generator(slow, Box::new(BarDirection), 1.0);
+        weighted.add_generator(fast, Box::new(BarDirection), 1.0);
+        weighted.generate_bar_signal(slow, &bars);
+        let combined = weighted.generate_bar_signal(fast, &bars);
+        assert_eq!(combined.signal_type, SignalType::Long);
+        assert!((combined.strength - 0.8).abs() < 1e-9);
+    }
 }
@@ -1,8 +1,9 @@
 //! Market microstructure signals
 
-use crate::types::{MarketData, Signal, SignalType, SignalMetadata, SignalGenerator};
+use crate::types::{MarketData, MarketTick, Side, Signal, SignalType, SignalMetadata, SignalGenerator};
 use std::collections::HashMap;
-use chrono::Utc;
+use ag_risk::clock;
+use chrono::{DateTime, Utc};
 
 /// Order book imbalance signal
 ///
@@ -31,7 +32,7 @@ impl SignalGenerator for OrderImbalance {
             Some(t) => t,
             None => {
                 return Signal {
-                    timestamp: Utc::now(),
+                    timestamp: clock::now(),
                     market_id: data.market.clone(),
                     signal_type: SignalType::Neutral,
                     strength: 0.0,
@@ -60,7 +61,7 @@ impl SignalGenerator for OrderImbalance {
         metadata.insert("ask_size".to_string(), format!("{:.2}", ask_size));
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
@@ -106,7 +107,7 @@ impl SignalGenerator for SpreadAnalyzer {
             Some(t) => t,
             None => {
                 return Signal {
-                    timestamp: Utc::now(),
+                    timestamp: clock::now(),
                     market_id: data.market.clone(),
                     signal_type: SignalType::Neutral,
                     strength: 0.0,
@@ -135,7 +136,7 @@ impl SignalGenerator for SpreadAnalyzer {
         metadata.insert("avg_spread_bps".to_string(), format!("{:.2}", self.avg_spread_bps));
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
@@ -158,6 +159,260 @@ impl SignalGenerator for SpreadAnalyzer {
     }
 }
 
+/// Queue position model configuration
+#[derive(Debug, Clone)]
+pub struct QueueModelConfig {
+    /// Time constant of the decayed trade-rate estimate (seconds)
+    pub rate_window_secs: f64,
+
+    /// Prior trade rate against our side (size per second) before any prints
+    pub initial_trade_rate: f64,
+
+    /// How strongly book imbalance scales the fill rate (0 = ignore)
+    ///
+    /// A book leaning towards our side (e.g. heavy bids while we rest on the
+    /// bid) predicts the price moving away from us, so fills slow down.
+    pub imbalance_sensitivity: f64,
+}
+
+impl Default for QueueModelConfig {
+    fn default() -> Self {
+        Self {
+            rate_window_secs: 30.0,
+            initial_trade_rate: 0.0,
+            imbalance_sensitivity: 0.5,
+        }
+    }
+}
+
+/// Snapshot of a resting order's estimated queue state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueueEstimate {
+    /// Size estimated to be ahead of us at our price level
+    pub queue_ahead: f64,
+    /// Remaining (unfilled) size of our order
+    pub remaining: f64,
+    /// Imbalance-adjusted trade rate against our side (size per second)
+    pub fill_rate: f64,
+    /// Expected seconds until fully filled (None if the rate is zero)
+    pub time_to_fill_secs: Option<f64>,
+}
+
+/// Queue position estimator for a single resting limit order
+///
+/// Tracks how much size sits ahead of our order at its price level:
+///
+/// - We join at the back of the level when placed.
+/// - Trade prints against our side at our price consume the queue ahead
+///   first, then fill us; prints through our price clear the level.
+/// - Size removed from the level without a print is treated as cancels,
+///   spread uniformly across the queue, so only the share ahead of us
+///   moves us forward.
+/// - Size added to the level joins behind us.
+///
+/// Time-to-fill and fill probability come from an exponentially decayed
+/// estimate of the trade rate against our side, scaled by book imbalance.
+pub struct QueuePositionEstimator {
+    config: QueueModelConfig,
+    side: Side,
+    price: f64,
+    remaining: f64,
+    queue_ahead: f64,
+    level_size: f64,
+    traded_since_update: f64,
+    imbalance: f64,
+    trade_rate: f64,
+    rate_updated: DateTime<Utc>,
+}
+
+impl QueuePositionEstimator {
+    /// Start tracking an order of `size` placed at `price`, joining behind
+    /// `level_size` already resting at that price
+    pub fn new(
+        config: QueueModelConfig,
+        side: Side,
+        price: f64,
+        size: f64,
+        level_size: f64,
+        placed_at: DateTime<Utc>,
+    ) -> Self {
+        let trade_rate = config.initial_trade_rate;
+        Self {
+            config,
+            side,
+            price,
+            remaining: size,
+            queue_ahead: level_size.max(0.0),
+            level_size: level_size.max(0.0) + size,
+            traded_since_update: 0.0,
+            imbalance: 0.0,
+            trade_rate,
+            rate_updated: placed_at,
+        }
+    }
+
+    /// Order side
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Order price
+    pub fn price(&self) -> f64 {
+        self.price
+    }
+
+    /// Estimated size ahead of us
+    pub fn queue_ahead(&self) -> f64 {
+        self.queue_ahead
+    }
+
+    /// Estimated remaining size of our order
+    pub fn remaining(&self) -> f64 {
+        self.remaining
+    }
+
+    /// Whether the order is estimated to be completely filled
+    pub fn is_filled(&self) -> bool {
+        self.remaining < 1e-8
+    }
+
+    /// Apply a new total size at our price level (including our order)
+    pub fn on_level_update(&mut self, level_size: f64) {
+        let level_size = level_size.max(0.0);
+        let removed = self.level_size - level_size - self.traded_since_update;
+
+        if removed > 0.0 {
+            let others = (self.level_size - self.remaining).max(1e-8);
+            let cancelled_ahead = removed * (self.queue_ahead / others).min(1.0);
+            self.queue_ahead = (self.queue_ahead - cancelled_ahead).max(0.0);
+        }
+
+        // Never more ahead of us than the level holds besides us
+        self.queue_ahead = self.queue_ahead.min((level_size - self.remaining).max(0.0));
+        self.level_size = level_size;
+        self.traded_since_update = 0.0;
+    }
+
+    /// Update the top-of-book imbalance used to scale the fill rate
+    pub fn on_imbalance(&mut self, imbalance: f64) {
+        self.imbalance = imbalance.clamp(-1.0, 1.0);
+    }
+
+    /// Apply a market tick
+    ///
+    /// Updates imbalance from top-of-book sizes and, when our price is the
+    /// best price on our side, the level size.
+    pub fn on_tick(&mut self, tick: &MarketTick) {
+        if let (Some(bid_size), Some(ask_size)) = (tick.bid_size, tick.ask_size) {
+            self.on_imbalance(OrderImbalance::calculate_imbalance(bid_size, ask_size));
+        }
+
+        let (best, size) = match self.side {
+            Side::Buy => (tick.bid, tick.bid_size),
+            Side::Sell => (tick.ask, tick.ask_size),
+        };
+        if let (Some(best), Some(size)) = (best, size) {
+            if (best - self.price).abs() < 1e-9 {
+                self.on_level_update(size);
+            }
+        }
+    }
+
+    /// Apply a trade print; returns the size estimated to have filled us
+    ///
+    /// `aggressor` is the side of the taker. Only prints against our side
+    /// (sells for a resting buy, buys for a resting sell) affect the queue.
+    pub fn on_trade(&mut self, price: f64, size: f64, aggressor: Side, timestamp: DateTime<Utc>) -> f64 {
+        if aggressor == self.side || size <= 0.0 {
+            return 0.0;
+        }
+
+        self.record_trade(size, timestamp);
+
+        let through = match self.side {
+            Side::Buy => price < self.price - 1e-9,
+            Side::Sell => price > self.price + 1e-9,
+        };
+        let at_level = (price - self.price).abs() < 1e-9;
+
+        if through {
+            // Level swept: everything ahead of us and our order traded
+            let filled = self.remaining;
+            self.traded_since_update += self.queue_ahead + filled;
+            self.queue_ahead = 0.0;
+            self.remaining = 0.0;
+            return filled;
+        }
+        if !at_level {
+            return 0.0;
+        }
+
+        let consumed = size.min(self.queue_ahead);
+        self.queue_ahead -= consumed;
+        let filled = (size - consumed).min(self.remaining);
+        self.remaining -= filled;
+        self.traded_since_update += consumed + filled;
+        filled
+    }
+
+    /// Imbalance-adjusted trade rate against our side (size per second)
+    pub fn fill_rate(&self, now: DateTime<Utc>) -> f64 {
+        let leaning = match self.side {
+            Side::Buy => self.imbalance,
+            Side::Sell => -self.imbalance,
+        };
+        let scale = (1.0 - self.config.imbalance_sensitivity * leaning).max(0.0);
+        self.decayed_rate(now) * scale
+    }
+
+    /// Expected seconds until fully filled (None if no flow is expected)
+    pub fn time_to_fill(&self, now: DateTime<Utc>) -> Option<f64> {
+        if self.is_filled() {
+            return Some(0.0);
+        }
+        let rate = self.fill_rate(now);
+        if rate < 1e-12 {
+            return None;
+        }
+        Some((self.queue_ahead + self.remaining) / rate)
+    }
+
+    /// Probability of being fully filled within `horizon_secs`
+    ///
+    /// Models flow against our side as a Poisson process at the current
+    /// fill rate: `P = 1 - exp(-rate * horizon / (queue_ahead + remaining))`.
+    pub fn fill_probability(&self, horizon_secs: f64, now: DateTime<Utc>) -> f64 {
+        if self.is_filled() {
+            return 1.0;
+        }
+        let needed = self.queue_ahead + self.remaining;
+        let expected = self.fill_rate(now) * horizon_secs.max(0.0);
+        (1.0 - (-expected / needed).exp()).clamp(0.0, 1.0)
+    }
+
+    /// Current estimate snapshot
+    pub fn estimate(&self, now: DateTime<Utc>) -> QueueEstimate {
+        QueueEstimate {
+            queue_ahead: self.queue_ahead,
+            remaining: self.remaining,
+            fill_rate: self.fill_rate(now),
+            time_to_fill_secs: self.time_to_fill(now),
+        }
+    }
+
+    fn record_trade(&mut self, size: f64, timestamp: DateTime<Utc>) {
+        let tau = self.config.rate_window_secs.max(1e-3);
+        self.trade_rate = self.decayed_rate(timestamp) + size / tau;
+        self.rate_updated = timestamp.max(self.rate_updated);
+    }
+
+    fn decayed_rate(&self, now: DateTime<Utc>) -> f64 {
+        let tau = self.config.rate_window_secs.max(1e-3);
+        let elapsed = (now - self.rate_updated).num_milliseconds().max(0) as f64 / 1000.0;
+        self.trade_rate * (-elapsed / tau).exp()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,4 +435,75 @@ mod tests {
         let imbalance = OrderImbalance::calculate_imbalance(0.0, 0.0);
         assert_eq!(imbalance, 0.0);
     }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_queue_trades_and_cancels() {
+        let mut q = QueuePositionEstimator::new(QueueModelConfig::default(), Side::Buy, 0.50, 10.0, 100.0, at(0));
+        assert_eq!(q.queue_ahead(), 100.0);
+
+        // Sell print at our level consumes the queue ahead
+        assert_eq!(q.on_trade(0.50, 40.0, Side::Sell, at(1)), 0.0);
+        assert_eq!(q.queue_ahead(), 60.0);
+
+        // Buy prints and prints away from our level are ignored
+        assert_eq!(q.on_trade(0.51, 5.0, Side::Buy, at(1)), 0.0);
+        assert_eq!(q.on_trade(0.51, 5.0, Side::Sell, at(1)), 0.0);
+
+        // Level 110 -> 70 after the 40 traded; then 70 -> 40: 30 cancelled,
+        // spread over the 60 others so 30 * 60/60 ahead of us
+        q.on_level_update(70.0);
+        assert!((q.queue_ahead() - 60.0).abs() < 1e-9);
+        q.on_level_update(40.0);
+        assert!((q.queue_ahead() - 30.0).abs() < 1e-9);
+
+        // Print larger than the queue fills us partially
+        assert!((q.on_trade(0.50, 35.0, Side::Sell, at(2)) - 5.0).abs() < 1e-9);
+        assert!((q.remaining() - 5.0).abs() < 1e-9);
+
+        // Print through our price fills the rest
+        assert!((q.on_trade(0.49, 1.0, Side::Sell, at(3)) - 5.0).abs() < 1e-9);
+        assert!(q.is_filled());
+        assert_eq!(q.fill_probability(1.0, at(3)), 1.0);
+    }
+
+    #[test]
+    fn test_queue_fill_estimates() {
+        let config = QueueModelConfig {
+            rate_window_secs: 10.0,
+            initial_trade_rate: 10.0,
+            imbalance_sensitivity: 0.5,
+        };
+        let mut q = QueuePositionEstimator::new(config, Side::Buy, 0.50, 10.0, 90.0, at(0));
+
+        // 100 needed at 10/s
+        assert!((q.time_to_fill(at(0)).unwrap() - 10.0).abs() < 1e-9);
+        let p_short = q.fill_probability(1.0, at(0));
+        let p_long = q.fill_probability(30.0, at(0));
+        assert!(p_short < p_long && p_long < 1.0);
+
+        // Heavy bids (our side) slow fills down; rate decays without prints
+        q.on_imbalance(0.8);
+        assert!(q.fill_rate(at(0)) < 10.0);
+        assert!(q.fill_rate(at(20)) < q.fill_rate(at(0)));
+
+        let tick = MarketTick {
+            market: "m1".to_string(),
+            timestamp: at(0),
+            bid: Some(0.50),
+            bid_size: Some(50.0),
+            ask: Some(0.52),
+            ask_size: Some(150.0),
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        };
+        q.on_tick(&tick);
+        assert_eq!(q.queue_ahead(), 40.0);
+        assert!(q.fill_rate(at(0)) > 10.0);
+    }
 }
@@ -3,6 +3,9 @@
 pub mod technical;
 pub mod microstructure;
 pub mod composite;
+pub mod toxicity;
+pub mod volatility;
+pub mod bars;
 
 pub use technical::{
     SimpleMovingAverage,
@@ -15,9 +18,32 @@ pub use technical::{
 pub use microstructure::{
     OrderImbalance,
     SpreadAnalyzer,
+    QueuePositionEstimator,
+    QueueModelConfig,
+    QueueEstimate,
+};
+
+pub use toxicity::{
+    ToxicityMonitor,
+    ToxicityConfig,
+    ToxicityAction,
+    Markout,
+};
+
+pub use volatility::{
+    VolatilityEstimator,
+    AdaptiveRequoteConfig,
 };
 
 pub use composite::{
     CompositeSignal,
     SignalAggregator,
+    MultiTimeframeSignal,
+    TimeframeCombine,
+};
+
+pub use bars::{
+    BarAggregator,
+    BarSet,
+    Timeframe,
 };
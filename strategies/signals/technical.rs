@@ -2,7 +2,7 @@
 
 use crate::types::{MarketData, Signal, SignalType, SignalMetadata, SignalGenerator};
 use std::collections::{HashMap, VecDeque};
-use chrono::Utc;
+use ag_risk::clock;
 
 /// Simple Moving Average indicator
 pub struct SimpleMovingAverage {
@@ -36,9 +36,7 @@ impl SimpleMovingAverage {
 impl SignalGenerator for SimpleMovingAverage {
     fn generate_signal(&mut self, data: &MarketData) -> Signal {
         // Get latest price
-        let price = data.ticks.last()
-            .and_then(|t| t.last)
-            .unwrap_or(0.0);
+        let price = data.last_price().unwrap_or(0.0);
 
         self.update(price);
 
@@ -54,7 +52,7 @@ impl SignalGenerator for SimpleMovingAverage {
         };
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
@@ -106,9 +104,7 @@ impl ExponentialMovingAverage {
 
 impl SignalGenerator for ExponentialMovingAverage {
     fn generate_signal(&mut self, data: &MarketData) -> Signal {
-        let price = data.ticks.last()
-            .and_then(|t| t.last)
-            .unwrap_or(0.0);
+        let price = data.last_price().unwrap_or(0.0);
 
         self.update(price);
 
@@ -123,7 +119,7 @@ impl SignalGenerator for ExponentialMovingAverage {
         };
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
@@ -200,9 +196,7 @@ impl RelativeStrengthIndex {
 
 impl SignalGenerator for RelativeStrengthIndex {
     fn generate_signal(&mut self, data: &MarketData) -> Signal {
-        let price = data.ticks.last()
-            .and_then(|t| t.last)
-            .unwrap_or(0.0);
+        let price = data.last_price().unwrap_or(0.0);
 
         self.update(price);
 
@@ -218,7 +212,7 @@ impl SignalGenerator for RelativeStrengthIndex {
         };
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
@@ -291,9 +285,7 @@ impl BollingerBands {
 
 impl SignalGenerator for BollingerBands {
     fn generate_signal(&mut self, data: &MarketData) -> Signal {
-        let price = data.ticks.last()
-            .and_then(|t| t.last)
-            .unwrap_or(0.0);
+        let price = data.last_price().unwrap_or(0.0);
 
         self.update(price);
 
@@ -310,7 +302,7 @@ impl SignalGenerator for BollingerBands {
         };
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
@@ -371,9 +363,7 @@ impl MovingAverageConvergenceDivergence {
 
 impl SignalGenerator for MovingAverageConvergenceDivergence {
     fn generate_signal(&mut self, data: &MarketData) -> Signal {
-        let price = data.ticks.last()
-            .and_then(|t| t.last)
-            .unwrap_or(0.0);
+        let price = data.last_price().unwrap_or(0.0);
 
         self.update(price);
 
@@ -390,7 +380,7 @@ impl SignalGenerator for MovingAverageConvergenceDivergence {
         };
 
         Signal {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             market_id: data.market.clone(),
             signal_type,
             strength,
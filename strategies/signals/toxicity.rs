@@ -0,0 +1,311 @@
+//! Order flow toxicity (adverse selection) monitoring
+//!
+//! Measures markouts: how the mid price moves over fixed horizons after each
+//! of our fills. Persistently negative markouts mean we are being picked off
+//! by better-informed flow, and quoting should widen or stop.
+
+use crate::types::Side;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Toxicity monitor configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToxicityConfig {
+    /// Markout horizons in milliseconds
+    pub horizons_ms: Vec<u64>,
+
+    /// EWMA weight of each new markout observation (0..1]
+    pub ewma_alpha: f64,
+
+    /// Minimum markouts per horizon before the monitor acts
+    pub min_samples: usize,
+
+    /// Start widening when the worst horizon's average markout falls below
+    /// this (bps, negative = adverse)
+    pub widen_threshold_bps: f64,
+
+    /// Pull quotes when the worst horizon's average markout falls below this
+    pub pull_threshold_bps: f64,
+
+    /// Spread multiplier reached just before the pull threshold
+    pub max_spread_multiplier: f64,
+}
+
+impl Default for ToxicityConfig {
+    fn default() -> Self {
+        Self {
+            horizons_ms: vec![1_000, 5_000, 30_000],
+            ewma_alpha: 0.2,
+            min_samples: 5,
+            widen_threshold_bps: -2.0,
+            pull_threshold_bps: -15.0,
+            max_spread_multiplier: 3.0,
+        }
+    }
+}
+
+/// Quoting adjustment recommended by the monitor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToxicityAction {
+    /// Quote normally
+    Normal,
+    /// Multiply the quoted spread by the given factor (> 1)
+    Widen(f64),
+    /// Stop quoting until markouts recover
+    Pull,
+}
+
+impl ToxicityAction {
+    /// Spread multiplier implied by the action (`None` when quotes are pulled)
+    pub fn spread_multiplier(&self) -> Option<f64> {
+        match self {
+            ToxicityAction::Normal => Some(1.0),
+            ToxicityAction::Widen(m) => Some(*m),
+            ToxicityAction::Pull => None,
+        }
+    }
+}
+
+/// A resolved markout for one fill at one horizon
+#[derive(Debug, Clone, PartialEq)]
+pub struct Markout {
+    /// Market identifier
+    pub market: String,
+    /// Horizon in milliseconds
+    pub horizon_ms: u64,
+    /// Markout in bps of the fill price (positive = favourable to us)
+    pub bps: f64,
+    /// Markout in quote currency (`bps` scaled by fill notional)
+    pub pnl: f64,
+}
+
+#[derive(Debug, Clone)]
+struct PendingFill {
+    side: Side,
+    price: f64,
+    size: f64,
+    timestamp: DateTime<Utc>,
+    /// Index of the next horizon to resolve
+    next_horizon: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct HorizonStats {
+    ewma_bps: f64,
+    samples: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MarketToxicity {
+    pending: VecDeque<PendingFill>,
+    horizons: Vec<HorizonStats>,
+}
+
+/// Per-market markout tracker
+///
+/// Feed it our fills and mid prices; it resolves each fill's markout at
+/// every configured horizon from the first mid observed at or after
+/// `fill time + horizon`, and keeps an EWMA per market and horizon.
+pub struct ToxicityMonitor {
+    config: ToxicityConfig,
+    markets: HashMap<String, MarketToxicity>,
+}
+
+impl ToxicityMonitor {
+    /// Create a monitor; horizons are sorted ascending
+    pub fn new(mut config: ToxicityConfig) -> Self {
+        config.horizons_ms.sort_unstable();
+        config.horizons_ms.dedup();
+        Self {
+            config,
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Monitor configuration
+    pub fn config(&self) -> &ToxicityConfig {
+        &self.config
+    }
+
+    /// Record one of our fills
+    pub fn record_fill(&mut self, market: &str, side: Side, price: f64, size: f64, timestamp: DateTime<Utc>) {
+        if price <= 0.0 || size <= 0.0 || self.config.horizons_ms.is_empty() {
+            return;
+        }
+        self.market_mut(market).pending.push_back(PendingFill {
+            side,
+            price,
+            size,
+            timestamp,
+            next_horizon: 0,
+        });
+    }
+
+    /// Observe a mid price; returns the markouts it resolved
+    pub fn on_mid(&mut self, market: &str, mid: f64, timestamp: DateTime<Utc>) -> Vec<Markout> {
+        let horizons = self.config.horizons_ms.clone();
+        let alpha = self.config.ewma_alpha.clamp(0.0, 1.0);
+        let Some(state) = self.markets.get_mut(market) else {
+            return Vec::new();
+        };
+
+        let mut resolved = Vec::new();
+        for fill in state.pending.iter_mut() {
+            while fill.next_horizon < horizons.len() {
+                let horizon_ms = horizons[fill.next_horizon];
+                if timestamp < fill.timestamp + Duration::milliseconds(horizon_ms as i64) {
+                    break;
+                }
+
+                let direction = match fill.side {
+                    Side::Buy => 1.0,
+                    Side::Sell => -1.0,
+                };
+                let bps = direction * (mid - fill.price) / fill.price * 10_000.0;
+
+                let stats = &mut state.horizons[fill.next_horizon];
+                stats.ewma_bps = if stats.samples == 0 {
+                    bps
+                } else {
+                    alpha * bps + (1.0 - alpha) * stats.ewma_bps
+                };
+                stats.samples += 1;
+
+                resolved.push(Markout {
+                    market: market.to_string(),
+                    horizon_ms,
+                    bps,
+                    pnl: direction * (mid - fill.price) * fill.size,
+                });
+                fill.next_horizon += 1;
+            }
+        }
+        state.pending.retain(|f| f.next_horizon < horizons.len());
+
+        resolved
+    }
+
+    /// Average markout (bps) for a market and horizon, once `min_samples`
+    /// markouts have been observed
+    pub fn average_markout(&self, market: &str, horizon_ms: u64) -> Option<f64> {
+        let index = self.config.horizons_ms.iter().position(|&h| h == horizon_ms)?;
+        let stats = self.markets.get(market)?.horizons.get(index)?;
+        (stats.samples >= self.config.min_samples.max(1)).then_some(stats.ewma_bps)
+    }
+
+    /// Worst (most adverse) average markout across horizons
+    pub fn worst_markout(&self, market: &str) -> Option<f64> {
+        self.config
+            .horizons_ms
+            .iter()
+            .filter_map(|&h| self.average_markout(market, h))
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Recommended quoting adjustment for a market
+    pub fn action(&self, market: &str) -> ToxicityAction {
+        let Some(worst) = self.worst_markout(market) else {
+            return ToxicityAction::Normal;
+        };
+
+        let widen = self.config.widen_threshold_bps;
+        let pull = self.config.pull_threshold_bps.min(widen);
+
+        if worst >= widen {
+            ToxicityAction::Normal
+        } else if worst <= pull {
+            ToxicityAction::Pull
+        } else {
+            let severity = (widen - worst) / (widen - pull);
+            ToxicityAction::Widen(1.0 + severity * (self.config.max_spread_multiplier - 1.0).max(0.0))
+        }
+    }
+
+    /// Number of fills still awaiting markouts for a market
+    pub fn pending_fills(&self, market: &str) -> usize {
+        self.markets.get(market).map(|s| s.pending.len()).unwrap_or(0)
+    }
+
+    /// Forget all state for a market
+    pub fn reset(&mut self, market: &str) {
+        self.markets.remove(market);
+    }
+
+    fn market_mut(&mut self, market: &str) -> &mut MarketToxicity {
+        let horizons = self.config.horizons_ms.len();
+        self.markets
+            .entry(market.to_string())
+            .or_insert_with(|| MarketToxicity {
+                pending: VecDeque::new(),
+                horizons: vec![HorizonStats::default(); horizons],
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(1_700_000_000_000 + ms).unwrap()
+    }
+
+    fn config() -> ToxicityConfig {
+        ToxicityConfig {
+            horizons_ms: vec![5_000, 1_000],
+            ewma_alpha: 1.0,
+            min_samples: 1,
+            widen_threshold_bps: -10.0,
+            pull_threshold_bps: -50.0,
+            max_spread_multiplier: 3.0,
+        }
+    }
+
+    #[test]
+    fn test_markouts_resolve_per_horizon() {
+        let mut monitor = ToxicityMonitor::new(config());
+        monitor.record_fill("m1", Side::Buy, 0.50, 100.0, at(0));
+        assert_eq!(monitor.action("m1"), ToxicityAction::Normal);
+
+        // Before the first horizon nothing resolves
+        assert!(monitor.on_mid("m1", 0.49, at(500)).is_empty());
+
+        // 1s horizon: bought at 0.50, mid 0.51 -> +200 bps
+        let markouts = monitor.on_mid("m1", 0.51, at(1_200));
+        assert_eq!(markouts.len(), 1);
+        assert_eq!(markouts[0].horizon_ms, 1_000);
+        assert!((markouts[0].bps - 200.0).abs() < 1e-6);
+        assert!((markouts[0].pnl - 1.0).abs() < 1e-9);
+        assert_eq!(monitor.pending_fills("m1"), 1);
+
+        // 5s horizon resolves and the fill is dropped
+        let markouts = monitor.on_mid("m1", 0.50, at(6_000));
+        assert_eq!(markouts.len(), 1);
+        assert_eq!(monitor.pending_fills("m1"), 0);
+        assert_eq!(monitor.average_markout("m1", 5_000), Some(0.0));
+        assert!(monitor.on_mid("other", 0.5, at(6_000)).is_empty());
+    }
+
+    #[test]
+    fn test_action_widens_then_pulls() {
+        let mut monitor = ToxicityMonitor::new(config());
+
+        // Sold at 0.50, mid rises to 0.5015 -> -30 bps: widen halfway
+        monitor.record_fill("m1", Side::Sell, 0.50, 10.0, at(0));
+        monitor.on_mid("m1", 0.5015, at(1_000));
+        match monitor.action("m1") {
+            ToxicityAction::Widen(m) => assert!((m - 2.0).abs() < 1e-6),
+            other => panic!("expected widen, got {:?}", other),
+        }
+
+        // Mid at 0.51 -> -200 bps at 5s: pull
+        monitor.on_mid("m1", 0.51, at(5_000));
+        assert_eq!(monitor.action("m1"), ToxicityAction::Pull);
+        assert_eq!(monitor.action("m1").spread_multiplier(), None);
+
+        monitor.reset("m1");
+        assert_eq!(monitor.action("m1"), ToxicityAction::Normal);
+    }
+}
@@ -0,0 +1,260 @@
+//! Realized volatility and trade intensity estimation
+//!
+//! [`VolatilityEstimator`] keeps time-decayed estimates of the mid's return
+//! variance and of the trade arrival rate for one market, so that quoting
+//! logic can react to how busy the market currently is.
+//! [`AdaptiveRequoteConfig`] turns those estimates into a requote interval.
+
+use crate::types::MarketTick;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Adaptive requote interval settings
+///
+/// The base interval (the market maker's `min_quote_interval_ms`) applies
+/// when the mid's expected move over that interval is `spread_fraction` of
+/// the quoted half spread, or when trades arrive at
+/// `reference_trades_per_sec`. Busier markets shorten the interval in
+/// proportion, quieter ones lengthen it, within the configured bounds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptiveRequoteConfig {
+    /// Fastest allowed requote interval in milliseconds
+    pub min_interval_ms: u64,
+
+    /// Slowest requote interval in milliseconds (also used with no activity)
+    pub max_interval_ms: u64,
+
+    /// Half-life of the volatility and trade rate estimates in milliseconds
+    pub half_life_ms: u64,
+
+    /// Share of the half spread the mid may move per base interval before
+    /// requoting speeds up
+    pub spread_fraction: f64,
+
+    /// Trade rate at which the base interval applies
+    pub reference_trades_per_sec: f64,
+}
+
+impl Default for AdaptiveRequoteConfig {
+    fn default() -> Self {
+        Self {
+            min_interval_ms: 25,
+            max_interval_ms: 2_000,
+            half_life_ms: 30_000,
+            spread_fraction: 0.25,
+            reference_trades_per_sec: 1.0,
+        }
+    }
+}
+
+impl AdaptiveRequoteConfig {
+    /// Effective requote interval in milliseconds
+    ///
+    /// `base_interval_ms` applies at reference activity and `half_spread_bps`
+    /// is the half spread currently being quoted.
+    pub fn interval_ms(&self, base_interval_ms: u64, half_spread_bps: f64, estimator: &VolatilityEstimator) -> u64 {
+        let min = self.min_interval_ms.min(self.max_interval_ms);
+        let max = self.max_interval_ms;
+
+        let vol_activity = if half_spread_bps > 0.0 && self.spread_fraction > 0.0 {
+            let base_secs = base_interval_ms.max(1) as f64 / 1000.0;
+            estimator.volatility_bps() * base_secs.sqrt() / (self.spread_fraction * half_spread_bps)
+        } else {
+            0.0
+        };
+        let trade_activity = if self.reference_trades_per_sec > 0.0 {
+            estimator.trades_per_sec() / self.reference_trades_per_sec
+        } else {
+            0.0
+        };
+
+        let activity = vol_activity.max(trade_activity);
+        if activity <= f64::EPSILON {
+            return max;
+        }
+
+        let interval = base_interval_ms as f64 / activity;
+        if interval.is_finite() {
+            (interval.round() as u64).clamp(min, max)
+        } else {
+            max
+        }
+    }
+}
+
+/// Time-decayed realized volatility and trade rate for one market
+///
+/// Each squared log return of the mid (and each detected trade) is added to
+/// an exponentially decaying sum normalised by the decay time constant, so
+/// the estimates are per-second rates independent of tick frequency. Trades
+/// are detected from increases in `volume_24h`, or from changes in `last`
+/// when the feed has no volume.
+#[derive(Debug, Clone)]
+pub struct VolatilityEstimator {
+    /// Decay time constant in seconds
+    tau_secs: f64,
+    variance_rate: f64,
+    trade_rate: f64,
+    last_mid: Option<f64>,
+    last_trade_price: Option<f64>,
+    last_volume: Option<f64>,
+    last_update: Option<DateTime<Utc>>,
+}
+
+impl VolatilityEstimator {
+    /// Create an estimator with the given half-life
+    pub fn new(half_life_ms: u64) -> Self {
+        Self {
+            tau_secs: half_life_ms.max(1) as f64 / 1000.0 / std::f64::consts::LN_2,
+            variance_rate: 0.0,
+            trade_rate: 0.0,
+            last_mid: None,
+            last_trade_price: None,
+            last_volume: None,
+            last_update: None,
+        }
+    }
+
+    /// Update from a market tick
+    pub fn on_tick(&mut self, tick: &MarketTick) {
+        let mid = tick.mid_price();
+        let mid = (mid > 1e-8).then_some(mid);
+
+        let traded = match (tick.volume_24h, self.last_volume) {
+            (Some(volume), Some(previous)) => volume > previous,
+            (Some(_), None) => false,
+            (None, _) => matches!(
+                (tick.last, self.last_trade_price),
+                (Some(last), Some(previous)) if (last - previous).abs() > 1e-12
+            ),
+        };
+
+        self.observe(tick.timestamp, mid, u32::from(traded));
+
+        if tick.volume_24h.is_some() {
+            self.last_volume = tick.volume_24h;
+        }
+        if tick.last.is_some() {
+            self.last_trade_price = tick.last;
+        }
+    }
+
+    /// Update from a mid observation and a count of trades since the last one
+    pub fn observe(&mut self, timestamp: DateTime<Utc>, mid: Option<f64>, trades: u32) {
+        if let Some(last_update) = self.last_update {
+            let dt = (timestamp - last_update).num_milliseconds().max(0) as f64 / 1000.0;
+            let decay = (-dt / self.tau_secs).exp();
+            self.variance_rate *= decay;
+            self.trade_rate *= decay;
+        }
+
+        if let (Some(mid), Some(last_mid)) = (mid, self.last_mid) {
+            let log_return = (mid / last_mid).ln();
+            self.variance_rate += log_return * log_return / self.tau_secs;
+        }
+        self.trade_rate += trades as f64 / self.tau_secs;
+
+        if mid.is_some() {
+            self.last_mid = mid;
+        }
+        if !matches!(self.last_update, Some(last) if last >= timestamp) {
+            self.last_update = Some(timestamp);
+        }
+    }
+
+    /// Realized volatility of the mid in bps per square-root second
+    pub fn volatility_bps(&self) -> f64 {
+        self.variance_rate.sqrt() * 10_000.0
+    }
+
+    /// Trade arrivals per second
+    pub fn trades_per_sec(&self) -> f64 {
+        self.trade_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(1_700_000_000_000 + ms).unwrap()
+    }
+
+    #[test]
+    fn test_estimates_decay_with_half_life() {
+        let mut estimator = VolatilityEstimator::new(1_000);
+        estimator.observe(at(0), Some(0.50), 0);
+        estimator.observe(at(100), Some(0.51), 1);
+        let vol = estimator.volatility_bps();
+        let rate = estimator.trades_per_sec();
+        assert!(vol > 0.0 && rate > 0.0);
+
+        // One half-life later with no move: variance (and the rate) halve
+        estimator.observe(at(1_100), Some(0.51), 0);
+        assert!((estimator.trades_per_sec() - rate / 2.0).abs() < 1e-9);
+        assert!((estimator.volatility_bps() - vol / 2f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interval_follows_activity() {
+        let config = AdaptiveRequoteConfig {
+            min_interval_ms: 20,
+            max_interval_ms: 1_000,
+            half_life_ms: 10_000,
+            spread_fraction: 0.25,
+            reference_trades_per_sec: 2.0,
+        };
+
+        // No activity: slowest interval
+        let quiet = VolatilityEstimator::new(config.half_life_ms);
+        assert_eq!(config.interval_ms(100, 10.0, &quiet), 1_000);
+
+        // Trades at twice the reference rate halve the base interval
+        let mut busy = VolatilityEstimator::new(config.half_life_ms);
+        let tau = 10.0 / std::f64::consts::LN_2;
+        busy.observe(at(0), Some(0.5), (4.0 * tau).round() as u32);
+        let interval = config.interval_ms(100, 10.0, &busy);
+        assert!((49..=51).contains(&interval), "interval {}", interval);
+
+        // A volatile mid relative to a tight spread hits the floor
+        let mut volatile = VolatilityEstimator::new(config.half_life_ms);
+        for i in 0..20 {
+            let mid = if i % 2 == 0 { 0.50 } else { 0.52 };
+            volatile.observe(at(i * 100), Some(mid), 0);
+        }
+        assert_eq!(config.interval_ms(100, 10.0, &volatile), 20);
+        assert!(config.interval_ms(100, 10_000.0, &volatile) > 20);
+    }
+
+    #[test]
+    fn test_trades_detected_from_volume() {
+        let mut estimator = VolatilityEstimator::new(1_000);
+        let mut tick = MarketTick {
+            market: "m1".to_string(),
+            timestamp: at(0),
+            bid: Some(0.49),
+            ask: Some(0.51),
+            bid_size: None,
+            ask_size: None,
+            last: None,
+            volume_24h: Some(100.0),
+            sequence: None,
+        };
+        estimator.on_tick(&tick);
+        assert_eq!(estimator.trades_per_sec(), 0.0);
+
+        tick.timestamp = at(100);
+        tick.volume_24h = Some(100.0);
+        estimator.on_tick(&tick);
+        assert_eq!(estimator.trades_per_sec(), 0.0);
+
+        tick.timestamp = at(200);
+        tick.volume_24h = Some(150.0);
+        estimator.on_tick(&tick);
+        assert!(estimator.trades_per_sec() > 0.0);
+        assert_eq!(estimator.volatility_bps(), 0.0);
+    }
+}
@@ -1,10 +1,14 @@
 //! Strategy execution context
 
 use crate::{StrategyError, StrategyResult, StrategyParams};
-use crate::types::{Order, OrderId, Position, MarketId};
-use crate::metrics::StrategyMetric;
-use ag_risk::{RiskEngine, RiskContext};
-use std::collections::HashMap;
+use crate::types::{Fill, Order, OrderId, OrderStatus, Position, MarketId, Side};
+use crate::flags::{self, FeatureFlags};
+use crate::venues::VenueStatuses;
+use crate::matching::MatchingEngine;
+use crate::metrics::{metric_names, StrategyMetric};
+use ag_risk::clock::{system_clock, SequentialIdGenerator, SharedClock, SharedIdGenerator};
+use ag_risk::{RiskDecision, RiskEngine, RiskContext, RiskLeg};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use parking_lot::Mutex;
 use chrono::Utc;
@@ -13,7 +17,10 @@ use chrono::Utc;
 /// In production, this will interface with the actual exec/ module
 pub struct MockExecutionEngine {
     orders: HashMap<OrderId, Order>,
-    next_order_id: u64,
+    /// Source of order ID numbers
+    ids: SharedIdGenerator,
+    /// Shared matching engine and the owner to trade as
+    matching: Option<(Arc<Mutex<MatchingEngine>>, String)>,
 }
 
 impl Default for MockExecutionEngine {
@@ -24,28 +31,72 @@ impl Default for MockExecutionEngine {
 
 impl MockExecutionEngine {
     pub fn new() -> Self {
+        Self::with_id_generator(Arc::new(SequentialIdGenerator::new(1)))
+    }
+
+    /// Number orders from `ids` instead of a private counter
+    pub fn with_id_generator(ids: SharedIdGenerator) -> Self {
         Self {
             orders: HashMap::new(),
-            next_order_id: 1,
+            ids,
+            matching: None,
         }
     }
 
-    pub fn submit_order(&mut self, mut order: Order) -> StrategyResult<OrderId> {
-        let order_id = format!("order_{}", self.next_order_id);
-        self.next_order_id += 1;
+    /// Match orders on a shared [`MatchingEngine`] as `owner` instead of
+    /// only recording them
+    pub fn with_matching_engine(mut self, engine: Arc<Mutex<MatchingEngine>>, owner: impl Into<String>) -> Self {
+        self.matching = Some((engine, owner.into()));
+        self
+    }
 
+    pub fn submit_order(&mut self, mut order: Order) -> StrategyResult<OrderId> {
+        let number = self.ids.next_id();
+        let order_id = match &self.matching {
+            // Owner-prefixed so IDs stay unique across strategies sharing a book
+            Some((_, owner)) => format!("{}_order_{}", owner, number),
+            None => format!("order_{}", number),
+        };
         order.id = Some(order_id.clone());
+
+        if let Some((engine, owner)) = &self.matching {
+            let result = engine.lock().submit(owner, &order)?;
+            if result.resting <= 0.0 {
+                return Ok(order_id);
+            }
+        }
         self.orders.insert(order_id.clone(), order);
 
         Ok(order_id)
     }
 
     pub fn cancel_order(&mut self, order_id: &OrderId) -> StrategyResult<()> {
+        if let Some((engine, _)) = &self.matching {
+            self.orders.remove(order_id);
+            engine.lock().cancel(order_id)?;
+            return Ok(());
+        }
         self.orders.remove(order_id)
             .ok_or_else(|| StrategyError::OrderNotFound(order_id.clone()))?;
         Ok(())
     }
 
+    /// Take fills the matching engine queued for this engine's owner
+    pub fn take_fills(&mut self) -> Vec<Fill> {
+        match &self.matching {
+            Some((engine, owner)) => {
+                let fills = engine.lock().take_fills(owner);
+                for fill in &fills {
+                    if engine.lock().resting_size(&fill.order_id).is_none() {
+                        self.orders.remove(&fill.order_id);
+                    }
+                }
+                fills
+            }
+            None => Vec::new(),
+        }
+    }
+
     pub fn get_order(&self, order_id: &OrderId) -> Option<&Order> {
         self.orders.get(order_id)
     }
@@ -67,6 +118,12 @@ pub struct StrategyContext {
     /// Mock execution engine (will be replaced with real exec module)
     exec_engine: Arc<Mutex<MockExecutionEngine>>,
 
+    /// Source of order IDs, shared with the execution engine
+    ids: SharedIdGenerator,
+
+    /// Time source for order, position and metric timestamps
+    clock: SharedClock,
+
     /// Risk engine
     risk_engine: Arc<Mutex<RiskEngine>>,
 
@@ -79,6 +136,31 @@ pub struct StrategyContext {
     /// Strategy parameters
     pub params: StrategyParams,
 
+    /// Capital allocated by the coordinator (None until allocated)
+    pub allocated_capital: Option<f64>,
+
+    /// Target position value per market in USD (signed, long YES positive),
+    /// set by [`MultiMarketCoordinator::allocate_by_weights`](crate::MultiMarketCoordinator::allocate_by_weights)
+    pub target_exposures: HashMap<String, f64>,
+
+    /// Whether the market data feed is live; orders are refused while it
+    /// is not, so quotes are never placed against frozen prices
+    pub feed_connected: bool,
+
+    /// Market ticks left before the strategy's warm-up completes; orders are
+    /// refused until it reaches zero
+    pub warmup_remaining: usize,
+
+    /// Runtime feature flags, shared with the coordinator
+    pub flags: FeatureFlags,
+
+    /// Venue availability, shared with the coordinator
+    pub venues: VenueStatuses,
+
+    /// Tags added to every submitted order, e.g. the run ID; tags set on
+    /// the order itself take precedence
+    pub order_tags: BTreeMap<String, String>,
+
     /// Metrics buffer (to be sent to monitor)
     metrics_buffer: Vec<StrategyMetric>,
 }
@@ -90,13 +172,23 @@ impl StrategyContext {
         risk_engine: Arc<Mutex<RiskEngine>>,
         params: StrategyParams,
     ) -> Self {
+        let ids: SharedIdGenerator = Arc::new(SequentialIdGenerator::new(1));
         Self {
             strategy_id,
-            exec_engine: Arc::new(Mutex::new(MockExecutionEngine::new())),
+            exec_engine: Arc::new(Mutex::new(MockExecutionEngine::with_id_generator(ids.clone()))),
+            ids,
+            clock: system_clock(),
             risk_engine,
             positions: HashMap::new(),
             orders: HashMap::new(),
             params,
+            allocated_capital: None,
+            target_exposures: HashMap::new(),
+            feed_connected: true,
+            warmup_remaining: 0,
+            flags: FeatureFlags::default(),
+            venues: VenueStatuses::default(),
+            order_tags: BTreeMap::new(),
             metrics_buffer: Vec::new(),
         }
     }
@@ -104,9 +196,99 @@ impl StrategyContext {
     /// Submit an order with risk checks
     ///
     /// This method performs pre-trade risk checks before submitting the order
-    /// to the execution engine.
+    /// to the execution engine. Fails with [`StrategyError::FeedDisconnected`]
+    /// while the market data feed is down, [`StrategyError::WarmingUp`]
+    /// until warm-up completes and [`StrategyError::FeatureDisabled`] for
+    /// taker orders while [`ENABLE_TAKER_ORDERS`](flags::ENABLE_TAKER_ORDERS)
+    /// is off.
     pub async fn submit_order(&mut self, order: Order) -> StrategyResult<OrderId> {
-        // Build risk context
+        self.check_submittable(&order)?;
+
+        // Evaluate risk
+        let risk_ctx = self.risk_context(&order);
+        let risk_decision = {
+            let risk_engine = self.risk_engine.lock();
+            risk_engine.evaluate(&risk_ctx)
+        };
+
+        if !risk_decision.allowed {
+            return Err(StrategyError::RiskRejected {
+                violations: risk_decision.violations,
+            });
+        }
+
+        self.place_order(order)
+    }
+
+    /// Risk decision [`submit_order`](Self::submit_order) would get for
+    /// `order` right now, without recording the evaluation
+    pub fn check_risk(&self, order: &Order) -> RiskDecision {
+        let risk_ctx = self.risk_context(order);
+        self.risk_engine.lock().dry_run(&risk_ctx)
+    }
+
+    /// Submit the legs of a multi-leg trade (e.g. both sides of an arb)
+    /// all or nothing
+    ///
+    /// The legs are risk checked together with
+    /// [`RiskEngine::evaluate_group`](ag_risk::RiskEngine::evaluate_group)
+    /// as trades in the same outcome, so a `HedgedGroupLimit` policy nets a
+    /// buy in one market against a sell in another instead of rejecting
+    /// each leg on its per-market limits. Nothing is submitted unless every
+    /// leg passes. Returns the order IDs in input order.
+    pub async fn submit_order_group(&mut self, orders: Vec<Order>) -> StrategyResult<Vec<OrderId>> {
+        for order in &orders {
+            self.check_submittable(order)?;
+        }
+
+        let legs: Vec<RiskLeg> = orders.iter().map(|order| RiskLeg::new(self.risk_context(order))).collect();
+        let decision = {
+            let risk_engine = self.risk_engine.lock();
+            risk_engine.evaluate_group(&legs)
+        };
+
+        if !decision.aggregate.allowed {
+            return Err(StrategyError::RiskRejected {
+                violations: decision.aggregate.violations,
+            });
+        }
+
+        orders.into_iter().map(|order| self.place_order(order)).collect()
+    }
+
+    /// CVaR a trade of `size` (signed: +buy, -sell) at `price` would add to
+    /// the portfolio, in USD
+    ///
+    /// Negative when the trade hedges. None when the risk engine has no
+    /// return history for the market (see
+    /// [`RiskEngine::set_return_history`](ag_risk::RiskEngine::set_return_history)).
+    pub fn marginal_cvar(&self, market_id: &str, size: f64, price: f64, confidence_level: f64) -> Option<f64> {
+        let risk_engine = self.risk_engine.lock();
+        risk_engine
+            .marginal_cvar(market_id, size * price, confidence_level)
+            .ok()
+            .map(|cvar| cvar.impact())
+    }
+
+    /// Refuse orders while the feed is down, during warm-up and for taker
+    /// orders while they are disabled
+    fn check_submittable(&self, order: &Order) -> StrategyResult<()> {
+        if !self.feed_connected {
+            return Err(StrategyError::FeedDisconnected);
+        }
+        if self.warmup_remaining > 0 {
+            return Err(StrategyError::WarmingUp {
+                remaining: self.warmup_remaining,
+            });
+        }
+        if flags::is_taker_order(order) && !self.flags.is_enabled(flags::ENABLE_TAKER_ORDERS) {
+            return Err(StrategyError::FeatureDisabled(flags::ENABLE_TAKER_ORDERS.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Build the risk context of an order
+    fn risk_context(&self, order: &Order) -> RiskContext {
         let position = self.get_position(&order.market)
             .map(|p| p.size)
             .unwrap_or(0.0);
@@ -116,39 +298,120 @@ impl StrategyContext {
             crate::types::Side::Sell => -order.size,
         };
 
-        let inventory_value = self.calculate_total_inventory_value();
-
-        let risk_ctx = RiskContext {
+        RiskContext {
             market_id: order.market.clone(),
             current_position: position,
             proposed_size,
-            inventory_value_usd: inventory_value,
-        };
-
-        // Evaluate risk
-        let risk_decision = {
-            let risk_engine = self.risk_engine.lock();
-            risk_engine.evaluate(&risk_ctx)
-        };
-
-        if !risk_decision.allowed {
-            return Err(StrategyError::RiskRejected {
-                policies: risk_decision.violated_policies,
-            });
+            inventory_value_usd: self.calculate_total_inventory_value(),
         }
+    }
 
-        // Submit order to execution engine
+    /// Send a risk-checked order to the execution engine and track it
+    fn place_order(&mut self, mut order: Order) -> StrategyResult<OrderId> {
+        for (key, value) in &self.order_tags {
+            order.tags.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        order.timestamp = self.clock.now();
         let order_id = {
             let mut exec_engine = self.exec_engine.lock();
             exec_engine.submit_order(order.clone())?
         };
 
-        // Track order
+        // Track order under its assigned ID
+        order.id = Some(order_id.clone());
         self.orders.insert(order_id.clone(), order);
 
         Ok(order_id)
     }
 
+    /// Trade on a shared [`MatchingEngine`] as this strategy instead of the
+    /// paper engine, so fills depend on the book
+    ///
+    /// Orders that trade on submission, and resting orders hit later, queue
+    /// fills that [`take_paper_fills`](Self::take_paper_fills) returns.
+    pub fn use_matching_engine(&mut self, engine: Arc<Mutex<MatchingEngine>>) {
+        let exec_engine = MockExecutionEngine::with_id_generator(self.ids.clone())
+            .with_matching_engine(engine, self.strategy_id.clone());
+        self.exec_engine = Arc::new(Mutex::new(exec_engine));
+    }
+
+    /// Stamp orders, positions and metrics with `clock` instead of the
+    /// system clock (e.g. a `MockClock` in tests and backtests)
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Time source of the context
+    pub fn clock(&self) -> SharedClock {
+        self.clock.clone()
+    }
+
+    /// Current time according to the context's clock
+    pub fn now(&self) -> chrono::DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Number orders from `ids`; sharing one generator between contexts
+    /// keeps order IDs unique across strategies
+    ///
+    /// Replaces the execution engine, so call it before submitting orders.
+    pub fn set_id_generator(&mut self, ids: SharedIdGenerator) {
+        self.exec_engine = Arc::new(Mutex::new(MockExecutionEngine::with_id_generator(ids.clone())));
+        self.ids = ids;
+    }
+
+    /// Fills from the matching engine since the last call, oldest first
+    ///
+    /// They are not applied; pass each to [`apply_fill`](Self::apply_fill)
+    /// (or route it through the coordinator) before `Strategy::on_fill`.
+    pub fn take_paper_fills(&mut self) -> Vec<Fill> {
+        self.exec_engine.lock().take_fills()
+    }
+
+    /// Whether order submission is suppressed by warm-up
+    pub fn is_warming_up(&self) -> bool {
+        self.warmup_remaining > 0
+    }
+
+    /// Start a warm-up of `ticks` market ticks and report it as a
+    /// [`WARMUP_REMAINING`](crate::metrics::metric_names::WARMUP_REMAINING) gauge
+    pub fn start_warmup(&mut self, ticks: usize) {
+        self.warmup_remaining = ticks;
+        if ticks > 0 {
+            self.push_warmup_metric();
+        }
+    }
+
+    /// Count a market tick towards warm-up; returns true on the tick that
+    /// completes it
+    ///
+    /// Called by the coordinator and backtest engine before
+    /// `Strategy::on_market_tick`, so the strategy may trade on the last
+    /// warm-up tick.
+    pub fn record_warmup_tick(&mut self) -> bool {
+        if self.warmup_remaining == 0 {
+            return false;
+        }
+        self.warmup_remaining -= 1;
+        if self.warmup_remaining == 0 {
+            self.push_warmup_metric();
+            return true;
+        }
+        false
+    }
+
+    /// Buffer the current warm-up state as a gauge
+    pub(crate) fn push_warmup_metric(&mut self) {
+        let mut metric = StrategyMetric::gauge(
+            self.strategy_id.clone(),
+            metric_names::WARMUP_REMAINING.to_string(),
+            self.warmup_remaining as f64,
+            HashMap::new(),
+        );
+        metric.timestamp = self.clock.now();
+        self.metrics_buffer.push(metric);
+    }
+
     /// Cancel an order
     pub async fn cancel_order(&mut self, order_id: &OrderId) -> StrategyResult<()> {
         {
@@ -170,6 +433,39 @@ impl StrategyContext {
         self.positions.get_mut(market_id)
     }
 
+    /// Apply a fill routed to this strategy: updates the position and the
+    /// filled order
+    ///
+    /// The coordinator (and backtest engine) call this before
+    /// `Strategy::on_fill` unless the strategy manages positions itself, so
+    /// strategies should not also call [`update_position`](Self::update_position)
+    /// for routed fills.
+    pub fn apply_fill(&mut self, fill: &Fill) {
+        let size_delta = match fill.side {
+            Side::Buy => fill.size,
+            Side::Sell => -fill.size,
+        };
+        self.update_position(&fill.market, size_delta, fill.price);
+        self.record_order_fill(fill);
+    }
+
+    /// Reduce the filled order's remaining size, dropping it once fully
+    /// filled; the position is left untouched
+    ///
+    /// Partially filled orders stay in [`orders`](Self::orders) with
+    /// `size` set to the unfilled remainder.
+    pub fn record_order_fill(&mut self, fill: &Fill) {
+        let Some(order) = self.orders.get_mut(&fill.order_id) else {
+            return;
+        };
+        order.size -= fill.size;
+        if order.size > 1e-8 {
+            order.status = OrderStatus::PartiallyFilled;
+        } else {
+            self.orders.remove(&fill.order_id);
+        }
+    }
+
     /// Update position (typically called after fills)
     pub fn update_position(&mut self, market_id: &str, size_delta: f64, price: f64) {
         let position = self.positions
@@ -191,7 +487,7 @@ impl StrategyContext {
 
         position.size = new_size;
         position.mark_price = price;
-        position.timestamp = Utc::now();
+        position.timestamp = self.clock.now();
 
         // Calculate unrealized PnL
         if position.size.abs() > 1e-8 {
@@ -203,6 +499,26 @@ impl StrategyContext {
         position.value_usd = position.size.abs() * price;
     }
 
+    /// Settle the position in a resolved market at `payout` per share
+    ///
+    /// Books `size * (payout - entry_price)` as realized PnL and leaves the
+    /// position flat, marked at the payout. Returns the position as it was
+    /// before settlement (`None` if there was none).
+    pub fn settle_position(&mut self, market_id: &str, payout: f64) -> Option<Position> {
+        let now = self.clock.now();
+        let position = self.positions.get_mut(market_id)?;
+        let settled = position.clone();
+
+        position.realized_pnl += position.size * (payout - position.entry_price);
+        position.size = 0.0;
+        position.entry_price = 0.0;
+        position.mark_price = payout;
+        position.unrealized_pnl = 0.0;
+        position.value_usd = 0.0;
+        position.timestamp = now;
+        Some(settled)
+    }
+
     /// Get all open orders
     pub fn get_open_orders(&self) -> Vec<&Order> {
         self.orders.values().collect()
@@ -216,10 +532,11 @@ impl StrategyContext {
             .collect()
     }
 
-    /// Emit a strategy metric
-    pub async fn emit_metric(&mut self, metric: StrategyMetric) -> StrategyResult<()> {
+    /// Emit a strategy metric, stamped with the context's clock
+    pub async fn emit_metric(&mut self, mut metric: StrategyMetric) -> StrategyResult<()> {
         // In production, this would send to monitor module
         // For now, buffer the metrics
+        metric.timestamp = self.clock.now();
         self.metrics_buffer.push(metric);
         Ok(())
     }
@@ -306,8 +623,115 @@ policies:
             ..Default::default()
         };
 
-        let result = ctx.submit_order(order).await;
+        let result = ctx.submit_order(order.clone()).await;
         assert!(result.is_ok());
+
+        ctx.feed_connected = false;
+        let result = ctx.submit_order(order.clone()).await;
+        assert!(matches!(result, Err(StrategyError::FeedDisconnected)));
+
+        ctx.feed_connected = true;
+        ctx.start_warmup(2);
+        let result = ctx.submit_order(order.clone()).await;
+        assert!(matches!(result, Err(StrategyError::WarmingUp { remaining: 2 })));
+        assert!(!ctx.record_warmup_tick());
+        assert!(ctx.record_warmup_tick());
+        assert!(!ctx.is_warming_up());
+
+        // Taker orders are gated at runtime; resting quotes are not
+        ctx.flags.set(crate::flags::ENABLE_TAKER_ORDERS, false);
+        let ioc = Order { time_in_force: TimeInForce::IOC, size: 10.0, ..order.clone() };
+        let result = ctx.submit_order(ioc).await;
+        assert!(matches!(result, Err(StrategyError::FeatureDisabled(flag)) if flag == "enable_taker_orders"));
+        assert!(ctx.submit_order(Order { size: 10.0, ..order.clone() }).await.is_ok());
+
+        // Context tags are added without overriding the order's own
+        ctx.order_tags.insert("run_id".to_string(), "run_7".to_string());
+        ctx.order_tags.insert("signal".to_string(), "default".to_string());
+        let order_id = ctx.submit_order(Order { size: 10.0, ..order }.with_signal("momentum")).await.unwrap();
+        assert_eq!(ctx.orders[&order_id].tags.get("run_id").map(String::as_str), Some("run_7"));
+        assert_eq!(ctx.orders[&order_id].signal(), Some("momentum"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_order_group() {
+        let yaml = r#"
+policies:
+  - type: PositionLimit
+    max_size: 100.0
+  - type: HedgedGroupLimit
+    max_net_exposure: 0.0
+"#;
+        let risk_engine = Arc::new(Mutex::new(RiskEngine::from_yaml(yaml).unwrap()));
+        let mut ctx = StrategyContext::new("arb".to_string(), risk_engine, StrategyParams::new());
+        let leg = |market: &str, side: Side, size: f64| Order {
+            venue: "polymarket".to_string(),
+            market: market.to_string(),
+            side,
+            order_type: OrderType::Limit,
+            price: Some(0.5),
+            size,
+            ..Default::default()
+        };
+
+        // Hedged legs pass although each exceeds the position limit
+        let ids = ctx
+            .submit_order_group(vec![leg("a", Side::Buy, 200.0), leg("b", Side::Sell, 200.0)])
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ctx.orders[&ids[0]].market, "a");
+        assert!(matches!(
+            ctx.submit_order(leg("a", Side::Buy, 200.0)).await,
+            Err(StrategyError::RiskRejected { .. })
+        ));
+
+        // An unbalanced group is rejected without submitting any leg
+        let result = ctx
+            .submit_order_group(vec![leg("a", Side::Buy, 200.0), leg("b", Side::Sell, 150.0)])
+            .await;
+        assert!(matches!(result, Err(StrategyError::RiskRejected { .. })));
+        assert_eq!(ctx.orders.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_trades_against_matching_engine() {
+        let engine = Arc::new(Mutex::new(MatchingEngine::default()));
+        let mut ctx = create_test_context();
+        ctx.use_matching_engine(engine.clone());
+
+        let ask = Order {
+            market: "market1".to_string(),
+            side: Side::Sell,
+            price: Some(0.60),
+            size: 30.0,
+            ..Default::default()
+        };
+        engine.lock().submit("liquidity", &ask).unwrap();
+
+        // Crosses for 30, rests 20
+        let bid = Order {
+            market: "market1".to_string(),
+            side: Side::Buy,
+            price: Some(0.61),
+            size: 50.0,
+            ..Default::default()
+        };
+        let order_id = ctx.submit_order(bid).await.unwrap();
+        for fill in ctx.take_paper_fills() {
+            ctx.apply_fill(&fill);
+        }
+        assert_eq!(ctx.get_position("market1").unwrap().size, 30.0);
+        assert_eq!(ctx.orders[&order_id].size, 20.0);
+
+        // Hit later by another owner
+        let sell = Order { side: Side::Sell, price: Some(0.61), size: 5.0, ..ask };
+        engine.lock().submit("liquidity", &sell).unwrap();
+        let fills = ctx.take_paper_fills();
+        assert_eq!((fills.len(), fills[0].size, fills[0].price), (1, 5.0, 0.61));
+
+        ctx.cancel_order(&order_id).await.unwrap();
+        assert!(engine.lock().book("market1").unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -329,6 +753,40 @@ policies:
         assert!((pos.entry_price - expected_entry).abs() < 0.01);
     }
 
+    #[tokio::test]
+    async fn test_apply_partial_fills() {
+        let mut ctx = create_test_context();
+
+        let order = Order {
+            venue: "polymarket".to_string(),
+            market: "market1".to_string(),
+            side: Side::Sell,
+            price: Some(0.6),
+            size: 100.0,
+            ..Default::default()
+        };
+        let order_id = ctx.submit_order(order).await.unwrap();
+        let fill = |size: f64| Fill {
+            order_id: order_id.clone(),
+            market: "market1".to_string(),
+            price: 0.6,
+            size,
+            side: Side::Sell,
+            fee: 0.0,
+            timestamp: Utc::now(),
+        };
+
+        ctx.apply_fill(&fill(40.0));
+        assert_eq!(ctx.get_position("market1").unwrap().size, -40.0);
+        let remaining = &ctx.orders[&order_id];
+        assert_eq!(remaining.size, 60.0);
+        assert_eq!(remaining.status, OrderStatus::PartiallyFilled);
+
+        ctx.apply_fill(&fill(60.0));
+        assert_eq!(ctx.get_position("market1").unwrap().size, -100.0);
+        assert!(ctx.orders.is_empty());
+    }
+
     #[test]
     fn test_inventory_calculations() {
         let mut ctx = create_test_context();
@@ -340,4 +798,37 @@ policies:
         // 100 * 100 + 50 * 200 = 20000
         assert_eq!(total_value, 20000.0);
     }
+
+    #[tokio::test]
+    async fn test_injected_clock_and_ids() {
+        use ag_risk::clock::{Clock, MockClock, SequentialIdGenerator};
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let ids: SharedIdGenerator = Arc::new(SequentialIdGenerator::new(100));
+
+        // Two contexts sharing a generator never hand out the same ID
+        let mut contexts: Vec<StrategyContext> = (0..2).map(|_| create_test_context()).collect();
+        for ctx in &mut contexts {
+            ctx.set_clock(Arc::new(clock.clone()));
+            ctx.set_id_generator(ids.clone());
+        }
+        let order = Order {
+            market: "market1".to_string(),
+            price: Some(0.5),
+            size: 10.0,
+            ..Default::default()
+        };
+        assert_eq!(contexts[0].submit_order(order.clone()).await.unwrap(), "order_100");
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(contexts[1].submit_order(order).await.unwrap(), "order_101");
+        assert_eq!(contexts[1].orders["order_101"].timestamp, start + chrono::Duration::seconds(1));
+
+        let ctx = &mut contexts[0];
+        ctx.start_warmup(1);
+        ctx.update_position("market1", 5.0, 0.5);
+        assert_eq!(ctx.get_metrics_buffer()[0].timestamp, clock.now());
+        assert_eq!(ctx.get_position("market1").unwrap().timestamp, clock.now());
+    }
 }
@@ -1,13 +1,61 @@
 //! Multi-market strategy coordinator
 
-use crate::{Strategy, StrategyError, StrategyResult, StrategyContext};
+use crate::{RegistrationConflict, Strategy, StrategyError, StrategyResult, StrategyContext, StrategyMetric};
+use crate::flags::{FeatureFlags, ENABLE_NEW_MARKET_ONBOARDING};
+use crate::venues::VenueStatuses;
+use ag_risk::clock::{system_clock, SharedClock};
+use crate::mailbox::{EventPriority, StrategyEvent, StrategyMailbox, DEFAULT_MAX_BOOK_UPDATES};
 use crate::types::{MarketTick, Fill, OrderId, Position};
-use std::collections::HashMap;
+use crate::timer::{TimerConfig, TimerWheel};
+use crate::utilization::StrategyLoad;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use tracing::{warn, Instrument};
+
+/// Span around strategy callbacks, so their logs carry the strategy ID
+fn strategy_span(strategy_id: &str) -> tracing::Span {
+    tracing::info_span!("strategy", strategy_id = %strategy_id)
+}
+
+/// Check a subscription list against itself and the strategy's metadata
+fn check_markets(strategy_id: &str, strategy: &dyn Strategy, markets: &[String]) -> Result<(), RegistrationConflict> {
+    if let Some((_, market)) = markets.iter().enumerate().find(|(i, m)| markets[..*i].contains(m)) {
+        return Err(RegistrationConflict::DuplicateMarket {
+            strategy_id: strategy_id.to_string(),
+            market: market.clone(),
+        });
+    }
+
+    let declared = strategy.metadata().markets;
+    if declared.is_empty() {
+        return Ok(());
+    }
+    let missing: Vec<String> = declared.iter().filter(|m| !markets.contains(m)).cloned().collect();
+    let undeclared: Vec<String> = markets.iter().filter(|m| !declared.contains(m)).cloned().collect();
+    if missing.is_empty() && undeclared.is_empty() {
+        Ok(())
+    } else {
+        Err(RegistrationConflict::MarketMismatch {
+            strategy_id: strategy_id.to_string(),
+            missing,
+            undeclared,
+        })
+    }
+}
 
 /// Multi-market coordinator
 ///
 /// Orchestrates multiple strategies across different markets, routing market data
 /// and execution events to the appropriate strategies.
+///
+/// Events are either routed immediately (`route_*`) or queued per strategy
+/// (`enqueue_*`) and handled by [`dispatch_pending`](Self::dispatch_pending)
+/// in priority order: fills, cancels, book updates, timers.
+///
+/// Every callback is timed; [`drain_metrics`](Self::drain_metrics) reports
+/// each strategy's [`StrategyLoad`] (processing time, queue depth and
+/// utilization) since the previous drain.
 pub struct MultiMarketCoordinator {
     /// Registered strategies by ID
     strategies: HashMap<String, Box<dyn Strategy>>,
@@ -20,6 +68,36 @@ pub struct MultiMarketCoordinator {
 
     /// Strategy subscriptions: strategy_id -> market_ids
     strategy_markets: HashMap<String, Vec<String>>,
+
+    /// Per-strategy timer schedule
+    timers: TimerWheel,
+
+    /// Relative capital weights by strategy (default 1.0)
+    capital_weights: HashMap<String, f64>,
+
+    /// Whether the market data feed is live
+    feed_connected: bool,
+
+    /// Queued events by strategy
+    mailboxes: HashMap<String, StrategyMailbox>,
+
+    /// Bound on queued book updates per strategy
+    max_book_updates: usize,
+
+    /// Event loop load by strategy since the last metrics drain
+    loads: HashMap<String, StrategyLoad>,
+
+    /// Strategies that receive no market ticks or timers
+    paused: HashSet<String>,
+
+    /// Runtime feature flags, shared with every strategy context
+    flags: FeatureFlags,
+
+    /// Venue availability, shared with every strategy context
+    venues: VenueStatuses,
+
+    /// Time source for timers, shared with every strategy context
+    clock: SharedClock,
 }
 
 impl MultiMarketCoordinator {
@@ -30,25 +108,133 @@ impl MultiMarketCoordinator {
             contexts: HashMap::new(),
             market_subscriptions: HashMap::new(),
             strategy_markets: HashMap::new(),
+            timers: TimerWheel::new(),
+            capital_weights: HashMap::new(),
+            feed_connected: true,
+            mailboxes: HashMap::new(),
+            max_book_updates: DEFAULT_MAX_BOOK_UPDATES,
+            loads: HashMap::new(),
+            paused: HashSet::new(),
+            flags: FeatureFlags::default(),
+            venues: VenueStatuses::default(),
+            clock: system_clock(),
+        }
+    }
+
+    /// Drive timers and strategy contexts from `clock` instead of the
+    /// system clock (e.g. a `MockClock` in tests and backtests)
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        for context in self.contexts.values_mut() {
+            context.set_clock(clock.clone());
+        }
+        self.clock = clock;
+    }
+
+    /// Feature flags consulted by the coordinator and strategy contexts
+    ///
+    /// The handle is shared: flags set through it apply immediately.
+    pub fn feature_flags(&self) -> &FeatureFlags {
+        &self.flags
+    }
+
+    /// Replace the feature flag set, for this coordinator and every
+    /// registered strategy context
+    pub fn set_feature_flags(&mut self, flags: FeatureFlags) {
+        for context in self.contexts.values_mut() {
+            context.flags = flags.clone();
         }
+        self.flags = flags;
+    }
+
+    /// Venue availability seen by strategy contexts
+    ///
+    /// The handle is shared: states set through it apply immediately.
+    pub fn venue_statuses(&self) -> &VenueStatuses {
+        &self.venues
+    }
+
+    /// Bound queued book updates per strategy; older ticks are dropped first
+    pub fn set_max_book_updates(&mut self, max_book_updates: usize) {
+        self.max_book_updates = max_book_updates;
     }
 
     /// Register a strategy with markets
     ///
+    /// Fails with [`StrategyError::RegistrationConflict`], before the
+    /// strategy is initialized, if the id is already registered, a market is
+    /// listed twice or the strategy's metadata declares markets that differ
+    /// from `markets` (an empty declaration accepts any markets).
+    ///
     /// # Arguments
     /// * `strategy_id` - Unique identifier for the strategy
     /// * `strategy` - Strategy implementation
     /// * `context` - Strategy execution context
     /// * `markets` - List of markets to subscribe to
     pub async fn register_strategy(
+        &mut self,
+        strategy_id: String,
+        strategy: Box<dyn Strategy>,
+        context: StrategyContext,
+        markets: Vec<String>,
+    ) -> StrategyResult<()> {
+        if self.strategies.contains_key(&strategy_id) {
+            return Err(RegistrationConflict::DuplicateId { strategy_id }.into());
+        }
+        check_markets(&strategy_id, strategy.as_ref(), &markets)?;
+        self.insert_strategy(strategy_id, strategy, context, markets).await
+    }
+
+    /// Register a strategy in place of the one with the same id
+    ///
+    /// The existing strategy (if any) is unregistered and shut down first;
+    /// its timer and capital weight carry over to the new one. Returns
+    /// whether a strategy was replaced. Markets are checked as in
+    /// [`register_strategy`](Self::register_strategy) before anything is
+    /// removed, but if the new strategy fails to initialize the id is left
+    /// unregistered.
+    pub async fn replace_strategy(
+        &mut self,
+        strategy_id: String,
+        strategy: Box<dyn Strategy>,
+        context: StrategyContext,
+        markets: Vec<String>,
+    ) -> StrategyResult<bool> {
+        check_markets(&strategy_id, strategy.as_ref(), &markets)?;
+
+        let replaced = self.strategies.contains_key(&strategy_id);
+        let timer = self.timers.cancel(&strategy_id);
+        let weight = self.capital_weights.get(&strategy_id).copied();
+        if replaced {
+            if let Err(e) = self.unregister_strategy(&strategy_id).await {
+                warn!(strategy_id = %strategy_id, error = %e, "Replaced strategy failed to shut down");
+            }
+        }
+
+        self.insert_strategy(strategy_id.clone(), strategy, context, markets).await?;
+        if let Some(timer) = timer {
+            self.timers.schedule(&strategy_id, timer, self.clock.now());
+        }
+        if let Some(weight) = weight {
+            self.capital_weights.insert(strategy_id, weight);
+        }
+        Ok(replaced)
+    }
+
+    /// Initialize a strategy and subscribe it to its markets
+    async fn insert_strategy(
         &mut self,
         strategy_id: String,
         mut strategy: Box<dyn Strategy>,
         mut context: StrategyContext,
         markets: Vec<String>,
     ) -> StrategyResult<()> {
-        // Initialize the strategy
-        strategy.initialize(&mut context).await?;
+        // Initialize the strategy; orders stay suppressed during warm-up
+        context.feed_connected = self.feed_connected;
+        context.flags = self.flags.clone();
+        context.venues = self.venues.clone();
+        context.set_clock(self.clock.clone());
+        context.start_warmup(strategy.metadata().warmup_ticks);
+        strategy.initialize(&mut context).instrument(strategy_span(&strategy_id)).await?;
 
         // Register market subscriptions
         for market in &markets {
@@ -62,18 +248,58 @@ impl MultiMarketCoordinator {
         self.strategy_markets.insert(strategy_id.clone(), markets);
 
         // Store strategy and context
+        self.loads.insert(strategy_id.clone(), StrategyLoad::new(Instant::now()));
         self.strategies.insert(strategy_id.clone(), strategy);
         self.contexts.insert(strategy_id.clone(), context);
 
         Ok(())
     }
 
+    /// Register a strategy with its own timer cadence
+    ///
+    /// Same as [`register_strategy`](Self::register_strategy), additionally
+    /// scheduling `on_timer` according to `timer` (see [`fire_due_timers`](Self::fire_due_timers)).
+    pub async fn register_strategy_with_timer(
+        &mut self,
+        strategy_id: String,
+        strategy: Box<dyn Strategy>,
+        context: StrategyContext,
+        markets: Vec<String>,
+        timer: TimerConfig,
+    ) -> StrategyResult<()> {
+        self.register_strategy(strategy_id.clone(), strategy, context, markets).await?;
+        self.timers.schedule(&strategy_id, timer, self.clock.now());
+        Ok(())
+    }
+
+    /// Set or replace the timer for a registered strategy
+    pub fn set_timer(&mut self, strategy_id: &str, timer: TimerConfig) -> StrategyResult<()> {
+        if !self.strategies.contains_key(strategy_id) {
+            return Err(StrategyError::Other(format!("Strategy not found: {}", strategy_id)));
+        }
+        self.timers.schedule(strategy_id, timer, self.clock.now());
+        Ok(())
+    }
+
+    /// Remove a strategy's timer (it will only be reached via `on_timer_all`)
+    pub fn clear_timer(&mut self, strategy_id: &str) -> Option<TimerConfig> {
+        self.timers.cancel(strategy_id)
+    }
+
     /// Unregister a strategy
+    ///
+    /// Events still queued for it are discarded.
     pub async fn unregister_strategy(&mut self, strategy_id: &str) -> StrategyResult<()> {
         // Get strategy's markets
         let markets = self.strategy_markets.remove(strategy_id)
             .ok_or_else(|| StrategyError::Other(format!("Strategy not found: {}", strategy_id)))?;
 
+        self.timers.cancel(strategy_id);
+        self.capital_weights.remove(strategy_id);
+        self.mailboxes.remove(strategy_id);
+        self.loads.remove(strategy_id);
+        self.paused.remove(strategy_id);
+
         // Remove from market subscriptions
         for market in markets {
             if let Some(subs) = self.market_subscriptions.get_mut(&market) {
@@ -89,12 +315,199 @@ impl MultiMarketCoordinator {
             self.strategies.remove(strategy_id),
             self.contexts.remove(strategy_id),
         ) {
-            strategy.shutdown(&mut context).await?;
+            strategy.shutdown(&mut context).instrument(strategy_span(strategy_id)).await?;
         }
 
         Ok(())
     }
 
+    /// Subscribe a registered strategy to an additional market at runtime
+    ///
+    /// Calls [`Strategy::add_market`] and returns `true` if the market had no
+    /// subscribers before, i.e. market data must now be requested for it.
+    /// Adding a market the strategy already trades is a no-op; adding a new
+    /// one fails with [`StrategyError::FeatureDisabled`] while
+    /// [`ENABLE_NEW_MARKET_ONBOARDING`] is off.
+    pub async fn add_market(&mut self, strategy_id: &str, market_id: &str) -> StrategyResult<bool> {
+        let markets = self.strategy_markets.get_mut(strategy_id)
+            .ok_or_else(|| StrategyError::Other(format!("Strategy not found: {}", strategy_id)))?;
+        if markets.iter().any(|m| m == market_id) {
+            return Ok(false);
+        }
+        if !self.flags.is_enabled(ENABLE_NEW_MARKET_ONBOARDING) {
+            return Err(StrategyError::FeatureDisabled(ENABLE_NEW_MARKET_ONBOARDING.to_string()));
+        }
+
+        if let (Some(strategy), Some(context)) = (
+            self.strategies.get_mut(strategy_id),
+            self.contexts.get_mut(strategy_id),
+        ) {
+            strategy.add_market(market_id, context).instrument(strategy_span(strategy_id)).await?;
+        }
+        markets.push(market_id.to_string());
+
+        let subs = self.market_subscriptions.entry(market_id.to_string()).or_default();
+        subs.push(strategy_id.to_string());
+        Ok(subs.len() == 1)
+    }
+
+    /// Unsubscribe a strategy from a market at runtime
+    ///
+    /// Calls [`Strategy::remove_market`], then cancels the strategy's open
+    /// orders in the market and drops its position there if flat. A non-flat
+    /// position is left in the context and reported in the returned
+    /// [`MarketRemoval`] so the caller can unwind it.
+    pub async fn remove_market(&mut self, strategy_id: &str, market_id: &str) -> StrategyResult<MarketRemoval> {
+        let markets = self.strategy_markets.get_mut(strategy_id)
+            .ok_or_else(|| StrategyError::Other(format!("Strategy not found: {}", strategy_id)))?;
+        let mut removal = MarketRemoval {
+            strategy_id: strategy_id.to_string(),
+            market_id: market_id.to_string(),
+            ..Default::default()
+        };
+        if !markets.iter().any(|m| m == market_id) {
+            return Ok(removal);
+        }
+        markets.retain(|m| m != market_id);
+
+        if let Some(subs) = self.market_subscriptions.get_mut(market_id) {
+            subs.retain(|id| id != strategy_id);
+            if subs.is_empty() {
+                self.market_subscriptions.remove(market_id);
+                removal.unsubscribed = true;
+            }
+        }
+
+        if let (Some(strategy), Some(context)) = (
+            self.strategies.get_mut(strategy_id),
+            self.contexts.get_mut(strategy_id),
+        ) {
+            strategy.remove_market(market_id, context).instrument(strategy_span(strategy_id)).await?;
+
+            let order_ids: Vec<OrderId> = context.orders
+                .iter()
+                .filter(|(_, order)| order.market == market_id)
+                .map(|(id, _)| id.clone())
+                .collect();
+            for order_id in order_ids {
+                context.cancel_order(&order_id).await?;
+                removal.cancelled_orders.push(order_id);
+            }
+
+            match context.positions.get(market_id) {
+                Some(position) if position.size.abs() > 1e-8 => {
+                    removal.residual_position = Some(position.clone());
+                }
+                Some(_) => {
+                    context.positions.remove(market_id);
+                }
+                None => {}
+            }
+        }
+
+        Ok(removal)
+    }
+
+    /// Remove a strategy from all its markets and unregister it
+    ///
+    /// Used to stop one strategy during an incident without touching the
+    /// others. Returns one [`MarketRemoval`] per market; residual positions
+    /// are reported there, as the strategy's context is dropped.
+    pub async fn halt_strategy(&mut self, strategy_id: &str) -> StrategyResult<Vec<MarketRemoval>> {
+        let markets = self.strategy_markets.get(strategy_id)
+            .cloned()
+            .ok_or_else(|| StrategyError::Other(format!("Strategy not found: {}", strategy_id)))?;
+
+        let mut removals = Vec::with_capacity(markets.len());
+        for market in &markets {
+            removals.push(self.remove_market(strategy_id, market).await?);
+        }
+        self.unregister_strategy(strategy_id).await?;
+
+        Ok(removals)
+    }
+
+    /// Remove every strategy from a market
+    ///
+    /// The strategies keep running in their other markets. Returns one
+    /// [`MarketRemoval`] per strategy that traded the market.
+    pub async fn halt_market(&mut self, market_id: &str) -> StrategyResult<Vec<MarketRemoval>> {
+        let mut removals = Vec::new();
+        for strategy_id in self.market_strategies(market_id) {
+            removals.push(self.remove_market(&strategy_id, market_id).await?);
+        }
+        Ok(removals)
+    }
+
+    /// Settle every strategy's position in a resolved market and remove the
+    /// market
+    ///
+    /// For each subscribed strategy the position is settled at `payout` per
+    /// share ([`StrategyContext::settle_position`]), then
+    /// [`Strategy::on_market_resolved`] is called and the market is removed
+    /// as in [`remove_market`](Self::remove_market), which cancels orders
+    /// left in it. Returns one [`MarketSettlement`] per strategy.
+    pub async fn resolve_market(&mut self, market_id: &str, payout: f64) -> StrategyResult<Vec<MarketSettlement>> {
+        let mut settlements = Vec::new();
+        for strategy_id in self.market_strategies(market_id) {
+            let mut settled = None;
+            if let (Some(strategy), Some(context)) = (
+                self.strategies.get_mut(&strategy_id),
+                self.contexts.get_mut(&strategy_id),
+            ) {
+                settled = context.settle_position(market_id, payout);
+                strategy
+                    .on_market_resolved(market_id, payout, context)
+                    .instrument(strategy_span(&strategy_id))
+                    .await?;
+            }
+            let removal = self.remove_market(&strategy_id, market_id).await?;
+            settlements.push(MarketSettlement {
+                payout,
+                realized_pnl: settled.as_ref().map_or(0.0, |p| p.size * (payout - p.entry_price)),
+                settled_position: settled.filter(|p| !p.is_flat()),
+                removal,
+            });
+        }
+        Ok(settlements)
+    }
+
+    /// Replace a strategy's market set, adding and removing the difference
+    ///
+    /// Typically driven by a market scanner's universe updates. While
+    /// [`ENABLE_NEW_MARKET_ONBOARDING`] is off, new markets are skipped and
+    /// listed in [`SubscriptionChanges::deferred`] instead.
+    pub async fn set_strategy_markets(
+        &mut self,
+        strategy_id: &str,
+        markets: &[String],
+    ) -> StrategyResult<SubscriptionChanges> {
+        let current = self.strategy_markets.get(strategy_id)
+            .cloned()
+            .ok_or_else(|| StrategyError::Other(format!("Strategy not found: {}", strategy_id)))?;
+
+        let mut changes = SubscriptionChanges::default();
+        for market in current.iter().filter(|m| !markets.contains(m)) {
+            changes.removals.push(self.remove_market(strategy_id, market).await?);
+        }
+        for market in markets.iter().filter(|m| !current.contains(m)) {
+            if !self.flags.is_enabled(ENABLE_NEW_MARKET_ONBOARDING) {
+                changes.deferred.push(market.clone());
+                continue;
+            }
+            if self.add_market(strategy_id, market).await? {
+                changes.subscribed.push(market.clone());
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Markets a strategy is subscribed to
+    pub fn strategy_markets(&self, strategy_id: &str) -> Option<&[String]> {
+        self.strategy_markets.get(strategy_id).map(|m| m.as_slice())
+    }
+
     /// Route market tick to relevant strategies
     pub async fn route_market_tick(
         &mut self,
@@ -109,11 +522,17 @@ impl MultiMarketCoordinator {
 
         // Route to each strategy
         for strategy_id in strategy_ids {
+            if self.paused.contains(&strategy_id) {
+                continue;
+            }
             if let (Some(strategy), Some(context)) = (
                 self.strategies.get_mut(&strategy_id),
                 self.contexts.get_mut(&strategy_id),
             ) {
-                strategy.on_market_tick(market_id, tick, context).await?;
+                let started = Instant::now();
+                let result = deliver_tick(&strategy_id, strategy.as_mut(), context, market_id, tick).await;
+                self.record_load(&strategy_id, started);
+                result?;
             }
         }
 
@@ -121,6 +540,9 @@ impl MultiMarketCoordinator {
     }
 
     /// Route fill to a specific strategy
+    ///
+    /// The fill is applied to the strategy's context (position and order)
+    /// before `on_fill`, unless the strategy manages its own positions.
     pub async fn route_fill(
         &mut self,
         strategy_id: &str,
@@ -132,7 +554,34 @@ impl MultiMarketCoordinator {
         let context = self.contexts.get_mut(strategy_id)
             .ok_or_else(|| StrategyError::Other(format!("Context not found: {}", strategy_id)))?;
 
-        strategy.on_fill(fill, context).await
+        let started = Instant::now();
+        let result = deliver_fill(strategy_id, strategy.as_mut(), context, fill).await;
+        self.record_load(strategy_id, started);
+        result
+    }
+
+    /// Set a strategy's position in a market, e.g. one imported from the
+    /// venue on startup
+    ///
+    /// Replaces the context's position without calling `on_fill`; `price`
+    /// becomes the entry price of the difference.
+    pub fn set_position(&mut self, strategy_id: &str, market_id: &str, size: f64, price: f64) -> StrategyResult<()> {
+        let context = self.contexts.get_mut(strategy_id)
+            .ok_or_else(|| StrategyError::Other(format!("Context not found: {}", strategy_id)))?;
+
+        let current = context.get_position(market_id).map(|p| p.size).unwrap_or(0.0);
+        context.update_position(market_id, size - current, price);
+        Ok(())
+    }
+
+    /// Tag every order a strategy submits from now on (tags the strategy
+    /// sets itself take precedence)
+    pub fn set_order_tag(&mut self, strategy_id: &str, key: &str, value: &str) -> StrategyResult<()> {
+        let context = self.contexts.get_mut(strategy_id)
+            .ok_or_else(|| StrategyError::Other(format!("Context not found: {}", strategy_id)))?;
+
+        context.order_tags.insert(key.to_string(), value.to_string());
+        Ok(())
     }
 
     /// Route cancellation to a specific strategy
@@ -147,25 +596,181 @@ impl MultiMarketCoordinator {
         let context = self.contexts.get_mut(strategy_id)
             .ok_or_else(|| StrategyError::Other(format!("Context not found: {}", strategy_id)))?;
 
-        strategy.on_cancel(order_id, context).await
+        let started = Instant::now();
+        let result = strategy.on_cancel(order_id, context).instrument(strategy_span(strategy_id)).await;
+        self.record_load(strategy_id, started);
+        result
     }
 
     /// Call timer callback for all strategies
     pub async fn on_timer_all(&mut self) -> StrategyResult<()> {
-        let strategy_ids: Vec<String> = self.strategies.keys().cloned().collect();
+        let strategy_ids: Vec<String> =
+            self.strategies.keys().filter(|id| !self.paused.contains(*id)).cloned().collect();
 
         for strategy_id in strategy_ids {
             if let (Some(strategy), Some(context)) = (
                 self.strategies.get_mut(&strategy_id),
                 self.contexts.get_mut(&strategy_id),
             ) {
-                strategy.on_timer(context).await?;
+                let started = Instant::now();
+                let result = strategy.on_timer(context).instrument(strategy_span(&strategy_id)).await;
+                self.record_load(&strategy_id, started);
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fire `on_timer` for every strategy whose timer is due at `now`
+    ///
+    /// Returns the IDs of the strategies that fired. Drive this from the
+    /// event loop, sleeping until [`next_timer_deadline`](Self::next_timer_deadline).
+    pub async fn fire_due_timers(&mut self, now: DateTime<Utc>) -> StrategyResult<Vec<String>> {
+        let mut due = self.timers.due(now);
+        due.retain(|id| !self.paused.contains(id));
+
+        for strategy_id in &due {
+            if let (Some(strategy), Some(context)) = (
+                self.strategies.get_mut(strategy_id),
+                self.contexts.get_mut(strategy_id),
+            ) {
+                let started = Instant::now();
+                let result = strategy.on_timer(context).instrument(strategy_span(strategy_id)).await;
+                self.record_load(strategy_id, started);
+                result?;
             }
         }
 
+        Ok(due)
+    }
+
+    /// Earliest pending strategy timer deadline
+    pub fn next_timer_deadline(&mut self) -> Option<DateTime<Utc>> {
+        self.timers.next_deadline()
+    }
+
+    /// Queue a market tick for every strategy subscribed to the market
+    pub fn enqueue_market_tick(&mut self, market_id: &str, tick: &MarketTick) {
+        let Some(strategy_ids) = self.market_subscriptions.get(market_id) else {
+            return;
+        };
+        for strategy_id in strategy_ids.iter().filter(|id| !self.paused.contains(*id)) {
+            self.mailboxes
+                .entry(strategy_id.clone())
+                .or_insert_with(|| StrategyMailbox::new(self.max_book_updates))
+                .push(StrategyEvent::MarketTick { market_id: market_id.to_string(), tick: tick.clone() });
+        }
+    }
+
+    /// Queue a fill for a strategy
+    pub fn enqueue_fill(&mut self, strategy_id: &str, fill: Fill) -> StrategyResult<()> {
+        self.mailbox(strategy_id)?.push(StrategyEvent::Fill(fill));
+        Ok(())
+    }
+
+    /// Queue a cancellation for a strategy
+    pub fn enqueue_cancel(&mut self, strategy_id: &str, order_id: OrderId) -> StrategyResult<()> {
+        self.mailbox(strategy_id)?.push(StrategyEvent::Cancel(order_id));
         Ok(())
     }
 
+    /// Queue `on_timer` for every strategy whose timer is due at `now`
+    ///
+    /// Returns the IDs of the strategies queued.
+    pub fn enqueue_due_timers(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut due = self.timers.due(now);
+        due.retain(|id| !self.paused.contains(id));
+        for strategy_id in &due {
+            if let Ok(mailbox) = self.mailbox(strategy_id) {
+                mailbox.push(StrategyEvent::Timer);
+            }
+        }
+        due
+    }
+
+    /// Handle every queued event, each strategy's in priority order
+    ///
+    /// Returns the number of events handled. On a strategy error the
+    /// remaining events stay queued.
+    pub async fn dispatch_pending(&mut self) -> StrategyResult<usize> {
+        let strategy_ids: Vec<String> = self
+            .mailboxes
+            .iter()
+            .filter(|(_, mailbox)| !mailbox.is_empty())
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let mut dispatched = 0;
+        for strategy_id in strategy_ids {
+            while let Some(event) = self.mailboxes.get_mut(&strategy_id).and_then(|m| m.pop()) {
+                let (Some(strategy), Some(context)) = (
+                    self.strategies.get_mut(&strategy_id),
+                    self.contexts.get_mut(&strategy_id),
+                ) else {
+                    break;
+                };
+                let strategy = strategy.as_mut();
+                let started = Instant::now();
+                let result = match event {
+                    StrategyEvent::Fill(fill) => deliver_fill(&strategy_id, strategy, context, &fill).await,
+                    StrategyEvent::Cancel(order_id) => {
+                        strategy.on_cancel(&order_id, context).instrument(strategy_span(&strategy_id)).await
+                    }
+                    StrategyEvent::MarketTick { market_id, tick } => {
+                        deliver_tick(&strategy_id, strategy, context, &market_id, &tick).await
+                    }
+                    StrategyEvent::Timer => strategy.on_timer(context).instrument(strategy_span(&strategy_id)).await,
+                };
+                self.record_load(&strategy_id, started);
+                result?;
+                dispatched += 1;
+            }
+        }
+
+        Ok(dispatched)
+    }
+
+    /// Events queued for a strategy
+    pub fn pending_events(&self, strategy_id: &str) -> usize {
+        self.mailboxes.get(strategy_id).map_or(0, |m| m.len())
+    }
+
+    /// Book updates dropped from a strategy's full queue
+    pub fn dropped_book_updates(&self, strategy_id: &str) -> u64 {
+        self.mailboxes.get(strategy_id).map_or(0, |m| m.dropped_book_updates())
+    }
+
+    /// Event loop load of a strategy since the last metrics drain
+    pub fn strategy_load(&self, strategy_id: &str) -> Option<&StrategyLoad> {
+        self.loads.get(strategy_id)
+    }
+
+    /// Utilization of every strategy since the last metrics drain
+    pub fn loop_utilization(&self) -> HashMap<String, f64> {
+        let now = Instant::now();
+        self.loads.iter().map(|(id, load)| (id.clone(), load.utilization(now))).collect()
+    }
+
+    /// Time a handled event against the strategy's load, with the events
+    /// still queued behind it
+    fn record_load(&mut self, strategy_id: &str, started: Instant) {
+        let queued = self.mailboxes.get(strategy_id).map_or(0, |m| m.len());
+        if let Some(load) = self.loads.get_mut(strategy_id) {
+            load.record(started.elapsed(), queued);
+        }
+    }
+
+    fn mailbox(&mut self, strategy_id: &str) -> StrategyResult<&mut StrategyMailbox> {
+        if !self.strategies.contains_key(strategy_id) {
+            return Err(StrategyError::Other(format!("Strategy not found: {}", strategy_id)));
+        }
+        Ok(self
+            .mailboxes
+            .entry(strategy_id.to_string())
+            .or_insert_with(|| StrategyMailbox::new(self.max_book_updates)))
+    }
+
     /// Get cross-market positions
     ///
     /// Returns all positions grouped by strategy
@@ -208,6 +813,91 @@ impl MultiMarketCoordinator {
         }
     }
 
+    /// Set a strategy's relative share of free capital (default 1.0)
+    pub fn set_capital_weight(&mut self, strategy_id: &str, weight: f64) -> StrategyResult<()> {
+        if !self.contexts.contains_key(strategy_id) {
+            return Err(StrategyError::Other(format!("Strategy not found: {}", strategy_id)));
+        }
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(StrategyError::InvalidParameter(format!(
+                "Capital weight for {} must be non-negative, got {}",
+                strategy_id, weight
+            )));
+        }
+        self.capital_weights.insert(strategy_id.to_string(), weight);
+        Ok(())
+    }
+
+    /// Split free capital across strategies in proportion to their weights
+    ///
+    /// Sets each context's `allocated_capital` and returns the allocations.
+    pub fn allocate_capital(&mut self, free_capital: f64) -> HashMap<String, f64> {
+        let free_capital = free_capital.max(0.0);
+        let weight = |id: &str| self.capital_weights.get(id).copied().unwrap_or(1.0);
+        let total_weight: f64 = self.contexts.keys().map(|id| weight(id)).sum();
+
+        let allocations: HashMap<String, f64> = self
+            .contexts
+            .keys()
+            .map(|id| {
+                let share = if total_weight > 0.0 {
+                    weight(id) / total_weight
+                } else {
+                    0.0
+                };
+                (id.clone(), free_capital * share)
+            })
+            .collect();
+
+        for (id, amount) in &allocations {
+            if let Some(context) = self.contexts.get_mut(id) {
+                context.allocated_capital = Some(*amount);
+            }
+        }
+        allocations
+    }
+
+    /// Split free capital by target market weights (e.g. from
+    /// `ag_risk::advanced::PortfolioOptimizer`)
+    ///
+    /// Each market's target value (`weight * free_capital`, signed) is split
+    /// across the strategies subscribed to it in proportion to their capital
+    /// weights. Every context gets its share in `target_exposures` and the
+    /// gross of it in `allocated_capital`. Returns the allocations.
+    pub fn allocate_by_weights(&mut self, free_capital: f64, weights: &HashMap<String, f64>) -> HashMap<String, f64> {
+        let free_capital = free_capital.max(0.0);
+        let weight = |id: &str| self.capital_weights.get(id).copied().unwrap_or(1.0);
+
+        let mut targets: HashMap<String, HashMap<String, f64>> =
+            self.contexts.keys().map(|id| (id.clone(), HashMap::new())).collect();
+        for (market, market_weight) in weights {
+            let Some(subscribers) = self.market_subscriptions.get(market) else {
+                continue;
+            };
+            let total_weight: f64 = subscribers.iter().map(|id| weight(id)).sum();
+            if total_weight <= 0.0 {
+                continue;
+            }
+            for id in subscribers {
+                if let Some(exposures) = targets.get_mut(id) {
+                    let value = market_weight * free_capital * weight(id) / total_weight;
+                    exposures.insert(market.clone(), value);
+                }
+            }
+        }
+
+        let mut allocations = HashMap::new();
+        for (id, exposures) in targets {
+            let amount: f64 = exposures.values().map(|v| v.abs()).sum();
+            if let Some(context) = self.contexts.get_mut(&id) {
+                context.allocated_capital = Some(amount);
+                context.target_exposures = exposures;
+            }
+            allocations.insert(id, amount);
+        }
+        allocations
+    }
+
     /// Get number of registered strategies
     pub fn strategy_count(&self) -> usize {
         self.strategies.len()
@@ -218,10 +908,128 @@ impl MultiMarketCoordinator {
         self.strategies.keys().cloned().collect()
     }
 
+    /// Stop delivering market ticks and timers to a strategy
+    ///
+    /// Fills and cancellations are still delivered, so its positions and
+    /// orders stay in sync; its resting orders are left alone. Queued ticks
+    /// and timers are discarded. Returns `false` if it was already paused.
+    pub fn pause_strategy(&mut self, strategy_id: &str) -> StrategyResult<bool> {
+        if !self.strategies.contains_key(strategy_id) {
+            return Err(StrategyError::Other(format!("Strategy not found: {}", strategy_id)));
+        }
+        if let Some(mailbox) = self.mailboxes.get_mut(strategy_id) {
+            mailbox.discard(EventPriority::BookUpdate);
+            mailbox.discard(EventPriority::Timer);
+        }
+        Ok(self.paused.insert(strategy_id.to_string()))
+    }
+
+    /// Deliver market ticks and timers to a paused strategy again
+    ///
+    /// Returns `false` if it was not paused.
+    pub fn resume_strategy(&mut self, strategy_id: &str) -> StrategyResult<bool> {
+        if !self.strategies.contains_key(strategy_id) {
+            return Err(StrategyError::Other(format!("Strategy not found: {}", strategy_id)));
+        }
+        Ok(self.paused.remove(strategy_id))
+    }
+
+    /// Whether a strategy is paused
+    pub fn is_paused(&self, strategy_id: &str) -> bool {
+        self.paused.contains(strategy_id)
+    }
+
+    /// IDs of paused strategies
+    pub fn paused_strategies(&self) -> Vec<String> {
+        self.paused.iter().cloned().collect()
+    }
+
     /// Get strategy context (immutable)
     pub fn get_context(&self, strategy_id: &str) -> Option<&StrategyContext> {
         self.contexts.get(strategy_id)
     }
+
+    /// Strategies subscribed to a market
+    pub fn market_strategies(&self, market_id: &str) -> Vec<String> {
+        self.market_subscriptions.get(market_id).cloned().unwrap_or_default()
+    }
+
+    /// Report whether the market data feed is live
+    ///
+    /// While disconnected every context refuses new orders
+    /// ([`StrategyError::FeedDisconnected`]); strategies can also check
+    /// `ctx.feed_connected` to pause quoting logic.
+    pub fn set_feed_connected(&mut self, connected: bool) {
+        self.feed_connected = connected;
+        for context in self.contexts.values_mut() {
+            context.feed_connected = connected;
+        }
+    }
+
+    /// Whether the market data feed is live
+    pub fn feed_connected(&self) -> bool {
+        self.feed_connected
+    }
+
+    /// Get all markets with at least one subscribed strategy
+    pub fn subscribed_markets(&self) -> Vec<String> {
+        self.market_subscriptions.keys().cloned().collect()
+    }
+
+    /// IDs of strategies still warming up
+    pub fn warming_up(&self) -> Vec<String> {
+        self.contexts.iter().filter(|(_, c)| c.is_warming_up()).map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Take buffered metrics from every strategy context, plus the warm-up
+    /// gauge of strategies still warming up and each strategy's event loop
+    /// load (which starts a new load window)
+    pub fn drain_metrics(&mut self) -> Vec<StrategyMetric> {
+        let mut metrics = Vec::new();
+        for context in self.contexts.values_mut() {
+            if context.is_warming_up() {
+                context.push_warmup_metric();
+            }
+            metrics.extend_from_slice(context.get_metrics_buffer());
+            context.clear_metrics_buffer();
+        }
+        let now = Instant::now();
+        for (strategy_id, load) in self.loads.iter_mut() {
+            metrics.extend(load.metrics(strategy_id, now));
+            load.reset(now);
+        }
+        metrics
+    }
+}
+
+/// Count the tick toward warm-up and hand it to the strategy
+async fn deliver_tick(
+    strategy_id: &str,
+    strategy: &mut dyn Strategy,
+    context: &mut StrategyContext,
+    market_id: &str,
+    tick: &MarketTick,
+) -> StrategyResult<()> {
+    if context.record_warmup_tick() {
+        tracing::info!(strategy_id = %strategy_id, "Warm-up complete");
+    }
+    strategy.on_market_tick(market_id, tick, context).instrument(strategy_span(strategy_id)).await
+}
+
+/// Apply the fill to the context (unless the strategy manages positions)
+/// and hand it to the strategy
+async fn deliver_fill(
+    strategy_id: &str,
+    strategy: &mut dyn Strategy,
+    context: &mut StrategyContext,
+    fill: &Fill,
+) -> StrategyResult<()> {
+    if strategy.manages_positions() {
+        context.record_order_fill(fill);
+    } else {
+        context.apply_fill(fill);
+    }
+    strategy.on_fill(fill, context).instrument(strategy_span(strategy_id)).await
 }
 
 impl Default for MultiMarketCoordinator {
@@ -246,10 +1054,75 @@ pub struct CrossMarketExposure {
     pub positions_by_market: HashMap<String, f64>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{StrategyMetadata, StrategyParams};
+/// Outcome of unsubscribing a strategy from a market
+#[derive(Debug, Clone, Default)]
+pub struct MarketRemoval {
+    /// Strategy that was unsubscribed
+    pub strategy_id: String,
+
+    /// Market that was removed
+    pub market_id: String,
+
+    /// Strategy orders cancelled in the market
+    pub cancelled_orders: Vec<OrderId>,
+
+    /// Non-flat position still held in the market
+    pub residual_position: Option<Position>,
+
+    /// Whether no strategy is subscribed to the market anymore
+    pub unsubscribed: bool,
+}
+
+/// Outcome of settling a strategy in a resolved market, see
+/// [`MultiMarketCoordinator::resolve_market`]
+#[derive(Debug, Clone)]
+pub struct MarketSettlement {
+    /// Payout per share the position was settled at
+    pub payout: f64,
+
+    /// Position before settlement (`None` if flat)
+    pub settled_position: Option<Position>,
+
+    /// PnL realized by the settlement
+    pub realized_pnl: f64,
+
+    /// Removal of the strategy from the market
+    pub removal: MarketRemoval,
+}
+
+/// Subscription changes produced by [`MultiMarketCoordinator::set_strategy_markets`]
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionChanges {
+    /// Markets that gained their first subscriber
+    pub subscribed: Vec<String>,
+
+    /// Per-market removal results
+    pub removals: Vec<MarketRemoval>,
+
+    /// New markets not added because onboarding is disabled
+    pub deferred: Vec<String>,
+}
+
+impl SubscriptionChanges {
+    /// Markets that lost their last subscriber
+    pub fn unsubscribed(&self) -> Vec<String> {
+        self.removals
+            .iter()
+            .filter(|r| r.unsubscribed)
+            .map(|r| r.market_id.clone())
+            .collect()
+    }
+
+    /// Whether nothing changed for the market data feed
+    pub fn is_empty(&self) -> bool {
+        self.subscribed.is_empty() && self.removals.iter().all(|r| !r.unsubscribed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StrategyMetadata, StrategyParams};
     use crate::types::{MarketTick, Side};
     use async_trait::async_trait;
     use chrono::Utc;
@@ -261,6 +1134,58 @@ mod tests {
         ticks_received: usize,
     }
 
+    struct TimerCountStrategy {
+        timer_calls: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Strategy for TimerCountStrategy {
+        async fn initialize(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_market_tick(
+            &mut self,
+            _market_id: &str,
+            _tick: &MarketTick,
+            _ctx: &mut StrategyContext,
+        ) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_fill(&mut self, _fill: &Fill, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_cancel(
+            &mut self,
+            _order_id: &OrderId,
+            _ctx: &mut StrategyContext,
+        ) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_timer(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            self.timer_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn shutdown(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        fn metadata(&self) -> StrategyMetadata {
+            StrategyMetadata {
+                name: "TimerCountStrategy".to_string(),
+                version: "1.0.0".to_string(),
+                description: "Test".to_string(),
+                markets: vec![],
+                required_params: vec![],
+                warmup_ticks: 0,
+            }
+        }
+    }
+
     #[async_trait]
     impl Strategy for TestStrategy {
         async fn initialize(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
@@ -308,6 +1233,121 @@ mod tests {
                 description: "Test".to_string(),
                 markets: vec![],
                 required_params: vec![],
+                warmup_ticks: 0,
+            }
+        }
+    }
+
+    /// Declares a warm-up and tries to buy on every tick
+    struct WarmupStrategy {
+        warmup_ticks: usize,
+        orders_placed: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Strategy for WarmupStrategy {
+        async fn initialize(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_market_tick(
+            &mut self,
+            market_id: &str,
+            _tick: &MarketTick,
+            ctx: &mut StrategyContext,
+        ) -> StrategyResult<()> {
+            if ctx.is_warming_up() {
+                return Ok(());
+            }
+            let order = crate::types::Order {
+                market: market_id.to_string(),
+                side: Side::Buy,
+                price: Some(0.5),
+                size: 1.0,
+                ..Default::default()
+            };
+            ctx.submit_order(order).await?;
+            self.orders_placed.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn on_fill(&mut self, _fill: &Fill, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_cancel(&mut self, _order_id: &OrderId, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_timer(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn shutdown(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        fn metadata(&self) -> StrategyMetadata {
+            StrategyMetadata {
+                name: "WarmupStrategy".to_string(),
+                version: "1.0.0".to_string(),
+                description: "Test".to_string(),
+                markets: vec![],
+                required_params: vec![],
+                warmup_ticks: self.warmup_ticks,
+            }
+        }
+    }
+
+    /// Records the events it handles, with its position at each tick
+    struct RecordingStrategy {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Strategy for RecordingStrategy {
+        async fn initialize(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        async fn on_market_tick(
+            &mut self,
+            market_id: &str,
+            tick: &MarketTick,
+            ctx: &mut StrategyContext,
+        ) -> StrategyResult<()> {
+            let position = ctx.get_position(market_id).map_or(0.0, |p| p.size);
+            self.events.lock().push(format!("tick {} pos {}", tick.bid.unwrap_or_default(), position));
+            Ok(())
+        }
+
+        async fn on_fill(&mut self, fill: &Fill, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            self.events.lock().push(format!("fill {}", fill.order_id));
+            Ok(())
+        }
+
+        async fn on_cancel(&mut self, order_id: &OrderId, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            self.events.lock().push(format!("cancel {}", order_id));
+            Ok(())
+        }
+
+        async fn on_timer(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            self.events.lock().push("timer".to_string());
+            Ok(())
+        }
+
+        async fn shutdown(&mut self, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+            Ok(())
+        }
+
+        fn metadata(&self) -> StrategyMetadata {
+            StrategyMetadata {
+                name: "RecordingStrategy".to_string(),
+                version: "1.0.0".to_string(),
+                description: "Test".to_string(),
+                markets: vec![],
+                required_params: vec![],
+                warmup_ticks: 0,
             }
         }
     }
@@ -365,6 +1405,7 @@ policies:
             ask_size: Some(10.0),
             last: Some(100.5),
             volume_24h: Some(1000.0),
+            sequence: None,
         };
 
         coordinator.route_market_tick("market1", &tick).await.unwrap();
@@ -392,4 +1433,554 @@ policies:
 
         assert_eq!(coordinator.strategy_count(), 0);
     }
+
+    #[tokio::test]
+    async fn test_registration_conflicts() {
+        use crate::r#impl::{MarketMakerConfig, MarketMakerStrategy};
+        use std::time::Duration;
+
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.register_strategy_with_timer(
+            "test1".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test1"),
+            vec!["market1".to_string()],
+            TimerConfig::every(Duration::from_secs(1)),
+        ).await.unwrap();
+        coordinator.set_capital_weight("test1", 2.0).unwrap();
+
+        let err = coordinator.register_strategy(
+            "test1".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test1"),
+            vec!["market2".to_string()],
+        ).await.unwrap_err();
+        assert!(matches!(
+            err,
+            StrategyError::RegistrationConflict(RegistrationConflict::DuplicateId { ref strategy_id })
+                if strategy_id == "test1"
+        ));
+        assert_eq!(coordinator.market_strategies("market2"), Vec::<String>::new());
+
+        let err = coordinator.register_strategy(
+            "test2".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test2"),
+            vec!["market1".to_string(), "market1".to_string()],
+        ).await.unwrap_err();
+        assert!(matches!(
+            err,
+            StrategyError::RegistrationConflict(RegistrationConflict::DuplicateMarket { .. })
+        ));
+
+        // The market maker declares the one market it quotes
+        let mm = MarketMakerStrategy::new("market1".to_string(), MarketMakerConfig::default());
+        let err = coordinator.register_strategy(
+            "mm".to_string(),
+            Box::new(mm),
+            create_test_context("mm"),
+            vec!["market2".to_string(), "market3".to_string()],
+        ).await.unwrap_err();
+        match err {
+            StrategyError::RegistrationConflict(RegistrationConflict::MarketMismatch { missing, undeclared, .. }) => {
+                assert_eq!(missing, vec!["market1".to_string()]);
+                assert_eq!(undeclared, vec!["market2".to_string(), "market3".to_string()]);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(coordinator.strategy_count(), 1);
+
+        // Replacing keeps the timer and weight and moves the subscriptions
+        let replaced = coordinator.replace_strategy(
+            "test1".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test1"),
+            vec!["market2".to_string()],
+        ).await.unwrap();
+        assert!(replaced);
+        assert_eq!(coordinator.strategy_count(), 1);
+        assert_eq!(coordinator.subscribed_markets(), vec!["market2".to_string()]);
+        assert!(coordinator.next_timer_deadline().is_some());
+
+        let replaced = coordinator.replace_strategy(
+            "test2".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test2"),
+            vec!["market2".to_string()],
+        ).await.unwrap();
+        assert!(!replaced);
+        assert_eq!(coordinator.market_strategies("market2").len(), 2);
+        assert!((coordinator.allocate_capital(90.0)["test1"] - 60.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_per_strategy_timers() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let mut coordinator = MultiMarketCoordinator::new();
+        let fast_calls = Arc::new(AtomicUsize::new(0));
+        let slow_calls = Arc::new(AtomicUsize::new(0));
+
+        coordinator.register_strategy_with_timer(
+            "fast".to_string(),
+            Box::new(TimerCountStrategy { timer_calls: fast_calls.clone() }),
+            create_test_context("fast"),
+            vec!["market1".to_string()],
+            TimerConfig::every(Duration::from_millis(100)),
+        ).await.unwrap();
+
+        coordinator.register_strategy_with_timer(
+            "slow".to_string(),
+            Box::new(TimerCountStrategy { timer_calls: slow_calls.clone() }),
+            create_test_context("slow"),
+            vec!["market1".to_string()],
+            TimerConfig::every(Duration::from_secs(3600)),
+        ).await.unwrap();
+
+        let fired = coordinator
+            .fire_due_timers(Utc::now() + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+
+        assert_eq!(fired, vec!["fast".to_string()]);
+        assert_eq!(fast_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(slow_calls.load(Ordering::SeqCst), 0);
+
+        coordinator.unregister_strategy("fast").await.unwrap();
+        let next = coordinator.next_timer_deadline().unwrap();
+        assert!(next > Utc::now() + chrono::Duration::minutes(59));
+    }
+
+    #[tokio::test]
+    async fn test_route_fill_applies_position() {
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.register_strategy(
+            "test1".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test1"),
+            vec!["market1".to_string()],
+        ).await.unwrap();
+
+        let fill = Fill {
+            order_id: "order_1".to_string(),
+            market: "market1".to_string(),
+            price: 0.4,
+            size: 25.0,
+            side: Side::Buy,
+            fee: 0.0,
+            timestamp: Utc::now(),
+        };
+        coordinator.route_fill("test1", &fill).await.unwrap();
+
+        let context = coordinator.get_context("test1").unwrap();
+        assert_eq!(context.get_position("market1").unwrap().size, 25.0);
+
+        // Seeding replaces the position rather than adding to it
+        coordinator.set_position("test1", "market1", -10.0, 0.5).unwrap();
+        let context = coordinator.get_context("test1").unwrap();
+        assert_eq!(context.get_position("market1").unwrap().size, -10.0);
+        assert!(coordinator.set_position("missing", "market1", 1.0, 0.5).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_market_subscriptions() {
+        use crate::types::Order;
+
+        let mut coordinator = MultiMarketCoordinator::new();
+        let mut context = create_test_context("test1");
+        let order_id = context.submit_order(Order {
+            market: "market1".to_string(),
+            price: Some(0.5),
+            size: 10.0,
+            ..Default::default()
+        }).await.unwrap();
+        context.update_position("market1", 5.0, 0.5);
+
+        coordinator.register_strategy(
+            "test1".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            context,
+            vec!["market1".to_string()],
+        ).await.unwrap();
+
+        assert!(coordinator.add_market("test1", "market2").await.unwrap());
+        assert!(!coordinator.add_market("test1", "market2").await.unwrap());
+
+        let changes = coordinator
+            .set_strategy_markets("test1", &["market2".to_string(), "market3".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(changes.subscribed, vec!["market3".to_string()]);
+        assert_eq!(changes.unsubscribed(), vec!["market1".to_string()]);
+
+        let removal = &changes.removals[0];
+        assert_eq!(removal.cancelled_orders, vec![order_id]);
+        assert_eq!(removal.residual_position.as_ref().unwrap().size, 5.0);
+
+        let mut markets = coordinator.subscribed_markets();
+        markets.sort();
+        assert_eq!(markets, vec!["market2".to_string(), "market3".to_string()]);
+        assert!(coordinator.get_context("test1").unwrap().orders.is_empty());
+        assert!(coordinator.add_market("missing", "market1").await.is_err());
+
+        // With onboarding disabled, existing markets stay and new ones are deferred
+        coordinator.feature_flags().set(crate::flags::ENABLE_NEW_MARKET_ONBOARDING, false);
+        assert!(matches!(
+            coordinator.add_market("test1", "market4").await,
+            Err(StrategyError::FeatureDisabled(_))
+        ));
+        let changes = coordinator
+            .set_strategy_markets("test1", &["market3".to_string(), "market4".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(changes.deferred, vec!["market4".to_string()]);
+        assert_eq!(changes.unsubscribed(), vec!["market2".to_string()]);
+        assert_eq!(coordinator.strategy_markets("test1").unwrap(), &["market3".to_string()]);
+        assert!(!coordinator.get_context("test1").unwrap().flags.is_enabled("enable_new_market_onboarding"));
+
+        // Venue states reach registered contexts through the shared handle
+        coordinator.venue_statuses().set("polymarket", crate::VenueState::Down);
+        assert_eq!(coordinator.get_context("test1").unwrap().venues.state("polymarket"), crate::VenueState::Down);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_market_settles_positions() {
+        use crate::types::Order;
+
+        let mut coordinator = MultiMarketCoordinator::new();
+        let mut context = create_test_context("test1");
+        let order_id = context.submit_order(Order {
+            market: "market1".to_string(),
+            price: Some(0.5),
+            size: 10.0,
+            ..Default::default()
+        }).await.unwrap();
+        context.update_position("market1", 20.0, 0.4);
+        coordinator.register_strategy(
+            "test1".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            context,
+            vec!["market1".to_string(), "market2".to_string()],
+        ).await.unwrap();
+        coordinator.register_strategy(
+            "test2".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("test2"),
+            vec!["market1".to_string()],
+        ).await.unwrap();
+
+        let settlements = coordinator.resolve_market("market1", 1.0).await.unwrap();
+        assert_eq!(settlements.len(), 2);
+
+        let first = settlements.iter().find(|s| s.removal.strategy_id == "test1").unwrap();
+        assert_eq!(first.settled_position.as_ref().unwrap().size, 20.0);
+        assert!((first.realized_pnl - 12.0).abs() < 1e-9);
+        assert_eq!(first.removal.cancelled_orders, vec![order_id]);
+        assert!(first.removal.residual_position.is_none());
+
+        let second = settlements.iter().find(|s| s.removal.strategy_id == "test2").unwrap();
+        assert!(second.settled_position.is_none());
+        assert_eq!(second.realized_pnl, 0.0);
+        assert!(second.removal.unsubscribed);
+
+        assert_eq!(coordinator.subscribed_markets(), vec!["market2".to_string()]);
+        assert!(coordinator.get_context("test1").unwrap().get_position("market1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_halt_strategy_and_market() {
+        use crate::types::Order;
+
+        let mut coordinator = MultiMarketCoordinator::new();
+        for id in ["mm", "arb"] {
+            let mut context = create_test_context(id);
+            context.submit_order(Order {
+                market: "market1".to_string(),
+                price: Some(0.5),
+                size: 10.0,
+                ..Default::default()
+            }).await.unwrap();
+            context.update_position("market1", 5.0, 0.5);
+            coordinator.register_strategy(
+                id.to_string(),
+                Box::new(TestStrategy { ticks_received: 0 }),
+                context,
+                vec!["market1".to_string(), format!("{}_only", id)],
+            ).await.unwrap();
+        }
+
+        // Halting one strategy leaves the shared market to the other
+        let removals = coordinator.halt_strategy("mm").await.unwrap();
+        assert_eq!(removals.len(), 2);
+        assert!(removals.iter().all(|r| r.strategy_id == "mm"));
+        assert!(!removals[0].unsubscribed);
+        assert_eq!(removals[0].cancelled_orders.len(), 1);
+        assert_eq!(removals[0].residual_position.as_ref().unwrap().size, 5.0);
+        assert!(removals[1].unsubscribed);
+        assert_eq!(coordinator.strategy_ids(), vec!["arb".to_string()]);
+        assert!(coordinator.halt_strategy("mm").await.is_err());
+
+        // Halting a market keeps its strategies running elsewhere
+        let removals = coordinator.halt_market("market1").await.unwrap();
+        assert_eq!(removals.len(), 1);
+        assert_eq!(removals[0].strategy_id, "arb");
+        assert!(removals[0].unsubscribed);
+        assert_eq!(coordinator.strategy_markets("arb").unwrap(), &["arb_only".to_string()]);
+        assert!(coordinator.halt_market("market1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_allocate_capital() {
+        let mut coordinator = MultiMarketCoordinator::new();
+        for id in ["mm", "arb"] {
+            coordinator.register_strategy(
+                id.to_string(),
+                Box::new(TestStrategy { ticks_received: 0 }),
+                create_test_context(id),
+                vec!["market1".to_string()],
+            ).await.unwrap();
+        }
+
+        coordinator.set_capital_weight("mm", 3.0).unwrap();
+        assert!(coordinator.set_capital_weight("arb", -1.0).is_err());
+        assert!(coordinator.set_capital_weight("missing", 1.0).is_err());
+
+        let allocations = coordinator.allocate_capital(1000.0);
+        assert_eq!(allocations["mm"], 750.0);
+        assert_eq!(allocations["arb"], 250.0);
+        assert_eq!(coordinator.get_context("arb").unwrap().allocated_capital, Some(250.0));
+
+        // Negative free capital (over-committed) allocates nothing
+        let allocations = coordinator.allocate_capital(-50.0);
+        assert_eq!(allocations["mm"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_allocate_by_weights() {
+        let mut coordinator = MultiMarketCoordinator::new();
+        for (id, markets) in [("mm", vec!["market1", "market2"]), ("arb", vec!["market1"])] {
+            coordinator.register_strategy(
+                id.to_string(),
+                Box::new(TestStrategy { ticks_received: 0 }),
+                create_test_context(id),
+                markets.into_iter().map(String::from).collect(),
+            ).await.unwrap();
+        }
+        coordinator.set_capital_weight("mm", 3.0).unwrap();
+
+        let weights = HashMap::from([
+            ("market1".to_string(), 0.4),
+            ("market2".to_string(), -0.1),
+            ("unsubscribed".to_string(), 0.5),
+        ]);
+        let allocations = coordinator.allocate_by_weights(1000.0, &weights);
+        assert!((allocations["mm"] - 400.0).abs() < 1e-9);
+        assert!((allocations["arb"] - 100.0).abs() < 1e-9);
+
+        let mm = coordinator.get_context("mm").unwrap();
+        assert!((mm.target_exposures["market1"] - 300.0).abs() < 1e-9);
+        assert!((mm.target_exposures["market2"] + 100.0).abs() < 1e-9);
+        assert_eq!(coordinator.get_context("arb").unwrap().allocated_capital, Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_feed_connection_propagates() {
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.register_strategy(
+            "mm".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("mm"),
+            vec!["market1".to_string()],
+        ).await.unwrap();
+
+        coordinator.set_feed_connected(false);
+        assert!(!coordinator.get_context("mm").unwrap().feed_connected);
+
+        // Strategies registered while disconnected start disconnected
+        coordinator.register_strategy(
+            "arb".to_string(),
+            Box::new(TestStrategy { ticks_received: 0 }),
+            create_test_context("arb"),
+            vec!["market1".to_string()],
+        ).await.unwrap();
+        assert!(!coordinator.get_context("arb").unwrap().feed_connected);
+
+        coordinator.set_feed_connected(true);
+        assert!(coordinator.get_context("arb").unwrap().feed_connected);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_suppresses_orders() {
+        use crate::metrics::metric_names::WARMUP_REMAINING;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let orders_placed = Arc::new(AtomicUsize::new(0));
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.register_strategy(
+            "ma".to_string(),
+            Box::new(WarmupStrategy { warmup_ticks: 3, orders_placed: orders_placed.clone() }),
+            create_test_context("ma"),
+            vec!["market1".to_string()],
+        ).await.unwrap();
+        assert_eq!(coordinator.warming_up(), vec!["ma".to_string()]);
+
+        let tick = MarketTick {
+            market: "market1".to_string(),
+            timestamp: Utc::now(),
+            bid: Some(0.49),
+            ask: Some(0.51),
+            bid_size: Some(10.0),
+            ask_size: Some(10.0),
+            last: Some(0.5),
+            volume_24h: None,
+            sequence: None,
+        };
+        for _ in 0..2 {
+            coordinator.route_market_tick("market1", &tick).await.unwrap();
+        }
+        assert_eq!(orders_placed.load(Ordering::SeqCst), 0);
+        assert_eq!(coordinator.get_context("ma").unwrap().warmup_remaining, 1);
+
+        // Registration and flush both report the remaining ticks
+        let warmup: Vec<f64> = coordinator.drain_metrics().iter()
+            .filter(|m| m.metric_name == WARMUP_REMAINING)
+            .map(|m| m.value)
+            .collect();
+        assert_eq!(warmup, vec![3.0, 1.0]);
+
+        // The last warm-up tick may trade
+        coordinator.route_market_tick("market1", &tick).await.unwrap();
+        assert_eq!(orders_placed.load(Ordering::SeqCst), 1);
+        assert!(coordinator.warming_up().is_empty());
+        let warmup: Vec<f64> = coordinator.drain_metrics().iter()
+            .filter(|m| m.metric_name == WARMUP_REMAINING)
+            .map(|m| m.value)
+            .collect();
+        assert_eq!(warmup, vec![0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_priority_dispatch_under_load() {
+        use crate::metrics::metric_names::{EVENTS_HANDLED, LOOP_UTILIZATION};
+        use std::time::Duration;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.set_max_book_updates(3);
+        coordinator.register_strategy_with_timer(
+            "mm".to_string(),
+            Box::new(RecordingStrategy { events: events.clone() }),
+            create_test_context("mm"),
+            vec!["market1".to_string()],
+            TimerConfig::every(Duration::from_millis(100)),
+        ).await.unwrap();
+
+        // A burst of book updates with a timer, a cancel and a fill behind it
+        let tick = |bid: f64| MarketTick {
+            market: "market1".to_string(),
+            timestamp: Utc::now(),
+            bid: Some(bid),
+            ask: Some(bid + 0.02),
+            bid_size: None,
+            ask_size: None,
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        };
+        for i in 1..=5 {
+            coordinator.enqueue_market_tick("market1", &tick(i as f64 / 10.0));
+        }
+        assert_eq!(coordinator.enqueue_due_timers(Utc::now() + chrono::Duration::seconds(1)), vec!["mm".to_string()]);
+        coordinator.enqueue_cancel("mm", "order_1".to_string()).unwrap();
+        coordinator.enqueue_fill("mm", Fill {
+            order_id: "order_2".to_string(),
+            market: "market1".to_string(),
+            price: 0.4,
+            size: 25.0,
+            side: Side::Buy,
+            fee: 0.0,
+            timestamp: Utc::now(),
+        }).unwrap();
+        assert!(coordinator.enqueue_fill("missing", Fill {
+            order_id: "order_3".to_string(),
+            market: "market1".to_string(),
+            price: 0.4,
+            size: 1.0,
+            side: Side::Buy,
+            fee: 0.0,
+            timestamp: Utc::now(),
+        }).is_err());
+        assert_eq!(coordinator.pending_events("mm"), 6);
+        assert_eq!(coordinator.dropped_book_updates("mm"), 2);
+
+        // Fill first (so every tick sees the new position), then the cancel,
+        // the newest book updates in order, and the timer last
+        assert_eq!(coordinator.dispatch_pending().await.unwrap(), 6);
+        assert_eq!(
+            *events.lock(),
+            vec!["fill order_2", "cancel order_1", "tick 0.3 pos 25", "tick 0.4 pos 25", "tick 0.5 pos 25", "timer"]
+        );
+        assert_eq!(coordinator.pending_events("mm"), 0);
+        assert_eq!(coordinator.dispatch_pending().await.unwrap(), 0);
+
+        // Each event is timed with the backlog behind it
+        let load = coordinator.strategy_load("mm").unwrap();
+        assert_eq!(load.events(), 6);
+        assert_eq!(load.queue_depth().max(), 5.0);
+        assert!(coordinator.loop_utilization()["mm"] <= 1.0);
+
+        // Draining reports the load and starts a new window
+        let metrics = coordinator.drain_metrics();
+        let handled = metrics.iter().find(|m| m.metric_name == EVENTS_HANDLED && m.strategy_id == "mm").unwrap();
+        assert_eq!(handled.value, 6.0);
+        assert!(metrics.iter().any(|m| m.metric_name == LOOP_UTILIZATION));
+        assert_eq!(coordinator.strategy_load("mm").unwrap().events(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_strategy() {
+        use std::time::Duration;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut coordinator = MultiMarketCoordinator::new();
+        coordinator.register_strategy_with_timer(
+            "mm".to_string(),
+            Box::new(RecordingStrategy { events: events.clone() }),
+            create_test_context("mm"),
+            vec!["market1".to_string()],
+            TimerConfig::every(Duration::from_millis(100)),
+        ).await.unwrap();
+        let tick = |bid: f64| MarketTick {
+            market: "market1".to_string(),
+            timestamp: Utc::now(),
+            bid: Some(bid),
+            ask: Some(bid + 0.02),
+            bid_size: None,
+            ask_size: None,
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        };
+
+        // Ticks queued before the pause are discarded with the ones after it
+        coordinator.enqueue_market_tick("market1", &tick(0.1));
+        assert!(coordinator.pause_strategy("mm").unwrap());
+        assert!(!coordinator.pause_strategy("mm").unwrap());
+        assert!(coordinator.pause_strategy("missing").is_err());
+        coordinator.enqueue_market_tick("market1", &tick(0.2));
+        coordinator.route_market_tick("market1", &tick(0.3)).await.unwrap();
+        assert!(coordinator.enqueue_due_timers(Utc::now() + chrono::Duration::seconds(1)).is_empty());
+
+        // Fills still arrive
+        coordinator.enqueue_cancel("mm", "order_1".to_string()).unwrap();
+        coordinator.dispatch_pending().await.unwrap();
+        assert_eq!(*events.lock(), vec!["cancel order_1"]);
+        assert_eq!(coordinator.paused_strategies(), vec!["mm".to_string()]);
+
+        assert!(coordinator.resume_strategy("mm").unwrap());
+        assert!(!coordinator.is_paused("mm"));
+        coordinator.route_market_tick("market1", &tick(0.4)).await.unwrap();
+        assert_eq!(events.lock().last().unwrap(), "tick 0.4 pos 0");
+    }
 }
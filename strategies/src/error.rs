@@ -1,14 +1,15 @@
 //! Error types for the strategy engine
 
+use ag_risk::Violation;
 use thiserror::Error;
 
 /// Main error type for strategy operations
 #[derive(Error, Debug)]
 pub enum StrategyError {
     /// Risk engine rejected the action
-    #[error("Risk rejected: {policies:?}")]
+    #[error("Risk rejected: {}", join_violations(.violations))]
     RiskRejected {
-        policies: Vec<String>,
+        violations: Vec<Violation>,
     },
 
     /// Execution engine error
@@ -35,6 +36,24 @@ pub enum StrategyError {
     #[error("Strategy not initialized")]
     NotInitialized,
 
+    /// Market data feed is disconnected; prices may be stale
+    #[error("Market data feed disconnected")]
+    FeedDisconnected,
+
+    /// Strategy is still warming up its indicators
+    #[error("Strategy warming up ({remaining} ticks remaining)")]
+    WarmingUp {
+        remaining: usize,
+    },
+
+    /// Behaviour gated by a disabled feature flag
+    #[error("Feature disabled: {0}")]
+    FeatureDisabled(String),
+
+    /// Strategy registration refused by the coordinator
+    #[error("Registration conflict: {0}")]
+    RegistrationConflict(#[from] RegistrationConflict),
+
     /// Configuration error
     #[error("Configuration error: {0}")]
     ConfigError(String),
@@ -70,3 +89,44 @@ pub enum StrategyError {
 
 /// Result type for strategy operations
 pub type StrategyResult<T> = Result<T, StrategyError>;
+
+/// Why [`MultiMarketCoordinator::register_strategy`](crate::MultiMarketCoordinator::register_strategy)
+/// refused a strategy
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationConflict {
+    /// A strategy with this id is already registered (use
+    /// [`replace_strategy`](crate::MultiMarketCoordinator::replace_strategy)
+    /// to overwrite it)
+    #[error("strategy {strategy_id} is already registered")]
+    DuplicateId {
+        strategy_id: String,
+    },
+
+    /// The same market appears more than once in the subscription list
+    #[error("strategy {strategy_id} subscribes to market {market} more than once")]
+    DuplicateMarket {
+        strategy_id: String,
+        market: String,
+    },
+
+    /// The subscribed markets differ from the markets in the strategy's
+    /// metadata
+    #[error(
+        "markets of strategy {strategy_id} do not match its metadata \
+         (declared but not subscribed: [{}]; subscribed but not declared: [{}])",
+        .missing.join(", "),
+        .undeclared.join(", ")
+    )]
+    MarketMismatch {
+        strategy_id: String,
+        /// Declared in the metadata but not subscribed
+        missing: Vec<String>,
+        /// Subscribed but not declared in the metadata
+        undeclared: Vec<String>,
+    },
+}
+
+/// Log messages of risk violations, separated by "; "
+fn join_violations(violations: &[Violation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
@@ -0,0 +1,119 @@
+//! Runtime feature flags
+//!
+//! Risky behaviours are gated on named boolean flags that can be flipped
+//! while the bot runs, without a redeploy. [`FeatureFlags`] is a cheap,
+//! cloneable handle: the coordinator and every strategy context share the
+//! same flag set, so a change made through any handle is seen by all of
+//! them on their next check.
+//!
+//! Two flags are enforced by the framework itself:
+//!
+//! - [`ENABLE_TAKER_ORDERS`]: `StrategyContext::submit_order` refuses orders
+//!   that can only take liquidity (market and stop orders, IOC and FOK)
+//! - [`ENABLE_NEW_MARKET_ONBOARDING`]: the coordinator refuses to subscribe
+//!   strategies to markets they do not trade yet
+//!
+//! Both default to enabled. Strategies may consult their own flags through
+//! `ctx.flags`; flags that were never set read as disabled.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::types::{Order, OrderType, TimeInForce};
+
+/// Allow orders that cross the spread
+pub const ENABLE_TAKER_ORDERS: &str = "enable_taker_orders";
+
+/// Allow subscribing strategies to markets they do not trade yet
+pub const ENABLE_NEW_MARKET_ONBOARDING: &str = "enable_new_market_onboarding";
+
+/// Gauge reporting each flag as 1.0 (enabled) or 0.0 (label: flag)
+pub const FLAG_METRIC: &str = "feature_flag.enabled";
+
+/// Flags enforced by the framework, with their defaults
+pub const BUILTIN_FLAGS: &[(&str, bool)] = &[(ENABLE_TAKER_ORDERS, true), (ENABLE_NEW_MARKET_ONBOARDING, true)];
+
+/// Shared, runtime-mutable set of named boolean flags
+#[derive(Debug, Clone)]
+pub struct FeatureFlags {
+    flags: Arc<RwLock<HashMap<String, bool>>>,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+impl FeatureFlags {
+    /// Built-in flags at their defaults, overridden by `overrides`
+    pub fn new(overrides: HashMap<String, bool>) -> Self {
+        let mut flags: HashMap<String, bool> =
+            BUILTIN_FLAGS.iter().map(|(name, enabled)| (name.to_string(), *enabled)).collect();
+        flags.extend(overrides);
+        Self {
+            flags: Arc::new(RwLock::new(flags)),
+        }
+    }
+
+    /// Whether a flag is enabled (unset flags are disabled)
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags.read().get(name).copied().unwrap_or(false)
+    }
+
+    /// Set a flag, returning its previous value
+    pub fn set(&self, name: &str, enabled: bool) -> Option<bool> {
+        let previous = self.flags.write().insert(name.to_string(), enabled);
+        if previous != Some(enabled) {
+            tracing::info!("Feature flag {} set to {}", name, enabled);
+        }
+        previous
+    }
+
+    /// Current state of every flag, sorted by name
+    pub fn snapshot(&self) -> BTreeMap<String, bool> {
+        self.flags.read().iter().map(|(name, enabled)| (name.clone(), *enabled)).collect()
+    }
+}
+
+/// Whether an order can only take liquidity, and so is gated by
+/// [`ENABLE_TAKER_ORDERS`]
+pub fn is_taker_order(order: &Order) -> bool {
+    matches!(order.order_type, OrderType::Market | OrderType::Stop)
+        || matches!(order.time_in_force, TimeInForce::IOC | TimeInForce::FOK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_flags() {
+        let flags = FeatureFlags::new(HashMap::from([(ENABLE_TAKER_ORDERS.to_string(), false)]));
+        let shared = flags.clone();
+        assert!(!shared.is_enabled(ENABLE_TAKER_ORDERS));
+        assert!(shared.is_enabled(ENABLE_NEW_MARKET_ONBOARDING));
+        assert!(!shared.is_enabled("enable_experimental_signal"));
+
+        assert_eq!(flags.set(ENABLE_TAKER_ORDERS, true), Some(false));
+        assert!(shared.is_enabled(ENABLE_TAKER_ORDERS));
+        assert_eq!(
+            flags.snapshot().into_iter().collect::<Vec<_>>(),
+            vec![(ENABLE_NEW_MARKET_ONBOARDING.to_string(), true), (ENABLE_TAKER_ORDERS.to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_taker_orders() {
+        let limit = Order {
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::GTC,
+            ..Order::default()
+        };
+        assert!(!is_taker_order(&limit));
+        assert!(is_taker_order(&Order { time_in_force: TimeInForce::IOC, ..limit.clone() }));
+        assert!(is_taker_order(&Order { order_type: OrderType::Market, ..limit }));
+    }
+}
@@ -0,0 +1,317 @@
+//! Market data hygiene
+//!
+//! Exchange replays and reconnects resend ticks that were already
+//! delivered, and merged feeds can deliver a market's ticks slightly out of
+//! order. Fed straight through, both double-count: indicators take the same
+//! price twice and the backtest engine simulates fills against it twice.
+//! [`TickHygiene`] sits in front of the strategies and, per market:
+//!
+//! - drops exact duplicates of any of the last `dedup_window` ticks
+//! - keeps timestamps non-decreasing: a tick up to `tolerance_ms` behind the
+//!   market's latest is kept (with its timestamp raised to the latest when
+//!   `correct_timestamps` is set); one further behind is dropped
+//! - counts every anomaly in [`HygieneStats`]
+//!
+//! Unlike `ag_exec::SequenceTracker`, which classifies messages by venue
+//! sequence number, this works on tick content and needs no sequence
+//! numbers.
+//!
+//! ```rust
+//! use ag_strategies::hygiene::{HygieneConfig, TickHygiene};
+//! use ag_strategies::MarketTick;
+//! use chrono::Utc;
+//!
+//! let tick = MarketTick {
+//!     market: "0x123".to_string(),
+//!     timestamp: Utc::now(),
+//!     bid: Some(0.49),
+//!     ask: Some(0.51),
+//!     bid_size: None,
+//!     ask_size: None,
+//!     last: None,
+//!     volume_24h: None,
+//!     sequence: None,
+//! };
+//! let mut hygiene = TickHygiene::new(HygieneConfig::default());
+//! let ticks = hygiene.filter(vec![tick.clone(), tick]);
+//! assert_eq!(ticks.len(), 1);
+//! assert_eq!(hygiene.stats("0x123").unwrap().duplicates, 1);
+//! ```
+
+use crate::types::MarketTick;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Hygiene settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HygieneConfig {
+    /// Recent ticks per market compared against for exact duplicates
+    pub dedup_window: usize,
+
+    /// How far behind the market's latest tick a tick may be and still be
+    /// kept, in milliseconds
+    pub tolerance_ms: u64,
+
+    /// Raise kept late ticks' timestamps to the market's latest, so
+    /// timestamps are strictly non-decreasing
+    pub correct_timestamps: bool,
+}
+
+impl Default for HygieneConfig {
+    fn default() -> Self {
+        Self {
+            dedup_window: 16,
+            tolerance_ms: 0,
+            correct_timestamps: true,
+        }
+    }
+}
+
+/// Outcome of checking one tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickVerdict {
+    /// Clean tick
+    Accepted,
+    /// Late within tolerance; kept, timestamp raised by `by` (zero when
+    /// correction is off)
+    Corrected { by: Duration },
+    /// Exact copy of a recent tick; dropped
+    Duplicate,
+    /// Further behind the latest tick than the tolerance; dropped
+    OutOfOrder { behind: Duration },
+}
+
+impl TickVerdict {
+    /// Whether the tick should be discarded
+    pub fn is_dropped(&self) -> bool {
+        matches!(self, TickVerdict::Duplicate | TickVerdict::OutOfOrder { .. })
+    }
+}
+
+/// Anomaly counters for one market
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HygieneStats {
+    /// Ticks checked
+    pub received: u64,
+    /// Exact duplicates dropped
+    pub duplicates: u64,
+    /// Ticks dropped for being too far behind
+    pub out_of_order: u64,
+    /// Late ticks kept within tolerance
+    pub corrected: u64,
+}
+
+impl HygieneStats {
+    /// Counters as `(metric name, value)` pairs
+    pub fn metric_values(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("market_data.hygiene.received", self.received as f64),
+            ("market_data.hygiene.duplicates", self.duplicates as f64),
+            ("market_data.hygiene.out_of_order", self.out_of_order as f64),
+            ("market_data.hygiene.corrected", self.corrected as f64),
+        ]
+    }
+
+    /// Add another market's counters
+    pub fn merge(&mut self, other: &HygieneStats) {
+        self.received += other.received;
+        self.duplicates += other.duplicates;
+        self.out_of_order += other.out_of_order;
+        self.corrected += other.corrected;
+    }
+
+    /// Ticks dropped for any reason
+    pub fn dropped(&self) -> u64 {
+        self.duplicates + self.out_of_order
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct MarketState {
+    /// Recent ticks as received (before correction), oldest first
+    recent: VecDeque<MarketTick>,
+    /// Latest timestamp passed through
+    latest: Option<DateTime<Utc>>,
+    stats: HygieneStats,
+}
+
+/// Per-market tick deduplication and timestamp enforcement
+#[derive(Debug, Clone, Default)]
+pub struct TickHygiene {
+    config: HygieneConfig,
+    markets: HashMap<String, MarketState>,
+}
+
+impl TickHygiene {
+    /// Create a filter
+    pub fn new(config: HygieneConfig) -> Self {
+        Self {
+            config,
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Filter settings
+    pub fn config(&self) -> &HygieneConfig {
+        &self.config
+    }
+
+    /// Check a tick, correcting its timestamp in place if it is late
+    /// within tolerance
+    pub fn check(&mut self, tick: &mut MarketTick) -> TickVerdict {
+        let state = self.markets.entry(tick.market.clone()).or_default();
+        state.stats.received += 1;
+
+        if state.recent.contains(tick) {
+            state.stats.duplicates += 1;
+            return TickVerdict::Duplicate;
+        }
+
+        let received = tick.clone();
+        let verdict = match state.latest {
+            Some(latest) if tick.timestamp < latest => {
+                let behind = latest - tick.timestamp;
+                if behind > Duration::milliseconds(self.config.tolerance_ms as i64) {
+                    state.stats.out_of_order += 1;
+                    return TickVerdict::OutOfOrder { behind };
+                }
+                state.stats.corrected += 1;
+                if self.config.correct_timestamps {
+                    tick.timestamp = latest;
+                    TickVerdict::Corrected { by: behind }
+                } else {
+                    TickVerdict::Corrected { by: Duration::zero() }
+                }
+            }
+            _ => {
+                state.latest = Some(tick.timestamp);
+                TickVerdict::Accepted
+            }
+        };
+
+        if self.config.dedup_window > 0 {
+            if state.recent.len() == self.config.dedup_window {
+                state.recent.pop_front();
+            }
+            state.recent.push_back(received);
+        }
+        verdict
+    }
+
+    /// Keep the ticks that pass, in order, with corrected timestamps
+    pub fn filter(&mut self, ticks: Vec<MarketTick>) -> Vec<MarketTick> {
+        ticks
+            .into_iter()
+            .filter_map(|mut tick| (!self.check(&mut tick).is_dropped()).then_some(tick))
+            .collect()
+    }
+
+    /// Counters for one market
+    pub fn stats(&self, market: &str) -> Option<&HygieneStats> {
+        self.markets.get(market).map(|state| &state.stats)
+    }
+
+    /// Counters for every market
+    pub fn all_stats(&self) -> HashMap<String, HygieneStats> {
+        self.markets
+            .iter()
+            .map(|(market, state)| (market.clone(), state.stats.clone()))
+            .collect()
+    }
+
+    /// Counters summed over every market
+    pub fn totals(&self) -> HygieneStats {
+        let mut totals = HygieneStats::default();
+        for state in self.markets.values() {
+            totals.merge(&state.stats);
+        }
+        totals
+    }
+
+    /// Forget a market (e.g. after unsubscribing or a resync)
+    pub fn reset(&mut self, market: &str) -> Option<HygieneStats> {
+        self.markets.remove(market).map(|state| state.stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(market: &str, at: DateTime<Utc>, bid: f64) -> MarketTick {
+        MarketTick {
+            market: market.to_string(),
+            timestamp: at,
+            bid: Some(bid),
+            ask: Some(bid + 0.02),
+            bid_size: Some(100.0),
+            ask_size: Some(100.0),
+            last: None,
+            volume_24h: None,
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_drops_replayed_ticks() {
+        let now = Utc::now();
+        let mut hygiene = TickHygiene::new(HygieneConfig::default());
+        let first = tick("m1", now, 0.40);
+        let second = tick("m1", now + Duration::seconds(1), 0.41);
+        let third = tick("m1", now + Duration::seconds(2), 0.42);
+
+        // A reconnect replays the first two ticks after the third
+        let ticks = vec![first.clone(), second.clone(), third.clone(), first, second, tick("m2", now, 0.40)];
+        let kept = hygiene.filter(ticks);
+        assert_eq!(kept.len(), 4);
+        assert_eq!(kept[2], third);
+
+        let stats = hygiene.stats("m1").unwrap();
+        assert_eq!((stats.received, stats.duplicates, stats.out_of_order), (5, 2, 0));
+        assert_eq!(hygiene.totals().received, 6);
+
+        // Same timestamp, different prices is not a duplicate
+        let mut update = tick("m1", now + Duration::seconds(2), 0.43);
+        assert_eq!(hygiene.check(&mut update), TickVerdict::Accepted);
+    }
+
+    #[test]
+    fn test_late_ticks() {
+        let now = Utc::now();
+        let mut hygiene = TickHygiene::new(HygieneConfig {
+            tolerance_ms: 100,
+            ..HygieneConfig::default()
+        });
+        hygiene.check(&mut tick("m1", now, 0.40));
+
+        // Slightly late: kept at the latest timestamp
+        let mut late = tick("m1", now - Duration::milliseconds(50), 0.41);
+        assert_eq!(
+            hygiene.check(&mut late),
+            TickVerdict::Corrected {
+                by: Duration::milliseconds(50)
+            }
+        );
+        assert_eq!(late.timestamp, now);
+
+        // Too late: dropped
+        let mut stale = tick("m1", now - Duration::seconds(1), 0.42);
+        assert!(hygiene.check(&mut stale).is_dropped());
+
+        // Without correction late ticks keep their timestamp
+        let mut lenient = TickHygiene::new(HygieneConfig {
+            tolerance_ms: 100,
+            correct_timestamps: false,
+            ..HygieneConfig::default()
+        });
+        lenient.check(&mut tick("m1", now, 0.40));
+        let mut late = tick("m1", now - Duration::milliseconds(50), 0.41);
+        assert!(!lenient.check(&mut late).is_dropped());
+        assert_eq!(late.timestamp, now - Duration::milliseconds(50));
+
+        let stats = hygiene.stats("m1").unwrap();
+        assert_eq!((stats.corrected, stats.out_of_order, stats.dropped()), (1, 1, 1));
+    }
+}
@@ -74,18 +74,32 @@
 //!             description: "My custom strategy".to_string(),
 //!             markets: vec![],
 //!             required_params: vec![],
+//!             warmup_ticks: 0,
 //!         }
 //!     }
 //! }
 //! ```
 
 pub mod error;
+pub mod flags;
+pub mod hygiene;
 pub mod types;
+pub mod venues;
+#[cfg(feature = "runtime")]
 pub mod context;
+#[cfg(feature = "runtime")]
 pub mod coordinator;
+pub mod mailbox;
+pub mod matching;
 pub mod metrics;
+pub mod sizing;
+pub mod timer;
+pub mod utilization;
+#[cfg(feature = "runtime")]
+pub mod plugin;
 
 // Strategy implementations
+#[cfg(feature = "runtime")]
 #[path = "../impl/mod.rs"]
 pub mod r#impl;
 
@@ -94,25 +108,44 @@ pub mod r#impl;
 pub mod signals;
 
 // Backtesting engine
+#[cfg(feature = "runtime")]
 #[path = "../backtest/mod.rs"]
 pub mod backtest;
 
+// OpenTelemetry export
+#[cfg(feature = "otel")]
+pub mod telemetry;
+
 // Re-export main types
-pub use error::{StrategyError, StrategyResult};
+pub use error::{RegistrationConflict, StrategyError, StrategyResult};
+pub use flags::FeatureFlags;
+pub use hygiene::{HygieneConfig, HygieneStats, TickHygiene, TickVerdict};
+pub use venues::{VenueState, VenueStatuses};
 pub use types::{
     StrategyMetadata, StrategyParams,
-    Order, OrderId, OrderType, OrderStatus, Side, TimeInForce,
+    Order, OrderId, OrderTemplate, OrderType, OrderStatus, Peg, PegReference, Side, TimeInForce, SIGNAL_TAG,
     Fill, Trade, Position,
-    MarketTick, MarketData,
+    MarketTick, MarketStatus, MarketStatusUpdate, MarketData, OhlcvBar,
     Signal, SignalType, SignalMetadata, SignalGenerator,
 };
+#[cfg(feature = "runtime")]
 pub use context::StrategyContext;
-pub use coordinator::MultiMarketCoordinator;
+#[cfg(feature = "runtime")]
+pub use coordinator::{MarketRemoval, MarketSettlement, MultiMarketCoordinator, SubscriptionChanges};
+pub use mailbox::{EventPriority, StrategyEvent, StrategyMailbox};
+pub use matching::{MatchingConfig, MatchingEngine, SelfMatchPolicy};
 pub use metrics::{StrategyMetric, MetricType};
+pub use sizing::{DrawdownTracker, PositionSizer, SizingConfig, SizingInputs, SizingMethod};
+pub use timer::{TimerConfig, TimerWheel};
+pub use utilization::{BucketHistogram, StrategyLoad};
+#[cfg(feature = "runtime")]
+pub use plugin::{PluginRegistrar, StrategyFactory, StrategyRegistry};
 
+#[cfg(feature = "runtime")]
 use async_trait::async_trait;
 
 /// Base strategy trait that all strategies must implement
+#[cfg(feature = "runtime")]
 #[async_trait]
 pub trait Strategy: Send + Sync {
     async fn initialize(&mut self, ctx: &mut StrategyContext) -> StrategyResult<()>;
@@ -143,10 +176,46 @@ pub trait Strategy: Send + Sync {
 
     async fn shutdown(&mut self, ctx: &mut StrategyContext) -> StrategyResult<()>;
 
+    /// Called when the coordinator subscribes this strategy to a new market at runtime
+    async fn add_market(&mut self, _market_id: &str, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+        Ok(())
+    }
+
+    /// Called before the coordinator unsubscribes this strategy from a market
+    ///
+    /// Open orders in the market are cancelled by the coordinator afterwards.
+    async fn remove_market(&mut self, _market_id: &str, _ctx: &mut StrategyContext) -> StrategyResult<()> {
+        Ok(())
+    }
+
+    /// Called when a market the strategy trades resolves
+    ///
+    /// The position has already been settled at `payout` per share (see
+    /// [`StrategyContext::settle_position`]); afterwards the coordinator
+    /// removes the market, cancelling any orders left in it.
+    async fn on_market_resolved(
+        &mut self,
+        _market_id: &str,
+        _payout: f64,
+        _ctx: &mut StrategyContext,
+    ) -> StrategyResult<()> {
+        Ok(())
+    }
+
+    /// Whether the strategy keeps its own positions
+    ///
+    /// By default fills are applied to the context's positions
+    /// (`StrategyContext::apply_fill`) before `on_fill` is called. Return
+    /// true to opt out and update positions yourself; filled orders are
+    /// still reduced in `ctx.orders`.
+    fn manages_positions(&self) -> bool {
+        false
+    }
+
     fn metadata(&self) -> types::StrategyMetadata;
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "runtime"))]
 mod tests {
     use super::*;
 
@@ -198,6 +267,7 @@ mod tests {
                 description: "Test strategy".to_string(),
                 markets: vec![],
                 required_params: vec![],
+                warmup_ticks: 0,
             }
         }
     }
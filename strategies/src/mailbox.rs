@@ -0,0 +1,269 @@
+//! Per-strategy event mailbox with priority classes
+//!
+//! When events arrive faster than a strategy handles them, the order they
+//! are handled in matters: a fill changes the position every later decision
+//! depends on, while a book update is superseded by the next one. The
+//! coordinator queues events per strategy in a [`StrategyMailbox`] and
+//! dispatches them by [`EventPriority`]: fills, then cancels, then book
+//! updates, then timers. Within a class events keep their arrival order.
+//!
+//! Book updates are bounded: past `max_book_updates` a queued tick is
+//! dropped, as it is stale by the time it would be handled. The oldest tick
+//! of the incoming tick's market goes first, then the oldest of any market
+//! with a newer tick queued, so a busy market cannot crowd out the latest
+//! book of a quiet one. Timers are coalesced, since a second pending
+//! `on_timer` adds nothing to the first.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::types::{Fill, MarketTick, OrderId};
+
+/// Default bound on queued book updates per strategy
+pub const DEFAULT_MAX_BOOK_UPDATES: usize = 1024;
+
+/// Dispatch priority of a strategy event (higher is dispatched first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    Timer,
+    BookUpdate,
+    Cancel,
+    Fill,
+}
+
+impl EventPriority {
+    /// All classes, highest priority first
+    pub const ALL: [EventPriority; 4] = [
+        EventPriority::Fill,
+        EventPriority::Cancel,
+        EventPriority::BookUpdate,
+        EventPriority::Timer,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            EventPriority::Fill => 0,
+            EventPriority::Cancel => 1,
+            EventPriority::BookUpdate => 2,
+            EventPriority::Timer => 3,
+        }
+    }
+}
+
+/// Event queued for a strategy
+#[derive(Debug, Clone)]
+pub enum StrategyEvent {
+    Fill(Fill),
+    Cancel(OrderId),
+    MarketTick { market_id: String, tick: MarketTick },
+    Timer,
+}
+
+impl StrategyEvent {
+    pub fn priority(&self) -> EventPriority {
+        match self {
+            StrategyEvent::Fill(_) => EventPriority::Fill,
+            StrategyEvent::Cancel(_) => EventPriority::Cancel,
+            StrategyEvent::MarketTick { .. } => EventPriority::BookUpdate,
+            StrategyEvent::Timer => EventPriority::Timer,
+        }
+    }
+}
+
+/// Priority-ordered event queue of one strategy
+#[derive(Debug, Clone)]
+pub struct StrategyMailbox {
+    queues: [VecDeque<StrategyEvent>; 4],
+    max_book_updates: usize,
+    dropped_book_updates: u64,
+}
+
+impl Default for StrategyMailbox {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BOOK_UPDATES)
+    }
+}
+
+impl StrategyMailbox {
+    pub fn new(max_book_updates: usize) -> Self {
+        Self {
+            queues: Default::default(),
+            max_book_updates: max_book_updates.max(1),
+            dropped_book_updates: 0,
+        }
+    }
+
+    /// Queue an event
+    pub fn push(&mut self, event: StrategyEvent) {
+        let priority = event.priority();
+        let queue = &mut self.queues[priority.index()];
+        match priority {
+            EventPriority::Timer if !queue.is_empty() => return,
+            EventPriority::BookUpdate if queue.len() >= self.max_book_updates => {
+                let stale = stale_book_update(queue, market_of(&event));
+                queue.remove(stale);
+                self.dropped_book_updates += 1;
+            }
+            _ => {}
+        }
+        queue.push_back(event);
+    }
+
+    /// Next event by priority, oldest first within a class
+    pub fn pop(&mut self) -> Option<StrategyEvent> {
+        self.queues.iter_mut().find_map(|queue| queue.pop_front())
+    }
+
+    /// Queued events
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(|q| q.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(|q| q.is_empty())
+    }
+
+    /// Queued events of one class
+    pub fn pending(&self, priority: EventPriority) -> usize {
+        self.queues[priority.index()].len()
+    }
+
+    /// Book updates dropped because the queue was full
+    pub fn dropped_book_updates(&self) -> u64 {
+        self.dropped_book_updates
+    }
+
+    /// Discard all queued events
+    pub fn clear(&mut self) {
+        self.queues.iter_mut().for_each(|q| q.clear());
+    }
+
+    /// Discard the queued events of one class, returning how many there were
+    pub fn discard(&mut self, priority: EventPriority) -> usize {
+        let queue = &mut self.queues[priority.index()];
+        let discarded = queue.len();
+        queue.clear();
+        discarded
+    }
+}
+
+fn market_of(event: &StrategyEvent) -> Option<&str> {
+    match event {
+        StrategyEvent::MarketTick { market_id, .. } => Some(market_id),
+        _ => None,
+    }
+}
+
+/// Index of the queued book update to drop for a tick in `market`: that
+/// market's oldest, else the oldest tick superseded by a newer one of its
+/// market, else the oldest
+fn stale_book_update(queue: &VecDeque<StrategyEvent>, market: Option<&str>) -> usize {
+    if let Some(index) = queue.iter().position(|event| market_of(event) == market) {
+        return index;
+    }
+    let mut newer = HashSet::new();
+    let mut stale = 0;
+    for (index, event) in queue.iter().enumerate().rev() {
+        if !newer.insert(market_of(event)) {
+            stale = index;
+        }
+    }
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+    use chrono::Utc;
+
+    fn tick(market: &str, bid: f64) -> StrategyEvent {
+        StrategyEvent::MarketTick {
+            market_id: market.to_string(),
+            tick: MarketTick {
+                market: market.to_string(),
+                timestamp: Utc::now(),
+                bid: Some(bid),
+                ask: Some(bid + 0.01),
+                bid_size: None,
+                ask_size: None,
+                last: None,
+                volume_24h: None,
+                sequence: None,
+            },
+        }
+    }
+
+    fn fill(order_id: &str) -> StrategyEvent {
+        StrategyEvent::Fill(Fill {
+            order_id: order_id.to_string(),
+            market: "m1".to_string(),
+            price: 0.5,
+            size: 10.0,
+            side: Side::Buy,
+            fee: 0.0,
+            timestamp: Utc::now(),
+        })
+    }
+
+    #[test]
+    fn test_priority_order() {
+        let mut mailbox = StrategyMailbox::default();
+        mailbox.push(StrategyEvent::Timer);
+        mailbox.push(tick("m1", 0.40));
+        mailbox.push(StrategyEvent::Cancel("o-1".to_string()));
+        mailbox.push(tick("m1", 0.41));
+        mailbox.push(fill("o-2"));
+        mailbox.push(StrategyEvent::Timer);
+        mailbox.push(fill("o-3"));
+        assert_eq!(mailbox.len(), 6);
+        assert_eq!(mailbox.pending(EventPriority::Timer), 1);
+
+        let order: Vec<String> = std::iter::from_fn(|| mailbox.pop())
+            .map(|event| match event {
+                StrategyEvent::Fill(f) => format!("fill {}", f.order_id),
+                StrategyEvent::Cancel(id) => format!("cancel {}", id),
+                StrategyEvent::MarketTick { tick, .. } => format!("tick {}", tick.bid.unwrap()),
+                StrategyEvent::Timer => "timer".to_string(),
+            })
+            .collect();
+        assert_eq!(order, vec!["fill o-2", "fill o-3", "cancel o-1", "tick 0.4", "tick 0.41", "timer"]);
+        assert!(mailbox.is_empty());
+    }
+
+    #[test]
+    fn test_book_updates_bounded() {
+        let mut mailbox = StrategyMailbox::new(2);
+        for i in 0..5 {
+            mailbox.push(tick("m1", i as f64));
+        }
+        mailbox.push(fill("o-1"));
+
+        assert_eq!(mailbox.pending(EventPriority::BookUpdate), 2);
+        assert_eq!(mailbox.dropped_book_updates(), 3);
+        assert!(matches!(mailbox.pop(), Some(StrategyEvent::Fill(_))));
+        match mailbox.pop() {
+            Some(StrategyEvent::MarketTick { tick, .. }) => assert_eq!(tick.bid, Some(3.0)),
+            other => panic!("expected the oldest kept tick, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_book_updates_bounded_per_market() {
+        let mut mailbox = StrategyMailbox::new(3);
+        mailbox.push(tick("m2", 0.2));
+        for bid in [0.1, 0.3, 0.5, 0.7] {
+            mailbox.push(tick("m1", bid));
+        }
+        // A new market displaces a superseded tick, not another market's latest
+        mailbox.push(tick("m3", 0.6));
+
+        let books: Vec<String> = std::iter::from_fn(|| mailbox.pop())
+            .map(|event| match event {
+                StrategyEvent::MarketTick { market_id, tick } => format!("{} {}", market_id, tick.bid.unwrap()),
+                other => panic!("expected a tick, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(books, vec!["m2 0.2", "m1 0.7", "m3 0.6"]);
+        assert_eq!(mailbox.dropped_book_updates(), 3);
+    }
+}
@@ -0,0 +1,634 @@
+//! Limit order book matching engine for strategy tests
+//!
+//! A small in-process exchange: orders from any number of owners (strategy
+//! IDs, or a synthetic "market" owner seeding liquidity) are matched with
+//! price-time priority, including partial fills, IOC/FOK handling and
+//! self-match prevention. It is much lighter than
+//! [`BacktestEngine`](crate::backtest::BacktestEngine): tests place orders
+//! and assert on the exact fill sequence.
+//!
+//! ```rust
+//! use ag_strategies::matching::{MatchingConfig, MatchingEngine};
+//! use ag_strategies::{Order, Side};
+//!
+//! let mut engine = MatchingEngine::new(MatchingConfig::default());
+//! let ask = Order { market: "m".into(), side: Side::Sell, price: Some(0.55), size: 10.0, ..Default::default() };
+//! engine.submit("market", &ask).unwrap();
+//!
+//! let bid = Order { market: "m".into(), side: Side::Buy, price: Some(0.56), size: 4.0, ..Default::default() };
+//! let result = engine.submit("mm_1", &bid).unwrap();
+//! assert_eq!(result.filled, 4.0);
+//! assert_eq!(engine.take_fills("mm_1")[0].price, 0.55);
+//! assert_eq!(engine.book("m").unwrap().best_ask(), Some((0.55, 6.0)));
+//! ```
+//!
+//! A [`StrategyContext`](crate::StrategyContext) can trade against a shared
+//! engine instead of its paper engine with
+//! [`use_matching_engine`](crate::StrategyContext::use_matching_engine).
+
+use crate::types::{Fill, MarketId, MarketTick, Order, OrderId, OrderStatus, OrderType, Side, TimeInForce};
+use crate::{StrategyError, StrategyResult};
+use ag_risk::clock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Sizes below this are treated as zero
+const SIZE_EPSILON: f64 = 1e-9;
+
+/// What happens when an incoming order would trade with a resting order of
+/// the same owner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfMatchPolicy {
+    /// Trade with yourself
+    Allow,
+    /// Cancel the resting order and keep matching
+    CancelResting,
+    /// Cancel the rest of the incoming order
+    #[default]
+    CancelIncoming,
+    /// Cancel both
+    CancelBoth,
+}
+
+/// Matching engine settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchingConfig {
+    /// Price increment; orders within half a tick share a price level
+    pub tick_size: f64,
+    /// Fee charged to the resting side, in bps of notional (negative = rebate)
+    pub maker_fee_bps: f64,
+    /// Fee charged to the incoming side, in bps of notional
+    pub taker_fee_bps: f64,
+    /// Self-match prevention
+    pub self_match: SelfMatchPolicy,
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        Self {
+            tick_size: 0.001,
+            maker_fee_bps: 0.0,
+            taker_fee_bps: 0.0,
+            self_match: SelfMatchPolicy::default(),
+        }
+    }
+}
+
+/// One trade between an incoming and a resting order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Execution {
+    pub market: MarketId,
+    /// Resting order's price
+    pub price: f64,
+    pub size: f64,
+    /// Side of the incoming order
+    pub taker_side: Side,
+    pub taker_order_id: OrderId,
+    pub taker_owner: String,
+    pub maker_order_id: OrderId,
+    pub maker_owner: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Outcome of submitting an order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub order_id: OrderId,
+    /// `Filled`, `PartiallyFilled`, `Acknowledged` (resting, unfilled) or
+    /// `Cancelled` (nothing filled and nothing rests)
+    pub status: OrderStatus,
+    /// Size filled on submission
+    pub filled: f64,
+    /// Size left resting on the book
+    pub resting: f64,
+    /// Trades, in matching order
+    pub executions: Vec<Execution>,
+    /// Resting orders cancelled by self-match prevention
+    pub self_match_cancels: Vec<OrderId>,
+}
+
+/// Aggregated size at one price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: f64,
+    pub size: f64,
+    /// Number of resting orders
+    pub orders: usize,
+}
+
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    order_id: OrderId,
+    owner: String,
+    price: f64,
+    remaining: f64,
+}
+
+/// Price-time priority book for one market
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    market: MarketId,
+    tick_size: f64,
+    /// Levels keyed by price in ticks
+    bids: BTreeMap<i64, VecDeque<RestingOrder>>,
+    asks: BTreeMap<i64, VecDeque<RestingOrder>>,
+    /// Resting order -> (side, level)
+    index: HashMap<OrderId, (Side, i64)>,
+}
+
+impl OrderBook {
+    pub fn new(market: impl Into<MarketId>, tick_size: f64) -> Self {
+        Self {
+            market: market.into(),
+            tick_size,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn market(&self) -> &str {
+        &self.market
+    }
+
+    fn ticks(&self, price: f64) -> i64 {
+        (price / self.tick_size).round() as i64
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<i64, VecDeque<RestingOrder>> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<i64, VecDeque<RestingOrder>> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    fn level(queue: &VecDeque<RestingOrder>) -> BookLevel {
+        BookLevel {
+            price: queue.front().map_or(0.0, |o| o.price),
+            size: queue.iter().map(|o| o.remaining).sum(),
+            orders: queue.len(),
+        }
+    }
+
+    /// Best bid as (price, size)
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.depth(Side::Buy, 1).first().map(|l| (l.price, l.size))
+    }
+
+    /// Best ask as (price, size)
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.depth(Side::Sell, 1).first().map(|l| (l.price, l.size))
+    }
+
+    /// Up to `levels` price levels of one side, best first
+    pub fn depth(&self, side: Side, levels: usize) -> Vec<BookLevel> {
+        let book = self.levels(side);
+        let iter: Box<dyn Iterator<Item = (&i64, &VecDeque<RestingOrder>)>> = match side {
+            Side::Buy => Box::new(book.iter().rev()),
+            Side::Sell => Box::new(book.iter()),
+        };
+        iter.take(levels).map(|(_, queue)| Self::level(queue)).collect()
+    }
+
+    /// Remaining size of a resting order
+    pub fn resting_size(&self, order_id: &str) -> Option<f64> {
+        let (side, price) = self.index.get(order_id)?;
+        self.levels(*side).get(price)?.iter().find(|o| o.order_id == order_id).map(|o| o.remaining)
+    }
+
+    fn owner(&self, order_id: &str) -> Option<&str> {
+        let (side, price) = self.index.get(order_id)?;
+        self.levels(*side).get(price)?.iter().find(|o| o.order_id == order_id).map(|o| o.owner.as_str())
+    }
+
+    /// Number of resting orders
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Remove a resting order; returns its remaining size
+    pub fn cancel(&mut self, order_id: &str) -> Option<f64> {
+        let (side, price) = self.index.remove(order_id)?;
+        let levels = self.levels_mut(side);
+        let queue = levels.get_mut(&price)?;
+        let position = queue.iter().position(|o| o.order_id == order_id)?;
+        let order = queue.remove(position)?;
+        if queue.is_empty() {
+            levels.remove(&price);
+        }
+        Some(order.remaining)
+    }
+
+    /// Liquidity an order could take, ignoring self-match prevention
+    fn crossing_size(&self, side: Side, limit: Option<i64>, owner: &str, policy: SelfMatchPolicy) -> f64 {
+        let crosses = |price: i64| match (side, limit) {
+            (_, None) => true,
+            (Side::Buy, Some(limit)) => price <= limit,
+            (Side::Sell, Some(limit)) => price >= limit,
+        };
+        self.levels(side.opposite())
+            .iter()
+            .filter(|(price, _)| crosses(**price))
+            .flat_map(|(_, queue)| queue.iter())
+            .filter(|o| policy == SelfMatchPolicy::Allow || o.owner != owner)
+            .map(|o| o.remaining)
+            .sum()
+    }
+
+    fn rest(&mut self, order_id: OrderId, owner: String, side: Side, price: f64, size: f64) {
+        let ticks = self.ticks(price);
+        self.index.insert(order_id.clone(), (side, ticks));
+        self.levels_mut(side).entry(ticks).or_default().push_back(RestingOrder {
+            order_id,
+            owner,
+            price,
+            remaining: size,
+        });
+    }
+
+    /// Match an incoming order and rest any remainder allowed by its time in force
+    #[allow(clippy::too_many_arguments)]
+    fn submit(
+        &mut self,
+        order_id: OrderId,
+        owner: &str,
+        side: Side,
+        limit: Option<f64>,
+        size: f64,
+        time_in_force: TimeInForce,
+        policy: SelfMatchPolicy,
+    ) -> MatchResult {
+        let limit_ticks = limit.map(|p| self.ticks(p));
+        let mut result = MatchResult {
+            order_id: order_id.clone(),
+            status: OrderStatus::Cancelled,
+            filled: 0.0,
+            resting: 0.0,
+            executions: Vec::new(),
+            self_match_cancels: Vec::new(),
+        };
+
+        if time_in_force == TimeInForce::FOK
+            && self.crossing_size(side, limit_ticks, owner, policy) + SIZE_EPSILON < size
+        {
+            return result;
+        }
+
+        let now = clock::now();
+        let mut remaining = size;
+        let mut stop = false;
+        while remaining > SIZE_EPSILON && !stop {
+            let opposite = side.opposite();
+            let best = match opposite {
+                Side::Buy => self.bids.keys().next_back().copied(),
+                Side::Sell => self.asks.keys().next().copied(),
+            };
+            let Some(price) = best else { break };
+            let crosses = match (side, limit_ticks) {
+                (_, None) => true,
+                (Side::Buy, Some(limit)) => price <= limit,
+                (Side::Sell, Some(limit)) => price >= limit,
+            };
+            if !crosses {
+                break;
+            }
+
+            let levels = match opposite {
+                Side::Buy => &mut self.bids,
+                Side::Sell => &mut self.asks,
+            };
+            let queue = levels.get_mut(&price).expect("best level exists");
+            let maker = queue.front_mut().expect("levels are never empty");
+
+            if maker.owner == owner && policy != SelfMatchPolicy::Allow {
+                if matches!(policy, SelfMatchPolicy::CancelResting | SelfMatchPolicy::CancelBoth) {
+                    let cancelled = queue.pop_front().expect("front exists");
+                    if queue.is_empty() {
+                        levels.remove(&price);
+                    }
+                    self.index.remove(&cancelled.order_id);
+                    result.self_match_cancels.push(cancelled.order_id);
+                }
+                stop = matches!(policy, SelfMatchPolicy::CancelIncoming | SelfMatchPolicy::CancelBoth);
+                continue;
+            }
+
+            let traded = remaining.min(maker.remaining);
+            maker.remaining -= traded;
+            remaining -= traded;
+            result.executions.push(Execution {
+                market: self.market.clone(),
+                price: maker.price,
+                size: traded,
+                taker_side: side,
+                taker_order_id: order_id.clone(),
+                taker_owner: owner.to_string(),
+                maker_order_id: maker.order_id.clone(),
+                maker_owner: maker.owner.clone(),
+                timestamp: now,
+            });
+
+            if maker.remaining <= SIZE_EPSILON {
+                let filled = queue.pop_front().expect("front exists");
+                if queue.is_empty() {
+                    levels.remove(&price);
+                }
+                self.index.remove(&filled.order_id);
+            }
+        }
+
+        result.filled = size - remaining;
+        let rests = !stop
+            && remaining > SIZE_EPSILON
+            && matches!(time_in_force, TimeInForce::GTC | TimeInForce::GTD)
+            && limit.is_some();
+        if rests {
+            self.rest(order_id, owner.to_string(), side, limit.unwrap_or_default(), remaining);
+            result.resting = remaining;
+        }
+
+        result.status = if remaining <= SIZE_EPSILON {
+            OrderStatus::Filled
+        } else if result.filled > SIZE_EPSILON {
+            OrderStatus::PartiallyFilled
+        } else if rests {
+            OrderStatus::Acknowledged
+        } else {
+            OrderStatus::Cancelled
+        };
+        result
+    }
+}
+
+/// Multi-market matching engine with per-owner fill queues
+#[derive(Debug, Clone)]
+pub struct MatchingEngine {
+    config: MatchingConfig,
+    books: HashMap<MarketId, OrderBook>,
+    /// Resting order -> market
+    order_markets: HashMap<OrderId, MarketId>,
+    /// Fills not yet taken, by owner
+    fills: HashMap<String, Vec<Fill>>,
+    next_order_id: u64,
+}
+
+impl MatchingEngine {
+    pub fn new(config: MatchingConfig) -> Self {
+        Self {
+            config,
+            books: HashMap::new(),
+            order_markets: HashMap::new(),
+            fills: HashMap::new(),
+            next_order_id: 1,
+        }
+    }
+
+    pub fn config(&self) -> &MatchingConfig {
+        &self.config
+    }
+
+    /// Book of a market, if any order was ever submitted to it
+    pub fn book(&self, market: &str) -> Option<&OrderBook> {
+        self.books.get(market)
+    }
+
+    /// Match an order for `owner`
+    ///
+    /// Limit orders need a price and rest their remainder unless IOC or
+    /// FOK; market orders sweep the book and never rest. The order's `id`
+    /// is used if set, otherwise one is assigned. Fills of both sides are
+    /// queued for [`take_fills`](Self::take_fills).
+    pub fn submit(&mut self, owner: &str, order: &Order) -> StrategyResult<MatchResult> {
+        if !order.size.is_finite() || order.size <= 0.0 {
+            return Err(StrategyError::InvalidParameter(format!("Order size must be positive: {}", order.size)));
+        }
+        let limit = match order.order_type {
+            OrderType::Limit => match order.price {
+                Some(price) if price > 0.0 => Some(price),
+                _ => return Err(StrategyError::InvalidParameter("Limit order needs a positive price".to_string())),
+            },
+            OrderType::Market => None,
+            other => {
+                return Err(StrategyError::InvalidParameter(format!("{:?} orders are not matched", other)));
+            }
+        };
+
+        let order_id = match &order.id {
+            Some(id) if self.order_markets.contains_key(id) => {
+                return Err(StrategyError::InvalidParameter(format!("Duplicate order id: {}", id)));
+            }
+            Some(id) => id.clone(),
+            None => {
+                let id = format!("book_{}", self.next_order_id);
+                self.next_order_id += 1;
+                id
+            }
+        };
+
+        let tick_size = self.config.tick_size;
+        let book = self.books.entry(order.market.clone()).or_insert_with(|| OrderBook::new(&order.market, tick_size));
+        let result = book.submit(
+            order_id.clone(),
+            owner,
+            order.side,
+            limit,
+            order.size,
+            order.time_in_force,
+            self.config.self_match,
+        );
+
+        let filled_makers: Vec<OrderId> = result
+            .executions
+            .iter()
+            .filter(|e| book.resting_size(&e.maker_order_id).is_none())
+            .map(|e| e.maker_order_id.clone())
+            .collect();
+        for order_id in result.self_match_cancels.iter().chain(&filled_makers) {
+            self.order_markets.remove(order_id);
+        }
+        for execution in &result.executions {
+            self.queue_fills(execution);
+        }
+        if result.resting > 0.0 {
+            self.order_markets.insert(order_id, order.market.clone());
+        }
+        Ok(result)
+    }
+
+    fn queue_fills(&mut self, execution: &Execution) {
+        let notional = execution.price * execution.size;
+        let fill = |order_id: &OrderId, side: Side, fee_bps: f64| Fill {
+            order_id: order_id.clone(),
+            market: execution.market.clone(),
+            price: execution.price,
+            size: execution.size,
+            side,
+            fee: notional * fee_bps / 10000.0,
+            timestamp: execution.timestamp,
+        };
+        let maker = fill(&execution.maker_order_id, execution.taker_side.opposite(), self.config.maker_fee_bps);
+        let taker = fill(&execution.taker_order_id, execution.taker_side, self.config.taker_fee_bps);
+        self.fills.entry(execution.maker_owner.clone()).or_default().push(maker);
+        self.fills.entry(execution.taker_owner.clone()).or_default().push(taker);
+    }
+
+    /// Cancel a resting order; returns its unfilled size
+    pub fn cancel(&mut self, order_id: &str) -> StrategyResult<f64> {
+        let market = self.order_markets.remove(order_id).ok_or_else(|| StrategyError::OrderNotFound(order_id.to_string()))?;
+        self.books
+            .get_mut(&market)
+            .and_then(|book| book.cancel(order_id))
+            .ok_or_else(|| StrategyError::OrderNotFound(order_id.to_string()))
+    }
+
+    /// Remaining size of a resting order
+    pub fn resting_size(&self, order_id: &str) -> Option<f64> {
+        let market = self.order_markets.get(order_id)?;
+        self.books.get(market)?.resting_size(order_id)
+    }
+
+    /// Take the fills queued for an owner, oldest first
+    pub fn take_fills(&mut self, owner: &str) -> Vec<Fill> {
+        self.fills.remove(owner).unwrap_or_default()
+    }
+
+    /// Replace `owner`'s resting orders in the tick's market with one bid and
+    /// one ask at the tick's touch, e.g. to give strategies a book to trade
+    /// against from recorded ticks
+    pub fn seed_from_tick(&mut self, owner: &str, tick: &MarketTick) -> StrategyResult<()> {
+        let stale: Vec<OrderId> = self
+            .order_markets
+            .iter()
+            .filter(|(_, market)| **market == tick.market)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for order_id in stale {
+            if self.books.get(&tick.market).and_then(|book| book.owner(&order_id)) == Some(owner) {
+                self.cancel(&order_id)?;
+            }
+        }
+
+        let quotes = [(Side::Buy, tick.bid, tick.bid_size), (Side::Sell, tick.ask, tick.ask_size)];
+        for (side, price, size) in quotes {
+            if let (Some(price), Some(size)) = (price, size) {
+                if price > 0.0 && size > 0.0 {
+                    let order =
+                        Order { market: tick.market.clone(), side, price: Some(price), size, ..Default::default() };
+                    self.submit(owner, &order)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for MatchingEngine {
+    fn default() -> Self {
+        Self::new(MatchingConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(side: Side, price: f64, size: f64) -> Order {
+        Order { market: "m".to_string(), side, price: Some(price), size, ..Default::default() }
+    }
+
+    #[test]
+    fn test_price_time_priority_and_partial_fills() {
+        let mut engine = MatchingEngine::default();
+        let first = engine.submit("a", &limit(Side::Sell, 0.55, 5.0)).unwrap();
+        let second = engine.submit("b", &limit(Side::Sell, 0.55, 5.0)).unwrap();
+        let better = engine.submit("c", &limit(Side::Sell, 0.54, 2.0)).unwrap();
+        assert_eq!(first.status, OrderStatus::Acknowledged);
+
+        let result = engine.submit("taker", &limit(Side::Buy, 0.55, 8.0)).unwrap();
+        assert_eq!(result.status, OrderStatus::Filled);
+        let makers: Vec<(&str, f64, f64)> =
+            result.executions.iter().map(|e| (e.maker_order_id.as_str(), e.price, e.size)).collect();
+        assert_eq!(
+            makers,
+            vec![
+                (better.order_id.as_str(), 0.54, 2.0),
+                (first.order_id.as_str(), 0.55, 5.0),
+                (second.order_id.as_str(), 0.55, 1.0)
+            ]
+        );
+        assert_eq!(engine.resting_size(&second.order_id), Some(4.0));
+        assert_eq!(engine.resting_size(&first.order_id), None);
+
+        let fills = engine.take_fills("b");
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].side, fills[0].size), (Side::Sell, 1.0));
+        assert_eq!(engine.take_fills("taker").len(), 3);
+        assert!(engine.take_fills("taker").is_empty());
+
+        // Remainder of a limit order rests; IOC remainder is dropped
+        let result = engine.submit("taker", &limit(Side::Buy, 0.56, 10.0)).unwrap();
+        assert_eq!((result.status, result.filled, result.resting), (OrderStatus::PartiallyFilled, 4.0, 6.0));
+        assert_eq!(engine.book("m").unwrap().best_bid(), Some((0.56, 6.0)));
+        let ioc = Order { time_in_force: TimeInForce::IOC, ..limit(Side::Sell, 0.50, 10.0) };
+        let result = engine.submit("d", &ioc).unwrap();
+        assert_eq!((result.filled, result.resting), (6.0, 0.0));
+        assert!(engine.book("m").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fok_and_market_orders() {
+        let mut engine = MatchingEngine::default();
+        engine.submit("a", &limit(Side::Buy, 0.40, 3.0)).unwrap();
+        engine.submit("a", &limit(Side::Buy, 0.39, 3.0)).unwrap();
+
+        let fok = Order { time_in_force: TimeInForce::FOK, ..limit(Side::Sell, 0.39, 7.0) };
+        let result = engine.submit("b", &fok).unwrap();
+        assert_eq!(result.status, OrderStatus::Cancelled);
+        assert!(result.executions.is_empty());
+
+        let market = Order { order_type: OrderType::Market, price: None, ..limit(Side::Sell, 0.0, 7.0) };
+        let result = engine.submit("b", &market).unwrap();
+        assert_eq!((result.status, result.filled, result.resting), (OrderStatus::PartiallyFilled, 6.0, 0.0));
+
+        let stop = Order { order_type: OrderType::Stop, ..limit(Side::Sell, 0.39, 1.0) };
+        assert!(engine.submit("b", &stop).is_err());
+        assert!(engine.cancel("book_1").is_err());
+    }
+
+    #[test]
+    fn test_self_match_policies() {
+        let run = |policy: SelfMatchPolicy| {
+            let mut engine = MatchingEngine::new(MatchingConfig { self_match: policy, ..Default::default() });
+            let own = engine.submit("mm", &limit(Side::Sell, 0.50, 5.0)).unwrap();
+            engine.submit("other", &limit(Side::Sell, 0.51, 5.0)).unwrap();
+            let result = engine.submit("mm", &limit(Side::Buy, 0.51, 5.0)).unwrap();
+            (result, engine.resting_size(&own.order_id))
+        };
+
+        let (result, own) = run(SelfMatchPolicy::Allow);
+        assert_eq!((result.executions[0].maker_owner.as_str(), result.filled, own), ("mm", 5.0, None));
+
+        let (result, own) = run(SelfMatchPolicy::CancelResting);
+        assert_eq!(result.self_match_cancels.len(), 1);
+        assert_eq!((result.executions[0].maker_owner.as_str(), result.filled, own), ("other", 5.0, None));
+
+        let (result, own) = run(SelfMatchPolicy::CancelIncoming);
+        assert_eq!((result.status, result.resting, own), (OrderStatus::Cancelled, 0.0, Some(5.0)));
+
+        let (result, own) = run(SelfMatchPolicy::CancelBoth);
+        assert_eq!((result.status, result.self_match_cancels.len(), own), (OrderStatus::Cancelled, 1, None));
+    }
+}
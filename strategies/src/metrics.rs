@@ -1,5 +1,6 @@
 //! Strategy metrics for monitoring and analysis
 
+use ag_risk::clock;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -46,7 +47,7 @@ impl StrategyMetric {
         labels: HashMap<String, String>,
     ) -> Self {
         Self {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             strategy_id,
             metric_type: MetricType::Counter,
             metric_name: name,
@@ -63,7 +64,7 @@ impl StrategyMetric {
         labels: HashMap<String, String>,
     ) -> Self {
         Self {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             strategy_id,
             metric_type: MetricType::Gauge,
             metric_name: name,
@@ -80,7 +81,7 @@ impl StrategyMetric {
         labels: HashMap<String, String>,
     ) -> Self {
         Self {
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             strategy_id,
             metric_type: MetricType::Histogram,
             metric_name: name,
@@ -128,6 +129,37 @@ pub mod metric_names {
     /// Signal confidence
     pub const SIGNAL_CONFIDENCE: &str = "strategy.signal_confidence";
 
+    /// Fill markout in bps (labels: market, horizon_ms)
+    pub const MARKOUT_BPS: &str = "strategy.markout_bps";
+
+    /// Spread multiplier applied by the toxicity monitor
+    pub const TOXICITY_SPREAD_MULTIPLIER: &str = "strategy.toxicity_spread_multiplier";
+
+    /// Number of times quotes were pulled due to toxic flow
+    pub const QUOTES_PULLED: &str = "strategy.quotes_pulled";
+
+    /// Effective requote interval in milliseconds (adaptive market making)
+    pub const REQUOTE_INTERVAL_MS: &str = "strategy.requote_interval_ms";
+
+    /// Z-score of the spread between two markets (pairs trading; labels:
+    /// market_a, market_b)
+    pub const PAIR_ZSCORE: &str = "strategy.pair_zscore";
+
+    /// Market ticks left before warm-up completes (0 once trading is allowed)
+    pub const WARMUP_REMAINING: &str = "strategy.warmup_remaining";
+
+    /// Callback processing time in milliseconds (label: quantile)
+    pub const EVENT_PROCESSING_MS: &str = "strategy.event_processing_ms";
+
+    /// Events queued behind each handled event (label: quantile)
+    pub const EVENT_QUEUE_DEPTH: &str = "strategy.event_queue_depth";
+
+    /// Events handled since the last flush
+    pub const EVENTS_HANDLED: &str = "strategy.events_handled";
+
+    /// Fraction of wall time spent in callbacks since the last flush
+    pub const LOOP_UTILIZATION: &str = "strategy.loop_utilization";
+
     /// Fill rate (fills / orders)
     pub const FILL_RATE: &str = "strategy.fill_rate";
 
@@ -206,6 +238,59 @@ impl MetricBuilder {
         )
     }
 
+    /// Build a fill markout metric
+    pub fn markout(&self, market_id: &str, horizon_ms: u64, bps: f64) -> StrategyMetric {
+        let mut labels = HashMap::new();
+        labels.insert("market".to_string(), market_id.to_string());
+        labels.insert("horizon_ms".to_string(), horizon_ms.to_string());
+
+        StrategyMetric::histogram(
+            self.strategy_id.clone(),
+            metric_names::MARKOUT_BPS.to_string(),
+            bps,
+            labels,
+        )
+    }
+
+    /// Build a toxicity spread multiplier metric
+    pub fn toxicity_spread_multiplier(&self, market_id: &str, multiplier: f64) -> StrategyMetric {
+        let mut labels = HashMap::new();
+        labels.insert("market".to_string(), market_id.to_string());
+
+        StrategyMetric::gauge(
+            self.strategy_id.clone(),
+            metric_names::TOXICITY_SPREAD_MULTIPLIER.to_string(),
+            multiplier,
+            labels,
+        )
+    }
+
+    /// Build a requote interval metric
+    pub fn requote_interval(&self, market_id: &str, interval_ms: f64) -> StrategyMetric {
+        let mut labels = HashMap::new();
+        labels.insert("market".to_string(), market_id.to_string());
+
+        StrategyMetric::gauge(
+            self.strategy_id.clone(),
+            metric_names::REQUOTE_INTERVAL_MS.to_string(),
+            interval_ms,
+            labels,
+        )
+    }
+
+    /// Build a quotes pulled metric
+    pub fn quotes_pulled(&self, market_id: &str) -> StrategyMetric {
+        let mut labels = HashMap::new();
+        labels.insert("market".to_string(), market_id.to_string());
+
+        StrategyMetric::counter(
+            self.strategy_id.clone(),
+            metric_names::QUOTES_PULLED.to_string(),
+            1.0,
+            labels,
+        )
+    }
+
     /// Build an order filled metric
     pub fn order_filled(&self, market_id: &str) -> StrategyMetric {
         let mut labels = HashMap::new();
@@ -0,0 +1,358 @@
+//! Strategy registry and dynamic plugin loading
+//!
+//! Strategies are registered by name in a [`StrategyRegistry`] and created
+//! from parameters and markets at runtime. Proprietary strategies can ship as
+//! `cdylib` plugins exporting a [`PluginDeclaration`] via
+//! [`declare_strategy_plugin!`]; with the `plugins` feature the registry can
+//! load every plugin from a directory without recompiling the host binary.
+//!
+//! Plugins exchange Rust trait objects with the host, so they must be built
+//! with the same compiler version and the same `ag-strategies` version.
+//! Both are checked at load time.
+//!
+//! # Writing a plugin
+//!
+//! ```rust,ignore
+//! // Cargo.toml: crate-type = ["cdylib"], depends on ag-strategies
+//! use ag_strategies::plugin::PluginRegistrar;
+//! use ag_strategies::declare_strategy_plugin;
+//!
+//! fn register(registrar: &mut dyn PluginRegistrar) {
+//!     registrar.register_strategy(
+//!         "my_alpha",
+//!         Box::new(|params, markets| Ok(Box::new(MyAlpha::new(params, markets)?))),
+//!     );
+//! }
+//!
+//! declare_strategy_plugin!(register);
+//! ```
+
+use crate::r#impl::{
+    CrossMarketArbConfig, CrossMarketArbStrategy, MarketMakerConfig, MarketMakerStrategy, PairsTradingConfig,
+    PairsTradingStrategy,
+};
+use crate::{Strategy, StrategyError, StrategyParams, StrategyResult};
+use std::collections::HashMap;
+
+/// Plugin ABI version; bumped whenever [`PluginDeclaration`] or
+/// [`PluginRegistrar`] change shape
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Version of ag-strategies the host/plugin was compiled against
+pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Name of the static symbol exported by plugins
+pub const PLUGIN_SYMBOL: &[u8] = b"AG_STRATEGY_PLUGIN\0";
+
+/// Creates a strategy instance from parameters and subscribed markets
+pub type StrategyFactory =
+    Box<dyn Fn(&StrategyParams, &[String]) -> StrategyResult<Box<dyn Strategy>> + Send + Sync>;
+
+/// Sink for strategy factories provided by a plugin
+pub trait PluginRegistrar {
+    /// Register a strategy factory under `name`
+    fn register_strategy(&mut self, name: &str, factory: StrategyFactory);
+}
+
+/// Static declaration exported by every plugin
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// Must equal [`PLUGIN_API_VERSION`]
+    pub api_version: u32,
+    /// Must equal the host's [`CORE_VERSION`]
+    pub core_version: &'static str,
+    /// Must equal the host's [`RUSTC_VERSION`]
+    pub rustc_version: &'static str,
+    /// Registration entry point
+    pub register: fn(&mut dyn PluginRegistrar),
+}
+
+/// Declare a strategy plugin entry point
+///
+/// Expands to the exported `AG_STRATEGY_PLUGIN` static the loader looks for.
+#[macro_export]
+macro_rules! declare_strategy_plugin {
+    ($register:expr) => {
+        #[no_mangle]
+        pub static AG_STRATEGY_PLUGIN: $crate::plugin::PluginDeclaration =
+            $crate::plugin::PluginDeclaration {
+                api_version: $crate::plugin::PLUGIN_API_VERSION,
+                core_version: $crate::plugin::CORE_VERSION,
+                rustc_version: $crate::plugin::RUSTC_VERSION,
+                register: $register,
+            };
+    };
+}
+
+/// rustc version the current build was compiled with (set by build.rs)
+pub const RUSTC_VERSION: &str = env!("AG_RUSTC_VERSION");
+
+/// Registry of named strategy factories
+#[derive(Default)]
+pub struct StrategyRegistry {
+    factories: HashMap<String, StrategyFactory>,
+
+    /// Loaded plugin libraries; kept alive for as long as the registry so
+    /// factory closures and strategy vtables stay valid
+    #[cfg(feature = "plugins")]
+    libraries: Vec<libloading::Library>,
+}
+
+impl StrategyRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in strategies
+    ///
+    /// * `market_maker` - one market, params as [`MarketMakerConfig::from_params`]
+    /// * `cross_market_arb` - two markets, params as [`CrossMarketArbConfig::from_params`]
+    /// * `pairs_trading` - two markets, params as [`PairsTradingConfig::from_params`]
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            "market_maker",
+            Box::new(|params, markets| {
+                let [market] = markets else {
+                    return Err(StrategyError::InvalidParameter(
+                        "market_maker requires exactly one market".to_string(),
+                    ));
+                };
+                Ok(Box::new(MarketMakerStrategy::new(
+                    market.clone(),
+                    MarketMakerConfig::from_params(params),
+                )))
+            }),
+        );
+        registry.register(
+            "cross_market_arb",
+            Box::new(|params, markets| {
+                let [market_a, market_b] = markets else {
+                    return Err(StrategyError::InvalidParameter(
+                        "cross_market_arb requires exactly two markets".to_string(),
+                    ));
+                };
+                Ok(Box::new(CrossMarketArbStrategy::new(
+                    market_a.clone(),
+                    market_b.clone(),
+                    CrossMarketArbConfig::from_params(params),
+                )))
+            }),
+        );
+        registry.register(
+            "pairs_trading",
+            Box::new(|params, markets| {
+                let [market_a, market_b] = markets else {
+                    return Err(StrategyError::InvalidParameter(
+                        "pairs_trading requires exactly two markets".to_string(),
+                    ));
+                };
+                Ok(Box::new(PairsTradingStrategy::new(
+                    market_a.clone(),
+                    market_b.clone(),
+                    PairsTradingConfig::from_params(params),
+                )))
+            }),
+        );
+        registry
+    }
+
+    /// Register a factory, replacing any existing one with the same name
+    pub fn register(&mut self, name: impl Into<String>, factory: StrategyFactory) {
+        self.factories.insert(name.into(), factory);
+    }
+
+    /// Whether a strategy name is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Registered strategy names
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.factories.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Create a strategy by name
+    pub fn create(
+        &self,
+        name: &str,
+        params: &StrategyParams,
+        markets: &[String],
+    ) -> StrategyResult<Box<dyn Strategy>> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| StrategyError::ConfigError(format!("Unknown strategy: {}", name)))?;
+        factory(params, markets)
+    }
+
+    /// Load a single plugin library and register its strategies
+    ///
+    /// Returns the names registered by the plugin.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialisers and trusts the exported
+    /// declaration. Only load plugins from trusted locations. Strategies
+    /// created from the plugin must be dropped before this registry.
+    #[cfg(feature = "plugins")]
+    pub unsafe fn load_plugin(&mut self, path: impl AsRef<std::path::Path>) -> StrategyResult<Vec<String>> {
+        let path = path.as_ref();
+        let library = libloading::Library::new(path).map_err(|e| {
+            StrategyError::InitializationFailed(format!("Failed to load plugin {}: {}", path.display(), e))
+        })?;
+
+        let declaration = library
+            .get::<*const PluginDeclaration>(PLUGIN_SYMBOL)
+            .map_err(|e| {
+                StrategyError::InitializationFailed(format!(
+                    "{} is not a strategy plugin: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+            .read();
+
+        if declaration.api_version != PLUGIN_API_VERSION
+            || declaration.core_version != CORE_VERSION
+            || declaration.rustc_version != RUSTC_VERSION
+        {
+            return Err(StrategyError::InitializationFailed(format!(
+                "Plugin {} is incompatible (api {}, ag-strategies {}, rustc {}; host expects api {}, ag-strategies {}, rustc {})",
+                path.display(),
+                declaration.api_version,
+                declaration.core_version,
+                declaration.rustc_version,
+                PLUGIN_API_VERSION,
+                CORE_VERSION,
+                RUSTC_VERSION,
+            )));
+        }
+
+        let mut collector = Collector::default();
+        (declaration.register)(&mut collector);
+
+        let names: Vec<String> = collector.factories.iter().map(|(name, _)| name.clone()).collect();
+        for (name, factory) in collector.factories {
+            tracing::info!("Registered plugin strategy {} from {}", name, path.display());
+            self.factories.insert(name, factory);
+        }
+        self.libraries.push(library);
+
+        Ok(names)
+    }
+
+    /// Load every plugin library (`.so` / `.dylib` / `.dll`) in a directory
+    ///
+    /// Returns the names registered across all plugins.
+    ///
+    /// # Safety
+    ///
+    /// See [`load_plugin`](Self::load_plugin).
+    #[cfg(feature = "plugins")]
+    pub unsafe fn load_dir(&mut self, dir: impl AsRef<std::path::Path>) -> StrategyResult<Vec<String>> {
+        let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(dir.as_ref())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+
+        let mut names = Vec::new();
+        for path in paths {
+            names.extend(self.load_plugin(&path)?);
+        }
+        Ok(names)
+    }
+}
+
+/// Registrar collecting a plugin's factories before they are committed
+#[cfg(feature = "plugins")]
+#[derive(Default)]
+struct Collector {
+    factories: Vec<(String, StrategyFactory)>,
+}
+
+#[cfg(feature = "plugins")]
+impl PluginRegistrar for Collector {
+    fn register_strategy(&mut self, name: &str, factory: StrategyFactory) {
+        self.factories.push((name.to_string(), factory));
+    }
+}
+
+impl PluginRegistrar for StrategyRegistry {
+    fn register_strategy(&mut self, name: &str, factory: StrategyFactory) {
+        self.register(name, factory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(registrar: &mut dyn PluginRegistrar) {
+        registrar.register_strategy(
+            "mm",
+            Box::new(|_params, markets| {
+                let market = markets.first().cloned().unwrap_or_default();
+                Ok(Box::new(MarketMakerStrategy::new(market, MarketMakerConfig::default())))
+            }),
+        );
+    }
+
+    #[test]
+    fn test_registry_create() {
+        let mut registry = StrategyRegistry::new();
+        register(&mut registry);
+
+        assert!(registry.contains("mm"));
+        assert_eq!(registry.names(), vec!["mm".to_string()]);
+
+        let strategy = registry
+            .create("mm", &StrategyParams::new(), &["m1".to_string()])
+            .unwrap();
+        assert_eq!(strategy.metadata().name, "MarketMaker");
+        assert!(registry.create("missing", &StrategyParams::new(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_builtins() {
+        let registry = StrategyRegistry::with_builtins();
+        assert_eq!(
+            registry.names(),
+            vec![
+                "cross_market_arb".to_string(),
+                "market_maker".to_string(),
+                "pairs_trading".to_string()
+            ]
+        );
+
+        let mut params = StrategyParams::new();
+        params.set("target_spread_bps".to_string(), "30".to_string());
+        assert!(registry.create("market_maker", &params, &["m1".to_string()]).is_ok());
+        assert!(registry.create("cross_market_arb", &params, &["m1".to_string()]).is_err());
+        let pair = ["m1".to_string(), "m2".to_string()];
+        assert_eq!(registry.create("pairs_trading", &params, &pair).unwrap().metadata().name, "PairsTrading");
+    }
+
+    #[test]
+    fn test_declaration_macro() {
+        mod plugin {
+            use super::register;
+            crate::declare_strategy_plugin!(register);
+        }
+
+        let declaration = &plugin::AG_STRATEGY_PLUGIN;
+        assert_eq!(declaration.api_version, PLUGIN_API_VERSION);
+        assert_eq!(declaration.core_version, CORE_VERSION);
+
+        let mut registry = StrategyRegistry::new();
+        (declaration.register)(&mut registry);
+        assert!(registry.contains("mm"));
+    }
+}
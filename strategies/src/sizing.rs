@@ -0,0 +1,414 @@
+//! Drawdown-aware position sizing
+//!
+//! A [`PositionSizer`] turns current equity, the drawdown from peak equity
+//! and a signal strength into an order size using one of two
+//! [`SizingMethod`]s:
+//!
+//! - **Fractional Kelly**: the Kelly fraction of equity for a continuous
+//!   bet, `edge / volatility²`, where the edge scales with signal strength,
+//!   times `kelly_fraction` (full Kelly is far too aggressive when the edge
+//!   is estimated)
+//! - **Volatility target**: the notional whose per-period volatility is
+//!   `target_volatility` of equity, scaled by signal strength
+//!
+//! Either way the notional shrinks linearly with drawdown, reaching zero at
+//! `max_drawdown`, and is capped at `max_notional_fraction` of equity.
+//!
+//! A notional cap alone lets size explode at low prices: 2% of equity at
+//! 0.02 buys shares paying out equity in full. With `max_payout_fraction`
+//! the shares are also capped so their payout (1 USD each) stays within
+//! that fraction of equity while the price is within `payout_price_band`
+//! of 0 (see `ag_risk::payout`).
+//!
+//! Size alone ignores what a trade does to the portfolio: adding to a
+//! correlated book adds more tail risk than an offsetting trade of the same
+//! notional. With `max_marginal_cvar_fraction` set,
+//! [`PositionSizer::cap_marginal_cvar`] shrinks a trade until the CVaR it
+//! adds (see [`StrategyContext::marginal_cvar`](crate::StrategyContext::marginal_cvar))
+//! stays within that fraction of equity.
+//!
+//! ```rust
+//! use ag_strategies::sizing::{DrawdownTracker, PositionSizer, SizingConfig, SizingInputs};
+//!
+//! let sizer = PositionSizer::new(SizingConfig::default());
+//! let mut drawdown = DrawdownTracker::new();
+//! drawdown.update(10_000.0);
+//!
+//! let size = sizer.size(&SizingInputs {
+//!     equity: 9_500.0,
+//!     drawdown: drawdown.update(9_500.0),
+//!     signal_strength: 0.8,
+//!     price: 0.45,
+//!     volatility: Some(0.04),
+//! });
+//! assert!(size > 0.0);
+//! ```
+
+use crate::types::StrategyParams;
+use ag_risk::payout;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How a signal is converted into a fraction of equity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizingMethod {
+    /// Fraction of the Kelly-optimal notional
+    #[default]
+    FractionalKelly,
+    /// Notional with a target per-period volatility
+    VolatilityTarget,
+}
+
+impl FromStr for SizingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kelly" | "fractional_kelly" => Ok(SizingMethod::FractionalKelly),
+            "vol_target" | "volatility_target" => Ok(SizingMethod::VolatilityTarget),
+            other => Err(format!("unknown sizing method: {}", other)),
+        }
+    }
+}
+
+/// Position sizing configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizingConfig {
+    /// Sizing method
+    #[serde(default)]
+    pub method: SizingMethod,
+
+    /// Fraction of full Kelly to bet (Kelly only)
+    pub kelly_fraction: f64,
+
+    /// Expected return per period at full signal strength (Kelly only)
+    pub max_edge: f64,
+
+    /// Target per-period volatility of the position as a fraction of
+    /// equity (volatility target only)
+    pub target_volatility: f64,
+
+    /// Per-period return volatility assumed when none is supplied
+    pub default_volatility: f64,
+
+    /// Drawdown from peak equity at which sizing reaches zero
+    pub max_drawdown: f64,
+
+    /// Maximum notional as a fraction of equity
+    pub max_notional_fraction: f64,
+
+    /// Maximum payout near price extremes as a fraction of equity
+    /// (None = uncapped)
+    #[serde(default)]
+    pub max_payout_fraction: Option<f64>,
+
+    /// Distance from 0 within which the payout cap applies
+    #[serde(default = "default_payout_price_band")]
+    pub payout_price_band: f64,
+
+    /// Maximum CVaR a single trade may add as a fraction of equity
+    /// (None = uncapped)
+    #[serde(default)]
+    pub max_marginal_cvar_fraction: Option<f64>,
+
+    /// Confidence level of the marginal CVaR cap
+    #[serde(default = "default_cvar_confidence")]
+    pub cvar_confidence: f64,
+}
+
+fn default_payout_price_band() -> f64 {
+    payout::DEFAULT_PRICE_BAND
+}
+
+fn default_cvar_confidence() -> f64 {
+    0.95
+}
+
+impl Default for SizingConfig {
+    fn default() -> Self {
+        Self {
+            method: SizingMethod::FractionalKelly,
+            kelly_fraction: 0.25,
+            max_edge: 0.01,
+            target_volatility: 0.005,
+            default_volatility: 0.05,
+            max_drawdown: 0.2,
+            max_notional_fraction: 0.1,
+            max_payout_fraction: None,
+            payout_price_band: payout::DEFAULT_PRICE_BAND,
+            max_marginal_cvar_fraction: None,
+            cvar_confidence: default_cvar_confidence(),
+        }
+    }
+}
+
+impl SizingConfig {
+    /// Build a config from strategy params, or None when `sizing_method`
+    /// (`kelly` or `vol_target`) is not set
+    ///
+    /// Tuned with `sizing_kelly_fraction`, `sizing_max_edge`,
+    /// `sizing_target_volatility`, `sizing_volatility`,
+    /// `sizing_max_drawdown`, `sizing_max_notional_fraction`,
+    /// `sizing_max_payout_fraction`, `sizing_payout_price_band`,
+    /// `sizing_max_marginal_cvar_fraction` and `sizing_cvar_confidence`.
+    pub fn from_params(params: &StrategyParams) -> Option<Self> {
+        let method = params.get_typed("sizing_method")?;
+        let defaults = Self::default();
+        Some(Self {
+            method,
+            kelly_fraction: params.get_typed("sizing_kelly_fraction").unwrap_or(defaults.kelly_fraction),
+            max_edge: params.get_typed("sizing_max_edge").unwrap_or(defaults.max_edge),
+            target_volatility: params
+                .get_typed("sizing_target_volatility")
+                .unwrap_or(defaults.target_volatility),
+            default_volatility: params.get_typed("sizing_volatility").unwrap_or(defaults.default_volatility),
+            max_drawdown: params.get_typed("sizing_max_drawdown").unwrap_or(defaults.max_drawdown),
+            max_notional_fraction: params
+                .get_typed("sizing_max_notional_fraction")
+                .unwrap_or(defaults.max_notional_fraction),
+            max_payout_fraction: params.get_typed("sizing_max_payout_fraction"),
+            payout_price_band: params
+                .get_typed("sizing_payout_price_band")
+                .unwrap_or(defaults.payout_price_band),
+            max_marginal_cvar_fraction: params.get_typed("sizing_max_marginal_cvar_fraction"),
+            cvar_confidence: params.get_typed("sizing_cvar_confidence").unwrap_or(defaults.cvar_confidence),
+        })
+    }
+}
+
+/// Inputs to a sizing decision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizingInputs {
+    /// Current equity
+    pub equity: f64,
+
+    /// Drawdown from peak equity (0.0 to 1.0)
+    pub drawdown: f64,
+
+    /// Signal strength; only the magnitude is used, clamped to 1.0
+    pub signal_strength: f64,
+
+    /// Price per unit of size (for a short, the price of the opposite
+    /// outcome bought instead)
+    pub price: f64,
+
+    /// Per-period return volatility (config default if None)
+    pub volatility: Option<f64>,
+}
+
+/// Converts equity, drawdown and signal strength into order sizes
+#[derive(Debug, Clone)]
+pub struct PositionSizer {
+    config: SizingConfig,
+}
+
+impl PositionSizer {
+    pub fn new(config: SizingConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &SizingConfig {
+        &self.config
+    }
+
+    /// Multiplier applied for a drawdown: 1.0 at the peak, 0.0 at
+    /// `max_drawdown` and beyond
+    pub fn drawdown_scale(&self, drawdown: f64) -> f64 {
+        if self.config.max_drawdown <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - drawdown.max(0.0) / self.config.max_drawdown).clamp(0.0, 1.0)
+    }
+
+    /// Notional to hold as a fraction of equity
+    pub fn notional_fraction(&self, inputs: &SizingInputs) -> f64 {
+        let strength = inputs.signal_strength.abs().min(1.0);
+        let volatility = inputs
+            .volatility
+            .filter(|v| *v > 0.0)
+            .unwrap_or(self.config.default_volatility);
+        if volatility <= 0.0 {
+            return 0.0;
+        }
+
+        let fraction = match self.config.method {
+            SizingMethod::FractionalKelly => {
+                self.config.kelly_fraction * strength * self.config.max_edge / (volatility * volatility)
+            }
+            SizingMethod::VolatilityTarget => strength * self.config.target_volatility / volatility,
+        };
+
+        (fraction * self.drawdown_scale(inputs.drawdown)).clamp(0.0, self.config.max_notional_fraction)
+    }
+
+    /// Order size (always non-negative; the side comes from the signal)
+    pub fn size(&self, inputs: &SizingInputs) -> f64 {
+        if inputs.equity <= 0.0 || inputs.price <= 0.0 {
+            return 0.0;
+        }
+        let size = self.notional_fraction(inputs) * inputs.equity / inputs.price;
+        match self.config.max_payout_fraction {
+            Some(fraction) => payout::cap_payout(
+                size,
+                inputs.price,
+                self.config.payout_price_band,
+                fraction * inputs.equity,
+            ),
+            None => size,
+        }
+    }
+
+    /// Largest size up to `size` whose marginal CVaR stays within
+    /// `max_marginal_cvar_fraction` of equity
+    ///
+    /// `marginal_cvar` returns the CVaR a trade of the given size adds, or
+    /// None without return history, in which case the size is unchanged.
+    /// CVaR is convex in the trade, so the sizes within budget form an
+    /// interval from zero and are found by bisection.
+    pub fn cap_marginal_cvar(&self, size: f64, equity: f64, marginal_cvar: impl Fn(f64) -> Option<f64>) -> f64 {
+        let Some(fraction) = self.config.max_marginal_cvar_fraction else {
+            return size;
+        };
+        let budget = fraction * equity.max(0.0);
+        match marginal_cvar(size) {
+            None => return size,
+            Some(impact) if impact <= budget => return size,
+            Some(_) => {}
+        }
+
+        let (mut low, mut high) = (0.0, size);
+        for _ in 0..CVAR_BISECTION_STEPS {
+            let mid = (low + high) / 2.0;
+            if marginal_cvar(mid).is_some_and(|impact| impact <= budget) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+/// Bisection steps of the marginal CVaR cap (precision of size / 2^30)
+const CVAR_BISECTION_STEPS: usize = 30;
+
+/// Tracks peak equity to measure drawdown
+#[derive(Debug, Clone, Default)]
+pub struct DrawdownTracker {
+    peak: Option<f64>,
+}
+
+impl DrawdownTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record current equity and return the drawdown from peak (0.0 to 1.0)
+    pub fn update(&mut self, equity: f64) -> f64 {
+        let peak = self.peak.map_or(equity, |p| p.max(equity));
+        self.peak = Some(peak);
+        if peak <= 0.0 {
+            return 0.0;
+        }
+        ((peak - equity) / peak).clamp(0.0, 1.0)
+    }
+
+    /// Highest equity seen
+    pub fn peak(&self) -> Option<f64> {
+        self.peak
+    }
+
+    /// Forget the peak (e.g. after capital is withdrawn)
+    pub fn reset(&mut self) {
+        self.peak = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(drawdown: f64, signal_strength: f64) -> SizingInputs {
+        SizingInputs {
+            equity: 10_000.0,
+            drawdown,
+            signal_strength,
+            price: 0.5,
+            volatility: Some(0.1),
+        }
+    }
+
+    #[test]
+    fn test_kelly_and_vol_target() {
+        let kelly = PositionSizer::new(SizingConfig {
+            max_edge: 0.02,
+            ..SizingConfig::default()
+        });
+        // 0.25 * 0.02 / 0.1² = 0.5, capped at 10% of equity
+        assert!((kelly.notional_fraction(&inputs(0.0, 1.0)) - 0.1).abs() < 1e-12);
+        // Weak signal: 0.25 * 0.1 * 0.02 / 0.01 = 0.05 -> $500 at 0.5 = 1000 contracts
+        assert!((kelly.size(&inputs(0.0, -0.1)) - 1000.0).abs() < 1e-9);
+
+        let vol = PositionSizer::new(SizingConfig {
+            method: SizingMethod::VolatilityTarget,
+            ..SizingConfig::default()
+        });
+        // 0.5 * 0.005 / 0.1 = 0.025 of equity
+        assert!((vol.notional_fraction(&inputs(0.0, 0.5)) - 0.025).abs() < 1e-12);
+
+        assert_eq!("vol_target".parse::<SizingMethod>(), Ok(SizingMethod::VolatilityTarget));
+        assert!("martingale".parse::<SizingMethod>().is_err());
+    }
+
+    #[test]
+    fn test_drawdown_scaling() {
+        let sizer = PositionSizer::new(SizingConfig {
+            method: SizingMethod::VolatilityTarget,
+            ..SizingConfig::default()
+        });
+        let full = sizer.size(&inputs(0.0, 1.0));
+        assert!((sizer.size(&inputs(0.1, 1.0)) - full / 2.0).abs() < 1e-9);
+        assert_eq!(sizer.size(&inputs(0.25, 1.0)), 0.0);
+
+        let mut tracker = DrawdownTracker::new();
+        assert_eq!(tracker.update(1000.0), 0.0);
+        assert!((tracker.update(900.0) - 0.1).abs() < 1e-12);
+        assert_eq!(tracker.update(1100.0), 0.0);
+        assert_eq!(tracker.peak(), Some(1100.0));
+    }
+
+    #[test]
+    fn test_payout_cap_near_zero() {
+        let sizer = PositionSizer::new(SizingConfig {
+            max_payout_fraction: Some(0.5),
+            ..SizingConfig::default()
+        });
+        let at = |price: f64| SizingInputs { price, ..inputs(0.0, 1.0) };
+
+        // 10% of equity: 2,000 shares at 0.50, 50,000 (5x equity in payout) at 0.02
+        assert!((sizer.size(&at(0.5)) - 2_000.0).abs() < 1e-9);
+        assert!((sizer.size(&at(0.02)) - 5_000.0).abs() < 1e-9);
+        // Outside the band only the notional cap applies
+        assert!((sizer.size(&at(0.08)) - 12_500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_marginal_cvar_cap() {
+        let sizer = PositionSizer::new(SizingConfig {
+            max_marginal_cvar_fraction: Some(0.01),
+            ..SizingConfig::default()
+        });
+        // Each contract adds $0.25 of CVaR beyond the first 100 (hedging ones)
+        let impact = |size: f64| Some((size - 100.0) * 0.25);
+
+        // $100 budget: up to 500 contracts
+        assert_eq!(sizer.cap_marginal_cvar(400.0, 10_000.0, impact), 400.0);
+        assert!((sizer.cap_marginal_cvar(1_000.0, 10_000.0, impact) - 500.0).abs() < 1e-3);
+        // Without return history the size is unchanged
+        assert_eq!(sizer.cap_marginal_cvar(1_000.0, 10_000.0, |_| None), 1_000.0);
+        // Uncapped by default
+        let uncapped = PositionSizer::new(SizingConfig::default());
+        assert_eq!(uncapped.cap_marginal_cvar(1_000.0, 10_000.0, impact), 1_000.0);
+    }
+}
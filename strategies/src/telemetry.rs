@@ -0,0 +1,143 @@
+//! OpenTelemetry export for strategy metrics (feature `otel`)
+//!
+//! Maps [`StrategyMetric`] values onto OpenTelemetry instruments on the
+//! global meter provider, so strategy PnL, positions and order counters end
+//! up in the same observability stack as the exec/storage traces.
+//!
+//! The meter provider itself is installed by the application (for example
+//! via `ag_exec::telemetry::init_tracing_otel`).
+
+use crate::metrics::{MetricType, StrategyMetric};
+use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge};
+use opentelemetry::{global, KeyValue};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Last observed gauge values, keyed by series (sorted attribute list)
+type GaugeSeries = Arc<Mutex<HashMap<Vec<(String, String)>, f64>>>;
+
+/// Exports buffered strategy metrics to OpenTelemetry
+///
+/// Counters become `f64` counters, histograms become `f64` histograms and
+/// gauges become observable gauges reporting the last value per label set.
+pub struct OtelMetricExporter {
+    meter: Meter,
+    counters: HashMap<String, Counter<f64>>,
+    histograms: HashMap<String, Histogram<f64>>,
+    gauges: HashMap<String, (ObservableGauge<f64>, GaugeSeries)>,
+}
+
+impl OtelMetricExporter {
+    /// Create an exporter on the `ag-strategies` meter
+    pub fn new() -> Self {
+        Self {
+            meter: global::meter("ag-strategies"),
+            counters: HashMap::new(),
+            histograms: HashMap::new(),
+            gauges: HashMap::new(),
+        }
+    }
+
+    /// Export a batch of metrics (e.g. `ctx.get_metrics_buffer()`)
+    pub fn export(&mut self, metrics: &[StrategyMetric]) {
+        for metric in metrics {
+            self.record(metric);
+        }
+    }
+
+    /// Record a single metric
+    pub fn record(&mut self, metric: &StrategyMetric) {
+        let series = series_key(metric);
+
+        match metric.metric_type {
+            MetricType::Counter => {
+                let meter = &self.meter;
+                let counter = self
+                    .counters
+                    .entry(metric.metric_name.clone())
+                    .or_insert_with(|| meter.f64_counter(metric.metric_name.clone()).init());
+                counter.add(metric.value, &to_attributes(&series));
+            }
+            MetricType::Histogram => {
+                let meter = &self.meter;
+                let histogram = self
+                    .histograms
+                    .entry(metric.metric_name.clone())
+                    .or_insert_with(|| meter.f64_histogram(metric.metric_name.clone()).init());
+                histogram.record(metric.value, &to_attributes(&series));
+            }
+            MetricType::Gauge => {
+                let meter = &self.meter;
+                let (_, values) = self
+                    .gauges
+                    .entry(metric.metric_name.clone())
+                    .or_insert_with(|| {
+                        let values: GaugeSeries = Arc::new(Mutex::new(HashMap::new()));
+                        let observed = values.clone();
+                        let gauge = meter
+                            .f64_observable_gauge(metric.metric_name.clone())
+                            .with_callback(move |observer| {
+                                for (series, value) in observed.lock().iter() {
+                                    observer.observe(*value, &to_attributes(series));
+                                }
+                            })
+                            .init();
+                        (gauge, values)
+                    });
+                values.lock().insert(series, metric.value);
+            }
+        }
+    }
+}
+
+impl Default for OtelMetricExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Build a stable series key from the strategy id and labels
+fn series_key(metric: &StrategyMetric) -> Vec<(String, String)> {
+    let mut series: Vec<(String, String)> = metric
+        .labels
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    series.push(("strategy_id".to_string(), metric.strategy_id.clone()));
+    series.sort();
+    series
+}
+
+fn to_attributes(series: &[(String, String)]) -> Vec<KeyValue> {
+    series
+        .iter()
+        .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_key_includes_strategy_and_is_sorted() {
+        let mut labels = HashMap::new();
+        labels.insert("market".to_string(), "m1".to_string());
+        labels.insert("side".to_string(), "buy".to_string());
+        let metric = StrategyMetric::counter("mm".to_string(), "x".to_string(), 1.0, labels);
+
+        let key = series_key(&metric);
+        assert_eq!(key.len(), 3);
+        assert_eq!(key[0].0, "market");
+        assert_eq!(key[2], ("strategy_id".to_string(), "mm".to_string()));
+    }
+
+    #[test]
+    fn test_export_without_provider_is_noop() {
+        let mut exporter = OtelMetricExporter::new();
+        let metric = StrategyMetric::gauge("mm".to_string(), "g".to_string(), 2.0, HashMap::new());
+        exporter.export(&[metric.clone(), metric]);
+        assert_eq!(exporter.gauges.len(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+//! Per-strategy timer scheduling
+//!
+//! Each strategy registered with a [`TimerConfig`] gets its own cadence.
+//! The coordinator keeps all deadlines in a [`TimerWheel`] and fires
+//! `on_timer` only for strategies whose deadline has passed, so a 100ms
+//! requote loop and an hourly rebalance can share one coordinator.
+
+use ag_sched::{Schedule, Scheduler};
+use chrono::{DateTime, Utc};
+
+/// Timer configuration for a single strategy
+///
+/// An [`ag_sched::IntervalSchedule`]: interval, jitter and wall-clock
+/// alignment.
+pub use ag_sched::IntervalSchedule as TimerConfig;
+
+/// Deadline-ordered timer schedule keyed by strategy ID
+#[derive(Debug, Default)]
+pub struct TimerWheel {
+    scheduler: Scheduler<String>,
+}
+
+impl TimerWheel {
+    /// Create an empty wheel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a strategy timer, first firing after one interval
+    pub fn schedule(&mut self, strategy_id: &str, config: TimerConfig, now: DateTime<Utc>) {
+        self.scheduler.schedule(strategy_id.to_string(), config, now);
+    }
+
+    /// Remove a strategy timer
+    pub fn cancel(&mut self, strategy_id: &str) -> Option<TimerConfig> {
+        match self.scheduler.cancel(strategy_id) {
+            Some(Schedule::Interval(config)) => Some(config),
+            _ => None,
+        }
+    }
+
+    /// Get a strategy's timer configuration
+    pub fn get(&self, strategy_id: &str) -> Option<&TimerConfig> {
+        match self.scheduler.get(strategy_id) {
+            Some(Schedule::Interval(config)) => Some(config),
+            _ => None,
+        }
+    }
+
+    /// Pop all strategies due at `now` and reschedule them
+    ///
+    /// Returned IDs are ordered by deadline. A strategy that fell behind by
+    /// several intervals fires once, not once per missed interval.
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        self.scheduler.due(now)
+    }
+
+    /// Earliest pending deadline, if any
+    pub fn next_deadline(&mut self) -> Option<DateTime<Utc>> {
+        self.scheduler.next_deadline()
+    }
+
+    /// Number of active timers
+    pub fn len(&self) -> usize {
+        self.scheduler.len()
+    }
+
+    /// Whether no timers are registered
+    pub fn is_empty(&self) -> bool {
+        self.scheduler.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::Duration;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(ms).unwrap()
+    }
+
+    #[test]
+    fn test_aligned_deadline() {
+        let config = TimerConfig::every(Duration::from_secs(3600)).aligned();
+        let now = at(3_600_000 * 10 + 1234);
+        assert_eq!(config.next_deadline(now), at(3_600_000 * 11));
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        let config = TimerConfig::every(Duration::from_millis(100))
+            .with_jitter(Duration::from_millis(50));
+        let now = at(0);
+        for _ in 0..100 {
+            let deadline = config.next_deadline(now);
+            assert!(deadline >= at(100) && deadline <= at(150));
+        }
+    }
+
+    #[test]
+    fn test_wheel_mixed_intervals() {
+        let mut wheel = TimerWheel::new();
+        wheel.schedule("fast", TimerConfig::every(Duration::from_millis(100)), at(0));
+        wheel.schedule("slow", TimerConfig::every(Duration::from_secs(3600)), at(0));
+
+        assert!(wheel.due(at(50)).is_empty());
+        assert_eq!(wheel.due(at(100)), vec!["fast".to_string()]);
+        assert_eq!(wheel.due(at(250)), vec!["fast".to_string()]);
+        assert_eq!(wheel.next_deadline(), Some(at(350)));
+        assert_eq!(wheel.due(at(3_600_000)), vec!["fast".to_string(), "slow".to_string()]);
+    }
+
+    #[test]
+    fn test_wheel_cancel_and_reschedule() {
+        let mut wheel = TimerWheel::new();
+        wheel.schedule("a", TimerConfig::every(Duration::from_millis(100)), at(0));
+        wheel.schedule("a", TimerConfig::every(Duration::from_millis(500)), at(0));
+        assert_eq!(wheel.len(), 1);
+        assert!(wheel.due(at(100)).is_empty());
+        assert_eq!(wheel.due(at(500)), vec!["a".to_string()]);
+
+        wheel.cancel("a");
+        assert!(wheel.is_empty());
+        assert_eq!(wheel.next_deadline(), None);
+    }
+}
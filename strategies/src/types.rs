@@ -1,11 +1,16 @@
 //! Core types for the strategy framework
 
+use ag_risk::clock;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
-pub use crate::{Strategy, StrategyError, StrategyResult};
+use crate::signals::{BarSet, Timeframe};
+
+#[cfg(feature = "runtime")]
+pub use crate::Strategy;
+pub use crate::{StrategyError, StrategyResult};
 
 /// Unique identifier for an order
 pub type OrderId = String;
@@ -16,6 +21,10 @@ pub type MarketId = String;
 /// Unique identifier for a venue
 pub type VenueId = String;
 
+/// Order tag naming the signal an order acts on, used to attribute PnL to
+/// signals
+pub const SIGNAL_TAG: &str = "signal";
+
 /// Order side (buy/sell)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Side {
@@ -23,6 +32,16 @@ pub enum Side {
     Sell,
 }
 
+impl Side {
+    /// The other side
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
 impl fmt::Display for Side {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -45,6 +64,48 @@ pub enum OrderType {
     StopLimit,
 }
 
+/// Price a pegged order follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PegReference {
+    /// Best bid
+    BestBid,
+    /// Best ask
+    BestAsk,
+    /// Midpoint of the touch
+    Mid,
+}
+
+/// Limit price pegged to the touch, kept there by the execution engine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Peg {
+    /// Reference price
+    pub reference: PegReference,
+    /// Ticks from the reference; positive is more aggressive
+    #[serde(default)]
+    pub offset_ticks: i32,
+    /// Worst price the peg may reach: a cap for buys, a floor for sells
+    #[serde(default)]
+    pub limit_price: Option<f64>,
+}
+
+impl Peg {
+    /// Peg to `reference` offset by `offset_ticks`
+    pub fn new(reference: PegReference, offset_ticks: i32) -> Self {
+        Self {
+            reference,
+            offset_ticks,
+            limit_price: None,
+        }
+    }
+
+    /// Never price beyond `limit_price`
+    pub fn with_limit(mut self, limit_price: f64) -> Self {
+        self.limit_price = Some(limit_price);
+        self
+    }
+}
+
 /// Time in force specification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TimeInForce {
@@ -104,6 +165,13 @@ pub struct Order {
     pub timestamp: DateTime<Utc>,
     /// Order status
     pub status: OrderStatus,
+    /// Free-form labels stored with the order (see [`SIGNAL_TAG`])
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Price peg; when set the execution engine prices the order from the
+    /// touch and reprices it as the touch moves
+    #[serde(default)]
+    pub peg: Option<Peg>,
 }
 
 impl Default for Order {
@@ -118,8 +186,80 @@ impl Default for Order {
             size: 0.0,
             time_in_force: TimeInForce::GTC,
             client_order_id: None,
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             status: OrderStatus::Pending,
+            tags: BTreeMap::new(),
+            peg: None,
+        }
+    }
+}
+
+impl Order {
+    /// Label the order
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Attribute the order to a signal
+    pub fn with_signal(self, signal_id: impl Into<String>) -> Self {
+        self.with_tag(SIGNAL_TAG, signal_id)
+    }
+
+    /// Peg the limit price to the touch (e.g. best bid + 1 tick)
+    pub fn with_peg(mut self, peg: Peg) -> Self {
+        self.peg = Some(peg);
+        self
+    }
+
+    /// Signal the order acts on, if tagged
+    pub fn signal(&self) -> Option<&str> {
+        self.tags.get(SIGNAL_TAG).map(String::as_str)
+    }
+}
+
+/// Order prebuilt for one market, stamped out per quote
+///
+/// What stays fixed between quotes (venue, market, order type, time in
+/// force, tags) is set up once; each quote clones it and fills in side,
+/// price and size. Orders are timestamped when submitted.
+#[derive(Debug, Clone)]
+pub struct OrderTemplate {
+    order: Order,
+}
+
+impl OrderTemplate {
+    /// Limit orders in `market` on `venue`
+    pub fn limit(venue: impl Into<VenueId>, market: impl Into<MarketId>, time_in_force: TimeInForce) -> Self {
+        Self {
+            order: Order {
+                venue: venue.into(),
+                market: market.into(),
+                order_type: OrderType::Limit,
+                time_in_force,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Label every order from the template
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.order.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Market the template trades
+    pub fn market(&self) -> &str {
+        &self.order.market
+    }
+
+    /// Order for `size` at `price`
+    pub fn order(&self, side: Side, price: f64, size: f64) -> Order {
+        Order {
+            side,
+            price: Some(price),
+            size,
+            ..self.order.clone()
         }
     }
 }
@@ -194,7 +334,7 @@ impl Position {
             unrealized_pnl: 0.0,
             realized_pnl: 0.0,
             value_usd: 0.0,
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
         }
     }
 
@@ -215,7 +355,7 @@ impl Position {
 }
 
 /// Market tick data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarketTick {
     /// Market identifier
     pub market: MarketId,
@@ -233,6 +373,9 @@ pub struct MarketTick {
     pub last: Option<f64>,
     /// 24h volume
     pub volume_24h: Option<f64>,
+    /// Feed sequence number, when the venue provides one
+    #[serde(default)]
+    pub sequence: Option<u64>,
 }
 
 impl MarketTick {
@@ -260,6 +403,32 @@ impl MarketTick {
     }
 }
 
+/// Trading status of a market, as reported by the market data feed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum MarketStatus {
+    /// Trading normally
+    Open,
+    /// Trading has stopped; the outcome is not known yet
+    Closed,
+    /// Outcome known: each long share is worth `payout` (1.0 if the market
+    /// resolved YES, 0.0 if NO)
+    Resolved {
+        payout: f64,
+    },
+}
+
+/// Market status message from the market data feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketStatusUpdate {
+    /// Market identifier
+    pub market: MarketId,
+    /// New status
+    pub status: MarketStatus,
+    /// Timestamp
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Historical market data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
@@ -271,6 +440,16 @@ pub struct MarketData {
     pub bars: Vec<OhlcvBar>,
 }
 
+impl MarketData {
+    /// Latest price: the last tick's trade price, else the last bar's close
+    pub fn last_price(&self) -> Option<f64> {
+        match self.ticks.last() {
+            Some(tick) => tick.last,
+            None => self.bars.last().map(|bar| bar.close),
+        }
+    }
+}
+
 /// OHLCV bar
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OhlcvBar {
@@ -336,6 +515,21 @@ pub trait SignalGenerator: Send + Sync {
 
     /// Get signal metadata
     fn metadata(&self) -> SignalMetadata;
+
+    /// Bar timeframes the generator consumes, e.g. a 1m trend filter and a
+    /// 5s entry trigger (empty = driven by `generate_signal` only)
+    fn timeframes(&self) -> Vec<Timeframe> {
+        Vec::new()
+    }
+
+    /// Generate a signal when a bar of `closed` closes
+    ///
+    /// `bars` holds the market's closed bars of every timeframe, as kept by
+    /// a `BarAggregator`. By default the closed timeframe's bars are passed
+    /// to `generate_signal`.
+    fn generate_bar_signal(&mut self, closed: Timeframe, bars: &BarSet) -> Signal {
+        self.generate_signal(&bars.market_data(closed))
+    }
 }
 
 /// Strategy metadata
@@ -351,6 +545,10 @@ pub struct StrategyMetadata {
     pub markets: Vec<String>,
     /// Required parameters
     pub required_params: Vec<String>,
+    /// Market ticks to receive before orders are allowed, so indicators
+    /// are primed (0 = trade from the first tick)
+    #[serde(default)]
+    pub warmup_ticks: usize,
 }
 
 /// Strategy parameters
@@ -406,13 +604,14 @@ mod tests {
     fn test_market_tick_calculations() {
         let tick = MarketTick {
             market: "market1".to_string(),
-            timestamp: Utc::now(),
+            timestamp: clock::now(),
             bid: Some(100.0),
             ask: Some(101.0),
             bid_size: Some(10.0),
             ask_size: Some(10.0),
             last: Some(100.5),
             volume_24h: Some(1000.0),
+            sequence: None,
         };
 
         assert_eq!(tick.mid_price(), 100.5);
@@ -0,0 +1,249 @@
+//! Event loop utilization
+//!
+//! A strategy that takes longer to handle its events than the feed takes to
+//! deliver them falls behind: its mailbox fills up and book updates start
+//! being dropped. [`StrategyLoad`] tracks, per strategy, how long each
+//! callback took, how many events were queued behind it, and the fraction
+//! of wall time spent inside callbacks since the last flush:
+//!
+//! - `strategy.event_processing_ms` (labels `quantile`: `p50`, `p90`,
+//!   `p99`, `max`)
+//! - `strategy.event_queue_depth` (same labels)
+//! - `strategy.events_handled`
+//! - `strategy.loop_utilization` (busy time / wall time, 0 to 1)
+//!
+//! A utilization approaching 1 means the strategy is close to saturation
+//! even if no ticks have been dropped yet.
+//!
+//! Times are measured with the monotonic system clock, not the coordinator's
+//! clock, since they describe real processing cost.
+
+use crate::metrics::{metric_names, StrategyMetric};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Bucket upper bounds for callback processing time, in milliseconds
+pub const PROCESSING_MS_BOUNDS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 1000.0];
+
+/// Bucket upper bounds for queue depth, in events
+pub const QUEUE_DEPTH_BOUNDS: &[f64] = &[0.0, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0, 1024.0];
+
+/// Fixed-bucket histogram
+///
+/// Quantiles are reported as the upper bound of the bucket they fall in
+/// (the largest observed value for the overflow bucket).
+#[derive(Debug, Clone)]
+pub struct BucketHistogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+    max: f64,
+}
+
+impl BucketHistogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            count: 0,
+            sum: 0.0,
+            max: 0.0,
+        }
+    }
+
+    /// Add an observation
+    pub fn record(&mut self, value: f64) {
+        let bucket = self.bounds.iter().position(|bound| value <= *bound).unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum += value;
+        self.max = self.max.max(value);
+    }
+
+    /// Observations recorded
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Approximate quantile (`q` in 0..=1)
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(self.bounds.get(bucket).map_or(self.max, |bound| bound.min(self.max)));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Counts per bucket, the last one counting values above every bound
+    pub fn buckets(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        self.count = 0;
+        self.sum = 0.0;
+        self.max = 0.0;
+    }
+}
+
+/// Event loop load of one strategy since the last flush
+#[derive(Debug, Clone)]
+pub struct StrategyLoad {
+    processing_ms: BucketHistogram,
+    queue_depth: BucketHistogram,
+    busy: Duration,
+    window_start: Instant,
+}
+
+impl StrategyLoad {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            processing_ms: BucketHistogram::new(PROCESSING_MS_BOUNDS),
+            queue_depth: BucketHistogram::new(QUEUE_DEPTH_BOUNDS),
+            busy: Duration::ZERO,
+            window_start: now,
+        }
+    }
+
+    /// Record a handled event: how long the callback took and how many
+    /// events were still queued behind it
+    pub fn record(&mut self, elapsed: Duration, queued: usize) {
+        self.busy += elapsed;
+        self.processing_ms.record(elapsed.as_secs_f64() * 1000.0);
+        self.queue_depth.record(queued as f64);
+    }
+
+    /// Callback processing times, in milliseconds
+    pub fn processing_ms(&self) -> &BucketHistogram {
+        &self.processing_ms
+    }
+
+    /// Events queued behind each handled event
+    pub fn queue_depth(&self) -> &BucketHistogram {
+        &self.queue_depth
+    }
+
+    /// Events handled since the last flush
+    pub fn events(&self) -> u64 {
+        self.processing_ms.count()
+    }
+
+    /// Time spent in callbacks since the last flush
+    pub fn busy(&self) -> Duration {
+        self.busy
+    }
+
+    /// Busy time over wall time since the last flush
+    pub fn utilization(&self, now: Instant) -> f64 {
+        let wall = now.saturating_duration_since(self.window_start);
+        if wall.is_zero() {
+            return 0.0;
+        }
+        (self.busy.as_secs_f64() / wall.as_secs_f64()).min(1.0)
+    }
+
+    /// Metrics for the window ending at `now`
+    pub fn metrics(&self, strategy_id: &str, now: Instant) -> Vec<StrategyMetric> {
+        let mut metrics = vec![
+            StrategyMetric::gauge(
+                strategy_id.to_string(),
+                metric_names::LOOP_UTILIZATION.to_string(),
+                self.utilization(now),
+                HashMap::new(),
+            ),
+            StrategyMetric::counter(
+                strategy_id.to_string(),
+                metric_names::EVENTS_HANDLED.to_string(),
+                self.events() as f64,
+                HashMap::new(),
+            ),
+        ];
+        for (name, histogram) in [
+            (metric_names::EVENT_PROCESSING_MS, &self.processing_ms),
+            (metric_names::EVENT_QUEUE_DEPTH, &self.queue_depth),
+        ] {
+            for (quantile, value) in [
+                ("p50", histogram.quantile(0.5)),
+                ("p90", histogram.quantile(0.9)),
+                ("p99", histogram.quantile(0.99)),
+                ("max", (histogram.count() > 0).then(|| histogram.max())),
+            ] {
+                if let Some(value) = value {
+                    let labels = HashMap::from([("quantile".to_string(), quantile.to_string())]);
+                    metrics.push(StrategyMetric::gauge(strategy_id.to_string(), name.to_string(), value, labels));
+                }
+            }
+        }
+        metrics
+    }
+
+    /// Start a new window at `now`
+    pub fn reset(&mut self, now: Instant) {
+        self.processing_ms.reset();
+        self.queue_depth.reset();
+        self.busy = Duration::ZERO;
+        self.window_start = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_quantiles() {
+        let mut histogram = BucketHistogram::new(PROCESSING_MS_BOUNDS);
+        assert_eq!(histogram.quantile(0.5), None);
+        for _ in 0..98 {
+            histogram.record(0.3);
+        }
+        histogram.record(7.0);
+        histogram.record(4000.0);
+
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.quantile(0.5), Some(0.5));
+        assert_eq!(histogram.quantile(0.99), Some(10.0));
+        assert_eq!(histogram.quantile(1.0), Some(4000.0));
+        assert_eq!(histogram.buckets().last(), Some(&1));
+    }
+
+    #[test]
+    fn test_utilization() {
+        let start = Instant::now();
+        let mut load = StrategyLoad::new(start);
+        load.record(Duration::from_millis(30), 0);
+        load.record(Duration::from_millis(20), 5);
+
+        let now = start + Duration::from_millis(200);
+        assert!((load.utilization(now) - 0.25).abs() < 1e-9);
+        assert_eq!(load.queue_depth().max(), 5.0);
+
+        let metrics = load.metrics("mm", now);
+        let utilization = metrics.iter().find(|m| m.metric_name == metric_names::LOOP_UTILIZATION).unwrap();
+        assert!((utilization.value - 0.25).abs() < 1e-9);
+        assert_eq!(metrics.iter().filter(|m| m.metric_name == metric_names::EVENT_PROCESSING_MS).count(), 4);
+
+        load.reset(now);
+        assert_eq!(load.events(), 0);
+        assert_eq!(load.utilization(now + Duration::from_millis(100)), 0.0);
+        // An idle window reports utilization and the event count only
+        assert_eq!(load.metrics("mm", now).len(), 2);
+    }
+}
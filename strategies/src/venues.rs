@@ -0,0 +1,98 @@
+//! Venue availability as seen by strategies
+//!
+//! The execution layer pauses order flow to venues that are degraded or
+//! down (maintenance, failing health checks, elevated error rates).
+//! [`VenueStatuses`] mirrors those states into strategy contexts so
+//! strategies can stop quoting, shift flow to another venue or widen,
+//! instead of learning about an outage from rejected orders. Like
+//! [`FeatureFlags`](crate::FeatureFlags) it is a shared handle: the bot
+//! updates it through the coordinator and every context sees the change.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// Availability of a venue, from best to worst
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VenueState {
+    /// Accepting all orders
+    #[default]
+    Up,
+    /// Accepting only orders that reduce a position
+    Degraded,
+    /// Accepting no orders
+    Down,
+}
+
+impl VenueState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VenueState::Up => "up",
+            VenueState::Degraded => "degraded",
+            VenueState::Down => "down",
+        }
+    }
+}
+
+impl fmt::Display for VenueState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Shared, runtime-updated state of each venue
+#[derive(Debug, Clone, Default)]
+pub struct VenueStatuses {
+    states: Arc<RwLock<HashMap<String, VenueState>>>,
+}
+
+impl VenueStatuses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// State of a venue (venues never reported are up)
+    pub fn state(&self, venue: &str) -> VenueState {
+        self.states.read().get(venue).copied().unwrap_or_default()
+    }
+
+    /// Whether a venue accepts new entries
+    pub fn is_up(&self, venue: &str) -> bool {
+        self.state(venue) == VenueState::Up
+    }
+
+    /// Set a venue's state, returning its previous state
+    pub fn set(&self, venue: &str, state: VenueState) -> VenueState {
+        self.states.write().insert(venue.to_string(), state).unwrap_or_default()
+    }
+
+    /// State of every reported venue, sorted by venue
+    pub fn snapshot(&self) -> BTreeMap<String, VenueState> {
+        self.states.read().iter().map(|(venue, state)| (venue.clone(), *state)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_statuses() {
+        let statuses = VenueStatuses::new();
+        let shared = statuses.clone();
+        assert!(shared.is_up("polymarket"));
+
+        assert_eq!(statuses.set("polymarket", VenueState::Down), VenueState::Up);
+        assert_eq!(shared.state("polymarket"), VenueState::Down);
+        assert!(!shared.is_up("polymarket"));
+        assert_eq!(statuses.set("polymarket", VenueState::Degraded), VenueState::Down);
+        assert_eq!(
+            shared.snapshot().into_iter().collect::<Vec<_>>(),
+            vec![("polymarket".to_string(), VenueState::Degraded)]
+        );
+    }
+}